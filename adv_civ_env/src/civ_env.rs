@@ -0,0 +1,653 @@
+use adv_civ::agent_api::dispatch_game_move;
+use adv_civ::civilization::*;
+use adv_civ::player::Player;
+use adv_civ::stupid_ai::{AgentControlled, IsHuman, StupidAi};
+use adv_civ::{GameActivity, GameState};
+use adv_civ_server::simulation::{Outcome, simulation_app};
+use bevy::ecs::system::RunSystemOnce;
+use bevy::platform::collections::HashMap;
+use bevy::prelude::*;
+use std::time::{Duration, Instant};
+
+/// Width of [`CivEnv::action_mask`]: the most legal actions one decision
+/// offers. Larger lists are cut off (and logged).
+pub const MAX_ACTIONS: usize = 1024;
+
+/// How long `reset` waits for the map, card and personality assets.
+const ASSET_LOAD_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Rewards handed out per step, as `docs/reinforcement-learning.md` §2 lays
+/// them out: dense A.S.T. progress plus a terminal win or loss.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RewardShaping {
+    /// Per A.S.T. space gained (lost, when a marker slips back per rule 33.4).
+    pub per_ast_space: f32,
+    /// For finishing first in the final standings (rule 35).
+    pub win: f32,
+    /// For any other place in a finished game. A stalled game pays neither.
+    pub loss: f32,
+}
+
+impl Default for RewardShaping {
+    fn default() -> Self {
+        RewardShaping {
+            per_ast_space: 0.1,
+            win: 1.0,
+            loss: -1.0,
+        }
+    }
+}
+
+/// How [`CivEnv::reset`] sets up an episode.
+#[derive(Debug, Clone)]
+pub struct EnvConfig {
+    /// Players at the table, agents included.
+    pub players: usize,
+    /// Factions the caller plays; the built-in AI plays the rest. Several
+    /// factions make a self-play table sharing one policy.
+    pub agents: Vec<GameFaction>,
+    /// Rule 34.1B's round limit, so episodes have a bounded length.
+    pub round_limit: Option<usize>,
+    /// Frames without a decision or phase change before the episode is cut
+    /// off as stalled.
+    pub stall_frames: u64,
+    /// AI personalities for the other seats, by name (default: the line-up).
+    pub playstyles: Vec<String>,
+    pub rewards: RewardShaping,
+}
+
+impl Default for EnvConfig {
+    fn default() -> Self {
+        EnvConfig {
+            players: 5,
+            agents: vec![GameFaction::Egypt],
+            round_limit: Some(30),
+            stall_frames: 20_000,
+            playstyles: Vec::new(),
+            rewards: RewardShaping::default(),
+        }
+    }
+}
+
+/// One entry of the legal list: the `AvailableMoves` entry it comes from
+/// and, for moves that offer "up to `max_tokens`", the exact count. Each
+/// token count is its own action, so the policy never picks a number.
+#[derive(Debug, Clone)]
+pub struct EnvAction {
+    pub index: usize,
+    pub tokens: Option<usize>,
+    pub game_move: GameMove,
+    /// [`ObservationEncoder::encode_move`] of this action, for scoring it.
+    pub features: Vec<f32>,
+}
+
+/// What the acting agent sees before choosing.
+#[derive(Debug, Clone)]
+pub struct Observation {
+    /// The agent faction to act; `None` once the episode is over.
+    pub faction: Option<GameFaction>,
+    pub activity: Option<GameActivity>,
+    pub round: usize,
+    /// The board in the save-file format, as the acting agent (the first
+    /// agent once the episode is over) sees it: `GameSaveData::public_view`,
+    /// so no other hand, undrawn card or unrevealed offer.
+    pub board: GameSaveData,
+    /// The board encoded by [`CivEnv::encoder`] for the acting agent (the
+    /// first agent once the episode is over).
+    pub tensor: Vec<f32>,
+    /// The legal list, in `AvailableMoves` index order; at most
+    /// [`MAX_ACTIONS`] long.
+    pub actions: Vec<EnvAction>,
+}
+
+/// Everything about a step besides the acting agent's reward.
+#[derive(Debug, Clone, Default)]
+pub struct StepInfo {
+    /// The faction whose action the step applied.
+    pub acted: Option<GameFaction>,
+    /// This step's reward for every agent faction, for self-play learners.
+    pub rewards: Vec<(GameFaction, f32)>,
+    /// Set once the episode is over.
+    pub outcome: Option<Outcome>,
+    pub winner: Option<GameFaction>,
+    /// Frames the rules engine ran to reach the next decision.
+    pub frames: u64,
+}
+
+/// The factions [`CivEnv`] drives, for the setup system.
+#[derive(Resource)]
+struct EnvAgents(Vec<GameFaction>);
+
+/// Bumped whenever an agent's `AvailableMoves` is (re)computed, so a
+/// decision is only offered once the move list reflects the last action.
+#[derive(Resource, Default)]
+struct MoveGenerations(HashMap<GameFaction, u64>);
+
+/// Like `bind_seats`: the agent factions lose their `StupidAi` and wait for
+/// moves as agent-controlled humans.
+fn bind_agents(
+    agents: Res<EnvAgents>,
+    players: Query<(Entity, &Faction), With<Player>>,
+    mut commands: Commands,
+) {
+    for (player, faction) in &players {
+        if agents.0.contains(&faction.faction) {
+            commands
+                .entity(player)
+                .remove::<StupidAi>()
+                .insert((IsHuman, AgentControlled));
+        }
+    }
+}
+
+/// Trade is left to scripted play for now (§3.4 of the RL doc): agents opt
+/// out as soon as the phase offers it, as `POST /trade/stop` would.
+fn agents_skip_trade(
+    traders: Query<Entity, (With<AgentControlled>, With<CanTrade>)>,
+    mut commands: Commands,
+) {
+    for player in &traders {
+        commands.entity(player).remove::<CanTrade>();
+    }
+}
+
+fn count_move_generations(
+    mut generations: ResMut<MoveGenerations>,
+    changed: Query<&Faction, (With<AgentControlled>, Changed<AvailableMoves>)>,
+) {
+    for faction in &changed {
+        *generations.0.entry(faction.faction).or_insert(0) += 1;
+    }
+}
+
+fn capture_board(source: SaveDataSource) -> GameSaveData {
+    source.capture()
+}
+
+fn build_encoder(source: ObservationSource) -> (ObservationEncoder, HashMap<Entity, i32>) {
+    (source.encoder(), source.area_ids())
+}
+
+/// A headless game stepped one agent decision at a time.
+///
+/// [`CivEnv::reset`] boots a fresh game and runs it to the first agent
+/// decision; [`CivEnv::step`] plays the chosen entry of the legal list and
+/// runs the game (the AI seats included) to the next one. Trade, ship
+/// placement and calamity choices are played by the built-in AI for agent
+/// factions.
+pub struct CivEnv {
+    app: Option<App>,
+    config: EnvConfig,
+    observation: Option<Observation>,
+    /// Move generation per faction when it last acted.
+    acted_at: HashMap<GameFaction, u64>,
+    ast: HashMap<GameFaction, u32>,
+    /// Built from the map on the first observation of an episode.
+    encoder: Option<ObservationEncoder>,
+    area_ids: HashMap<Entity, i32>,
+    done: bool,
+}
+
+impl Default for CivEnv {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CivEnv {
+    pub fn new() -> Self {
+        CivEnv {
+            app: None,
+            config: EnvConfig::default(),
+            observation: None,
+            acted_at: HashMap::default(),
+            ast: HashMap::default(),
+            encoder: None,
+            area_ids: HashMap::default(),
+            done: true,
+        }
+    }
+
+    /// Starts a new episode with game seed `seed` and returns the first
+    /// observation. The previous game, if any, is dropped.
+    pub fn reset(&mut self, seed: u64, config: EnvConfig) -> Result<Observation, String> {
+        if config.agents.is_empty() {
+            return Err("at least one agent faction is needed".to_string());
+        }
+        if config.agents.len() > config.players {
+            return Err(format!(
+                "{} agent factions at a table of {}",
+                config.agents.len(),
+                config.players
+            ));
+        }
+        let mut app = simulation_app(
+            DebugOptions {
+                number_of_players: config.players,
+                reserved_factions: config.agents.clone(),
+                playstyles: config.playstyles.clone(),
+                game_seed: Some(seed),
+                ..DebugOptions::default()
+            },
+            config.round_limit,
+        );
+        app.insert_resource(EnvAgents(config.agents.clone()))
+            .init_resource::<MoveGenerations>()
+            .add_systems(
+                OnEnter(GameActivity::PrepareGame),
+                bind_agents.after(setup_players),
+            )
+            .add_systems(Update, agents_skip_trade)
+            .add_systems(Last, count_move_generations);
+        app.finish();
+        app.cleanup();
+
+        self.app = Some(app);
+        self.config = config;
+        self.acted_at.clear();
+        self.ast.clear();
+        self.encoder = None;
+        self.area_ids.clear();
+        self.done = false;
+        self.wait_for_assets()?;
+        let (observation, _) = self.run_to_decision()?;
+        for faction in &self.config.agents {
+            let space = self.ast_space(*faction);
+            self.ast.insert(*faction, space);
+        }
+        Ok(observation)
+    }
+
+    /// Plays entry `action` of the current legal list and runs the game to
+    /// the next agent decision or the end of the episode. The reward is the
+    /// acting faction's; [`StepInfo::rewards`] has every agent's.
+    pub fn step(&mut self, action: usize) -> Result<(Observation, f32, bool, StepInfo), String> {
+        if self.done {
+            return Err("the episode is over; call reset".to_string());
+        }
+        let Some(observation) = &self.observation else {
+            return Err("no decision pending; call reset".to_string());
+        };
+        let Some(faction) = observation.faction else {
+            return Err("no decision pending".to_string());
+        };
+        let Some(chosen) = observation.actions.get(action).cloned() else {
+            return Err(format!(
+                "action {action} out of range ({} legal)",
+                observation.actions.len()
+            ));
+        };
+
+        self.apply(faction, &chosen)?;
+        let generation = self.generation(faction);
+        self.acted_at.insert(faction, generation);
+        let (observation, mut info) = self.run_to_decision()?;
+
+        let rewards = self.config.rewards;
+        for agent in self.config.agents.clone() {
+            let space = self.ast_space(agent);
+            let before = self.ast.insert(agent, space).unwrap_or(space);
+            let mut reward = (space as f32 - before as f32) * rewards.per_ast_space;
+            if info.outcome == Some(Outcome::Finished) {
+                reward += if info.winner == Some(agent) {
+                    rewards.win
+                } else {
+                    rewards.loss
+                };
+            }
+            info.rewards.push((agent, reward));
+        }
+        info.acted = Some(faction);
+        let reward = info
+            .rewards
+            .iter()
+            .find(|(f, _)| *f == faction)
+            .map_or(0.0, |(_, r)| *r);
+        Ok((observation, reward, self.done, info))
+    }
+
+    /// `true` for each index of [`MAX_ACTIONS`] that is a legal action now.
+    pub fn action_mask(&self) -> Vec<bool> {
+        let legal = self.observation.as_ref().map_or(0, |o| o.actions.len());
+        (0..MAX_ACTIONS).map(|i| i < legal).collect()
+    }
+
+    /// The observation the next [`CivEnv::step`] acts on.
+    pub fn observation(&self) -> Option<&Observation> {
+        self.observation.as_ref()
+    }
+
+    /// The encoder behind [`Observation::tensor`] and
+    /// [`EnvAction::features`]; its widths are fixed for the episode's map.
+    pub fn encoder(&self) -> Option<&ObservationEncoder> {
+        self.encoder.as_ref()
+    }
+
+    /// The running game, for callers that want more than the observation.
+    pub fn app(&self) -> Option<&App> {
+        self.app.as_ref()
+    }
+
+    fn app_mut(&mut self) -> Result<&mut App, String> {
+        self.app.as_mut().ok_or("no game; call reset".to_string())
+    }
+
+    /// Asset loading waits on real time, not frames.
+    fn wait_for_assets(&mut self) -> Result<(), String> {
+        let started = Instant::now();
+        let app = self.app_mut()?;
+        while app.world().resource::<State<GameState>>().get() == &GameState::Loading {
+            if started.elapsed() > ASSET_LOAD_TIMEOUT {
+                return Err("assets did not load; is BEVY_ASSET_ROOT set?".to_string());
+            }
+            app.update();
+        }
+        Ok(())
+    }
+
+    /// Updates until an agent has a fresh move list, the game ends or it
+    /// stalls, and records the new observation.
+    fn run_to_decision(&mut self) -> Result<(Observation, StepInfo), String> {
+        let stall_frames = self.config.stall_frames;
+        let mut info = StepInfo::default();
+        let mut activity = self.activity();
+        let mut since_change = 0;
+        let decision = loop {
+            self.app_mut()?.update();
+            info.frames += 1;
+            since_change += 1;
+            if self.app_mut()?.world().contains_resource::<GameResult>() {
+                info.outcome = Some(Outcome::Finished);
+                info.winner = self.winner();
+                break None;
+            }
+            let current = self.activity();
+            if current != activity {
+                activity = current;
+                since_change = 0;
+            }
+            if let Some(decision) = self.pending_decision() {
+                break Some(decision);
+            }
+            if since_change > stall_frames {
+                warn!("[env] no decision for {stall_frames} frames in {activity:?}; stalled");
+                info.outcome = Some(Outcome::Stalled);
+                break None;
+            }
+        };
+
+        self.done = decision.is_none();
+        let observation = self.observe(decision)?;
+        self.observation = Some(observation.clone());
+        Ok((observation, info))
+    }
+
+    fn activity(&self) -> Option<GameActivity> {
+        self.app
+            .as_ref()?
+            .world()
+            .get_resource::<State<GameActivity>>()
+            .map(|a| a.get().clone())
+    }
+
+    fn generation(&self, faction: GameFaction) -> u64 {
+        self.app
+            .as_ref()
+            .and_then(|app| app.world().get_resource::<MoveGenerations>())
+            .and_then(|g| g.0.get(&faction).copied())
+            .unwrap_or(0)
+    }
+
+    /// The first agent, in faction order, whose move list has been computed
+    /// since it last acted.
+    fn pending_decision(&mut self) -> Option<(Entity, GameFaction, Vec<(usize, GameMove)>)> {
+        let app = self.app.as_mut()?;
+        let world = app.world_mut();
+        let mut candidates: Vec<(Entity, GameFaction, Vec<(usize, GameMove)>)> = world
+            .query_filtered::<(Entity, &Faction, &AvailableMoves), With<AgentControlled>>()
+            .iter(world)
+            .filter(|(_, _, moves)| !moves.moves.is_empty())
+            .map(|(player, faction, moves)| {
+                let mut moves: Vec<(usize, GameMove)> =
+                    moves.moves.iter().map(|(i, m)| (*i, m.clone())).collect();
+                moves.sort_by_key(|(i, _)| *i);
+                (player, faction.faction, moves)
+            })
+            .collect();
+        candidates.sort_by_key(|(_, faction, _)| *faction as u8);
+        candidates.into_iter().find(|(_, faction, _)| {
+            let acted = self.acted_at.get(faction).copied();
+            acted.is_none_or(|acted| self.generation(*faction) > acted)
+        })
+    }
+
+    fn observe(
+        &mut self,
+        decision: Option<(Entity, GameFaction, Vec<(usize, GameMove)>)>,
+    ) -> Result<Observation, String> {
+        let activity = self.activity();
+        let observer = decision
+            .as_ref()
+            .map(|(_, faction, _)| *faction)
+            .or(self.config.agents.first().copied())
+            .unwrap_or_default();
+        let world = self.app_mut()?.world_mut();
+        let board = world
+            .run_system_once(capture_board)
+            .map_err(|e| format!("could not capture the board: {e}"))?;
+        if self.encoder.is_none() {
+            let (encoder, area_ids) = self
+                .app_mut()?
+                .world_mut()
+                .run_system_once(build_encoder)
+                .map_err(|e| format!("could not read the map: {e}"))?;
+            self.encoder = Some(encoder);
+            self.area_ids = area_ids;
+        }
+        let encoder = self.encoder.as_ref().ok_or("no encoder")?;
+        let (faction, mut actions) = match decision {
+            Some((_, faction, moves)) => (Some(faction), expand_actions(moves)),
+            None => (None, Vec::new()),
+        };
+        for action in &mut actions {
+            action.features = encoder.encode_move(
+                &board,
+                observer,
+                &self.area_ids,
+                &action.game_move,
+                action.tokens,
+            );
+        }
+        Ok(Observation {
+            faction,
+            activity,
+            round: board.round,
+            tensor: encoder.encode(&board, observer),
+            board: board.public_view(Some(observer)),
+            actions,
+        })
+    }
+
+    fn ast_space(&mut self, faction: GameFaction) -> u32 {
+        let Some(app) = self.app.as_mut() else {
+            return 0;
+        };
+        let world = app.world_mut();
+        world
+            .query::<(&Faction, &AstPosition)>()
+            .iter(world)
+            .find(|(f, _)| f.faction == faction)
+            .map_or(0, |(_, ast)| ast.space)
+    }
+
+    /// The faction first in the final standings.
+    fn winner(&mut self) -> Option<GameFaction> {
+        let app = self.app.as_mut()?;
+        let world = app.world_mut();
+        let first = world
+            .get_resource::<GameResult>()?
+            .standings
+            .first()?
+            .0
+            .clone();
+        world
+            .query_filtered::<(&Name, &Faction), With<Player>>()
+            .iter(world)
+            .find(|(name, _)| name.as_str() == first)
+            .map(|(_, faction)| faction.faction)
+    }
+
+    /// Plays `action` the way the agent API's `POST /move` does.
+    fn apply(&mut self, faction: GameFaction, action: &EnvAction) -> Result<(), String> {
+        let world = self.app_mut()?.world_mut();
+        let player = world
+            .query_filtered::<(Entity, &Faction), With<AgentControlled>>()
+            .iter(world)
+            .find(|(_, f)| f.faction == faction)
+            .map(|(player, _)| player)
+            .ok_or(format!("no agent player for {faction}"))?;
+        dispatch_game_move(world, player, &action.game_move, action.tokens.unwrap_or(1))
+    }
+}
+
+/// The legal list: one action per move, and per token count for the moves
+/// that offer a range. Trade moves go through the trade phase, not here.
+/// Features are left for `observe` to fill.
+fn expand_actions(moves: Vec<(usize, GameMove)>) -> Vec<EnvAction> {
+    let mut actions = Vec::new();
+    for (index, game_move) in moves {
+        let max_tokens = match &game_move {
+            GameMove::PopulationExpansion(m) => Some(m.max_tokens),
+            GameMove::Movement(m)
+            | GameMove::ShipFerry(m)
+            | GameMove::AttackArea(m)
+            | GameMove::AttackCity(m) => Some(m.max_tokens),
+            GameMove::Trade(_) => continue,
+            _ => None,
+        };
+        match max_tokens {
+            Some(max) => actions.extend((1..=max).map(|tokens| EnvAction {
+                index,
+                tokens: Some(tokens),
+                game_move: game_move.clone(),
+                features: Vec::new(),
+            })),
+            None => actions.push(EnvAction {
+                index,
+                tokens: None,
+                game_move,
+                features: Vec::new(),
+            }),
+        }
+    }
+    if actions.len() > MAX_ACTIONS {
+        warn!(
+            "[env] {} legal actions, only the first {MAX_ACTIONS} are offered",
+            actions.len()
+        );
+        actions.truncate(MAX_ACTIONS);
+    }
+    actions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pop_exp(max_tokens: usize) -> GameMove {
+        GameMove::PopulationExpansion(PopExpMove::new(Entity::PLACEHOLDER, max_tokens))
+    }
+
+    #[test]
+    fn token_ranges_become_one_action_per_count() {
+        let actions = expand_actions(vec![(1, pop_exp(2)), (2, GameMove::EndMovement)]);
+
+        let flat: Vec<(usize, Option<usize>)> =
+            actions.iter().map(|a| (a.index, a.tokens)).collect();
+        assert_eq!(flat, vec![(1, Some(1)), (1, Some(2)), (2, None)]);
+    }
+
+    #[test]
+    fn the_legal_list_is_capped() {
+        let actions = expand_actions(vec![(1, pop_exp(MAX_ACTIONS + 5))]);
+
+        assert_eq!(actions.len(), MAX_ACTIONS);
+    }
+
+    #[test]
+    fn the_mask_is_empty_without_a_game() {
+        let env = CivEnv::new();
+
+        assert_eq!(env.action_mask().len(), MAX_ACTIONS);
+        assert!(env.action_mask().iter().all(|legal| !legal));
+    }
+
+    #[test]
+    fn reset_rejects_a_table_too_small_for_the_agents() {
+        let mut env = CivEnv::new();
+        let config = EnvConfig {
+            players: 1,
+            agents: vec![GameFaction::Egypt, GameFaction::Crete],
+            ..EnvConfig::default()
+        };
+
+        assert!(env.reset(1, config).is_err());
+    }
+
+    #[test]
+    fn a_seeded_episode_plays_to_the_end() {
+        adv_civ_server::simulation::use_workspace_assets();
+        let mut env = CivEnv::new();
+        let config = EnvConfig {
+            players: 3,
+            round_limit: Some(2),
+            ..EnvConfig::default()
+        };
+        let rewards = config.rewards;
+        let mut observation = env.reset(7, config).unwrap();
+        let start = env.ast_space(GameFaction::Egypt);
+
+        let mut total = 0.0;
+        let mut last = StepInfo::default();
+        for _ in 0..10_000 {
+            assert_eq!(observation.faction, Some(GameFaction::Egypt));
+            let mask = env.action_mask();
+            assert!(!observation.actions.is_empty());
+            assert_eq!(
+                mask.iter().filter(|legal| **legal).count(),
+                observation.actions.len()
+            );
+            assert!(mask[..observation.actions.len()].iter().all(|legal| *legal));
+            assert!(
+                observation
+                    .board
+                    .players
+                    .iter()
+                    .filter(|p| p.faction != GameFaction::Egypt)
+                    .all(|p| p.trade_cards.is_empty())
+            );
+
+            let (next, reward, done, info) = env.step(0).unwrap();
+            assert_eq!(info.rewards, vec![(GameFaction::Egypt, reward)]);
+            total += reward;
+            observation = next;
+            last = info;
+            if done {
+                break;
+            }
+        }
+
+        assert_eq!(last.outcome, Some(Outcome::Finished));
+        assert!(observation.actions.is_empty());
+        assert!(env.action_mask().iter().all(|legal| !legal));
+        assert!(env.step(0).is_err());
+        let terminal = if last.winner == Some(GameFaction::Egypt) {
+            rewards.win
+        } else {
+            rewards.loss
+        };
+        let progress =
+            (env.ast_space(GameFaction::Egypt) as f32 - start as f32) * rewards.per_ast_space;
+        assert!((total - progress - terminal).abs() < 1e-4, "{total}");
+    }
+}
@@ -0,0 +1,27 @@
+//! In-process reinforcement-learning environment: the real rules engine
+//! (`CivLogicPlugins`, booted headless as by the `simulate` binary) stepped
+//! one agent decision at a time, with no frame pacing, JSON or sockets in
+//! between.
+//!
+//! ```no_run
+//! use adv_civ_env::{CivEnv, EnvConfig};
+//!
+//! let mut env = CivEnv::new();
+//! let mut obs = env.reset(1, EnvConfig::default()).unwrap();
+//! while !obs.actions.is_empty() {
+//!     let (next, _reward, done, _info) = env.step(0).unwrap();
+//!     obs = next;
+//!     if done {
+//!         break;
+//!     }
+//! }
+//! ```
+//!
+//! See `docs/reinforcement-learning.md` for the action and reward design.
+
+mod civ_env;
+#[cfg(feature = "python")]
+mod python;
+
+pub use adv_civ_server::simulation::Outcome;
+pub use civ_env::*;
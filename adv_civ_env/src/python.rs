@@ -0,0 +1,166 @@
+//! `adv_civ_env.CivEnv` for Python trainers (`maturin develop --features
+//! python`). `reset` and `step` hand back the encoded observation and action
+//! mask; the board as JSON (save-file format, as the acting agent sees it)
+//! is a separate `observation_json` call, made only when wanted.
+
+use crate::{CivEnv, EnvConfig, MAX_ACTIONS, Observation, StepInfo};
+use adv_civ::civilization::GameFaction;
+use pyo3::exceptions::{PyRuntimeError, PyValueError};
+use pyo3::prelude::*;
+use serde_json::{Value, json};
+
+const FACTIONS: [GameFaction; 9] = [
+    GameFaction::Egypt,
+    GameFaction::Crete,
+    GameFaction::Africa,
+    GameFaction::Asia,
+    GameFaction::Assyria,
+    GameFaction::Babylon,
+    GameFaction::Illyria,
+    GameFaction::Iberia,
+    GameFaction::Thrace,
+];
+
+fn parse_faction(name: &str) -> PyResult<GameFaction> {
+    FACTIONS
+        .into_iter()
+        .find(|f| f.to_string().eq_ignore_ascii_case(name.trim()))
+        .ok_or_else(|| PyValueError::new_err(format!("unknown faction '{name}'")))
+}
+
+fn observation_json(observation: &Observation) -> String {
+    let actions: Vec<Value> = observation
+        .actions
+        .iter()
+        .map(|a| {
+            json!({
+                "index": a.index,
+                "tokens": a.tokens,
+                "move": format!("{:?}", a.game_move),
+            })
+        })
+        .collect();
+    json!({
+        "faction": observation.faction.map(|f| f.to_string()),
+        "activity": observation.activity.as_ref().map(|a| format!("{a:?}")),
+        "round": observation.round,
+        "board": serde_json::to_value(&observation.board).unwrap_or(Value::Null),
+        "actions": actions,
+    })
+    .to_string()
+}
+
+fn info_json(info: &StepInfo) -> String {
+    json!({
+        "acted": info.acted.map(|f| f.to_string()),
+        "rewards": info
+            .rewards
+            .iter()
+            .map(|(f, r)| (f.to_string(), json!(r)))
+            .collect::<serde_json::Map<String, Value>>(),
+        "outcome": info.outcome.map(|o| format!("{o:?}")),
+        "winner": info.winner.map(|f| f.to_string()),
+        "frames": info.frames,
+    })
+    .to_string()
+}
+
+/// The Bevy app inside is not `Send`; the env stays on the thread that made
+/// it.
+#[pyclass(unsendable, name = "CivEnv")]
+struct PyCivEnv {
+    env: CivEnv,
+}
+
+#[pymethods]
+impl PyCivEnv {
+    #[new]
+    fn new() -> Self {
+        PyCivEnv { env: CivEnv::new() }
+    }
+
+    #[classattr]
+    const MAX_ACTIONS: usize = MAX_ACTIONS;
+
+    #[pyo3(signature = (seed, players=5, agents=None, round_limit=Some(30), stall_frames=20_000))]
+    fn reset(
+        &mut self,
+        seed: u64,
+        players: usize,
+        agents: Option<Vec<String>>,
+        round_limit: Option<usize>,
+        stall_frames: u64,
+    ) -> PyResult<(Vec<f32>, Vec<bool>)> {
+        let mut config = EnvConfig {
+            players,
+            round_limit,
+            stall_frames,
+            ..EnvConfig::default()
+        };
+        if let Some(agents) = agents {
+            config.agents = agents
+                .iter()
+                .map(|name| parse_faction(name))
+                .collect::<PyResult<_>>()?;
+        }
+        let observation = self
+            .env
+            .reset(seed, config)
+            .map_err(PyRuntimeError::new_err)?;
+        Ok((observation.tensor, self.env.action_mask()))
+    }
+
+    /// `(tensor, action_mask, reward, done, info_json)`.
+    fn step(&mut self, action: usize) -> PyResult<(Vec<f32>, Vec<bool>, f32, bool, String)> {
+        let (observation, reward, done, info) =
+            self.env.step(action).map_err(PyValueError::new_err)?;
+        Ok((
+            observation.tensor,
+            self.env.action_mask(),
+            reward,
+            done,
+            info_json(&info),
+        ))
+    }
+
+    /// The current observation as JSON: faction, activity, round, the board
+    /// and the legal actions.
+    fn observation_json(&self) -> PyResult<String> {
+        self.env
+            .observation()
+            .map(observation_json)
+            .ok_or_else(|| PyRuntimeError::new_err("no episode; call reset"))
+    }
+
+    fn action_mask(&self) -> Vec<bool> {
+        self.env.action_mask()
+    }
+
+    /// The current observation as a flat vector (layout in
+    /// `ObservationEncoder`'s docs).
+    fn tensor(&self) -> Vec<f32> {
+        self.env
+            .observation()
+            .map_or_else(Vec::new, |o| o.tensor.clone())
+    }
+
+    /// One feature row per legal action, in action order.
+    fn action_features(&self) -> Vec<Vec<f32>> {
+        self.env.observation().map_or_else(Vec::new, |o| {
+            o.actions.iter().map(|a| a.features.clone()).collect()
+        })
+    }
+
+    /// `(observation_width, move_width)` for the current episode's map.
+    fn widths(&self) -> PyResult<(usize, usize)> {
+        self.env
+            .encoder()
+            .map(|e| (e.width(), e.move_width()))
+            .ok_or_else(|| PyRuntimeError::new_err("no episode; call reset"))
+    }
+}
+
+#[pymodule]
+fn adv_civ_env(module: &Bound<'_, PyModule>) -> PyResult<()> {
+    module.add_class::<PyCivEnv>()
+}
@@ -0,0 +1,384 @@
+//! Self-play tuner for the AI `Weights`: a genetic algorithm that evaluates
+//! each candidate in a batch of headless games against the defined
+//! personalities and writes the best found as personality definitions.
+//!
+//! The default output, `assets/definitions/tuned.personalities.ron`, is
+//! loaded with the other definitions: `Tuned` is the best, then `Tuned2`,
+//! `Tuned3`, ... They stay out of the default line-up; pick them by name.
+//!
+//! ```sh
+//! cargo run --release -p adv_civ_server --bin evolve -- \
+//!     --generations 30 --population 16 --games-per-eval 6 --round-limit 30
+//! ```
+
+use adv_civ::civilization::DebugOptions;
+use adv_civ::stupid_ai::{
+    Personality, PersonalityDefinition, PersonalityDefinitions, Picker, Playstyle, TradeKnobs,
+    Weights,
+};
+use adv_civ_server::simulation::{self, Outcome};
+use rand::rngs::StdRng;
+use rand::{RngExt, SeedableRng};
+use std::process::ExitCode;
+
+const USAGE: &str = "\
+usage: evolve [options]
+  --generations G      generations to run (default 20)
+  --population P       candidates per generation (default 16)
+  --games-per-eval E   games each candidate plays per generation (default 6)
+  --players N          players per game (default 5)
+  --opponents P[,Q..]  personalities (by name) to play against (default: the
+                       definitions' line-up)
+  --elites K           best candidates carried over unchanged (default 2)
+  --mutation S         standard deviation of a gene's mutation (default 0.1)
+  --keep K             personalities to write, best first (default 3)
+  --seed S             seed for games and mutation (default 1)
+  --round-limit R      end each game after R rounds (rule 34.1B)
+  --stall-frames F     give up on a game after F frames in one phase
+                       (default 20000); a stalled game scores nothing
+  --out FILE           definitions file to write (default
+                       assets/definitions/tuned.personalities.ron)";
+
+/// Softmax temperature range; the tenth gene.
+const TEMPERATURE_RANGE: (f32, f32) = (0.02, 1.0);
+/// Candidates drawn per tournament selection.
+const SELECTION_SIZE: usize = 3;
+
+/// The nine knobs plus the picker's softmax temperature.
+type Genome = [f32; 10];
+
+fn genome_of(personality: &Personality) -> Genome {
+    let temperature = match personality.picker {
+        Picker::Greedy => TEMPERATURE_RANGE.0,
+        Picker::Softmax { temperature } => temperature,
+    };
+    let mut genome = [0.0; 10];
+    genome[..9].copy_from_slice(&personality.weights.to_array());
+    genome[9] = temperature;
+    genome
+}
+
+fn personality_of(genome: &Genome, name: &str) -> Personality {
+    let mut knobs = [0.0; 9];
+    knobs.copy_from_slice(&genome[..9]);
+    Personality {
+        name: name.to_string(),
+        picker: Picker::Softmax {
+            temperature: genome[9],
+        },
+        weights: Weights::from_array(knobs),
+        trade: TradeKnobs::default(),
+    }
+}
+
+/// `Tuned` for the best, then `Tuned2`, `Tuned3`, ...
+fn tuned_name(rank: usize) -> String {
+    match rank {
+        0 => "Tuned".to_string(),
+        n => format!("Tuned{}", n + 1),
+    }
+}
+
+/// Keep every gene in range: knobs in `[0, 1]`, temperature in
+/// [`TEMPERATURE_RANGE`].
+fn clamp(genome: &mut Genome) {
+    for knob in &mut genome[..9] {
+        *knob = knob.clamp(0.0, 1.0);
+    }
+    genome[9] = genome[9].clamp(TEMPERATURE_RANGE.0, TEMPERATURE_RANGE.1);
+}
+
+/// Standard normal (Box-Muller).
+fn gaussian(rng: &mut StdRng) -> f64 {
+    let u = 1.0 - rng.random::<f64>();
+    let v = rng.random::<f64>();
+    (-2.0 * u.ln()).sqrt() * (core::f64::consts::TAU * v).cos()
+}
+
+/// Uniform crossover, then Gaussian mutation of every gene.
+fn offspring(a: &Genome, b: &Genome, sigma: f64, rng: &mut StdRng) -> Genome {
+    let mut child = [0.0; 10];
+    for (i, gene) in child.iter_mut().enumerate() {
+        let parent = if rng.random_bool(0.5) { a } else { b };
+        *gene = parent[i] + (sigma * gaussian(rng)) as f32;
+    }
+    clamp(&mut child);
+    child
+}
+
+/// One game's worth of fitness: 1 for first place, 0 for last.
+fn placing_score(place: usize, players: usize) -> f32 {
+    if players < 2 {
+        return 0.0;
+    }
+    (players - 1 - place) as f32 / (players - 1) as f32
+}
+
+struct EvolveConfig {
+    generations: usize,
+    population: usize,
+    games_per_eval: usize,
+    players: usize,
+    opponents: Vec<Personality>,
+    elites: usize,
+    sigma: f64,
+    keep: usize,
+    seed: u64,
+    round_limit: Option<usize>,
+    stall_frames: u64,
+    out: String,
+}
+
+impl EvolveConfig {
+    fn from_args(args: impl IntoIterator<Item = String>) -> Result<Self, String> {
+        let mut config = EvolveConfig {
+            generations: 20,
+            population: 16,
+            games_per_eval: 6,
+            players: 5,
+            opponents: Vec::new(),
+            elites: 2,
+            sigma: 0.1,
+            keep: 3,
+            seed: 1,
+            round_limit: None,
+            stall_frames: 20_000,
+            out: simulation::definitions_dir()
+                .join("tuned.personalities.ron")
+                .display()
+                .to_string(),
+        };
+        let library = simulation::personality_library()?;
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            let mut value = || args.next().ok_or(format!("{arg} needs a value"));
+            match arg.as_str() {
+                "--generations" => config.generations = parse(&arg, &value()?)?,
+                "--population" => config.population = parse(&arg, &value()?)?,
+                "--games-per-eval" => config.games_per_eval = parse(&arg, &value()?)?,
+                "--players" => config.players = parse::<usize>(&arg, &value()?)?.clamp(2, 9),
+                "--opponents" => {
+                    config.opponents = value()?
+                        .split(',')
+                        .map(|name| {
+                            library
+                                .get(name)
+                                .ok_or(format!("unknown personality \"{name}\""))
+                        })
+                        .collect::<Result<_, _>>()?;
+                }
+                "--elites" => config.elites = parse(&arg, &value()?)?,
+                "--mutation" => config.sigma = parse(&arg, &value()?)?,
+                "--keep" => config.keep = parse(&arg, &value()?)?,
+                "--seed" => config.seed = parse(&arg, &value()?)?,
+                "--round-limit" => config.round_limit = Some(parse(&arg, &value()?)?),
+                "--stall-frames" => config.stall_frames = parse(&arg, &value()?)?,
+                "--out" => config.out = value()?,
+                "--help" | "-h" => return Err(String::new()),
+                other => return Err(format!("unknown option {other}")),
+            }
+        }
+        if config.opponents.is_empty() {
+            config.opponents = library.lineup();
+        }
+        if config.population < 2 || config.elites >= config.population {
+            return Err("--population must be at least 2 and above --elites".to_string());
+        }
+        if config.opponents.is_empty() || config.games_per_eval == 0 {
+            return Err("a candidate needs opponents and at least one game".to_string());
+        }
+        Ok(config)
+    }
+}
+
+fn parse<T: core::str::FromStr>(option: &str, value: &str) -> Result<T, String> {
+    value
+        .trim()
+        .parse()
+        .map_err(|_| format!("{option}: \"{value}\" is not a number"))
+}
+
+/// Mean placing score of `genome` over this generation's games. Every
+/// candidate of a generation plays the same seeds, seats and opponents, so
+/// their fitnesses differ by play rather than by luck of the deal.
+fn evaluate(genome: &Genome, generation: usize, config: &EvolveConfig) -> f32 {
+    const CANDIDATE: usize = 0;
+    let mut total = 0.0;
+    for game in 0..config.games_per_eval {
+        let seat = game % config.players;
+        let table = (0..config.players)
+            .map(|s| {
+                if s == seat {
+                    (CANDIDATE, personality_of(genome, "Candidate"))
+                } else {
+                    let opponent = &config.opponents[(game + s) % config.opponents.len()];
+                    (s + 1, opponent.clone())
+                }
+            })
+            .collect();
+
+        let mut app = simulation::simulation_app(
+            DebugOptions {
+                number_of_players: config.players,
+                game_seed: Some(config.seed + (generation * config.games_per_eval + game) as u64),
+                ..DebugOptions::default()
+            },
+            config.round_limit,
+        );
+        simulation::seat_at_table(&mut app, table);
+        let summary = simulation::play(&mut app, game, config.stall_frames);
+        if summary.outcome == Outcome::Stalled {
+            continue;
+        }
+        let seated = simulation::seated(&app);
+        if let Some(place) = summary
+            .standings
+            .iter()
+            .position(|p| seated.get(&p.faction) == Some(&CANDIDATE))
+        {
+            total += placing_score(place, summary.standings.len());
+        }
+    }
+    total / config.games_per_eval as f32
+}
+
+fn pick<'a>(scored: &'a [(Genome, f32)], rng: &mut StdRng) -> &'a Genome {
+    let mut best = &scored[rng.random_range(..scored.len())];
+    for _ in 1..SELECTION_SIZE {
+        let other = &scored[rng.random_range(..scored.len())];
+        if other.1 > best.1 {
+            best = other;
+        }
+    }
+    &best.0
+}
+
+fn main() -> ExitCode {
+    let config = match EvolveConfig::from_args(std::env::args().skip(1)) {
+        Ok(config) => config,
+        Err(e) => {
+            if !e.is_empty() {
+                eprintln!("{e}");
+            }
+            eprintln!("{USAGE}");
+            return ExitCode::from(2);
+        }
+    };
+    let mut rng = StdRng::seed_from_u64(config.seed);
+
+    // Start from the hand-tuned archetypes, the rest mutated copies of them.
+    let mut population: Vec<Genome> = Playstyle::ALL
+        .iter()
+        .map(|&p| genome_of(&Personality::from_playstyle(p)))
+        .take(config.population)
+        .collect();
+    while population.len() < config.population {
+        let parent = population[rng.random_range(..Playstyle::ALL.len().min(population.len()))];
+        population.push(offspring(&parent, &parent, 2.0 * config.sigma, &mut rng));
+    }
+
+    // The best showings so far, with where they came from.
+    let mut best: Vec<(Personality, f32, String)> = Vec::new();
+    for generation in 0..config.generations {
+        let mut scored: Vec<(Genome, f32)> = population
+            .iter()
+            .map(|genome| (*genome, evaluate(genome, generation, &config)))
+            .collect();
+        scored.sort_by(|a, b| b.1.total_cmp(&a.1));
+        let mean = scored.iter().map(|(_, f)| f).sum::<f32>() / scored.len() as f32;
+        eprintln!(
+            "generation {generation}: best {:.3}, mean {mean:.3}",
+            scored[0].1
+        );
+
+        for (rank, (genome, fitness)) in scored.iter().take(config.keep).enumerate() {
+            let personality = personality_of(genome, "Tuned");
+            // Elites come back every generation; keep their best showing once.
+            if let Some(seen) = best
+                .iter_mut()
+                .find(|(p, _, _)| p.weights == personality.weights)
+            {
+                seen.1 = seen.1.max(*fitness);
+                continue;
+            }
+            best.push((personality, *fitness, format!("gen{generation}-{rank}")));
+        }
+        best.sort_by(|a, b| b.1.total_cmp(&a.1));
+        best.truncate(config.keep);
+        // Written every generation, so stopping early keeps what was found.
+        let written = PersonalityDefinitions {
+            personalities: best
+                .iter()
+                .enumerate()
+                .map(|(rank, (personality, fitness, origin))| {
+                    let mut definition = PersonalityDefinition::from_personality(personality);
+                    definition.name = tuned_name(rank);
+                    definition.description = Some(format!(
+                        "Evolved ({origin}): fitness {fitness:.3} over {} games",
+                        config.games_per_eval
+                    ));
+                    definition.in_lineup = false;
+                    definition
+                })
+                .collect(),
+        }
+        .to_ron()
+        .and_then(|ron| std::fs::write(&config.out, ron).map_err(|e| e.to_string()));
+        if let Err(e) = written {
+            eprintln!("Cannot write {}: {e}", config.out);
+            return ExitCode::FAILURE;
+        }
+
+        population = scored
+            .iter()
+            .take(config.elites)
+            .map(|(genome, _)| *genome)
+            .collect();
+        while population.len() < config.population {
+            let a = *pick(&scored, &mut rng);
+            let b = *pick(&scored, &mut rng);
+            population.push(offspring(&a, &b, config.sigma, &mut rng));
+        }
+    }
+
+    eprintln!("Wrote {} personalities to {}", best.len(), config.out);
+    ExitCode::SUCCESS
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a_genome_round_trips_through_a_personality() {
+        let merchant = Personality::from_playstyle(Playstyle::Merchant);
+        let back = personality_of(&genome_of(&merchant), "Merchant");
+        assert_eq!(back.weights, merchant.weights);
+        assert_eq!(back.picker, merchant.picker);
+    }
+
+    #[test]
+    fn offspring_stay_in_range_however_wild_the_mutation() {
+        let mut rng = StdRng::seed_from_u64(3);
+        let parent = genome_of(&Personality::from_playstyle(Playstyle::Warlord));
+        for _ in 0..200 {
+            let child = offspring(&parent, &parent, 5.0, &mut rng);
+            assert!(child[..9].iter().all(|k| (0.0..=1.0).contains(k)));
+            assert!((TEMPERATURE_RANGE.0..=TEMPERATURE_RANGE.1).contains(&child[9]));
+        }
+    }
+
+    #[test]
+    fn the_best_is_plain_tuned_and_the_rest_are_numbered() {
+        assert_eq!(tuned_name(0), "Tuned");
+        assert_eq!(tuned_name(1), "Tuned2");
+        assert_eq!(tuned_name(2), "Tuned3");
+    }
+
+    #[test]
+    fn placing_scores_run_from_first_to_last() {
+        assert_eq!(placing_score(0, 5), 1.0);
+        assert_eq!(placing_score(2, 5), 0.5);
+        assert_eq!(placing_score(4, 5), 0.0);
+    }
+}
@@ -0,0 +1,251 @@
+//! Headless batch simulator: plays full AI-vs-AI games back to back, as fast
+//! as the rules engine goes, and prints one summary row per game.
+//!
+//! Same boot as the server's AI-only mode (`HeadlessGamePlugin`), minus the
+//! networking, with time stepped by hand instead of waiting on the clock.
+//! Meant for judging AI changes over many seeds and for catching games that
+//! stall: the exit code is 1 if any game stopped making progress.
+//!
+//! ```sh
+//! cargo run --release -p adv_civ_server --bin simulate -- \
+//!     --games 20 --players 4,6 --playstyles warlord,merchant --seed 100 \
+//!     --round-limit 40 --format csv --out results.csv
+//! ```
+
+use adv_civ::civilization::DebugOptions;
+use adv_civ_server::simulation::{self, GameSummary, Outcome, PlayerSummary};
+use serde_json::json;
+use std::io::Write;
+use std::process::ExitCode;
+
+const USAGE: &str = "\
+usage: simulate [options]
+  --games N            games to play (default 10)
+  --players A[,B..]    players per game, cycled over the games (default 5)
+  --playstyles P[,Q..] AI personalities (by name) in seat order, wrapping
+                       round (default: the definitions' line-up)
+  --seed S             seed of the first game; game i uses S+i (default 1)
+  --round-limit R      end each game after R rounds (rule 34.1B)
+  --stall-frames F     give up on a game after F frames in one phase
+                       (default 20000)
+  --format csv|json    output format (default csv)
+  --out FILE           write there instead of stdout";
+
+struct SimulationConfig {
+    games: usize,
+    player_counts: Vec<usize>,
+    playstyles: Vec<String>,
+    first_seed: u64,
+    round_limit: Option<usize>,
+    stall_frames: u64,
+    json: bool,
+    out: Option<String>,
+}
+
+impl SimulationConfig {
+    fn from_args(args: impl IntoIterator<Item = String>) -> Result<Self, String> {
+        let mut config = SimulationConfig {
+            games: 10,
+            player_counts: vec![5],
+            playstyles: Vec::new(),
+            first_seed: 1,
+            round_limit: None,
+            stall_frames: 20_000,
+            json: false,
+            out: None,
+        };
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            let mut value = || args.next().ok_or(format!("{arg} needs a value"));
+            match arg.as_str() {
+                "--games" => config.games = parse(&arg, &value()?)?,
+                "--players" => {
+                    config.player_counts = value()?
+                        .split(',')
+                        .map(|n| parse::<usize>(&arg, n).map(|n| n.clamp(2, 9)))
+                        .collect::<Result<_, _>>()?;
+                }
+                "--playstyles" => {
+                    let library = simulation::personality_library()?;
+                    config.playstyles = value()?
+                        .split(',')
+                        .map(|name| {
+                            library
+                                .get(name)
+                                .map(|p| p.name)
+                                .ok_or(format!("unknown personality \"{name}\""))
+                        })
+                        .collect::<Result<_, _>>()?;
+                }
+                "--seed" => config.first_seed = parse(&arg, &value()?)?,
+                "--round-limit" => config.round_limit = Some(parse(&arg, &value()?)?),
+                "--stall-frames" => config.stall_frames = parse(&arg, &value()?)?,
+                "--format" => {
+                    config.json = match value()?.as_str() {
+                        "csv" => false,
+                        "json" => true,
+                        other => return Err(format!("unknown format \"{other}\"")),
+                    };
+                }
+                "--out" => config.out = Some(value()?),
+                "--help" | "-h" => return Err(String::new()),
+                other => return Err(format!("unknown option {other}")),
+            }
+        }
+        if config.player_counts.is_empty() {
+            return Err("--players needs at least one count".to_string());
+        }
+        Ok(config)
+    }
+}
+
+fn parse<T: core::str::FromStr>(option: &str, value: &str) -> Result<T, String> {
+    value
+        .trim()
+        .parse()
+        .map_err(|_| format!("{option}: \"{value}\" is not a number"))
+}
+
+fn play_game(config: &SimulationConfig, game: usize) -> GameSummary {
+    let players = config.player_counts[game % config.player_counts.len()];
+    let mut app = simulation::simulation_app(
+        DebugOptions {
+            number_of_players: players,
+            playstyles: config.playstyles.clone(),
+            game_seed: Some(config.first_seed + game as u64),
+            ..DebugOptions::default()
+        },
+        config.round_limit,
+    );
+    simulation::play(&mut app, game, config.stall_frames)
+}
+
+fn csv(summaries: &[GameSummary]) -> String {
+    let mut out = String::from(
+        "game,seed,players,outcome,activity,rounds,frames,seconds,winner,winner_playstyle,\
+         score,ast,cities,evaluation,calamities,calamities_by_type\n",
+    );
+    for s in summaries {
+        let per_player = |field: fn(&PlayerSummary) -> String| {
+            s.standings
+                .iter()
+                .map(|p| format!("{:?}:{}", p.faction, field(p)))
+                .collect::<Vec<_>>()
+                .join(";")
+        };
+        let by_type = s
+            .calamities
+            .iter()
+            .map(|(calamity, n)| format!("{calamity}:{n}"))
+            .collect::<Vec<_>>()
+            .join(";");
+        out.push_str(&format!(
+            "{},{},{},{:?},{},{},{},{:.2},{},{},{},{},{},{},{},{}\n",
+            s.game,
+            s.seed,
+            s.players,
+            s.outcome,
+            s.activity,
+            s.rounds,
+            s.frames,
+            s.seconds,
+            s.winner.map_or_else(String::new, |w| format!("{w:?}")),
+            s.winner_playstyle().unwrap_or(""),
+            per_player(|p| p.score.map_or_else(String::new, |s| s.to_string())),
+            per_player(|p| p.ast_space.to_string()),
+            per_player(|p| p.cities.to_string()),
+            per_player(|p| format!("{:.3}", p.evaluation)),
+            s.calamities.values().sum::<usize>(),
+            by_type,
+        ));
+    }
+    out
+}
+
+fn json(summaries: &[GameSummary]) -> String {
+    let games: Vec<serde_json::Value> = summaries
+        .iter()
+        .map(|s| {
+            json!({
+                "game": s.game,
+                "seed": s.seed,
+                "players": s.players,
+                "outcome": format!("{:?}", s.outcome),
+                "activity": s.activity,
+                "rounds": s.rounds,
+                "frames": s.frames,
+                "seconds": s.seconds,
+                "winner": s.winner.map(|w| format!("{w:?}")),
+                "winner_playstyle": s.winner_playstyle(),
+                "standings": s.standings.iter().map(|p| json!({
+                    "faction": format!("{:?}", p.faction),
+                    "score": p.score,
+                    "playstyle": p.playstyle,
+                    "ast_space": p.ast_space,
+                    "cities": p.cities,
+                    "evaluation": p.evaluation,
+                })).collect::<Vec<_>>(),
+                "calamities": s.calamities,
+            })
+        })
+        .collect();
+    serde_json::to_string_pretty(&games).unwrap_or_default()
+}
+
+fn main() -> ExitCode {
+    let config = match SimulationConfig::from_args(std::env::args().skip(1)) {
+        Ok(config) => config,
+        Err(e) => {
+            if !e.is_empty() {
+                eprintln!("{e}");
+            }
+            eprintln!("{USAGE}");
+            return ExitCode::from(2);
+        }
+    };
+
+    let mut summaries = Vec::with_capacity(config.games);
+    for game in 0..config.games {
+        let summary = play_game(&config, game);
+        eprintln!(
+            "game {} (seed {}, {} players): {:?} in {} after {} rounds, {:.1}s{}",
+            summary.game,
+            summary.seed,
+            summary.players,
+            summary.outcome,
+            summary.activity,
+            summary.rounds,
+            summary.seconds,
+            summary
+                .winner
+                .map_or_else(String::new, |w| format!(", won by {w:?}"))
+        );
+        summaries.push(summary);
+    }
+
+    let report = if config.json {
+        json(&summaries)
+    } else {
+        csv(&summaries)
+    };
+    let written = match &config.out {
+        Some(path) => std::fs::write(path, &report).map_err(|e| format!("{path}: {e}")),
+        None => std::io::stdout()
+            .write_all(report.as_bytes())
+            .map_err(|e| e.to_string()),
+    };
+    if let Err(e) = written {
+        eprintln!("Cannot write the report: {e}");
+        return ExitCode::from(2);
+    }
+
+    let stalled = summaries
+        .iter()
+        .filter(|s| s.outcome == Outcome::Stalled)
+        .count();
+    if stalled > 0 {
+        eprintln!("{stalled} of {} games stalled", summaries.len());
+        return ExitCode::FAILURE;
+    }
+    ExitCode::SUCCESS
+}
@@ -0,0 +1,486 @@
+//! Personality tournament: seats AI personalities (the defined ones, or
+//! variants with knobs overridden) at headless AI-only tables across many seeded
+//! games, rates them with multiplayer Elo and prints a ranked league table.
+//!
+//! This is the "baseline vs. challenger" comparison from
+//! `docs/reinforcement-learning.md`: enter the current personality and a
+//! tweaked one and see whether the tweak's interval clears the baseline's.
+//!
+//! ```sh
+//! cargo run --release -p adv_civ_server --bin tournament -- \
+//!     --games 120 --players 4,5 --round-limit 30 \
+//!     --entrant balanced --entrant warlord \
+//!     --entrant greedy-merchant=merchant:trade_drive=1.0,risk=0.2
+//! ```
+
+use adv_civ::civilization::{DebugOptions, GameFaction};
+use adv_civ::stupid_ai::{Personalities, Personality, Weights};
+use adv_civ_server::simulation::{self, Outcome};
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{RngExt, SeedableRng};
+use std::cmp::Ordering;
+use std::process::ExitCode;
+
+const USAGE: &str = "\
+usage: tournament [options]
+  --entrant SPEC       a personality to enter, repeatable (default: the
+                       definitions' line-up). SPEC is
+                       [label=]personality[:knob=value,..], e.g.
+                       cautious=warlord:risk=0.3,defense=0.6; personalities
+                       are looked up in assets/definitions/*.personalities.ron
+  --games N            games to play (default 60)
+  --players A[,B..]    players per game, cycled over the games (default 5)
+  --seed S             seed of the first game; game i uses S+i (default 1)
+  --round-limit R      end each game after R rounds (rule 34.1B)
+  --stall-frames F     give up on a game after F frames in one phase
+                       (default 20000); stalled games are not rated
+  --bootstrap B        resamples for the 95% intervals (default 200)";
+
+/// Every entrant starts here.
+const INITIAL_RATING: f64 = 1500.0;
+/// Rating points at stake per game, split over the pairings at the table.
+const K_FACTOR: f64 = 32.0;
+
+/// One personality in the tournament.
+#[derive(Debug, Clone)]
+struct Entrant {
+    label: String,
+    personality: Personality,
+}
+
+impl Entrant {
+    /// Parse `[label=]personality[:knob=value,...]`, the personality named
+    /// as in `library`.
+    fn parse(spec: &str, library: &Personalities) -> Result<Self, String> {
+        let (label, rest) = match spec.split_once('=') {
+            Some((label, rest)) if !label.contains(':') => (Some(label.trim()), rest),
+            _ => (None, spec),
+        };
+        let (name, knobs) = rest.split_once(':').unwrap_or((rest, ""));
+        let mut personality = library.get(name).ok_or(format!(
+            "unknown personality \"{}\" (defined: {})",
+            name.trim(),
+            library.names().join(", ")
+        ))?;
+        for knob in knobs.split(',').filter(|k| !k.trim().is_empty()) {
+            let (name, value) = knob
+                .split_once('=')
+                .ok_or(format!("\"{knob}\" is not knob=value"))?;
+            let slot = personality.weights.knob_mut(name.trim()).ok_or(format!(
+                "unknown knob \"{}\" (knobs: {})",
+                name.trim(),
+                Weights::KNOBS.join(", ")
+            ))?;
+            *slot = value
+                .trim()
+                .parse()
+                .map_err(|_| format!("{name}: \"{value}\" is not a number"))?;
+        }
+
+        let label = match label {
+            Some(label) if !label.is_empty() => label.to_string(),
+            _ if knobs.is_empty() => personality.name.clone(),
+            _ => spec.to_string(),
+        };
+        personality.name = label.clone();
+        Ok(Entrant { label, personality })
+    }
+}
+
+struct TournamentConfig {
+    entrants: Vec<Entrant>,
+    games: usize,
+    player_counts: Vec<usize>,
+    first_seed: u64,
+    round_limit: Option<usize>,
+    stall_frames: u64,
+    bootstrap: usize,
+}
+
+impl TournamentConfig {
+    fn from_args(args: impl IntoIterator<Item = String>) -> Result<Self, String> {
+        let mut config = TournamentConfig {
+            entrants: Vec::new(),
+            games: 60,
+            player_counts: vec![5],
+            first_seed: 1,
+            round_limit: None,
+            stall_frames: 20_000,
+            bootstrap: 200,
+        };
+        let library = simulation::personality_library()?;
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            let mut value = || args.next().ok_or(format!("{arg} needs a value"));
+            match arg.as_str() {
+                "--entrant" => config.entrants.push(Entrant::parse(&value()?, &library)?),
+                "--games" => config.games = parse(&arg, &value()?)?,
+                "--players" => {
+                    config.player_counts = value()?
+                        .split(',')
+                        .map(|n| parse::<usize>(&arg, n).map(|n| n.clamp(2, 9)))
+                        .collect::<Result<_, _>>()?;
+                }
+                "--seed" => config.first_seed = parse(&arg, &value()?)?,
+                "--round-limit" => config.round_limit = Some(parse(&arg, &value()?)?),
+                "--stall-frames" => config.stall_frames = parse(&arg, &value()?)?,
+                "--bootstrap" => config.bootstrap = parse(&arg, &value()?)?,
+                "--help" | "-h" => return Err(String::new()),
+                other => return Err(format!("unknown option {other}")),
+            }
+        }
+        if config.entrants.is_empty() {
+            config.entrants = library
+                .lineup()
+                .into_iter()
+                .map(|personality| Entrant {
+                    label: personality.name.clone(),
+                    personality,
+                })
+                .collect();
+        }
+        if config.entrants.len() < 2 {
+            return Err("a tournament needs at least two entrants".to_string());
+        }
+        if config.player_counts.is_empty() {
+            return Err("--players needs at least one count".to_string());
+        }
+        Ok(config)
+    }
+}
+
+fn parse<T: core::str::FromStr>(option: &str, value: &str) -> Result<T, String> {
+    value
+        .trim()
+        .parse()
+        .map_err(|_| format!("{option}: \"{value}\" is not a number"))
+}
+
+/// The entrant (index) for each seat at this game's table, in seat order.
+/// Entrants take turns in a fixed cycle so all of them play about equally
+/// often and meet every mix of opponents; the seat order is then shuffled.
+fn table_for(game: usize, players: usize, entrants: usize, rng: &mut StdRng) -> Vec<usize> {
+    let mut table: Vec<usize> = (0..players)
+        .map(|seat| (game * players + seat) % entrants)
+        .collect();
+    table.shuffle(rng);
+    table
+}
+
+/// A finished game: each entrant's `(score, A.S.T. space)`, best first.
+type Placings = Vec<(usize, (u32, u32))>;
+
+/// Multiplayer Elo: every pair at the table is a head-to-head decided by
+/// final score (A.S.T. breaking ties, rule 35.2), with the K-factor split
+/// over the `n - 1` opponents so a game is worth the same at any table size.
+/// Two seats held by the same entrant are not rated against each other.
+fn elo<'a>(entrants: usize, games: impl IntoIterator<Item = &'a Placings>) -> Vec<f64> {
+    let mut ratings = vec![INITIAL_RATING; entrants];
+    for table in games {
+        if table.len() < 2 {
+            continue;
+        }
+        let k = K_FACTOR / (table.len() - 1) as f64;
+        let mut delta = vec![0.0; entrants];
+        for (i, &(a, a_result)) in table.iter().enumerate() {
+            for &(b, b_result) in &table[i + 1..] {
+                if a == b {
+                    continue;
+                }
+                let expected = 1.0 / (1.0 + 10f64.powf((ratings[b] - ratings[a]) / 400.0));
+                let actual = match a_result.cmp(&b_result) {
+                    Ordering::Greater => 1.0,
+                    Ordering::Equal => 0.5,
+                    Ordering::Less => 0.0,
+                };
+                delta[a] += k * (actual - expected);
+                delta[b] -= k * (actual - expected);
+            }
+        }
+        for (rating, delta) in ratings.iter_mut().zip(delta) {
+            *rating += delta;
+        }
+    }
+    ratings
+}
+
+/// 95% interval per entrant: rate `resamples` bootstrap resamples of the
+/// games (drawn with replacement, so also in a fresh order -- Elo depends
+/// on it) and take the 2.5th and 97.5th percentiles.
+fn bootstrap_intervals(
+    entrants: usize,
+    games: &[Placings],
+    resamples: usize,
+    rng: &mut StdRng,
+) -> Vec<(f64, f64)> {
+    if games.is_empty() || resamples == 0 {
+        return vec![(INITIAL_RATING, INITIAL_RATING); entrants];
+    }
+    let mut samples = vec![Vec::with_capacity(resamples); entrants];
+    for _ in 0..resamples {
+        let resample: Vec<&Placings> = (0..games.len())
+            .map(|_| &games[rng.random_range(..games.len())])
+            .collect();
+        for (entrant, rating) in elo(entrants, resample).into_iter().enumerate() {
+            samples[entrant].push(rating);
+        }
+    }
+    samples
+        .into_iter()
+        .map(|mut ratings| {
+            ratings.sort_by(f64::total_cmp);
+            let at = |q: f64| ratings[((ratings.len() - 1) as f64 * q).round() as usize];
+            (at(0.025), at(0.975))
+        })
+        .collect()
+}
+
+struct LeagueRow {
+    label: String,
+    rating: f64,
+    interval: (f64, f64),
+    games: usize,
+    wins: usize,
+    placings: usize,
+    /// Sum of the final `evaluate_position` over the rated games.
+    evaluations: f64,
+}
+
+fn league_table(entrants: &[Entrant], games: &[Placings], mut rows: Vec<LeagueRow>) -> String {
+    rows.sort_by(|a, b| b.rating.total_cmp(&a.rating));
+    let width = entrants
+        .iter()
+        .map(|e| e.label.len())
+        .max()
+        .unwrap_or(0)
+        .max(7);
+    let mut out = format!(
+        "{} rated games\n{:>3}  {:<width$}  {:>5}  {:>13}  {:>5}  {:>4}  {:>5}  {:>9}  {:>8}\n",
+        games.len(),
+        "#",
+        "entrant",
+        "Elo",
+        "95% CI",
+        "games",
+        "wins",
+        "win%",
+        "avg place",
+        "avg eval",
+    );
+    for (rank, row) in rows.iter().enumerate() {
+        let (win_rate, average_place, average_evaluation) = if row.games == 0 {
+            (0.0, 0.0, 0.0)
+        } else {
+            (
+                100.0 * row.wins as f64 / row.games as f64,
+                row.placings as f64 / row.games as f64,
+                row.evaluations / row.games as f64,
+            )
+        };
+        out.push_str(&format!(
+            "{:>3}  {:<width$}  {:>5.0}  {:>13}  {:>5}  {:>4}  {:>5.1}  {:>9.2}  {:>8.3}\n",
+            rank + 1,
+            row.label,
+            row.rating,
+            format!("[{:.0}, {:.0}]", row.interval.0, row.interval.1),
+            row.games,
+            row.wins,
+            win_rate,
+            average_place,
+            average_evaluation,
+        ));
+    }
+    out
+}
+
+fn main() -> ExitCode {
+    let config = match TournamentConfig::from_args(std::env::args().skip(1)) {
+        Ok(config) => config,
+        Err(e) => {
+            if !e.is_empty() {
+                eprintln!("{e}");
+            }
+            eprintln!("{USAGE}");
+            return ExitCode::from(2);
+        }
+    };
+    let entrants = config.entrants.len();
+    let mut rng = StdRng::seed_from_u64(config.first_seed);
+
+    let mut rated: Vec<Placings> = Vec::new();
+    let mut evaluations = vec![0.0; entrants];
+    let mut stalled = 0;
+    for game in 0..config.games {
+        let players = config.player_counts[game % config.player_counts.len()];
+        let table = table_for(game, players, entrants, &mut rng);
+
+        let mut app = simulation::simulation_app(
+            DebugOptions {
+                number_of_players: players,
+                game_seed: Some(config.first_seed + game as u64),
+                ..DebugOptions::default()
+            },
+            config.round_limit,
+        );
+        simulation::seat_at_table(
+            &mut app,
+            table
+                .iter()
+                .map(|&e| (e, config.entrants[e].personality.clone()))
+                .collect(),
+        );
+
+        let summary = simulation::play(&mut app, game, config.stall_frames);
+        let seated = simulation::seated(&app);
+        let label = |faction: &GameFaction| {
+            seated
+                .get(faction)
+                .map_or("?", |&e| config.entrants[e].label.as_str())
+        };
+        eprintln!(
+            "game {} (seed {}): {:?} after {} rounds, {:.1}s -- {}",
+            summary.game,
+            summary.seed,
+            summary.outcome,
+            summary.rounds,
+            summary.seconds,
+            summary
+                .standings
+                .iter()
+                .map(|p| format!(
+                    "{} ({:?}) {} [{:.2}]",
+                    label(&p.faction),
+                    p.faction,
+                    p.score.unwrap_or(0),
+                    p.evaluation
+                ))
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+
+        if summary.outcome == Outcome::Stalled {
+            stalled += 1;
+            continue;
+        }
+        for p in &summary.standings {
+            if let Some(&entrant) = seated.get(&p.faction) {
+                evaluations[entrant] += f64::from(p.evaluation);
+            }
+        }
+        rated.push(
+            summary
+                .standings
+                .iter()
+                .filter_map(|p| {
+                    let entrant = *seated.get(&p.faction)?;
+                    Some((entrant, (p.score.unwrap_or(0), p.ast_space)))
+                })
+                .collect(),
+        );
+    }
+
+    let ratings = elo(entrants, &rated);
+    let intervals = bootstrap_intervals(entrants, &rated, config.bootstrap, &mut rng);
+    let rows = config
+        .entrants
+        .iter()
+        .enumerate()
+        .map(|(e, entrant)| {
+            let mut row = LeagueRow {
+                label: entrant.label.clone(),
+                rating: ratings[e],
+                interval: intervals[e],
+                games: 0,
+                wins: 0,
+                placings: 0,
+                evaluations: evaluations[e],
+            };
+            for table in &rated {
+                for (place, _) in table.iter().enumerate().filter(|(_, (who, _))| *who == e) {
+                    row.games += 1;
+                    row.placings += place + 1;
+                    row.wins += usize::from(place == 0);
+                }
+            }
+            row
+        })
+        .collect();
+    print!("{}", league_table(&config.entrants, &rated, rows));
+
+    if stalled > 0 {
+        eprintln!(
+            "{stalled} of {} games stalled and were not rated",
+            config.games
+        );
+        return ExitCode::FAILURE;
+    }
+    ExitCode::SUCCESS
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use adv_civ::stupid_ai::Playstyle;
+
+    #[test]
+    fn an_entrant_that_always_wins_rates_above_one_that_always_loses() {
+        let games: Vec<Placings> = (0..20)
+            .map(|_| vec![(0, (900, 5)), (2, (600, 4)), (1, (300, 3))])
+            .collect();
+        let ratings = elo(3, &games);
+        assert!(
+            ratings[0] > ratings[2] && ratings[2] > ratings[1],
+            "{ratings:?}"
+        );
+        let total: f64 = ratings.iter().sum();
+        assert!(
+            (total - 3.0 * INITIAL_RATING).abs() < 1e-6,
+            "Elo is zero-sum"
+        );
+    }
+
+    #[test]
+    fn draws_and_mirror_seats_leave_ratings_alone() {
+        let games: Vec<Placings> = vec![
+            vec![(0, (500, 2)), (1, (500, 2))],
+            vec![(0, (800, 2)), (0, (100, 1))],
+        ];
+        assert_eq!(elo(2, &games), vec![INITIAL_RATING; 2]);
+    }
+
+    #[test]
+    fn entrant_specs_parse_labels_and_knobs() {
+        let library = Personalities::default();
+        let plain = Entrant::parse("warlord", &library).unwrap();
+        assert_eq!(plain.label, "Warlord");
+        assert_eq!(
+            plain.personality.weights,
+            Personality::from_playstyle(Playstyle::Warlord).weights
+        );
+
+        let custom = Entrant::parse("cautious=warlord:risk=0.3, defense=0.6", &library).unwrap();
+        assert_eq!(custom.label, "cautious");
+        assert_eq!(custom.personality.name, "cautious");
+        assert_eq!(custom.personality.weights.risk, 0.3);
+        assert_eq!(custom.personality.weights.defense, 0.6);
+
+        let unlabelled = Entrant::parse("merchant:risk=0.1", &library).unwrap();
+        assert_eq!(unlabelled.label, "merchant:risk=0.1");
+
+        assert!(Entrant::parse("warlord:bravery=1", &library).is_err());
+        assert!(Entrant::parse("pacifist", &library).is_err());
+    }
+
+    #[test]
+    fn every_entrant_sits_down_about_equally_often() {
+        let mut rng = StdRng::seed_from_u64(7);
+        let mut seats = [0; 6];
+        for game in 0..12 {
+            for entrant in table_for(game, 5, 6, &mut rng) {
+                seats[entrant] += 1;
+            }
+        }
+        assert_eq!(seats, [10; 6]);
+    }
+}
@@ -6,7 +6,7 @@
 //! resources unconditionally, so this plugin inserts inert stand-ins
 //! (`ButtonInput`, `Touches`, `TextureAssets` with dead handles, `LavaTheme`).
 //!
-//! State flow: `Loading` (wait for map + civ-card RON assets) → `Menu`
+//! State flow: `Loading` (wait for map, civ-card and personality RON assets) → `Menu`
 //! (lobby; clients claim seats) → `Playing` (normal `GameActivity` flow).
 //! Seats reserve their factions via `DebugOptions::reserved_factions`, and
 //! `bind_seats` swaps those players from AI to human at `StartGame`.
@@ -14,7 +14,7 @@
 use adv_civ::civilization::*;
 use adv_civ::loading::TextureAssets;
 use adv_civ::player::Player;
-use adv_civ::stupid_ai::{AgentControlled, IsHuman, StupidAi};
+use adv_civ::stupid_ai::{AgentControlled, IsHuman, PersonalitySources, StupidAi};
 use adv_civ::{GameActivity, GameState};
 use adv_civ_protocol::GameFaction;
 use bevy::asset::AssetPlugin;
@@ -63,14 +63,23 @@ impl Seats {
     }
 }
 
-pub struct HeadlessGamePlugin;
+#[derive(Default)]
+pub struct HeadlessGamePlugin {
+    /// No human seats whatever `SEATS` says: the game starts as soon as the
+    /// assets are in and the AI plays every faction (replays, batch runs).
+    pub ai_only: bool,
+}
 
 impl Plugin for HeadlessGamePlugin {
     fn build(&self, app: &mut App) {
         // Seat/player counts: SEATS human seats (default 2; 0 = AI-only
         // self-play), NUM_PLAYERS total players (default 5); the difference
         // is AI-controlled.
-        let human_seats = env_count("SEATS", 2).min(SEAT_FACTION_ORDER.len());
+        let human_seats = if self.ai_only {
+            0
+        } else {
+            env_count("SEATS", 2).min(SEAT_FACTION_ORDER.len())
+        };
         let total_players = env_count("NUM_PLAYERS", 5).clamp(human_seats.max(1), 9);
         let seat_factions: Vec<GameFaction> = SEAT_FACTION_ORDER
             .iter()
@@ -88,14 +97,15 @@ impl Plugin for HeadlessGamePlugin {
             .add_plugins(CivLogicPlugins);
 
         // Override the env-derived defaults: no local human, our seat
-        // factions reserved, no debug niceties.
+        // factions reserved, no debug niceties. The rest (notably GAME_SEED)
+        // still comes from the environment.
         app.insert_resource(DebugOptions {
             add_human_player: false,
             number_of_players: total_players,
             reserved_factions: seat_factions.clone(),
             show_debug_ui: false,
             print_selected_moves: false,
-            ..DebugOptions::default()
+            ..DebugOptions::from_env()
         });
 
         // Inert stand-ins for resources/messages that UI-flavoured systems
@@ -203,9 +213,12 @@ fn dead_texture_assets() -> TextureAssets {
 fn open_lobby_when_assets_ready(
     maps: Res<Assets<Map>>,
     civ_cards: Res<Assets<AvailableCivCards>>,
+    personalities: Option<Res<PersonalitySources>>,
+    asset_server: Res<AssetServer>,
     mut next: ResMut<NextState<GameState>>,
 ) {
-    if !maps.is_empty() && !civ_cards.is_empty() {
+    let personalities_settled = personalities.is_some_and(|p| p.is_settled(&asset_server));
+    if !maps.is_empty() && !civ_cards.is_empty() && personalities_settled {
         info!("Assets loaded — lobby open");
         next.set(GameState::Menu);
     }
@@ -14,7 +14,7 @@
 //! join requests into the ECS through a channel, so seat checks and
 //! client-id assignment happen on the game thread.
 
-use crate::game::Seats;
+use adv_civ_server::game::Seats;
 use base64::Engine;
 use bevy::platform::collections::HashMap;
 use bevy::prelude::*;
@@ -0,0 +1,5 @@
+//! The headless game shared by the server and the batch binaries: the boot
+//! in [`game`] and the AI-vs-AI runner in [`simulation`].
+
+pub mod game;
+pub mod simulation;
@@ -4,18 +4,25 @@
 //! no rendering, serving human seats over WebSocket. Configuration via env:
 //! `SEATS` (human seats, default 2), `NUM_PLAYERS` (total incl. AI,
 //! default 5), `PORT` (default 5111).
+//!
+//! `adv_civ_server --replay <file>` (or `GAME_REPLAY=<file>`) instead plays a
+//! `GAME_RECORD` recording back headless, as fast as it will go, with no
+//! networking: exit code 0 if every checkpoint matched, 1 on the first
+//! divergence. Meant for CI, to catch rule changes that alter game outcomes.
 
-mod game;
 mod http;
 mod net;
 
 use adv_civ::GameState;
+use adv_civ::civilization::{REPLAY_ENV_VAR, ReplayControl, ReplayFile, ReplayPlayback};
+use adv_civ_server::game;
 use bevy::app::ScheduleRunnerPlugin;
 use bevy::log::LogPlugin;
 use bevy::prelude::*;
 use bevy::state::app::StatesPlugin;
 use core::time::Duration;
 use lightyear::prelude::server::ServerPlugins;
+use std::path::PathBuf;
 
 /// Network tick rate. Turn-based game — nothing here is latency-sensitive.
 pub const TICK_HZ: f64 = 32.0;
@@ -27,7 +34,22 @@ pub fn server_port() -> u16 {
         .unwrap_or(5111)
 }
 
-fn main() {
+/// `--replay <file>` from the command line, else `GAME_REPLAY`.
+fn replay_path() -> Option<PathBuf> {
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        if arg == "--replay" {
+            return args.next().map(PathBuf::from);
+        }
+    }
+    std::env::var(REPLAY_ENV_VAR).ok().map(PathBuf::from)
+}
+
+fn main() -> AppExit {
+    if let Some(path) = replay_path() {
+        return run_replay(path);
+    }
+
     let mut app = App::new();
     app.add_plugins(
         MinimalPlugins.set(ScheduleRunnerPlugin::run_loop(Duration::from_secs_f64(
@@ -45,10 +67,52 @@ fn main() {
     app.add_plugins(adv_civ_protocol::ProtocolPlugin);
 
     app.add_plugins((
-        game::HeadlessGamePlugin,
+        game::HeadlessGamePlugin::default(),
         http::HttpApiPlugin,
         net::NetBridgePlugin,
     ));
 
-    app.run();
+    app.run()
+}
+
+fn run_replay(path: PathBuf) -> AppExit {
+    let file = match ReplayFile::load(&path) {
+        Ok(file) => file,
+        Err(e) => {
+            error!("Cannot replay {}: {}", path.display(), e);
+            return AppExit::error();
+        }
+    };
+    println!(
+        "Replaying {} ({} decisions, {} commands, {} checkpoints)",
+        path.display(),
+        file.decisions.len(),
+        file.commands.len(),
+        file.checkpoints.len()
+    );
+
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins.set(ScheduleRunnerPlugin::run_loop(Duration::ZERO)))
+        .add_plugins(LogPlugin::default())
+        .add_plugins(StatesPlugin)
+        .insert_state(GameState::Loading)
+        .add_plugins(game::HeadlessGamePlugin { ai_only: true })
+        .insert_resource(ReplayPlayback::new(file, ReplayControl::RunToEnd))
+        .add_systems(Update, exit_when_replay_done);
+    app.run()
+}
+
+fn exit_when_replay_done(playback: Res<ReplayPlayback>, mut exit: MessageWriter<AppExit>) {
+    if let Some(divergence) = playback.divergence() {
+        eprintln!("Replay diverged: {divergence}");
+        exit.write(AppExit::error());
+    } else if playback.is_finished() {
+        println!(
+            "Replay matched: {} decisions, {} commands, {} checkpoints",
+            playback.decisions_played(),
+            playback.commands_played(),
+            playback.checkpoints_verified()
+        );
+        exit.write(AppExit::Success);
+    }
 }
@@ -3,11 +3,11 @@
 //! protocol. This is the seam described in docs/multiplayer.md: clients only
 //! ever pick from moves the server offered.
 
-use crate::game::Seats;
 use adv_civ::civilization::*;
 use adv_civ::player::Player;
 use adv_civ::{GameActivity, GameState};
 use adv_civ_protocol::*;
+use adv_civ_server::game::Seats;
 use bevy::prelude::*;
 use core::net::{IpAddr, Ipv4Addr, SocketAddr};
 use lightyear::prelude::server::*;
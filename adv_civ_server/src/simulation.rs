@@ -0,0 +1,391 @@
+//! Headless AI-vs-AI games without networking, for the `simulate` and
+//! `tournament` binaries.
+//!
+//! Same boot as the server's AI-only mode (`HeadlessGamePlugin`), with time
+//! stepped by hand instead of waiting on the clock, and a tracker that tells
+//! a finished game from a stalled one.
+
+use crate::game::HeadlessGamePlugin;
+use adv_civ::civilization::resolve_calamities::resolve_calamities_events::CalamityResolved;
+use adv_civ::civilization::{
+    AvailableCivCards, DebugOptions, Faction, GameFaction, GameInfoAndStuff, GameResult,
+    RoundLimit, setup_players,
+};
+use adv_civ::player::Player;
+use adv_civ::stupid_ai::{
+    PERSONALITIES_DIR, Personalities, Personality, PlayerPosition, PositionQueryData,
+    PositionSnapshot, StupidAi, evaluate_position,
+};
+use adv_civ::{GameActivity, GameState};
+use bevy::prelude::*;
+use bevy::state::app::StatesPlugin;
+use bevy::time::TimeUpdateStrategy;
+use core::time::Duration;
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+/// Virtual time per frame: long enough that AI move delays and the trade
+/// countdown elapse in a handful of frames.
+const FRAME_STEP: Duration = Duration::from_millis(250);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    /// Reached GameOver.
+    Finished,
+    /// Sat in one phase for the stall limit.
+    Stalled,
+}
+
+pub struct PlayerSummary {
+    pub faction: GameFaction,
+    pub playstyle: String,
+    /// Final score (rule 35.1); `None` if the game never finished.
+    pub score: Option<u32>,
+    pub ast_space: u32,
+    pub cities: usize,
+    /// `evaluate_position` at the end: who was ahead, even in a game that
+    /// never finished.
+    pub evaluation: f32,
+}
+
+pub struct GameSummary {
+    pub game: usize,
+    pub seed: u64,
+    pub players: usize,
+    pub outcome: Outcome,
+    /// The phase a stalled game was stuck in; the last phase otherwise.
+    pub activity: String,
+    pub rounds: usize,
+    pub frames: u64,
+    pub seconds: f64,
+    pub winner: Option<GameFaction>,
+    /// Best first: by final score when the game finished, else by A.S.T. space
+    /// and then evaluation.
+    pub standings: Vec<PlayerSummary>,
+    pub calamities: BTreeMap<String, usize>,
+}
+
+impl GameSummary {
+    pub fn winner_playstyle(&self) -> Option<&str> {
+        let winner = self.winner?;
+        self.standings
+            .iter()
+            .find(|p| p.faction == winner)
+            .map(|p| p.playstyle.as_str())
+    }
+}
+
+/// Watches a running game: how long since the phase last changed, and every
+/// calamity resolved so far.
+#[derive(Resource, Default)]
+struct SimulationTracker {
+    frame: u64,
+    activity: Option<GameActivity>,
+    activity_since: u64,
+    calamities: BTreeMap<String, usize>,
+}
+
+fn track_progress(
+    mut tracker: ResMut<SimulationTracker>,
+    activity: Option<Res<State<GameActivity>>>,
+    mut resolved: MessageReader<CalamityResolved>,
+) {
+    tracker.frame += 1;
+    let current = activity.map(|a| a.get().clone());
+    if current != tracker.activity {
+        tracker.activity = current;
+        tracker.activity_since = tracker.frame;
+    }
+    for calamity in resolved.read() {
+        *tracker
+            .calamities
+            .entry(format!("{:?}", calamity.calamity))
+            .or_default() += 1;
+    }
+}
+
+/// `$BEVY_ASSET_ROOT/assets/definitions`, or the working directory's.
+pub fn definitions_dir() -> PathBuf {
+    let root = std::env::var("BEVY_ASSET_ROOT").unwrap_or_else(|_| ".".to_string());
+    Path::new(&root).join("assets").join(PERSONALITIES_DIR)
+}
+
+/// The personalities the games will load, read straight from
+/// [`definitions_dir`] so command lines can be checked against them before
+/// any game runs.
+pub fn personality_library() -> Result<Personalities, String> {
+    Personalities::load_dir(definitions_dir())
+}
+
+/// An AI-only game set up from `debug_options`, ready for [`play`]. Callers
+/// may add their own systems before playing it.
+pub fn simulation_app(debug_options: DebugOptions, round_limit: Option<usize>) -> App {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .add_plugins(StatesPlugin)
+        .insert_state(GameState::Loading)
+        .add_plugins(HeadlessGamePlugin { ai_only: true });
+
+    // Replaces what the headless plugin read from the environment.
+    app.insert_resource(DebugOptions {
+        add_human_player: false,
+        ai_move_delay_secs: 0.0,
+        show_debug_ui: false,
+        print_selected_moves: false,
+        autosave: false,
+        ..debug_options
+    })
+    .insert_resource(RoundLimit(round_limit))
+    .insert_resource(TimeUpdateStrategy::ManualDuration(FRAME_STEP))
+    .init_resource::<SimulationTracker>()
+    .add_systems(Last, track_progress);
+    app
+}
+
+/// This game's seating: one personality per seat, in seat order, each with
+/// the caller's tag for it.
+#[derive(Resource)]
+struct Table(Vec<(usize, Personality)>);
+
+/// Which tag ended up playing which faction.
+#[derive(Resource, Default)]
+struct Seated(HashMap<GameFaction, usize>);
+
+/// Seats are the AI players in faction order; factions themselves are dealt
+/// by the game seed, so seats rotate through them across seeds.
+fn seat_personalities(
+    table: Res<Table>,
+    mut seated: ResMut<Seated>,
+    mut players: Query<(&Faction, &mut Personality), With<StupidAi>>,
+) {
+    let mut players: Vec<_> = players.iter_mut().collect();
+    players.sort_by_key(|(faction, _)| format!("{:?}", faction.faction));
+    for ((faction, mut personality), (tag, seat)) in players.into_iter().zip(&table.0) {
+        *personality = seat.clone();
+        seated.0.insert(faction.faction, *tag);
+    }
+}
+
+/// Play `table`'s personalities instead of the usual line-up. Read back who
+/// played which faction with [`seated`] once the game is over.
+pub fn seat_at_table(app: &mut App, table: Vec<(usize, Personality)>) {
+    app.insert_resource(Table(table))
+        .init_resource::<Seated>()
+        .add_systems(
+            OnEnter(GameActivity::PrepareGame),
+            seat_personalities.after(setup_players),
+        );
+}
+
+/// The tag of the personality each faction was played by.
+pub fn seated(app: &App) -> &HashMap<GameFaction, usize> {
+    &app.world().resource::<Seated>().0
+}
+
+/// Run the game to GameOver, or until it spends `stall_frames` frames in one
+/// phase, and sum it up. The app is left as the game ended, for callers that
+/// want more than the summary.
+pub fn play(app: &mut App, game: usize, stall_frames: u64) -> GameSummary {
+    let started = Instant::now();
+    app.finish();
+    app.cleanup();
+
+    let outcome = loop {
+        app.update();
+        let world = app.world();
+        if world.contains_resource::<GameResult>() {
+            break Outcome::Finished;
+        }
+        let tracker = world.resource::<SimulationTracker>();
+        // Asset loading waits on real time, not frames; only count frames
+        // once the game is under way.
+        if tracker.activity.is_some() && tracker.frame - tracker.activity_since > stall_frames {
+            break Outcome::Stalled;
+        }
+    };
+
+    summarize(app.world_mut(), game, outcome, started)
+}
+
+fn summarize(world: &mut World, game: usize, outcome: Outcome, started: Instant) -> GameSummary {
+    let scores: BTreeMap<String, u32> = world
+        .get_resource::<GameResult>()
+        .map(|result| {
+            result
+                .standings
+                .iter()
+                .map(|(name, score, _)| (name.clone(), *score))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let defs = world.get_resource::<AvailableCivCards>().cloned();
+    let players: Vec<(String, String, PlayerPosition)> = world
+        .query_filtered::<(&Name, &Personality, PositionQueryData), With<Player>>()
+        .iter(world)
+        .map(|(name, personality, position)| {
+            (
+                name.to_string(),
+                personality.name.clone(),
+                PlayerPosition::from_components(position, defs.as_ref()),
+            )
+        })
+        .collect();
+    let snapshot = PositionSnapshot {
+        players: players.iter().map(|(_, _, p)| p.clone()).collect(),
+    };
+    let mut standings: Vec<PlayerSummary> = players
+        .into_iter()
+        .map(|(name, playstyle, position)| PlayerSummary {
+            faction: position.faction,
+            playstyle,
+            score: scores.get(name.as_str()).copied(),
+            ast_space: position.ast_space,
+            cities: position.cities,
+            evaluation: evaluate_position(position.faction, &snapshot),
+        })
+        .collect();
+    // Same order as the final scoring: score, then A.S.T. (rule 35.2).
+    standings.sort_by(|a, b| {
+        b.score
+            .cmp(&a.score)
+            .then(b.ast_space.cmp(&a.ast_space))
+            .then(b.evaluation.total_cmp(&a.evaluation))
+            .then(format!("{:?}", a.faction).cmp(&format!("{:?}", b.faction)))
+    });
+
+    let tracker = world.resource::<SimulationTracker>();
+    let debug_options = world.resource::<DebugOptions>();
+    GameSummary {
+        game,
+        seed: debug_options.game_seed.unwrap_or_default(),
+        players: standings.len(),
+        outcome,
+        activity: tracker
+            .activity
+            .as_ref()
+            .map_or_else(|| "Loading".to_string(), |a| format!("{a:?}")),
+        rounds: world.resource::<GameInfoAndStuff>().round,
+        frames: tracker.frame,
+        seconds: started.elapsed().as_secs_f64(),
+        winner: (outcome == Outcome::Finished)
+            .then(|| standings.first().map(|p| p.faction))
+            .flatten(),
+        standings,
+        calamities: tracker.calamities.clone(),
+    }
+}
+
+/// Points asset loading at the workspace root, for tests in any member crate
+/// that boot a real game.
+pub fn use_workspace_assets() {
+    static ONCE: std::sync::Once = std::sync::Once::new();
+    // SAFETY: only ever set here, once, before any game reads it.
+    ONCE.call_once(|| unsafe {
+        std::env::set_var(
+            "BEVY_ASSET_ROOT",
+            concat!(env!("CARGO_MANIFEST_DIR"), "/.."),
+        );
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use adv_civ::civilization::{AstPosition, Census, PlayerCities, PlayerTradeCards, Treasury};
+    use adv_civ::stupid_ai::Playstyle;
+
+    fn player(world: &mut World, faction: GameFaction, playstyle: Playstyle, space: u32) {
+        world.spawn((
+            Player,
+            Name::new(format!("{faction:?}")),
+            Faction::new(faction),
+            Personality::from_playstyle(playstyle),
+            AstPosition::new(space),
+            PlayerCities::default(),
+            Census::default(),
+            Treasury::default(),
+            PlayerTradeCards::default(),
+        ));
+    }
+
+    fn ended_world() -> World {
+        let mut world = World::new();
+        world.insert_resource(DebugOptions {
+            game_seed: Some(9),
+            ..DebugOptions::default()
+        });
+        world.insert_resource(GameInfoAndStuff {
+            round: 4,
+            ..Default::default()
+        });
+        world.insert_resource(SimulationTracker {
+            frame: 120,
+            activity: Some(GameActivity::Movement),
+            calamities: BTreeMap::from([("Flood".to_string(), 2)]),
+            ..Default::default()
+        });
+        player(&mut world, GameFaction::Egypt, Playstyle::Warlord, 5);
+        player(&mut world, GameFaction::Crete, Playstyle::Merchant, 3);
+        world
+    }
+
+    #[test]
+    fn a_finished_game_ranks_by_final_score() {
+        let mut world = ended_world();
+        world.insert_resource(GameResult {
+            standings: vec![("Crete".to_string(), 700, 3), ("Egypt".to_string(), 650, 5)],
+        });
+
+        let summary = summarize(&mut world, 3, Outcome::Finished, Instant::now());
+
+        assert_eq!((summary.game, summary.seed, summary.players), (3, 9, 2));
+        assert_eq!((summary.rounds, summary.frames), (4, 120));
+        assert_eq!(summary.activity, "Movement");
+        assert_eq!(summary.winner, Some(GameFaction::Crete));
+        assert_eq!(
+            summary.winner_playstyle(),
+            Some(
+                Personality::from_playstyle(Playstyle::Merchant)
+                    .name
+                    .as_str()
+            )
+        );
+        assert_eq!(summary.standings[0].score, Some(700));
+        assert_eq!(summary.standings[1].faction, GameFaction::Egypt);
+        assert_eq!(summary.calamities.get("Flood"), Some(&2));
+    }
+
+    #[test]
+    fn a_stalled_game_has_no_winner_and_ranks_by_ast() {
+        let mut world = ended_world();
+
+        let summary = summarize(&mut world, 0, Outcome::Stalled, Instant::now());
+
+        assert_eq!(summary.winner, None);
+        assert_eq!(summary.winner_playstyle(), None);
+        assert_eq!(summary.standings[0].faction, GameFaction::Egypt);
+        assert!(summary.standings.iter().all(|p| p.score.is_none()));
+    }
+
+    #[test]
+    fn a_short_seeded_game_plays_to_its_round_limit() {
+        use_workspace_assets();
+        let mut app = simulation_app(
+            DebugOptions {
+                number_of_players: 3,
+                game_seed: Some(5),
+                ..DebugOptions::default()
+            },
+            Some(2),
+        );
+
+        let summary = play(&mut app, 0, 20_000);
+
+        assert_eq!(summary.outcome, Outcome::Finished);
+        assert_eq!((summary.seed, summary.players), (5, 3));
+        assert!(summary.winner.is_some());
+        assert!(summary.standings.iter().all(|p| p.score.is_some()));
+    }
+}
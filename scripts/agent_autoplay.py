@@ -101,12 +101,17 @@ def play():
                 mv = get(f"/moves?faction={p['faction']}")
                 choice = pick_move(mv.get("moves", []) if mv else [])
                 if choice:
-                    r = post("/move", {"faction": p["faction"], "index": choice["index"]})
+                    token = mv.get("turn_token")
+                    r = post("/move", {"faction": p["faction"], "index": choice["index"],
+                                        "token": token})
                     ok = r.get("ok") if isinstance(r, dict) else r
                     print(f"[{phase}] {p['faction']} -> {choice.get('kind')} ({ok})")
+                    # Block until the move lands and the list is recomputed.
+                    get(f"/wait?faction={p['faction']}&since={token}&timeout_ms=2000")
                     acted = True
 
-        time.sleep(0.3 if acted else 0.6)
+        if not acted:
+            time.sleep(0.6)
 
 
 if __name__ == "__main__":
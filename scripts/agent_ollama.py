@@ -265,13 +265,18 @@ def play():
                             print(f"!! NOTE: first decision used the heuristic, not "
                                   f"{MODEL}. If this keeps happening, the model is "
                                   f"unreachable/erroring — check `ollama serve`.")
-                    r = post("/move", {"faction": p["faction"], "index": choice["index"]})
+                    token = mv.get("turn_token")
+                    r = post("/move", {"faction": p["faction"], "index": choice["index"],
+                                        "token": token})
                     ok = r.get("ok") if isinstance(r, dict) else r
                     print(f"[{phase}] {p['faction']} -> {choice.get('kind')} "
                           f"(via {src}, ok={ok}) [brain {brain_n}/heur {fallback_n}]")
+                    # Block until the move lands and the list is recomputed.
+                    get(f"/wait?faction={p['faction']}&since={token}&timeout_ms=2000")
                     acted = True
 
-        time.sleep(0.1 if acted else 0.6)
+        if not acted:
+            time.sleep(0.6)
 
 
 if __name__ == "__main__":
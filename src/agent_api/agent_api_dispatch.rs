@@ -0,0 +1,127 @@
+use crate::civilization::resolve_calamities::resolve_calamities_components::GrainLockedForPurchase;
+use crate::civilization::*;
+use crate::stupid_ai::compute_ai_payment;
+use bevy::platform::collections::HashMap;
+use bevy::prelude::*;
+
+/// Writes the command that plays `game_move` for `player`, moving `tokens`
+/// where the move takes a count. Shared by `POST /move` and the in-process
+/// RL environment so both play a move the same way.
+pub fn dispatch_game_move(
+    world: &mut World,
+    player: Entity,
+    game_move: &GameMove,
+    tokens: usize,
+) -> Result<(), String> {
+    match game_move {
+        GameMove::PopulationExpansion(m) => {
+            world.write_message(ExpandPopulationManuallyCommand::new(player, m.area, tokens));
+        }
+        GameMove::Movement(m) | GameMove::AttackArea(m) | GameMove::AttackCity(m) => {
+            world.write_message(MoveTokenFromAreaToAreaCommand::new(
+                m.source, m.target, tokens, player,
+            ));
+        }
+        GameMove::ShipFerry(m) => {
+            world.write_message(ShipFerryCommand::new(m.source, m.target, tokens, player));
+        }
+        GameMove::EndMovement => {
+            world.write_message(PlayerMovementEnded::new(player));
+        }
+        GameMove::CityConstruction(m) => {
+            world.write_message(BuildCityCommand::new(player, m.target));
+        }
+        GameMove::EndCityConstruction => {
+            world.write_message(EndPlayerCityConstruction::new(player));
+        }
+        GameMove::EliminateCity(m) => {
+            world.write_message(EliminateCity::new(player, m.city, m.area, false));
+        }
+        GameMove::AcquireCivilizationCards(AcquireCivilizationCardsMove::AcquireCard(card)) => {
+            let payment = civ_card_payment(world, player, *card)?;
+            world.write_message(ConfirmCivCardPurchase {
+                player,
+                cards_to_buy: vec![*card],
+                payment,
+            });
+        }
+        GameMove::AcquireCivilizationCards(AcquireCivilizationCardsMove::DoneAcquiringCards) => {
+            world.write_message(PlayerDoneAcquiringCivilizationCards(player));
+        }
+        GameMove::Trade(_) => {
+            return Err(format!("{game_move:?} goes through the trade routes"));
+        }
+    }
+    Ok(())
+}
+
+/// The AI's cheapest payment for a civilization card at its current cost.
+fn civ_card_payment(
+    world: &mut World,
+    player: Entity,
+    card: CivCardName,
+) -> Result<HashMap<TradeCard, usize>, String> {
+    let defs = world
+        .get_resource::<AvailableCivCards>()
+        .cloned()
+        .ok_or("no civilization card definitions")?;
+    let def = defs
+        .cards
+        .iter()
+        .find(|c| c.name == card)
+        .ok_or(format!("no definition for {card:?}"))?;
+    let mut holdings = world.query::<(
+        &PlayerTradeCards,
+        &PlayerCivilizationCards,
+        Option<&GrainLockedForPurchase>,
+        Option<&CardsHeldBeforePurchasing>,
+    )>();
+    let (trade_cards, civ_cards, grain_locked, held_before) =
+        holdings.get(world, player).map_err(|e| e.to_string())?;
+    // Rule 31.53: see CardsHeldBeforePurchasing's doc comment.
+    let credits = defs.total_credits(held_before.map_or(&civ_cards.cards, |c| &c.0));
+    let cost = def.calculate_cost(&credits) as usize;
+    Ok(compute_ai_payment(
+        trade_cards,
+        cost,
+        grain_locked.map_or(0, |l| l.0),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::player::Player;
+
+    #[test]
+    fn a_move_becomes_the_command_that_plays_it() {
+        let mut world = World::new();
+        world.init_resource::<Messages<MoveTokenFromAreaToAreaCommand>>();
+        let player = world.spawn(Player).id();
+        let (source, target) = (world.spawn_empty().id(), world.spawn_empty().id());
+        let attack = GameMove::AttackArea(MovementMove::new(source, target, player, 4));
+
+        dispatch_game_move(&mut world, player, &attack, 3).unwrap();
+
+        let messages = world.resource::<Messages<MoveTokenFromAreaToAreaCommand>>();
+        let sent: Vec<_> = messages.iter_current_update_messages().collect();
+        assert_eq!(sent.len(), 1);
+        assert_eq!(
+            (
+                sent[0].source_area,
+                sent[0].target_area,
+                sent[0].number_of_tokens
+            ),
+            (source, target, 3)
+        );
+    }
+
+    #[test]
+    fn trade_moves_are_not_dispatched() {
+        let mut world = World::new();
+        let player = world.spawn(Player).id();
+        let trade = GameMove::Trade(TradeMove::StopTrading);
+
+        assert!(dispatch_game_move(&mut world, player, &trade, 1).is_err());
+    }
+}
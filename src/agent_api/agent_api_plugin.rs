@@ -1,4 +1,5 @@
 use crate::agent_api::agent_api_systems::{AgentServer, poll_agent_api};
+use crate::agent_api::agent_api_turns::{ParkedWaits, TurnTokens, update_turn_tokens};
 use bevy::prelude::*;
 use tiny_http::Server;
 
@@ -17,7 +18,9 @@ impl Plugin for AgentApiPlugin {
                 // Always-on so an agent can poll /state to learn when a game starts;
                 // handlers simply report no human player when not in a game.
                 app.insert_resource(AgentServer { server })
-                    .add_systems(Update, poll_agent_api);
+                    .init_resource::<TurnTokens>()
+                    .init_resource::<ParkedWaits>()
+                    .add_systems(Update, (update_turn_tokens, poll_agent_api).chain());
             }
             Err(e) => {
                 warn!("[agent-api] disabled — could not bind {AGENT_API_ADDR}: {e}");
@@ -1,25 +1,84 @@
 use crate::GameActivity;
+use crate::agent_api::agent_api_dispatch::dispatch_game_move;
+use crate::agent_api::agent_api_turns::{ParkedWait, ParkedWaits, TurnTokens, wait_timeout};
 use crate::civilization::*;
-use crate::stupid_ai::{AgentControlled, compute_ai_payment};
+use crate::stupid_ai::{AgentControlled, AiDecisionTraces, DecisionTrace};
 use bevy::ecs::system::SystemParam;
 use bevy::platform::collections::HashMap;
 use bevy::prelude::*;
 use serde_json::{Value, json};
-use tiny_http::{Header, Method, Response, Server};
+use std::sync::PoisonError;
+use std::time::Instant;
+use tiny_http::{Header, Method, Request, Response, Server};
 
-/// The command writers the agent move-translator emits into, bundled so
-/// `poll_agent_api` stays under Bevy's system-parameter limit.
+/// What `GET /state/full` reads: the save snapshot plus the map around it.
 #[derive(SystemParam)]
-pub struct MoveWriters<'w> {
-    expand: MessageWriter<'w, ExpandPopulationManuallyCommand>,
-    move_tokens: MessageWriter<'w, MoveTokenFromAreaToAreaCommand>,
-    ferry: MessageWriter<'w, ShipFerryCommand>,
-    end_move: MessageWriter<'w, PlayerMovementEnded>,
-    build_city: MessageWriter<'w, BuildCityCommand>,
-    end_city: MessageWriter<'w, EndPlayerCityConstruction>,
-    eliminate_city: MessageWriter<'w, EliminateCity>,
-    done_civ: MessageWriter<'w, PlayerDoneAcquiringCivilizationCards>,
-    purchase: MessageWriter<'w, ConfirmCivCardPurchase>,
+pub struct FullStateSource<'w, 's> {
+    save: SaveDataSource<'w, 's>,
+    areas: Query<
+        'w,
+        's,
+        (
+            &'static GameArea,
+            Option<&'static Name>,
+            &'static Population,
+            &'static LandPassage,
+            Option<&'static SeaPassage>,
+            Has<CitySite>,
+            Has<FloodPlain>,
+            Has<Volcano>,
+        ),
+    >,
+    area_ids: Query<'w, 's, &'static GameArea>,
+}
+
+/// The fixed parts of one area, for `GET /state/full`.
+struct MapArea {
+    area_id: i32,
+    name: String,
+    max_population: usize,
+    land: Vec<i32>,
+    sea: Vec<i32>,
+    city_site: bool,
+    flood_plain: bool,
+    volcano: bool,
+}
+
+impl FullStateSource<'_, '_> {
+    /// Every area by ascending id, connections as area ids.
+    fn map(&self) -> Vec<MapArea> {
+        let ids = |areas: &[Entity]| -> Vec<i32> {
+            let mut ids: Vec<i32> = areas
+                .iter()
+                .filter_map(|a| self.area_ids.get(*a).ok())
+                .map(|a| a.id)
+                .collect();
+            ids.sort_unstable();
+            ids
+        };
+        let mut map: Vec<MapArea> = self
+            .areas
+            .iter()
+            .map(
+                |(area, name, population, land, sea, city_site, flood_plain, volcano)| MapArea {
+                    area_id: area.id,
+                    // Area entities are named "<id>:<place>".
+                    name: name
+                        .map(|n| n.as_str().split_once(':').map_or(n.as_str(), |(_, p)| p))
+                        .unwrap_or_default()
+                        .to_string(),
+                    max_population: population.max_population,
+                    land: ids(&land.to_areas),
+                    sea: sea.map_or_else(Vec::new, |s| ids(&s.to_areas)),
+                    city_site,
+                    flood_plain,
+                    volcano,
+                },
+            )
+            .collect();
+        map.sort_by_key(|a| a.area_id);
+        map
+    }
 }
 
 /// Holds the embedded HTTP server. `tiny_http::Server` is `Send + Sync`, so it
@@ -47,6 +106,8 @@ struct PlayerInfo {
     can_trade: bool,
     /// Commodity cards in hand, by card name.
     hand: Vec<(String, usize)>,
+    /// What `POST /move` must send back; `None` while a move is pending.
+    turn_token: Option<u64>,
 }
 
 /// An `OpenTradeOffer`, flattened to owned data for the API.
@@ -113,62 +174,32 @@ impl PlayerInfo {
     }
 }
 
-/// A chosen move resolved to concrete parameters, ready to emit as a command.
-#[derive(Debug, PartialEq, Eq)]
-enum ResolvedMove {
-    Expand {
-        player: Entity,
-        area: Entity,
-        tokens: usize,
-    },
-    MoveTokens {
-        source: Entity,
-        target: Entity,
-        tokens: usize,
-        player: Entity,
-    },
-    ShipFerry {
-        source: Entity,
-        target: Entity,
-        tokens: usize,
-        player: Entity,
-    },
-    EndMovement {
-        player: Entity,
-    },
-    BuildCity {
-        player: Entity,
-        area: Entity,
-    },
-    EndCityConstruction {
-        player: Entity,
-    },
-    EliminateCity {
-        player: Entity,
-        city: Entity,
-        area: Entity,
-    },
-    AcquireCivCard {
-        player: Entity,
-        card: CivCardName,
-    },
-    DoneAcquiringCivCards {
-        player: Entity,
-    },
+/// A chosen move resolved for its player, with the token count clamped to
+/// what the move allows, ready for [`dispatch_game_move`].
+#[derive(Debug)]
+struct ResolvedMove {
+    player: Entity,
+    game_move: GameMove,
+    tokens: usize,
 }
 
 impl ResolvedMove {
     fn applied_json(&self, faction: &str) -> Value {
-        let kind = match self {
-            ResolvedMove::Expand { .. } => "PopulationExpansion",
-            ResolvedMove::MoveTokens { .. } => "Movement",
-            ResolvedMove::ShipFerry { .. } => "ShipFerry",
-            ResolvedMove::EndMovement { .. } => "EndMovement",
-            ResolvedMove::BuildCity { .. } => "CityConstruction",
-            ResolvedMove::EndCityConstruction { .. } => "EndCityConstruction",
-            ResolvedMove::EliminateCity { .. } => "EliminateCity",
-            ResolvedMove::AcquireCivCard { .. } => "AcquireCard",
-            ResolvedMove::DoneAcquiringCivCards { .. } => "DoneAcquiringCards",
+        let kind = match &self.game_move {
+            GameMove::PopulationExpansion(_) => "PopulationExpansion",
+            GameMove::Movement(_) | GameMove::AttackArea(_) | GameMove::AttackCity(_) => "Movement",
+            GameMove::ShipFerry(_) => "ShipFerry",
+            GameMove::EndMovement => "EndMovement",
+            GameMove::CityConstruction(_) => "CityConstruction",
+            GameMove::EndCityConstruction => "EndCityConstruction",
+            GameMove::EliminateCity(_) => "EliminateCity",
+            GameMove::Trade(_) => "Trade",
+            GameMove::AcquireCivilizationCards(AcquireCivilizationCardsMove::AcquireCard(_)) => {
+                "AcquireCard"
+            }
+            GameMove::AcquireCivilizationCards(
+                AcquireCivilizationCardsMove::DoneAcquiringCards,
+            ) => "DoneAcquiringCards",
         };
         json!({ "ok": true, "applied": kind, "faction": faction })
     }
@@ -246,14 +277,11 @@ pub fn poll_agent_api(
     area_query: AreaQuery,
     mut offer_query: OfferQuery,
     faction_query: FactionQuery,
-    civ_data: Option<Res<AvailableCivCards>>,
-    civ_cards_query: Query<(
-        &PlayerTradeCards,
-        &PlayerCivilizationCards,
-        Option<&crate::civilization::resolve_calamities::resolve_calamities_components::GrainLockedForPurchase>,
-        Option<&crate::civilization::CardsHeldBeforePurchasing>,
-    )>,
-    mut writers: MoveWriters,
+    traces: Option<Res<AiDecisionTraces>>,
+    debug_options: Option<Res<DebugOptions>>,
+    mut tokens: ResMut<TurnTokens>,
+    mut waits: ResMut<ParkedWaits>,
+    full_state: FullStateSource,
 ) {
     let snapshot = build_snapshot(
         activity.as_ref(),
@@ -261,7 +289,10 @@ pub fn poll_agent_api(
         &area_query,
         &offer_query,
         &faction_query,
+        &tokens,
     );
+    let waits = waits.0.get_mut().unwrap_or_else(PoisonError::into_inner);
+    answer_parked_waits(waits, &snapshot, &tokens);
 
     while let Ok(Some(request)) = server.server.try_recv() {
         let mut request = request;
@@ -270,11 +301,30 @@ pub fn poll_agent_api(
         let path = url.split('?').next().unwrap_or("").to_string();
         let faction_q = query_param(&url, "faction");
 
+        if method == Method::Get && path == "/wait" {
+            if let Some(parked) = park_wait(request, &url, &snapshot, &tokens) {
+                waits.push(parked);
+            }
+            continue;
+        }
+
         let body: Value = match (&method, path.as_str()) {
             (Method::Get, "/state") => state_json(&snapshot),
+            (Method::Get, "/state/full") => full_state_json(
+                &snapshot,
+                faction_q.as_deref(),
+                &full_state.save.capture(),
+                &full_state.map(),
+            ),
             (Method::Get, "/players") => players_json(&snapshot),
             (Method::Get, "/moves") => moves_json(&snapshot, faction_q.as_deref()),
             (Method::Get, "/trade") => trade_json(&snapshot, faction_q.as_deref()),
+            (Method::Get, "/ai/trace") => trace_json(
+                traces.as_deref(),
+                faction_q.as_deref(),
+                query_param(&url, "history").and_then(|n| n.parse().ok()),
+                debug_options.is_some_and(|d| d.show_debug_ui),
+            ),
             (Method::Post, "/trade/stop") => {
                 let payload = read_json_body(&mut request);
                 let faction = payload
@@ -403,98 +453,29 @@ pub fn poll_agent_api(
                     .and_then(|v| v.as_str())
                     .map(std::string::ToString::to_string)
                     .or(faction_q);
-                match resolve_move(&snapshot, faction.as_deref(), payload) {
+                match resolve_move(&snapshot, faction.as_deref(), payload, &tokens) {
                     Err(e) => e,
                     Ok((resolved, faction)) => {
-                        match &resolved {
-                            ResolvedMove::Expand {
-                                player,
-                                area,
-                                tokens,
-                            } => {
-                                writers.expand.write(ExpandPopulationManuallyCommand::new(
-                                    *player, *area, *tokens,
-                                ));
-                            }
-                            ResolvedMove::MoveTokens {
-                                source,
-                                target,
-                                tokens,
-                                player,
-                            } => {
-                                writers
-                                    .move_tokens
-                                    .write(MoveTokenFromAreaToAreaCommand::new(
-                                        *source, *target, *tokens, *player,
-                                    ));
-                            }
-                            ResolvedMove::ShipFerry {
-                                source,
-                                target,
-                                tokens,
-                                player,
-                            } => {
-                                writers.ferry.write(ShipFerryCommand::new(
-                                    *source, *target, *tokens, *player,
-                                ));
-                            }
-                            ResolvedMove::EndMovement { player } => {
-                                writers.end_move.write(PlayerMovementEnded::new(*player));
-                            }
-                            ResolvedMove::BuildCity { player, area } => {
-                                writers
-                                    .build_city
-                                    .write(BuildCityCommand::new(*player, *area));
-                            }
-                            ResolvedMove::EndCityConstruction { player } => {
-                                writers
-                                    .end_city
-                                    .write(EndPlayerCityConstruction::new(*player));
+                        tokens.spend(faction);
+                        let body = resolved.applied_json(&format!("{faction:?}"));
+                        commands.queue(move |world: &mut World| {
+                            if let Err(e) = dispatch_game_move(
+                                world,
+                                resolved.player,
+                                &resolved.game_move,
+                                resolved.tokens,
+                            ) {
+                                warn!("[agent-api] {faction:?} move not played: {e}");
                             }
-                            ResolvedMove::EliminateCity { player, city, area } => {
-                                writers
-                                    .eliminate_city
-                                    .write(EliminateCity::new(*player, *city, *area, false));
-                            }
-                            ResolvedMove::AcquireCivCard { player, card } => {
-                                if let (
-                                    Some(cards_res),
-                                    Ok((trade_cards, civ_cards, grain_locked, cards_held_before)),
-                                ) = (civ_data.as_deref(), civ_cards_query.get(*player))
-                                {
-                                    // Rule 31.53: see CardsHeldBeforePurchasing's doc comment.
-                                    let credits = cards_res.total_credits(
-                                        cards_held_before.map_or(&civ_cards.cards, |c| &c.0),
-                                    );
-                                    if let Some(def) =
-                                        cards_res.cards.iter().find(|c| c.name == *card)
-                                    {
-                                        let cost = def.calculate_cost(&credits) as usize;
-                                        let payment = compute_ai_payment(
-                                            trade_cards,
-                                            cost,
-                                            grain_locked.map_or(0, |l| l.0),
-                                        );
-                                        writers.purchase.write(ConfirmCivCardPurchase {
-                                            player: *player,
-                                            cards_to_buy: vec![*card],
-                                            payment,
-                                        });
-                                    }
-                                }
-                            }
-                            ResolvedMove::DoneAcquiringCivCards { player } => {
-                                writers
-                                    .done_civ
-                                    .write(PlayerDoneAcquiringCivilizationCards(*player));
-                            }
-                        }
-                        resolved.applied_json(&faction)
+                        });
+                        body
                     }
                 }
             }
             _ => json!({ "error": "unknown route", "routes": [
-                "/state", "/players", "/moves?faction=", "POST /move {faction?,index,number?}",
+                "/state", "/state/full?faction=", "/players", "/moves?faction=", "POST /move {faction?,index,number?,token}",
+                "/wait?faction=&since=&timeout_ms=",
+                "/ai/trace?faction=&history=",
                 "/trade?faction=", "POST /trade/stop {faction?}",
                 "POST /trade/accept {faction?,id}",
                 "POST /trade/offer {faction?,offering_guaranteed,offering_hidden,wanting_guaranteed,wanting_hidden,target?}",
@@ -502,8 +483,7 @@ pub fn poll_agent_api(
             ] }),
         };
 
-        let response = Response::from_string(body.to_string()).with_header(json_header());
-        let _ = request.respond(response);
+        respond(request, &body);
     }
 }
 
@@ -522,6 +502,7 @@ fn build_snapshot(
     area_query: &AreaQuery,
     offer_query: &OfferQuery,
     faction_query: &FactionQuery,
+    tokens: &TurnTokens,
 ) -> Snapshot {
     let phase = activity.map_or_else(|| "NotPlaying".to_string(), |a| format!("{:?}", a.get()));
 
@@ -576,6 +557,7 @@ fn build_snapshot(
                     areas,
                     can_trade,
                     hand,
+                    turn_token: tokens.current(faction.faction),
                 }
             },
         )
@@ -616,6 +598,7 @@ fn player_summary(p: &PlayerInfo) -> Value {
         "faction": p.faction_str(),
         "name": p.name,
         "your_turn": !p.moves.is_empty(),
+        "turn_token": p.turn_token,
         "areas": areas,
     })
 }
@@ -628,11 +611,160 @@ fn state_json(snapshot: &Snapshot) -> Value {
     })
 }
 
+/// The whole board as `faction` sees it, built from the save snapshot with
+/// [`GameSaveData::public_view`]: every area with its connections, tokens
+/// and city, and every player's public holdings. Only `faction`'s own hand
+/// is listed; without `faction` no hand is.
+fn full_state_json(
+    snapshot: &Snapshot,
+    faction: Option<&str>,
+    board: &GameSaveData,
+    map: &[MapArea],
+) -> Value {
+    let viewer = match faction.map(|name| snapshot.select(Some(name))) {
+        None => None,
+        Some(Ok(player)) => Some(player.faction),
+        Some(Err(e)) => return e,
+    };
+    let view = board.public_view(viewer);
+    let name = |f: GameFaction| format!("{f:?}");
+    let areas: Vec<Value> = map
+        .iter()
+        .map(|area| {
+            let saved = view
+                .area_populations
+                .iter()
+                .find(|a| a.area_id == area.area_id);
+            let tokens: serde_json::Map<String, Value> = saved
+                .map(|a| {
+                    a.tokens_by_faction
+                        .iter()
+                        .filter(|(_, n)| *n > 0)
+                        .map(|(f, n)| (name(*f), json!(n)))
+                        .collect()
+                })
+                .unwrap_or_default();
+            let city = saved.and_then(|a| {
+                if a.city_is_pirate {
+                    Some("Pirate".to_string())
+                } else {
+                    a.city_owner.map(name)
+                }
+            });
+            json!({
+                "area_id": area.area_id,
+                "name": area.name,
+                "max_population": area.max_population,
+                "land": area.land,
+                "sea": area.sea,
+                "city_site": area.city_site,
+                "flood_plain": area.flood_plain,
+                "volcano": area.volcano,
+                "tokens": tokens,
+                "city": city,
+            })
+        })
+        .collect();
+    let players: Vec<Value> = view
+        .players
+        .iter()
+        .zip(&board.players)
+        .map(|(p, full)| {
+            let cities = view
+                .area_populations
+                .iter()
+                .filter(|a| !a.city_is_pirate && a.city_owner == Some(p.faction))
+                .count();
+            let mut civ_cards: Vec<String> =
+                p.owned_civ_cards.iter().map(|c| format!("{c:?}")).collect();
+            civ_cards.sort();
+            let mut player = json!({
+                "faction": name(p.faction),
+                "name": p.name,
+                "is_human": p.is_human,
+                "treasury": p.treasury,
+                "stock": p.tokens_in_stock,
+                "city_stock": p.city_tokens_in_stock,
+                "cities": cities,
+                "census": p.census_population,
+                "ast_space": p.ast_space,
+                "civ_cards": civ_cards,
+                "hand_size": full.trade_cards.iter().map(|(_, n)| n).sum::<usize>(),
+            });
+            if Some(p.faction) == viewer {
+                let hand: Vec<(String, usize)> = p
+                    .trade_cards
+                    .iter()
+                    .map(|(card, n)| (format!("{card}"), *n))
+                    .collect();
+                player["hand"] = map_json(&hand);
+            }
+            player
+        })
+        .collect();
+    json!({
+        "ok": true,
+        "faction": viewer.map(name),
+        "phase": snapshot.phase,
+        "round": view.round,
+        "census_order": view.census_order.iter().map(|f| name(*f)).collect::<Vec<_>>(),
+        "current_mover": view.current_mover.map(name),
+        "areas": areas,
+        "players": players,
+    })
+}
+
+/// The AI's latest decision per faction, or with `history` its last N
+/// decisions (oldest first). Only with the debug UI on do the traces show
+/// what the AI read off its own hidden hand.
+fn trace_json(
+    traces: Option<&AiDecisionTraces>,
+    faction: Option<&str>,
+    history: Option<usize>,
+    show_hands: bool,
+) -> Value {
+    let Some(traces) = traces else {
+        return json!({ "ok": false, "error": "no AI in this game" });
+    };
+    let mut factions: Vec<GameFaction> = traces.factions().collect();
+    factions.sort_by_key(|&f| f as u8);
+    if let Some(name) = faction {
+        factions.retain(|f| f.to_string().eq_ignore_ascii_case(name));
+        if factions.is_empty() {
+            return json!({ "ok": false, "error": format!("no AI decisions for faction '{name}'") });
+        }
+    }
+    let view = |trace: &DecisionTrace| {
+        if show_hands {
+            trace.clone()
+        } else {
+            trace.public_view()
+        }
+    };
+    let decisions = |f: GameFaction| -> Value {
+        match history {
+            Some(n) => {
+                let all: Vec<_> = traces.history(f).map(view).collect();
+                json!(&all[all.len().saturating_sub(n)..])
+            }
+            None => json!(traces.latest(f).map(view)),
+        }
+    };
+    json!({
+        "ok": true,
+        "traces": factions.iter().map(|&f| json!({
+            "faction": f.to_string(),
+            "decisions": decisions(f),
+        })).collect::<Vec<_>>(),
+    })
+}
+
 fn players_json(snapshot: &Snapshot) -> Value {
     json!({
         "phase": snapshot.phase,
         "players": snapshot.players.iter().map(|p| json!({
             "faction": p.faction_str(), "name": p.name, "your_turn": !p.moves.is_empty(),
+            "turn_token": p.turn_token,
         })).collect::<Vec<_>>(),
     })
 }
@@ -648,7 +780,12 @@ fn moves_json(snapshot: &Snapshot, faction: Option<&str>) -> Value {
         .map(|(index, game_move)| describe_move(*index, game_move, &snapshot.area_ids))
         .collect();
     list.sort_by_key(|v| v["index"].as_u64().unwrap_or(0));
-    json!({ "faction": player.faction_str(), "your_turn": !list.is_empty(), "moves": list })
+    json!({
+        "faction": player.faction_str(),
+        "your_turn": !list.is_empty(),
+        "turn_token": player.turn_token,
+        "moves": list,
+    })
 }
 
 /// JSON object from `(card_name, count)` pairs.
@@ -724,13 +861,39 @@ fn describe_move(index: usize, game_move: &GameMove, area_ids: &HashMap<Entity,
 }
 
 /// Resolves the move chosen by `index` for the selected player to a concrete
-/// `ResolvedMove` + the player's faction name, or an error JSON. Pure.
+/// `ResolvedMove` + the player's faction, or an error JSON. The payload's
+/// `token` must be the faction's current turn token. Pure.
 fn resolve_move(
     snapshot: &Snapshot,
     faction: Option<&str>,
     payload: Value,
-) -> Result<(ResolvedMove, String), Value> {
+    tokens: &TurnTokens,
+) -> Result<(ResolvedMove, GameFaction), Value> {
     let player_info = snapshot.select(faction)?;
+    let current = tokens.current(player_info.faction);
+    match payload.get("token").and_then(serde_json::Value::as_u64) {
+        None => {
+            return Err(json!({
+                "ok": false,
+                "error": "expected \"token\": the turn_token from /moves or /wait",
+                "turn_token": current,
+            }));
+        }
+        Some(_) if current.is_none() => {
+            return Err(json!({
+                "ok": false,
+                "error": "the previous move is still being applied; GET /wait for the new list",
+            }));
+        }
+        Some(token) if Some(token) != current => {
+            return Err(json!({
+                "ok": false,
+                "error": "stale turn token; the move list has changed",
+                "turn_token": current,
+            }));
+        }
+        Some(_) => {}
+    }
     let Some(index) = payload
         .get("index")
         .and_then(serde_json::Value::as_u64)
@@ -746,58 +909,103 @@ fn resolve_move(
     let Some((_, game_move)) = player_info.moves.iter().find(|(i, _)| *i == index) else {
         return Err(json!({ "ok": false, "error": format!("no move with index {index}") }));
     };
-    let player = player_info.player;
-    let clamp = |m: &MovementMove| number.unwrap_or(m.max_tokens).min(m.max_tokens).max(1);
-
-    let resolved = match game_move {
-        GameMove::PopulationExpansion(m) => ResolvedMove::Expand {
-            player,
-            area: m.area,
-            tokens: number.unwrap_or(m.max_tokens).min(m.max_tokens),
-        },
-        GameMove::Movement(m) | GameMove::AttackArea(m) | GameMove::AttackCity(m) => {
-            ResolvedMove::MoveTokens {
-                source: m.source,
-                target: m.target,
-                tokens: clamp(m),
-                player,
-            }
-        }
-        GameMove::ShipFerry(m) => ResolvedMove::ShipFerry {
-            source: m.source,
-            target: m.target,
-            tokens: clamp(m),
-            player,
-        },
-        GameMove::EndMovement => ResolvedMove::EndMovement { player },
-        GameMove::CityConstruction(m) => ResolvedMove::BuildCity {
-            player,
-            area: m.target,
-        },
-        GameMove::EndCityConstruction => ResolvedMove::EndCityConstruction { player },
-        GameMove::EliminateCity(m) => ResolvedMove::EliminateCity {
-            player,
-            city: m.city,
-            area: m.area,
-        },
-        GameMove::AcquireCivilizationCards(AcquireCivilizationCardsMove::AcquireCard(card)) => {
-            ResolvedMove::AcquireCivCard {
-                player,
-                card: *card,
-            }
-        }
-        GameMove::AcquireCivilizationCards(AcquireCivilizationCardsMove::DoneAcquiringCards) => {
-            ResolvedMove::DoneAcquiringCivCards { player }
-        }
-        other => {
+    let clamp = |max: usize| number.unwrap_or(max).min(max);
+    let tokens = match game_move {
+        GameMove::PopulationExpansion(m) => clamp(m.max_tokens),
+        GameMove::Movement(m)
+        | GameMove::AttackArea(m)
+        | GameMove::AttackCity(m)
+        | GameMove::ShipFerry(m) => clamp(m.max_tokens).max(1),
+        GameMove::Trade(_) => {
             return Err(json!({
                 "ok": false,
                 "error": "move kind not yet supported by the agent API",
-                "kind": format!("{:?}", other),
+                "kind": format!("{:?}", game_move),
             }));
         }
+        _ => 1,
+    };
+    let resolved = ResolvedMove {
+        player: player_info.player,
+        game_move: game_move.clone(),
+        tokens,
     };
-    Ok((resolved, player_info.faction_str()))
+    Ok((resolved, player_info.faction))
+}
+
+/// Answers `GET /wait` at once when the faction has already moved on past
+/// `since` (or `since` is absent), else parks it until it does or the
+/// timeout passes.
+fn park_wait(
+    request: Request,
+    url: &str,
+    snapshot: &Snapshot,
+    tokens: &TurnTokens,
+) -> Option<ParkedWait> {
+    let faction = query_param(url, "faction");
+    let player = match faction.as_deref().map(|f| snapshot.select(Some(f))) {
+        None => {
+            respond(
+                request,
+                &json!({ "ok": false, "error": "expected ?faction=<name>" }),
+            );
+            return None;
+        }
+        Some(Err(e)) => {
+            respond(request, &e);
+            return None;
+        }
+        Some(Ok(player)) => player,
+    };
+    let since = query_param(url, "since").and_then(|s| s.parse().ok());
+    if since.is_none() || tokens.moved_on(player.faction, since) {
+        respond(request, &wait_json(snapshot, player.faction, tokens, true));
+        return None;
+    }
+    Some(ParkedWait {
+        request,
+        faction: player.faction,
+        since,
+        deadline: Instant::now() + wait_timeout(query_param(url, "timeout_ms").as_deref()),
+    })
+}
+
+/// Releases the parked waits whose faction has moved on or whose time is up.
+fn answer_parked_waits(waits: &mut Vec<ParkedWait>, snapshot: &Snapshot, tokens: &TurnTokens) {
+    let now = Instant::now();
+    let mut still_parked = Vec::new();
+    for wait in waits.drain(..) {
+        let changed = tokens.moved_on(wait.faction, wait.since);
+        if changed || now >= wait.deadline {
+            let body = wait_json(snapshot, wait.faction, tokens, changed);
+            respond(wait.request, &body);
+        } else {
+            still_parked.push(wait);
+        }
+    }
+    *waits = still_parked;
+}
+
+fn wait_json(
+    snapshot: &Snapshot,
+    faction: GameFaction,
+    tokens: &TurnTokens,
+    changed: bool,
+) -> Value {
+    let player = snapshot.players.iter().find(|p| p.faction == faction);
+    json!({
+        "ok": true,
+        "faction": format!("{faction:?}"),
+        "changed": changed,
+        "turn_token": tokens.current(faction),
+        "phase": snapshot.phase,
+        "your_turn": player.is_some_and(|p| !p.moves.is_empty()),
+    })
+}
+
+fn respond(request: Request, body: &Value) {
+    let response = Response::from_string(body.to_string()).with_header(json_header());
+    let _ = request.respond(response);
 }
 
 #[cfg(test)]
@@ -810,6 +1018,12 @@ mod tests {
     #[derive(Resource, Default)]
     struct ResolveResult(Option<ResolvedMove>);
 
+    #[derive(Resource, Default)]
+    struct TokenErrors(Vec<String>);
+
+    #[derive(Resource, Default)]
+    struct FullState(Value);
+
     fn spawn_controlled(
         app: &mut App,
         faction: GameFaction,
@@ -854,11 +1068,50 @@ mod tests {
             &area_query,
             &offer_query,
             &faction_query,
+            &TurnTokens::default(),
         );
         // Two players have moves → must select by faction.
-        result.0 = resolve_move(&snapshot, Some("Egypt"), json!({ "index": 1, "number": 2 }))
-            .ok()
-            .map(|(rm, _)| rm);
+        result.0 = resolve_move(
+            &snapshot,
+            Some("Egypt"),
+            json!({ "index": 1, "number": 2, "token": 0 }),
+            &TurnTokens::default(),
+        )
+        .ok()
+        .map(|(rm, _)| rm);
+    }
+
+    fn run_resolve_with_tokens(
+        activity: Option<Res<State<GameActivity>>>,
+        controlled_query: ControlledQuery,
+        area_query: AreaQuery,
+        offer_query: OfferQuery,
+        faction_query: FactionQuery,
+        mut errors: ResMut<TokenErrors>,
+    ) {
+        let mut tokens = TurnTokens::default();
+        tokens.bump(GameFaction::Egypt);
+        let snapshot = build_snapshot(
+            activity.as_ref(),
+            &controlled_query,
+            &area_query,
+            &offer_query,
+            &faction_query,
+            &tokens,
+        );
+        let current = snapshot.players[0].turn_token.unwrap();
+        let mut attempt = |payload: Value, tokens: &TurnTokens| {
+            if let Err(e) = resolve_move(&snapshot, Some("Egypt"), payload, tokens) {
+                errors
+                    .0
+                    .push(e["error"].as_str().unwrap_or_default().to_string());
+            }
+        };
+        attempt(json!({ "index": 1 }), &tokens);
+        attempt(json!({ "index": 1, "token": current + 1 }), &tokens);
+        attempt(json!({ "index": 1, "token": current }), &tokens);
+        tokens.spend(GameFaction::Egypt);
+        attempt(json!({ "index": 1, "token": current }), &tokens);
     }
 
     #[test]
@@ -871,13 +1124,114 @@ mod tests {
         app.world_mut().run_system_once(run_resolve).unwrap();
 
         let resolved = app.world().resource::<ResolveResult>().0.as_ref().unwrap();
-        match resolved {
-            ResolvedMove::Expand { player, tokens, .. } => {
-                assert_eq!(*player, egypt, "selected the Egypt player");
-                assert_eq!(*tokens, 2, "clamped requested token count");
-            }
-            other => panic!("expected Expand, got {other:?}"),
-        }
+        assert!(matches!(
+            resolved.game_move,
+            GameMove::PopulationExpansion(_)
+        ));
+        assert_eq!(resolved.player, egypt, "selected the Egypt player");
+        assert_eq!(resolved.tokens, 2, "clamped requested token count");
+    }
+
+    fn run_full_state(
+        activity: Option<Res<State<GameActivity>>>,
+        controlled_query: ControlledQuery,
+        area_query: AreaQuery,
+        offer_query: OfferQuery,
+        faction_query: FactionQuery,
+        mut result: ResMut<FullState>,
+    ) {
+        let snapshot = build_snapshot(
+            activity.as_ref(),
+            &controlled_query,
+            &area_query,
+            &offer_query,
+            &faction_query,
+            &TurnTokens::default(),
+        );
+        let player = |faction| SavedPlayer {
+            name: format!("{faction:?}"),
+            faction,
+            is_human: true,
+            census_population: 4,
+            treasury: 1,
+            tokens_in_stock: 50,
+            city_tokens_in_stock: 8,
+            trade_cards: vec![(TradeCard::Ochre, 1), (TradeCard::Salt, 2)],
+            done_with_current_activity: false,
+            ast_space: 2,
+            owned_civ_cards: vec![CivCardName::Pottery],
+            calamity_traded_by: Vec::new(),
+        };
+        let board = GameSaveData {
+            version: SAVE_GAME_VERSION.to_string(),
+            round: 2,
+            game_activity: GameActivity::Trade,
+            players: vec![player(GameFaction::Egypt), player(GameFaction::Crete)],
+            area_populations: vec![SavedAreaPopulation {
+                area_id: 7,
+                tokens_by_faction: vec![(GameFaction::Crete, 2)],
+                city_owner: Some(GameFaction::Egypt),
+                city_is_pirate: false,
+            }],
+            census_order: vec![GameFaction::Egypt, GameFaction::Crete],
+            left_to_move: Vec::new(),
+            current_mover: None,
+            phase_state: SavedPhaseState::default(),
+        };
+        let map = [MapArea {
+            area_id: 7,
+            name: "Thebes".to_string(),
+            max_population: 3,
+            land: vec![6, 8],
+            sea: Vec::new(),
+            city_site: true,
+            flood_plain: true,
+            volcano: false,
+        }];
+        result.0 = full_state_json(&snapshot, Some("Egypt"), &board, &map);
+    }
+
+    #[test]
+    fn the_full_state_shows_only_the_requesters_hand() {
+        let mut app = App::new();
+        app.init_resource::<FullState>();
+        spawn_controlled(&mut app, GameFaction::Egypt, 9, 5);
+
+        app.world_mut().run_system_once(run_full_state).unwrap();
+
+        let state = &app.world().resource::<FullState>().0;
+        assert_eq!(state["faction"], "Egypt");
+        let area = &state["areas"][0];
+        assert_eq!(area["land"], json!([6, 8]));
+        assert_eq!(area["tokens"]["Crete"], 2);
+        assert_eq!(area["city"], "Egypt");
+        let (egypt, crete) = (&state["players"][0], &state["players"][1]);
+        assert_eq!(egypt["hand"]["Salt"], 2);
+        assert_eq!(egypt["cities"], 1);
+        assert!(crete.get("hand").is_none(), "another player's hand leaked");
+        assert_eq!(crete["hand_size"], 3);
+        assert_eq!(crete["civ_cards"], json!(["Pottery"]));
+    }
+
+    #[test]
+    fn moves_need_the_current_unspent_turn_token() {
+        let mut app = App::new();
+        app.init_resource::<TokenErrors>();
+        spawn_controlled(&mut app, GameFaction::Egypt, 9, 5);
+
+        app.world_mut()
+            .run_system_once(run_resolve_with_tokens)
+            .unwrap();
+
+        let errors = &app.world().resource::<TokenErrors>().0;
+        assert_eq!(
+            errors.len(),
+            3,
+            "only the current token is accepted: {errors:?}"
+        );
+        assert!(errors[0].contains("expected \"token\""));
+        assert!(errors[1].contains("stale"));
+        assert!(errors[2].contains("still being applied"));
     }
 
     #[test]
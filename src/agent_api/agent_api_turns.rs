@@ -0,0 +1,190 @@
+use crate::GameActivity;
+use crate::civilization::{AvailableMoves, Faction, GameFaction};
+use crate::stupid_ai::AgentControlled;
+use bevy::platform::collections::HashMap;
+use bevy::prelude::*;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tiny_http::Request;
+
+/// How long `GET /wait` blocks when the request names no `timeout_ms`.
+pub const DEFAULT_WAIT: Duration = Duration::from_secs(25);
+/// Upper bound on `timeout_ms`, so a parked request cannot outlive a client.
+pub const MAX_WAIT: Duration = Duration::from_secs(60);
+/// Frames a spent token waits for its list to change. Applying a move and
+/// recomputing the list takes a frame or two; a list still unchanged after
+/// this many means the game refused the move.
+pub const SETTLE_FRAMES: u8 = 4;
+
+/// Per-faction turn tokens. A faction's token changes whenever its
+/// `AvailableMoves` is added, recomputed or removed, and whenever the phase
+/// changes, so a token names one version of one move list.
+///
+/// Once `POST /move` has applied a move, the faction has no current token
+/// until its list changes: a second move against the old list is refused.
+/// If the list has not changed after [`SETTLE_FRAMES`] the move was refused,
+/// and the faction gets a fresh token for the same list.
+#[derive(Resource, Default)]
+pub struct TurnTokens {
+    counter: u64,
+    tokens: HashMap<GameFaction, u64>,
+    /// Frames left before a spent token is given up on.
+    spent: HashMap<GameFaction, u8>,
+}
+
+impl TurnTokens {
+    pub fn bump(&mut self, faction: GameFaction) {
+        self.counter += 1;
+        self.tokens.insert(faction, self.counter);
+        self.spent.remove(&faction);
+    }
+
+    /// The token a move for `faction` must carry; `None` while a move it
+    /// already made is waiting to be applied.
+    pub fn current(&self, faction: GameFaction) -> Option<u64> {
+        (!self.spent.contains_key(&faction))
+            .then(|| self.tokens.get(&faction).copied().unwrap_or(0))
+    }
+
+    pub fn spend(&mut self, faction: GameFaction) {
+        self.spent.insert(faction, SETTLE_FRAMES);
+    }
+
+    /// Counts down the spent tokens whose list has not changed, reissuing
+    /// those that ran out.
+    pub fn settle(&mut self) {
+        let mut refused = Vec::new();
+        for (faction, frames) in &mut self.spent {
+            *frames = frames.saturating_sub(1);
+            if *frames == 0 {
+                refused.push(*faction);
+            }
+        }
+        for faction in refused {
+            self.bump(faction);
+        }
+    }
+
+    /// Whether `GET /wait?since=<since>` for `faction` can return.
+    pub fn moved_on(&self, faction: GameFaction, since: Option<u64>) -> bool {
+        self.current(faction)
+            .is_some_and(|token| Some(token) != since)
+    }
+}
+
+/// Bumps the tokens of agent-controlled factions. Runs before
+/// `poll_agent_api`, so answers within a frame see the frame's move lists.
+pub fn update_turn_tokens(
+    activity: Option<Res<State<GameActivity>>>,
+    changed: Query<&Faction, (With<AgentControlled>, Changed<AvailableMoves>)>,
+    mut removed: RemovedComponents<AvailableMoves>,
+    agents: Query<&Faction, With<AgentControlled>>,
+    mut tokens: ResMut<TurnTokens>,
+) {
+    if activity.is_some_and(|a| a.is_changed()) {
+        for faction in &agents {
+            tokens.bump(faction.faction);
+        }
+        removed.clear();
+        return;
+    }
+    for faction in &changed {
+        tokens.bump(faction.faction);
+    }
+    for player in removed.read() {
+        if let Ok(faction) = agents.get(player) {
+            tokens.bump(faction.faction);
+        }
+    }
+    tokens.settle();
+}
+
+/// A `GET /wait` request held open until its faction moves on or it times
+/// out.
+pub struct ParkedWait {
+    pub request: Request,
+    pub faction: GameFaction,
+    pub since: Option<u64>,
+    pub deadline: Instant,
+}
+
+/// `tiny_http::Request` is `Send` but not `Sync`; the mutex makes the queue a
+/// valid resource and is only ever accessed through `ResMut`.
+#[derive(Resource, Default)]
+pub struct ParkedWaits(pub Mutex<Vec<ParkedWait>>);
+
+/// The wait a `timeout_ms` query parameter asks for, capped at [`MAX_WAIT`].
+pub fn wait_timeout(timeout_ms: Option<&str>) -> Duration {
+    timeout_ms
+        .and_then(|ms| ms.parse().ok())
+        .map_or(DEFAULT_WAIT, Duration::from_millis)
+        .min(MAX_WAIT)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a_spent_token_blocks_until_the_list_changes() {
+        let mut tokens = TurnTokens::default();
+        tokens.bump(GameFaction::Egypt);
+        let token = tokens.current(GameFaction::Egypt);
+
+        tokens.spend(GameFaction::Egypt);
+        assert_eq!(tokens.current(GameFaction::Egypt), None);
+        assert!(!tokens.moved_on(GameFaction::Egypt, token));
+
+        tokens.bump(GameFaction::Egypt);
+        assert!(tokens.moved_on(GameFaction::Egypt, token));
+        assert!(!tokens.moved_on(GameFaction::Egypt, tokens.current(GameFaction::Egypt)));
+    }
+
+    #[test]
+    fn a_refused_move_gets_a_fresh_token() {
+        let mut tokens = TurnTokens::default();
+        tokens.bump(GameFaction::Egypt);
+        let token = tokens.current(GameFaction::Egypt);
+        tokens.spend(GameFaction::Egypt);
+
+        for _ in 1..SETTLE_FRAMES {
+            tokens.settle();
+        }
+        assert_eq!(tokens.current(GameFaction::Egypt), None);
+        tokens.settle();
+
+        assert!(tokens.current(GameFaction::Egypt).is_some());
+        assert!(tokens.moved_on(GameFaction::Egypt, token));
+    }
+
+    #[test]
+    fn tokens_follow_move_lists() {
+        let mut app = App::new();
+        app.init_resource::<TurnTokens>()
+            .add_systems(Update, update_turn_tokens);
+        let egypt = app
+            .world_mut()
+            .spawn((AgentControlled, Faction::new(GameFaction::Egypt)))
+            .id();
+        app.world_mut()
+            .spawn((AgentControlled, Faction::new(GameFaction::Crete)));
+        app.update();
+        let current = |app: &App, f| app.world().resource::<TurnTokens>().current(f);
+        let (egypt_0, crete_0) = (
+            current(&app, GameFaction::Egypt),
+            current(&app, GameFaction::Crete),
+        );
+
+        app.world_mut()
+            .entity_mut(egypt)
+            .insert(AvailableMoves::new(HashMap::default()));
+        app.update();
+        let egypt_1 = current(&app, GameFaction::Egypt);
+        assert_ne!(egypt_1, egypt_0);
+        assert_eq!(current(&app, GameFaction::Crete), crete_0);
+
+        app.world_mut().entity_mut(egypt).remove::<AvailableMoves>();
+        app.update();
+        assert_ne!(current(&app, GameFaction::Egypt), egypt_1);
+    }
+}
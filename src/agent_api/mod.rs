@@ -1,5 +1,9 @@
+mod agent_api_dispatch;
 mod agent_api_plugin;
 mod agent_api_systems;
+mod agent_api_turns;
 
+pub use agent_api_dispatch::dispatch_game_move;
 pub use agent_api_plugin::{AGENT_API_ADDR, AgentApiPlugin};
 pub use agent_api_systems::AgentServer;
+pub use agent_api_turns::{DEFAULT_WAIT, MAX_WAIT, TurnTokens};
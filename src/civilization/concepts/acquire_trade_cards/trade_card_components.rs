@@ -4,8 +4,8 @@ use crate::civilization::concepts::acquire_trade_cards::trade_card_enums::{
 use bevy::platform::collections::{HashMap, HashSet};
 use bevy::prelude::{Color, Component, Entity, Reflect, ReflectComponent, Resource};
 use itertools::Itertools;
-use rand::RngExt;
 use rand::seq::SliceRandom;
+use rand::{Rng, RngExt};
 
 pub const MIN_CARDS_REQUIRED_TO_TRADE: usize = 5;
 
@@ -15,7 +15,7 @@ pub struct CivilizationTradeCards {
 }
 
 impl CivilizationTradeCards {
-    pub fn new() -> Self {
+    pub fn new<R: Rng + ?Sized>(rng: &mut R) -> Self {
         let mut cards: HashMap<usize, Vec<TradeCard>> = HashMap::new();
         for trade_card in TradeCard::iter() {
             cards
@@ -23,13 +23,21 @@ impl CivilizationTradeCards {
                 .or_default()
                 .extend(vec![trade_card; trade_card.number_of_cards()]);
         }
+        let mut deck = Self { card_piles: cards };
         // Shuffle each pile so calamities and commodities are mixed
-        let mut rng = rand::rng();
-        for pile in cards.values_mut() {
-            pile.shuffle(&mut rng);
+        deck.shuffle_piles(rng);
+        deck
+    }
+
+    /// Shuffle every pile, in pile order so the result only depends on `rng`.
+    pub fn shuffle_piles<R: Rng + ?Sized>(&mut self, rng: &mut R) {
+        let mut piles: Vec<_> = self.card_piles.iter_mut().collect();
+        piles.sort_by_key(|(value, _)| **value);
+        for (_, pile) in piles {
+            pile.shuffle(rng);
         }
-        Self { card_piles: cards }
     }
+
     pub fn pull_card_from(&mut self, pile: usize) -> Option<TradeCard> {
         if let Some(p) = self.card_piles.get_mut(&pile) {
             p.pop()
@@ -151,12 +159,12 @@ impl PlayerTradeCards {
     /// not each distinct type — has an equal chance, so a 3-of-a-kind stack
     /// is three times as likely to be hit as a singleton. `None` if the hand
     /// is empty.
-    pub fn remove_random_card(&mut self) -> Option<TradeCard> {
+    pub fn remove_random_card<R: Rng + ?Sized>(&mut self, rng: &mut R) -> Option<TradeCard> {
         let total = self.number_of_trade_cards();
         if total == 0 {
             return None;
         }
-        let mut pick = rand::rng().random_range(0..total);
+        let mut pick = rng.random_range(0..total);
         for (card, count) in &self.cards {
             if pick < *count {
                 let card = *card;
@@ -431,7 +439,7 @@ mod tests {
     #[test]
     fn remove_random_card_on_empty_hand_returns_none() {
         let mut hand = PlayerTradeCards::default();
-        assert_eq!(hand.remove_random_card(), None);
+        assert_eq!(hand.remove_random_card(&mut rand::rng()), None);
     }
 
     /// Rule 24.51: draws exactly one card, and only one, from the hand.
@@ -443,7 +451,9 @@ mod tests {
         hand.add_trade_card(TradeCard::Iron);
         assert_eq!(hand.number_of_trade_cards(), 3);
 
-        let drawn = hand.remove_random_card().expect("hand is non-empty");
+        let drawn = hand
+            .remove_random_card(&mut rand::rng())
+            .expect("hand is non-empty");
         assert_eq!(hand.number_of_trade_cards(), 2);
         assert_eq!(hand.number_of_cards_for_trade_card(drawn), 0);
     }
@@ -453,9 +463,10 @@ mod tests {
     fn remove_random_card_single_card_hand_is_deterministic() {
         let mut hand = PlayerTradeCards::default();
         hand.add_trade_card(TradeCard::Iron);
-        assert_eq!(hand.remove_random_card(), Some(TradeCard::Iron));
+        let mut rng = rand::rng();
+        assert_eq!(hand.remove_random_card(&mut rng), Some(TradeCard::Iron));
         assert_eq!(hand.number_of_trade_cards(), 0);
-        assert_eq!(hand.remove_random_card(), None);
+        assert_eq!(hand.remove_random_card(&mut rng), None);
     }
 
     /// Every physical card gets picked over enough draws — regression guard
@@ -463,12 +474,13 @@ mod tests {
     #[test]
     fn remove_random_card_can_draw_each_distinct_type() {
         let mut seen = std::collections::HashSet::new();
+        let mut rng = rand::rng();
         for _ in 0..200 {
             let mut hand = PlayerTradeCards::default();
             hand.add_trade_card(TradeCard::Ochre);
             hand.add_trade_card(TradeCard::Salt);
             hand.add_trade_card(TradeCard::Iron);
-            if let Some(card) = hand.remove_random_card() {
+            if let Some(card) = hand.remove_random_card(&mut rng) {
                 seen.insert(card);
             }
         }
@@ -4,17 +4,29 @@ use crate::civilization::concepts::acquire_trade_cards::trade_card_events::{
     CheckIfWeCanTrade, HumanPlayerTradeCardsUpdated,
 };
 use crate::civilization::concepts::acquire_trade_cards::trade_card_systems::{
-    acquire_trade_cards, transition_to_trade,
+    acquire_trade_cards, deal_trade_card_piles, transition_to_trade,
 };
+use crate::civilization::concepts::game_rng::seed_game_rng;
+use crate::civilization::concepts::map::map_plugin::load_map;
+use crate::civilization::general_systems::setup_players;
 use bevy::prelude::{App, IntoScheduleConfigs, OnEnter, Plugin, Update, in_state};
 
 pub struct TradeCardPlugin;
 
 impl Plugin for TradeCardPlugin {
     fn build(&self, app: &mut App) {
-        app.insert_resource(CivilizationTradeCards::new())
+        app.init_resource::<CivilizationTradeCards>()
             .add_message::<CheckIfWeCanTrade>()
             .add_message::<HumanPlayerTradeCardsUpdated>()
+            .add_systems(
+                OnEnter(GameActivity::PrepareGame),
+                // After `load_map`, which also draws from `GameRng`, so one
+                // seed always deals the same piles and start places.
+                deal_trade_card_piles
+                    .after(seed_game_rng)
+                    .after(load_map)
+                    .before(setup_players),
+            )
             .add_systems(
                 OnEnter(GameActivity::AcquireTradeCards),
                 acquire_trade_cards,
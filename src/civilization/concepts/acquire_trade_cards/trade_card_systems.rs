@@ -7,6 +7,7 @@ use crate::civilization::concepts::acquire_trade_cards::trade_card_components::{
 use crate::civilization::concepts::acquire_trade_cards::trade_card_events::{
     CheckIfWeCanTrade, HumanPlayerTradeCardsUpdated,
 };
+use crate::civilization::concepts::game_rng::GameRng;
 use crate::civilization::plugins::DebugOptions;
 use crate::stupid_ai::IsHuman;
 use bevy::prelude::{
@@ -20,6 +21,15 @@ pub const NINTH_STACK_PILE: usize = 9;
 /// Rule 27.51: 18 tokens from treasury per card bought from the ninth stack.
 pub const NINTH_STACK_COST: usize = 18;
 
+/// A new game starts from a full, freshly shuffled set of trade-card piles,
+/// drawn from the just-seeded `GameRng` so the deck order is reproducible.
+pub fn deal_trade_card_piles(
+    mut trade_card_resource: ResMut<CivilizationTradeCards>,
+    mut game_rng: ResMut<GameRng>,
+) {
+    *trade_card_resource = CivilizationTradeCards::new(game_rng.rng());
+}
+
 /// Rule 27.51: buy up to `max_cards` cards from the ninth (Gold/Ivory/Piracy)
 /// stack at `NINTH_STACK_COST` tokens from treasury each; spent tokens are
 /// returned to stock. Stops early (without spending) once the treasury can no
@@ -11,7 +11,7 @@ impl EndPlayerCityConstruction {
     }
 }
 
-#[derive(Message, Debug, Reflect)]
+#[derive(Message, Debug, Clone, Reflect)]
 pub struct BuildCityCommand {
     pub player: Entity,
     pub area: Entity,
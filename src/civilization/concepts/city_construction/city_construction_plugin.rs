@@ -3,6 +3,7 @@ use crate::civilization::concepts::city_construction::city_construction_events::
 use crate::civilization::concepts::city_construction::city_construction_systems::*;
 use crate::civilization::concepts::city_construction::city_construction_ui_components::CityConstructionSelectionState;
 use crate::civilization::concepts::city_construction::city_construction_ui_systems::*;
+use crate::civilization::events::CommandApplied;
 use bevy::app::{App, Update};
 use bevy::prelude::{IntoScheduleConfigs, OnEnter, OnExit, Plugin, in_state};
 
@@ -12,6 +13,7 @@ impl Plugin for CityConstructionPlugin {
     fn build(&self, app: &mut App) {
         app.add_message::<EndPlayerCityConstruction>()
             .add_message::<BuildCityCommand>()
+            .add_message::<CommandApplied<BuildCityCommand>>()
             .init_resource::<CityConstructionSelectionState>()
             .add_systems(
                 OnEnter(GameActivity::CityConstruction),
@@ -8,6 +8,7 @@ use crate::civilization::concepts::city_construction::city_construction_events::
 use crate::civilization::concepts::civ_cards::PlayerCivilizationCards;
 use crate::civilization::concepts::map::map_plugin::AvailableFactions;
 use crate::civilization::concepts::save_game::LoadingFromSave;
+use crate::civilization::events::CommandApplied;
 use crate::civilization::functions::{build_city_in_area, return_all_tokens_from_area_to_players};
 use crate::civilization::game_moves::{AvailableMoves, RecalculatePlayerMoves};
 use crate::player::Player;
@@ -50,6 +51,7 @@ pub fn build_city(
     )>,
     mut commands: Commands,
     mut recalculate_player_moves: MessageWriter<RecalculatePlayerMoves>,
+    mut applied: MessageWriter<CommandApplied<BuildCityCommand>>,
     game_factions: Res<AvailableFactions>,
 ) {
     for build_city in command.read() {
@@ -101,6 +103,7 @@ pub fn build_city(
                 area_transform,
             );
             recalculate_player_moves.write(RecalculatePlayerMoves::new(build_city.player));
+            applied.write(CommandApplied(build_city.clone()));
         }
     }
 }
@@ -8,7 +8,7 @@ use serde::{Deserialize, Serialize};
 #[derive(Resource)]
 pub struct CardHandle(pub Handle<AvailableCivCards>);
 
-#[derive(Resource, Asset, Default, Serialize, Deserialize, TypePath, Clone)]
+#[derive(Resource, Asset, Debug, Default, Serialize, Deserialize, TypePath, Clone)]
 pub struct AvailableCivCards {
     pub cards: Vec<CivCardDefinition>,
 }
@@ -19,7 +19,7 @@ pub struct ProceedToPayment;
 pub struct BackToCardSelection;
 
 /// Message to confirm purchase with selected payment
-#[derive(Message)]
+#[derive(Message, Debug, Clone)]
 pub struct ConfirmCivCardPurchase {
     pub player: Entity,
     pub cards_to_buy: Vec<CivCardName>,
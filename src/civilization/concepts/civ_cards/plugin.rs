@@ -1,7 +1,7 @@
 use crate::civilization::concepts::civ_cards::assets_resources::AvailableCivCards;
 use crate::civilization::{
-    BackToCardSelection, CivCardSelectionState, CivTradeUi, ConfirmCivCardPurchase, PaymentState,
-    PlayerDoneAcquiringCivilizationCards, ProceedToPayment, RefreshCivCardsUi,
+    BackToCardSelection, CivCardSelectionState, CivTradeUi, CommandApplied, ConfirmCivCardPurchase,
+    PaymentState, PlayerDoneAcquiringCivilizationCards, ProceedToPayment, RefreshCivCardsUi,
     ToggleCivCardSelection, begin_acquire_civ_cards, ensure_human_civ_cards_ui,
     handle_back_to_selection, handle_payment_adjust, handle_proceed_to_payment_message,
     handle_toggle_card_selection, init_civ_cards, load_civ_cards,
@@ -52,6 +52,7 @@ impl Plugin for CivCardsPlugin {
             .add_message::<ProceedToPayment>()
             .add_message::<BackToCardSelection>()
             .add_message::<ConfirmCivCardPurchase>()
+            .add_message::<CommandApplied<ConfirmCivCardPurchase>>()
             .add_message::<RefreshCivCardsUi>()
             .add_systems(OnEnter(GameState::Loading), load_civ_cards)
             .add_systems(OnEnter(GameState::Playing), init_civ_cards)
@@ -3,6 +3,7 @@ use crate::civilization::Z_DIALOG;
 use crate::civilization::concepts::acquire_trade_cards::{
     CivilizationTradeCards, PlayerTradeCards, TradeCard, TradeCardTrait,
 };
+use crate::civilization::concepts::game_rng::GameRng;
 use crate::civilization::concepts::resolve_calamities::resolve_calamities_components::{
     GrainLockedForPurchase, usable_grain_count,
 };
@@ -10,16 +11,17 @@ use crate::civilization::game_moves::RecalculatePlayerMoves;
 use crate::civilization::{
     AvailableCivCards, BackToCardSelection, CardHandle, CardsHeldBeforePurchasing,
     CivCardDefinition, CivCardName, CivCardPurchasePhase, CivCardSelectionState, CivCardType,
-    CivCardsAcquisition, CivTradeUi, ConfirmCivCardPurchase, Credits, PaymentAdjustButton,
-    PaymentSelectionPanel, PaymentState, PaymentValueDisplay, PlayerAcquiringCivilizationCards,
-    PlayerCivilizationCards, PlayerDoneAcquiringCivilizationCards, ProceedToPayment,
-    RefreshCivCardsUi, SelectedCardsSummary, ToggleCivCardSelection,
+    CivCardsAcquisition, CivTradeUi, CommandApplied, ConfirmCivCardPurchase, Credits,
+    PaymentAdjustButton, PaymentSelectionPanel, PaymentState, PaymentValueDisplay,
+    PlayerAcquiringCivilizationCards, PlayerCivilizationCards,
+    PlayerDoneAcquiringCivilizationCards, ProceedToPayment, RefreshCivCardsUi,
+    SelectedCardsSummary, ToggleCivCardSelection,
 };
 use crate::player::Player;
 use crate::stupid_ai::IsHuman;
 use bevy::asset::{AssetServer, Assets};
 use bevy::color::Color;
-use bevy::platform::collections::HashSet;
+use bevy::platform::collections::{HashMap, HashSet};
 use bevy::prelude::{
     Add, Button, Changed, Commands, Entity, Has, Interaction, Local, MessageReader, MessageWriter,
     NextState, On, Query, Res, ResMut, Time, Val, With, percent, warn,
@@ -711,6 +713,7 @@ pub fn process_civ_card_purchase(
     mut selection_state: ResMut<CivCardSelectionState>,
     mut done_writer: MessageWriter<PlayerDoneAcquiringCivilizationCards>,
     mut recalc_writer: MessageWriter<RecalculatePlayerMoves>,
+    mut applied: MessageWriter<CommandApplied<ConfirmCivCardPurchase>>,
     mut commands: Commands,
     ui_query: Query<Entity, With<CivTradeUi>>,
 ) {
@@ -731,6 +734,7 @@ pub fn process_civ_card_purchase(
             let locked_grain = grain_locked.map_or(0, |l| l.0);
 
             // Remove trade cards used for payment and return to piles
+            let mut paid: HashMap<TradeCard, usize> = HashMap::default();
             for (trade_card, count) in &purchase.payment {
                 let count = if *trade_card == TradeCard::Grain {
                     let held = player_trade_cards.number_of_cards_for_trade_card(*trade_card);
@@ -745,6 +749,7 @@ pub fn process_civ_card_purchase(
                     .remove_n_trade_cards(count, *trade_card)
                     .is_some()
                 {
+                    paid.insert(*trade_card, count);
                     // Return cards to the appropriate pile
                     let pile = trade_card.value();
                     if let Some(pile_vec) = trade_cards_resource.card_piles.get_mut(&pile) {
@@ -755,6 +760,12 @@ pub fn process_civ_card_purchase(
                 }
             }
 
+            applied.write(CommandApplied(ConfirmCivCardPurchase {
+                player: purchase.player,
+                cards_to_buy: purchase.cards_to_buy.clone(),
+                payment: paid,
+            }));
+
             if is_human {
                 // Only the human owns the purchase UI and the selection state.
                 // AI purchases land in the same frames as the human's UI is
@@ -771,9 +782,9 @@ pub fn process_civ_card_purchase(
                 // done. (The human can re-open and buy again if they choose.)
                 done_writer.write(PlayerDoneAcquiringCivilizationCards(purchase.player));
             } else {
-                // AI buys one card per move (rule 31.1 allows one or more per
-                // turn): regenerate the affordable-card move set so the AI can buy
-                // again with its reduced reserve. The loop ends when no card is
+                // AI buys one card or one planned bundle per move (rule 31.1
+                // allows one or more per turn): regenerate the affordable-card
+                // move set so the AI can buy again with its reduced reserve. The loop ends when no card is
                 // affordable (only DoneAcquiringCards remains) or the AI scores
                 // stopping highest — then select_stupid_civ_card_move writes Done.
                 recalc_writer.write(RecalculatePlayerMoves::new(purchase.player));
@@ -940,11 +951,9 @@ pub fn begin_acquire_civ_cards(
     }
 }
 
-pub fn shuffle_trade_card_piles_on_exit(mut trade_cards_resource: ResMut<CivilizationTradeCards>) {
-    use rand::seq::SliceRandom;
-    let mut rng = rand::rng();
-
-    for pile in trade_cards_resource.card_piles.values_mut() {
-        pile.shuffle(&mut rng);
-    }
+pub fn shuffle_trade_card_piles_on_exit(
+    mut trade_cards_resource: ResMut<CivilizationTradeCards>,
+    mut game_rng: ResMut<GameRng>,
+) {
+    trade_cards_resource.shuffle_piles(game_rng.rng());
 }
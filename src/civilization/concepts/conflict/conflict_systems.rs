@@ -41,9 +41,9 @@ pub fn find_conflict_zones(
 mod tests {
     use super::*;
     use crate::GameState;
-    use crate::civilization::CameraFocusQueue;
     use crate::civilization::components::*;
     use crate::civilization::concepts::conflict::conflict_triggers::*;
+    use crate::civilization::{CameraFocusQueue, GameRng};
     use bevy::ecs::system::{RunSystemError, RunSystemOnce};
     use bevy::prelude::*;
     use bevy::state::app::StatesPlugin;
@@ -58,6 +58,7 @@ mod tests {
         app.insert_state(GameActivity::Conflict);
         app.init_resource::<ConflictCounterResource>();
         app.init_resource::<CameraFocusQueue>();
+        app.insert_resource(GameRng::from_seed(0));
         app.add_observer(on_add_unresolved_conflict);
         app.add_observer(on_add_unresolved_city_conflict);
 
@@ -8,7 +8,7 @@ use crate::civilization::concepts::map::CameraFocusQueue;
 use crate::civilization::functions::{
     replace_city_with_tokens_for_conflict, return_all_tokens_from_area_to_player,
 };
-use crate::civilization::{CivCardName, ConflictCounterResource};
+use crate::civilization::{CivCardName, ConflictCounterResource, GameRng};
 use crate::stupid_ai::IsHuman;
 use bevy::log::info;
 use bevy::platform::collections::HashSet;
@@ -137,6 +137,7 @@ pub fn on_add_unresolved_city_conflict(
     civ_cards_query: Query<&PlayerCivilizationCards>,
     mut trade_cards_query: Query<&mut PlayerTradeCards>,
     mut treasury_query: Query<&mut Treasury>,
+    mut game_rng: ResMut<GameRng>,
 ) {
     if let Ok((area_entity, name, mut population, built_city, transform)) =
         areas.get_mut(trigger.event().entity)
@@ -256,7 +257,7 @@ pub fn on_add_unresolved_city_conflict(
                         if trade_cards_query.get(attacker).is_ok()
                             && let Ok(mut victim_cards) =
                                 trade_cards_query.get_mut(built_city.player)
-                            && let Some(drawn) = victim_cards.remove_random_card()
+                            && let Some(drawn) = victim_cards.remove_random_card(game_rng.rng())
                         {
                             if let Ok(mut attacker_cards) = trade_cards_query.get_mut(attacker) {
                                 attacker_cards.add_trade_card(drawn);
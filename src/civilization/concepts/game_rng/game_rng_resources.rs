@@ -0,0 +1,120 @@
+use bevy::prelude::Resource;
+use rand::rngs::StdRng;
+use rand::{RngExt, SeedableRng};
+
+/// Mixed into the seed to derive the AI's stream from the rules stream's.
+const AI_STREAM: u64 = 0x9E37_79B9_7F4A_7C15;
+
+/// The single source of randomness for the rules engine and the AI: deck
+/// shuffles, faction/ruler/area-name assignment, random card draws, calamity
+/// selection and every AI pick go through this, never through `rand::rng()`.
+///
+/// Together with the deterministic `FixedHasher` behind Bevy's `HashMap` /
+/// `HashSet` (so iteration order only depends on insertion order), this makes
+/// a game a pure function of its seed, its players and the moves made: two
+/// runs with the same `GAME_SEED` play out identically.
+///
+/// The rules and the AI draw from two separate streams, both derived from the
+/// seed. A replay feeds recorded AI choices back instead of letting the AI
+/// pick, and with a shared stream every skipped pick would shift all later
+/// rules draws.
+#[derive(Resource, Debug)]
+pub struct GameRng {
+    seed: u64,
+    rng: StdRng,
+    ai: StdRng,
+    draws: u64,
+}
+
+impl GameRng {
+    pub fn from_seed(seed: u64) -> Self {
+        Self {
+            seed,
+            rng: StdRng::seed_from_u64(seed),
+            ai: StdRng::seed_from_u64(seed ^ AI_STREAM),
+            draws: 0,
+        }
+    }
+
+    /// A fresh, unpredictable seed, for games started without `GAME_SEED`.
+    pub fn random_seed() -> u64 {
+        rand::rng().random()
+    }
+
+    /// The seed this generator was last (re)seeded with — log it or store it
+    /// alongside a bug report to reproduce the game.
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Restart the stream from `seed`, discarding all previous draws.
+    pub fn reseed(&mut self, seed: u64) {
+        *self = Self::from_seed(seed);
+    }
+
+    /// The rules' generator, for `shuffle`, `choose`, `random_range` etc.
+    pub fn rng(&mut self) -> &mut StdRng {
+        self.draws += 1;
+        &mut self.rng
+    }
+
+    /// The AI's generator: tie-breaks, weighted picks, trade-offer whims.
+    pub fn ai_rng(&mut self) -> &mut StdRng {
+        &mut self.ai
+    }
+
+    /// How many times the rules have drawn from [`Self::rng`] since the last
+    /// (re)seed. Replays compare it to spot a rule that draws more or less
+    /// often than it did when the game was recorded.
+    pub fn draws(&self) -> u64 {
+        self.draws
+    }
+}
+
+impl Default for GameRng {
+    fn default() -> Self {
+        Self::from_seed(Self::random_seed())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::seq::SliceRandom;
+
+    #[test]
+    fn same_seed_produces_the_same_stream() {
+        let mut a = GameRng::from_seed(42);
+        let mut b = GameRng::from_seed(42);
+        let mut deck_a: Vec<u32> = (0..50).collect();
+        let mut deck_b = deck_a.clone();
+        deck_a.shuffle(a.rng());
+        deck_b.shuffle(b.rng());
+        assert_eq!(deck_a, deck_b);
+        assert_eq!(
+            a.rng().random_range(0..1000u32),
+            b.rng().random_range(0..1000u32)
+        );
+    }
+
+    #[test]
+    fn reseed_restarts_the_stream() {
+        let mut rng = GameRng::from_seed(7);
+        let first: u64 = rng.rng().random();
+        rng.reseed(7);
+        assert_eq!(rng.seed(), 7);
+        assert_eq!(rng.draws(), 0);
+        assert_eq!(rng.rng().random::<u64>(), first);
+    }
+
+    #[test]
+    fn ai_draws_leave_the_rules_stream_alone() {
+        let mut quiet = GameRng::from_seed(3);
+        let mut busy = GameRng::from_seed(3);
+        for _ in 0..10 {
+            let _: u64 = busy.ai_rng().random();
+        }
+        assert_eq!(quiet.rng().random::<u64>(), busy.rng().random::<u64>());
+        assert_eq!(busy.draws(), 1);
+    }
+}
@@ -0,0 +1,13 @@
+use crate::civilization::concepts::game_rng::game_rng_resources::GameRng;
+use crate::civilization::plugins::DebugOptions;
+use bevy::prelude::{Res, ResMut, info};
+
+/// Runs first on `PrepareGame`: reseed the shared [`GameRng`] from
+/// `DebugOptions::game_seed`, or from a fresh random seed if none is set. The
+/// seed is always logged, so any game — including a long AI self-play one —
+/// can be reproduced by rerunning with `GAME_SEED=<seed>`.
+pub fn seed_game_rng(debug_options: Res<DebugOptions>, mut game_rng: ResMut<GameRng>) {
+    let seed = debug_options.game_seed.unwrap_or_else(GameRng::random_seed);
+    game_rng.reseed(seed);
+    info!("[RNG] Game seed: {seed} (reproduce with GAME_SEED={seed})");
+}
@@ -0,0 +1,5 @@
+mod game_rng_resources;
+mod game_rng_systems;
+
+pub use game_rng_resources::*;
+pub use game_rng_systems::*;
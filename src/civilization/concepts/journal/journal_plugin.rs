@@ -0,0 +1,32 @@
+use crate::civilization::concepts::journal::journal_resources::GameJournal;
+use crate::civilization::concepts::journal::journal_systems::*;
+use crate::{GameActivity, GameState};
+use bevy::prelude::{App, IntoScheduleConfigs, OnEnter, Plugin, Update, in_state};
+
+/// Records the game as it is played into the [`GameJournal`]: every
+/// `GameActivity` transition, every move/settlement/purchase command once its
+/// handler has applied it (see `CommandApplied`) and every calamity outcome,
+/// keyed by faction and area id. Set `GAME_JOURNAL=<path>` to also stream it
+/// to disk as JSON lines.
+pub struct JournalPlugin;
+
+impl Plugin for JournalPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(GameJournal::from_env())
+            .add_systems(OnEnter(GameActivity::PrepareGame), start_new_journal)
+            .add_systems(OnEnter(GameActivity::StartGame), record_game_start)
+            .add_systems(
+                Update,
+                (
+                    record_phase_transitions,
+                    record_population_expansion,
+                    record_token_movement,
+                    record_city_construction,
+                    record_trade_settlements,
+                    record_calamity_outcomes,
+                    record_civ_card_purchases,
+                )
+                    .run_if(in_state(GameState::Playing)),
+            );
+    }
+}
@@ -0,0 +1,292 @@
+use crate::GameActivity;
+use crate::civilization::enums::GameFaction;
+use crate::civilization::{CivCardName, TradeCard};
+use bevy::prelude::{Resource, error, info};
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::Write;
+use std::path::PathBuf;
+
+/// Env var naming a file the journal is streamed to as JSON lines, one entry
+/// per line, flushed as it is recorded (so a crashed game still leaves one).
+pub const JOURNAL_ENV_VAR: &str = "GAME_JOURNAL";
+
+/// One thing that happened in a game. Players are named by faction and areas
+/// by their map id, never by `Entity`, so a journal stays meaningful after the
+/// run that produced it is gone.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(tag = "type")]
+pub enum JournalEvent {
+    /// Players are in place; `seed` is the `GameRng` seed the game runs on.
+    GameStarted {
+        seed: u64,
+        factions: Vec<GameFaction>,
+    },
+    PhaseChanged {
+        from: Option<GameActivity>,
+        to: Option<GameActivity>,
+    },
+    PopulationExpanded {
+        faction: GameFaction,
+        area: i32,
+        tokens: usize,
+    },
+    TokensMoved {
+        faction: GameFaction,
+        from_area: i32,
+        to_area: i32,
+        tokens: usize,
+    },
+    TokensFerried {
+        faction: GameFaction,
+        from_area: i32,
+        to_area: i32,
+        tokens: usize,
+    },
+    CityBuilt {
+        faction: GameFaction,
+        area: i32,
+    },
+    /// One side of a settled trade: `from` handed `cards` to `to`.
+    TradeCardsSent {
+        from: GameFaction,
+        to: GameFaction,
+        cards: Vec<(TradeCard, usize)>,
+    },
+    /// A calamity finished resolving against `faction`, with the victim's
+    /// board presence as it stood afterwards.
+    CalamityResolved {
+        faction: GameFaction,
+        calamity: TradeCard,
+        cities_after: usize,
+        population_after: usize,
+    },
+    CivCardsPurchased {
+        faction: GameFaction,
+        cards: Vec<CivCardName>,
+        payment: Vec<(TradeCard, usize)>,
+    },
+}
+
+impl JournalEvent {
+    /// Every faction this event is about.
+    pub fn factions(&self) -> Vec<GameFaction> {
+        match self {
+            JournalEvent::GameStarted { factions, .. } => factions.clone(),
+            JournalEvent::PhaseChanged { .. } => Vec::new(),
+            JournalEvent::PopulationExpanded { faction, .. }
+            | JournalEvent::TokensMoved { faction, .. }
+            | JournalEvent::TokensFerried { faction, .. }
+            | JournalEvent::CityBuilt { faction, .. }
+            | JournalEvent::CalamityResolved { faction, .. }
+            | JournalEvent::CivCardsPurchased { faction, .. } => vec![*faction],
+            JournalEvent::TradeCardsSent { from, to, .. } => vec![*from, *to],
+        }
+    }
+
+    /// Every map area id this event touches.
+    pub fn areas(&self) -> Vec<i32> {
+        match self {
+            JournalEvent::PopulationExpanded { area, .. }
+            | JournalEvent::CityBuilt { area, .. } => {
+                vec![*area]
+            }
+            JournalEvent::TokensMoved {
+                from_area, to_area, ..
+            }
+            | JournalEvent::TokensFerried {
+                from_area, to_area, ..
+            } => vec![*from_area, *to_area],
+            _ => Vec::new(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct JournalEntry {
+    /// Position in the journal, starting at 0 for each new game.
+    pub seq: usize,
+    pub round: usize,
+    /// The activity the game was in when the event was recorded (`None`
+    /// outside `GameState::Playing`).
+    pub activity: Option<GameActivity>,
+    pub event: JournalEvent,
+}
+
+/// Structured, append-only record of every phase transition and applied
+/// command of the current game. Queryable in memory; additionally streamed to
+/// the `GAME_JOURNAL` file as JSON lines when that env var is set.
+#[derive(Resource, Debug, Default)]
+pub struct GameJournal {
+    entries: Vec<JournalEntry>,
+    output: Option<PathBuf>,
+    file: Option<File>,
+}
+
+impl GameJournal {
+    pub fn from_env() -> Self {
+        Self {
+            output: std::env::var(JOURNAL_ENV_VAR).ok().map(PathBuf::from),
+            ..Self::default()
+        }
+    }
+
+    /// Journal that streams to `path` (truncated at the start of each game).
+    pub fn with_output(path: impl Into<PathBuf>) -> Self {
+        Self {
+            output: Some(path.into()),
+            ..Self::default()
+        }
+    }
+
+    /// Forget the previous game's entries and start its output file afresh.
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.file = None;
+        if let Some(path) = &self.output {
+            match File::create(path) {
+                Ok(file) => {
+                    info!("[JOURNAL] Writing game journal to {}", path.display());
+                    self.file = Some(file);
+                }
+                Err(e) => error!("[JOURNAL] Cannot create {}: {}", path.display(), e),
+            }
+        }
+    }
+
+    pub fn record(&mut self, round: usize, activity: Option<GameActivity>, event: JournalEvent) {
+        let entry = JournalEntry {
+            seq: self.entries.len(),
+            round,
+            activity,
+            event,
+        };
+        if let Some(file) = &mut self.file
+            && let Ok(line) = serde_json::to_string(&entry)
+            && let Err(e) = writeln!(file, "{line}")
+        {
+            error!("[JOURNAL] Failed to write entry {}: {}", entry.seq, e);
+            self.file = None;
+        }
+        self.entries.push(entry);
+    }
+
+    pub fn entries(&self) -> &[JournalEntry] {
+        &self.entries
+    }
+
+    pub fn last(&self) -> Option<&JournalEntry> {
+        self.entries.last()
+    }
+
+    pub fn for_faction(&self, faction: GameFaction) -> impl Iterator<Item = &JournalEntry> {
+        self.entries
+            .iter()
+            .filter(move |e| e.event.factions().contains(&faction))
+    }
+
+    pub fn for_area(&self, area: i32) -> impl Iterator<Item = &JournalEntry> {
+        self.entries
+            .iter()
+            .filter(move |e| e.event.areas().contains(&area))
+    }
+
+    pub fn in_round(&self, round: usize) -> impl Iterator<Item = &JournalEntry> {
+        self.entries.iter().filter(move |e| e.round == round)
+    }
+
+    pub fn to_json_lines(&self) -> String {
+        self.entries
+            .iter()
+            .filter_map(|e| serde_json::to_string(e).ok())
+            .map(|line| line + "\n")
+            .collect()
+    }
+
+    pub fn from_json_lines(text: &str) -> serde_json::Result<Vec<JournalEntry>> {
+        text.lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(serde_json::from_str::<JournalEntry>)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn journal_with_a_few_entries() -> GameJournal {
+        let mut journal = GameJournal::default();
+        journal.record(
+            1,
+            Some(GameActivity::PopulationExpansion),
+            JournalEvent::PopulationExpanded {
+                faction: GameFaction::Egypt,
+                area: 12,
+                tokens: 2,
+            },
+        );
+        journal.record(
+            1,
+            Some(GameActivity::Movement),
+            JournalEvent::TokensMoved {
+                faction: GameFaction::Crete,
+                from_area: 3,
+                to_area: 12,
+                tokens: 1,
+            },
+        );
+        journal.record(
+            2,
+            Some(GameActivity::Trade),
+            JournalEvent::TradeCardsSent {
+                from: GameFaction::Egypt,
+                to: GameFaction::Crete,
+                cards: vec![(TradeCard::Salt, 2)],
+            },
+        );
+        journal
+    }
+
+    #[test]
+    fn entries_are_numbered_in_recording_order() {
+        let journal = journal_with_a_few_entries();
+        let seqs: Vec<usize> = journal.entries().iter().map(|e| e.seq).collect();
+        assert_eq!(seqs, vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn queries_filter_by_faction_area_and_round() {
+        let journal = journal_with_a_few_entries();
+        assert_eq!(journal.for_faction(GameFaction::Egypt).count(), 2);
+        assert_eq!(journal.for_faction(GameFaction::Crete).count(), 2);
+        assert_eq!(journal.for_faction(GameFaction::Thrace).count(), 0);
+        assert_eq!(journal.for_area(12).count(), 2);
+        assert_eq!(journal.in_round(2).count(), 1);
+    }
+
+    #[test]
+    fn json_lines_round_trip() {
+        let journal = journal_with_a_few_entries();
+        let text = journal.to_json_lines();
+        assert_eq!(text.lines().count(), 3);
+        let parsed = GameJournal::from_json_lines(&text).unwrap();
+        assert_eq!(parsed, journal.entries());
+    }
+
+    #[test]
+    fn clear_starts_a_new_game_from_seq_zero() {
+        let mut journal = journal_with_a_few_entries();
+        journal.clear();
+        assert!(journal.entries().is_empty());
+        journal.record(
+            0,
+            None,
+            JournalEvent::PhaseChanged {
+                from: None,
+                to: Some(GameActivity::PrepareGame),
+            },
+        );
+        assert_eq!(journal.last().map(|e| e.seq), Some(0));
+    }
+}
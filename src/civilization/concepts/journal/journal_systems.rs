@@ -0,0 +1,202 @@
+use crate::GameActivity;
+use crate::civilization::components::{Faction, GameArea, PlayerAreas, PlayerCities};
+use crate::civilization::concepts::census::GameInfoAndStuff;
+use crate::civilization::concepts::game_rng::GameRng;
+use crate::civilization::concepts::journal::journal_resources::{GameJournal, JournalEvent};
+use crate::civilization::concepts::resolve_calamities::resolve_calamities_events::CalamityResolved;
+use crate::civilization::concepts::trade::SendTradingCardsCommand;
+use crate::civilization::enums::GameFaction;
+use crate::civilization::{
+    BuildCityCommand, CommandApplied, ConfirmCivCardPurchase, ExpandPopulationManuallyCommand,
+    MoveTokenFromAreaToAreaCommand, ShipFerryCommand,
+};
+use crate::player::Player;
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+
+/// Everything needed to turn an ECS message into a [`JournalEvent`] and file
+/// it: entity → faction / area-id lookups plus the current round and phase.
+#[derive(SystemParam)]
+pub struct JournalWriter<'w, 's> {
+    journal: ResMut<'w, GameJournal>,
+    game_info: Res<'w, GameInfoAndStuff>,
+    activity: Option<Res<'w, State<GameActivity>>>,
+    factions: Query<'w, 's, &'static Faction>,
+    areas: Query<'w, 's, &'static GameArea>,
+}
+
+impl JournalWriter<'_, '_> {
+    pub fn faction(&self, player: Entity) -> Option<GameFaction> {
+        self.factions.get(player).ok().map(|f| f.faction)
+    }
+
+    pub fn area(&self, area: Entity) -> Option<i32> {
+        self.areas.get(area).ok().map(|a| a.id)
+    }
+
+    pub fn record(&mut self, event: JournalEvent) {
+        let activity = self.activity.as_ref().map(|a| a.get().clone());
+        self.journal.record(self.game_info.round, activity, event);
+    }
+}
+
+/// A new game (fresh or loaded) starts a new journal.
+pub fn start_new_journal(mut journal: ResMut<GameJournal>) {
+    journal.clear();
+}
+
+pub fn record_game_start(
+    mut writer: JournalWriter,
+    game_rng: Res<GameRng>,
+    players: Query<&Faction, With<Player>>,
+) {
+    let factions = players.iter().map(|f| f.faction).collect();
+    writer.record(JournalEvent::GameStarted {
+        seed: game_rng.seed(),
+        factions,
+    });
+}
+
+pub fn record_phase_transitions(
+    mut transitions: MessageReader<StateTransitionEvent<GameActivity>>,
+    mut writer: JournalWriter,
+) {
+    for transition in transitions.read() {
+        if transition.exited == transition.entered {
+            continue;
+        }
+        writer.record(JournalEvent::PhaseChanged {
+            from: transition.exited.clone(),
+            to: transition.entered.clone(),
+        });
+    }
+}
+
+pub fn record_population_expansion(
+    mut commands: MessageReader<CommandApplied<ExpandPopulationManuallyCommand>>,
+    mut writer: JournalWriter,
+) {
+    for CommandApplied(command) in commands.read() {
+        if let (Some(faction), Some(area)) =
+            (writer.faction(command.player), writer.area(command.area))
+        {
+            writer.record(JournalEvent::PopulationExpanded {
+                faction,
+                area,
+                tokens: command.number_of_tokens,
+            });
+        }
+    }
+}
+
+pub fn record_token_movement(
+    mut moves: MessageReader<CommandApplied<MoveTokenFromAreaToAreaCommand>>,
+    mut ferries: MessageReader<CommandApplied<ShipFerryCommand>>,
+    mut writer: JournalWriter,
+) {
+    for CommandApplied(command) in moves.read() {
+        if let (Some(faction), Some(from_area), Some(to_area)) = (
+            writer.faction(command.player),
+            writer.area(command.source_area),
+            writer.area(command.target_area),
+        ) {
+            writer.record(JournalEvent::TokensMoved {
+                faction,
+                from_area,
+                to_area,
+                tokens: command.number_of_tokens,
+            });
+        }
+    }
+    for CommandApplied(command) in ferries.read() {
+        if let (Some(faction), Some(from_area), Some(to_area)) = (
+            writer.faction(command.player),
+            writer.area(command.source_area),
+            writer.area(command.target_area),
+        ) {
+            writer.record(JournalEvent::TokensFerried {
+                faction,
+                from_area,
+                to_area,
+                tokens: command.number_of_tokens,
+            });
+        }
+    }
+}
+
+pub fn record_city_construction(
+    mut commands: MessageReader<CommandApplied<BuildCityCommand>>,
+    mut writer: JournalWriter,
+) {
+    for CommandApplied(command) in commands.read() {
+        if let (Some(faction), Some(area)) =
+            (writer.faction(command.player), writer.area(command.area))
+        {
+            writer.record(JournalEvent::CityBuilt { faction, area });
+        }
+    }
+}
+
+pub fn record_trade_settlements(
+    mut commands: MessageReader<CommandApplied<SendTradingCardsCommand>>,
+    mut writer: JournalWriter,
+) {
+    for CommandApplied(command) in commands.read() {
+        if let (Some(from), Some(to)) = (
+            writer.faction(command.sending_player),
+            writer.faction(command.receiving_player),
+        ) {
+            let cards = command
+                .cards_to_send
+                .iter()
+                .map(|(card, count)| (*card, *count))
+                .collect();
+            writer.record(JournalEvent::TradeCardsSent { from, to, cards });
+        }
+    }
+}
+
+pub fn record_calamity_outcomes(
+    mut resolved: MessageReader<CalamityResolved>,
+    victims: Query<(&PlayerCities, &PlayerAreas)>,
+    mut writer: JournalWriter,
+) {
+    for outcome in resolved.read() {
+        let Some(faction) = writer.faction(outcome.player) else {
+            continue;
+        };
+        let (cities_after, population_after) = victims
+            .get(outcome.player)
+            .map_or((0, 0), |(cities, areas)| {
+                (cities.number_of_cities(), areas.total_population())
+            });
+        writer.record(JournalEvent::CalamityResolved {
+            faction,
+            calamity: outcome.calamity,
+            cities_after,
+            population_after,
+        });
+    }
+}
+
+pub fn record_civ_card_purchases(
+    mut purchases: MessageReader<CommandApplied<ConfirmCivCardPurchase>>,
+    mut writer: JournalWriter,
+) {
+    for CommandApplied(purchase) in purchases.read() {
+        let Some(faction) = writer.faction(purchase.player) else {
+            continue;
+        };
+        let payment = purchase
+            .payment
+            .iter()
+            .filter(|(_, count)| **count > 0)
+            .map(|(card, count)| (*card, *count))
+            .collect();
+        writer.record(JournalEvent::CivCardsPurchased {
+            faction,
+            cards: purchase.cards_to_buy.clone(),
+            payment,
+        });
+    }
+}
@@ -0,0 +1,7 @@
+mod journal_plugin;
+mod journal_resources;
+mod journal_systems;
+
+pub use journal_plugin::*;
+pub use journal_resources::*;
+pub use journal_systems::*;
@@ -2,6 +2,7 @@ use crate::civilization::components::{
     CityFlood, CitySite, FloodPlain, GameArea, GameCamera, LandPassage, NeedsConnections,
     Population, SeaPassage, StartArea, Volcano,
 };
+use crate::civilization::concepts::game_rng::{GameRng, seed_game_rng};
 use crate::civilization::concepts::map::camera_focus::{CameraFocusQueue, process_camera_focus};
 use crate::civilization::enums::GameFaction;
 use crate::civilization::general_systems::setup_players;
@@ -30,7 +31,13 @@ impl Plugin for MapPlugin {
             .add_systems(Startup, setup)
             .add_systems(
                 OnEnter(GameActivity::PrepareGame),
-                (load_map, setup_players, start_game_after_player_setup).chain(),
+                (
+                    seed_game_rng,
+                    load_map,
+                    setup_players,
+                    start_game_after_player_setup,
+                )
+                    .chain(),
             )
             .add_systems(
                 Update,
@@ -150,9 +157,9 @@ fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
     commands.insert_resource(map);
 }
 
-fn remove_random_place(places: &mut HashSet<String>) -> Option<String> {
+fn remove_random_place(places: &mut HashSet<String>, rng: &mut GameRng) -> Option<String> {
     // Randomly pick an item from the HashSet
-    let selected_place = places.iter().choose(&mut rand::rng()).cloned();
+    let selected_place = places.iter().choose(rng.rng()).cloned();
 
     if let Some(place) = selected_place {
         // Remove it from the HashSet
@@ -183,7 +190,7 @@ pub struct Area {
     pub volcano: bool,
 }
 
-fn load_map(
+pub fn load_map(
     mut commands: Commands,
     map: Res<MapHandle>,
     maps: Res<Assets<Map>>,
@@ -192,6 +199,7 @@ fn load_map(
     images: Res<Assets<Image>>,
     windows: Query<&Window, With<PrimaryWindow>>,
     mut camera: Query<(&Camera, &mut Projection, &mut Transform), With<GameCamera>>,
+    mut game_rng: ResMut<GameRng>,
 ) {
     if let Some(level) = maps.get(map.0.id()) {
         let mut ancient_places: HashSet<String> = vec![
@@ -377,7 +385,8 @@ fn load_map(
         ));
 
         for area in level.areas.clone() {
-            let n = remove_random_place(&mut ancient_places).unwrap_or("STANDARD_NAME".to_string());
+            let n = remove_random_place(&mut ancient_places, &mut game_rng)
+                .unwrap_or("STANDARD_NAME".to_string());
 
             let entity = commands
                 .spawn((
@@ -5,16 +5,20 @@ mod check_city_support;
 mod city_construction;
 mod civ_cards;
 mod conflict;
+mod game_rng;
+mod journal;
 mod map;
 mod movement;
 mod population_expansion;
 mod remove_surplus_population;
+mod replay;
 pub mod resolve_calamities;
 pub mod save_game;
 mod ships;
 mod succession;
 mod taxation;
 mod trade;
+mod undo;
 
 pub use acquire_trade_cards::*;
 pub use area_info::*;
@@ -23,12 +27,16 @@ pub use check_city_support::*;
 pub use city_construction::*;
 pub use civ_cards::*;
 pub use conflict::*;
+pub use game_rng::*;
+pub use journal::*;
 pub use map::*;
 pub use movement::*;
 pub use population_expansion::*;
 pub use remove_surplus_population::*;
+pub use replay::*;
 pub use save_game::*;
 pub use ships::*;
 pub use succession::*;
 pub use taxation::*;
 pub use trade::*;
+pub use undo::*;
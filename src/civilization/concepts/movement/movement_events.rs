@@ -2,7 +2,7 @@ use bevy::prelude::{Entity, Message, Reflect};
 
 /// Command: use a ship in `source_area` to ferry `number_of_tokens` unmoved tokens
 /// to `target_area` via a sea passage. Also moves the ship entity to the target.
-#[derive(Message, Debug, Reflect)]
+#[derive(Message, Debug, Clone, Reflect)]
 pub struct ShipFerryCommand {
     pub source_area: Entity,
     pub target_area: Entity,
@@ -40,7 +40,7 @@ impl PlayerMovementEnded {
 #[derive(Message, Debug, Reflect)]
 pub struct NextPlayerStarted;
 
-#[derive(Message, Debug, Reflect)]
+#[derive(Message, Debug, Clone, Reflect)]
 pub struct MoveTokenFromAreaToAreaCommand {
     pub source_area: Entity,
     pub target_area: Entity,
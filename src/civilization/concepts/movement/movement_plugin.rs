@@ -11,6 +11,7 @@ use crate::civilization::concepts::movement::movement_ui_systems::{
     handle_movement_target_click, pan_camera_to_current_source, setup_human_movement_options,
     spawn_movement_controls_ui, update_source_area_display, update_token_count_display,
 };
+use crate::civilization::events::CommandApplied;
 use bevy::app::App;
 use bevy::prelude::{
     IntoScheduleConfigs, OnEnter, OnExit, Plugin, SystemCondition, Update, in_state,
@@ -21,7 +22,9 @@ pub struct MovementPlugin;
 impl Plugin for MovementPlugin {
     fn build(&self, app: &mut App) {
         app.add_message::<MoveTokenFromAreaToAreaCommand>()
+            .add_message::<CommandApplied<MoveTokenFromAreaToAreaCommand>>()
             .add_message::<ShipFerryCommand>()
+            .add_message::<CommandApplied<ShipFerryCommand>>()
             .add_message::<PlayerMovementEnded>()
             .add_message::<NextPlayerStarted>()
             .init_resource::<MovementSelectionState>()
@@ -8,9 +8,11 @@ use crate::civilization::concepts::map::CameraFocusQueue;
 use crate::civilization::concepts::movement::movement_components::*;
 use crate::civilization::concepts::movement::movement_events::*;
 use crate::civilization::concepts::save_game::LoadingFromSave;
+use crate::civilization::events::CommandApplied;
 use crate::civilization::game_moves::{AvailableMoves, RecalculatePlayerMoves};
 use crate::player::Player;
 use crate::stupid_ai::IsHuman;
+use bevy::platform::collections::HashSet;
 use bevy::prelude::{
     Commands, Entity, Has, MessageReader, MessageWriter, Name, NextState, Query, Res, ResMut,
     Transform, With, Without, info,
@@ -100,13 +102,21 @@ pub fn on_exit_movement(
     }
 }
 
+/// Ends a player's movement and starts the next mover. An end for a player
+/// who is not moving, such as a second one in the same frame, is ignored, so
+/// it cannot skip the next mover.
 pub fn player_end_movement(
     mut end_event: MessageReader<PlayerMovementEnded>,
     mut commands: Commands,
     mut next_player: MessageWriter<NextPlayerStarted>,
     names: Query<&Name>,
+    moving: Query<(), With<PerformingMovement>>,
 ) {
+    let mut ended = HashSet::new();
     for end_movement_event in end_event.read() {
+        if !moving.contains(end_movement_event.player) || !ended.insert(end_movement_event.player) {
+            continue;
+        }
         let name = names
             .get(end_movement_event.player)
             .map_or("?", bevy::prelude::Name::as_str);
@@ -140,6 +150,7 @@ pub fn move_tokens_from_area_to_area(
     human_query: Query<Entity, With<IsHuman>>,
     player_is_human: Query<Has<IsHuman>>,
     mut camera_focus: ResMut<CameraFocusQueue>,
+    mut applied: MessageWriter<CommandApplied<MoveTokenFromAreaToAreaCommand>>,
 ) {
     let human_player = human_query.iter().next();
 
@@ -199,6 +210,7 @@ pub fn move_tokens_from_area_to_area(
                                 player_area.add_token_to_area(ev.target_area, *token);
                             }
                         }
+                        applied.write(CommandApplied(ev.clone()));
                     }
                 }
             }
@@ -222,6 +234,7 @@ pub fn execute_ship_ferry(
     tokens_that_can_move: Query<&Token, Without<TokenHasMoved>>,
     token_transform: Query<&Transform, With<Token>>,
     mut recalculate_player_moves: MessageWriter<RecalculatePlayerMoves>,
+    mut applied: MessageWriter<CommandApplied<ShipFerryCommand>>,
     mut commands: Commands,
 ) {
     for ev in ferry_events.read() {
@@ -309,6 +322,12 @@ pub fn execute_ship_ferry(
             );
         }
 
+        applied.write(CommandApplied(ShipFerryCommand::new(
+            ev.source_area,
+            ev.target_area,
+            tokens_to_ferry.len(),
+            ev.player,
+        )));
         commands.entity(ev.player).insert(HasJustMoved);
         commands.entity(ev.source_area).insert(FixTokenPositions);
         commands.entity(ev.target_area).insert(FixTokenPositions);
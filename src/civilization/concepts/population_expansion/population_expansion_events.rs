@@ -1,6 +1,6 @@
 use bevy::prelude::{Entity, Message};
 
-#[derive(Message, Debug)]
+#[derive(Message, Debug, Clone)]
 pub struct ExpandPopulationManuallyCommand {
     pub player: Entity,
     pub area: Entity,
@@ -12,6 +12,7 @@ use crate::civilization::concepts::population_expansion::population_expansion_sy
     population_expansion_gate,
 };
 use crate::civilization::concepts::population_expansion::population_expansion_triggers::on_remove_needs_expansion;
+use crate::civilization::events::CommandApplied;
 use crate::civilization::general_systems::move_tokens_from_stock_to_area;
 use bevy::app::{App, Plugin, Update};
 use bevy::prelude::{IntoScheduleConfigs, OnEnter, in_state};
@@ -25,6 +26,7 @@ impl Plugin for PopulationExpansionPlugin {
             .register_type::<PopExpHighlightMarker>()
             .add_message::<CheckPlayerExpansionEligibility>()
             .add_message::<ExpandPopulationManuallyCommand>()
+            .add_message::<CommandApplied<ExpandPopulationManuallyCommand>>()
             .add_message::<CheckGate>()
             .add_systems(
                 OnEnter(GameActivity::PopulationExpansion),
@@ -9,7 +9,7 @@ use crate::civilization::concepts::population_expansion::population_expansion_ev
     CheckGate, CheckPlayerExpansionEligibility, ExpandPopulationManuallyCommand,
 };
 use crate::civilization::concepts::save_game::LoadingFromSave;
-use crate::civilization::events::MoveTokensFromStockToAreaCommand;
+use crate::civilization::events::{CommandApplied, MoveTokensFromStockToAreaCommand};
 use crate::civilization::game_moves::{AvailableMoves, GameMove};
 use crate::loading::TextureAssets;
 use crate::stupid_ai::IsHuman;
@@ -257,6 +257,7 @@ pub fn expand_population_manually(
     mut event_reader: MessageReader<ExpandPopulationManuallyCommand>,
     mut event_writer: MessageWriter<MoveTokensFromStockToAreaCommand>,
     mut checker: MessageWriter<CheckPlayerExpansionEligibility>,
+    mut applied: MessageWriter<CommandApplied<ExpandPopulationManuallyCommand>>,
     mut commands: Commands,
 ) {
     for event in event_reader.read() {
@@ -265,6 +266,7 @@ pub fn expand_population_manually(
             event.player,
             event.number_of_tokens,
         ));
+        applied.write(CommandApplied(event.clone()));
 
         /*
         The missing piece of the puzzle is that we must remove the expandmanually component from the player
@@ -0,0 +1,11 @@
+mod replay_components;
+mod replay_plugin;
+mod replay_resources;
+mod replay_systems;
+mod replay_ui_plugin;
+
+pub use replay_components::*;
+pub use replay_plugin::*;
+pub use replay_resources::*;
+pub use replay_systems::*;
+pub use replay_ui_plugin::*;
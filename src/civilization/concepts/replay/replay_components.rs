@@ -0,0 +1,8 @@
+use bevy::prelude::Component;
+
+/// A human or agent seat whose commands come from the replay file. It loses
+/// `IsHuman` so nothing waits on its controls, but while calamities resolve it
+/// sits as a chooser again, so its recorded selections can be put on the
+/// panels it chose them on.
+#[derive(Component)]
+pub struct ReplayedSeat;
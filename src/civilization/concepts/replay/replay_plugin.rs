@@ -0,0 +1,81 @@
+use crate::civilization::concepts::game_rng::seed_game_rng;
+use crate::civilization::concepts::replay::replay_resources::{ReplayPlayback, ReplayRecorder};
+use crate::civilization::concepts::replay::replay_systems::*;
+use crate::civilization::concepts::trade::{handle_send_trading_cards_command, setup_trade};
+use crate::{GameActivity, GameState};
+use bevy::prelude::{
+    App, IntoScheduleConfigs, OnEnter, OnExit, Plugin, PostUpdate, PreUpdate, Update, in_state,
+    not, resource_exists,
+};
+
+/// Records every game the AI plays so it can be replayed move for move, and
+/// plays such a recording back.
+///
+/// Set `GAME_RECORD=<path>` to write the seed, each AI move choice, each
+/// command a human or agent seat applies and calamity selection it confirms,
+/// each settled trade and a state checkpoint per phase change as JSON lines.
+/// Set `GAME_REPLAY=<path>` to play one back: the same seed deals the same
+/// table, the AI takes its choices and the other seats their commands and
+/// calamity selections from the file, and every checkpoint is compared as the
+/// game passes it, so the first rule change that alters the outcome is
+/// reported with the round and phase it happened in.
+pub struct ReplayPlugin;
+
+impl Plugin for ReplayPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(ReplayRecorder::from_env());
+        if let Some(playback) = ReplayPlayback::from_env() {
+            app.insert_resource(playback);
+        }
+        app.add_systems(
+            OnEnter(GameActivity::PrepareGame),
+            apply_replay_setup
+                .before(seed_game_rng)
+                .run_if(resource_exists::<ReplayPlayback>),
+        )
+        .add_systems(
+            OnEnter(GameActivity::StartGame),
+            (
+                hand_seats_to_replay.run_if(resource_exists::<ReplayPlayback>),
+                start_recording.run_if(not(resource_exists::<ReplayPlayback>)),
+            ),
+        )
+        .add_systems(
+            OnEnter(GameActivity::ResolveCalamities),
+            seat_replayed_choosers.run_if(resource_exists::<ReplayPlayback>),
+        )
+        .add_systems(
+            OnExit(GameActivity::ResolveCalamities),
+            unseat_replayed_choosers.run_if(resource_exists::<ReplayPlayback>),
+        )
+        .add_systems(
+            OnEnter(GameActivity::Trade),
+            apply_recorded_trades
+                .after(setup_trade)
+                .run_if(resource_exists::<ReplayPlayback>),
+        )
+        .add_systems(
+            Update,
+            (
+                record_trades,
+                end_replayed_trading
+                    .after(handle_send_trading_cards_command)
+                    .run_if(resource_exists::<ReplayPlayback>),
+            )
+                .run_if(in_state(GameActivity::Trade)),
+        )
+        .add_systems(
+            Update,
+            (play_recorded_commands, play_recorded_calamity_choices)
+                .run_if(in_state(GameState::Playing).and(resource_exists::<ReplayPlayback>)),
+        )
+        .add_systems(
+            PostUpdate,
+            (record_seat_commands, record_calamity_choices).run_if(in_state(GameState::Playing)),
+        )
+        .add_systems(
+            PreUpdate,
+            take_checkpoint.run_if(in_state(GameState::Playing)),
+        );
+    }
+}
@@ -0,0 +1,855 @@
+use crate::GameActivity;
+use crate::civilization::enums::GameFaction;
+use crate::civilization::{CivCardName, TradeCard};
+use crate::stupid_ai::PersonalityDefinition;
+use bevy::prelude::{Resource, error, info};
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// Env var naming the file a game is recorded to, as JSON lines.
+pub const RECORD_ENV_VAR: &str = "GAME_RECORD";
+/// Env var naming a recorded game to play back instead of a live one.
+pub const REPLAY_ENV_VAR: &str = "GAME_REPLAY";
+/// Bumped whenever the meaning of a recorded line changes.
+pub const REPLAY_FORMAT_VERSION: u32 = 3;
+
+/// Give up on a replay once the next recorded decision or command has been
+/// held back this many times without its faction ever being asked to move.
+pub const MAX_STALLED_WAITS: u32 = 5_000;
+
+/// Everything `setup_players` needs to deal the same table again.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ReplaySetup {
+    pub format_version: u32,
+    pub seed: u64,
+    pub number_of_players: usize,
+    /// The local human's faction, if the recorded game had one. Their seat is
+    /// dealt the same way on replay and then driven by their recorded
+    /// commands.
+    pub human_faction: Option<GameFaction>,
+    pub reserved_factions: Vec<GameFaction>,
+    /// Each faction's personality as it was dealt, weights and all, so a
+    /// replay plays the same minds whatever personality files it finds.
+    pub personalities: Vec<(GameFaction, PersonalityDefinition)>,
+    /// The factions that ended up at the table, for a sanity check on replay.
+    pub factions: Vec<GameFaction>,
+}
+
+/// One AI move choice: the key of the chosen entry in the player's
+/// `AvailableMoves`. Everything the AI does after picking is a pure function
+/// of the game state, so the key is all a replay needs.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ReplayDecision {
+    pub faction: GameFaction,
+    pub round: usize,
+    pub activity: GameActivity,
+    pub choice: usize,
+}
+
+/// A move a human or agent seat made, in the terms of the command it sent or
+/// the calamity selection it confirmed. Areas go by map id, players by faction
+/// and cards by name, so the action means the same thing on a replayed board.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(tag = "kind")]
+pub enum ReplayAction {
+    ExpandPopulation {
+        area: i32,
+        tokens: usize,
+    },
+    MoveTokens {
+        from: i32,
+        to: i32,
+        tokens: usize,
+    },
+    ShipFerry {
+        from: i32,
+        to: i32,
+        tokens: usize,
+    },
+    EndMovement,
+    BuildCity {
+        area: i32,
+    },
+    EndCityConstruction,
+    EliminateCity {
+        area: i32,
+    },
+    BuyCivCards {
+        cards: Vec<CivCardName>,
+        payment: Vec<(TradeCard, usize)>,
+    },
+    DoneAcquiringCivCards,
+    /// Cities picked on the calamity selection panel, by area.
+    ChooseCalamityCities {
+        areas: Vec<i32>,
+    },
+    /// A Civil War share: tokens handed over plus cities by area.
+    ChooseCivilWarShare {
+        tokens: usize,
+        areas: Vec<i32>,
+    },
+    /// The Civil War faction the victim keeps (30.415).
+    KeepCivilWarFaction {
+        first: bool,
+    },
+    /// How a Flood, Famine or Epidemic victim spread the loss over the
+    /// other players.
+    AllocateCalamityLoss {
+        victims: Vec<(GameFaction, usize)>,
+    },
+    /// A victim's own unit loss: tokens per area and the cities given up.
+    ChooseUnitLoss {
+        tokens: Vec<(i32, usize)>,
+        cities: Vec<i32>,
+    },
+    /// Monotheism targets, one per token, by area and owner.
+    ChooseMonotheismTargets {
+        tokens: Vec<(i32, GameFaction)>,
+    },
+}
+
+impl ReplayAction {
+    /// A choice a calamity waits for, rather than a command the seat sends
+    /// on its own.
+    pub fn is_calamity_choice(&self) -> bool {
+        matches!(
+            self,
+            Self::ChooseCalamityCities { .. }
+                | Self::ChooseCivilWarShare { .. }
+                | Self::KeepCivilWarFaction { .. }
+                | Self::AllocateCalamityLoss { .. }
+                | Self::ChooseUnitLoss { .. }
+                | Self::ChooseMonotheismTargets { .. }
+        )
+    }
+}
+
+/// One command from a human or agent seat. `after_decisions` is how many AI
+/// decisions had been recorded when it was applied; a replay feeds it once
+/// that many have been played, and holds the next AI decision back until it
+/// has.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ReplayCommand {
+    pub faction: GameFaction,
+    pub round: usize,
+    pub activity: GameActivity,
+    pub after_decisions: usize,
+    pub action: ReplayAction,
+}
+
+/// One side of a settled trade. Trading runs on a wall-clock countdown, so
+/// replays apply its outcome instead of re-running the negotiation.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ReplayTrade {
+    pub round: usize,
+    pub from: GameFaction,
+    pub to: GameFaction,
+    pub cards: Vec<(TradeCard, usize)>,
+}
+
+/// Snapshot taken just before each phase change, compared on replay.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ReplayCheckpoint {
+    pub round: usize,
+    pub from: GameActivity,
+    pub to: GameActivity,
+    /// Decisions made so far.
+    pub decisions: usize,
+    /// `GameRng::draws` so far.
+    pub draws: u64,
+    /// Hash of the board, hands and civ cards; see `state_digest`.
+    pub digest: u64,
+}
+
+impl ReplayCheckpoint {
+    /// Human-readable list of what differs from `expected`, empty if nothing.
+    pub fn differences(&self, expected: &ReplayCheckpoint) -> Vec<String> {
+        let mut differences = Vec::new();
+        if (self.round, &self.from, &self.to) != (expected.round, &expected.from, &expected.to) {
+            differences.push(format!(
+                "phase change: expected round {} {:?} -> {:?}, got round {} {:?} -> {:?}",
+                expected.round, expected.from, expected.to, self.round, self.from, self.to
+            ));
+        }
+        if self.decisions != expected.decisions {
+            differences.push(format!(
+                "decisions: expected {}, got {}",
+                expected.decisions, self.decisions
+            ));
+        }
+        if self.draws != expected.draws {
+            differences.push(format!(
+                "rules random draws: expected {}, got {}",
+                expected.draws, self.draws
+            ));
+        }
+        if self.digest != expected.digest {
+            differences.push(format!(
+                "game state digest: expected {:016x}, got {:016x}",
+                expected.digest, self.digest
+            ));
+        }
+        differences
+    }
+}
+
+/// One line of a replay file.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(tag = "type")]
+pub enum ReplayRecord {
+    Setup(ReplaySetup),
+    Decision(ReplayDecision),
+    Command(ReplayCommand),
+    Trade(ReplayTrade),
+    Checkpoint(ReplayCheckpoint),
+}
+
+/// A recorded game, as read back from its JSON-lines file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReplayFile {
+    pub setup: ReplaySetup,
+    pub decisions: Vec<ReplayDecision>,
+    pub commands: Vec<ReplayCommand>,
+    pub trades: Vec<ReplayTrade>,
+    pub checkpoints: Vec<ReplayCheckpoint>,
+}
+
+impl ReplayFile {
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let text = std::fs::read_to_string(path)
+            .map_err(|e| format!("cannot read {}: {e}", path.display()))?;
+        Self::from_json_lines(&text)
+    }
+
+    pub fn from_json_lines(text: &str) -> Result<Self, String> {
+        let mut setup = None;
+        let mut decisions = Vec::new();
+        let mut commands = Vec::new();
+        let mut trades = Vec::new();
+        let mut checkpoints = Vec::new();
+        for (n, line) in text.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let record = serde_json::from_str::<ReplayRecord>(line)
+                .map_err(|e| format!("line {}: {e}", n + 1))?;
+            match record {
+                ReplayRecord::Setup(s) => {
+                    if setup.is_some() {
+                        return Err(format!("line {}: second setup record", n + 1));
+                    }
+                    setup = Some(s);
+                }
+                ReplayRecord::Decision(d) => decisions.push(d),
+                ReplayRecord::Command(c) => commands.push(c),
+                ReplayRecord::Trade(t) => trades.push(t),
+                ReplayRecord::Checkpoint(c) => checkpoints.push(c),
+            }
+        }
+        let setup = setup.ok_or("no setup record")?;
+        if setup.format_version != REPLAY_FORMAT_VERSION {
+            return Err(format!(
+                "format version {} (this build reads {REPLAY_FORMAT_VERSION})",
+                setup.format_version
+            ));
+        }
+        Ok(Self {
+            setup,
+            decisions,
+            commands,
+            trades,
+            checkpoints,
+        })
+    }
+}
+
+/// Streams the current game to the `GAME_RECORD` file, one [`ReplayRecord`]
+/// per line, flushed as it goes so a hung or crashed session still leaves a
+/// replayable prefix. Inert when no output is configured or while a replay
+/// is running.
+#[derive(Resource, Debug, Default)]
+pub struct ReplayRecorder {
+    output: Option<PathBuf>,
+    file: Option<File>,
+    decisions: usize,
+}
+
+impl ReplayRecorder {
+    pub fn from_env() -> Self {
+        Self {
+            output: std::env::var(RECORD_ENV_VAR).ok().map(PathBuf::from),
+            ..Self::default()
+        }
+    }
+
+    pub fn with_output(path: impl Into<PathBuf>) -> Self {
+        Self {
+            output: Some(path.into()),
+            ..Self::default()
+        }
+    }
+
+    pub fn is_recording(&self) -> bool {
+        self.file.is_some()
+    }
+
+    /// Decisions recorded in the current game.
+    pub fn decisions(&self) -> usize {
+        self.decisions
+    }
+
+    /// Start a new recording, truncating the output file.
+    pub fn start(&mut self, setup: ReplaySetup) {
+        self.file = None;
+        self.decisions = 0;
+        let Some(path) = &self.output else {
+            return;
+        };
+        match File::create(path) {
+            Ok(file) => {
+                info!("[REPLAY] Recording game to {}", path.display());
+                self.file = Some(file);
+                self.write(&ReplayRecord::Setup(setup));
+            }
+            Err(e) => error!("[REPLAY] Cannot create {}: {}", path.display(), e),
+        }
+    }
+
+    pub fn record_decision(&mut self, decision: ReplayDecision) {
+        if self.is_recording() {
+            self.decisions += 1;
+            self.write(&ReplayRecord::Decision(decision));
+        }
+    }
+
+    /// Record a command a human or agent seat applied, placed after the AI
+    /// decisions recorded so far.
+    pub fn record_command(
+        &mut self,
+        faction: GameFaction,
+        round: usize,
+        activity: GameActivity,
+        action: ReplayAction,
+    ) {
+        self.write(&ReplayRecord::Command(ReplayCommand {
+            faction,
+            round,
+            activity,
+            after_decisions: self.decisions,
+            action,
+        }));
+    }
+
+    pub fn record_trade(&mut self, trade: ReplayTrade) {
+        self.write(&ReplayRecord::Trade(trade));
+    }
+
+    pub fn record_checkpoint(&mut self, checkpoint: ReplayCheckpoint) {
+        self.write(&ReplayRecord::Checkpoint(checkpoint));
+    }
+
+    fn write(&mut self, record: &ReplayRecord) {
+        if let Some(file) = &mut self.file
+            && let Ok(line) = serde_json::to_string(record)
+            && let Err(e) = writeln!(file, "{line}")
+        {
+            error!("[REPLAY] Failed to write replay record: {}", e);
+            self.file = None;
+        }
+    }
+}
+
+/// How far a replay may run before it pauses.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReplayControl {
+    Paused,
+    /// Feed exactly one more decision or command, then pause.
+    Step,
+    /// Run until the game reaches this round, then pause.
+    RunToRound(usize),
+    RunToEnd,
+}
+
+/// What the AI should do with its pending move under a replay.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReplayStep {
+    /// Play this `AvailableMoves` key.
+    Choice(usize),
+    /// Not now: paused, diverged, or another faction moves first.
+    Wait,
+    /// The recording is used up; the AI plays on by itself.
+    Exhausted,
+}
+
+/// A recorded game being played back. Present only during a replay; the AI
+/// takes its move choices from here, in recorded order, instead of picking,
+/// and the human and agent seats' commands are sent from here in between.
+#[derive(Resource, Debug)]
+pub struct ReplayPlayback {
+    pub file: ReplayFile,
+    pub control: ReplayControl,
+    next_decision: usize,
+    next_command: usize,
+    next_checkpoint: usize,
+    stalled_waits: u32,
+    divergence: Option<String>,
+}
+
+impl ReplayPlayback {
+    pub fn new(file: ReplayFile, control: ReplayControl) -> Self {
+        Self {
+            file,
+            control,
+            next_decision: 0,
+            next_command: 0,
+            next_checkpoint: 0,
+            stalled_waits: 0,
+            divergence: None,
+        }
+    }
+
+    /// Playback of the `GAME_REPLAY` file, starting paused, if one is set.
+    pub fn from_env() -> Option<Self> {
+        let path = PathBuf::from(std::env::var(REPLAY_ENV_VAR).ok()?);
+        match ReplayFile::load(&path) {
+            Ok(file) => {
+                info!(
+                    "[REPLAY] Loaded {} ({} decisions, {} commands, {} checkpoints)",
+                    path.display(),
+                    file.decisions.len(),
+                    file.commands.len(),
+                    file.checkpoints.len()
+                );
+                Some(Self::new(file, ReplayControl::Paused))
+            }
+            Err(e) => {
+                error!("[REPLAY] Not replaying {}: {}", path.display(), e);
+                None
+            }
+        }
+    }
+
+    pub fn decisions_played(&self) -> usize {
+        self.next_decision
+    }
+
+    pub fn commands_played(&self) -> usize {
+        self.next_command
+    }
+
+    pub fn checkpoints_verified(&self) -> usize {
+        self.next_checkpoint
+    }
+
+    pub fn divergence(&self) -> Option<&str> {
+        self.divergence.as_deref()
+    }
+
+    /// Every recorded decision and command played and every checkpoint
+    /// matched.
+    pub fn is_finished(&self) -> bool {
+        self.next_decision >= self.file.decisions.len()
+            && self.next_command >= self.file.commands.len()
+            && self.next_checkpoint >= self.file.checkpoints.len()
+    }
+
+    /// Round of the next decision to be fed, if any are left.
+    pub fn next_round(&self) -> Option<usize> {
+        self.file.decisions.get(self.next_decision).map(|d| d.round)
+    }
+
+    fn diverge(&mut self, reason: String) {
+        error!("[REPLAY] Diverged: {}", reason);
+        self.divergence = Some(reason);
+        self.control = ReplayControl::Paused;
+    }
+
+    /// Whether the next recorded command has to go before the next decision.
+    fn command_due(&self) -> bool {
+        self.file
+            .commands
+            .get(self.next_command)
+            .is_some_and(|c| c.after_decisions <= self.next_decision)
+    }
+
+    fn stall(&mut self, reason: impl FnOnce() -> String) {
+        self.stalled_waits += 1;
+        if self.stalled_waits >= MAX_STALLED_WAITS {
+            self.diverge(reason());
+        }
+    }
+
+    fn played_one(&mut self) {
+        self.stalled_waits = 0;
+        if self.control == ReplayControl::Step {
+            self.control = ReplayControl::Paused;
+        }
+    }
+
+    fn may_advance(&mut self, round: usize) -> bool {
+        match self.control {
+            ReplayControl::Paused => false,
+            ReplayControl::Step | ReplayControl::RunToEnd => true,
+            ReplayControl::RunToRound(target) if round >= target => {
+                info!("[REPLAY] Reached round {round}");
+                self.control = ReplayControl::Paused;
+                false
+            }
+            ReplayControl::RunToRound(_) => true,
+        }
+    }
+
+    /// The recorded choice for `faction`'s pending move, if it's their turn
+    /// in the recording. `scored` is the AI's scored move list, used to check
+    /// the recorded key is still on offer.
+    pub fn next_decision(
+        &mut self,
+        faction: GameFaction,
+        round: usize,
+        activity: &GameActivity,
+        scored: &[(usize, f32)],
+    ) -> ReplayStep {
+        if self.divergence.is_some() || !self.may_advance(round) {
+            return ReplayStep::Wait;
+        }
+        if self.command_due() {
+            return ReplayStep::Wait;
+        }
+        let Some(decision) = self.file.decisions.get(self.next_decision).cloned() else {
+            return ReplayStep::Exhausted;
+        };
+        if decision.faction != faction {
+            let next = self.next_decision;
+            self.stall(|| {
+                format!(
+                    "decision #{next} belongs to {:?}, who is never asked to move",
+                    decision.faction
+                )
+            });
+            return ReplayStep::Wait;
+        }
+        if decision.round != round || decision.activity != *activity {
+            self.diverge(format!(
+                "decision #{} was recorded in round {} {:?}, but {:?} is asked in round {} {:?}",
+                self.next_decision, decision.round, decision.activity, faction, round, activity
+            ));
+            return ReplayStep::Wait;
+        }
+        if !scored.iter().any(|(key, _)| *key == decision.choice) {
+            self.diverge(format!(
+                "decision #{}: {:?} has no move {} in round {} {:?}",
+                self.next_decision, faction, decision.choice, round, activity
+            ));
+            return ReplayStep::Wait;
+        }
+        self.next_decision += 1;
+        self.played_one();
+        ReplayStep::Choice(decision.choice)
+    }
+
+    /// The next recorded command, if its turn has come: every AI decision
+    /// before it has been played and the game is in its round and phase.
+    pub fn next_command(&mut self, round: usize, activity: &GameActivity) -> Option<ReplayCommand> {
+        if self.divergence.is_some() || !self.command_due() {
+            return None;
+        }
+        let command = self.file.commands[self.next_command].clone();
+        if command.round != round || command.activity != *activity {
+            // The phase the command belongs to may be a frame or two away.
+            let next = self.next_command;
+            self.stall(|| {
+                format!(
+                    "command #{next} was recorded in round {} {:?}, but the game stays in round {round} {activity:?}",
+                    command.round, command.activity
+                )
+            });
+            return None;
+        }
+        if !self.may_advance(round) {
+            return None;
+        }
+        self.next_command += 1;
+        self.played_one();
+        Some(command)
+    }
+
+    /// The next recorded command once every AI decision before it has been
+    /// played, without playing it.
+    pub fn peek_command(&self) -> Option<&ReplayCommand> {
+        if self.divergence.is_some() || !self.command_due() {
+            return None;
+        }
+        self.file.commands.get(self.next_command)
+    }
+
+    /// Hold the next command back for a frame: a calamity choice waits until
+    /// the game asks its seat for it.
+    pub fn hold_command(&mut self) {
+        let next = self.next_command;
+        self.stall(|| format!("command #{next} is a calamity choice its seat is never asked for"));
+    }
+
+    /// Compare a phase change against the recording.
+    pub fn verify_checkpoint(&mut self, actual: &ReplayCheckpoint) {
+        if self.divergence.is_some() {
+            return;
+        }
+        let Some(expected) = self.file.checkpoints.get(self.next_checkpoint) else {
+            return;
+        };
+        let differences = actual.differences(expected);
+        if differences.is_empty() {
+            self.next_checkpoint += 1;
+        } else {
+            self.diverge(format!(
+                "checkpoint #{}: {}",
+                self.next_checkpoint,
+                differences.join("; ")
+            ));
+        }
+    }
+
+    /// The recorded trades of `round`, in the order they were settled.
+    pub fn trades_in_round(&self, round: usize) -> impl Iterator<Item = &ReplayTrade> {
+        self.file.trades.iter().filter(move |t| t.round == round)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stupid_ai::Playstyle;
+
+    fn setup() -> ReplaySetup {
+        ReplaySetup {
+            format_version: REPLAY_FORMAT_VERSION,
+            seed: 42,
+            number_of_players: 2,
+            human_faction: None,
+            reserved_factions: Vec::new(),
+            personalities: vec![(
+                GameFaction::Egypt,
+                PersonalityDefinition::from_playstyle(Playstyle::Warlord),
+            )],
+            factions: vec![GameFaction::Egypt, GameFaction::Crete],
+        }
+    }
+
+    fn decision(faction: GameFaction, choice: usize) -> ReplayDecision {
+        ReplayDecision {
+            faction,
+            round: 1,
+            activity: GameActivity::PopulationExpansion,
+            choice,
+        }
+    }
+
+    fn file_with(decisions: Vec<ReplayDecision>) -> ReplayFile {
+        ReplayFile {
+            setup: setup(),
+            decisions,
+            commands: Vec::new(),
+            trades: Vec::new(),
+            checkpoints: Vec::new(),
+        }
+    }
+
+    fn command(after_decisions: usize, action: ReplayAction) -> ReplayCommand {
+        ReplayCommand {
+            faction: GameFaction::Crete,
+            round: 1,
+            activity: GameActivity::PopulationExpansion,
+            after_decisions,
+            action,
+        }
+    }
+
+    const SCORED: [(usize, f32); 3] = [(0, 0.1), (1, 0.5), (2, 0.2)];
+
+    #[test]
+    fn json_lines_round_trip() {
+        let records = [
+            ReplayRecord::Setup(setup()),
+            ReplayRecord::Decision(decision(GameFaction::Egypt, 1)),
+            ReplayRecord::Command(command(
+                1,
+                ReplayAction::ExpandPopulation { area: 3, tokens: 2 },
+            )),
+            ReplayRecord::Trade(ReplayTrade {
+                round: 1,
+                from: GameFaction::Egypt,
+                to: GameFaction::Crete,
+                cards: vec![(TradeCard::Salt, 2)],
+            }),
+        ];
+        let text: String = records
+            .iter()
+            .map(|r| serde_json::to_string(r).unwrap() + "\n")
+            .collect();
+        let file = ReplayFile::from_json_lines(&text).unwrap();
+        assert_eq!(file.setup, setup());
+        assert_eq!(file.decisions, vec![decision(GameFaction::Egypt, 1)]);
+        assert_eq!(
+            file.commands,
+            vec![command(
+                1,
+                ReplayAction::ExpandPopulation { area: 3, tokens: 2 }
+            )]
+        );
+        assert_eq!(file.trades.len(), 1);
+    }
+
+    #[test]
+    fn a_file_from_another_format_version_is_rejected() {
+        let mut old = setup();
+        old.format_version = REPLAY_FORMAT_VERSION + 1;
+        let text = serde_json::to_string(&ReplayRecord::Setup(old)).unwrap();
+        assert!(ReplayFile::from_json_lines(&text).is_err());
+    }
+
+    #[test]
+    fn decisions_are_fed_in_recorded_order() {
+        let mut playback = ReplayPlayback::new(
+            file_with(vec![
+                decision(GameFaction::Egypt, 1),
+                decision(GameFaction::Crete, 2),
+            ]),
+            ReplayControl::RunToEnd,
+        );
+        let activity = GameActivity::PopulationExpansion;
+        assert_eq!(
+            playback.next_decision(GameFaction::Crete, 1, &activity, &SCORED),
+            ReplayStep::Wait
+        );
+        assert_eq!(
+            playback.next_decision(GameFaction::Egypt, 1, &activity, &SCORED),
+            ReplayStep::Choice(1)
+        );
+        assert_eq!(
+            playback.next_decision(GameFaction::Crete, 1, &activity, &SCORED),
+            ReplayStep::Choice(2)
+        );
+        assert_eq!(
+            playback.next_decision(GameFaction::Egypt, 1, &activity, &SCORED),
+            ReplayStep::Exhausted
+        );
+        assert!(playback.is_finished());
+    }
+
+    #[test]
+    fn a_seat_command_is_played_between_the_decisions_around_it() {
+        let mut file = file_with(vec![
+            decision(GameFaction::Egypt, 0),
+            decision(GameFaction::Egypt, 1),
+        ]);
+        file.commands = vec![command(1, ReplayAction::EndMovement)];
+        let mut playback = ReplayPlayback::new(file, ReplayControl::RunToEnd);
+        let activity = GameActivity::PopulationExpansion;
+
+        assert_eq!(playback.next_command(1, &activity), None);
+        assert_eq!(
+            playback.next_decision(GameFaction::Egypt, 1, &activity, &SCORED),
+            ReplayStep::Choice(0)
+        );
+        assert_eq!(
+            playback.next_decision(GameFaction::Egypt, 1, &activity, &SCORED),
+            ReplayStep::Wait
+        );
+        assert_eq!(playback.next_command(2, &activity), None);
+        assert_eq!(
+            playback.next_command(1, &activity),
+            Some(command(1, ReplayAction::EndMovement))
+        );
+        assert_eq!(
+            playback.next_decision(GameFaction::Egypt, 1, &activity, &SCORED),
+            ReplayStep::Choice(1)
+        );
+        assert!(playback.is_finished());
+    }
+
+    #[test]
+    fn a_held_calamity_choice_stays_next_in_line() {
+        let choice = ReplayAction::AllocateCalamityLoss {
+            victims: vec![(GameFaction::Egypt, 3)],
+        };
+        let mut file = file_with(Vec::new());
+        file.commands = vec![command(0, choice.clone())];
+        let mut playback = ReplayPlayback::new(file, ReplayControl::RunToEnd);
+        let activity = GameActivity::PopulationExpansion;
+
+        assert!(playback.peek_command().unwrap().action.is_calamity_choice());
+        playback.hold_command();
+        assert_eq!(playback.commands_played(), 0);
+        assert_eq!(
+            playback.next_command(1, &activity),
+            Some(command(0, choice))
+        );
+        assert!(playback.peek_command().is_none());
+    }
+
+    #[test]
+    fn step_feeds_one_decision_then_pauses() {
+        let mut playback = ReplayPlayback::new(
+            file_with(vec![
+                decision(GameFaction::Egypt, 0),
+                decision(GameFaction::Egypt, 1),
+            ]),
+            ReplayControl::Step,
+        );
+        let activity = GameActivity::PopulationExpansion;
+        assert_eq!(
+            playback.next_decision(GameFaction::Egypt, 1, &activity, &SCORED),
+            ReplayStep::Choice(0)
+        );
+        assert_eq!(playback.control, ReplayControl::Paused);
+        assert_eq!(
+            playback.next_decision(GameFaction::Egypt, 1, &activity, &SCORED),
+            ReplayStep::Wait
+        );
+    }
+
+    #[test]
+    fn a_move_that_is_no_longer_on_offer_is_a_divergence() {
+        let mut playback = ReplayPlayback::new(
+            file_with(vec![decision(GameFaction::Egypt, 7)]),
+            ReplayControl::RunToEnd,
+        );
+        let step = playback.next_decision(
+            GameFaction::Egypt,
+            1,
+            &GameActivity::PopulationExpansion,
+            &SCORED,
+        );
+        assert_eq!(step, ReplayStep::Wait);
+        assert!(playback.divergence().is_some());
+        assert_eq!(playback.decisions_played(), 0);
+    }
+
+    #[test]
+    fn a_mismatched_checkpoint_is_a_divergence() {
+        let checkpoint = ReplayCheckpoint {
+            round: 1,
+            from: GameActivity::PopulationExpansion,
+            to: GameActivity::Census,
+            decisions: 4,
+            draws: 10,
+            digest: 0xabc,
+        };
+        let mut file = file_with(Vec::new());
+        file.checkpoints = vec![checkpoint.clone(), checkpoint.clone()];
+        let mut playback = ReplayPlayback::new(file, ReplayControl::RunToEnd);
+
+        playback.verify_checkpoint(&checkpoint);
+        assert_eq!(playback.checkpoints_verified(), 1);
+        assert!(playback.divergence().is_none());
+
+        playback.verify_checkpoint(&ReplayCheckpoint {
+            digest: 0xdef,
+            ..checkpoint
+        });
+        assert_eq!(playback.checkpoints_verified(), 1);
+        assert!(playback.divergence().unwrap().contains("digest"));
+    }
+}
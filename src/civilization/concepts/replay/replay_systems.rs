@@ -0,0 +1,997 @@
+use crate::GameActivity;
+use crate::civilization::components::{BuiltCity, Faction, GameArea, Population, Token, Treasury};
+use crate::civilization::concepts::acquire_trade_cards::PlayerTradeCards;
+use crate::civilization::concepts::census::GameInfoAndStuff;
+use crate::civilization::concepts::check_city_support::EliminateCity;
+use crate::civilization::concepts::city_construction::{
+    BuildCityCommand, EndPlayerCityConstruction,
+};
+use crate::civilization::concepts::civ_cards::{
+    ConfirmCivCardPurchase, PlayerCivilizationCards, PlayerDoneAcquiringCivilizationCards,
+};
+use crate::civilization::concepts::game_rng::GameRng;
+use crate::civilization::concepts::movement::{
+    MoveTokenFromAreaToAreaCommand, PlayerMovementEnded, ShipFerryCommand,
+};
+use crate::civilization::concepts::population_expansion::ExpandPopulationManuallyCommand;
+use crate::civilization::concepts::replay::replay_components::ReplayedSeat;
+use crate::civilization::concepts::replay::replay_resources::{
+    REPLAY_FORMAT_VERSION, ReplayAction, ReplayCheckpoint, ReplayPlayback, ReplayRecorder,
+    ReplaySetup, ReplayTrade,
+};
+use crate::civilization::concepts::resolve_calamities::calamities::civil_war::FactionChoice;
+use crate::civilization::concepts::resolve_calamities::resolve_calamities_ui_components::{
+    AwaitingHumanCalamitySelection, AwaitingMonotheismSelection, CalamitySelectionState,
+    CivilWarSelectionState, CivilWarUiRole, EpidemicSelectionState, FamineSelectionState,
+    FloodSelectionState, MonotheismSelectionState, UnitLossSelectionState,
+};
+use crate::civilization::concepts::trade::{CanTrade, SendTradingCardsCommand};
+use crate::civilization::enums::GameFaction;
+use crate::civilization::events::CommandApplied;
+use crate::civilization::plugins::DebugOptions;
+use crate::player::Player;
+use crate::stupid_ai::{AgentControlled, IsHuman, Personality, PersonalityDefinition};
+use bevy::ecs::system::SystemParam;
+use bevy::platform::collections::HashMap;
+use bevy::prelude::*;
+
+type AreaDigestQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        &'static GameArea,
+        &'static Population,
+        Option<&'static BuiltCity>,
+    ),
+>;
+type PlayerDigestQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        Entity,
+        &'static Faction,
+        &'static PlayerTradeCards,
+        &'static PlayerCivilizationCards,
+        &'static Treasury,
+    ),
+    With<Player>,
+>;
+
+/// 64-bit FNV-1a, written out so a digest is the same on every platform,
+/// toolchain and run; the std hashers promise none of that.
+struct Fnv1a(u64);
+
+impl Fnv1a {
+    const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0000_0100_0000_01b3;
+
+    fn new() -> Self {
+        Self(Self::OFFSET_BASIS)
+    }
+
+    fn bytes(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 ^= u64::from(*byte);
+            self.0 = self.0.wrapping_mul(Self::PRIME);
+        }
+    }
+
+    fn number(&mut self, n: u64) {
+        self.bytes(&n.to_le_bytes());
+    }
+
+    /// Length first, so neighbouring strings cannot run into each other.
+    fn text(&mut self, text: &str) {
+        self.number(text.len() as u64);
+        self.bytes(text.as_bytes());
+    }
+}
+
+/// Hash of everything a rules regression would disturb: who has how many
+/// tokens and which city in every area, plus each faction's hand size, civ
+/// cards and treasury. Keyed by faction and area id, never by `Entity`, so it
+/// is comparable between the windowed game and a headless run.
+pub fn state_digest(areas: &AreaDigestQuery, players: &PlayerDigestQuery) -> u64 {
+    let faction_of: HashMap<Entity, String> = players
+        .iter()
+        .map(|(player, faction, ..)| (player, format!("{:?}", faction.faction)))
+        .collect();
+    let name = |player: &Entity| faction_of.get(player).cloned().unwrap_or_default();
+
+    let mut board: Vec<(i32, Vec<(String, usize)>, Option<String>)> = areas
+        .iter()
+        .map(|(area, population, city)| {
+            let mut tokens: Vec<(String, usize)> = population
+                .player_tokens()
+                .iter()
+                .map(|(player, tokens)| (name(player), tokens.len()))
+                .collect();
+            tokens.sort();
+            (area.id, tokens, city.map(|c| name(&c.player)))
+        })
+        .collect();
+    board.sort();
+
+    let mut hands: Vec<(String, usize, Vec<String>, usize)> = players
+        .iter()
+        .map(|(_, faction, trade_cards, civ_cards, treasury)| {
+            let mut civ: Vec<String> = civ_cards.cards.iter().map(|c| format!("{c:?}")).collect();
+            civ.sort();
+            (
+                format!("{:?}", faction.faction),
+                trade_cards.number_of_trade_cards(),
+                civ,
+                treasury.tokens_in_treasury(),
+            )
+        })
+        .collect();
+    hands.sort();
+
+    let mut hasher = Fnv1a::new();
+    hasher.number(board.len() as u64);
+    for (area, tokens, city) in &board {
+        hasher.number(i64::from(*area) as u64);
+        hasher.number(tokens.len() as u64);
+        for (faction, count) in tokens {
+            hasher.text(faction);
+            hasher.number(*count as u64);
+        }
+        match city {
+            Some(owner) => {
+                hasher.number(1);
+                hasher.text(owner);
+            }
+            None => hasher.number(0),
+        }
+    }
+    hasher.number(hands.len() as u64);
+    for (faction, trade_cards, civ_cards, treasury) in &hands {
+        hasher.text(faction);
+        hasher.number(*trade_cards as u64);
+        hasher.number(civ_cards.len() as u64);
+        for card in civ_cards {
+            hasher.text(card);
+        }
+        hasher.number(*treasury as u64);
+    }
+    hasher.0
+}
+
+/// Deal the recorded table: same seed, same player count and seats, so
+/// `setup_players` hands out the same factions, rulers and start areas. The
+/// recorded personalities go on in `hand_seats_to_replay`, once there are
+/// players to give them to.
+pub fn apply_replay_setup(playback: Res<ReplayPlayback>, mut debug_options: ResMut<DebugOptions>) {
+    let setup = &playback.file.setup;
+    debug_options.game_seed = Some(setup.seed);
+    debug_options.number_of_players = setup.number_of_players;
+    debug_options.add_human_player = setup.human_faction.is_some();
+    if let Some(faction) = setup.human_faction {
+        debug_options.human_faction = faction;
+    }
+    debug_options.reserved_factions = setup.reserved_factions.clone();
+    debug_options.start_at_activity = None;
+    info!(
+        "[REPLAY] Replaying seed {} with {} players",
+        setup.seed, setup.number_of_players
+    );
+}
+
+/// Every seat is driven from the recording, so nobody waits for a keyboard or
+/// an agent: AI seats replay their decisions, and human and agent seats lose
+/// their controls and replay their commands (see `play_recorded_commands`)
+/// and calamity choices (see `play_recorded_calamity_choices`).
+pub fn hand_seats_to_replay(
+    mut commands: Commands,
+    playback: Res<ReplayPlayback>,
+    players: Query<(Entity, &Faction, Has<IsHuman>), With<Player>>,
+) {
+    let setup = &playback.file.setup;
+    for (player, faction, is_human) in &players {
+        if is_human {
+            commands
+                .entity(player)
+                .remove::<(IsHuman, AgentControlled)>()
+                .insert(ReplayedSeat);
+        }
+        if let Some((_, definition)) = setup
+            .personalities
+            .iter()
+            .find(|(f, _)| *f == faction.faction)
+        {
+            commands.entity(player).insert(definition.personality());
+        }
+    }
+    let seated: Vec<GameFaction> = players.iter().map(|(_, f, _)| f.faction).collect();
+    let recorded = &setup.factions;
+    if seated.len() != recorded.len() || seated.iter().any(|f| !recorded.contains(f)) {
+        warn!(
+            "[REPLAY] Seated factions differ from the recording ({:?}); expect a divergence",
+            recorded
+        );
+    }
+}
+
+/// A replayed seat makes its calamity choices the way it did at the table:
+/// the calamity waits on it as on a human, and the recording answers.
+pub fn seat_replayed_choosers(mut commands: Commands, seats: Query<Entity, With<ReplayedSeat>>) {
+    for seat in &seats {
+        commands.entity(seat).insert(IsHuman);
+    }
+}
+
+pub fn unseat_replayed_choosers(mut commands: Commands, seats: Query<Entity, With<ReplayedSeat>>) {
+    for seat in &seats {
+        commands.entity(seat).remove::<IsHuman>();
+    }
+}
+
+pub fn start_recording(
+    mut recorder: ResMut<ReplayRecorder>,
+    debug_options: Res<DebugOptions>,
+    game_rng: Res<GameRng>,
+    players: Query<(&Faction, Option<&Personality>), With<Player>>,
+) {
+    let setup = ReplaySetup {
+        format_version: REPLAY_FORMAT_VERSION,
+        seed: game_rng.seed(),
+        number_of_players: debug_options.number_of_players,
+        human_faction: debug_options
+            .add_human_player
+            .then_some(debug_options.human_faction),
+        reserved_factions: debug_options.reserved_factions.clone(),
+        personalities: players
+            .iter()
+            .filter_map(|(f, personality)| {
+                let definition = PersonalityDefinition::from_personality(personality?);
+                Some((f.faction, definition))
+            })
+            .collect(),
+        factions: players.iter().map(|(f, _)| f.faction).collect(),
+    };
+    recorder.start(setup);
+}
+
+/// The commands a seat can send, read where their handler can refuse them
+/// once it has applied them.
+#[derive(SystemParam)]
+pub struct SeatCommands<'w, 's> {
+    expand: MessageReader<'w, 's, CommandApplied<ExpandPopulationManuallyCommand>>,
+    move_tokens: MessageReader<'w, 's, CommandApplied<MoveTokenFromAreaToAreaCommand>>,
+    ferry: MessageReader<'w, 's, CommandApplied<ShipFerryCommand>>,
+    end_move: MessageReader<'w, 's, PlayerMovementEnded>,
+    build_city: MessageReader<'w, 's, CommandApplied<BuildCityCommand>>,
+    end_city: MessageReader<'w, 's, EndPlayerCityConstruction>,
+    eliminate_city: MessageReader<'w, 's, EliminateCity>,
+    purchase: MessageReader<'w, 's, CommandApplied<ConfirmCivCardPurchase>>,
+    done_civ: MessageReader<'w, 's, PlayerDoneAcquiringCivilizationCards>,
+}
+
+impl SeatCommands<'_, '_> {
+    /// Everything sent since the last read, with the player who sent it.
+    fn read(&mut self, area_id: impl Fn(Entity) -> Option<i32>) -> Vec<(Entity, ReplayAction)> {
+        let mut sent = Vec::new();
+        for CommandApplied(c) in self.expand.read() {
+            if let Some(area) = area_id(c.area) {
+                let tokens = c.number_of_tokens;
+                sent.push((c.player, ReplayAction::ExpandPopulation { area, tokens }));
+            }
+        }
+        for CommandApplied(c) in self.move_tokens.read() {
+            if let (Some(from), Some(to)) = (area_id(c.source_area), area_id(c.target_area)) {
+                let tokens = c.number_of_tokens;
+                sent.push((c.player, ReplayAction::MoveTokens { from, to, tokens }));
+            }
+        }
+        for CommandApplied(c) in self.ferry.read() {
+            if let (Some(from), Some(to)) = (area_id(c.source_area), area_id(c.target_area)) {
+                let tokens = c.number_of_tokens;
+                sent.push((c.player, ReplayAction::ShipFerry { from, to, tokens }));
+            }
+        }
+        sent.extend(
+            self.end_move
+                .read()
+                .map(|c| (c.player, ReplayAction::EndMovement)),
+        );
+        for CommandApplied(c) in self.build_city.read() {
+            if let Some(area) = area_id(c.area) {
+                sent.push((c.player, ReplayAction::BuildCity { area }));
+            }
+        }
+        sent.extend(
+            self.end_city
+                .read()
+                .map(|c| (c.player, ReplayAction::EndCityConstruction)),
+        );
+        // Conflict eliminations are the rules' doing, not the owner's.
+        for c in self.eliminate_city.read().filter(|c| !c.is_conflict) {
+            if let Some(area) = area_id(c.area_entity) {
+                sent.push((c.player, ReplayAction::EliminateCity { area }));
+            }
+        }
+        for CommandApplied(c) in self.purchase.read() {
+            let cards = c.cards_to_buy.clone();
+            let payment = c.payment.iter().map(|(card, n)| (*card, *n)).collect();
+            sent.push((c.player, ReplayAction::BuyCivCards { cards, payment }));
+        }
+        sent.extend(
+            self.done_civ
+                .read()
+                .map(|c| (c.0, ReplayAction::DoneAcquiringCivCards)),
+        );
+        sent
+    }
+}
+
+/// Records what human and agent seats do. Runs after the frame's handlers, so
+/// each command is placed after the AI decisions made alongside it.
+pub fn record_seat_commands(
+    mut recorder: ResMut<ReplayRecorder>,
+    mut commands: SeatCommands,
+    game_info: Res<GameInfoAndStuff>,
+    activity: Option<Res<State<GameActivity>>>,
+    seats: Query<&Faction, With<IsHuman>>,
+    areas: Query<&GameArea>,
+) {
+    let sent = commands.read(|area| areas.get(area).ok().map(|a| a.id));
+    let Some(activity) = activity.filter(|_| recorder.is_recording()) else {
+        return;
+    };
+    for (player, action) in sent {
+        if let Ok(seat) = seats.get(player) {
+            recorder.record_command(
+                seat.faction,
+                game_info.round,
+                activity.get().clone(),
+                action,
+            );
+        }
+    }
+}
+
+/// The panels a human or agent seat makes its calamity choices on.
+#[derive(SystemParam)]
+pub struct CalamitySelections<'w> {
+    cities: ResMut<'w, CalamitySelectionState>,
+    civil_war: ResMut<'w, CivilWarSelectionState>,
+    flood: ResMut<'w, FloodSelectionState>,
+    famine: ResMut<'w, FamineSelectionState>,
+    epidemic: ResMut<'w, EpidemicSelectionState>,
+    unit_loss: ResMut<'w, UnitLossSelectionState>,
+    monotheism: ResMut<'w, MonotheismSelectionState>,
+}
+
+impl CalamitySelections<'_> {
+    /// The selection `player` confirmed on whichever panel they hold.
+    fn confirmed(
+        &self,
+        player: Entity,
+        area_id: impl Fn(Entity) -> Option<i32>,
+        faction_of: impl Fn(Entity) -> Option<GameFaction>,
+    ) -> Option<ReplayAction> {
+        let area_ids = |areas: &[Entity]| {
+            areas
+                .iter()
+                .map(|a| area_id(*a))
+                .collect::<Option<Vec<_>>>()
+        };
+        if self.civil_war.acting_player == Some(player) {
+            return match self.civil_war.role {
+                CivilWarUiRole::ChooseFaction => {
+                    self.civil_war
+                        .chosen_faction
+                        .map(|choice| ReplayAction::KeepCivilWarFaction {
+                            first: choice == FactionChoice::First,
+                        })
+                }
+                CivilWarUiRole::Victim | CivilWarUiRole::Beneficiary => {
+                    Some(ReplayAction::ChooseCivilWarShare {
+                        tokens: self.civil_war.selected_token_count,
+                        areas: area_ids(&self.civil_war.selected_cities)?,
+                    })
+                }
+            };
+        }
+        for (acting_player, victims) in [
+            (self.flood.acting_player, &self.flood.victims),
+            (self.famine.acting_player, &self.famine.victims),
+            (self.epidemic.acting_player, &self.epidemic.victims),
+        ] {
+            if acting_player == Some(player) {
+                let victims = victims
+                    .iter()
+                    .filter(|(_, _, allocated)| *allocated > 0)
+                    .map(|&(victim, _, allocated)| Some((faction_of(victim)?, allocated)))
+                    .collect::<Option<_>>()?;
+                return Some(ReplayAction::AllocateCalamityLoss { victims });
+            }
+        }
+        if self.unit_loss.acting_player == Some(player) {
+            let tokens = self
+                .unit_loss
+                .areas
+                .iter()
+                .filter(|(_, _, allocated)| *allocated > 0)
+                .map(|&(area, _, allocated)| Some((area_id(area)?, allocated)))
+                .collect::<Option<_>>()?;
+            let cities = area_ids(&self.unit_loss.selected_cities())?;
+            return Some(ReplayAction::ChooseUnitLoss { tokens, cities });
+        }
+        if self.cities.player == Some(player) {
+            let areas = area_ids(&self.cities.selected_cities)?;
+            return Some(ReplayAction::ChooseCalamityCities { areas });
+        }
+        None
+    }
+
+    /// The Monotheism targets `player` confirmed, by area and owner.
+    fn confirmed_monotheism(
+        &self,
+        player: Entity,
+        area_id: impl Fn(Entity) -> Option<i32>,
+        owner_of: impl Fn(Entity) -> Option<GameFaction>,
+    ) -> Option<ReplayAction> {
+        if self.monotheism.player != Some(player) {
+            return None;
+        }
+        let tokens = self
+            .monotheism
+            .selected
+            .iter()
+            .map(|token| {
+                let (_, area) = self
+                    .monotheism
+                    .candidates
+                    .iter()
+                    .find(|(t, _)| t == token)?;
+                Some((area_id(*area)?, owner_of(*token)?))
+            })
+            .collect::<Option<_>>()?;
+        Some(ReplayAction::ChooseMonotheismTargets { tokens })
+    }
+
+    /// Whether the panel `action` was chosen on is waiting for `player`.
+    fn is_asking(&self, player: Entity, action: &ReplayAction) -> bool {
+        let player = Some(player);
+        match action {
+            ReplayAction::ChooseCalamityCities { .. } => self.cities.player == player,
+            ReplayAction::ChooseCivilWarShare { .. } => {
+                self.civil_war.acting_player == player
+                    && self.civil_war.role != CivilWarUiRole::ChooseFaction
+            }
+            ReplayAction::KeepCivilWarFaction { .. } => {
+                self.civil_war.acting_player == player
+                    && self.civil_war.role == CivilWarUiRole::ChooseFaction
+            }
+            ReplayAction::AllocateCalamityLoss { .. } => [
+                self.flood.acting_player,
+                self.famine.acting_player,
+                self.epidemic.acting_player,
+            ]
+            .contains(&player),
+            ReplayAction::ChooseUnitLoss { .. } => self.unit_loss.acting_player == player,
+            ReplayAction::ChooseMonotheismTargets { .. } => self.monotheism.player == player,
+            _ => false,
+        }
+    }
+
+    /// Puts a recorded choice on `player`'s panel as if they had made it
+    /// there; `None` if the board has no such area or player.
+    fn choose(
+        &mut self,
+        player: Entity,
+        action: &ReplayAction,
+        area: impl Fn(i32) -> Option<Entity>,
+        player_of: impl Fn(GameFaction) -> Option<Entity>,
+        owner_of: impl Fn(Entity) -> Option<GameFaction>,
+    ) -> Option<()> {
+        let areas = |ids: &[i32]| ids.iter().map(|id| area(*id)).collect::<Option<Vec<_>>>();
+        match action {
+            ReplayAction::ChooseCalamityCities { areas: ids } => {
+                self.cities.selected_cities = areas(ids)?;
+            }
+            ReplayAction::ChooseCivilWarShare { tokens, areas: ids } => {
+                self.civil_war.selected_token_count = *tokens;
+                self.civil_war.selected_cities = areas(ids)?;
+            }
+            ReplayAction::KeepCivilWarFaction { first } => {
+                self.civil_war.choose_faction(if *first {
+                    FactionChoice::First
+                } else {
+                    FactionChoice::Second
+                });
+            }
+            ReplayAction::AllocateCalamityLoss { victims } => {
+                let losses: Vec<(Entity, usize)> = victims
+                    .iter()
+                    .map(|(faction, n)| Some((player_of(*faction)?, *n)))
+                    .collect::<Option<_>>()?;
+                let panel = [
+                    (self.flood.acting_player, &mut self.flood.victims),
+                    (self.famine.acting_player, &mut self.famine.victims),
+                    (self.epidemic.acting_player, &mut self.epidemic.victims),
+                ]
+                .into_iter()
+                .find_map(|(acting, victims)| (acting == Some(player)).then_some(victims))?;
+                for (victim, _, allocated) in panel.iter_mut() {
+                    *allocated = losses
+                        .iter()
+                        .find_map(|(v, n)| (*v == *victim).then_some(*n))
+                        .unwrap_or(0);
+                }
+            }
+            ReplayAction::ChooseUnitLoss { tokens, cities } => {
+                let losses: Vec<(Entity, usize)> = tokens
+                    .iter()
+                    .map(|(id, n)| Some((area(*id)?, *n)))
+                    .collect::<Option<_>>()?;
+                let cities = areas(cities)?;
+                for (area, _, allocated) in &mut self.unit_loss.areas {
+                    *allocated = losses
+                        .iter()
+                        .find_map(|(a, n)| (*a == *area).then_some(*n))
+                        .unwrap_or(0);
+                }
+                for (city, selected) in &mut self.unit_loss.cities {
+                    *selected = cities.contains(city);
+                }
+            }
+            ReplayAction::ChooseMonotheismTargets { tokens } => {
+                let mut selected = Vec::new();
+                for (id, owner) in tokens {
+                    let target = area(*id)?;
+                    let (token, _) = self.monotheism.candidates.iter().find(|(token, area)| {
+                        *area == target
+                            && owner_of(*token) == Some(*owner)
+                            && !selected.contains(token)
+                    })?;
+                    selected.push(*token);
+                }
+                self.monotheism.selected = selected;
+            }
+            _ => return None,
+        }
+        Some(())
+    }
+}
+
+/// Records the calamity selections human and agent seats confirm. A panel's
+/// Confirm removes the seat's waiting marker and leaves the selection for the
+/// calamity to take next frame, so it is still on the panel here.
+#[allow(clippy::too_many_arguments)]
+pub fn record_calamity_choices(
+    mut recorder: ResMut<ReplayRecorder>,
+    mut confirmed: RemovedComponents<AwaitingHumanCalamitySelection>,
+    mut confirmed_monotheism: RemovedComponents<AwaitingMonotheismSelection>,
+    selections: CalamitySelections,
+    game_info: Res<GameInfoAndStuff>,
+    activity: Option<Res<State<GameActivity>>>,
+    players: Query<(&Faction, Has<IsHuman>), With<Player>>,
+    areas: Query<&GameArea>,
+    tokens: Query<&Token>,
+) {
+    let confirmed: Vec<Entity> = confirmed.read().collect();
+    let confirmed_monotheism: Vec<Entity> = confirmed_monotheism.read().collect();
+    let Some(activity) = activity.filter(|_| recorder.is_recording()) else {
+        return;
+    };
+    let area_id = |area: Entity| areas.get(area).ok().map(|a| a.id);
+    let faction_of = |player: Entity| players.get(player).ok().map(|(f, _)| f.faction);
+    let owner_of = |token: Entity| faction_of(tokens.get(token).ok()?.player());
+    let choices = confirmed
+        .into_iter()
+        .map(|player| (player, selections.confirmed(player, area_id, faction_of)))
+        .chain(confirmed_monotheism.into_iter().map(|player| {
+            let choice = selections.confirmed_monotheism(player, area_id, owner_of);
+            (player, choice)
+        }));
+    for (player, choice) in choices {
+        let Ok((seat, true)) = players.get(player) else {
+            continue;
+        };
+        match choice {
+            Some(action) => recorder.record_command(
+                seat.faction,
+                game_info.round,
+                activity.get().clone(),
+                action,
+            ),
+            None => warn!(
+                "[REPLAY] {:?} confirmed a calamity selection that could not be recorded",
+                seat.faction
+            ),
+        }
+    }
+}
+
+/// The command writers a replay sends recorded seat commands through.
+#[derive(SystemParam)]
+pub struct ReplayWriters<'w> {
+    expand: MessageWriter<'w, ExpandPopulationManuallyCommand>,
+    move_tokens: MessageWriter<'w, MoveTokenFromAreaToAreaCommand>,
+    ferry: MessageWriter<'w, ShipFerryCommand>,
+    end_move: MessageWriter<'w, PlayerMovementEnded>,
+    build_city: MessageWriter<'w, BuildCityCommand>,
+    end_city: MessageWriter<'w, EndPlayerCityConstruction>,
+    eliminate_city: MessageWriter<'w, EliminateCity>,
+    purchase: MessageWriter<'w, ConfirmCivCardPurchase>,
+    done_civ: MessageWriter<'w, PlayerDoneAcquiringCivilizationCards>,
+}
+
+/// Sends `action` for `player`; `None` if the board has no such area or
+/// city.
+fn send_recorded(
+    writers: &mut ReplayWriters,
+    areas: &Query<(Entity, &GameArea, Option<&BuiltCity>)>,
+    player: Entity,
+    action: &ReplayAction,
+) -> Option<()> {
+    let area = |id: i32| {
+        areas
+            .iter()
+            .find_map(|(entity, area, _)| (area.id == id).then_some(entity))
+    };
+    match action {
+        ReplayAction::ExpandPopulation { area: id, tokens } => {
+            writers.expand.write(ExpandPopulationManuallyCommand::new(
+                player,
+                area(*id)?,
+                *tokens,
+            ));
+        }
+        ReplayAction::MoveTokens { from, to, tokens } => {
+            writers
+                .move_tokens
+                .write(MoveTokenFromAreaToAreaCommand::new(
+                    area(*from)?,
+                    area(*to)?,
+                    *tokens,
+                    player,
+                ));
+        }
+        ReplayAction::ShipFerry { from, to, tokens } => {
+            writers.ferry.write(ShipFerryCommand::new(
+                area(*from)?,
+                area(*to)?,
+                *tokens,
+                player,
+            ));
+        }
+        ReplayAction::EndMovement => {
+            writers.end_move.write(PlayerMovementEnded::new(player));
+        }
+        ReplayAction::BuildCity { area: id } => {
+            writers
+                .build_city
+                .write(BuildCityCommand::new(player, area(*id)?));
+        }
+        ReplayAction::EndCityConstruction => {
+            writers
+                .end_city
+                .write(EndPlayerCityConstruction::new(player));
+        }
+        ReplayAction::EliminateCity { area: id } => {
+            let (entity, _, city) = areas.iter().find(|(_, area, _)| area.id == *id)?;
+            writers
+                .eliminate_city
+                .write(EliminateCity::new(player, city?.city, entity, false));
+        }
+        ReplayAction::BuyCivCards { cards, payment } => {
+            writers.purchase.write(ConfirmCivCardPurchase {
+                player,
+                cards_to_buy: cards.clone(),
+                payment: payment.iter().copied().collect(),
+            });
+        }
+        ReplayAction::DoneAcquiringCivCards => {
+            writers
+                .done_civ
+                .write(PlayerDoneAcquiringCivilizationCards(player));
+        }
+        // Made on a panel rather than sent; see `play_recorded_calamity_choices`.
+        _ => return None,
+    }
+    Some(())
+}
+
+/// Sends the human and agent seats' recorded commands as their turn in the
+/// recording comes up.
+pub fn play_recorded_commands(
+    mut playback: ResMut<ReplayPlayback>,
+    game_info: Res<GameInfoAndStuff>,
+    activity: Option<Res<State<GameActivity>>>,
+    players: Query<(Entity, &Faction), With<Player>>,
+    areas: Query<(Entity, &GameArea, Option<&BuiltCity>)>,
+    mut writers: ReplayWriters,
+) {
+    let Some(activity) = activity else {
+        return;
+    };
+    loop {
+        // Calamity choices wait for their panel; see `play_recorded_calamity_choices`.
+        if playback
+            .peek_command()
+            .is_some_and(|c| c.action.is_calamity_choice())
+        {
+            break;
+        }
+        let Some(command) = playback.next_command(game_info.round, activity.get()) else {
+            break;
+        };
+        let player = players
+            .iter()
+            .find_map(|(player, f)| (f.faction == command.faction).then_some(player));
+        if player
+            .and_then(|player| send_recorded(&mut writers, &areas, player, &command.action))
+            .is_none()
+        {
+            warn!(
+                "[REPLAY] Cannot send {:?} for {:?}: not on this board",
+                command.action, command.faction
+            );
+        }
+    }
+}
+
+/// Makes a replayed seat's recorded calamity choice once the calamity asks
+/// that seat for it: the choice goes on the panel and the waiting marker
+/// comes off, as the panel's Confirm would. One choice a frame, so the
+/// calamity has taken it before the next one goes on a panel.
+#[allow(clippy::too_many_arguments)]
+pub fn play_recorded_calamity_choices(
+    mut commands: Commands,
+    mut playback: ResMut<ReplayPlayback>,
+    mut selections: CalamitySelections,
+    game_info: Res<GameInfoAndStuff>,
+    activity: Option<Res<State<GameActivity>>>,
+    players: Query<
+        (
+            Entity,
+            &Faction,
+            Has<AwaitingHumanCalamitySelection>,
+            Has<AwaitingMonotheismSelection>,
+        ),
+        With<Player>,
+    >,
+    areas: Query<(Entity, &GameArea)>,
+    tokens: Query<&Token>,
+) {
+    let Some(activity) = activity else {
+        return;
+    };
+    let Some(due) = playback
+        .peek_command()
+        .filter(|c| c.action.is_calamity_choice())
+        .cloned()
+    else {
+        return;
+    };
+    let seat = players
+        .iter()
+        .find(|(_, faction, ..)| faction.faction == due.faction);
+    let asked = seat.filter(|&(player, _, awaiting, awaiting_monotheism)| {
+        let waiting = match due.action {
+            ReplayAction::ChooseMonotheismTargets { .. } => awaiting_monotheism,
+            _ => awaiting,
+        };
+        waiting && selections.is_asking(player, &due.action)
+    });
+    let Some((player, ..)) = asked else {
+        playback.hold_command();
+        return;
+    };
+    let Some(command) = playback.next_command(game_info.round, activity.get()) else {
+        return;
+    };
+
+    let area = |id: i32| {
+        areas
+            .iter()
+            .find_map(|(entity, area)| (area.id == id).then_some(entity))
+    };
+    let player_of = |faction: GameFaction| {
+        players
+            .iter()
+            .find_map(|(player, f, ..)| (f.faction == faction).then_some(player))
+    };
+    let owner_of = |token: Entity| {
+        let owner = tokens.get(token).ok()?.player();
+        players.get(owner).ok().map(|(_, f, ..)| f.faction)
+    };
+    if selections
+        .choose(player, &command.action, area, player_of, owner_of)
+        .is_none()
+    {
+        warn!(
+            "[REPLAY] Cannot make {:?} for {:?}: not on this board",
+            command.action, command.faction
+        );
+    }
+    match command.action {
+        ReplayAction::ChooseMonotheismTargets { .. } => {
+            commands
+                .entity(player)
+                .remove::<AwaitingMonotheismSelection>();
+        }
+        _ => {
+            commands
+                .entity(player)
+                .remove::<AwaitingHumanCalamitySelection>();
+        }
+    }
+}
+
+pub fn record_trades(
+    mut recorder: ResMut<ReplayRecorder>,
+    mut sends: MessageReader<CommandApplied<SendTradingCardsCommand>>,
+    factions: Query<&Faction>,
+    game_info: Res<GameInfoAndStuff>,
+) {
+    for CommandApplied(send) in sends.read() {
+        if !recorder.is_recording() || send.cards_to_send.is_empty() {
+            continue;
+        }
+        if let (Ok(from), Ok(to)) = (
+            factions.get(send.sending_player),
+            factions.get(send.receiving_player),
+        ) {
+            recorder.record_trade(ReplayTrade {
+                round: game_info.round,
+                from: from.faction,
+                to: to.faction,
+                cards: send
+                    .cards_to_send
+                    .iter()
+                    .map(|(card, count)| (*card, *count))
+                    .collect(),
+            });
+        }
+    }
+}
+
+/// Runs in `PreUpdate`, when a phase change is pending: the previous frame is
+/// done and the transition hasn't happened yet, so the state is exactly the
+/// end-of-phase state, whatever order that phase's systems ran in. Recorded
+/// while recording, compared against the recording while replaying.
+pub fn take_checkpoint(
+    activity: Option<Res<State<GameActivity>>>,
+    next_activity: Option<Res<NextState<GameActivity>>>,
+    game_info: Res<GameInfoAndStuff>,
+    game_rng: Res<GameRng>,
+    mut recorder: ResMut<ReplayRecorder>,
+    playback: Option<ResMut<ReplayPlayback>>,
+    areas: AreaDigestQuery,
+    players: PlayerDigestQuery,
+) {
+    let (Some(activity), Some(next_activity)) = (activity, next_activity) else {
+        return;
+    };
+    let to = match next_activity.as_ref() {
+        NextState::Pending(to) | NextState::PendingIfNeq(to) => to,
+        NextState::Unchanged => return,
+    };
+    if to == activity.get() {
+        return;
+    }
+    if playback.is_none() && !recorder.is_recording() {
+        return;
+    }
+
+    let mut checkpoint = ReplayCheckpoint {
+        round: game_info.round,
+        from: activity.get().clone(),
+        to: to.clone(),
+        decisions: recorder.decisions(),
+        draws: game_rng.draws(),
+        digest: state_digest(&areas, &players),
+    };
+    if let Some(mut playback) = playback {
+        checkpoint.decisions = playback.decisions_played();
+        playback.verify_checkpoint(&checkpoint);
+    } else {
+        recorder.record_checkpoint(checkpoint);
+    }
+}
+
+/// Settle the round's recorded trades as soon as trading opens.
+pub fn apply_recorded_trades(
+    playback: Res<ReplayPlayback>,
+    game_info: Res<GameInfoAndStuff>,
+    players: Query<(Entity, &Faction), With<Player>>,
+    mut sends: MessageWriter<SendTradingCardsCommand>,
+) {
+    let player_of = |faction: GameFaction| {
+        players
+            .iter()
+            .find(|(_, f)| f.faction == faction)
+            .map(|(player, _)| player)
+    };
+    for trade in playback.trades_in_round(game_info.round) {
+        if let (Some(from), Some(to)) = (player_of(trade.from), player_of(trade.to)) {
+            sends.write(SendTradingCardsCommand::new(
+                from,
+                to,
+                trade.cards.iter().copied().collect(),
+            ));
+        }
+    }
+}
+
+/// With the recorded trades applied there's nothing left to negotiate; close
+/// the phase instead of waiting out the countdown.
+pub fn end_replayed_trading(mut commands: Commands, traders: Query<Entity, With<CanTrade>>) {
+    for player in &traders {
+        commands.entity(player).remove::<CanTrade>();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::ecs::system::RunSystemOnce;
+
+    fn fnv1a(text: &str) -> u64 {
+        let mut hasher = Fnv1a::new();
+        hasher.bytes(text.as_bytes());
+        hasher.0
+    }
+
+    #[test]
+    fn the_digest_hash_is_fnv_1a() {
+        assert_eq!(fnv1a(""), 0xcbf2_9ce4_8422_2325);
+        assert_eq!(fnv1a("a"), 0xaf63_dc4c_8601_ec8c);
+        assert_eq!(fnv1a("foobar"), 0x8594_4171_f739_67e8);
+    }
+
+    #[test]
+    fn a_recorded_loss_allocation_goes_back_on_the_panel() {
+        let mut world = World::new();
+        world.init_resource::<CalamitySelectionState>();
+        world.init_resource::<CivilWarSelectionState>();
+        world.init_resource::<FloodSelectionState>();
+        world.init_resource::<FamineSelectionState>();
+        world.init_resource::<EpidemicSelectionState>();
+        world.init_resource::<UnitLossSelectionState>();
+        world.init_resource::<MonotheismSelectionState>();
+        let chooser = world.spawn(Faction::new(GameFaction::Egypt)).id();
+        let victim = world.spawn(Faction::new(GameFaction::Crete)).id();
+        world
+            .resource_mut::<FamineSelectionState>()
+            .populate(chooser, vec![(victim, 5)], 5);
+        world.resource_mut::<FamineSelectionState>().victims[0].2 = 3;
+
+        let recorded = world
+            .run_system_once(
+                move |panels: CalamitySelections, factions: Query<&Faction>| {
+                    panels.confirmed(
+                        chooser,
+                        |_| None,
+                        |p| factions.get(p).ok().map(|f| f.faction),
+                    )
+                },
+            )
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            recorded,
+            ReplayAction::AllocateCalamityLoss {
+                victims: vec![(GameFaction::Crete, 3)]
+            }
+        );
+
+        world.resource_mut::<FamineSelectionState>().victims[0].2 = 0;
+        let chosen = world
+            .run_system_once(move |mut panels: CalamitySelections| {
+                assert!(panels.is_asking(chooser, &recorded));
+                let player_of = |f: GameFaction| (f == GameFaction::Crete).then_some(victim);
+                panels.choose(chooser, &recorded, |_| None, player_of, |_| None)
+            })
+            .unwrap();
+        assert_eq!(chosen, Some(()));
+        assert_eq!(
+            world.resource_mut::<FamineSelectionState>().take_result(),
+            vec![(victim, 3)]
+        );
+    }
+}
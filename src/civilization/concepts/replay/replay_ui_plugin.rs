@@ -0,0 +1,182 @@
+use crate::GameActivity;
+use crate::civilization::Z_PANEL;
+use crate::civilization::concepts::census::GameInfoAndStuff;
+use crate::civilization::concepts::replay::replay_resources::{ReplayControl, ReplayPlayback};
+use bevy::prelude::*;
+use bevy::ui_widgets::Activate;
+use lava_ui_builder::{LavaTheme, TextStyle, UIBuilder};
+
+#[derive(Component, Default)]
+pub struct ReplayPanel;
+
+#[derive(Component, Default)]
+pub struct ReplayStatusText;
+
+#[derive(Component, Default)]
+pub struct ReplayJumpText;
+
+/// Round the "Jump" button runs the replay to.
+#[derive(Resource, Default)]
+pub struct ReplayJumpTarget(pub usize);
+
+/// Step / run / jump controls for a `GAME_REPLAY` playback, in the top-right
+/// corner. Only spawned when a replay is loaded.
+pub struct ReplayUiPlugin;
+
+impl Plugin for ReplayUiPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ReplayJumpTarget>()
+            .add_systems(
+                OnEnter(GameActivity::StartGame),
+                spawn_replay_panel.run_if(resource_exists::<ReplayPlayback>),
+            )
+            .add_systems(
+                Update,
+                update_replay_panel.run_if(resource_exists::<ReplayPlayback>),
+            );
+    }
+}
+
+pub fn spawn_replay_panel(
+    commands: Commands,
+    theme: Res<LavaTheme>,
+    existing: Query<Entity, With<ReplayPanel>>,
+) {
+    if !existing.is_empty() {
+        return;
+    }
+    let mut ui = UIBuilder::new(commands, Some(theme.clone()));
+
+    ui.component::<ReplayPanel>()
+        .absolute_position()
+        .top(Val::Px(8.0))
+        .right(Val::Px(8.0))
+        .z_index(Z_PANEL)
+        .display_flex()
+        .flex_column()
+        .gap_px(4.0)
+        .padding_all_px(6.0)
+        .bg_color(Color::srgba(0.0, 0.0, 0.0, 0.9))
+        .border_radius_all_px(5.0);
+
+    ui.add_text_child("Replay", Some(TextStyle::size_color(14.0, Color::WHITE)));
+    ui.with_child(|c| {
+        c.component::<ReplayStatusText>()
+            .with_text("", Some(TextStyle::size(12.0)))
+            .width_px(260.0);
+    });
+
+    ui.add_row(|row| {
+        row.align_items_center().gap_px(4.0);
+        row.add_button_observe(
+            "Step",
+            |btn| {
+                btn.font_size(12.0);
+            },
+            |_: On<Activate>, mut playback: ResMut<ReplayPlayback>| {
+                playback.control = ReplayControl::Step;
+            },
+        );
+        row.add_button_observe(
+            "Pause",
+            |btn| {
+                btn.font_size(12.0);
+            },
+            |_: On<Activate>, mut playback: ResMut<ReplayPlayback>| {
+                playback.control = ReplayControl::Paused;
+            },
+        );
+        row.add_button_observe(
+            "Run to end",
+            |btn| {
+                btn.font_size(12.0);
+            },
+            |_: On<Activate>, mut playback: ResMut<ReplayPlayback>| {
+                playback.control = ReplayControl::RunToEnd;
+            },
+        );
+    });
+
+    // Jump row: [−] round N [+] [Jump]
+    ui.add_row(|row| {
+        row.align_items_center().gap_px(4.0);
+        row.add_button_observe(
+            "−",
+            |btn| {
+                btn.size_px(22.0, 22.0).font_size(12.0);
+            },
+            |_: On<Activate>, mut target: ResMut<ReplayJumpTarget>| {
+                target.0 = target.0.saturating_sub(1);
+            },
+        );
+        row.with_child(|c| {
+            c.component::<ReplayJumpText>()
+                .with_text("round 1", Some(TextStyle::size(12.0)))
+                .width_px(70.0);
+        });
+        row.add_button_observe(
+            "+",
+            |btn| {
+                btn.size_px(22.0, 22.0).font_size(12.0);
+            },
+            |_: On<Activate>, mut target: ResMut<ReplayJumpTarget>| {
+                target.0 += 1;
+            },
+        );
+        row.add_button_observe(
+            "Jump",
+            |btn| {
+                btn.font_size(12.0);
+            },
+            |_: On<Activate>,
+             target: Res<ReplayJumpTarget>,
+             mut playback: ResMut<ReplayPlayback>| {
+                playback.control = ReplayControl::RunToRound(target.0);
+            },
+        );
+    });
+
+    ui.build();
+}
+
+fn update_replay_panel(
+    playback: Res<ReplayPlayback>,
+    game_info: Res<GameInfoAndStuff>,
+    mut target: ResMut<ReplayJumpTarget>,
+    mut status_text: Query<&mut Text, (With<ReplayStatusText>, Without<ReplayJumpText>)>,
+    mut jump_text: Query<&mut Text, (With<ReplayJumpText>, Without<ReplayStatusText>)>,
+) {
+    // Never offer a jump backwards: a replay only runs forward.
+    if target.0 <= game_info.round {
+        target.0 = game_info.round + 1;
+    }
+    if let Ok(mut text) = jump_text.single_mut() {
+        text.0 = format!("round {}", target.0);
+    }
+
+    let Ok(mut text) = status_text.single_mut() else {
+        return;
+    };
+    let control = match playback.control {
+        ReplayControl::Paused => "paused".to_string(),
+        ReplayControl::Step => "stepping".to_string(),
+        ReplayControl::RunToRound(round) => format!("running to round {round}"),
+        ReplayControl::RunToEnd => "running".to_string(),
+    };
+    let mut status = format!(
+        "Round {} ({control})\nDecisions {}/{}\nCommands {}/{}\nCheckpoints {}/{}",
+        game_info.round,
+        playback.decisions_played(),
+        playback.file.decisions.len(),
+        playback.commands_played(),
+        playback.file.commands.len(),
+        playback.checkpoints_verified(),
+        playback.file.checkpoints.len(),
+    );
+    if let Some(divergence) = playback.divergence() {
+        status.push_str(&format!("\nDIVERGED: {divergence}"));
+    } else if playback.is_finished() {
+        status.push_str("\nReplay complete, no divergence");
+    }
+    text.0 = status;
+}
@@ -1,4 +1,6 @@
-use bevy::prelude::{Entity, Reflect};
+use crate::civilization::components::BarbarianToken;
+use crate::loading::TextureAssets;
+use bevy::prelude::{Color, Entity, Name, Reflect, Sprite, Transform, Vec3};
 
 /// State for Barbarian Hordes (rule 30.52).
 ///
@@ -94,6 +96,30 @@ impl BarbarianHordesState {
     }
 }
 
+/// The `i`th of a horde's tokens, laid out five to a row around `base` and
+/// drawn above player tokens (z = 0) and ships (z = 2). Used when the horde
+/// lands and when a save puts it back.
+pub fn barbarian_token(base: Vec3, i: usize) -> (BarbarianToken, Name, Transform) {
+    const COLS: usize = 5;
+    const SPACING: f32 = 10.0;
+    let col = (i % COLS) as f32;
+    let row = (i / COLS) as f32;
+    let offset = Vec3::new(col * SPACING - SPACING * 2.0, row * -SPACING + SPACING, 3.0);
+    (
+        BarbarianToken,
+        Name::new("Barbarian"),
+        Transform::from_scale(Vec3::splat(0.3)).with_translation(base + offset),
+    )
+}
+
+pub fn barbarian_sprite(textures: &TextureAssets) -> Sprite {
+    Sprite {
+        image: textures.dot.clone(),
+        color: Color::srgb(0.6, 0.1, 0.05),
+        ..Default::default()
+    }
+}
+
 /// Rule 30.5211/30.5234: "damage to the primary victim" in a candidate area.
 /// Tokens count 1 point each, an owned city counts 5 -- the same unit-point
 /// convention this codebase already uses elsewhere (e.g. taxation's revolt
@@ -201,8 +201,9 @@ impl CivilWarState {
             .collect();
     }
 
-    /// Rule 30.415 AI default: keep whichever faction has more total unit
-    /// points; keep the first faction on a tie.
+    /// Rule 30.415 plain default: keep whichever faction has more total unit
+    /// points; keep the first faction on a tie. AI victims weigh cities and
+    /// the next epoch gate as well, via `CalamityChooser::keep_faction`.
     pub fn default_ai_faction_choice(&self) -> FactionChoice {
         if self.second_faction_points() > self.first_faction_points() {
             FactionChoice::Second
@@ -1,5 +1,4 @@
 use bevy::prelude::*;
-use rand::rng;
 use rand::seq::SliceRandom;
 
 use crate::GameActivity;
@@ -8,9 +7,11 @@ use crate::civilization::concepts::civ_cards::PlayerCivilizationCards;
 use crate::civilization::concepts::conflict::{
     ConflictCounterResource, UnresolvedCityConflict, UnresolvedConflict,
 };
+use crate::civilization::concepts::game_rng::GameRng;
 use crate::civilization::concepts::resolve_calamities::calamities::ResolvingCalamity;
 use crate::civilization::concepts::resolve_calamities::calamities::barbarian_hordes::{
     BarbarianHordesPhase, BarbarianHordesState, MAX_CASCADE_ITERATIONS, barbarian_damage_score,
+    barbarian_sprite, barbarian_token,
 };
 use crate::civilization::concepts::resolve_calamities::calamities::civil_disorder::{
     CivilDisorderPhase, CivilDisorderState,
@@ -61,7 +62,7 @@ use crate::civilization::triggers::retire_city_token_visuals;
 use crate::civilization::{CivCardName, PlayerTradeCards, TradeCard, TradeCardTrait};
 use crate::loading::TextureAssets;
 use crate::player::Player;
-use crate::stupid_ai::IsHuman;
+use crate::stupid_ai::{CalamityChoosers, IsHuman, unit_points};
 
 /// Rule 30.312: Grain cards locked by a Famine/Pottery reduction stop being
 /// locked "the following turn" -- i.e. once the next round's Population
@@ -82,6 +83,7 @@ pub fn start_calamity_resolution(
         With<Player>,
     >,
     mut next_state: ResMut<NextState<GameActivity>>,
+    mut game_rng: ResMut<GameRng>,
 ) {
     info!("[CALAMITIES] Starting calamity resolution phase");
 
@@ -111,7 +113,7 @@ pub fn start_calamity_resolution(
         // implicated in it.
         let traded_by = |card: &TradeCard| trade_cards.calamity_traded_by(*card);
         let calamities_to_resolve: Vec<(TradeCard, Option<Entity>)> = if calamity_cards.len() > 2 {
-            select_random_calamities(&calamity_cards, 2)
+            select_random_calamities(&calamity_cards, 2, &mut game_rng)
                 .into_iter()
                 .map(|(card, _)| (card, traded_by(&card)))
                 .collect()
@@ -157,10 +159,10 @@ pub fn start_calamity_resolution(
 fn select_random_calamities(
     calamities: &[TradeCard],
     count: usize,
+    game_rng: &mut GameRng,
 ) -> Vec<(TradeCard, Option<Entity>)> {
-    let mut rng = rng();
     let mut shuffled: Vec<TradeCard> = calamities.to_vec();
-    shuffled.shuffle(&mut rng);
+    shuffled.shuffle(game_rng.rng());
     shuffled
         .into_iter()
         .take(count)
@@ -782,6 +784,7 @@ pub fn advance_flood(
     mut calamity_resolved: MessageWriter<CalamityResolved>,
     mut flood_selection: ResMut<FloodSelectionState>,
     mut calamity_selection: ResMut<CalamitySelectionState>,
+    choosers: CalamityChoosers,
 ) {
     for (player_entity, mut resolving, mut resolution, player_cities, is_human, is_awaiting) in
         &mut player_query
@@ -864,10 +867,11 @@ pub fn advance_flood(
                 // lose 10 pts. The primary victim divides that loss among them
                 // (allocate_secondary_loss); a human primary victim gets an
                 // interactive choice via FloodSelectionState (mirrors the
-                // Civil War selection pattern), AI falls back to an even
-                // split. If combined secondary availability is <=10 there's
-                // no decision to make at all -- everyone automatically loses
-                // everything, so the UI is skipped entirely in that case.
+                // Civil War selection pattern), an AI primary victim piles
+                // it on the victims its personality most wants hit. If
+                // combined secondary availability is <=10 there's no decision
+                // to make at all -- everyone automatically loses everything,
+                // so the UI is skipped entirely in that case.
                 let Some(fp_area) = state.flood_plain_area else {
                     state.phase = FloodPhase::Complete;
                     continue;
@@ -914,7 +918,10 @@ pub fn advance_flood(
                         continue; // selection resource is owned by a different player right now
                     }
                 } else {
-                    allocate_secondary_loss(&secondary_players, total_loss, None)
+                    let choice = choosers
+                        .for_player(player_entity)
+                        .divide_loss(&secondary_players, total_loss);
+                    allocate_secondary_loss(&secondary_players, total_loss, Some(&choice))
                 };
 
                 if let Ok(mut pop) = populations.get_mut(fp_area) {
@@ -1330,6 +1337,7 @@ pub fn advance_famine(
     mut calamity_resolved: MessageWriter<CalamityResolved>,
     mut famine_selection: ResMut<FamineSelectionState>,
     mut unit_loss: ResMut<UnitLossSelectionState>,
+    choosers: CalamityChoosers,
 ) {
     for (
         player_entity,
@@ -1389,10 +1397,11 @@ pub fn advance_famine(
                 // from any one of them. The primary victim divides that loss
                 // among them (allocate_famine_secondary_loss); a human primary
                 // victim gets an interactive choice via FamineSelectionState
-                // (mirrors Flood's rule-30.512 pattern), AI falls back to an
-                // even split. If combined secondary availability is <=20
-                // there's no decision to make at all -- everyone automatically
-                // loses everything, so the UI is skipped entirely in that case.
+                // (mirrors Flood's rule-30.512 pattern), an AI one lets its
+                // personality pick the victims. If combined secondary
+                // availability is <=20 there's no decision to make at all --
+                // everyone automatically loses everything, so the UI is
+                // skipped entirely in that case.
                 let primary_areas: bevy::platform::collections::HashSet<Entity> =
                     player_areas.areas().iter().copied().collect();
 
@@ -1445,7 +1454,10 @@ pub fn advance_famine(
                         continue; // selection resource is owned by a different player right now
                     }
                 } else {
-                    allocate_famine_secondary_loss(&secondary_players, total_loss, None)
+                    let choice = choosers
+                        .for_player(player_entity)
+                        .divide_loss(&secondary_players, total_loss);
+                    allocate_famine_secondary_loss(&secondary_players, total_loss, Some(&choice))
                 };
 
                 state.secondary_allocations =
@@ -1539,13 +1551,18 @@ enum BarbarianTieBreak {
 /// the player with the most units in stock decides."
 ///
 /// A single candidate is no tie at all and is returned immediately. An AI
-/// decider picks deterministically; a human decider is offered the tied
-/// areas on the selection panel.
+/// decider sends them where its personality most wants the damage (see
+/// `CalamityChooser::pick_area`); a human decider is offered the tied areas
+/// on the selection panel.
+#[allow(clippy::too_many_arguments)]
 fn break_barbarian_tie(
     tied: &[Entity],
     traded_by: Option<Entity>,
     stocks: &Query<(Entity, &TokenStock), With<Player>>,
     human_flags: &Query<(Has<IsHuman>, Has<AwaitingHumanCalamitySelection>), With<Player>>,
+    populations: &Query<&mut Population>,
+    city_query: &Query<&BuiltCity>,
+    choosers: &CalamityChoosers,
     calamity_selection: &mut CalamitySelectionState,
     commands: &mut Commands,
 ) -> BarbarianTieBreak {
@@ -1568,7 +1585,9 @@ fn break_barbarian_tie(
     };
     let (is_human, awaiting) = human_flags.get(decider).unwrap_or((false, false));
     if !is_human {
-        return BarbarianTieBreak::Chosen(first);
+        let occupants = barbarian_tie_occupants(tied, populations, city_query);
+        let picked = choosers.for_player(decider).pick_area(&occupants);
+        return BarbarianTieBreak::Chosen(picked.unwrap_or(first));
     }
 
     if awaiting {
@@ -1592,6 +1611,38 @@ fn break_barbarian_tie(
     BarbarianTieBreak::AwaitingHuman
 }
 
+/// Every player's unit points in each of the `tied` areas, owners in entity
+/// order so the weighing is deterministic.
+fn barbarian_tie_occupants(
+    tied: &[Entity],
+    populations: &Query<&mut Population>,
+    city_query: &Query<&BuiltCity>,
+) -> Vec<(Entity, Vec<(Entity, usize)>)> {
+    tied.iter()
+        .map(|&area| {
+            let city_owner = city_query.get(area).ok().map(|c| c.player);
+            let mut occupants: Vec<(Entity, usize)> = populations
+                .get(area)
+                .map(|pop| {
+                    pop.player_tokens()
+                        .iter()
+                        .map(|(&owner, tokens)| {
+                            (owner, unit_points(tokens.len(), city_owner == Some(owner)))
+                        })
+                        .collect()
+                })
+                .unwrap_or_default();
+            if let Some(owner) = city_owner
+                && !occupants.iter().any(|(o, _)| *o == owner)
+            {
+                occupants.push((owner, unit_points(0, true)));
+            }
+            occupants.sort_by_key(|(owner, _)| *owner);
+            (area, occupants)
+        })
+        .collect()
+}
+
 pub fn advance_barbarian_hordes(
     mut commands: Commands,
     mut player_query: Query<(
@@ -1615,6 +1666,7 @@ pub fn advance_barbarian_hordes(
     // just to satisfy this) can still run the real placement/conflict/
     // cascade logic; only the cosmetic Sprite is skipped without it.
     textures: Option<Res<TextureAssets>>,
+    choosers: CalamityChoosers,
     mut calamity_resolved: MessageWriter<CalamityResolved>,
 ) {
     for (player_entity, mut resolving, mut resolution, faction) in &mut player_query {
@@ -1678,6 +1730,9 @@ pub fn advance_barbarian_hordes(
                         resolution.context.traded_by,
                         &stocks,
                         &human_flags,
+                        &populations,
+                        &city_query,
+                        &choosers,
                         &mut calamity_selection,
                         &mut commands,
                     ) {
@@ -1704,8 +1759,6 @@ pub fn advance_barbarian_hordes(
                 // PlayerCities/TokenStock/PlayerAreas -- see the state
                 // struct's doc comment for why that's the point.
                 const TOKEN_COUNT: usize = 15;
-                const COLS: usize = 5;
-                const SPACING: f32 = 10.0;
 
                 let Some(landing) = state.landing_area else {
                     state.phase = BarbarianHordesPhase::Complete;
@@ -1722,25 +1775,9 @@ pub fn advance_barbarian_hordes(
 
                 if let Ok(mut pop) = populations.get_mut(landing) {
                     for i in 0..TOKEN_COUNT {
-                        let col = (i % COLS) as f32;
-                        let row = (i / COLS) as f32;
-                        let offset = bevy::math::Vec3::new(
-                            col * SPACING - SPACING * 2.0,
-                            row * -SPACING + SPACING,
-                            3.0, // above player tokens (z = 0) and ships (z = 2)
-                        );
-                        let mut entity_commands = commands.spawn((
-                            BarbarianToken,
-                            Name::new("Barbarian"),
-                            Transform::from_scale(bevy::math::Vec3::splat(0.3))
-                                .with_translation(base + offset),
-                        ));
+                        let mut entity_commands = commands.spawn(barbarian_token(base, i));
                         if let Some(textures) = &textures {
-                            entity_commands.insert(Sprite {
-                                image: textures.dot.clone(),
-                                color: bevy::prelude::Color::srgb(0.6, 0.1, 0.05),
-                                ..Default::default()
-                            });
+                            entity_commands.insert(barbarian_sprite(textures));
                         }
                         let token = entity_commands.id();
                         pop.add_token_to_area(barbarian_entity, token);
@@ -1873,6 +1910,9 @@ pub fn advance_barbarian_hordes(
                         resolution.context.traded_by,
                         &stocks,
                         &human_flags,
+                        &populations,
+                        &city_query,
+                        &choosers,
                         &mut calamity_selection,
                         &mut commands,
                     ) {
@@ -1963,6 +2003,7 @@ pub fn advance_epidemic(
     mut epidemic_selection: ResMut<EpidemicSelectionState>,
     mut unit_loss: ResMut<UnitLossSelectionState>,
     human_flags: Query<(Has<IsHuman>, Has<AwaitingHumanCalamitySelection>), With<Player>>,
+    choosers: CalamityChoosers,
 ) {
     for (
         player_entity,
@@ -2020,10 +2061,11 @@ pub fn advance_epidemic(
                 // victim collectively lose 25 pts, max 10 per player (5 with
                 // Medicine, rule 30.613). Immune player (trader) is exempt. A
                 // human primary victim divides the loss via EpidemicSelectionState
-                // (mirrors Flood's 30.512 pattern); AI falls back to a greedy
-                // fill in encounter order. If combined secondary caps are <=25
-                // there's no decision to make at all -- everyone automatically
-                // loses up to their own cap, so the UI is skipped entirely.
+                // (mirrors Flood's 30.512 pattern); an AI one fills the caps
+                // of the victims its personality most wants hit. If combined
+                // secondary caps are <=25 there's no decision to make at all
+                // -- everyone automatically loses up to their own cap, so the
+                // UI is skipped entirely.
                 let primary_areas: bevy::platform::collections::HashSet<Entity> =
                     player_areas.areas().iter().copied().collect();
 
@@ -2083,7 +2125,14 @@ pub fn advance_epidemic(
                             continue; // selection resource is owned by a different player right now
                         }
                     } else {
-                        allocate_epidemic_secondary_loss(&secondary_players, secondary_total, None)
+                        let choice = choosers
+                            .for_player(player_entity)
+                            .divide_loss(&secondary_players, secondary_total);
+                        allocate_epidemic_secondary_loss(
+                            &secondary_players,
+                            secondary_total,
+                            Some(&choice),
+                        )
                     };
 
                     state.secondary_allocations = allocation
@@ -2659,6 +2708,7 @@ pub fn advance_civil_war(
     all_players_civ_cards: Query<(Entity, &PlayerCivilizationCards), With<Player>>,
     human_query: Query<Entity, With<IsHuman>>,
     beneficiary_waiting: Query<Entity, With<AwaitingHumanCalamitySelection>>,
+    choosers: CalamityChoosers,
     mut calamity_resolved: MessageWriter<CalamityResolved>,
     mut cw_selection: ResMut<CivilWarSelectionState>,
 ) {
@@ -2919,7 +2969,15 @@ pub fn advance_civil_war(
                     state.kept_faction = Some(choice);
                     state.phase = CivilWarPhase::TransferFaction;
                 } else {
-                    let choice = state.default_ai_faction_choice();
+                    let first_cities = state.victim_selected_cities.len()
+                        + state.beneficiary_selected_cities.len();
+                    let choice = choosers.for_player(player_entity).keep_faction(
+                        (state.first_faction_points(), first_cities),
+                        (
+                            state.second_faction_points(),
+                            state.second_faction_cities.len(),
+                        ),
+                    );
                     info!("[CIVIL_WAR] AI victim keeps {:?} faction (30.415)", choice);
                     state.kept_faction = Some(choice);
                     state.phase = CivilWarPhase::TransferFaction;
@@ -3437,9 +3495,11 @@ fn finish_calamity(
 /// tokens from areas adjacent to any of their occupied areas. Theology holders
 /// are immune (rule 32.952).
 ///
-/// AI holders are auto-processed immediately. Human holders pause: `MonotheismSelectionState`
-/// is populated and `AwaitingMonotheismSelection` is inserted; the UI lets the human choose.
-/// Once all holders are done the system transitions to `CheckCitySupportAfterResolveCalamities`.
+/// AI holders pick at once, taking tokens from the owners their personality
+/// most wants weakened (`CalamityChooser::pick_tokens`). Human holders pause:
+/// `MonotheismSelectionState` is populated and `AwaitingMonotheismSelection`
+/// is inserted; the UI lets the human choose. Once all holders are done the
+/// system transitions to `CheckCitySupportAfterResolveCalamities`.
 pub fn apply_monotheism_conversions(
     mut commands: Commands,
     monotheism_holders: Query<(Entity, &PlayerAreas), With<NeedsMonotheismConversion>>,
@@ -3449,6 +3509,7 @@ pub fn apply_monotheism_conversions(
     all_players_civ: Query<(Entity, &PlayerCivilizationCards)>,
     land_passage_query: Query<&LandPassage>,
     population_query: Query<&Population>,
+    choosers: CalamityChoosers,
     mut mono_state: ResMut<MonotheismSelectionState>,
     mut next_state: ResMut<NextState<GameActivity>>,
 ) {
@@ -3480,9 +3541,10 @@ pub fn apply_monotheism_conversions(
         let is_human = human_query.get(holder_entity).is_ok();
         let is_waiting = awaiting_query.get(holder_entity).is_ok();
 
-        // Collect (token, area) candidates for this holder.
-        let mut candidates: Vec<(Entity, Entity)> = Vec::new();
-        'outer: for &held_area in &holder_areas.areas() {
+        // Collect every (token, area, owner) candidate for this holder; an
+        // area next to several held areas is only counted once.
+        let mut candidates: Vec<(Entity, Entity, Entity)> = Vec::new();
+        for &held_area in &holder_areas.areas() {
             if let Ok(passages) = land_passage_query.get(held_area) {
                 for &adj_area in &passages.to_areas {
                     if let Ok(pop) = population_query.get(adj_area) {
@@ -3493,9 +3555,8 @@ pub fn apply_monotheism_conversions(
                                 continue;
                             }
                             for &token in tokens {
-                                candidates.push((token, adj_area));
-                                if candidates.len() >= 2 {
-                                    break 'outer;
+                                if !candidates.iter().any(|(t, _, _)| *t == token) {
+                                    candidates.push((token, adj_area, enemy_player));
                                 }
                             }
                         }
@@ -3521,7 +3582,13 @@ pub fn apply_monotheism_conversions(
                     .remove::<NeedsMonotheismConversion>();
             } else if mono_state.player.is_none() {
                 // First time for this human holder: set up UI.
-                mono_state.populate(holder_entity, candidates);
+                mono_state.populate(
+                    holder_entity,
+                    candidates
+                        .iter()
+                        .map(|&(token, area, _)| (token, area))
+                        .collect(),
+                );
                 commands
                     .entity(holder_entity)
                     .insert(AwaitingMonotheismSelection);
@@ -3531,8 +3598,11 @@ pub fn apply_monotheism_conversions(
                 );
             }
         } else {
-            // AI: auto-select up to 2 candidates.
-            for (token, _) in candidates.into_iter().take(2) {
+            let by_owner: Vec<(Entity, Entity)> = candidates
+                .iter()
+                .map(|&(token, _, owner)| (token, owner))
+                .collect();
+            for token in choosers.for_player(holder_entity).pick_tokens(&by_owner, 2) {
                 commands.entity(token).insert(ReturnTokenToStock);
                 info!(
                     "[MONOTHEISM] {:?} eliminates token {:?}",
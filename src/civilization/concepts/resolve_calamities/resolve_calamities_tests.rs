@@ -509,6 +509,7 @@ mod tests {
 
         let mut world = World::new();
         world.init_resource::<NextState<GameActivity>>();
+        world.insert_resource(GameRng::from_seed(0));
 
         let mut cards = PlayerTradeCards::default();
         cards.add_trade_card(TradeCard::Famine);
@@ -552,6 +553,7 @@ mod tests {
 
         let mut world = World::new();
         world.init_resource::<NextState<GameActivity>>();
+        world.insert_resource(GameRng::from_seed(0));
 
         let mut cards = PlayerTradeCards::default();
         cards.add_trade_card(TradeCard::Famine);
@@ -1572,6 +1574,7 @@ mod tests {
 
         let mut world = World::new();
         world.init_resource::<NextState<GameActivity>>();
+        world.insert_resource(GameRng::from_seed(0));
 
         let trader = world
             .spawn((Player, Name::new("trader"), PlayerTradeCards::default()))
@@ -3310,7 +3313,7 @@ mod tests {
     };
     use crate::civilization::concepts::resolve_calamities::calamities::barbarian_hordes::BarbarianHordesState;
     use crate::civilization::concepts::resolve_calamities::resolve_calamities_systems::advance_barbarian_hordes;
-    use crate::civilization::{CameraFocusQueue, GameFaction};
+    use crate::civilization::{CameraFocusQueue, GameFaction, GameRng};
 
     /// Common resource wiring the conflict observers need to run at all,
     /// independent of the actual Conflict-phase state machinery (this test
@@ -3323,6 +3326,7 @@ mod tests {
         world.init_resource::<NextState<GameActivity>>();
         world.init_resource::<CameraFocusQueue>();
         world.init_resource::<crate::civilization::concepts::resolve_calamities::resolve_calamities_ui_components::CalamitySelectionState>();
+        world.insert_resource(GameRng::from_seed(0));
         world.add_observer(on_add_unresolved_conflict);
         world.add_observer(on_add_unresolved_city_conflict);
         world
@@ -3997,6 +4001,7 @@ mod tests {
         world.init_resource::<ConflictCounterResource>();
         world.init_resource::<NextState<GameActivity>>();
         world.init_resource::<CameraFocusQueue>();
+        world.insert_resource(GameRng::from_seed(0));
         world.add_observer(on_add_unresolved_conflict);
         world.add_observer(on_add_unresolved_city_conflict);
 
@@ -1,3 +1,11 @@
+mod save_browser;
+mod save_game_migrations;
+mod save_game_phase_state;
 mod save_game_plugin;
+mod save_slots;
 
+pub use save_browser::*;
+pub use save_game_migrations::*;
+pub use save_game_phase_state::*;
 pub use save_game_plugin::*;
+pub use save_slots::*;
@@ -0,0 +1,261 @@
+use crate::GameState;
+use crate::civilization::Z_DIALOG;
+use crate::civilization::concepts::save_game::save_game_plugin::LoadGameRequest;
+use crate::civilization::concepts::save_game::save_slots::{
+    SaveSlotInfo, SaveSlots, SlotKind, now_unix_secs,
+};
+use bevy::input::keyboard::{Key, KeyboardInput};
+use bevy::prelude::*;
+use bevy::ui_widgets::Activate;
+use lava_ui_builder::{LavaTheme, TextStyle, UIBuilder};
+
+/// Longest name a slot can be renamed to.
+const MAX_SLOT_NAME_LEN: usize = 40;
+
+/// Open (or rebuild) the load browser.
+#[derive(Message, Default)]
+pub struct OpenSaveBrowser;
+
+#[derive(Component, Default)]
+pub struct SaveBrowser;
+
+#[derive(Component, Default)]
+pub struct SlotRenameText;
+
+/// The slot being renamed and the name typed so far.
+#[derive(Resource, Debug)]
+pub struct SlotRename {
+    pub id: String,
+    pub name: String,
+}
+
+/// The main menu's "Load Game" screen: every save slot, newest first, with
+/// Load / Rename / Delete per slot.
+pub struct SaveBrowserPlugin;
+
+impl Plugin for SaveBrowserPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_message::<OpenSaveBrowser>()
+            .add_systems(
+                Update,
+                (spawn_save_browser, type_slot_name)
+                    .chain()
+                    .run_if(in_state(GameState::Menu)),
+            )
+            .add_systems(OnExit(GameState::Menu), close_save_browser);
+    }
+}
+
+fn spawn_save_browser(
+    mut commands: Commands,
+    mut open: MessageReader<OpenSaveBrowser>,
+    theme: Res<LavaTheme>,
+    slots: Res<SaveSlots>,
+    rename: Option<Res<SlotRename>>,
+    existing: Query<Entity, With<SaveBrowser>>,
+) {
+    if open.read().last().is_none() {
+        return;
+    }
+    for entity in existing.iter() {
+        commands.entity(entity).despawn();
+    }
+
+    let now = now_unix_secs();
+    let listed = slots.list();
+    let renaming = rename.map(|r| r.id.clone());
+    let mut ui = UIBuilder::new(commands, Some(theme.clone()));
+
+    ui.component::<SaveBrowser>()
+        .absolute_position()
+        .size_percent(100.0, 100.0)
+        .display_flex()
+        .flex_column()
+        .align_items_center()
+        .justify_center()
+        .z_index(Z_DIALOG)
+        .bg_color(Color::srgba(0.0, 0.0, 0.0, 0.9));
+
+    ui.add_panel(|panel| {
+        panel
+            .display_flex()
+            .flex_column()
+            .size_scaled(70., 80.)
+            .padding_all_px(10.0)
+            .row_gap_px(6.0)
+            .bg_color(Color::srgba(0.1, 0.1, 0.1, 0.95))
+            .border_radius_all_px(6.0);
+
+        panel.add_text_child("Load Game", Some(TextStyle::size(32.0)));
+        if listed.is_empty() {
+            panel.add_text_child("No saved games yet.", Some(TextStyle::size(16.0)));
+        }
+
+        panel.with_child(|list| {
+            list.display_flex()
+                .flex_column()
+                .row_gap_px(4.0)
+                .height(percent(100.))
+                .overflow_scroll_y();
+            list.foreach_child(listed.iter(), |row, slot| {
+                slot_row(
+                    row,
+                    slot,
+                    now,
+                    renaming.as_deref() == Some(slot.id.as_str()),
+                );
+            });
+        });
+
+        panel.add_button_observe(
+            "Back",
+            |btn| {
+                btn.size(px(200.0), px(40.0));
+            },
+            |_: On<Activate>, mut commands: Commands, browser: Query<Entity, With<SaveBrowser>>| {
+                commands.remove_resource::<SlotRename>();
+                for entity in browser.iter() {
+                    commands.entity(entity).despawn();
+                }
+            },
+        );
+    });
+
+    ui.build();
+}
+
+fn slot_row(row: &mut UIBuilder, slot: &SaveSlotInfo, now: u64, renaming: bool) {
+    row.display_flex()
+        .flex_row()
+        .align_items_center()
+        .gap_px(8.0)
+        .padding_all_px(4.0)
+        .bg_color(Color::srgba(0.18, 0.18, 0.2, 0.9))
+        .border_radius_all_px(4.0);
+
+    row.with_child(|text| {
+        text.display_flex().flex_column().width(percent(70.));
+        if renaming {
+            text.with_child(|name| {
+                name.component::<SlotRenameText>()
+                    .with_text(format!("{}_", slot.name), Some(TextStyle::size(16.0)));
+            });
+            text.add_text_child(
+                "Type a new name, Enter to keep it, Esc to cancel",
+                Some(TextStyle::size(12.0)),
+            );
+        } else {
+            let kind = match slot.kind {
+                SlotKind::Named => "",
+                SlotKind::Quicksave => " [quicksave]",
+                SlotKind::Autosave => " [autosave]",
+            };
+            text.add_text_child(
+                format!("{}{kind} ({})", slot.name, slot.age(now)),
+                Some(TextStyle::size(16.0)),
+            );
+            text.add_text_child(slot.summary.clone(), Some(TextStyle::size(12.0)));
+        }
+    });
+
+    let load_id = slot.id.clone();
+    row.add_button_observe(
+        "Load",
+        |btn| {
+            btn.font_size(12.0);
+        },
+        move |_: On<Activate>, mut writer: MessageWriter<LoadGameRequest>| {
+            writer.write(LoadGameRequest::Slot(load_id.clone()));
+        },
+    );
+
+    let rename_id = slot.id.clone();
+    let current_name = slot.name.clone();
+    row.add_button_observe(
+        "Rename",
+        |btn| {
+            btn.font_size(12.0);
+        },
+        move |_: On<Activate>, mut commands: Commands, mut open: MessageWriter<OpenSaveBrowser>| {
+            commands.insert_resource(SlotRename {
+                id: rename_id.clone(),
+                name: current_name.clone(),
+            });
+            open.write(OpenSaveBrowser);
+        },
+    );
+
+    let delete_id = slot.id.clone();
+    row.add_button_observe(
+        "Delete",
+        |btn| {
+            btn.font_size(12.0);
+        },
+        move |_: On<Activate>, slots: Res<SaveSlots>, mut open: MessageWriter<OpenSaveBrowser>| {
+            match slots.delete(&delete_id) {
+                Ok(()) => info!("Deleted save slot {}", delete_id),
+                Err(e) => error!("Failed to delete save slot {}: {}", delete_id, e),
+            }
+            open.write(OpenSaveBrowser);
+        },
+    );
+}
+
+/// Keyboard entry for the slot being renamed.
+fn type_slot_name(
+    mut commands: Commands,
+    mut keys: MessageReader<KeyboardInput>,
+    rename: Option<ResMut<SlotRename>>,
+    slots: Res<SaveSlots>,
+    mut open: MessageWriter<OpenSaveBrowser>,
+    mut text: Query<&mut Text, With<SlotRenameText>>,
+) {
+    let Some(mut rename) = rename else {
+        keys.clear();
+        return;
+    };
+    for key in keys.read().filter(|k| k.state.is_pressed()) {
+        match &key.logical_key {
+            Key::Enter => {
+                let name = rename.name.trim();
+                if !name.is_empty()
+                    && let Err(e) = slots.rename(&rename.id, name)
+                {
+                    error!("Failed to rename save slot {}: {}", rename.id, e);
+                }
+                commands.remove_resource::<SlotRename>();
+                open.write(OpenSaveBrowser);
+                return;
+            }
+            Key::Escape => {
+                commands.remove_resource::<SlotRename>();
+                open.write(OpenSaveBrowser);
+                return;
+            }
+            Key::Backspace => {
+                rename.name.pop();
+            }
+            _ => {
+                if let Some(typed) = &key.text
+                    && rename.name.chars().count() < MAX_SLOT_NAME_LEN
+                {
+                    rename
+                        .name
+                        .extend(typed.chars().filter(|c| !c.is_control()));
+                }
+            }
+        }
+    }
+    if rename.is_changed()
+        && let Ok(mut text) = text.single_mut()
+    {
+        text.0 = format!("{}_", rename.name);
+    }
+}
+
+fn close_save_browser(mut commands: Commands, browser: Query<Entity, With<SaveBrowser>>) {
+    commands.remove_resource::<SlotRename>();
+    for entity in browser.iter() {
+        commands.entity(entity).despawn();
+    }
+}
@@ -0,0 +1,243 @@
+use crate::civilization::concepts::save_game::save_game_plugin::GameSaveData;
+use serde_json::{Map, Value, json};
+
+/// The save format this build writes. Bump it whenever `GameSaveData` (or
+/// anything inside it) changes shape, and register a [`SaveMigration`] from
+/// the previous version in [`SAVE_MIGRATIONS`].
+pub const SAVE_GAME_VERSION: &str = "0.0.5";
+
+/// One step in the save format's history: rewrites a save written as `from`
+/// into the JSON shape `to` expects.
+pub struct SaveMigration {
+    pub from: &'static str,
+    pub to: &'static str,
+    pub migrate: fn(&mut Map<String, Value>) -> Result<(), String>,
+}
+
+/// Every format change, oldest first. Loading walks this from the file's
+/// version up to [`SAVE_GAME_VERSION`], so a save from any listed version
+/// still loads.
+pub const SAVE_MIGRATIONS: &[SaveMigration] = &[
+    SaveMigration {
+        from: "0.0.2",
+        to: "0.0.3",
+        migrate: spell_out_serde_defaults,
+    },
+    SaveMigration {
+        from: "0.0.3",
+        to: "0.0.4",
+        migrate: add_phase_state,
+    },
+    SaveMigration {
+        from: "0.0.4",
+        to: "0.0.5",
+        migrate: add_barbarian_horde,
+    },
+];
+
+/// Parse a save file, bringing it up to [`SAVE_GAME_VERSION`] first.
+///
+/// Fails with a message fit for the log when the file was written by a newer
+/// build, or by an older one no migration reaches back to.
+pub fn load_save_json(json: &str) -> Result<GameSaveData, String> {
+    let value: Value =
+        serde_json::from_str(json).map_err(|e| format!("Save file is not valid JSON: {e}"))?;
+    let value = migrate_save(value)?;
+    serde_json::from_value(value).map_err(|e| format!("Failed to parse save file: {e}"))
+}
+
+/// Run every migration between the save's own version and
+/// [`SAVE_GAME_VERSION`], in order.
+pub fn migrate_save(mut value: Value) -> Result<Value, String> {
+    let Some(save) = value.as_object_mut() else {
+        return Err("Save file is not a JSON object".to_string());
+    };
+    let mut version = save
+        .get("version")
+        .and_then(Value::as_str)
+        .ok_or("Save file has no version")?
+        .to_string();
+
+    if is_newer(&version, SAVE_GAME_VERSION) {
+        return Err(format!(
+            "Save file is from a newer version of the game (save format {version}, this build \
+             reads up to {SAVE_GAME_VERSION}). Update the game to load it."
+        ));
+    }
+
+    while version != SAVE_GAME_VERSION {
+        let Some(step) = SAVE_MIGRATIONS.iter().find(|m| m.from == version) else {
+            return Err(format!(
+                "Save file format {version} is too old to load (this build reads {} up to \
+                 {SAVE_GAME_VERSION}).",
+                SAVE_MIGRATIONS
+                    .first()
+                    .map_or(SAVE_GAME_VERSION, |m| m.from)
+            ));
+        };
+        (step.migrate)(save).map_err(|e| {
+            format!(
+                "Failed to migrate save file from {} to {}: {e}",
+                step.from, step.to
+            )
+        })?;
+        version = step.to.to_string();
+        save.insert("version".to_string(), Value::from(step.to));
+    }
+    Ok(value)
+}
+
+/// Whether dotted version `a` is later than `b` ("0.0.10" > "0.0.9").
+fn is_newer(a: &str, b: &str) -> bool {
+    let parts = |v: &str| {
+        v.split('.')
+            .map(|p| p.parse::<u64>().unwrap_or(0))
+            .collect::<Vec<_>>()
+    };
+    parts(a) > parts(b)
+}
+
+/// 0.0.2 -> 0.0.3: fields added to 0.0.2 without a version bump were left out
+/// of older files and filled in by `#[serde(default)]`. Write them out, so
+/// the structs no longer need the defaults.
+fn spell_out_serde_defaults(save: &mut Map<String, Value>) -> Result<(), String> {
+    fill(save, "census_order", json!([]));
+    fill(save, "left_to_move", json!([]));
+    fill(save, "current_mover", Value::Null);
+
+    for player in objects_in(save, "players")? {
+        fill(player, "done_with_current_activity", json!(false));
+        fill(player, "ast_space", json!(0));
+        fill(player, "owned_civ_cards", json!([]));
+        fill(player, "calamity_traded_by", json!([]));
+    }
+    for area in objects_in(save, "area_populations")? {
+        fill(area, "city_is_pirate", json!(false));
+    }
+    Ok(())
+}
+
+/// 0.0.3 -> 0.0.4: saves gained `phase_state`. Older files carried none, so
+/// they load with no round limit, no open trades, no calamity mid-resolution
+/// and a freshly shuffled trade card deck -- exactly what loading them did
+/// before.
+fn add_phase_state(save: &mut Map<String, Value>) -> Result<(), String> {
+    fill(
+        save,
+        "phase_state",
+        json!({
+            "round_limit": null,
+            "game_result": null,
+            "trade_card_piles": [],
+            "in_flight": {"entities": [], "trade_offers": 0, "components": []}
+        }),
+    );
+    Ok(())
+}
+
+/// 0.0.4 -> 0.0.5: the phase state gained the Barbarian horde. A 0.0.4 file
+/// could not be written while one was on the board, so it has none.
+fn add_barbarian_horde(save: &mut Map<String, Value>) -> Result<(), String> {
+    let in_flight = save
+        .get_mut("phase_state")
+        .and_then(|state| state.get_mut("in_flight"))
+        .and_then(Value::as_object_mut)
+        .ok_or("`phase_state.in_flight` is missing or not an object")?;
+    fill(in_flight, "barbarian_horde", Value::Null);
+    Ok(())
+}
+
+fn fill(object: &mut Map<String, Value>, key: &str, value: Value) {
+    object.entry(key).or_insert(value);
+}
+
+fn objects_in<'a>(
+    save: &'a mut Map<String, Value>,
+    key: &str,
+) -> Result<impl Iterator<Item = &'a mut Map<String, Value>>, String> {
+    let list = save
+        .get_mut(key)
+        .and_then(Value::as_array_mut)
+        .ok_or_else(|| format!("`{key}` is missing or not a list"))?;
+    Ok(list.iter_mut().filter_map(Value::as_object_mut))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::civilization::concepts::save_game::SavedPhaseState;
+    use crate::civilization::enums::GameFaction;
+
+    const SAVE_0_0_2: &str = r#"{
+        "version": "0.0.2",
+        "round": 3,
+        "game_activity": "Movement",
+        "players": [{
+            "name": "Old Timer",
+            "faction": "Egypt",
+            "is_human": true,
+            "census_population": 12,
+            "treasury": 4,
+            "tokens_in_stock": 30,
+            "city_tokens_in_stock": 8,
+            "trade_cards": []
+        }],
+        "area_populations": [{"area_id": 7, "tokens_by_faction": [], "city_owner": null}]
+    }"#;
+
+    #[test]
+    fn the_migrations_chain_up_to_the_current_version() {
+        let mut version = SAVE_MIGRATIONS[0].from;
+        for step in SAVE_MIGRATIONS {
+            assert_eq!(step.from, version, "a gap in the migration chain");
+            assert!(is_newer(step.to, step.from));
+            version = step.to;
+        }
+        assert_eq!(version, SAVE_GAME_VERSION);
+    }
+
+    #[test]
+    fn a_0_0_2_save_loads_with_its_missing_fields_filled_in() {
+        let save = load_save_json(SAVE_0_0_2).expect("0.0.2 saves remain loadable");
+        assert_eq!(save.version, SAVE_GAME_VERSION);
+        assert_eq!(save.round, 3);
+        let player = &save.players[0];
+        assert_eq!(player.faction, GameFaction::Egypt);
+        assert_eq!(player.treasury, 4);
+        assert!(!player.done_with_current_activity);
+        assert_eq!(player.ast_space, 0);
+        assert!(player.owned_civ_cards.is_empty());
+        assert!(!save.area_populations[0].city_is_pirate);
+        assert!(save.current_mover.is_none());
+        assert_eq!(save.phase_state, SavedPhaseState::default());
+    }
+
+    #[test]
+    fn a_current_save_passes_through_untouched() {
+        let migrated = migrate_save(serde_json::from_str(SAVE_0_0_2).unwrap()).unwrap();
+        let again = migrate_save(migrated.clone()).unwrap();
+        assert_eq!(migrated, again);
+    }
+
+    #[test]
+    fn a_save_from_a_newer_build_is_rejected() {
+        let json = SAVE_0_0_2.replace("0.0.2", "0.1.0");
+        let error = load_save_json(&json).unwrap_err();
+        assert!(error.contains("newer version"), "{error}");
+        assert!(error.contains("0.1.0"), "{error}");
+    }
+
+    #[test]
+    fn a_save_older_than_every_migration_is_rejected() {
+        let json = SAVE_0_0_2.replace("0.0.2", "0.0.1");
+        let error = load_save_json(&json).unwrap_err();
+        assert!(error.contains("too old"), "{error}");
+    }
+
+    #[test]
+    fn versions_compare_numerically() {
+        assert!(is_newer("0.0.10", "0.0.9"));
+        assert!(is_newer("0.1.0", "0.0.3"));
+        assert!(!is_newer("0.0.3", "0.0.3"));
+    }
+}
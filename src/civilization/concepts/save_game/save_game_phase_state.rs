@@ -0,0 +1,964 @@
+use std::any::TypeId;
+
+use bevy::platform::collections::{HashMap, HashSet};
+use bevy::prelude::*;
+use bevy::reflect::serde::{TypedReflectDeserializer, TypedReflectSerializer};
+use bevy::reflect::{PartialReflect, ReflectMut};
+use serde::de::DeserializeSeed;
+use serde::{Deserialize, Serialize};
+
+use crate::GameActivity;
+use crate::civilization::components::*;
+use crate::civilization::concepts::resolve_calamities::calamities::ResolvingCalamity;
+use crate::civilization::concepts::resolve_calamities::calamities::barbarian_hordes::{
+    BarbarianHordesState, barbarian_sprite, barbarian_token,
+};
+use crate::civilization::concepts::resolve_calamities::context::ActiveCalamityResolution;
+use crate::civilization::concepts::resolve_calamities::resolve_calamities_components::{
+    CalamityVictim, GrainLockedForPurchase, NeedsCalamityResolution, NeedsMonotheismConversion,
+    PendingCalamities, PirateNation,
+};
+use crate::civilization::enums::GameFaction;
+use crate::civilization::{
+    CanTrade, CivilizationTradeCards, GameResult, InSettlement, OpenTradeOffer, PlayerSettlements,
+    PlayerTradeInterests, PublishedOffer, RoundLimit, TradeCard, TradeOffer,
+};
+use crate::loading::TextureAssets;
+use crate::player::Player;
+
+/// Everything a phase can be holding mid-way through that the rest of
+/// [`GameSaveData`](super::GameSaveData) does not cover: the game-wide
+/// resources, plus the calamity and trade components that only exist while
+/// those phases run.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct SavedPhaseState {
+    /// Rule 34.1B's predetermined round limit, if one was set.
+    pub round_limit: Option<usize>,
+    /// Final standings, once the game is over.
+    pub game_result: Option<Vec<(String, u32, u32)>>,
+    /// The trade card stacks still to be drawn, top of each stack last.
+    /// Empty means "not recorded": the load keeps its freshly shuffled deck.
+    pub trade_card_piles: Vec<(usize, Vec<TradeCard>)>,
+    pub in_flight: SavedInFlight,
+}
+
+/// An entity a save can name. Entity ids do not survive a load, so every
+/// entity reference inside a saved component is stored as one of these.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+pub enum SavedEntity {
+    Player(GameFaction),
+    PirateNation,
+    Area(i32),
+    /// The city token standing in the area.
+    City {
+        area_id: i32,
+    },
+    /// Tokens of one owner in one area are interchangeable; `nth` only has
+    /// to tell them apart. `owner: None` is the Pirate nation.
+    Token {
+        area_id: i32,
+        owner: Option<GameFaction>,
+        nth: usize,
+    },
+    /// An open or published trade offer, numbered within the save.
+    TradeOffer(usize),
+    /// The owner of a Barbarian horde that is still resolving (rule 30.52).
+    BarbarianHorde,
+    /// The `nth` token of that horde's `BarbarianHordesState::all_tokens`.
+    BarbarianToken(usize),
+}
+
+/// Components that hold a phase's progress, reflected off the entities that
+/// carry them.
+///
+/// Entity references inside `value` are placeholders: `Entity` number `i`
+/// stands for `entities[i]`.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct SavedInFlight {
+    pub entities: Vec<SavedEntity>,
+    pub trade_offers: usize,
+    pub components: Vec<SavedComponent>,
+    /// The Barbarian horde, while one is on the board. Its tokens belong to
+    /// no player, so the board itself does not record them.
+    pub barbarian_horde: Option<SavedBarbarianHorde>,
+}
+
+/// Where each token of a horde stands, in the order of its
+/// `BarbarianHordesState::all_tokens`: the area id, or `None` once a
+/// conflict has eliminated it.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct SavedBarbarianHorde {
+    pub tokens: Vec<Option<i32>>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct SavedComponent {
+    pub on: SavedEntity,
+    pub type_path: String,
+    pub value: serde_json::Value,
+}
+
+/// The components [`SavedInFlight`] carries. Each must be registered with
+/// `#[reflect(Component)]`.
+///
+/// `AwaitingHumanCalamitySelection` and `AwaitingMonotheismSelection` are
+/// left out on purpose: the selection panels they wait on are UI state, so a
+/// reload asks the human again rather than waiting on a panel that is gone.
+fn in_flight_components() -> [TypeId; 14] {
+    [
+        TypeId::of::<NeedsCalamityResolution>(),
+        TypeId::of::<PendingCalamities>(),
+        TypeId::of::<CalamityVictim>(),
+        TypeId::of::<ActiveCalamityResolution>(),
+        TypeId::of::<ResolvingCalamity>(),
+        TypeId::of::<NeedsMonotheismConversion>(),
+        TypeId::of::<GrainLockedForPurchase>(),
+        TypeId::of::<CanTrade>(),
+        TypeId::of::<PlayerTradeInterests>(),
+        TypeId::of::<PlayerSettlements>(),
+        TypeId::of::<OpenTradeOffer>(),
+        TypeId::of::<TradeOffer>(),
+        TypeId::of::<PublishedOffer>(),
+        TypeId::of::<InSettlement>(),
+    ]
+}
+
+/// The phase state to put back once the board has been rebuilt, consumed by
+/// [`restore_phase_state`].
+#[derive(Resource)]
+pub struct PendingPhaseRestore {
+    pub activity: GameActivity,
+    pub state: SavedPhaseState,
+}
+
+/// Snapshot of everything [`SavedPhaseState`] covers.
+///
+/// Fails if a component points at an entity a save cannot name.
+pub fn capture_phase_state(world: &World) -> Result<SavedPhaseState, String> {
+    let mut trade_card_piles: Vec<(usize, Vec<TradeCard>)> = world
+        .get_resource::<CivilizationTradeCards>()
+        .map(|deck| {
+            deck.card_piles
+                .iter()
+                .map(|(value, pile)| (*value, pile.clone()))
+                .collect()
+        })
+        .unwrap_or_default();
+    trade_card_piles.sort_by_key(|(value, _)| *value);
+
+    Ok(SavedPhaseState {
+        round_limit: world.get_resource::<RoundLimit>().and_then(|limit| limit.0),
+        game_result: world
+            .get_resource::<GameResult>()
+            .map(|result| result.standings.clone()),
+        trade_card_piles,
+        in_flight: capture_in_flight(world)?,
+    })
+}
+
+/// Put a captured [`SavedPhaseState`] back into `world`, replacing whatever
+/// the phase's own `OnEnter` systems set up.
+pub fn apply_phase_state(world: &mut World, state: &SavedPhaseState) -> Result<(), String> {
+    world.insert_resource(RoundLimit(state.round_limit));
+    if let Some(standings) = &state.game_result {
+        world.insert_resource(GameResult {
+            standings: standings.clone(),
+        });
+    }
+    if !state.trade_card_piles.is_empty() {
+        world.insert_resource(CivilizationTradeCards {
+            card_piles: state.trade_card_piles.iter().cloned().collect(),
+        });
+    }
+    restore_in_flight(world, &state.in_flight)
+}
+
+/// Where the load has to wait for before restoring: Trade and Resolve
+/// Calamities set their state up on entry, so the saved state goes on top of
+/// that. Every other phase's state only needs the board.
+fn restores_on(activity: &GameActivity) -> GameActivity {
+    match activity {
+        GameActivity::Trade | GameActivity::ResolveCalamities => activity.clone(),
+        _ => GameActivity::StartGame,
+    }
+}
+
+/// Scheduled on entering StartGame, Trade and ResolveCalamities; acts on
+/// whichever of those the loaded save is waiting for.
+pub fn restore_phase_state(world: &mut World) {
+    let Some(pending) = world.get_resource::<PendingPhaseRestore>() else {
+        return;
+    };
+    let current = world
+        .get_resource::<State<GameActivity>>()
+        .map(|state| state.get().clone());
+    if current != Some(restores_on(&pending.activity)) {
+        return;
+    }
+    let Some(pending) = world.remove_resource::<PendingPhaseRestore>() else {
+        return;
+    };
+
+    match apply_phase_state(world, &pending.state) {
+        Ok(()) => info!(
+            "Restored {:?} phase state: {} components, {} trade offers",
+            pending.activity,
+            pending.state.in_flight.components.len(),
+            pending.state.in_flight.trade_offers
+        ),
+        Err(e) => error!(
+            "Failed to restore {:?} phase state: {}",
+            pending.activity, e
+        ),
+    }
+
+    // setup_trade / start_calamity_resolution skip their phase when nobody
+    // has anything to do, which a saved mid-phase state can look like.
+    if restores_on(&pending.activity) != GameActivity::StartGame
+        && let Some(mut next_state) = world.get_resource_mut::<NextState<GameActivity>>()
+    {
+        next_state.reset();
+    }
+}
+
+fn capture_in_flight(world: &World) -> Result<SavedInFlight, String> {
+    let names = name_entities(world);
+    let registry = world.resource::<AppTypeRegistry>().read();
+
+    let mut holders: Vec<(Entity, SavedEntity)> = names
+        .iter()
+        .filter(|(_, name)| {
+            matches!(
+                name,
+                SavedEntity::Player(_) | SavedEntity::PirateNation | SavedEntity::TradeOffer(_)
+            )
+        })
+        .map(|(entity, name)| (*entity, name.clone()))
+        .collect();
+    holders.sort_by_cached_key(|(_, name)| format!("{name:?}"));
+
+    // Copy the components out first, so every entity they mention is known
+    // before placeholders are handed out -- numbering them in a fixed order
+    // keeps the same game state producing the same save.
+    let mut copies = Vec::new();
+    let mut mentioned: HashSet<Entity> = HashSet::default();
+    for (entity, on) in holders {
+        let entity_ref = world.entity(entity);
+        for type_id in in_flight_components() {
+            let Some(registration) = registry.get(type_id) else {
+                continue;
+            };
+            let Some(reflect_component) = registration.data::<ReflectComponent>() else {
+                continue;
+            };
+            let Some(component) = reflect_component.reflect(entity_ref) else {
+                continue;
+            };
+            let type_path = registration.type_info().type_path();
+            let mut copy = component
+                .reflect_clone()
+                .map_err(|e| format!("cannot copy {type_path}: {e}"))?;
+            let _ = map_entities(copy.as_partial_reflect_mut(), &mut |e| {
+                mentioned.insert(e);
+                Some(e)
+            });
+            copies.push((on.clone(), type_id, copy));
+        }
+    }
+
+    let mut named: Vec<(SavedEntity, Entity)> = mentioned
+        .into_iter()
+        .map(|entity| {
+            names
+                .get(&entity)
+                .map(|name| (name.clone(), entity))
+                .ok_or_else(|| format!("phase state refers to {entity}, which a save cannot name"))
+        })
+        .collect::<Result<_, _>>()?;
+    named.sort_by_cached_key(|(name, _)| format!("{name:?}"));
+    let placeholders: HashMap<Entity, Entity> = named
+        .iter()
+        .enumerate()
+        .filter_map(|(n, (_, entity))| Some((*entity, Entity::from_raw_u32(n as u32)?)))
+        .collect();
+    let entities: Vec<SavedEntity> = named.into_iter().map(|(name, _)| name).collect();
+
+    let mut components = Vec::new();
+    for (on, type_id, mut copy) in copies {
+        let registration = registry.get(type_id).expect("registered above");
+        let type_path = registration.type_info().type_path();
+        map_entities(copy.as_partial_reflect_mut(), &mut |e| {
+            placeholders.get(&e).copied()
+        })
+        .map_err(|e| format!("{type_path} refers to {e}, which a save cannot name"))?;
+        let value = serde_json::to_value(TypedReflectSerializer::new(
+            copy.as_partial_reflect(),
+            &registry,
+        ))
+        .map_err(|e| format!("cannot serialize {type_path}: {e}"))?;
+        components.push(SavedComponent {
+            on,
+            type_path: type_path.to_string(),
+            value,
+        });
+    }
+
+    let trade_offers = names
+        .values()
+        .filter(|name| matches!(name, SavedEntity::TradeOffer(_)))
+        .count();
+    Ok(SavedInFlight {
+        entities,
+        trade_offers,
+        components,
+        barbarian_horde: capture_barbarian_horde(world),
+    })
+}
+
+/// The owner and tokens of the Barbarian horde on the board, if any.
+fn barbarian_horde(world: &World) -> Option<(Entity, Vec<Entity>)> {
+    let mut resolving = world.try_query::<&ResolvingCalamity>()?;
+    resolving.iter(world).find_map(|calamity| match calamity {
+        ResolvingCalamity::BarbarianHordes(BarbarianHordesState {
+            barbarian_entity: Some(owner),
+            all_tokens,
+            ..
+        }) => Some((*owner, all_tokens.clone())),
+        _ => None,
+    })
+}
+
+fn capture_barbarian_horde(world: &World) -> Option<SavedBarbarianHorde> {
+    let (owner, tokens) = barbarian_horde(world)?;
+    let mut standing: HashMap<Entity, i32> = HashMap::default();
+    if let Some(mut areas) = world.try_query::<(&GameArea, &Population)>() {
+        for (area, population) in areas.iter(world) {
+            if let Some(here) = population.player_tokens().get(&owner) {
+                standing.extend(here.iter().map(|token| (*token, area.id)));
+            }
+        }
+    }
+    Some(SavedBarbarianHorde {
+        tokens: tokens
+            .iter()
+            .map(|token| standing.get(token).copied())
+            .collect(),
+    })
+}
+
+/// Spawns a fresh owner and one token per saved token, standing in the area
+/// it stood in, and names them in `live`.
+fn restore_barbarian_horde(
+    world: &mut World,
+    horde: &SavedBarbarianHorde,
+    live: &mut HashMap<SavedEntity, Entity>,
+) {
+    let owner = world.spawn(Name::new("Barbarian Horde")).id();
+    live.insert(SavedEntity::BarbarianHorde, owner);
+    let sprite = world.get_resource::<TextureAssets>().map(barbarian_sprite);
+    for (nth, area_id) in horde.tokens.iter().enumerate() {
+        let area = area_id.and_then(|id| live.get(&SavedEntity::Area(id)).copied());
+        let base = area
+            .and_then(|area| world.get::<Transform>(area))
+            .map(|transform| transform.translation)
+            .unwrap_or_default();
+        let mut token = world.spawn(barbarian_token(base, nth));
+        if let Some(sprite) = &sprite {
+            token.insert(sprite.clone());
+        }
+        let token = token.id();
+        if let Some(mut population) = area.and_then(|area| world.get_mut::<Population>(area)) {
+            population.add_token_to_area(owner, token);
+        }
+        live.insert(SavedEntity::BarbarianToken(nth), token);
+    }
+}
+
+fn restore_in_flight(world: &mut World, saved: &SavedInFlight) -> Result<(), String> {
+    let registry = world.resource::<AppTypeRegistry>().clone();
+    let registry = registry.read();
+
+    // Offers are rebuilt from the save alone; whatever the phase's entry
+    // systems left lying around goes.
+    let stale_offers: Vec<Entity> = name_entities(world)
+        .into_iter()
+        .filter(|(_, name)| matches!(name, SavedEntity::TradeOffer(_)))
+        .map(|(entity, _)| entity)
+        .collect();
+    for offer in stale_offers {
+        world.despawn(offer);
+    }
+
+    let mut live: HashMap<SavedEntity, Entity> = name_entities(world)
+        .into_iter()
+        .map(|(entity, name)| (name, entity))
+        .collect();
+    for n in 0..saved.trade_offers {
+        live.insert(SavedEntity::TradeOffer(n), world.spawn_empty().id());
+    }
+    if let Some(horde) = &saved.barbarian_horde {
+        restore_barbarian_horde(world, horde, &mut live);
+    }
+    let resolved: Vec<Entity> = saved
+        .entities
+        .iter()
+        .map(|name| {
+            live.get(name)
+                .copied()
+                .ok_or_else(|| format!("{name:?} is not on the loaded board"))
+        })
+        .collect::<Result<_, _>>()?;
+
+    // Exact state: a player who had finished trading must not keep the
+    // `CanTrade` setup_trade just gave them.
+    for (name, entity) in &live {
+        if !matches!(name, SavedEntity::Player(_) | SavedEntity::PirateNation) {
+            continue;
+        }
+        for type_id in in_flight_components() {
+            if let Some(reflect_component) = registry
+                .get(type_id)
+                .and_then(|registration| registration.data::<ReflectComponent>())
+            {
+                reflect_component.remove(&mut world.entity_mut(*entity));
+            }
+        }
+    }
+
+    for saved_component in &saved.components {
+        let registration = registry
+            .get_with_type_path(&saved_component.type_path)
+            .ok_or_else(|| format!("unknown component {}", saved_component.type_path))?;
+        let reflect_component = registration
+            .data::<ReflectComponent>()
+            .ok_or_else(|| format!("{} is not a component", saved_component.type_path))?;
+        let mut value = TypedReflectDeserializer::new(registration, &registry)
+            .deserialize(saved_component.value.clone())
+            .map_err(|e| format!("cannot read {}: {e}", saved_component.type_path))?;
+        map_entities(value.as_mut(), &mut |placeholder| {
+            resolved.get(placeholder.index_u32() as usize).copied()
+        })
+        .map_err(|e| format!("{} refers to unknown {e}", saved_component.type_path))?;
+
+        let on = live
+            .get(&saved_component.on)
+            .copied()
+            .ok_or_else(|| format!("{:?} is not on the loaded board", saved_component.on))?;
+        reflect_component.insert(&mut world.entity_mut(on), value.as_ref(), &registry);
+    }
+    Ok(())
+}
+
+/// Every entity [`SavedEntity`] can name, keyed by its id in `world`.
+fn name_entities(world: &World) -> HashMap<Entity, SavedEntity> {
+    let mut names: HashMap<Entity, SavedEntity> = HashMap::default();
+
+    let mut factions: HashMap<Entity, GameFaction> = HashMap::default();
+    if let Some(mut players) = world.try_query_filtered::<(Entity, &Faction), With<Player>>() {
+        for (entity, faction) in players.iter(world) {
+            factions.insert(entity, faction.faction);
+            names.insert(entity, SavedEntity::Player(faction.faction));
+        }
+    }
+    let mut pirates = HashSet::default();
+    if let Some(mut pirate) = world.try_query_filtered::<Entity, With<PirateNation>>() {
+        for entity in pirate.iter(world) {
+            pirates.insert(entity);
+            names.insert(entity, SavedEntity::PirateNation);
+        }
+    }
+    if let Some((owner, tokens)) = barbarian_horde(world) {
+        names.insert(owner, SavedEntity::BarbarianHorde);
+        for (nth, token) in tokens.into_iter().enumerate() {
+            names.insert(token, SavedEntity::BarbarianToken(nth));
+        }
+    }
+
+    if let Some(mut areas) =
+        world.try_query::<(Entity, &GameArea, &Population, Option<&BuiltCity>)>()
+    {
+        for (entity, area, population, built_city) in areas.iter(world) {
+            names.insert(entity, SavedEntity::Area(area.id));
+            if let Some(city) = built_city {
+                names.insert(city.city, SavedEntity::City { area_id: area.id });
+            }
+            for (owner, tokens) in population.player_tokens() {
+                let owner = match factions.get(owner) {
+                    Some(faction) => Some(*faction),
+                    None if pirates.contains(owner) => None,
+                    // The Barbarian horde's, named above.
+                    None => continue,
+                };
+                let mut tokens: Vec<Entity> = tokens.iter().copied().collect();
+                tokens.sort();
+                for (nth, token) in tokens.into_iter().enumerate() {
+                    names.insert(
+                        token,
+                        SavedEntity::Token {
+                            area_id: area.id,
+                            owner,
+                            nth,
+                        },
+                    );
+                }
+            }
+        }
+    }
+
+    let mut offers: Vec<Entity> = Vec::new();
+    if let Some(mut open) = world.try_query_filtered::<Entity, With<OpenTradeOffer>>() {
+        offers.extend(open.iter(world));
+    }
+    if let Some(mut published) = world.try_query_filtered::<Entity, With<TradeOffer>>() {
+        offers.extend(published.iter(world));
+    }
+    offers.sort();
+    offers.dedup();
+    for (n, offer) in offers.into_iter().enumerate() {
+        names.insert(offer, SavedEntity::TradeOffer(n));
+    }
+
+    names
+}
+
+/// Rewrite every `Entity` inside `value` through `map`, stopping at the
+/// first one `map` has no answer for.
+fn map_entities(
+    value: &mut dyn PartialReflect,
+    map: &mut dyn FnMut(Entity) -> Option<Entity>,
+) -> Result<(), Entity> {
+    if let Some(entity) = value.try_downcast_mut::<Entity>() {
+        *entity = map(*entity).ok_or(*entity)?;
+        return Ok(());
+    }
+    match value.reflect_mut() {
+        ReflectMut::Struct(value) => {
+            for i in 0..value.field_len() {
+                if let Some(field) = value.field_at_mut(i) {
+                    map_entities(field, map)?;
+                }
+            }
+        }
+        ReflectMut::TupleStruct(value) => {
+            for i in 0..value.field_len() {
+                if let Some(field) = value.field_mut(i) {
+                    map_entities(field, map)?;
+                }
+            }
+        }
+        ReflectMut::Tuple(value) => {
+            for i in 0..value.field_len() {
+                if let Some(field) = value.field_mut(i) {
+                    map_entities(field, map)?;
+                }
+            }
+        }
+        ReflectMut::List(value) => {
+            for i in 0..value.len() {
+                if let Some(item) = value.get_mut(i) {
+                    map_entities(item, map)?;
+                }
+            }
+        }
+        ReflectMut::Array(value) => {
+            for i in 0..value.len() {
+                if let Some(item) = value.get_mut(i) {
+                    map_entities(item, map)?;
+                }
+            }
+        }
+        ReflectMut::Enum(value) => {
+            for i in 0..value.field_len() {
+                if let Some(field) = value.field_at_mut(i) {
+                    map_entities(field, map)?;
+                }
+            }
+        }
+        // Keys can be entities too, so entries are taken out, rewritten and
+        // put back.
+        ReflectMut::Map(value) => {
+            for (mut key, mut item) in value.drain() {
+                map_entities(key.as_mut(), map)?;
+                map_entities(item.as_mut(), map)?;
+                value.insert_boxed(key, item);
+            }
+        }
+        ReflectMut::Set(value) => {
+            for mut item in value.drain() {
+                map_entities(item.as_mut(), map)?;
+                value.insert_boxed(item);
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::civilization::concepts::resolve_calamities::calamities::barbarian_hordes::BarbarianHordesPhase;
+    use crate::civilization::concepts::resolve_calamities::calamities::civil_war::CivilWarState;
+
+    struct Board {
+        egypt: Entity,
+        thrace: Entity,
+        egypt_tokens: Vec<Entity>,
+        egypt_city: Entity,
+        pirate_token: Entity,
+    }
+
+    /// Two players and the Pirate nation on a two-area board. `padding`
+    /// shifts every entity id, the way a load does.
+    fn board(padding: usize) -> (World, Board) {
+        let mut world = World::new();
+        world.init_resource::<AppTypeRegistry>();
+        {
+            let mut registry = world.resource::<AppTypeRegistry>().write();
+            registry.register::<NeedsCalamityResolution>();
+            registry.register::<PendingCalamities>();
+            registry.register::<CalamityVictim>();
+            registry.register::<ActiveCalamityResolution>();
+            registry.register::<ResolvingCalamity>();
+            registry.register::<NeedsMonotheismConversion>();
+            registry.register::<GrainLockedForPurchase>();
+            registry.register::<CanTrade>();
+            registry.register::<PlayerTradeInterests>();
+            registry.register::<PlayerSettlements>();
+            registry.register::<OpenTradeOffer>();
+            registry.register::<TradeOffer>();
+            registry.register::<PublishedOffer>();
+            registry.register::<InSettlement>();
+        }
+        for _ in 0..padding {
+            world.spawn_empty();
+        }
+
+        let egypt = world.spawn((Player, Faction::new(GameFaction::Egypt))).id();
+        let thrace = world
+            .spawn((Player, Faction::new(GameFaction::Thrace)))
+            .id();
+        let pirates = world.spawn(PirateNation).id();
+
+        let mut area_1 = Population::new(5);
+        let egypt_tokens: Vec<Entity> = (0..3)
+            .map(|_| world.spawn(Token::new(egypt)).id())
+            .collect();
+        for token in &egypt_tokens {
+            area_1.add_token_to_area(egypt, *token);
+        }
+        for _ in 0..2 {
+            area_1.add_token_to_area(thrace, world.spawn(Token::new(thrace)).id());
+        }
+        world.spawn((GameArea::new(1), area_1));
+
+        let mut area_2 = Population::new(3);
+        let pirate_token = world.spawn(Token::new(pirates)).id();
+        area_2.add_token_to_area(pirates, pirate_token);
+        let egypt_city = world.spawn(CityToken::new(egypt)).id();
+        world.spawn((GameArea::new(2), area_2, BuiltCity::new(egypt_city, egypt)));
+
+        (
+            world,
+            Board {
+                egypt,
+                thrace,
+                egypt_tokens,
+                egypt_city,
+                pirate_token,
+            },
+        )
+    }
+
+    /// What a game saved at `activity` could be holding.
+    fn play_until(world: &mut World, board: &Board, activity: &GameActivity) {
+        world.insert_resource(RoundLimit(Some(12)));
+        world.insert_resource(CivilizationTradeCards {
+            card_piles: [
+                (1, vec![TradeCard::Hides, TradeCard::Ochre]),
+                (5, vec![TradeCard::Flood, TradeCard::Wine, TradeCard::Cloth]),
+            ]
+            .into_iter()
+            .collect(),
+        });
+
+        match activity {
+            GameActivity::Trade => {
+                let mut open = OpenTradeOffer::new(board.thrace, "Thrace", None, None);
+                open.offering_guaranteed.insert(TradeCard::Salt, 2);
+                open.wanting_hidden_count = 1;
+                world.spawn(open);
+
+                let mut settling =
+                    TradeOffer::propose_trade(board.egypt, "Egypt", board.thrace, "Thrace");
+                settling.initiator_pays.insert(TradeCard::Ochre, 2);
+                settling.accepts = Some(board.thrace);
+                settling.settled_players.insert(board.egypt);
+                let settling = world.spawn((settling, PublishedOffer, InSettlement)).id();
+
+                world.entity_mut(board.egypt).insert((
+                    CanTrade,
+                    PlayerSettlements {
+                        trades: [settling].into_iter().collect(),
+                        current_trade: Some(settling),
+                    },
+                ));
+                world.entity_mut(board.thrace).insert(PlayerTradeInterests {
+                    wants: vec![TradeCard::Ochre],
+                });
+            }
+            GameActivity::ResolveCalamities => {
+                let civil_war = CivilWarState {
+                    beneficiary: Some(board.thrace),
+                    victim_selected_units: board.egypt_tokens[..2].to_vec(),
+                    victim_selected_cities: vec![board.egypt_city],
+                    ..CivilWarState::new()
+                };
+                world.entity_mut(board.egypt).insert((
+                    NeedsCalamityResolution,
+                    PendingCalamities::new(vec![(TradeCard::Flood, Some(board.thrace))]),
+                    CalamityVictim::new(TradeCard::CivilWar, Some(board.thrace)),
+                    ResolvingCalamity::CivilWar(civil_war),
+                ));
+                world
+                    .entity_mut(board.thrace)
+                    .insert((NeedsMonotheismConversion, GrainLockedForPurchase(1)));
+            }
+            _ => {}
+        }
+    }
+
+    fn reflected(world: &World, component: &SavedComponent) -> Box<dyn PartialReflect> {
+        let registry = world.resource::<AppTypeRegistry>().read();
+        let registration = registry
+            .get_with_type_path(&component.type_path)
+            .expect("saved components are registered");
+        TypedReflectDeserializer::new(registration, &registry)
+            .deserialize(component.value.clone())
+            .expect("saved components deserialize")
+    }
+
+    /// Map iteration order is not part of a component's value, so the JSON
+    /// can differ between two equal worlds; compare through reflection.
+    fn assert_equivalent(world: &World, expected: &SavedPhaseState, actual: &SavedPhaseState) {
+        assert_eq!(actual.round_limit, expected.round_limit);
+        assert_eq!(actual.game_result, expected.game_result);
+        assert_eq!(actual.trade_card_piles, expected.trade_card_piles);
+        assert_eq!(actual.in_flight.entities, expected.in_flight.entities);
+        assert_eq!(
+            actual.in_flight.trade_offers,
+            expected.in_flight.trade_offers
+        );
+        assert_eq!(
+            actual.in_flight.components.len(),
+            expected.in_flight.components.len()
+        );
+        for (a, e) in actual
+            .in_flight
+            .components
+            .iter()
+            .zip(&expected.in_flight.components)
+        {
+            assert_eq!((&a.on, &a.type_path), (&e.on, &e.type_path));
+            assert_eq!(
+                reflected(world, a).reflect_partial_eq(reflected(world, e).as_ref()),
+                Some(true),
+                "{} on {:?} changed across the reload",
+                a.type_path,
+                a.on
+            );
+        }
+    }
+
+    /// Trade and ResolveCalamities set up their own state on entry, which a
+    /// restore replaces. Every activity reached in a real game is covered by
+    /// the headless simulation tests.
+    #[test]
+    fn restoring_replaces_what_the_phase_set_up_on_entry() {
+        for activity in [GameActivity::Trade, GameActivity::ResolveCalamities] {
+            let (mut saved_world, saved_board) = board(0);
+            play_until(&mut saved_world, &saved_board, &activity);
+            let saved = capture_phase_state(&saved_world)
+                .unwrap_or_else(|e| panic!("{activity:?} should save: {e}"));
+            let json = serde_json::to_string(&saved).unwrap();
+            let reloaded: SavedPhaseState = serde_json::from_str(&json).unwrap();
+
+            // The loaded board, with ids shifted and the phase's own entry
+            // systems having already run.
+            let (mut loaded_world, loaded_board) = board(17);
+            loaded_world
+                .entity_mut(loaded_board.thrace)
+                .insert(CanTrade);
+            loaded_world.spawn(OpenTradeOffer::new(loaded_board.egypt, "Egypt", None, None));
+            apply_phase_state(&mut loaded_world, &reloaded)
+                .unwrap_or_else(|e| panic!("{activity:?} should restore: {e}"));
+
+            let again = capture_phase_state(&loaded_world).unwrap();
+            assert_equivalent(&loaded_world, &saved, &again);
+        }
+    }
+
+    #[test]
+    fn restored_references_point_at_the_loaded_entities() {
+        let (mut saved_world, saved_board) = board(0);
+        play_until(
+            &mut saved_world,
+            &saved_board,
+            &GameActivity::ResolveCalamities,
+        );
+        let saved = capture_phase_state(&saved_world).unwrap();
+
+        let (mut loaded_world, loaded_board) = board(5);
+        apply_phase_state(&mut loaded_world, &saved).unwrap();
+
+        let ResolvingCalamity::CivilWar(civil_war) = loaded_world
+            .get::<ResolvingCalamity>(loaded_board.egypt)
+            .expect("the calamity is still resolving")
+        else {
+            panic!("a different calamity came back");
+        };
+        assert_eq!(civil_war.beneficiary, Some(loaded_board.thrace));
+        assert_eq!(
+            civil_war.victim_selected_cities,
+            vec![loaded_board.egypt_city]
+        );
+        for unit in &civil_war.victim_selected_units {
+            assert!(loaded_board.egypt_tokens.contains(unit));
+        }
+        assert!(
+            loaded_world
+                .get::<GrainLockedForPurchase>(loaded_board.thrace)
+                .is_some_and(|locked| locked.0 == 1)
+        );
+    }
+
+    #[test]
+    fn open_trades_come_back_linked_to_their_settlements() {
+        let (mut saved_world, saved_board) = board(0);
+        play_until(&mut saved_world, &saved_board, &GameActivity::Trade);
+        let saved = capture_phase_state(&saved_world).unwrap();
+        assert_eq!(saved.in_flight.trade_offers, 2);
+
+        let (mut loaded_world, loaded_board) = board(9);
+        apply_phase_state(&mut loaded_world, &saved).unwrap();
+
+        let settlements = loaded_world
+            .get::<PlayerSettlements>(loaded_board.egypt)
+            .expect("Egypt is mid-settlement");
+        let current = settlements.current_trade.expect("a trade is being settled");
+        let offer = loaded_world
+            .get::<TradeOffer>(current)
+            .expect("the settlement points at the reloaded offer");
+        assert_eq!(offer.receiver, loaded_board.thrace);
+        assert!(offer.settled_players.contains(&loaded_board.egypt));
+        assert!(loaded_world.get::<InSettlement>(current).is_some());
+        assert!(loaded_world.get::<CanTrade>(loaded_board.thrace).is_none());
+    }
+
+    #[test]
+    fn the_trade_card_stacks_keep_their_order() {
+        let (mut saved_world, saved_board) = board(0);
+        play_until(&mut saved_world, &saved_board, &GameActivity::Census);
+        let saved = capture_phase_state(&saved_world).unwrap();
+
+        let (mut loaded_world, _) = board(0);
+        loaded_world.insert_resource(CivilizationTradeCards::default());
+        apply_phase_state(&mut loaded_world, &saved).unwrap();
+
+        let deck = loaded_world.resource::<CivilizationTradeCards>();
+        assert_eq!(
+            deck.card_piles[&5],
+            vec![TradeCard::Flood, TradeCard::Wine, TradeCard::Cloth]
+        );
+        assert_eq!(loaded_world.resource::<RoundLimit>().0, Some(12));
+    }
+
+    fn area(world: &mut World, id: i32) -> Entity {
+        world
+            .query::<(Entity, &GameArea)>()
+            .iter(world)
+            .find_map(|(entity, area)| (area.id == id).then_some(entity))
+            .expect("the board has the area")
+    }
+
+    #[test]
+    fn a_barbarian_horde_survives_a_save_and_reload() {
+        let (mut saved_world, saved_board) = board(0);
+        let landing = area(&mut saved_world, 1);
+        let horde = saved_world.spawn_empty().id();
+        let tokens: Vec<Entity> = (0..3)
+            .map(|_| saved_world.spawn(BarbarianToken).id())
+            .collect();
+        // The third was eliminated in the landing conflict.
+        let mut population = saved_world.get_mut::<Population>(landing).unwrap();
+        for token in &tokens[..2] {
+            population.add_token_to_area(horde, *token);
+        }
+        saved_world
+            .entity_mut(saved_board.egypt)
+            .insert(ResolvingCalamity::BarbarianHordes(BarbarianHordesState {
+                phase: BarbarianHordesPhase::CheckSurplus,
+                landing_area: Some(landing),
+                barbarian_entity: Some(horde),
+                visited_areas: vec![landing],
+                all_tokens: tokens,
+                cascade_iterations: 0,
+            }));
+        let saved = capture_phase_state(&saved_world).expect("a horde can be saved");
+        assert_eq!(
+            saved.in_flight.barbarian_horde,
+            Some(SavedBarbarianHorde {
+                tokens: vec![Some(1), Some(1), None]
+            })
+        );
+
+        let (mut loaded_world, loaded_board) = board(11);
+        apply_phase_state(&mut loaded_world, &saved).unwrap();
+
+        let Some(ResolvingCalamity::BarbarianHordes(state)) =
+            loaded_world.get::<ResolvingCalamity>(loaded_board.egypt)
+        else {
+            panic!("the horde is still resolving");
+        };
+        let state = state.clone();
+        let landing = area(&mut loaded_world, 1);
+        assert_eq!(state.landing_area, Some(landing));
+        assert_eq!(state.visited_areas, vec![landing]);
+        let horde = state.barbarian_entity.expect("the horde has an owner");
+        let standing = loaded_world
+            .get::<Population>(landing)
+            .unwrap()
+            .player_tokens()
+            .get(&horde)
+            .cloned()
+            .unwrap_or_default();
+        assert_eq!(state.all_tokens.len(), 3);
+        assert!(state.all_tokens[..2].iter().all(|t| standing.contains(t)));
+        assert!(!standing.contains(&state.all_tokens[2]));
+        assert!(
+            loaded_world
+                .get::<BarbarianToken>(state.all_tokens[2])
+                .is_some()
+        );
+
+        let again = capture_phase_state(&loaded_world).unwrap();
+        assert_equivalent(&loaded_world, &saved, &again);
+        assert_eq!(
+            again.in_flight.barbarian_horde,
+            saved.in_flight.barbarian_horde
+        );
+    }
+
+    #[test]
+    fn pirate_tokens_are_named_by_area() {
+        let (world, board) = board(0);
+        assert_eq!(
+            name_entities(&world).get(&board.pirate_token),
+            Some(&SavedEntity::Token {
+                area_id: 2,
+                owner: None,
+                nth: 0
+            })
+        );
+    }
+}
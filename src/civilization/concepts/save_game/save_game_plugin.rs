@@ -1,8 +1,7 @@
+use bevy::ecs::system::SystemParam;
 use bevy::platform::collections::HashMap;
 use bevy::prelude::*;
 use serde::{Deserialize, Serialize};
-use std::fs;
-use std::path::Path;
 
 use crate::civilization::components::*;
 use crate::civilization::concepts::AvailableFactions;
@@ -11,34 +10,56 @@ use crate::civilization::concepts::city_construction::{CityConstructionPhaseActi
 use crate::civilization::concepts::movement::movement_components::PerformingMovement;
 use crate::civilization::concepts::population_expansion::population_expansion_components::NeedsExpansion;
 use crate::civilization::concepts::resolve_calamities::resolve_calamities_components::PirateNation;
-use crate::civilization::concepts::resolve_calamities::resolve_calamities_systems::ensure_pirate_nation;
+use crate::civilization::concepts::resolve_calamities::resolve_calamities_systems::{
+    ensure_pirate_nation, start_calamity_resolution,
+};
+use crate::civilization::concepts::save_game::save_game_migrations::{
+    SAVE_GAME_VERSION, load_save_json,
+};
+use crate::civilization::concepts::save_game::save_game_phase_state::{
+    PendingPhaseRestore, SavedInFlight, SavedPhaseState, capture_phase_state, restore_phase_state,
+};
+#[cfg(not(target_family = "wasm"))]
+use crate::civilization::concepts::save_game::save_slots::LEGACY_SAVE_FILES;
+use crate::civilization::concepts::save_game::save_slots::{
+    QUICKSAVE_SLOT, SaveSlotInfo, SaveSlots, SlotKind, autosaves_to_prune, now_unix_secs,
+};
 use crate::civilization::concepts::ships::create_ship_stock;
 use crate::civilization::enums::GameFaction;
 use crate::civilization::game_moves::RecalculatePlayerMoves;
 use crate::civilization::{
-    AstPosition, CanTrade, Census, CivCardName, PlayerAcquiringCivilizationCards,
-    PlayerCivilizationCards, PlayerTradeCards, TradeCard,
+    AstPosition, CanTrade, Census, CivCardName, DebugOptions, PlayerAcquiringCivilizationCards,
+    PlayerCivilizationCards, PlayerTradeCards, TradeCard, setup_trade,
 };
 use crate::player::Player;
-use crate::stupid_ai::{IsHuman, Personality, Playstyle, StupidAi};
+use crate::stupid_ai::{IsHuman, Personalities, StupidAi, init_personalities};
 use crate::{GameActivity, GameState};
 
-const SAVE_FILE_PATH: &str = "savegame.json";
-const SAVE_GAME_VERSION: &str = "0.0.2";
-
 /// Dev-workflow env var: when set (to anything), the game skips straight
-/// past the menu and loads `savegame.json` on boot if it exists -- lets you
+/// past the menu and loads the newest save slot on boot if there is one -- lets you
 /// iterate on UI/logic, restart, and land right back where you left off
 /// instead of re-driving the menu and early turns by hand every time.
 const AUTOLOAD_ENV_VAR: &str = "ADV_CIV_AUTOLOAD";
 
-/// Message to request a game save (fired by F5 key or menu button)
-#[derive(Message)]
-pub struct SaveGameRequest;
+/// Message to request a game save
+#[derive(Message, Debug, Clone, PartialEq, Eq)]
+pub enum SaveGameRequest {
+    /// F5: overwrite the quicksave slot.
+    Quicksave,
+    /// Pause menu: a new named slot, renameable from the load browser.
+    NewSlot,
+    /// After each move: the current phase's rotating autosave.
+    Autosave,
+}
 
-/// Message to request a game load (fired by F9 key or menu button)
-#[derive(Message)]
-pub struct LoadGameRequest;
+/// Message to request a game load
+#[derive(Message, Debug, Clone, PartialEq, Eq)]
+pub enum LoadGameRequest {
+    /// F9 / autoload: whichever slot was written last.
+    Latest,
+    /// A slot picked in the load browser, by id.
+    Slot(String),
+}
 
 /// Resource to signal that a game should be loaded
 #[derive(Resource)]
@@ -66,7 +87,8 @@ pub struct SaveGamePlugin;
 
 impl Plugin for SaveGamePlugin {
     fn build(&self, app: &mut App) {
-        app.add_message::<SaveGameRequest>()
+        app.init_resource::<SaveSlots>()
+            .add_message::<SaveGameRequest>()
             .add_message::<LoadGameRequest>()
             .add_systems(
                 Update,
@@ -81,9 +103,22 @@ impl Plugin for SaveGamePlugin {
             .add_systems(OnEnter(GameState::Menu), trigger_autoload)
             .add_systems(
                 OnEnter(GameActivity::PrepareGame),
-                load_game_from_save.before(crate::civilization::general_systems::setup_players),
+                load_game_from_save
+                    .after(init_personalities)
+                    .before(crate::civilization::general_systems::setup_players),
+            )
+            .add_systems(
+                OnEnter(GameActivity::StartGame),
+                (restore_area_populations, restore_phase_state).chain(),
+            )
+            .add_systems(
+                OnEnter(GameActivity::Trade),
+                restore_phase_state.after(setup_trade),
+            )
+            .add_systems(
+                OnEnter(GameActivity::ResolveCalamities),
+                restore_phase_state.after(start_calamity_resolution),
             )
-            .add_systems(OnEnter(GameActivity::StartGame), restore_area_populations)
             // Safety net: clean up LoadingFromSave for atomic activities that don't
             // consume it themselves (Census, Conflict, RemoveSurplus, CheckCitySupport,
             // AcquireTradeCards, Trade). The per-player activities (PopExpansion,
@@ -103,6 +138,11 @@ impl Plugin for SaveGamePlugin {
                 cleanup_loading_from_save,
             )
             .add_systems(OnEnter(GameActivity::Trade), cleanup_loading_from_save);
+        #[cfg(not(target_family = "wasm"))]
+        app.add_systems(
+            OnEnter(GameState::Menu),
+            import_legacy_saves.before(trigger_autoload),
+        );
     }
 }
 
@@ -129,27 +169,17 @@ pub struct SavedPlayer {
     pub trade_cards: Vec<(TradeCard, usize)>,
     /// Whether this player has completed the current game activity.
     /// Used on load to avoid re-running activity logic for players who already finished.
-    #[serde(default)]
     pub done_with_current_activity: bool,
-    #[serde(default = "default_ast_space")]
     pub ast_space: u32,
-    /// Civilization cards owned at save time (rule 31.x). Older save files
-    /// predate this field; `#[serde(default)]` loads them as owning none
-    /// rather than rejecting the whole file.
-    #[serde(default)]
+    /// Civilization cards owned at save time (rule 31.x).
     pub owned_civ_cards: Vec<CivCardName>,
     /// Who traded each held calamity to this player (rules 29.61/30.221).
     /// Stored by faction because entity ids are not stable across a load.
-    /// Older saves predate this field and load with no provenance, which is
-    /// the same as every calamity having been drawn rather than traded.
-    #[serde(default)]
+    /// Saves from before 0.0.3 load with no provenance, which is the same as
+    /// every calamity having been drawn rather than traded.
     pub calamity_traded_by: Vec<(TradeCard, GameFaction)>,
 }
 
-fn default_ast_space() -> u32 {
-    0
-}
-
 /// Saved data for population in an area
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct SavedAreaPopulation {
@@ -162,12 +192,13 @@ pub struct SavedAreaPopulation {
     /// Pirate city "remains until attacked and destroyed"). It has no
     /// `GameFaction`, so `city_owner` cannot name it -- without this flag a
     /// Pirate city silently vanished on save/load.
-    #[serde(default)]
     pub city_is_pirate: bool,
 }
 
-/// Complete game save data
-#[derive(Serialize, Deserialize, Debug)]
+/// Complete game save data. Any change to its shape (or to the types inside
+/// it) needs a `SAVE_GAME_VERSION` bump and a migration in
+/// `save_game_migrations.rs`.
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct GameSaveData {
     pub version: String,
     pub round: usize,
@@ -175,14 +206,33 @@ pub struct GameSaveData {
     pub players: Vec<SavedPlayer>,
     pub area_populations: Vec<SavedAreaPopulation>,
     /// Census order saved as factions (resolved to entities on load)
-    #[serde(default)]
     pub census_order: Vec<GameFaction>,
     /// Players left to move, saved as factions (resolved to entities on load)
-    #[serde(default)]
     pub left_to_move: Vec<GameFaction>,
     /// The faction currently performing movement (already popped from left_to_move)
-    #[serde(default)]
     pub current_mover: Option<GameFaction>,
+    /// Open trades, calamities mid-resolution, the trade card stacks and the
+    /// other game-wide resources.
+    pub phase_state: SavedPhaseState,
+}
+
+impl GameSaveData {
+    /// The save as `viewer` sees the table: the other players' hands and who
+    /// traded them calamities, the undrawn trade card stacks and the
+    /// in-flight phase state (offers carry unrevealed cards) are dropped.
+    /// With no viewer, no hand is shown.
+    pub fn public_view(&self, viewer: Option<GameFaction>) -> GameSaveData {
+        let mut view = self.clone();
+        for player in &mut view.players {
+            if Some(player.faction) != viewer {
+                player.trade_cards.clear();
+                player.calamity_traded_by.clear();
+            }
+        }
+        view.phase_state.trade_card_piles.clear();
+        view.phase_state.in_flight = SavedInFlight::default();
+        view
+    }
 }
 
 /// Determine whether a player has completed the current game activity.
@@ -230,260 +280,389 @@ fn is_player_done_with_activity(
 /// construction, trade, civ card purchase, population expansion, plus the
 /// generic per-phase "needs a move" triggers), so it's the closest thing this
 /// codebase has to a single "a move happened" choke point. Firing a save
-/// after each batch means `savegame.json` always reflects roughly the last
+/// after each batch means the newest autosave always reflects roughly the last
 /// move made, so a UI bug can be reached again with F9 or `ADV_CIV_AUTOLOAD`
 /// instead of replaying the whole game by hand.
 fn autosave_on_player_move(
     mut recalc_reader: MessageReader<RecalculatePlayerMoves>,
     mut save_writer: MessageWriter<SaveGameRequest>,
+    debug_options: Res<DebugOptions>,
 ) {
+    if !debug_options.autosave {
+        recalc_reader.clear();
+        return;
+    }
     if recalc_reader.read().next().is_some() {
         recalc_reader.clear();
-        save_writer.write(SaveGameRequest);
+        save_writer.write(SaveGameRequest::Autosave);
+    }
+}
+
+/// Brings `savegame.json` and `savegame.bak` from before save slots into the
+/// load browser. Each lands in its own named slot, once.
+#[cfg(not(target_family = "wasm"))]
+fn import_legacy_saves(slots: Res<SaveSlots>) {
+    let now = now_unix_secs();
+    for (file, id) in LEGACY_SAVE_FILES {
+        match slots.import_legacy(std::path::Path::new(file), id, now) {
+            Ok(Some(info)) => info!("Imported {} into save slot \"{}\"", file, info.name),
+            Ok(None) => {}
+            Err(e) => warn!("Could not import {}: {}", file, e),
+        }
     }
 }
 
 /// Dev-workflow convenience: skip the menu and jump straight into the last
 /// autosaved game when `ADV_CIV_AUTOLOAD` is set, so restarting the app to
 /// pick up a code/UI change lands back where you left off.
-fn trigger_autoload(mut writer: MessageWriter<LoadGameRequest>) {
-    if std::env::var(AUTOLOAD_ENV_VAR).is_ok() && Path::new(SAVE_FILE_PATH).exists() {
-        info!("{} set -- autoloading {}", AUTOLOAD_ENV_VAR, SAVE_FILE_PATH);
-        writer.write(LoadGameRequest);
+fn trigger_autoload(slots: Res<SaveSlots>, mut writer: MessageWriter<LoadGameRequest>) {
+    if std::env::var(AUTOLOAD_ENV_VAR).is_err() {
+        return;
+    }
+    if let Some(latest) = slots.latest() {
+        info!(
+            "{} set -- autoloading \"{}\"",
+            AUTOLOAD_ENV_VAR, latest.name
+        );
+        writer.write(LoadGameRequest::Latest);
     }
 }
 
 fn save_on_key(keys: Res<ButtonInput<KeyCode>>, mut writer: MessageWriter<SaveGameRequest>) {
     if keys.just_pressed(KeyCode::F5) {
-        writer.write(SaveGameRequest);
+        writer.write(SaveGameRequest::Quicksave);
     }
 }
 
-fn handle_save_request(
-    mut events: MessageReader<SaveGameRequest>,
-    game_info: Res<GameInfoAndStuff>,
-    current_activity: Option<Res<State<GameActivity>>>,
-    player_query: Query<
+type SavePlayerQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        Entity,
+        &'static Name,
+        &'static Faction,
+        &'static Census,
+        &'static Treasury,
+        &'static TokenStock,
+        &'static CityTokenStock,
+        &'static PlayerTradeCards,
+        Has<IsHuman>,
+        Option<&'static AstPosition>,
+        Option<&'static PlayerCivilizationCards>,
+    ),
+    With<Player>,
+>;
+
+/// Everything a [`GameSaveData`] is built from. Shared by the save file and
+/// anything else that needs a faction-keyed snapshot of the running game
+/// (the undo stack).
+#[derive(SystemParam)]
+pub struct SaveDataSource<'w, 's> {
+    game_info: Res<'w, GameInfoAndStuff>,
+    current_activity: Option<Res<'w, State<GameActivity>>>,
+    player_query: SavePlayerQuery<'w, 's>,
+    area_query: Query<
+        'w,
+        's,
         (
-            Entity,
-            &Name,
-            &Faction,
-            &Census,
-            &Treasury,
-            &TokenStock,
-            &CityTokenStock,
-            &PlayerTradeCards,
-            Has<IsHuman>,
-            Option<&AstPosition>,
-            Option<&PlayerCivilizationCards>,
+            &'static GameArea,
+            &'static Population,
+            Option<&'static BuiltCity>,
         ),
-        With<Player>,
     >,
-    area_query: Query<(&GameArea, &Population, Option<&BuiltCity>)>,
-    faction_query: Query<&Faction>,
-    pirate_query: Query<Entity, With<PirateNation>>,
-    needs_expansion_query: Query<Entity, With<NeedsExpansion>>,
-    performing_movement_query: Query<Entity, With<PerformingMovement>>,
-    is_building_query: Query<Entity, With<IsBuilding>>,
-    can_trade_query: Query<Entity, With<CanTrade>>,
-    acquiring_civ_cards_query: Query<Entity, With<PlayerAcquiringCivilizationCards>>,
-) {
-    if events.read().next().is_none() {
-        return;
-    }
+    faction_query: Query<'w, 's, &'static Faction>,
+    pirate_query: Query<'w, 's, Entity, With<PirateNation>>,
+    needs_expansion_query: Query<'w, 's, Entity, With<NeedsExpansion>>,
+    performing_movement_query: Query<'w, 's, Entity, With<PerformingMovement>>,
+    is_building_query: Query<'w, 's, Entity, With<IsBuilding>>,
+    can_trade_query: Query<'w, 's, Entity, With<CanTrade>>,
+    acquiring_civ_cards_query: Query<'w, 's, Entity, With<PlayerAcquiringCivilizationCards>>,
+}
 
-    let activity = current_activity
-        .as_ref()
-        .map(|a| a.get().clone())
-        .unwrap_or_default();
-
-    info!("Saving game (activity: {:?})...", activity);
-
-    // Collect player data with per-player completion state
-    let mut players = Vec::new();
-    for (
-        entity,
-        name,
-        faction,
-        census,
-        treasury,
-        token_stock,
-        city_stock,
-        trade_cards,
-        is_human,
-        ast_pos,
-        civ_cards,
-    ) in player_query.iter()
-    {
-        let done = is_player_done_with_activity(
+impl SaveDataSource<'_, '_> {
+    /// Snapshot of the game as it stands right now.
+    pub fn capture(&self) -> GameSaveData {
+        let activity = self
+            .current_activity
+            .as_ref()
+            .map(|a| a.get().clone())
+            .unwrap_or_default();
+        let faction_of = |player: &Entity| self.faction_query.get(*player).ok().map(|f| f.faction);
+
+        // Collect player data with per-player completion state
+        let mut players = Vec::new();
+        for (
             entity,
-            &activity,
-            &needs_expansion_query,
-            &performing_movement_query,
-            &is_building_query,
-            &game_info.left_to_move,
-            &can_trade_query,
-            &acquiring_civ_cards_query,
-        );
-        let saved_player = SavedPlayer {
-            name: name.to_string(),
-            faction: faction.faction,
+            name,
+            faction,
+            census,
+            treasury,
+            token_stock,
+            city_stock,
+            trade_cards,
             is_human,
-            census_population: census.population,
-            treasury: treasury.tokens_in_treasury(),
-            tokens_in_stock: token_stock.tokens_in_stock(),
-            city_tokens_in_stock: city_stock.city_tokens_in_stock(),
-            trade_cards: trade_cards.cards_as_vec(),
-            done_with_current_activity: done,
-            ast_space: ast_pos.map_or(0, |p| p.space),
-            owned_civ_cards: civ_cards.map_or_else(Vec::new, |c| c.cards.iter().copied().collect()),
-            calamity_traded_by: trade_cards
-                .calamity_origins_as_vec()
-                .into_iter()
-                .filter_map(|(card, from)| faction_query.get(from).ok().map(|f| (card, f.faction)))
-                .collect(),
-        };
-        if done {
-            info!(
-                "  Player {} ({:?}) is DONE with {:?}",
-                name, faction.faction, activity
-            );
-        } else {
-            info!(
-                "  Player {} ({:?}) is NOT done with {:?}",
-                name, faction.faction, activity
+            ast_pos,
+            civ_cards,
+        ) in self.player_query.iter()
+        {
+            let done = is_player_done_with_activity(
+                entity,
+                &activity,
+                &self.needs_expansion_query,
+                &self.performing_movement_query,
+                &self.is_building_query,
+                &self.game_info.left_to_move,
+                &self.can_trade_query,
+                &self.acquiring_civ_cards_query,
             );
+            players.push(SavedPlayer {
+                name: name.to_string(),
+                faction: faction.faction,
+                is_human,
+                census_population: census.population,
+                treasury: treasury.tokens_in_treasury(),
+                tokens_in_stock: token_stock.tokens_in_stock(),
+                city_tokens_in_stock: city_stock.city_tokens_in_stock(),
+                trade_cards: trade_cards.cards_as_vec(),
+                done_with_current_activity: done,
+                ast_space: ast_pos.map_or(0, |p| p.space),
+                owned_civ_cards: civ_cards
+                    .map_or_else(Vec::new, |c| c.cards.iter().copied().collect()),
+                calamity_traded_by: trade_cards
+                    .calamity_origins_as_vec()
+                    .into_iter()
+                    .filter_map(|(card, from)| faction_of(&from).map(|f| (card, f)))
+                    .collect(),
+            });
         }
-        players.push(saved_player);
-    }
 
-    // Collect area population data
-    let mut area_populations = Vec::new();
-    for (game_area, population, built_city) in area_query.iter() {
-        let mut tokens_by_faction = Vec::new();
-        for (player_entity, tokens) in population.player_tokens() {
-            if let Ok(faction) = faction_query.get(*player_entity) {
-                tokens_by_faction.push((faction.faction, tokens.len()));
+        // Collect area population data
+        let mut area_populations = Vec::new();
+        for (game_area, population, built_city) in self.area_query.iter() {
+            let mut tokens_by_faction = Vec::new();
+            for (player_entity, tokens) in population.player_tokens() {
+                if let Some(faction) = faction_of(player_entity) {
+                    tokens_by_faction.push((faction, tokens.len()));
+                }
+            }
+
+            let city_owner = built_city.and_then(|bc| faction_of(&bc.player));
+            let city_is_pirate =
+                built_city.is_some_and(|bc| self.pirate_query.get(bc.player).is_ok());
+
+            if !tokens_by_faction.is_empty() || city_owner.is_some() || city_is_pirate {
+                area_populations.push(SavedAreaPopulation {
+                    area_id: game_area.id,
+                    tokens_by_faction,
+                    city_owner,
+                    city_is_pirate,
+                });
             }
         }
 
-        let city_owner =
-            built_city.and_then(|bc| faction_query.get(bc.player).ok().map(|f| f.faction));
-        let city_is_pirate = built_city.is_some_and(|bc| pirate_query.get(bc.player).is_ok());
+        // Save census_order and left_to_move as faction lists
+        let census_order: Vec<GameFaction> = self
+            .game_info
+            .census_order
+            .iter()
+            .filter_map(faction_of)
+            .collect();
+        let left_to_move: Vec<GameFaction> = self
+            .game_info
+            .left_to_move
+            .iter()
+            .filter_map(faction_of)
+            .collect();
 
-        if !tokens_by_faction.is_empty() || city_owner.is_some() || city_is_pirate {
-            area_populations.push(SavedAreaPopulation {
-                area_id: game_area.id,
-                tokens_by_faction,
-                city_owner,
-                city_is_pirate,
-            });
+        // Find the player currently performing movement (already popped from left_to_move)
+        let current_mover = if activity == GameActivity::Movement {
+            self.performing_movement_query
+                .iter()
+                .next()
+                .and_then(|e| faction_of(&e))
+        } else {
+            None
+        };
+
+        GameSaveData {
+            version: SAVE_GAME_VERSION.to_string(),
+            round: self.game_info.round,
+            game_activity: activity,
+            players,
+            area_populations,
+            census_order,
+            left_to_move,
+            current_mover,
+            // Needs the whole world; `handle_save_request` fills it in.
+            phase_state: SavedPhaseState::default(),
         }
     }
+}
 
-    // Save census_order and left_to_move as faction lists
-    let census_order: Vec<GameFaction> = game_info
-        .census_order
-        .iter()
-        .filter_map(|e| faction_query.get(*e).ok().map(|f| f.faction))
-        .collect();
-    let left_to_move: Vec<GameFaction> = game_info
-        .left_to_move
-        .iter()
-        .filter_map(|e| faction_query.get(*e).ok().map(|f| f.faction))
-        .collect();
-
-    // Find the player currently performing movement (already popped from left_to_move)
-    let current_mover = if activity == GameActivity::Movement {
-        performing_movement_query
-            .iter()
-            .next()
-            .and_then(|e| faction_query.get(e).ok().map(|f| f.faction))
-    } else {
-        None
+fn handle_save_request(
+    mut events: MessageReader<SaveGameRequest>,
+    source: SaveDataSource,
+    slots: Res<SaveSlots>,
+    world: &World,
+) {
+    let mut requests: Vec<SaveGameRequest> = events.read().cloned().collect();
+    if requests.is_empty() {
+        return;
+    }
+    requests.dedup();
+
+    let mut save_data = source.capture();
+    match capture_phase_state(world) {
+        Ok(phase_state) => save_data.phase_state = phase_state,
+        Err(e) => {
+            error!("Failed to capture the phase state: {}", e);
+            return;
+        }
+    }
+    let json = match serde_json::to_string_pretty(&save_data) {
+        Ok(json) => json,
+        Err(e) => {
+            error!("Failed to serialize save data: {}", e);
+            return;
+        }
     };
+    let now = now_unix_secs();
 
-    let save_data = GameSaveData {
-        version: SAVE_GAME_VERSION.to_string(),
-        round: game_info.round,
-        game_activity: activity,
-        players,
-        area_populations,
-        census_order,
-        left_to_move,
-        current_mover,
-    };
+    for request in requests {
+        // An autosave reads the slot list once, to pick its slot and to prune.
+        let existing = if request == SaveGameRequest::Autosave {
+            slots.list()
+        } else {
+            Vec::new()
+        };
+        let (id, name, kind) = match request {
+            SaveGameRequest::Quicksave => (
+                QUICKSAVE_SLOT.to_string(),
+                "Quicksave".to_string(),
+                SlotKind::Quicksave,
+            ),
+            SaveGameRequest::NewSlot => (
+                slots.unused_id(&format!("save-{now}")),
+                format!("Round {} {:?}", save_data.round, save_data.game_activity),
+                SlotKind::Named,
+            ),
+            SaveGameRequest::Autosave => (
+                autosave_slot_id(&slots, &existing, &save_data, now),
+                format!("Autosave: round {}", save_data.round),
+                SlotKind::Autosave,
+            ),
+        };
+        let info = SaveSlotInfo::describe(id, name, kind, now, &save_data);
+        if let Err(e) = slots.write(&info, &json) {
+            error!("Failed to write save slot {}: {}", info.id, e);
+            continue;
+        }
 
-    match serde_json::to_string_pretty(&save_data) {
-        Ok(json) => {
-            if let Err(e) = fs::write(SAVE_FILE_PATH, &json) {
-                error!("Failed to write save file: {}", e);
-            } else {
-                info!(
-                    "Game saved to {} ({} players, {} areas with population)",
-                    SAVE_FILE_PATH,
-                    save_data.players.len(),
-                    save_data.area_populations.len()
-                );
+        if kind == SlotKind::Autosave {
+            let mut listed: Vec<SaveSlotInfo> =
+                existing.into_iter().filter(|s| s.id != info.id).collect();
+            listed.push(info);
+            for old in autosaves_to_prune(&listed) {
+                if let Err(e) = slots.delete(&old) {
+                    warn!("Failed to prune autosave {}: {}", old, e);
+                }
             }
+            continue;
+        }
+        info!(
+            "Game saved to slot \"{}\" (activity: {:?}, {} players, {} areas with population)",
+            info.name,
+            save_data.game_activity,
+            save_data.players.len(),
+            save_data.area_populations.len()
+        );
+        for player in &save_data.players {
+            let done = if player.done_with_current_activity {
+                "DONE"
+            } else {
+                "NOT done"
+            };
+            info!(
+                "  Player {} ({:?}) is {} with {:?}",
+                player.name, player.faction, done, save_data.game_activity
+            );
         }
-        Err(e) => error!("Failed to serialize save data: {}", e),
     }
 }
 
+/// One autosave per phase: keep overwriting the newest while the round and
+/// activity are unchanged, start a fresh (timestamped) one when they move on.
+/// `existing` is [`SaveSlots::list`], newest first.
+fn autosave_slot_id(
+    slots: &SaveSlots,
+    existing: &[SaveSlotInfo],
+    save_data: &GameSaveData,
+    now: u64,
+) -> String {
+    existing
+        .iter()
+        .find(|slot| slot.kind == SlotKind::Autosave)
+        .filter(|newest| {
+            newest.round == save_data.round && newest.activity == save_data.game_activity
+        })
+        .map_or_else(
+            || slots.unused_id(&format!("autosave-{now}")),
+            |newest| newest.id.clone(),
+        )
+}
+
 fn trigger_load_on_key(
     keys: Res<ButtonInput<KeyCode>>,
     mut writer: MessageWriter<LoadGameRequest>,
 ) {
     if keys.just_pressed(KeyCode::F9) {
-        writer.write(LoadGameRequest);
+        writer.write(LoadGameRequest::Latest);
     }
 }
 
 fn handle_load_request(
     mut events: MessageReader<LoadGameRequest>,
+    slots: Res<SaveSlots>,
     mut commands: Commands,
     mut next_state: ResMut<NextState<GameState>>,
 ) {
-    if events.read().next().is_none() {
+    let Some(request) = events.read().last().cloned() else {
         return;
-    }
+    };
 
     info!("Loading game...");
 
-    if !Path::new(SAVE_FILE_PATH).exists() {
-        warn!("No save file found at {}", SAVE_FILE_PATH);
-        return;
-    }
+    let id = match request {
+        LoadGameRequest::Latest => {
+            let Some(latest) = slots.latest() else {
+                warn!("No saved games found");
+                return;
+            };
+            latest.id
+        }
+        LoadGameRequest::Slot(id) => id,
+    };
 
-    match fs::read_to_string(SAVE_FILE_PATH) {
-        Ok(json) => {
-            match serde_json::from_str::<GameSaveData>(&json) {
-                Ok(save_data) => {
-                    if save_data.version != SAVE_GAME_VERSION {
-                        error!(
-                            "Save file version mismatch: expected {}, got {}. Save file rejected.",
-                            SAVE_GAME_VERSION, save_data.version
-                        );
-                        return;
-                    }
-                    info!(
-                        "Parsed save data (v{}): round {}, {} players, {} areas",
-                        save_data.version,
-                        save_data.round,
-                        save_data.players.len(),
-                        save_data.area_populations.len()
-                    );
-                    // Insert the pending load resource - will be processed on PrepareGame
-                    commands.insert_resource(PendingGameLoad(save_data));
-                    // Transition to Playing state to trigger the game start
-                    next_state.set(GameState::Playing);
-                    info!("Loading saved game...");
-                }
-                Err(e) => error!("Failed to parse save file: {}", e),
+    match slots.read_save(&id) {
+        Ok(json) => match load_save_json(&json) {
+            Ok(save_data) => {
+                info!(
+                    "Parsed save data (v{}) from slot {}: round {}, {} players, {} areas",
+                    save_data.version,
+                    id,
+                    save_data.round,
+                    save_data.players.len(),
+                    save_data.area_populations.len()
+                );
+                // Insert the pending load resource - will be processed on PrepareGame
+                commands.insert_resource(PendingGameLoad(save_data));
+                // Transition to Playing state to trigger the game start
+                next_state.set(GameState::Playing);
+                info!("Loading saved game...");
             }
-        }
-        Err(e) => error!("Failed to read save file: {}", e),
+            Err(e) => error!("Save file rejected: {}", e),
+        },
+        Err(e) => error!("Failed to read save slot {}: {}", id, e),
     }
 }
 
@@ -494,6 +673,7 @@ fn load_game_from_save(
     pending_load: Option<Res<PendingGameLoad>>,
     mut game_info: ResMut<GameInfoAndStuff>,
     _area_query: Query<(Entity, &GameArea, &mut Population)>,
+    personalities: Option<Res<Personalities>>,
 ) {
     let Some(pending) = pending_load else {
         return;
@@ -523,6 +703,10 @@ fn load_game_from_save(
         left_to_move: save_data.left_to_move.clone(),
         current_mover: save_data.current_mover,
     });
+    commands.insert_resource(PendingPhaseRestore {
+        activity: save_data.game_activity.clone(),
+        state: save_data.phase_state.clone(),
+    });
 
     // Set game round
     game_info.round = save_data.round;
@@ -539,6 +723,11 @@ fn load_game_from_save(
     }
 
     // Create players
+    let lineup = personalities
+        .as_deref()
+        .cloned()
+        .unwrap_or_default()
+        .lineup();
     for (n, saved_player) in save_data.players.iter().enumerate() {
         info!(
             "Creating player: {} ({:?})",
@@ -584,12 +773,11 @@ fn load_game_from_save(
             // event with no retry when the query fails -- an AI player
             // missing this component doesn't error, it just never moves
             // again, hanging that phase forever. setup_players round-robins
-            // over every archetype for a fresh game; do the same here so a
+            // over the line-up for a fresh game; do the same here so a
             // loaded game's AI table looks the same as it would have fresh.
-            let playstyle = Playstyle::ALL[n % Playstyle::ALL.len()];
             commands
                 .entity(player)
-                .insert((StupidAi, Personality::from_playstyle(playstyle)));
+                .insert((StupidAi, lineup[n % lineup.len()].clone()));
         }
 
         // Create tokens for stock
@@ -841,7 +1029,7 @@ mod tests {
     use crate::GameActivity;
     use crate::civilization::enums::GameFaction;
     use crate::civilization::{PlayerShips, ShipStock};
-    use crate::stupid_ai::{IsHuman, StupidAi};
+    use crate::stupid_ai::{IsHuman, Personality, StupidAi};
     use bevy::ecs::system::RunSystemOnce;
 
     /// A loaded player must come out with the same components a freshly
@@ -878,6 +1066,7 @@ mod tests {
             census_order: vec![],
             left_to_move: vec![],
             current_mover: None,
+            phase_state: SavedPhaseState::default(),
         }));
 
         world.run_system_once(load_game_from_save).unwrap();
@@ -901,6 +1090,52 @@ mod tests {
         );
     }
 
+    #[test]
+    fn the_public_view_keeps_only_the_viewers_hand() {
+        let player = |faction| SavedPlayer {
+            name: format!("{faction:?}"),
+            faction,
+            is_human: false,
+            census_population: 0,
+            treasury: 3,
+            tokens_in_stock: 40,
+            city_tokens_in_stock: 9,
+            trade_cards: vec![(TradeCard::Ochre, 2)],
+            done_with_current_activity: false,
+            ast_space: 1,
+            owned_civ_cards: vec![CivCardName::Pottery],
+            calamity_traded_by: vec![(TradeCard::Famine, GameFaction::Egypt)],
+        };
+        let save = GameSaveData {
+            version: SAVE_GAME_VERSION.to_string(),
+            round: 3,
+            game_activity: GameActivity::Trade,
+            players: vec![player(GameFaction::Egypt), player(GameFaction::Crete)],
+            area_populations: vec![],
+            census_order: vec![],
+            left_to_move: vec![],
+            current_mover: None,
+            phase_state: SavedPhaseState {
+                trade_card_piles: vec![(1, vec![TradeCard::Hides])],
+                ..SavedPhaseState::default()
+            },
+        };
+
+        let view = save.public_view(Some(GameFaction::Crete));
+
+        assert!(view.players[0].trade_cards.is_empty());
+        assert!(view.players[0].calamity_traded_by.is_empty());
+        assert_eq!(view.players[1].trade_cards, vec![(TradeCard::Ochre, 2)]);
+        assert_eq!(view.players[0].owned_civ_cards, vec![CivCardName::Pottery]);
+        assert!(view.phase_state.trade_card_piles.is_empty());
+        assert!(
+            save.public_view(None)
+                .players
+                .iter()
+                .all(|p| p.trade_cards.is_empty())
+        );
+    }
+
     /// Rule 30.913: a Pirate city "remains until attacked and destroyed".
     /// It belongs to the `PirateNation`, which has no `GameFaction`, so
     /// `city_owner` cannot name it -- before `city_is_pirate` existed the
@@ -948,11 +1183,13 @@ mod tests {
     /// Save files written before `city_is_pirate` existed must still load.
     #[test]
     fn an_area_entry_without_the_pirate_flag_still_deserializes() {
-        let json = r#"{"area_id": 7, "tokens_by_faction": [], "city_owner": null}"#;
-        let parsed: SavedAreaPopulation =
-            serde_json::from_str(json).expect("older saves remain loadable");
-        assert_eq!(parsed.area_id, 7);
-        assert!(!parsed.city_is_pirate);
+        let json = r#"{"version": "0.0.2", "round": 1, "game_activity": "Movement",
+            "players": [],
+            "area_populations": [{"area_id": 7, "tokens_by_faction": [], "city_owner": null}]}"#;
+        let parsed = load_save_json(json).expect("older saves remain loadable");
+        let area = &parsed.area_populations[0];
+        assert_eq!(area.area_id, 7);
+        assert!(!area.city_is_pirate);
     }
 
     /// Every AI move-selection system (`select_stupid_movement` et al.)
@@ -991,6 +1228,7 @@ mod tests {
             census_order: vec![],
             left_to_move: vec![],
             current_mover: None,
+            phase_state: SavedPhaseState::default(),
         }));
 
         world.run_system_once(load_game_from_save).unwrap();
@@ -1040,6 +1278,7 @@ mod tests {
             census_order: vec![],
             left_to_move: vec![],
             current_mover: None,
+            phase_state: SavedPhaseState::default(),
         }));
 
         world.run_system_once(load_game_from_save).unwrap();
@@ -1088,6 +1327,7 @@ mod tests {
             census_order: vec![],
             left_to_move: vec![],
             current_mover: None,
+            phase_state: SavedPhaseState::default(),
         }));
 
         world.run_system_once(load_game_from_save).unwrap();
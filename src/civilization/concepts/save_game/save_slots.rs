@@ -0,0 +1,480 @@
+use crate::GameActivity;
+use crate::civilization::concepts::save_game::save_game_migrations::load_save_json;
+use crate::civilization::concepts::save_game::save_game_plugin::GameSaveData;
+use crate::civilization::enums::GameFaction;
+use bevy::prelude::Resource;
+use serde::{Deserialize, Serialize};
+
+/// Where native builds keep their save slots, relative to the working dir.
+pub const SAVES_DIR: &str = "saves";
+
+/// How many autosaves are kept: one per phase, for the last this-many phases.
+pub const MAX_AUTOSAVES: usize = 5;
+
+/// The single F5/F9 slot.
+pub const QUICKSAVE_SLOT: &str = "quicksave";
+
+/// The single-file saves of 0.0.2 and before, in the working dir, and the
+/// named slot each is imported into on first start.
+#[cfg(not(target_family = "wasm"))]
+pub const LEGACY_SAVE_FILES: [(&str, &str); 2] = [
+    ("savegame.json", "legacy-savegame"),
+    ("savegame.bak", "legacy-savegame-backup"),
+];
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SlotKind {
+    /// Saved from the pause menu; kept until deleted.
+    Named,
+    /// F5. Every quicksave overwrites the last one.
+    Quicksave,
+    /// Written after every move, overwriting the current phase's autosave;
+    /// one per phase is kept, see [`MAX_AUTOSAVES`].
+    Autosave,
+}
+
+/// What the load browser shows for a slot, stored next to the save itself so
+/// the list never has to parse a whole game.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct SaveSlotInfo {
+    /// Storage key; never changes, even when the slot is renamed.
+    pub id: String,
+    pub name: String,
+    pub kind: SlotKind,
+    /// Seconds since the Unix epoch.
+    pub saved_at: u64,
+    pub version: String,
+    pub round: usize,
+    pub activity: GameActivity,
+    pub factions: Vec<GameFaction>,
+    /// Furthest along the A.S.T., if anyone is strictly ahead.
+    pub ast_leader: Option<GameFaction>,
+    pub summary: String,
+}
+
+impl SaveSlotInfo {
+    pub fn describe(
+        id: String,
+        name: String,
+        kind: SlotKind,
+        saved_at: u64,
+        save: &GameSaveData,
+    ) -> Self {
+        let factions: Vec<GameFaction> = save.players.iter().map(|p| p.faction).collect();
+        let ast_leader = ast_leader(save);
+        let humans = save.players.iter().filter(|p| p.is_human).count();
+
+        let mut summary = format!("Round {}, {:?}. ", save.round, save.game_activity);
+        match ast_leader.and_then(|f| save.players.iter().find(|p| p.faction == f)) {
+            Some(leader) => summary.push_str(&format!(
+                "{} leads the A.S.T. (space {}). ",
+                leader.name, leader.ast_space
+            )),
+            None => summary.push_str("Nobody leads the A.S.T. "),
+        }
+        summary.push_str(&format!(
+            "{} players, {} human.",
+            save.players.len(),
+            humans
+        ));
+
+        SaveSlotInfo {
+            id,
+            name,
+            kind,
+            saved_at,
+            version: save.version.clone(),
+            round: save.round,
+            activity: save.game_activity.clone(),
+            factions,
+            ast_leader,
+            summary,
+        }
+    }
+
+    /// "3 min ago"-style age, relative to `now` (Unix seconds).
+    pub fn age(&self, now: u64) -> String {
+        let seconds = now.saturating_sub(self.saved_at);
+        match seconds {
+            0..60 => "just now".to_string(),
+            60..3_600 => format!("{} min ago", seconds / 60),
+            3_600..86_400 => format!("{} h ago", seconds / 3_600),
+            _ => format!("{} days ago", seconds / 86_400),
+        }
+    }
+}
+
+fn ast_leader(save: &GameSaveData) -> Option<GameFaction> {
+    let best = save.players.iter().map(|p| p.ast_space).max()?;
+    let mut leaders = save.players.iter().filter(|p| p.ast_space == best);
+    let leader = leaders.next()?;
+    (best > 0 && leaders.next().is_none()).then_some(leader.faction)
+}
+
+/// Autosaves beyond the newest [`MAX_AUTOSAVES`], oldest first.
+pub fn autosaves_to_prune(slots: &[SaveSlotInfo]) -> Vec<String> {
+    let mut autosaves: Vec<&SaveSlotInfo> = slots
+        .iter()
+        .filter(|s| s.kind == SlotKind::Autosave)
+        .collect();
+    autosaves.sort_by_key(|s| std::cmp::Reverse(s.saved_at));
+    autosaves
+        .iter()
+        .skip(MAX_AUTOSAVES)
+        .rev()
+        .map(|s| s.id.clone())
+        .collect()
+}
+
+/// Seconds since the Unix epoch (the browser clock on wasm, where
+/// `SystemTime` isn't available).
+pub fn now_unix_secs() -> u64 {
+    #[cfg(not(target_family = "wasm"))]
+    {
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs())
+    }
+    #[cfg(target_family = "wasm")]
+    {
+        (web_sys::js_sys::Date::now() / 1000.0) as u64
+    }
+}
+
+/// The save slots: a `saves/` directory on native builds, browser local
+/// storage on wasm. Each slot is two entries, the [`GameSaveData`] JSON
+/// (`<id>.json`) and its [`SaveSlotInfo`] (`<id>.meta.json`).
+#[derive(Resource, Debug, Clone)]
+pub struct SaveSlots {
+    #[cfg(not(target_family = "wasm"))]
+    root: std::path::PathBuf,
+    #[cfg(target_family = "wasm")]
+    prefix: String,
+}
+
+impl Default for SaveSlots {
+    #[cfg(not(target_family = "wasm"))]
+    fn default() -> Self {
+        SaveSlots::in_dir(SAVES_DIR)
+    }
+
+    #[cfg(target_family = "wasm")]
+    fn default() -> Self {
+        SaveSlots {
+            prefix: "adv_civ.saves.".to_string(),
+        }
+    }
+}
+
+const META_SUFFIX: &str = ".meta.json";
+const SAVE_SUFFIX: &str = ".json";
+
+impl SaveSlots {
+    #[cfg(not(target_family = "wasm"))]
+    pub fn in_dir(root: impl Into<std::path::PathBuf>) -> Self {
+        SaveSlots { root: root.into() }
+    }
+
+    /// Every slot, newest first. Slots whose metadata can't be read are
+    /// skipped rather than hiding the rest.
+    pub fn list(&self) -> Vec<SaveSlotInfo> {
+        let mut slots: Vec<SaveSlotInfo> = self
+            .keys()
+            .iter()
+            .filter(|key| key.ends_with(META_SUFFIX))
+            .filter_map(|key| self.read(key).ok())
+            .filter_map(|json| serde_json::from_str(&json).ok())
+            .collect();
+        slots.sort_by_key(|s| std::cmp::Reverse(s.saved_at));
+        slots
+    }
+
+    pub fn latest(&self) -> Option<SaveSlotInfo> {
+        self.list().into_iter().next()
+    }
+
+    pub fn info(&self, id: &str) -> Option<SaveSlotInfo> {
+        let json = self.read(&format!("{id}{META_SUFFIX}")).ok()?;
+        serde_json::from_str(&json).ok()
+    }
+
+    /// The slot's save file, as written (run it through `load_save_json`).
+    pub fn read_save(&self, id: &str) -> Result<String, String> {
+        self.read(&format!("{id}{SAVE_SUFFIX}"))
+    }
+
+    pub fn write(&self, info: &SaveSlotInfo, save_json: &str) -> Result<(), String> {
+        let meta = serde_json::to_string_pretty(info)
+            .map_err(|e| format!("Failed to serialize slot info: {e}"))?;
+        self.write_key(&format!("{}{SAVE_SUFFIX}", info.id), save_json)?;
+        self.write_key(&format!("{}{META_SUFFIX}", info.id), &meta)
+    }
+
+    pub fn rename(&self, id: &str, name: &str) -> Result<(), String> {
+        let mut info = self
+            .info(id)
+            .ok_or_else(|| format!("No save slot `{id}`"))?;
+        info.name = name.to_string();
+        let meta = serde_json::to_string_pretty(&info)
+            .map_err(|e| format!("Failed to serialize slot info: {e}"))?;
+        self.write_key(&format!("{id}{META_SUFFIX}"), &meta)
+    }
+
+    pub fn delete(&self, id: &str) -> Result<(), String> {
+        self.remove(&format!("{id}{SAVE_SUFFIX}"))?;
+        self.remove(&format!("{id}{META_SUFFIX}"))
+    }
+
+    /// `base`, or `base-2`, `base-3`, ... if that id is taken.
+    pub fn unused_id(&self, base: &str) -> String {
+        let taken = |id: &str| self.info(id).is_some();
+        if !taken(base) {
+            return base.to_string();
+        }
+        (2..)
+            .map(|n| format!("{base}-{n}"))
+            .find(|id| !taken(id))
+            .expect("some suffix is free")
+    }
+}
+
+#[cfg(not(target_family = "wasm"))]
+impl SaveSlots {
+    /// Copies a pre-slot save file into the named slot `id`, unless that slot
+    /// already exists. The file itself is left alone. `None` when there was
+    /// nothing to import.
+    pub fn import_legacy(
+        &self,
+        path: &std::path::Path,
+        id: &str,
+        now: u64,
+    ) -> Result<Option<SaveSlotInfo>, String> {
+        if self.info(id).is_some() || !path.exists() {
+            return Ok(None);
+        }
+        let json = std::fs::read_to_string(path).map_err(|e| format!("{}: {e}", path.display()))?;
+        let save = load_save_json(&json)?;
+        let name = format!("Imported {}", path.display());
+        let info = SaveSlotInfo::describe(id.to_string(), name, SlotKind::Named, now, &save);
+        self.write(&info, &json)?;
+        Ok(Some(info))
+    }
+
+    fn keys(&self) -> Vec<String> {
+        std::fs::read_dir(&self.root)
+            .map(|entries| {
+                entries
+                    .filter_map(Result::ok)
+                    .filter_map(|e| e.file_name().into_string().ok())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    fn read(&self, key: &str) -> Result<String, String> {
+        let path = self.root.join(key);
+        std::fs::read_to_string(&path).map_err(|e| format!("{}: {e}", path.display()))
+    }
+
+    fn write_key(&self, key: &str, contents: &str) -> Result<(), String> {
+        std::fs::create_dir_all(&self.root).map_err(|e| format!("{}: {e}", self.root.display()))?;
+        let path = self.root.join(key);
+        std::fs::write(&path, contents).map_err(|e| format!("{}: {e}", path.display()))
+    }
+
+    fn remove(&self, key: &str) -> Result<(), String> {
+        let path = self.root.join(key);
+        match std::fs::remove_file(&path) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+                Err(format!("{}: {e}", path.display()))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(target_family = "wasm")]
+impl SaveSlots {
+    fn storage() -> Result<web_sys::Storage, String> {
+        web_sys::window()
+            .and_then(|w| w.local_storage().ok().flatten())
+            .ok_or_else(|| "Browser local storage is unavailable".to_string())
+    }
+
+    fn keys(&self) -> Vec<String> {
+        let Ok(storage) = Self::storage() else {
+            return Vec::new();
+        };
+        let len = storage.length().unwrap_or(0);
+        (0..len)
+            .filter_map(|i| storage.key(i).ok().flatten())
+            .filter_map(|key| key.strip_prefix(&self.prefix).map(str::to_string))
+            .collect()
+    }
+
+    fn read(&self, key: &str) -> Result<String, String> {
+        Self::storage()?
+            .get_item(&format!("{}{key}", self.prefix))
+            .ok()
+            .flatten()
+            .ok_or_else(|| format!("No `{key}` in local storage"))
+    }
+
+    fn write_key(&self, key: &str, contents: &str) -> Result<(), String> {
+        // Fails once the origin's storage quota (~5 MB) is used up.
+        Self::storage()?
+            .set_item(&format!("{}{key}", self.prefix), contents)
+            .map_err(|_| format!("Local storage refused `{key}` (quota full?)"))
+    }
+
+    fn remove(&self, key: &str) -> Result<(), String> {
+        Self::storage()?
+            .remove_item(&format!("{}{key}", self.prefix))
+            .map_err(|_| format!("Local storage refused to remove `{key}`"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::civilization::concepts::save_game::{
+        SAVE_GAME_VERSION, SavedPhaseState, SavedPlayer,
+    };
+
+    fn player(faction: GameFaction, ast_space: u32, is_human: bool) -> SavedPlayer {
+        SavedPlayer {
+            name: format!("{faction:?}"),
+            faction,
+            is_human,
+            census_population: 0,
+            treasury: 0,
+            tokens_in_stock: 47,
+            city_tokens_in_stock: 9,
+            trade_cards: vec![],
+            done_with_current_activity: false,
+            ast_space,
+            owned_civ_cards: vec![],
+            calamity_traded_by: vec![],
+        }
+    }
+
+    fn save(players: Vec<SavedPlayer>) -> GameSaveData {
+        GameSaveData {
+            version: SAVE_GAME_VERSION.to_string(),
+            round: 4,
+            game_activity: GameActivity::Movement,
+            players,
+            area_populations: vec![],
+            census_order: vec![],
+            left_to_move: vec![],
+            current_mover: None,
+            phase_state: SavedPhaseState::default(),
+        }
+    }
+
+    fn slot(id: &str, kind: SlotKind, saved_at: u64) -> SaveSlotInfo {
+        SaveSlotInfo::describe(
+            id.to_string(),
+            id.to_string(),
+            kind,
+            saved_at,
+            &save(vec![]),
+        )
+    }
+
+    #[test]
+    fn metadata_names_the_ast_leader() {
+        let save = save(vec![
+            player(GameFaction::Egypt, 3, true),
+            player(GameFaction::Crete, 2, false),
+        ]);
+        let info = SaveSlotInfo::describe("a".into(), "A".into(), SlotKind::Named, 0, &save);
+        assert_eq!(info.round, 4);
+        assert_eq!(info.factions, vec![GameFaction::Egypt, GameFaction::Crete]);
+        assert_eq!(info.ast_leader, Some(GameFaction::Egypt));
+        assert!(
+            info.summary.contains("Egypt leads the A.S.T."),
+            "{}",
+            info.summary
+        );
+        assert!(
+            info.summary.contains("2 players, 1 human"),
+            "{}",
+            info.summary
+        );
+    }
+
+    #[test]
+    fn a_tie_on_the_ast_has_no_leader() {
+        let save = save(vec![
+            player(GameFaction::Egypt, 3, true),
+            player(GameFaction::Crete, 3, false),
+        ]);
+        let info = SaveSlotInfo::describe("a".into(), "A".into(), SlotKind::Named, 0, &save);
+        assert_eq!(info.ast_leader, None);
+    }
+
+    #[test]
+    fn only_the_oldest_autosaves_are_pruned() {
+        let mut slots: Vec<SaveSlotInfo> = (0..MAX_AUTOSAVES as u64 + 2)
+            .map(|t| slot(&format!("autosave-{t}"), SlotKind::Autosave, t))
+            .collect();
+        slots.push(slot("ancient", SlotKind::Named, 0));
+        assert_eq!(autosaves_to_prune(&slots), vec!["autosave-0", "autosave-1"]);
+    }
+
+    #[test]
+    fn slots_round_trip_through_a_directory() {
+        let dir = std::env::temp_dir().join(format!("adv_civ_slots_{}", std::process::id()));
+        let slots = SaveSlots::in_dir(&dir);
+
+        let older = slot("first", SlotKind::Named, 10);
+        let newer = slot("second", SlotKind::Quicksave, 20);
+        slots.write(&older, "{}").unwrap();
+        slots.write(&newer, "{\"x\": 1}").unwrap();
+
+        let listed: Vec<String> = slots.list().into_iter().map(|s| s.id).collect();
+        assert_eq!(listed, vec!["second", "first"]);
+        assert_eq!(slots.read_save("second").unwrap(), "{\"x\": 1}");
+        assert_eq!(slots.unused_id("first"), "first-2");
+
+        slots.rename("first", "My long game").unwrap();
+        assert_eq!(slots.info("first").unwrap().name, "My long game");
+
+        slots.delete("second").unwrap();
+        assert_eq!(slots.latest().unwrap().id, "first");
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn a_legacy_save_is_imported_into_a_named_slot_once() {
+        let dir = std::env::temp_dir().join(format!("adv_civ_legacy_{}", std::process::id()));
+        let slots = SaveSlots::in_dir(dir.join("saves"));
+        let legacy = dir.join("savegame.json");
+        std::fs::create_dir_all(&dir).unwrap();
+        let game = save(vec![player(GameFaction::Egypt, 2, true)]);
+        std::fs::write(&legacy, serde_json::to_string(&game).unwrap()).unwrap();
+
+        let imported = slots.import_legacy(&legacy, "legacy", 50).unwrap().unwrap();
+        assert_eq!(imported.kind, SlotKind::Named);
+        assert_eq!(imported.ast_leader, Some(GameFaction::Egypt));
+        assert!(load_save_json(&slots.read_save("legacy").unwrap()).is_ok());
+        assert!(legacy.exists());
+
+        assert_eq!(slots.import_legacy(&legacy, "legacy", 60).unwrap(), None);
+        assert_eq!(slots.info("legacy").unwrap().saved_at, 50);
+        let missing = dir.join("savegame.bak");
+        assert_eq!(slots.import_legacy(&missing, "backup", 60).unwrap(), None);
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn age_reads_like_a_person_would_say_it() {
+        let info = slot("a", SlotKind::Named, 1_000);
+        assert_eq!(info.age(1_030), "just now");
+        assert_eq!(info.age(1_000 + 5 * 60), "5 min ago");
+        assert_eq!(info.age(1_000 + 2 * 86_400), "2 days ago");
+    }
+}
@@ -11,9 +11,14 @@ use crate::civilization::concepts::ships::ship_ui_components::{
 };
 use crate::loading::TextureAssets;
 use crate::player::Player;
-use crate::stupid_ai::{AgentControlled, IsHuman};
+use crate::stupid_ai::{
+    AgentControlled, AreaSummary, AreaSummaryQueryData, IsHuman, Personality, ShipReach, Weights,
+    plan_ship_builds,
+};
+use bevy::platform::collections::HashMap;
 use bevy::prelude::{
-    Commands, Entity, Has, Name, NextState, Query, Res, ResMut, Sprite, Transform, With, info,
+    Commands, Entity, Has, Name, NextState, Query, Res, ResMut, Sprite, Transform, With, debug,
+    info,
 };
 
 /// Rule 22.1/22.11: ships are built (and maintained) in census order; players
@@ -36,7 +41,8 @@ pub fn ship_build_order(
 /// treasury or by a levy from the area the ship occupies. Unpaid ships
 /// return to stock.
 ///
-/// Pass 2 — Building: AI players auto-build. Human players are paused here:
+/// Pass 2 — Building: AI players build where `plan_ship_builds` finds a ship
+/// worth its cost. Human players are paused here:
 /// `ShipConstructionState` is populated and `AwaitingShipPlacement` is inserted;
 /// `advance_ship_construction` waits until the human confirms before transitioning.
 pub fn enter_ship_construction(
@@ -53,7 +59,8 @@ pub fn enter_ship_construction(
         ),
         With<Player>,
     >,
-    sea_passage_query: Query<Has<SeaPassage>>,
+    sea_passage_query: Query<&SeaPassage>,
+    area_info_query: Query<(Entity, AreaSummaryQueryData), With<GameArea>>,
     area_transform_query: Query<&Transform, With<GameArea>>,
     mut area_pop_query: Query<&mut Population, With<GameArea>>,
     mut commands: Commands,
@@ -62,6 +69,7 @@ pub fn enter_ship_construction(
     textures: Res<TextureAssets>,
     game_info: Res<GameInfoAndStuff>,
     civ_cards_query: Query<&PlayerCivilizationCards>,
+    personality_query: Query<&Personality>,
 ) {
     let mut human_needs_input = false;
 
@@ -154,7 +162,11 @@ pub fn enter_ship_construction(
             let mut available_areas: Vec<Entity> = player_areas
                 .areas()
                 .into_iter()
-                .filter(|&a| sea_passage_query.get(a).unwrap_or(false))
+                .filter(|&a| {
+                    sea_passage_query
+                        .get(a)
+                        .is_ok_and(|s| !s.to_areas.is_empty())
+                })
                 .collect();
             if available_areas.is_empty() {
                 available_areas = player_areas.areas().into_iter().collect();
@@ -189,72 +201,93 @@ pub fn enter_ship_construction(
                 player_entity
             );
         } else {
-            // AI: prefer a coastal area, fall back to any area.
-            let candidate_area = player_areas
-                .areas()
-                .into_iter()
-                .find(|&a| sea_passage_query.get(a).unwrap_or(false))
-                .or_else(|| player_areas.areas().into_iter().next());
-            let Some(area) = candidate_area else { continue };
-
-            // Check affordability: treasury + area tokens must cover 2. The levy
-            // must leave at least 1 token in the area — never depopulate it for a
-            // ship, or the player wipes out their only city on round 1 (treasury
-            // is empty early, so a naive levy of 2 from a 2-token start area would
-            // empty the board). Only spare tokens above 1 are leviable.
-            let treasury_tokens = treasury.tokens_in_treasury();
-            let area_tokens = area_pop_query
-                .get(area)
-                .map_or(0, |pop| pop.population_for_player(player_entity));
-            let spare_area_tokens = area_tokens.saturating_sub(1);
-            if treasury_tokens + spare_area_tokens < 2 {
-                continue;
-            }
-
-            let Some(ship_entity) = ship_stock.take_ship() else {
-                continue;
+            // AI: build only where a ship reaches coast the tokens can't walk
+            // to, best port first (see `plan_ship_builds`).
+            let areas: HashMap<Entity, AreaSummary> = area_info_query
+                .iter()
+                .filter_map(|(area, info)| {
+                    let pop = area_pop_query.get(area).ok()?;
+                    Some((area, AreaSummary::from_components(player_entity, pop, info)))
+                })
+                .collect();
+            let mut candidates: Vec<Entity> = player_areas.areas().into_iter().collect();
+            candidates.sort();
+            let ships_by_area: HashMap<Entity, usize> = player_ships
+                .ships_by_area
+                .iter()
+                .map(|(area, ships)| (*area, ships.len()))
+                .collect();
+            let owns = |card: CivCardName| {
+                civ_cards_query
+                    .get(player_entity)
+                    .is_ok_and(|c| c.owns(&card))
             };
+            let reach =
+                ShipReach::for_cards(owns(CivCardName::ClothMaking), owns(CivCardName::Astronomy));
+            // Agent-controlled players have no personality; build for them
+            // like a balanced AI.
+            let weights = personality_query
+                .get(player_entity)
+                .map_or(Weights::uniform(0.5), |p| p.weights);
+            let buildable =
+                (ShipStock::MAX_SHIPS - ships_on_board).min(ship_stock.count_in_stock());
+            let plan = plan_ship_builds(
+                &candidates,
+                treasury.tokens_in_treasury(),
+                &ships_by_area,
+                buildable,
+                &areas,
+                reach,
+                &weights,
+            );
 
-            // Pay: prefer treasury, levy the remainder from the area.
-            let from_treasury = treasury_tokens.min(2);
-            let from_levy = 2 - from_treasury;
-            for _ in 0..from_treasury {
-                // Return spent treasury tokens to stock (same finite pool).
-                if let Some(token) = treasury.remove_token_from_treasury() {
-                    commands.entity(token).insert(ReturnTokenToStock);
+            for build in plan {
+                let Some(ship_entity) = ship_stock.take_ship() else {
+                    break;
+                };
+
+                // Pay: treasury first, levy the remainder from the area. The
+                // plan never levies an area's last token.
+                for _ in 0..build.from_treasury {
+                    // Return spent treasury tokens to stock (same finite pool).
+                    if let Some(token) = treasury.remove_token_from_treasury() {
+                        commands.entity(token).insert(ReturnTokenToStock);
+                    }
                 }
-            }
-            if from_levy > 0
-                && let Ok(mut pop) = area_pop_query.get_mut(area)
-                && let Some(levied) = pop.remove_tokens_from_area(&player_entity, from_levy)
-            {
-                for token in levied {
-                    commands.entity(token).insert(ReturnTokenToStock);
+                if build.from_levy > 0
+                    && let Ok(mut pop) = area_pop_query.get_mut(build.area)
+                    && let Some(levied) =
+                        pop.remove_tokens_from_area(&player_entity, build.from_levy)
+                {
+                    for token in levied {
+                        commands.entity(token).insert(ReturnTokenToStock);
+                    }
                 }
-            }
 
-            let area_pos = area_transform_query
-                .get(area)
-                .map(|t| t.translation.truncate())
-                .unwrap_or_default();
-            commands.entity(ship_entity).insert((
-                Sprite {
-                    image: textures.ship.clone(),
-                    ..Default::default()
-                },
-                Transform::from_xyz(area_pos.x, area_pos.y, 2.0),
-            ));
-
-            player_ships.place_ship(area, ship_entity);
-            info!(
-                "[SHIPS] {} builds a ship at {:?} (treasury: {}, levy: {}) (fleet: {}/{})",
-                name,
-                area,
-                from_treasury,
-                from_levy,
-                player_ships.total_ships_on_board(),
-                ShipStock::MAX_SHIPS
-            );
+                let area_pos = area_transform_query
+                    .get(build.area)
+                    .map(|t| t.translation.truncate())
+                    .unwrap_or_default();
+                commands.entity(ship_entity).insert((
+                    Sprite {
+                        image: textures.ship.clone(),
+                        ..Default::default()
+                    },
+                    Transform::from_xyz(area_pos.x, area_pos.y, 2.0),
+                ));
+
+                player_ships.place_ship(build.area, ship_entity);
+                debug!("[SHIPS] {} ship score: {:?}", name, build.breakdown);
+                info!(
+                    "[SHIPS] {} builds a ship at {:?} (treasury: {}, levy: {}) (fleet: {}/{})",
+                    name,
+                    build.area,
+                    build.from_treasury,
+                    build.from_levy,
+                    player_ships.total_ships_on_board(),
+                    ShipStock::MAX_SHIPS
+                );
+            }
         }
     }
 
@@ -2,13 +2,16 @@ use bevy::prelude::*;
 use bevy::ui::ZIndex;
 use lava_ui_builder::{LavaTheme, TextStyle, UIBuilder};
 
-use crate::civilization::Z_PANEL;
 use crate::civilization::components::Faction;
 use crate::civilization::concepts::succession::succession_components::{
     AstEpoch, AstPosition, AstTrack,
 };
 use crate::civilization::enums::GameFaction;
+use crate::civilization::{AvailableCivCards, Z_PANEL};
 use crate::player::Player;
+use crate::stupid_ai::{
+    AgentControlled, IsHuman, PlayerPosition, PositionQueryData, PositionSnapshot,
+};
 
 /// Root node of the A.S.T. panel.
 #[derive(Component)]
@@ -19,6 +22,10 @@ pub struct AstUiRoot;
 #[derive(Component)]
 pub struct AstTrackRow;
 
+/// The "who's winning" line under the track.
+#[derive(Component)]
+pub struct AstStandingsText;
+
 /// One space on the rendered track.
 #[derive(Component)]
 pub struct AstCell {
@@ -173,6 +180,12 @@ pub fn spawn_ast_ui(
         }
     });
 
+    // Standings, filled in by `update_ast_standings`.
+    ui.with_child(|t| {
+        t.insert(AstStandingsText)
+            .with_text("", Some(TextStyle::size_color(11.0, Color::WHITE)));
+    });
+
     ui.build();
 }
 
@@ -226,6 +239,43 @@ pub fn update_ast_markers(
     }
 }
 
+/// Ranks the players by `evaluate_position` for the standings line, best
+/// first. Only the player at the keyboard's own hand is counted, as in
+/// `GameSaveData::public_view`: everyone else's cards are hidden, and a drawn
+/// calamity must not show as a drop in the ranking.
+pub fn update_ast_standings(
+    player_query: Query<PositionQueryData, With<Player>>,
+    local_human: Query<&Faction, (With<IsHuman>, Without<AgentControlled>)>,
+    civ_card_defs: Option<Res<AvailableCivCards>>,
+    mut text_query: Query<&mut Text, With<AstStandingsText>>,
+) {
+    let Ok(mut text) = text_query.single_mut() else {
+        return;
+    };
+    let viewer = local_human.single().ok().map(|f| f.faction);
+    let snapshot = PositionSnapshot {
+        players: player_query
+            .iter()
+            .map(|p| {
+                let mut position = PlayerPosition::from_components(p, civ_card_defs.as_deref());
+                if Some(position.faction) != viewer {
+                    position.trade_cards.clear();
+                }
+                position
+            })
+            .collect(),
+    };
+    let standings: Vec<String> = snapshot
+        .ranking()
+        .iter()
+        .map(|(faction, value)| format!("{faction:?} {value:.2}"))
+        .collect();
+    let line = format!("Leading: {}", standings.join("  "));
+    if text.0 != line {
+        text.0 = line;
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -275,6 +325,78 @@ mod tests {
         assert_eq!(roots, 1, "single panel root");
     }
 
+    #[test]
+    fn the_standings_line_puts_the_best_placed_player_first() {
+        use crate::civilization::{Census, PlayerCities, PlayerTradeCards, Treasury};
+
+        let mut app = ui_app();
+        for (faction, space) in [(GameFaction::Egypt, 2), (GameFaction::Crete, 6)] {
+            app.world_mut().spawn((
+                Player,
+                Faction::new(faction),
+                AstPosition::new(space),
+                PlayerCities::default(),
+                Census::default(),
+                Treasury::default(),
+                PlayerTradeCards::default(),
+            ));
+        }
+
+        app.world_mut().run_system_once(spawn_ast_ui).unwrap();
+        app.world_mut()
+            .run_system_once(update_ast_standings)
+            .unwrap();
+
+        let mut q = app
+            .world_mut()
+            .query_filtered::<&Text, With<AstStandingsText>>();
+        let line = q.single(app.world()).unwrap().0.clone();
+        assert!(line.starts_with("Leading: Crete"), "{line}");
+        assert!(line.contains("Egypt"), "{line}");
+    }
+
+    #[test]
+    fn the_standings_line_ignores_what_other_players_hold() {
+        use crate::civilization::{Census, PlayerCities, PlayerTradeCards, TradeCard, Treasury};
+
+        let standings = |crete_hand: PlayerTradeCards| {
+            let mut app = ui_app();
+            for (faction, hand) in [
+                (GameFaction::Egypt, PlayerTradeCards::default()),
+                (GameFaction::Crete, crete_hand),
+            ] {
+                let player = app
+                    .world_mut()
+                    .spawn((
+                        Player,
+                        Faction::new(faction),
+                        AstPosition::new(2),
+                        PlayerCities::default(),
+                        Census::default(),
+                        Treasury::default(),
+                        hand,
+                    ))
+                    .id();
+                if faction == GameFaction::Egypt {
+                    app.world_mut().entity_mut(player).insert(IsHuman);
+                }
+            }
+            app.world_mut().run_system_once(spawn_ast_ui).unwrap();
+            app.world_mut()
+                .run_system_once(update_ast_standings)
+                .unwrap();
+            let mut q = app
+                .world_mut()
+                .query_filtered::<&Text, With<AstStandingsText>>();
+            q.single(app.world()).unwrap().0.clone()
+        };
+
+        let mut struck = PlayerTradeCards::default();
+        struck.add_trade_card(TradeCard::VolcanoEarthquake);
+        struck.add_trade_cards(TradeCard::Gold, 3);
+        assert_eq!(standings(struck), standings(PlayerTradeCards::default()));
+    }
+
     #[test]
     fn markers_sharing_a_space_do_not_overlap() {
         let mut app = ui_app();
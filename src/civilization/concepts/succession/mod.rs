@@ -4,7 +4,8 @@ mod succession_plugin;
 pub mod succession_systems;
 
 pub use ast_ui_systems::{
-    AstCell, AstMarker, AstUiRoot, ast_faction_color, spawn_ast_ui, update_ast_markers,
+    AstCell, AstMarker, AstStandingsText, AstUiRoot, ast_faction_color, spawn_ast_ui,
+    update_ast_markers, update_ast_standings,
 };
 pub use succession_components::*;
 pub use succession_plugin::SuccessionPlugin;
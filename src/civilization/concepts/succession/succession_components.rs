@@ -47,7 +47,10 @@ impl AstPosition {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Reflect)]
+/// Ordered by time, so a later epoch compares greater.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, Reflect,
+)]
 pub enum AstEpoch {
     StoneAge,
     EarlyBronze,
@@ -69,6 +72,17 @@ impl AstEpoch {
         }
     }
 
+    /// The epoch after this one, i.e. the next gate a marker has to pass.
+    pub fn next(&self) -> Option<AstEpoch> {
+        match self {
+            AstEpoch::StoneAge => Some(AstEpoch::EarlyBronze),
+            AstEpoch::EarlyBronze => Some(AstEpoch::LateBronze),
+            AstEpoch::LateBronze => Some(AstEpoch::EarlyIron),
+            AstEpoch::EarlyIron => Some(AstEpoch::LateIron),
+            AstEpoch::LateIron => None,
+        }
+    }
+
     /// Human-readable name for UI/logging.
     pub fn name(&self) -> &'static str {
         match self {
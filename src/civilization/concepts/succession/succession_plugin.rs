@@ -1,5 +1,5 @@
 use crate::civilization::concepts::succession::ast_ui_systems::{
-    spawn_ast_ui, toggle_ast_ui, update_ast_markers,
+    spawn_ast_ui, toggle_ast_ui, update_ast_markers, update_ast_standings,
 };
 use crate::civilization::concepts::succession::succession_components::{AstTrack, RoundLimit};
 use crate::civilization::concepts::succession::succession_systems::{
@@ -23,7 +23,8 @@ impl Plugin for SuccessionPlugin {
             .add_systems(OnEnter(GameActivity::StartGame), spawn_ast_ui)
             .add_systems(
                 Update,
-                (update_ast_markers, toggle_ast_ui).run_if(in_state(GameState::Playing)),
+                (update_ast_markers, update_ast_standings, toggle_ast_ui)
+                    .run_if(in_state(GameState::Playing)),
             );
     }
 }
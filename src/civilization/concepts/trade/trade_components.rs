@@ -2,8 +2,7 @@ use crate::civilization::concepts::acquire_trade_cards::PlayerTradeCards;
 use crate::civilization::concepts::acquire_trade_cards::TradeCard;
 use crate::civilization::game_moves::TradeMove;
 use bevy::platform::collections::{HashMap, HashSet};
-use bevy::prelude::{Component, Entity, Reflect};
-use rand::prelude::{IndexedRandom, ThreadRng};
+use bevy::prelude::{Component, Entity, Reflect, ReflectComponent};
 use std::collections::VecDeque;
 
 #[derive(Component, Default, Clone, PartialEq, Eq, Debug, Reflect)]
@@ -15,15 +14,18 @@ pub enum TradeButtonAction {
 }
 
 #[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
 pub struct InSettlement;
 
 #[derive(Component, Reflect, Debug, Default, Clone)]
+#[reflect(Component)]
 pub struct PlayerSettlements {
     pub trades: VecDeque<Entity>,
     pub current_trade: Option<Entity>,
 }
 
 #[derive(Component, Reflect, Clone, Debug, PartialEq, Default)]
+#[reflect(Component)]
 pub struct CanTrade;
 
 #[derive(Component, Reflect, Clone, Debug, PartialEq, Default)]
@@ -35,6 +37,7 @@ pub trait CoolDown {
 }
 
 #[derive(Debug, Component, Reflect, Clone, Eq, PartialEq)]
+#[reflect(Component)]
 pub struct PublishedOffer;
 
 #[derive(Component, Default)]
@@ -47,6 +50,7 @@ pub enum AvailableTradeOfferActions {
 }
 
 #[derive(Component, Debug, Reflect, Hash, Clone, Eq, PartialEq)]
+#[reflect(Component)]
 pub struct PlayerTradeInterests {
     pub wants: Vec<TradeCard>,
 }
@@ -57,13 +61,6 @@ impl PlayerTradeInterests {
             wants: player_trade_cards.wants(),
         }
     }
-
-    pub fn get_trade_thingie(&self, rng: &mut ThreadRng) -> HashMap<TradeCard, usize> {
-        HashMap::from([
-            (*self.wants.choose(&mut *rng).unwrap(), 2),
-            (*self.wants.choose(&mut *rng).unwrap(), 1),
-        ])
-    }
 }
 /*
 Step one, make trade infinitely simpler than what we have right now.
@@ -73,6 +70,7 @@ of story. No countering, no nothing. Everything else can grow from there.
  */
 
 #[derive(Debug, Component, Reflect, Clone, Eq, PartialEq)]
+#[reflect(Component)]
 pub struct TradeOffer {
     pub initiator: Entity,
     pub initiator_name: String,
@@ -309,6 +307,7 @@ impl TradeOffer {
 /// A simplified trade offer for the new trade UI system.
 /// Offers can be open (target = None) or directed at a specific player.
 #[derive(Debug, Component, Reflect, Clone, Eq, PartialEq)]
+#[reflect(Component)]
 pub struct OpenTradeOffer {
     pub creator: Entity,
     pub creator_name: String,
@@ -1,8 +1,9 @@
 use crate::civilization::concepts::acquire_trade_cards::TradeCard;
+use crate::civilization::concepts::trade::trade_components::OpenTradeOffer;
 use bevy::platform::collections::HashMap;
 use bevy::prelude::{Entity, Message, Reflect};
 
-#[derive(Message, Debug, Reflect)]
+#[derive(Message, Debug, Clone, Reflect)]
 pub struct SendTradingCardsCommand {
     pub sending_player: Entity,
     pub receiving_player: Entity,
@@ -22,3 +23,8 @@ impl SendTradingCardsCommand {
         }
     }
 }
+
+/// An `OpenTradeOffer` whose cards have been exchanged, as it stood at
+/// settlement: what each side stated and what each actually handed over.
+#[derive(Message, Debug, Clone)]
+pub struct OpenTradeOfferSettled(pub OpenTradeOffer);
@@ -1,22 +1,41 @@
 use crate::GameActivity;
-use crate::civilization::concepts::trade::trade_events::SendTradingCardsCommand;
+use crate::civilization::concepts::replay::ReplayPlayback;
+use crate::civilization::concepts::trade::trade_components::{
+    CanTrade, InSettlement, OpenTradeOffer, PlayerSettlements, PlayerTradeInterests,
+    PublishedOffer, TradeOffer,
+};
+use crate::civilization::concepts::trade::trade_events::{
+    OpenTradeOfferSettled, SendTradingCardsCommand,
+};
 use crate::civilization::concepts::trade::trade_resources::{
     CreateOfferState, TradeCountdown, TradePhaseState, TradeUiState,
 };
 use crate::civilization::concepts::trade::trade_systems::*;
 use crate::civilization::concepts::trade::trade_triggers::{can_trade_removed, offer_published};
+use crate::civilization::events::CommandApplied;
 use bevy::app::App;
-use bevy::prelude::{IntoScheduleConfigs, OnEnter, OnExit, Plugin, Update, in_state};
+use bevy::prelude::{
+    IntoScheduleConfigs, OnEnter, OnExit, Plugin, Update, in_state, not, resource_exists,
+};
 
 pub struct TradePlugin;
 
 impl Plugin for TradePlugin {
     fn build(&self, app: &mut App) {
-        app.insert_resource(TradeUiState::default())
+        app.register_type::<CanTrade>()
+            .register_type::<PlayerTradeInterests>()
+            .register_type::<PlayerSettlements>()
+            .register_type::<OpenTradeOffer>()
+            .register_type::<TradeOffer>()
+            .register_type::<PublishedOffer>()
+            .register_type::<InSettlement>()
+            .insert_resource(TradeUiState::default())
             .init_resource::<TradeCountdown>()
             .init_resource::<TradePhaseState>()
             .init_resource::<CreateOfferState>()
             .add_message::<SendTradingCardsCommand>()
+            .add_message::<CommandApplied<SendTradingCardsCommand>>()
+            .add_message::<OpenTradeOfferSettled>()
             .add_systems(
                 OnEnter(GameActivity::Trade),
                 (setup_trade, setup_trade_phase_ui),
@@ -80,7 +99,8 @@ impl Plugin for TradePlugin {
                     finalize_settled_open_offers,
                     ai_stop_trading_when_ready,
                 )
-                    .run_if(in_state(GameActivity::Trade)),
+                    .run_if(in_state(GameActivity::Trade))
+                    .run_if(not(resource_exists::<ReplayPlayback>)),
             )
             .add_systems(Update, button_action)
             .add_observer(offer_published)
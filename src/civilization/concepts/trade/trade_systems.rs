@@ -6,20 +6,25 @@ use crate::civilization::concepts::trade::trade_components::{
     InSettlement, NeedsTradeMove, OpenOffersListContainer, OpenTradeOffer, PlayerSettlements,
     PlayerTradeInterests, PublishedOffer, SettlementModal, TradeButtonAction, TradeOffer,
 };
-use crate::civilization::concepts::trade::trade_events::SendTradingCardsCommand;
+use crate::civilization::concepts::trade::trade_events::{
+    OpenTradeOfferSettled, SendTradingCardsCommand,
+};
 use crate::civilization::concepts::trade::trade_resources::{
     CreateOfferState, TradeCountdown, TradePhaseState, TradeUiState,
 };
+use crate::civilization::events::CommandApplied;
 use crate::civilization::game_moves::RecalculatePlayerMoves;
 use crate::civilization::game_moves::{
     AcquireCivilizationCardsMove, AvailableMoves, GameMove, TradeMove,
 };
 use crate::civilization::{
-    AvailableCivCards, CivCardName, PlayerCivilizationCards, TradeCardTrait, TradePhaseUiRoot,
+    AstPosition, AvailableCivCards, CivCardName, GameRng, PlayerCivilizationCards, TradeCardTrait,
+    TradePhaseUiRoot,
 };
 use crate::stupid_ai::{
-    IsHuman, Personality, accepts_calamity_offload, offer_creation_chance, stop_trading_threshold,
-    trade_accept_margin,
+    HiddenCardPlay, IsHuman, NEUTRAL_TRUST, Personality, TradeTrust, goal_gives, goal_wants,
+    hidden_card_play, offer_creation_chance, score_trade_for_goal, stop_trading_threshold,
+    trade_accept_margin, trade_goal,
 };
 use bevy::platform::collections::HashMap;
 use bevy::prelude::*;
@@ -245,6 +250,7 @@ pub fn trigger_trade_moves(
 pub fn handle_send_trading_cards_command(
     mut command_reader: MessageReader<SendTradingCardsCommand>,
     mut player_trading_cards: Query<&mut PlayerTradeCards>,
+    mut applied: MessageWriter<CommandApplied<SendTradingCardsCommand>>,
 ) {
     for event in command_reader.read() {
         debug!("Sending trading cards!");
@@ -259,12 +265,17 @@ pub fn handle_send_trading_cards_command(
             let mut target_trade_cards = player_trading_cards
                 .get_mut(event.receiving_player)
                 .unwrap();
-            for (card, count) in cards_to_send {
+            for (card, count) in &cards_to_send {
                 // Records the sender for any calamity in the bundle: rule
                 // 29.61 bars them from being a secondary victim of it, and
                 // 30.221 makes them the beneficiary of Treachery.
-                target_trade_cards.add_traded_cards(card, count, event.sending_player);
+                target_trade_cards.add_traded_cards(*card, *count, event.sending_player);
             }
+            applied.write(CommandApplied(SendTradingCardsCommand::new(
+                event.sending_player,
+                event.receiving_player,
+                cards_to_send,
+            )));
         }
     }
 }
@@ -2603,16 +2614,29 @@ pub fn despawn_settlement_modal(
 // AI TRADE BEHAVIOR
 // ============================================================================
 
-/// AI creates trade offers based on their cards and needs
+/// AI creates trade offers aimed at its next civ-card purchase: it asks for
+/// the set completions that add the most buying power and offers the cards
+/// it misses least.
+#[allow(clippy::type_complexity)]
 pub fn ai_create_trade_offers(
     mut commands: Commands,
     ai_players: Query<
-        (Entity, &Name, &PlayerTradeCards, &CanTrade, &Personality),
+        (
+            Entity,
+            &Name,
+            &PlayerTradeCards,
+            &CanTrade,
+            &Personality,
+            Option<&PlayerCivilizationCards>,
+            Option<&AstPosition>,
+        ),
         Without<IsHuman>,
     >,
     existing_offers: Query<&OpenTradeOffer>,
+    civ_card_defs: Option<Res<AvailableCivCards>>,
     time: Res<Time>,
     mut ai_offer_timer: Local<f32>,
+    mut game_rng: ResMut<GameRng>,
 ) {
     // Only create offers periodically (every 3-5 seconds)
     *ai_offer_timer += time.delta_secs();
@@ -2621,11 +2645,11 @@ pub fn ai_create_trade_offers(
     }
     *ai_offer_timer = 0.0;
 
-    let mut rng = rand::rng();
-    for (ai_entity, ai_name, ai_cards, _, personality) in ai_players.iter() {
+    let rng = game_rng.ai_rng();
+    for (ai_entity, ai_name, ai_cards, _, personality, civ_cards, ast) in ai_players.iter() {
         // Personality gate: eager traders flood the table with offers, reluctant
         // ones mostly sit the phase out.
-        if rng.random::<f32>() > offer_creation_chance(&personality.weights) {
+        if rng.random::<f32>() > offer_creation_chance(personality) {
             continue;
         }
 
@@ -2638,84 +2662,54 @@ pub fn ai_create_trade_offers(
             continue;
         }
 
-        // Get AI's commodity cards
-        let commodities: Vec<(TradeCard, usize)> = ai_cards.commodity_cards().into_iter().collect();
-        if commodities.len() < 2 {
+        // Ask for the two cards that close most of the gap to the planned
+        // purchase, and offer the two whose loss costs the least.
+        let goal = civ_card_defs
+            .as_deref()
+            .and_then(|defs| trade_goal(ai_cards, civ_cards, ast, defs, personality));
+        let wanting_guaranteed = goal_wants(ai_cards, goal.as_ref(), 2);
+        let offering_guaranteed = goal_gives(ai_cards, 2, &wanting_guaranteed);
+        let cards_wanted: usize = wanting_guaranteed.values().sum();
+        let cards_offered: usize = offering_guaranteed.values().sum();
+        if cards_wanted < 2 || cards_offered < 2 {
             continue; // Not enough cards to trade
         }
 
-        // Pick 2 random commodities to offer as guaranteed
-        let mut offering_guaranteed: HashMap<TradeCard, usize> = HashMap::default();
-        let mut cards_offered = 0;
-
-        // Prefer offering lower-value cards
-        let mut sorted_commodities: Vec<_> =
-            commodities.iter().filter(|(_, count)| *count > 0).collect();
-        sorted_commodities.sort_by_key(|(card, _)| card.value());
-
-        for (card, count) in sorted_commodities.iter().take(3) {
-            if cards_offered >= 2 {
-                break;
-            }
-            let to_offer = (*count).min(2 - cards_offered);
-            if to_offer > 0 {
-                offering_guaranteed.insert(*card, to_offer);
-                cards_offered += to_offer;
-            }
+        // Only put it on the table if the guaranteed cards move us towards the
+        // planned purchase. How we pay the hidden card is decided at settlement,
+        // against whoever accepts; an open offer is pitched at a stranger.
+        let before = ai_cards.total_stack_value() as u32;
+        let mut simulated = ai_cards.clone();
+        for (card, count) in &offering_guaranteed {
+            simulated.remove_n_trade_cards(*count, *card);
         }
-
-        if cards_offered < 2 {
-            continue;
+        for (card, count) in &wanting_guaranteed {
+            simulated.add_trade_cards(*card, *count);
         }
-
-        // Pick what AI wants — only request cards up to their own max card value.
-        // An AI with max value 4 cards should never ask for Gold (9) or Ivory (9).
-        let mut wanting_guaranteed: HashMap<TradeCard, usize> = HashMap::default();
-        let mut cards_wanted = 0;
-
-        let ai_card_counts: HashMap<TradeCard, usize> = commodities.iter().copied().collect();
-        let max_own_value = commodities
-            .iter()
-            .map(|(card, _)| card.value())
-            .max()
-            .unwrap_or(0);
-
-        // Want commodities that: (a) are at or below our max value, (b) we have few of,
-        // (c) prefer higher value within that range (to try completing sets)
-        let mut desired: Vec<TradeCard> = TradeCard::iter()
-            .filter(adv_civ_protocol::TradeCardTrait::is_commodity)
-            .filter(|c| c.value() <= max_own_value)
-            .filter(|c| ai_card_counts.get(c).copied().unwrap_or(0) < 2)
-            .collect();
-        desired.sort_by_key(|c| std::cmp::Reverse(c.value()));
-
-        for card in desired.iter().take(3) {
-            if cards_wanted >= 2 {
-                break;
-            }
-            let to_want = 1.min(2 - cards_wanted);
-            wanting_guaranteed.insert(*card, to_want);
-            cards_wanted += to_want;
-        }
-
-        if cards_wanted < 2 {
-            // Fallback: want any commodity at or below our max value
-            for card in TradeCard::iter()
-                .filter(adv_civ_protocol::TradeCardTrait::is_commodity)
-                .filter(|c| c.value() <= max_own_value)
-            {
-                if cards_wanted >= 2 {
-                    break;
-                }
-                if !wanting_guaranteed.contains_key(&card) {
-                    wanting_guaranteed.insert(card, 1);
-                    cards_wanted += 1;
-                }
-            }
+        let after = simulated.total_stack_value() as u32;
+        let play = hidden_card_play(
+            personality,
+            NEUTRAL_TRUST,
+            ai_cards.has_tradeable_calamity(),
+        );
+        let offloads_calamity = play == HiddenCardPlay::SlipCalamity;
+        if score_trade_for_goal(before, after, goal.as_ref(), offloads_calamity, personality) <= 0.0
+        {
+            continue;
         }
 
         // Add hidden cards to reach minimum 3 each side
-        let offering_hidden = 3_usize.saturating_sub(cards_offered);
+        // A bluffer over-promises: one more hidden card, paid in junk, as long
+        // as the hand can actually pay it at settlement.
+        let spare_cards = ai_cards
+            .commodity_cards()
+            .values()
+            .sum::<usize>()
+            .saturating_sub(cards_offered);
+        let mut offering_hidden = 3_usize.saturating_sub(cards_offered);
+        if play == HiddenCardPlay::Bluff && spare_cards > offering_hidden {
+            offering_hidden += 1;
+        }
         let wanting_hidden = 3_usize.saturating_sub(cards_wanted);
 
         // Create the offer
@@ -2732,8 +2726,11 @@ pub fn ai_create_trade_offers(
     }
 }
 
-/// AI accepts trade offers that benefit them.
-/// Criteria: The trade must either increase the AI's total stack value OR enable trading away a calamity.
+/// AI accepts trade offers that serve its next civ-card purchase.
+/// Criteria: the trade's value towards that purchase (buying power gained,
+/// the purchase reached or lost, a calamity passed on) must clear the
+/// personality's margin, raised for offers from players it has caught cheating.
+#[allow(clippy::type_complexity)]
 pub fn ai_accept_trade_offers(
     ai_players: Query<
         (
@@ -2743,13 +2740,21 @@ pub fn ai_accept_trade_offers(
             &CanTrade,
             &Personality,
             Option<&PlayerCivilizationCards>,
+            Option<&AstPosition>,
+            Option<&TradeTrust>,
         ),
         Without<IsHuman>,
     >,
     mut offers: Query<(Entity, &mut OpenTradeOffer)>,
+    civ_card_defs: Option<Res<AvailableCivCards>>,
 ) {
-    for (ai_entity, ai_name, ai_cards, _, personality, ai_civ_cards) in ai_players.iter() {
+    for (ai_entity, ai_name, ai_cards, _, personality, ai_civ_cards, ast, trust) in
+        ai_players.iter()
+    {
         let has_mining = ai_civ_cards.is_some_and(|c| c.owns(&CivCardName::Mining));
+        let goal = civ_card_defs
+            .as_deref()
+            .and_then(|defs| trade_goal(ai_cards, ai_civ_cards, ast, defs, personality));
         for (_offer_entity, mut offer) in &mut offers {
             // Skip if we can't accept
             if !offer.can_accept(ai_entity) {
@@ -2800,24 +2805,36 @@ pub fn ai_accept_trade_offers(
             let new_stack_value = simulated_cards.total_stack_value_with_mining(has_mining);
 
             // Check if this trade enables trading away a calamity (hidden card slot)
-            let can_trade_away_calamity =
-                ai_cards.has_tradeable_calamity() && offer.wanting_hidden_count > 0;
-
-            // Accept if the trade clears this personality's profit margin, or it lets
-            // a calamity-averse player dump a calamity card.
-            let margin = trade_accept_margin(&personality.weights);
-            let profitable = new_stack_value as f32 > current_stack_value as f32 + margin;
-            let dump_calamity =
-                can_trade_away_calamity && accepts_calamity_offload(&personality.weights);
-            if profitable || dump_calamity {
+            // -- and whether we would use it on this offerer.
+            let trust_in_creator = trust.map_or(NEUTRAL_TRUST, |t| t.trust(offer.creator));
+            let can_trade_away_calamity = offer.wanting_hidden_count > 0
+                && hidden_card_play(
+                    personality,
+                    trust_in_creator,
+                    ai_cards.has_tradeable_calamity(),
+                ) == HiddenCardPlay::SlipCalamity;
+
+            // Accept if the trade's worth towards the planned purchase -- a
+            // calamity passed on weighted by calamity aversion -- clears this
+            // personality's profit margin.
+            let margin = trade_accept_margin(personality, trust_in_creator);
+            let value = score_trade_for_goal(
+                current_stack_value as u32,
+                new_stack_value as u32,
+                goal.as_ref(),
+                can_trade_away_calamity,
+                personality,
+            );
+            if value > margin {
                 offer.accept(ai_entity, ai_name.to_string());
                 debug!(
-                    "{} accepted trade offer from {} (value: {} -> {}, calamity: {})",
+                    "{} accepted trade offer from {} (value: {} -> {}, calamity: {}, worth {:.1})",
                     ai_name,
                     offer.creator_name,
                     current_stack_value,
                     new_stack_value,
-                    can_trade_away_calamity
+                    can_trade_away_calamity,
+                    value
                 );
                 break; // Only accept one offer per frame
             }
@@ -2831,6 +2848,7 @@ pub fn finalize_settled_open_offers(
     mut commands: Commands,
     settled_offers: Query<(Entity, &OpenTradeOffer)>,
     mut command_writer: MessageWriter<SendTradingCardsCommand>,
+    mut settled_writer: MessageWriter<OpenTradeOfferSettled>,
 ) {
     for (offer_entity, offer) in settled_offers.iter() {
         if !offer.is_settled() {
@@ -2864,6 +2882,7 @@ pub fn finalize_settled_open_offers(
             offer.creator_name,
             offer.accepted_by_name.as_deref().unwrap_or("?")
         );
+        settled_writer.write(OpenTradeOfferSettled(offer.clone()));
         commands.entity(offer_entity).despawn();
     }
 }
@@ -2880,7 +2899,7 @@ pub fn ai_stop_trading_when_ready(
     for (ai_entity, personality) in ai_players.iter() {
         // Each personality keeps trading for a different slice of the phase: eager
         // traders hold out near the end, reluctant ones bail almost immediately.
-        if trade_phase_state.countdown_seconds > stop_trading_threshold(&personality.weights) {
+        if trade_phase_state.countdown_seconds > stop_trading_threshold(personality) {
             continue;
         }
 
@@ -2897,12 +2916,31 @@ pub fn ai_stop_trading_when_ready(
     }
 }
 
-/// AI settles trades by selecting cards
+/// AI settles trades by selecting cards, filling the hidden slots the way its
+/// personality plays them against the other side (see `hidden_card_play`).
 pub fn ai_settle_trades(
-    ai_players: Query<(Entity, &Name, &PlayerTradeCards), Without<IsHuman>>,
+    ai_players: Query<
+        (
+            Entity,
+            &Name,
+            &PlayerTradeCards,
+            &Personality,
+            Option<&TradeTrust>,
+        ),
+        Without<IsHuman>,
+    >,
     mut offers: Query<&mut OpenTradeOffer>,
 ) {
-    for (ai_entity, ai_name, ai_cards) in ai_players.iter() {
+    for (ai_entity, ai_name, ai_cards, personality, trust) in ai_players.iter() {
+        let play_against = |opponent: Option<Entity>| {
+            hidden_card_play(
+                personality,
+                trust
+                    .zip(opponent)
+                    .map_or(NEUTRAL_TRUST, |(t, o)| t.trust(o)),
+                ai_cards.has_tradeable_calamity(),
+            )
+        };
         for mut offer in &mut offers {
             if !offer.is_settling() {
                 continue;
@@ -2914,6 +2952,7 @@ pub fn ai_settle_trades(
                     ai_cards,
                     &offer.offering_guaranteed,
                     offer.offering_hidden_count,
+                    play_against(offer.accepted_by),
                 );
                 if let Some(cards) = cards {
                     offer.settle_creator(cards);
@@ -2927,6 +2966,7 @@ pub fn ai_settle_trades(
                     ai_cards,
                     &offer.wanting_guaranteed,
                     offer.wanting_hidden_count,
+                    play_against(Some(offer.creator)),
                 );
                 if let Some(cards) = cards {
                     offer.settle_acceptor(cards);
@@ -2937,11 +2977,13 @@ pub fn ai_settle_trades(
     }
 }
 
-/// Helper: AI selects cards for settlement
+/// Helper: AI selects cards for settlement. The guaranteed cards are fixed;
+/// `play` decides what goes into the hidden slots.
 fn ai_select_settlement_cards(
     player_cards: &PlayerTradeCards,
     required_guaranteed: &HashMap<TradeCard, usize>,
     hidden_count: usize,
+    play: HiddenCardPlay,
 ) -> Option<HashMap<TradeCard, usize>> {
     let mut selected: HashMap<TradeCard, usize> = HashMap::default();
     let mut available: HashMap<TradeCard, usize> =
@@ -2961,28 +3003,48 @@ fn ai_select_settlement_cards(
         }
     }
 
-    // Add hidden cards - prefer calamities first, then lowest value commodities
     let mut hidden_left = hidden_count;
 
-    // Try to add tradeable calamities -- every copy held, not just one per type
-    for calamity in player_cards.calamity_cards() {
-        if hidden_left == 0 {
-            break;
-        }
-        if !calamity.is_tradeable() {
-            continue;
+    // Slipping a calamity: every tradeable copy held goes in first.
+    if play == HiddenCardPlay::SlipCalamity {
+        for calamity in player_cards.calamity_cards() {
+            if hidden_left == 0 {
+                break;
+            }
+            if !calamity.is_tradeable() {
+                continue;
+            }
+            let to_add = player_cards
+                .number_of_cards_for_trade_card(calamity)
+                .min(hidden_left);
+            *selected.entry(calamity).or_insert(0) += to_add;
+            hidden_left -= to_add;
+        }
+    }
+
+    // Deceivers pay the rest in the cheapest commodities. Playing it straight
+    // means cards worth at least the cheapest stated one, the cheapest of
+    // those first, and only then anything lower, best first.
+    let stated = required_guaranteed
+        .keys()
+        .map(TradeCardTrait::value)
+        .min()
+        .unwrap_or(0);
+    let mut sorted_available: Vec<_> = available.iter().collect();
+    match play {
+        HiddenCardPlay::Straight => sorted_available.sort_by_key(|(card, _)| {
+            let value = card.value();
+            if value >= stated {
+                (0, value)
+            } else {
+                (1, usize::MAX - value)
+            }
+        }),
+        HiddenCardPlay::Bluff | HiddenCardPlay::SlipCalamity => {
+            sorted_available.sort_by_key(|(card, _)| card.value());
         }
-        let to_add = player_cards
-            .number_of_cards_for_trade_card(calamity)
-            .min(hidden_left);
-        *selected.entry(calamity).or_insert(0) += to_add;
-        hidden_left -= to_add;
     }
 
-    // Add the lowest value commodities for remaining hidden
-    let mut sorted_available: Vec<_> = available.iter().collect();
-    sorted_available.sort_by_key(|(card, _)| card.value());
-
     for (card, count) in sorted_available {
         if hidden_left == 0 {
             break;
@@ -2998,3 +3060,193 @@ fn ai_select_settlement_cards(
 
     Some(selected)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stupid_ai::Playstyle;
+    use core::time::Duration;
+
+    fn hand(cards: &[(TradeCard, usize)]) -> PlayerTradeCards {
+        let mut hand = PlayerTradeCards::default();
+        for (card, count) in cards {
+            hand.add_trade_cards(*card, *count);
+        }
+        hand
+    }
+
+    /// Salt is the one stated card; the rest is there to fill three hidden
+    /// slots from.
+    fn settling_hand() -> PlayerTradeCards {
+        hand(&[
+            (TradeCard::Ochre, 2),
+            (TradeCard::Salt, 2),
+            (TradeCard::Grain, 1),
+            (TradeCard::Cloth, 1),
+            (TradeCard::Epidemic, 1),
+            (TradeCard::Famine, 1),
+        ])
+    }
+
+    fn settle(play: HiddenCardPlay) -> HashMap<TradeCard, usize> {
+        let stated = HashMap::from_iter([(TradeCard::Salt, 1)]);
+        ai_select_settlement_cards(&settling_hand(), &stated, 3, play).unwrap()
+    }
+
+    #[test]
+    fn straight_pays_at_least_the_stated_value_cheapest_first() {
+        let cards = settle(HiddenCardPlay::Straight);
+
+        assert_eq!(
+            cards,
+            HashMap::from_iter([
+                (TradeCard::Salt, 2),
+                (TradeCard::Grain, 1),
+                (TradeCard::Cloth, 1),
+            ])
+        );
+        assert!(cards.keys().all(|card| !card.is_calamity()));
+    }
+
+    #[test]
+    fn straight_falls_back_to_the_best_of_the_cheaper_cards() {
+        let hand = hand(&[
+            (TradeCard::Ochre, 1),
+            (TradeCard::Iron, 1),
+            (TradeCard::Grain, 1),
+        ]);
+        let stated = HashMap::from_iter([(TradeCard::Grain, 1)]);
+
+        let cards = ai_select_settlement_cards(&hand, &stated, 1, HiddenCardPlay::Straight);
+
+        assert_eq!(
+            cards,
+            Some(HashMap::from_iter([
+                (TradeCard::Grain, 1),
+                (TradeCard::Iron, 1)
+            ]))
+        );
+    }
+
+    #[test]
+    fn a_bluff_pays_the_hidden_slots_in_junk() {
+        assert_eq!(
+            settle(HiddenCardPlay::Bluff),
+            HashMap::from_iter([(TradeCard::Salt, 2), (TradeCard::Ochre, 2)])
+        );
+    }
+
+    #[test]
+    fn slipping_a_calamity_puts_only_tradeable_ones_in_first() {
+        assert_eq!(
+            settle(HiddenCardPlay::SlipCalamity),
+            HashMap::from_iter([
+                (TradeCard::Salt, 1),
+                (TradeCard::Epidemic, 1),
+                (TradeCard::Ochre, 2),
+            ])
+        );
+    }
+
+    #[test]
+    fn settling_fails_without_the_stated_cards() {
+        let stated = HashMap::from_iter([(TradeCard::Gold, 1)]);
+        assert_eq!(
+            ai_select_settlement_cards(&settling_hand(), &stated, 1, HiddenCardPlay::Straight),
+            None
+        );
+    }
+
+    /// The hidden cards a lone AI with `risk` asks to give in its first offer.
+    fn offered_hidden_cards(risk: f32) -> usize {
+        let mut personality = Personality::from_playstyle(Playstyle::Warlord);
+        personality.weights.risk = risk;
+        personality.trade.offer_chance = Some(1.0);
+
+        let mut app = App::new();
+        app.init_resource::<Time>()
+            .insert_resource(GameRng::from_seed(1))
+            .add_systems(Update, ai_create_trade_offers);
+        app.world_mut().spawn((
+            Name::new("Crete"),
+            hand(&[
+                (TradeCard::Grain, 2),
+                (TradeCard::Ochre, 1),
+                (TradeCard::Hides, 1),
+                (TradeCard::Iron, 1),
+            ]),
+            CanTrade,
+            personality,
+        ));
+        app.world_mut()
+            .resource_mut::<Time>()
+            .advance_by(Duration::from_secs(4));
+        app.update();
+
+        let mut offers = app.world_mut().query::<&OpenTradeOffer>();
+        let offer = offers.single(app.world()).unwrap();
+        assert_eq!(offer.wanting_guaranteed.get(&TradeCard::Grain), Some(&2));
+        offer.offering_hidden_count
+    }
+
+    #[test]
+    fn a_bluffer_promises_one_more_hidden_card() {
+        assert_eq!(offered_hidden_cards(0.1), 1);
+        assert_eq!(offered_hidden_cards(0.9), 2);
+    }
+
+    #[test]
+    fn settling_plays_straight_with_a_trusted_partner_and_bluffs_a_distrusted_one() {
+        let mut personality = Personality::from_playstyle(Playstyle::Warlord);
+        personality.weights.risk = 0.9;
+
+        let mut app = App::new();
+        app.add_systems(Update, ai_settle_trades);
+        let trusted = app.world_mut().spawn_empty().id();
+        let distrusted = app.world_mut().spawn_empty().id();
+        let mut trust = TradeTrust::default();
+        trust.record(trusted, 1.0, 1.0);
+        trust.record(distrusted, 0.0, 1.0);
+        let ai = app
+            .world_mut()
+            .spawn((
+                Name::new("Crete"),
+                hand(&[
+                    (TradeCard::Ochre, 2),
+                    (TradeCard::Salt, 2),
+                    (TradeCard::Grain, 1),
+                    (TradeCard::Cloth, 1),
+                ]),
+                personality,
+                trust,
+            ))
+            .id();
+        for partner in [trusted, distrusted] {
+            let mut offer = OpenTradeOffer::new(ai, "Crete", None, None);
+            offer.offering_guaranteed = HashMap::from_iter([(TradeCard::Salt, 1)]);
+            offer.offering_hidden_count = 2;
+            offer.wanting_guaranteed = HashMap::from_iter([(TradeCard::Iron, 1)]);
+            offer.wanting_hidden_count = 2;
+            assert!(offer.accept(partner, "partner"));
+            app.world_mut().spawn(offer);
+        }
+        app.update();
+
+        let mut offers = app.world_mut().query::<&OpenTradeOffer>();
+        let mut settled_with = |partner: Entity| {
+            offers
+                .iter(app.world())
+                .find(|o| o.accepted_by == Some(partner))
+                .and_then(|o| o.creator_actual_cards.clone())
+                .unwrap()
+        };
+        assert_eq!(
+            settled_with(trusted),
+            HashMap::from_iter([(TradeCard::Salt, 2), (TradeCard::Grain, 1)])
+        );
+        assert_eq!(
+            settled_with(distrusted),
+            HashMap::from_iter([(TradeCard::Salt, 1), (TradeCard::Ochre, 2)])
+        );
+    }
+}
@@ -0,0 +1,9 @@
+mod undo_events;
+mod undo_plugin;
+mod undo_resources;
+mod undo_systems;
+
+pub use undo_events::*;
+pub use undo_plugin::*;
+pub use undo_resources::*;
+pub use undo_systems::*;
@@ -0,0 +1,26 @@
+use bevy::prelude::{Entity, Message, Reflect};
+
+/// Take back `player`'s last move in the current phase (Backspace for the
+/// local human).
+#[derive(Message, Debug, Reflect)]
+pub struct UndoRequest {
+    pub player: Entity,
+}
+
+impl UndoRequest {
+    pub fn new(player: Entity) -> Self {
+        UndoRequest { player }
+    }
+}
+
+/// `player`'s last move was taken back.
+#[derive(Message, Debug, Reflect)]
+pub struct MoveUndone {
+    pub player: Entity,
+}
+
+impl MoveUndone {
+    pub fn new(player: Entity) -> Self {
+        MoveUndone { player }
+    }
+}
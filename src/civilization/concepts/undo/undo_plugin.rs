@@ -0,0 +1,40 @@
+use crate::GameState;
+use crate::civilization::concepts::undo::undo_events::{MoveUndone, UndoRequest};
+use crate::civilization::concepts::undo::undo_resources::UndoStack;
+use crate::civilization::concepts::undo::undo_systems::*;
+use bevy::prelude::{App, IntoScheduleConfigs, Plugin, PreUpdate, Update, in_state};
+
+/// Lets a human player take back their own moves within Population
+/// Expansion, Movement and City Construction (Backspace, or an
+/// [`UndoRequest`]).
+///
+/// Before each move the player's position is captured through the save-game
+/// snapshot; undoing puts their tokens, cities, treasury and per-phase
+/// markers back as they were. A trade card draw or a conflict locks undo for
+/// the rest of the phase.
+pub struct UndoPlugin;
+
+impl Plugin for UndoPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<UndoStack>()
+            .add_message::<UndoRequest>()
+            .add_message::<MoveUndone>()
+            .add_systems(
+                PreUpdate,
+                (commit_human_moves, capture_undo_points)
+                    .chain()
+                    .run_if(in_state(GameState::Playing)),
+            )
+            .add_systems(
+                Update,
+                (
+                    undo_on_key,
+                    handle_undo_requests.after(undo_on_key),
+                    lock_undo_on_trade_card_draw,
+                )
+                    .run_if(in_state(GameState::Playing)),
+            )
+            .add_observer(lock_undo_on_conflict)
+            .add_observer(lock_undo_on_city_conflict);
+    }
+}
@@ -0,0 +1,299 @@
+use crate::GameActivity;
+use crate::civilization::concepts::save_game::GameSaveData;
+use crate::civilization::enums::GameFaction;
+use bevy::platform::collections::{HashMap, HashSet};
+use bevy::prelude::Resource;
+use std::collections::VecDeque;
+
+/// How many moves back a player can go within one phase.
+pub const MAX_UNDO_STEPS: usize = 16;
+
+/// The board as one player saw it just before one of their moves: the shared
+/// [`GameSaveData`] snapshot, plus the bits of per-phase progress a save file
+/// has no use for (which areas still need expanding, which tokens already
+/// moved this phase).
+#[derive(Debug)]
+pub struct UndoSnapshot {
+    pub faction: GameFaction,
+    pub save: GameSaveData,
+    /// Area ids still in the player's `NeedsExpansion` (Population Expansion).
+    pub expansion_areas: Vec<i32>,
+    /// How many of the player's tokens per area id carry `TokenHasMoved`.
+    pub moved_tokens: Vec<(i32, usize)>,
+}
+
+impl UndoSnapshot {
+    pub fn phase(&self) -> (usize, GameActivity) {
+        (self.save.round, self.save.game_activity.clone())
+    }
+
+    /// The player's token count per area id, as saved.
+    pub fn tokens_by_area(&self) -> HashMap<i32, usize> {
+        self.save
+            .area_populations
+            .iter()
+            .filter_map(|area| {
+                area.tokens_by_faction
+                    .iter()
+                    .find(|(f, _)| *f == self.faction)
+                    .map(|(_, tokens)| (area.area_id, *tokens))
+            })
+            .collect()
+    }
+
+    /// Area ids where the player had a city, as saved.
+    pub fn city_areas(&self) -> HashSet<i32> {
+        self.save
+            .area_populations
+            .iter()
+            .filter(|area| area.city_owner == Some(self.faction))
+            .map(|area| area.area_id)
+            .collect()
+    }
+
+    /// The player's treasury and whether they had finished the phase.
+    pub fn treasury_and_done(&self) -> Option<(usize, bool)> {
+        self.save
+            .players
+            .iter()
+            .find(|p| p.faction == self.faction)
+            .map(|p| (p.treasury, p.done_with_current_activity))
+    }
+}
+
+/// Per-move snapshots of the human players' positions in the current phase.
+///
+/// Only ever reaches back within one phase: a new phase (or round) starts the
+/// stack afresh. Anything that reveals hidden information -- a trade card
+/// draw, a conflict being resolved -- locks undo for the rest of the phase,
+/// so nobody can look and then take their move back.
+#[derive(Resource, Debug, Default)]
+pub struct UndoStack {
+    snapshots: VecDeque<UndoSnapshot>,
+    /// Each player's position as of the start of this frame; pushed onto the
+    /// stack when they make a move.
+    latest: HashMap<GameFaction, UndoSnapshot>,
+    phase: Option<(usize, GameActivity)>,
+    locked: bool,
+}
+
+impl UndoStack {
+    /// Start over if `phase` isn't the phase the stack was built in.
+    pub fn enter_phase(&mut self, phase: (usize, GameActivity)) {
+        if self.phase.as_ref() != Some(&phase) {
+            self.snapshots.clear();
+            self.latest.clear();
+            self.locked = false;
+            self.phase = Some(phase);
+        }
+    }
+
+    /// Remember where `snapshot.faction` stands before their next move.
+    pub fn set_latest(&mut self, snapshot: UndoSnapshot) {
+        self.enter_phase(snapshot.phase());
+        self.latest.insert(snapshot.faction, snapshot);
+    }
+
+    /// No position on hand for `faction` yet (and undo isn't locked).
+    pub fn needs_latest(&self, faction: GameFaction) -> bool {
+        !self.locked && !self.latest.contains_key(&faction)
+    }
+
+    /// `faction` just moved: their latest position becomes an undo step.
+    pub fn commit_move(&mut self, faction: GameFaction) {
+        if self.locked {
+            return;
+        }
+        let Some(snapshot) = self.latest.remove(&faction) else {
+            return;
+        };
+        if self.phase.as_ref() != Some(&snapshot.phase()) {
+            return;
+        }
+        if self
+            .snapshots
+            .iter()
+            .filter(|s| s.faction == faction)
+            .count()
+            >= MAX_UNDO_STEPS
+            && let Some(oldest) = self.snapshots.iter().position(|s| s.faction == faction)
+        {
+            self.snapshots.remove(oldest);
+        }
+        self.snapshots.push_back(snapshot);
+    }
+
+    /// `faction` made a move that can't be taken back (ending their
+    /// movement, ferrying by ship): everything before it is final.
+    pub fn forget(&mut self, faction: GameFaction) {
+        self.snapshots.retain(|s| s.faction != faction);
+        self.latest.remove(&faction);
+    }
+
+    /// Hidden information came out: no more undo this phase.
+    pub fn lock(&mut self) {
+        self.snapshots.clear();
+        self.latest.clear();
+        self.locked = true;
+    }
+
+    pub fn is_locked(&self) -> bool {
+        self.locked
+    }
+
+    pub fn steps_for(&self, faction: GameFaction) -> usize {
+        self.snapshots
+            .iter()
+            .filter(|s| s.faction == faction)
+            .count()
+    }
+
+    /// Take `faction`'s most recent step, if it belongs to the current phase.
+    pub fn pop(
+        &mut self,
+        faction: GameFaction,
+        phase: (usize, GameActivity),
+    ) -> Option<UndoSnapshot> {
+        if self.locked || self.phase.as_ref() != Some(&phase) {
+            return None;
+        }
+        let index = self.snapshots.iter().rposition(|s| s.faction == faction)?;
+        self.latest.remove(&faction);
+        self.snapshots.remove(index)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::civilization::concepts::save_game::{
+        SavedAreaPopulation, SavedPhaseState, SavedPlayer,
+    };
+
+    fn snapshot(faction: GameFaction, round: usize, tokens_in_area_1: usize) -> UndoSnapshot {
+        UndoSnapshot {
+            faction,
+            save: GameSaveData {
+                version: String::new(),
+                round,
+                game_activity: GameActivity::Movement,
+                players: vec![SavedPlayer {
+                    name: format!("{faction:?}"),
+                    faction,
+                    is_human: true,
+                    census_population: 0,
+                    treasury: 2,
+                    tokens_in_stock: 40,
+                    city_tokens_in_stock: 9,
+                    trade_cards: vec![],
+                    done_with_current_activity: false,
+                    ast_space: 0,
+                    owned_civ_cards: vec![],
+                    calamity_traded_by: vec![],
+                }],
+                area_populations: vec![
+                    SavedAreaPopulation {
+                        area_id: 1,
+                        tokens_by_faction: vec![
+                            (faction, tokens_in_area_1),
+                            (GameFaction::Thrace, 3),
+                        ],
+                        city_owner: None,
+                        city_is_pirate: false,
+                    },
+                    SavedAreaPopulation {
+                        area_id: 2,
+                        tokens_by_faction: vec![],
+                        city_owner: Some(faction),
+                        city_is_pirate: false,
+                    },
+                ],
+                census_order: vec![],
+                left_to_move: vec![],
+                current_mover: None,
+                phase_state: SavedPhaseState::default(),
+            },
+            expansion_areas: vec![],
+            moved_tokens: vec![],
+        }
+    }
+
+    fn movement(round: usize) -> (usize, GameActivity) {
+        (round, GameActivity::Movement)
+    }
+
+    #[test]
+    fn a_move_pushes_the_position_it_was_made_from() {
+        let mut stack = UndoStack::default();
+        stack.set_latest(snapshot(GameFaction::Egypt, 1, 4));
+        stack.commit_move(GameFaction::Egypt);
+        stack.set_latest(snapshot(GameFaction::Egypt, 1, 2));
+        stack.commit_move(GameFaction::Egypt);
+
+        let undone = stack.pop(GameFaction::Egypt, movement(1)).unwrap();
+        assert_eq!(undone.tokens_by_area().get(&1), Some(&2));
+        let undone = stack.pop(GameFaction::Egypt, movement(1)).unwrap();
+        assert_eq!(undone.tokens_by_area().get(&1), Some(&4));
+        assert!(stack.pop(GameFaction::Egypt, movement(1)).is_none());
+    }
+
+    #[test]
+    fn each_player_only_undoes_their_own_moves() {
+        let mut stack = UndoStack::default();
+        stack.set_latest(snapshot(GameFaction::Egypt, 1, 4));
+        stack.commit_move(GameFaction::Egypt);
+        stack.set_latest(snapshot(GameFaction::Crete, 1, 1));
+        stack.commit_move(GameFaction::Crete);
+
+        let undone = stack.pop(GameFaction::Egypt, movement(1)).unwrap();
+        assert_eq!(undone.faction, GameFaction::Egypt);
+        assert_eq!(stack.steps_for(GameFaction::Crete), 1);
+    }
+
+    #[test]
+    fn the_stack_is_bounded_per_player() {
+        let mut stack = UndoStack::default();
+        for tokens in 0..MAX_UNDO_STEPS + 3 {
+            stack.set_latest(snapshot(GameFaction::Egypt, 1, tokens));
+            stack.commit_move(GameFaction::Egypt);
+        }
+        assert_eq!(stack.steps_for(GameFaction::Egypt), MAX_UNDO_STEPS);
+        let newest = stack.pop(GameFaction::Egypt, movement(1)).unwrap();
+        assert_eq!(newest.tokens_by_area().get(&1), Some(&(MAX_UNDO_STEPS + 2)));
+    }
+
+    #[test]
+    fn a_new_phase_starts_the_stack_afresh() {
+        let mut stack = UndoStack::default();
+        stack.set_latest(snapshot(GameFaction::Egypt, 1, 4));
+        stack.commit_move(GameFaction::Egypt);
+        assert!(stack.pop(GameFaction::Egypt, movement(2)).is_none());
+
+        stack.set_latest(snapshot(GameFaction::Egypt, 2, 4));
+        assert_eq!(stack.steps_for(GameFaction::Egypt), 0);
+    }
+
+    #[test]
+    fn revealed_information_locks_undo_until_the_next_phase() {
+        let mut stack = UndoStack::default();
+        stack.set_latest(snapshot(GameFaction::Egypt, 1, 4));
+        stack.commit_move(GameFaction::Egypt);
+        stack.lock();
+        assert!(stack.pop(GameFaction::Egypt, movement(1)).is_none());
+
+        stack.set_latest(snapshot(GameFaction::Egypt, 1, 3));
+        stack.commit_move(GameFaction::Egypt);
+        assert_eq!(stack.steps_for(GameFaction::Egypt), 0, "still locked");
+
+        stack.set_latest(snapshot(GameFaction::Egypt, 2, 3));
+        assert!(!stack.is_locked());
+    }
+
+    #[test]
+    fn snapshot_reads_back_the_players_own_pieces() {
+        let snapshot = snapshot(GameFaction::Egypt, 1, 4);
+        assert_eq!(snapshot.tokens_by_area().len(), 1);
+        assert_eq!(snapshot.city_areas(), HashSet::from_iter([2]));
+        assert_eq!(snapshot.treasury_and_done(), Some((2, false)));
+    }
+}
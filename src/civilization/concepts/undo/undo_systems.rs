@@ -0,0 +1,388 @@
+use crate::GameActivity;
+use crate::civilization::components::*;
+use crate::civilization::concepts::AvailableFactions;
+use crate::civilization::concepts::census::GameInfoAndStuff;
+use crate::civilization::concepts::resolve_calamities::resolve_calamities_systems::ReturnCityToStock;
+use crate::civilization::concepts::save_game::SaveDataSource;
+use crate::civilization::concepts::undo::undo_events::{MoveUndone, UndoRequest};
+use crate::civilization::concepts::undo::undo_resources::{UndoSnapshot, UndoStack};
+use crate::civilization::events::CommandApplied;
+use crate::civilization::functions::return_token_to_stock;
+use crate::civilization::{
+    AreaIsExpanding, AvailableMoves, BuildCityCommand, CheckPlayerExpansionEligibility,
+    EndPlayerCityConstruction, ExpandManually, ExpandPopulationManuallyCommand,
+    HumanPlayerTradeCardsUpdated, IsBuilding, MoveTokenFromAreaToAreaCommand, NeedsExpansion,
+    PerformingMovement, PlayerMovementEnded, RecalculatePlayerMoves, ShipFerryCommand,
+    TokenHasMoved, UnresolvedCityConflict, UnresolvedConflict,
+};
+use crate::player::Player;
+use crate::stupid_ai::{AgentControlled, IsHuman};
+use bevy::ecs::system::SystemParam;
+use bevy::platform::collections::{HashMap, HashSet};
+use bevy::prelude::*;
+
+/// The phases where a human places pieces one move at a time and nothing
+/// hidden comes out until the phase is over.
+pub fn is_undoable(activity: &GameActivity) -> bool {
+    matches!(
+        activity,
+        GameActivity::PopulationExpansion | GameActivity::Movement | GameActivity::CityConstruction
+    )
+}
+
+/// Runs in `PreUpdate`, before [`capture_undo_points`]: a human move applied
+/// last frame turns the position captured before it -- the one the move was
+/// made from -- into an undo step. A move its handler refused changed
+/// nothing, so it never becomes one.
+pub fn commit_human_moves(
+    mut stack: ResMut<UndoStack>,
+    humans: Query<&Faction, (With<IsHuman>, Without<AgentControlled>)>,
+    mut expansions: MessageReader<CommandApplied<ExpandPopulationManuallyCommand>>,
+    mut moves: MessageReader<CommandApplied<MoveTokenFromAreaToAreaCommand>>,
+    mut builds: MessageReader<CommandApplied<BuildCityCommand>>,
+    mut ends_building: MessageReader<EndPlayerCityConstruction>,
+    mut ferries: MessageReader<CommandApplied<ShipFerryCommand>>,
+    mut ends_movement: MessageReader<PlayerMovementEnded>,
+) {
+    let movers = expansions
+        .read()
+        .map(|CommandApplied(c)| c.player)
+        .chain(moves.read().map(|CommandApplied(c)| c.player))
+        .chain(builds.read().map(|CommandApplied(c)| c.player))
+        .chain(ends_building.read().map(|c| c.player));
+    for player in movers {
+        if let Ok(faction) = humans.get(player) {
+            stack.commit_move(faction.faction);
+        }
+    }
+
+    // Ship positions aren't part of the save format, and ending movement
+    // hands the board to the next player: neither can be taken back.
+    let finals = ferries
+        .read()
+        .map(|CommandApplied(c)| c.player)
+        .chain(ends_movement.read().map(|c| c.player));
+    for player in finals {
+        if let Ok(faction) = humans.get(player) {
+            stack.forget(faction.faction);
+        }
+    }
+}
+
+/// Keeps each human's latest position (as of the start of this frame) ready
+/// to become an undo step, recapturing only when the board has changed.
+pub fn capture_undo_points(
+    mut stack: ResMut<UndoStack>,
+    activity: Option<Res<State<GameActivity>>>,
+    game_info: Res<GameInfoAndStuff>,
+    source: SaveDataSource,
+    humans: Query<
+        (Entity, &Faction, &PlayerAreas, Option<&NeedsExpansion>),
+        (
+            With<IsHuman>,
+            Without<AgentControlled>,
+            With<AvailableMoves>,
+        ),
+    >,
+    changed: Query<
+        (),
+        Or<(
+            Changed<Population>,
+            Changed<TokenStock>,
+            Changed<Treasury>,
+            Changed<AvailableMoves>,
+        )>,
+    >,
+    areas: Query<&GameArea>,
+    moved: Query<(), With<TokenHasMoved>>,
+) {
+    let Some(activity) = activity.map(|a| a.get().clone()) else {
+        return;
+    };
+    if !is_undoable(&activity) || humans.is_empty() {
+        return;
+    }
+    stack.enter_phase((game_info.round, activity));
+    let missing = humans
+        .iter()
+        .any(|(_, f, ..)| stack.needs_latest(f.faction));
+    if changed.is_empty() && !missing {
+        return;
+    }
+
+    let save = source.capture();
+    for (_, faction, player_areas, needs_expansion) in &humans {
+        let expansion_areas = needs_expansion
+            .map(|n| {
+                n.areas_that_need_expansion
+                    .iter()
+                    .filter_map(|area| areas.get(*area).ok().map(|a| a.id))
+                    .collect()
+            })
+            .unwrap_or_default();
+        let moved_tokens = player_areas
+            .areas_and_population()
+            .iter()
+            .filter_map(|(area, tokens)| {
+                let id = areas.get(*area).ok()?.id;
+                let count = tokens.iter().filter(|t| moved.contains(**t)).count();
+                (count > 0).then_some((id, count))
+            })
+            .collect();
+        stack.set_latest(UndoSnapshot {
+            faction: faction.faction,
+            save: save.clone(),
+            expansion_areas,
+            moved_tokens,
+        });
+    }
+}
+
+/// A trade card draw shows cards nobody saw before.
+pub fn lock_undo_on_trade_card_draw(
+    mut draws: MessageReader<HumanPlayerTradeCardsUpdated>,
+    mut stack: ResMut<UndoStack>,
+) {
+    if draws.read().next().is_some() {
+        stack.lock();
+    }
+}
+
+/// Resolving a conflict decides who keeps what.
+pub fn lock_undo_on_conflict(_trigger: On<Add, UnresolvedConflict>, mut stack: ResMut<UndoStack>) {
+    stack.lock();
+}
+
+pub fn lock_undo_on_city_conflict(
+    _trigger: On<Add, UnresolvedCityConflict>,
+    mut stack: ResMut<UndoStack>,
+) {
+    stack.lock();
+}
+
+/// Backspace: Z is already the zoom key.
+pub fn undo_on_key(
+    keys: Res<ButtonInput<KeyCode>>,
+    local_human: Query<Entity, (With<IsHuman>, Without<AgentControlled>)>,
+    mut writer: MessageWriter<UndoRequest>,
+) {
+    if keys.just_pressed(KeyCode::Backspace)
+        && let Some(player) = local_human.iter().next()
+    {
+        writer.write(UndoRequest::new(player));
+    }
+}
+
+pub fn handle_undo_requests(
+    mut requests: MessageReader<UndoRequest>,
+    mut stack: ResMut<UndoStack>,
+    activity: Option<Res<State<GameActivity>>>,
+    game_info: Res<GameInfoAndStuff>,
+    factions: Query<&Faction>,
+    mut board: UndoBoard,
+    mut undone: MessageWriter<MoveUndone>,
+) {
+    for request in requests.read() {
+        let (Some(activity), Ok(faction)) = (activity.as_ref(), factions.get(request.player))
+        else {
+            continue;
+        };
+        let activity = activity.get().clone();
+        if stack.is_locked() {
+            info!("[UNDO] Not available: information was revealed this phase");
+            continue;
+        }
+        let Some(snapshot) = stack.pop(faction.faction, (game_info.round, activity.clone())) else {
+            info!("[UNDO] Nothing to undo for {:?}", faction.faction);
+            continue;
+        };
+        if board.restore(request.player, &snapshot, &activity) {
+            info!(
+                "[UNDO] {:?} took back a move ({} more available)",
+                faction.faction,
+                stack.steps_for(faction.faction)
+            );
+            undone.write(MoveUndone::new(request.player));
+        }
+    }
+}
+
+/// Write access to one player's pieces, for putting them back where an
+/// [`UndoSnapshot`] saw them.
+#[derive(SystemParam)]
+pub struct UndoBoard<'w, 's> {
+    commands: Commands<'w, 's>,
+    players: Query<
+        'w,
+        's,
+        (
+            &'static Faction,
+            &'static mut TokenStock,
+            &'static mut PlayerAreas,
+            &'static mut Treasury,
+            Has<PerformingMovement>,
+            Has<IsBuilding>,
+        ),
+        With<Player>,
+    >,
+    areas: Query<
+        'w,
+        's,
+        (
+            Entity,
+            &'static GameArea,
+            &'static mut Population,
+            &'static Transform,
+            Option<&'static BuiltCity>,
+            Option<&'static mut AreaIsExpanding>,
+        ),
+    >,
+    moved: Query<'w, 's, (), With<TokenHasMoved>>,
+    game_factions: Res<'w, AvailableFactions>,
+    recalculate: MessageWriter<'w, RecalculatePlayerMoves>,
+    check_expansion: MessageWriter<'w, CheckPlayerExpansionEligibility>,
+}
+
+impl UndoBoard<'_, '_> {
+    /// Put `player`'s tokens, cities, treasury and phase progress back the
+    /// way `snapshot` saw them. Other players' pieces are left alone: they
+    /// may have moved in the meantime, and that isn't this player's to undo.
+    pub fn restore(
+        &mut self,
+        player: Entity,
+        snapshot: &UndoSnapshot,
+        activity: &GameActivity,
+    ) -> bool {
+        let Some((saved_treasury, was_done)) = snapshot.treasury_and_done() else {
+            return false;
+        };
+        let Ok((faction, mut stock, mut player_areas, mut treasury, performing, building)) =
+            self.players.get_mut(player)
+        else {
+            return false;
+        };
+        let saved_tokens = snapshot.tokens_by_area();
+        let saved_cities = snapshot.city_areas();
+        let saved_moved: HashMap<i32, usize> = snapshot.moved_tokens.iter().copied().collect();
+        let mut area_by_id: HashMap<i32, Entity> = HashMap::default();
+        let mut deficits: Vec<(Entity, usize)> = Vec::new();
+
+        for (area, game_area, mut population, _, built_city, _) in self.areas.iter_mut() {
+            area_by_id.insert(game_area.id, area);
+
+            if let Some(city) = built_city
+                && city.player == player
+                && !saved_cities.contains(&game_area.id)
+            {
+                self.commands.entity(area).remove::<BuiltCity>();
+                self.commands.entity(city.city).insert(ReturnCityToStock);
+            }
+
+            let mut tokens: Vec<Entity> = population
+                .tokens_for_player(&player)
+                .map(|t| t.iter().copied().collect())
+                .unwrap_or_default();
+            let saved = saved_tokens.get(&game_area.id).copied().unwrap_or(0);
+            if tokens.len() < saved {
+                deficits.push((area, saved - tokens.len()));
+            }
+            if tokens.len() > saved {
+                // Tokens that moved this phase are the ones that arrived
+                // since; send those back first.
+                tokens.sort_by_key(|t| !self.moved.contains(*t));
+                for token in tokens.drain(..tokens.len() - saved) {
+                    population.remove_token_from_area(player, token);
+                    return_token_to_stock(token, &mut stock, &mut player_areas);
+                    self.commands
+                        .entity(token)
+                        .remove::<(Sprite, Transform, Visibility, TokenHasMoved)>();
+                }
+                self.commands.entity(area).insert(FixTokenPositions);
+            }
+
+            // Whatever stayed should be exactly as free to move as before.
+            let target = saved_moved.get(&game_area.id).copied().unwrap_or(0);
+            let (flagged, unflagged): (Vec<Entity>, Vec<Entity>) =
+                tokens.into_iter().partition(|t| self.moved.contains(*t));
+            for token in flagged.iter().skip(target) {
+                self.commands.entity(*token).remove::<TokenHasMoved>();
+            }
+            for token in unflagged.iter().take(target.saturating_sub(flagged.len())) {
+                self.commands.entity(*token).insert(TokenHasMoved);
+            }
+        }
+
+        // Architecture's city-building token goes back out of the treasury.
+        while treasury.tokens_in_treasury() > saved_treasury {
+            let Some(token) = treasury.remove_token_from_treasury() else {
+                break;
+            };
+            stock.return_token_to_stock(token);
+        }
+
+        let icon = self
+            .game_factions
+            .faction_icons
+            .get(&faction.faction)
+            .cloned();
+        for (area, missing) in deficits {
+            let Ok((_, _, mut population, transform, _, _)) = self.areas.get_mut(area) else {
+                continue;
+            };
+            let tokens = stock
+                .remove_at_most_n_tokens_from_stock(missing)
+                .unwrap_or_default();
+            for token in tokens {
+                population.add_token_to_area(player, token);
+                player_areas.add_token_to_area(area, token);
+                let mut entity = self.commands.entity(token);
+                entity.remove::<TokenHasMoved>().insert(
+                    Transform::from_scale(Vec3::new(0.25, 0.25, 0.25))
+                        .with_translation(transform.translation),
+                );
+                if let Some(icon) = &icon {
+                    entity.insert(Sprite {
+                        image: icon.clone(),
+                        ..default()
+                    });
+                }
+            }
+            self.commands.entity(area).insert(FixTokenPositions);
+        }
+
+        if !was_done {
+            match activity {
+                GameActivity::PopulationExpansion => {
+                    let areas: HashSet<Entity> = snapshot
+                        .expansion_areas
+                        .iter()
+                        .filter_map(|id| area_by_id.get(id).copied())
+                        .collect();
+                    for area in &areas {
+                        if let Ok((.., Some(mut expanding))) = self.areas.get_mut(*area) {
+                            expanding.players_that_must_expand.insert(player);
+                        }
+                    }
+                    // Same dance as a manual expansion: drop ExpandManually
+                    // and let the eligibility check put it back, which
+                    // regenerates the player's moves.
+                    self.commands
+                        .entity(player)
+                        .insert(NeedsExpansion::new(areas))
+                        .remove::<ExpandManually>();
+                    self.check_expansion
+                        .write(CheckPlayerExpansionEligibility::new(player));
+                }
+                GameActivity::Movement if !performing => {
+                    self.commands.entity(player).insert(PerformingMovement);
+                }
+                GameActivity::CityConstruction if !building => {
+                    self.commands.entity(player).insert(IsBuilding);
+                }
+                _ => {}
+            }
+        }
+        self.recalculate.write(RecalculatePlayerMoves::new(player));
+        true
+    }
+}
@@ -1,5 +1,12 @@
 use bevy::prelude::{Entity, Message};
 
+/// Written by a command's handler once it has applied the command, with what
+/// was actually done (tokens actually moved, cards actually paid). The
+/// journal and the replay recorder read these, so a command the handler
+/// refused never reaches them.
+#[derive(Message, Debug, Clone)]
+pub struct CommandApplied<C: Send + Sync + 'static>(pub C);
+
 #[derive(Message, Debug)]
 pub struct MoveTokensFromStockToAreaCommand {
     pub area_entity: Entity,
@@ -0,0 +1,1015 @@
+use crate::civilization::concepts::resolve_calamities::calamities::barbarian_hordes::{
+    MAX_CASCADE_ITERATIONS, barbarian_damage_score,
+};
+use crate::civilization::concepts::resolve_calamities::calamities::civil_disorder::CivilDisorderState;
+use crate::civilization::concepts::resolve_calamities::calamities::civil_war::CivilWarState;
+use crate::civilization::concepts::resolve_calamities::calamities::epidemic::{
+    self, EpidemicState, allocate_removal_leaving_one_per_area,
+};
+use crate::civilization::concepts::resolve_calamities::calamities::famine::{self, FamineState};
+use crate::civilization::concepts::resolve_calamities::calamities::flood::{self, FloodState};
+use crate::civilization::concepts::resolve_calamities::calamities::iconoclasm_heresy::IconoclasmHeresyState;
+use crate::civilization::concepts::resolve_calamities::calamities::slave_revolt::SlaveRevoltState;
+use crate::civilization::concepts::resolve_calamities::calamities::superstition::SuperstitionState;
+use crate::civilization::concepts::resolve_calamities::resolve_calamities_ui_components::CITY_UNIT_POINTS;
+use crate::civilization::game_model::{CityOwner, GameModel};
+use crate::civilization::{CivCardName, GameFaction, TradeCard, TradeCardTrait};
+use crate::stupid_ai::{CalamityChooser, NEUTRAL_TRUST, PlayerStanding, Weights, unit_points};
+use bevy::prelude::{Entity, debug};
+use rand::seq::SliceRandom;
+
+/// Rule 30.5211: the size of a Barbarian horde.
+const BARBARIAN_TOKENS: usize = 15;
+
+/// Every nation in the game. The Barbarians borrow one nobody is playing,
+/// as the rules have them use the tokens of an unused nation (30.5211).
+const NATIONS: [GameFaction; 9] = [
+    GameFaction::Egypt,
+    GameFaction::Crete,
+    GameFaction::Africa,
+    GameFaction::Asia,
+    GameFaction::Assyria,
+    GameFaction::Babylon,
+    GameFaction::Illyria,
+    GameFaction::Iberia,
+    GameFaction::Thrace,
+];
+
+/// Rule 30.612: what a city counts for when paying an Epidemic loss.
+const EPIDEMIC_CITY_POINTS: usize = 4;
+
+/// The signature the per-calamity `allocate_secondary_loss` functions share.
+type SecondaryAllocation =
+    fn(&[(Entity, usize)], usize, Option<&[(Entity, usize)]>) -> Vec<(Entity, usize)>;
+
+/// An entity standing in for the `index`th player, area or piece when the
+/// ECS helpers and [`CalamityChooser`] ask for one.
+fn stand_in(index: usize) -> Entity {
+    Entity::from_raw_u32(index as u32).expect("index fits an entity")
+}
+
+impl GameModel {
+    /// `start_calamity_resolution`, `process_pending_calamities` and the
+    /// `advance_*` calamity systems, then `apply_monotheism_conversions`.
+    /// Every choice is made the way it is made for an AI player.
+    pub fn resolve_calamities(&mut self) {
+        let mut queue: Vec<(GameFaction, TradeCard, Option<GameFaction>)> = Vec::new();
+        for i in 0..self.players.len() {
+            let mut calamities: Vec<TradeCard> = self.players[i]
+                .hand_as_vec()
+                .into_iter()
+                .filter(|(card, _)| card.is_calamity())
+                .map(|(card, _)| card)
+                .collect();
+            let traded_by: Vec<(TradeCard, GameFaction)> =
+                self.players[i].calamity_traded_by.clone();
+            // Rule 29.5: at most two, picked at random; the rest are discarded.
+            if calamities.len() > 2 {
+                calamities.shuffle(&mut self.rng);
+                for discarded in calamities.split_off(2) {
+                    self.players[i].remove_card(discarded);
+                }
+            }
+            let faction = self.players[i].faction;
+            queue.extend(calamities.into_iter().map(|card| {
+                let trader = traded_by.iter().find(|(c, _)| *c == card).map(|(_, f)| *f);
+                (faction, card, trader)
+            }));
+        }
+        queue.sort_by_key(|(_, card, _)| (card.value(), card.is_tradeable()));
+
+        for (victim, calamity, traded_by) in queue {
+            debug!("[MODEL] {:?} resolves {:?}", victim, calamity);
+            if let Some(player) = self.player_mut(victim) {
+                player.remove_card(calamity);
+            }
+            match calamity {
+                TradeCard::VolcanoEarthquake => self.volcano_earthquake(victim),
+                TradeCard::Treachery => self.treachery(victim, traded_by),
+                TradeCard::Famine => self.famine(victim),
+                TradeCard::Superstition => self.superstition(victim),
+                TradeCard::CivilWar => self.civil_war(victim),
+                TradeCard::SlaveRevolt => self.slave_revolt(victim),
+                TradeCard::Flood => self.flood(victim),
+                TradeCard::BarbarianHordes => self.barbarian_hordes(victim, traded_by),
+                TradeCard::Epidemic => self.epidemic(victim, traded_by),
+                TradeCard::CivilDisorder => self.civil_disorder(victim),
+                TradeCard::IconoclasmAndHeresy => self.iconoclasm_and_heresy(victim, traded_by),
+                TradeCard::Piracy => self.piracy(victim, traded_by),
+                _ => {}
+            }
+        }
+        self.monotheism_conversions();
+    }
+
+    fn owns(&self, faction: GameFaction, card: CivCardName) -> bool {
+        self.player(faction).is_some_and(|p| p.owns(card))
+    }
+
+    fn is_coastal(&self, area: i32) -> bool {
+        self.rules.area(area).is_some_and(|a| !a.sea.is_empty())
+    }
+
+    fn entity_of(&self, faction: GameFaction) -> Entity {
+        stand_in(
+            self.players
+                .iter()
+                .position(|p| p.faction == faction)
+                .unwrap_or(self.players.len()),
+        )
+    }
+
+    fn faction_of(&self, entity: Entity) -> Option<GameFaction> {
+        (0..self.players.len())
+            .find(|i| stand_in(*i) == entity)
+            .map(|i| self.players[i].faction)
+    }
+
+    /// `CalamityChoosers::for_player` for a player without a personality or
+    /// trade history.
+    fn chooser(&self, me: GameFaction) -> CalamityChooser {
+        let standings = self
+            .players
+            .iter()
+            .enumerate()
+            .map(|(i, p)| {
+                (
+                    stand_in(i),
+                    PlayerStanding {
+                        ast_space: p.ast_space,
+                        cities: self.cities_of(p.faction).len(),
+                        trust: NEUTRAL_TRUST,
+                    },
+                )
+            })
+            .collect();
+        CalamityChooser {
+            me: self.entity_of(me),
+            standings,
+            weights: Weights::uniform(0.5),
+        }
+    }
+
+    /// A secondary loss of `total` among `(player, available)`, divided by
+    /// the primary victim's chooser and clamped by `allocate`.
+    fn divide_loss(
+        &self,
+        victim: GameFaction,
+        secondary: &[(GameFaction, usize)],
+        total: usize,
+        allocate: SecondaryAllocation,
+    ) -> Vec<(GameFaction, usize)> {
+        let secondary: Vec<(Entity, usize)> = secondary
+            .iter()
+            .map(|(f, available)| (self.entity_of(*f), *available))
+            .collect();
+        let choice = self.chooser(victim).divide_loss(&secondary, total);
+        allocate(&secondary, total, Some(&choice))
+            .into_iter()
+            .filter_map(|(e, n)| self.faction_of(e).map(|f| (f, n)))
+            .collect()
+    }
+
+    /// The players other than `victim` with tokens in one of its areas.
+    fn neighbours_of(&self, victim: GameFaction) -> Vec<GameFaction> {
+        let areas = self.areas_of(victim);
+        self.players
+            .iter()
+            .map(|p| p.faction)
+            .filter(|f| {
+                *f != victim
+                    && areas
+                        .iter()
+                        .any(|a| self.area(*a).is_some_and(|o| o.count(*f) > 0))
+            })
+            .collect()
+    }
+
+    /// `reduce_city_in_area`: the city goes back to stock and its owner
+    /// gets the area's population limit in tokens (+1 with Agriculture).
+    fn reduce_city(&mut self, area: i32) {
+        let Some(city) = self.area(area).and_then(|a| a.city) else {
+            return;
+        };
+        self.remove_city(area);
+        if let Some(owner) = city.faction() {
+            let agriculture = usize::from(self.owns(owner, CivCardName::Agriculture));
+            let tokens = (self.max_population(area) + agriculture).min(6);
+            self.place_from_stock(owner, area, tokens);
+        }
+    }
+
+    /// `transfer_city_to_new_owner`: the city goes back to its owner's stock
+    /// and the new owner builds one from theirs, if they have any left.
+    fn transfer_city(&mut self, area: i32, to: CityOwner) {
+        if self.area(area).and_then(|a| a.city).is_none_or(|c| c == to) {
+            return;
+        }
+        self.remove_city(area);
+        let built = match to {
+            CityOwner::Pirate => true,
+            CityOwner::Faction(faction) => self.player_mut(faction).is_some_and(|p| {
+                let has_stock = p.city_stock > 0;
+                p.city_stock -= usize::from(has_stock);
+                has_stock
+            }),
+        };
+        if built && let Some(occupancy) = self.areas.get_mut(&area) {
+            occupancy.city = Some(to);
+        }
+    }
+
+    /// `take_unit_point_loss` for an AI victim: tokens alone when they
+    /// cover the loss, otherwise the fewest cities that close the gap
+    /// (`split_unit_loss`), with tokens taken in area order.
+    fn unit_point_loss(
+        &mut self,
+        faction: GameFaction,
+        points: usize,
+        leave_one_per_area: bool,
+        spend_cities: bool,
+    ) {
+        if points == 0 {
+            return;
+        }
+        let areas = self.areas_of(faction);
+        let counts: Vec<usize> = areas
+            .iter()
+            .map(|a| self.area(*a).map_or(0, |o| o.count(faction)))
+            .collect();
+        let available: usize = counts
+            .iter()
+            .map(|n| {
+                if leave_one_per_area {
+                    n.saturating_sub(1)
+                } else {
+                    *n
+                }
+            })
+            .sum();
+        let cities = if spend_cities {
+            self.cities_of(faction)
+        } else {
+            Vec::new()
+        };
+        let (cities_needed, tokens) = if points <= available {
+            (0, points)
+        } else {
+            let cities_needed = (points - available)
+                .div_ceil(CITY_UNIT_POINTS)
+                .min(cities.len());
+            let tokens = points
+                .saturating_sub(cities_needed * CITY_UNIT_POINTS)
+                .min(available);
+            (cities_needed, tokens)
+        };
+        for area in &cities[..cities_needed] {
+            self.remove_city(*area);
+        }
+        let removals = if leave_one_per_area {
+            allocate_removal_leaving_one_per_area(&counts, tokens)
+        } else {
+            let mut remaining = tokens;
+            counts
+                .iter()
+                .map(|count| {
+                    let take = remaining.min(*count);
+                    remaining -= take;
+                    take
+                })
+                .collect()
+        };
+        for (area, n) in areas.into_iter().zip(removals) {
+            self.return_to_stock(faction, area, n);
+        }
+    }
+
+    /// `spend_epidemic_budget_on_cities`: each city, in order, takes up to 4
+    /// points and is destroyed while any of the loss is left. Returns what
+    /// remains for tokens.
+    fn spend_epidemic_budget_on_cities(&mut self, faction: GameFaction, points: usize) -> usize {
+        let mut remaining = points;
+        for area in self.cities_of(faction) {
+            if remaining == 0 {
+                break;
+            }
+            remaining -= remaining.min(EPIDEMIC_CITY_POINTS);
+            self.remove_city(area);
+        }
+        remaining
+    }
+
+    /// Rule 30.21: a volcano next to one of the victim's cities erupts where
+    /// it does the most damage, clearing its area and the land around it;
+    /// without one, an earthquake destroys the victim's first city and
+    /// reduces a neighbouring one.
+    fn volcano_earthquake(&mut self, victim: GameFaction) {
+        let mut eruption: Option<(usize, Vec<i32>)> = None;
+        for (id, area) in &self.rules.areas {
+            if !area.volcano {
+                continue;
+            }
+            let touched: Vec<i32> = std::iter::once(*id)
+                .chain(area.land.iter().copied())
+                .collect();
+            let mut damage = 0;
+            let mut victim_has_city = false;
+            for occupancy in touched.iter().filter_map(|a| self.area(*a)) {
+                if occupancy.city.is_some() {
+                    damage += CITY_UNIT_POINTS;
+                    victim_has_city |= occupancy.city_owner() == Some(victim);
+                }
+                damage += occupancy.total();
+            }
+            if victim_has_city && eruption.as_ref().is_none_or(|(best, _)| damage > *best) {
+                eruption = Some((damage, touched));
+            }
+        }
+        if let Some((_, touched)) = eruption {
+            for area in touched {
+                self.remove_city(area);
+                for faction in self.area(area).map(|a| a.factions()).unwrap_or_default() {
+                    self.return_to_stock(faction, area, usize::MAX);
+                }
+            }
+            return;
+        }
+
+        let Some(&city) = self.cities_of(victim).first() else {
+            return;
+        };
+        if self.owns(victim, CivCardName::Engineering) {
+            self.reduce_city(city);
+            return;
+        }
+        let neighbour = self.rules.area(city).and_then(|a| {
+            a.land.iter().copied().find(|adjacent| {
+                self.area(*adjacent)
+                    .and_then(|o| o.city)
+                    .is_some_and(|owner| {
+                        owner != CityOwner::Faction(victim)
+                            && !owner
+                                .faction()
+                                .is_some_and(|f| self.owns(f, CivCardName::Engineering))
+                    })
+            })
+        });
+        self.remove_city(city);
+        if let Some(neighbour) = neighbour {
+            self.reduce_city(neighbour);
+        }
+    }
+
+    /// Rule 30.23: the victim's first city goes to whoever traded the
+    /// calamity, or is reduced if it was drawn.
+    fn treachery(&mut self, victim: GameFaction, traded_by: Option<GameFaction>) {
+        let Some(&city) = self.cities_of(victim).first() else {
+            return;
+        };
+        match traded_by {
+            Some(trader) => self.transfer_city(city, CityOwner::Faction(trader)),
+            None => self.reduce_city(city),
+        }
+    }
+
+    /// Rule 30.31: 10 unit points (less 4 per Grain with Pottery, which then
+    /// stays locked), and 20 more from the players sharing the victim's
+    /// areas, at most 8 each.
+    fn famine(&mut self, victim: GameFaction) {
+        let grain = self
+            .player(victim)
+            .and_then(|p| p.hand.get(&TradeCard::Grain).copied())
+            .unwrap_or(0);
+        let state =
+            FamineState::new().with_grain_reduction(grain, self.owns(victim, CivCardName::Pottery));
+        self.unit_point_loss(victim, state.primary_loss.max(0) as usize, false, true);
+        if state.grain_cards_used > 0 {
+            self.grain_locked.insert(victim, state.grain_cards_used);
+        }
+
+        let cap = state.max_per_secondary.max(0) as usize;
+        let secondary: Vec<(GameFaction, usize)> = self
+            .neighbours_of(victim)
+            .into_iter()
+            .map(|f| (f, self.population(f).min(cap)))
+            .collect();
+        let total = state.secondary_total.max(0) as usize;
+        for (faction, loss) in
+            self.divide_loss(victim, &secondary, total, famine::allocate_secondary_loss)
+        {
+            self.unit_point_loss(faction, loss, false, true);
+        }
+    }
+
+    /// Rule 30.32: three cities reduced, fewer with Mysticism, Deism or
+    /// Enlightenment.
+    fn superstition(&mut self, victim: GameFaction) {
+        let state = if self.owns(victim, CivCardName::Enlightenment) {
+            SuperstitionState::new().with_enlightenment()
+        } else if self.owns(victim, CivCardName::Deism) {
+            SuperstitionState::new().with_deism()
+        } else if self.owns(victim, CivCardName::Mysticism) {
+            SuperstitionState::new().with_mysticism()
+        } else {
+            SuperstitionState::new()
+        };
+        for city in self
+            .cities_of(victim)
+            .into_iter()
+            .take(state.cities_to_reduce)
+        {
+            self.reduce_city(city);
+        }
+    }
+
+    /// Rule 30.42: 15 tokens cannot support cities (Mining +5,
+    /// Enlightenment −5), and the cities they leave unsupported are reduced.
+    fn slave_revolt(&mut self, victim: GameFaction) {
+        let mut state = match (
+            self.owns(victim, CivCardName::Mining),
+            self.owns(victim, CivCardName::Enlightenment),
+        ) {
+            (true, true) => SlaveRevoltState::new().with_mining_and_enlightenment(),
+            (true, false) => SlaveRevoltState::new().with_mining(),
+            (false, true) => SlaveRevoltState::new().with_enlightenment(),
+            (false, false) => SlaveRevoltState::new(),
+        };
+        let cities = self.cities_of(victim);
+        state.compute_cities_to_reduce(self.population(victim), cities.len());
+        for city in cities.into_iter().take(state.cities_to_reduce) {
+            self.reduce_city(city);
+        }
+    }
+
+    /// Rule 30.71: all but three cities reduced, the three moved by the
+    /// victim's civilization cards.
+    fn civil_disorder(&mut self, victim: GameFaction) {
+        let mut state = CivilDisorderState::new();
+        if self.owns(victim, CivCardName::Music) {
+            state = state.with_music();
+        }
+        if self.owns(victim, CivCardName::DramaAndPoetry) {
+            state = state.with_drama_and_poetry();
+        }
+        if self.owns(victim, CivCardName::Law) {
+            state = state.with_law();
+        }
+        if self.owns(victim, CivCardName::Democracy) {
+            state = state.with_democracy();
+        }
+        if self.owns(victim, CivCardName::Military) {
+            state = state.with_military();
+        }
+        if self.owns(victim, CivCardName::RoadBuilding) {
+            state = state.with_road_building();
+        }
+        let cities = self.cities_of(victim);
+        state.compute_cities_to_reduce(cities.len());
+        for city in cities.into_iter().take(state.cities_to_reduce) {
+            self.reduce_city(city);
+        }
+    }
+
+    /// Rule 30.81: four of the victim's cities reduced, moved by its cards,
+    /// and two cities of other players: never a Theology holder's, at most
+    /// one of a Philosophy holder's, none of the trader's.
+    fn iconoclasm_and_heresy(&mut self, victim: GameFaction, traded_by: Option<GameFaction>) {
+        let mut state = IconoclasmHeresyState::new();
+        if self.owns(victim, CivCardName::Law) {
+            state = state.with_law();
+        }
+        if self.owns(victim, CivCardName::Philosophy) {
+            state = state.with_philosophy();
+        }
+        if self.owns(victim, CivCardName::Theology) {
+            state = state.with_theology();
+        }
+        if self.owns(victim, CivCardName::Monotheism) {
+            state = state.with_monotheism();
+        }
+        if self.owns(victim, CivCardName::RoadBuilding) {
+            state = state.with_road_building();
+        }
+        let own: Vec<i32> = self
+            .cities_of(victim)
+            .into_iter()
+            .take(state.cities_to_reduce)
+            .collect();
+        let others: Vec<i32> = self
+            .players
+            .iter()
+            .filter(|p| {
+                p.faction != victim
+                    && Some(p.faction) != traded_by
+                    && !p.owns(CivCardName::Theology)
+            })
+            .flat_map(|p| {
+                let limit = if p.owns(CivCardName::Philosophy) {
+                    1
+                } else {
+                    usize::MAX
+                };
+                self.cities_of(p.faction).into_iter().take(limit)
+            })
+            .take(state.secondary_cities)
+            .collect();
+        for city in own.into_iter().chain(others) {
+            self.reduce_city(city);
+        }
+    }
+
+    /// Rule 30.51: the flood plain holding most of the victim's tokens loses
+    /// up to 17 of the victim's unit points (7 with Engineering) and 10 of
+    /// everyone else's there. Without one, a coastal city is lost instead.
+    fn flood(&mut self, victim: GameFaction) {
+        let mut state = FloodState::new();
+        if self.owns(victim, CivCardName::Engineering) {
+            state = state.with_engineering();
+        }
+        let mut plain: Option<i32> = None;
+        let mut most = 0;
+        for (id, area) in &self.rules.areas {
+            let tokens = self.area(*id).map_or(0, |a| a.count(victim));
+            if area.flood_plain && tokens > most {
+                most = tokens;
+                plain = Some(*id);
+            }
+        }
+
+        let Some(plain) = plain else {
+            let coastal = self
+                .cities_of(victim)
+                .into_iter()
+                .find(|a| self.is_coastal(*a));
+            match coastal {
+                Some(city) if state.has_engineering => self.reduce_city(city),
+                Some(city) => self.remove_city(city),
+                None => {}
+            }
+            return;
+        };
+        let mut cap = state.primary_max_loss.max(0) as usize;
+        let loses_city = cap > 0 && self.area(plain).and_then(|a| a.city_owner()) == Some(victim);
+        if loses_city {
+            cap -= cap.min(CITY_UNIT_POINTS);
+        }
+        self.return_to_stock(victim, plain, cap);
+        match loses_city {
+            true if state.has_engineering => self.reduce_city(plain),
+            true => self.remove_city(plain),
+            false => {}
+        }
+
+        let secondary: Vec<(GameFaction, usize)> = self
+            .area(plain)
+            .map(|a| {
+                a.tokens
+                    .iter()
+                    .copied()
+                    .filter(|(f, _)| *f != victim)
+                    .collect()
+            })
+            .unwrap_or_default();
+        for (faction, loss) in
+            self.divide_loss(victim, &secondary, 10, flood::allocate_secondary_loss)
+        {
+            self.return_to_stock(faction, plain, loss);
+        }
+    }
+
+    /// Rule 30.61: 16 unit points (Medicine −8, Road Building +5) and 25 more
+    /// from the players sharing the victim's areas, at most 10 each (5 with
+    /// Medicine). The trader is immune; every area keeps a token.
+    fn epidemic(&mut self, victim: GameFaction, traded_by: Option<GameFaction>) {
+        let mut state = EpidemicState::new();
+        if self.owns(victim, CivCardName::Medicine) {
+            state = state.with_medicine();
+        }
+        if self.owns(victim, CivCardName::RoadBuilding) {
+            state = state.with_road_building();
+        }
+        let remaining =
+            self.spend_epidemic_budget_on_cities(victim, state.primary_loss.max(0) as usize);
+        self.unit_point_loss(victim, remaining, true, false);
+
+        let secondary: Vec<(GameFaction, usize)> = self
+            .neighbours_of(victim)
+            .into_iter()
+            .filter(|f| Some(*f) != traded_by)
+            .map(|f| {
+                let cap = if self.owns(f, CivCardName::Medicine) {
+                    5
+                } else {
+                    10
+                };
+                (f, cap)
+            })
+            .collect();
+        let total = state.secondary_loss.max(0) as usize;
+        for (faction, loss) in
+            self.divide_loss(victim, &secondary, total, epidemic::allocate_secondary_loss)
+        {
+            let remaining = self.spend_epidemic_budget_on_cities(faction, loss);
+            self.unit_point_loss(faction, remaining, true, false);
+        }
+    }
+
+    /// Rule 30.41: the victim's holdings split into two factions, one picked
+    /// by the victim and topped up by the beneficiary (the player with most
+    /// tokens in stock), the other whatever is left. The victim keeps one
+    /// and the beneficiary takes over the other.
+    fn civil_war(&mut self, victim: GameFaction) {
+        let Some(beneficiary) = self
+            .players
+            .iter()
+            .filter(|p| p.faction != victim)
+            .max_by_key(|p| p.stock)
+            .map(|p| p.faction)
+        else {
+            return;
+        };
+        let mut state = CivilWarState::new();
+        if self.owns(victim, CivCardName::Philosophy) {
+            state = state.with_philosophy_override();
+        } else {
+            if self.owns(victim, CivCardName::Music) {
+                state.apply_music_bonus();
+            }
+            if self.owns(victim, CivCardName::DramaAndPoetry) {
+                state.apply_drama_poetry_bonus();
+            }
+            if self.owns(victim, CivCardName::Democracy) {
+                state.apply_democracy_bonus();
+            }
+        }
+        let military = self.owns(victim, CivCardName::Military)
+            || self.owns(beneficiary, CivCardName::Military);
+
+        // One entry per token, by area, then one per city: the state keeps
+        // them as entities, so each stands in as its index here.
+        let mut pieces: Vec<i32> = Vec::new();
+        for area in self.areas_of(victim) {
+            let count = self.area(area).map_or(0, |a| a.count(victim));
+            pieces.extend(std::iter::repeat_n(area, count));
+        }
+        let units: Vec<Entity> = (0..pieces.len()).map(stand_in).collect();
+        pieces.extend(self.cities_of(victim));
+        let cities: Vec<Entity> = (units.len()..pieces.len()).map(stand_in).collect();
+
+        let (mut remaining_units, mut remaining_cities) = (units.clone(), cities.clone());
+        let mut pick = |target: usize| {
+            let taken_units: Vec<Entity> = remaining_units
+                .drain(..target.min(remaining_units.len()))
+                .collect();
+            let mut points = taken_units.len();
+            let mut taken_cities = Vec::new();
+            while points < target && !remaining_cities.is_empty() {
+                taken_cities.push(remaining_cities.remove(0));
+                points += CITY_UNIT_POINTS;
+            }
+            (taken_units, taken_cities)
+        };
+        (state.victim_selected_units, state.victim_selected_cities) =
+            pick(state.victim_selection_points);
+        (
+            state.beneficiary_selected_units,
+            state.beneficiary_selected_cities,
+        ) = pick(state.beneficiary_selection_points);
+        if military {
+            state = state.with_military_penalty();
+        }
+        state.apply_military_penalty_to_first_faction();
+        state.compute_second_faction(units, cities);
+        state.apply_military_penalty_to_second_faction();
+        if !state.has_second_faction() {
+            return;
+        }
+
+        let first = (
+            state.first_faction_points(),
+            state.victim_selected_cities.len() + state.beneficiary_selected_cities.len(),
+        );
+        let second = (
+            state.second_faction_points(),
+            state.second_faction_cities.len(),
+        );
+        state.kept_faction = Some(self.chooser(victim).keep_faction(first, second));
+        let area_of = |piece: Entity| {
+            (0..pieces.len())
+                .find(|i| stand_in(*i) == piece)
+                .map(|i| pieces[i])
+        };
+        for area in state.transferring_units().into_iter().filter_map(area_of) {
+            self.return_to_stock(victim, area, 1);
+            self.place_from_stock(beneficiary, area, 1);
+        }
+        for area in state.transferring_cities().into_iter().filter_map(area_of) {
+            self.transfer_city(area, CityOwner::Faction(beneficiary));
+        }
+    }
+
+    /// Rule 30.91: two of the victim's coastal cities and one each of two
+    /// other players' become Pirate cities. The trader is spared.
+    fn piracy(&mut self, victim: GameFaction, traded_by: Option<GameFaction>) {
+        let mut cities: Vec<i32> = self
+            .cities_of(victim)
+            .into_iter()
+            .filter(|a| self.is_coastal(*a))
+            .take(2)
+            .collect();
+        let secondary: Vec<i32> = self
+            .players
+            .iter()
+            .filter(|p| p.faction != victim && Some(p.faction) != traded_by)
+            .filter_map(|p| {
+                self.cities_of(p.faction)
+                    .into_iter()
+                    .find(|a| self.is_coastal(*a))
+            })
+            .take(2)
+            .collect();
+        cities.extend(secondary);
+        for city in cities {
+            self.transfer_city(city, CityOwner::Pirate);
+        }
+    }
+
+    /// Rule 30.52: fifteen Barbarians land in the victim's start area where
+    /// they do most damage, fight there, and send any surplus on to the
+    /// neighbouring area where it does most damage, until none is left.
+    /// Surviving Barbarians leave the board afterwards, as in
+    /// `advance_barbarian_hordes`.
+    fn barbarian_hordes(&mut self, victim: GameFaction, traded_by: Option<GameFaction>) {
+        // Rule 30.527: Crete may not be the primary victim.
+        if victim == GameFaction::Crete {
+            return;
+        }
+        let Some(horde) = NATIONS.into_iter().find(|f| self.player(*f).is_none()) else {
+            debug!("[MODEL] every nation is playing; no Barbarians to field");
+            return;
+        };
+        let start_areas: Vec<i32> = self
+            .rules
+            .areas
+            .values()
+            .filter(|a| a.start_area == Some(victim))
+            .map(|a| a.id)
+            .collect();
+        let Some(mut area) = self.barbarian_target(victim, &start_areas, traded_by) else {
+            return;
+        };
+        if let Some(occupancy) = self.areas.get_mut(&area) {
+            occupancy.add(horde, BARBARIAN_TOKENS);
+        }
+
+        let mut visited: Vec<i32> = Vec::new();
+        let mut iterations = 0;
+        loop {
+            if !visited.contains(&area) {
+                visited.push(area);
+            }
+            let max = self.max_population(area);
+            let Some(occupancy) = self.area(area) else {
+                break;
+            };
+            let has_city = occupancy.city.is_some();
+            if (occupancy.number_of_players() > 1 && occupancy.total() > max)
+                || (has_city && occupancy.total() > 0)
+            {
+                if has_city {
+                    self.resolve_city_conflict(area);
+                } else {
+                    self.resolve_regular_conflict(area);
+                }
+            }
+            let surplus = self.area(area).map_or(0, |a| a.total().saturating_sub(max));
+            if surplus == 0 || iterations >= MAX_CASCADE_ITERATIONS {
+                break;
+            }
+            let neighbours: Vec<i32> = self
+                .rules
+                .area(area)
+                .map(|a| a.land.iter().chain(&a.sea).copied().collect())
+                .unwrap_or_default();
+            let Some(next) = self.barbarian_target(victim, &neighbours, traded_by) else {
+                break;
+            };
+            let moving = self
+                .areas
+                .get_mut(&area)
+                .map_or(0, |a| a.remove(horde, surplus));
+            if let Some(occupancy) = self.areas.get_mut(&next) {
+                occupancy.add(horde, moving);
+            }
+            area = next;
+            iterations += 1;
+        }
+        for area in visited {
+            if let Some(occupancy) = self.areas.get_mut(&area) {
+                occupancy.remove(horde, usize::MAX);
+            }
+        }
+    }
+
+    /// Of `candidates`, the area where Barbarians do the victim most damage
+    /// (rule 30.5234); a tie goes to the trader, or else to the player with
+    /// most tokens in stock (30.525), as `break_barbarian_tie` hands it out.
+    fn barbarian_target(
+        &self,
+        victim: GameFaction,
+        candidates: &[i32],
+        traded_by: Option<GameFaction>,
+    ) -> Option<i32> {
+        let scored: Vec<(i32, usize)> = candidates
+            .iter()
+            .map(|id| {
+                let occupancy = self.area(*id);
+                let tokens = occupancy.map_or(0, |a| a.count(victim));
+                let city = occupancy.is_some_and(|a| a.city_owner() == Some(victim));
+                (*id, barbarian_damage_score(tokens, city))
+            })
+            .collect();
+        let best = scored.iter().map(|(_, score)| *score).max()?;
+        let tied: Vec<i32> = scored
+            .into_iter()
+            .filter(|(_, score)| *score == best)
+            .map(|(id, _)| id)
+            .collect();
+        if tied.len() == 1 {
+            return tied.first().copied();
+        }
+        let Some(decider) = traded_by.or_else(|| {
+            self.players
+                .iter()
+                .max_by_key(|p| p.stock)
+                .map(|p| p.faction)
+        }) else {
+            return tied.first().copied();
+        };
+        let occupants: Vec<(Entity, Vec<(Entity, usize)>)> = tied
+            .iter()
+            .enumerate()
+            .map(|(i, id)| {
+                let occupancy = self.area(*id).cloned().unwrap_or_default();
+                let city_owner = occupancy.city_owner();
+                let mut owners: Vec<(Entity, usize)> = occupancy
+                    .tokens
+                    .iter()
+                    .filter(|(f, _)| self.player(*f).is_some())
+                    .map(|(f, n)| (self.entity_of(*f), unit_points(*n, city_owner == Some(*f))))
+                    .collect();
+                if let Some(owner) = city_owner
+                    && occupancy.count(owner) == 0
+                {
+                    owners.push((self.entity_of(owner), unit_points(0, true)));
+                }
+                owners.sort_by_key(|(owner, _)| *owner);
+                (stand_in(i), owners)
+            })
+            .collect();
+        let picked = self.chooser(decider).pick_area(&occupants);
+        (0..tied.len())
+            .find(|i| Some(stand_in(*i)) == picked)
+            .map_or(tied.first().copied(), |i| tied.get(i).copied())
+    }
+
+    /// Rule 32.94: each Monotheism holder sends two enemy tokens next to its
+    /// areas back to stock, taken from the owners its chooser most wants
+    /// hit. Theology holders are immune.
+    fn monotheism_conversions(&mut self) {
+        let holders: Vec<GameFaction> = self
+            .players
+            .iter()
+            .filter(|p| p.owns(CivCardName::Monotheism))
+            .map(|p| p.faction)
+            .collect();
+        for holder in holders {
+            let mut seen: Vec<i32> = Vec::new();
+            let mut candidates: Vec<(i32, GameFaction)> = Vec::new();
+            for area in self.areas_of(holder) {
+                let adjacent = self
+                    .rules
+                    .area(area)
+                    .map(|a| a.land.clone())
+                    .unwrap_or_default();
+                for adjacent in adjacent {
+                    if seen.contains(&adjacent) {
+                        continue;
+                    }
+                    seen.push(adjacent);
+                    for (owner, n) in self.area(adjacent).map_or(&[][..], |a| &a.tokens[..]) {
+                        if *owner != holder && !self.owns(*owner, CivCardName::Theology) {
+                            candidates.extend(std::iter::repeat_n((adjacent, *owner), *n));
+                        }
+                    }
+                }
+            }
+            let by_owner: Vec<(Entity, Entity)> = candidates
+                .iter()
+                .enumerate()
+                .map(|(i, (_, owner))| (stand_in(i), self.entity_of(*owner)))
+                .collect();
+            for token in self.chooser(holder).pick_tokens(&by_owner, 2) {
+                if let Some(&(area, owner)) = (0..candidates.len())
+                    .find(|i| stand_in(*i) == token)
+                    .and_then(|i| candidates.get(i))
+                {
+                    self.return_to_stock(owner, area, 1);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::civilization::AstTrack;
+    use crate::civilization::game_model::{ModelArea, ModelPlayer, ModelRules};
+    use std::sync::Arc;
+
+    fn model(n: i32) -> GameModel {
+        let areas = (1..=n).map(|id| ModelArea {
+            id,
+            max_population: 3,
+            land: Vec::new(),
+            sea: Vec::new(),
+            city_site: true,
+            flood_plain: false,
+            city_flood: false,
+            volcano: false,
+            start_area: None,
+        });
+        let rules = ModelRules::new(areas, None, AstTrack::standard());
+        let players = [GameFaction::Egypt, GameFaction::Crete]
+            .into_iter()
+            .map(|faction| ModelPlayer {
+                faction,
+                name: format!("{faction:?}"),
+                is_human: false,
+                stock: 55,
+                city_stock: 9,
+                treasury: 0,
+                hand: Default::default(),
+                calamity_traded_by: Vec::new(),
+                civ_cards: Default::default(),
+                ast_space: 0,
+                census_population: 0,
+            })
+            .collect();
+        GameModel::new(Arc::new(rules), players, 7)
+    }
+
+    fn build(model: &mut GameModel, faction: GameFaction, areas: &[i32]) {
+        for area in areas {
+            model.areas.get_mut(area).unwrap().city = Some(CityOwner::Faction(faction));
+            model.player_mut(faction).unwrap().city_stock -= 1;
+        }
+    }
+
+    #[test]
+    fn superstition_reduces_three_cities_and_mysticism_spares_one() {
+        let mut model = model(7);
+        build(&mut model, GameFaction::Egypt, &[1, 3, 5, 7]);
+        build(&mut model, GameFaction::Crete, &[2, 4, 6]);
+        for faction in [GameFaction::Egypt, GameFaction::Crete] {
+            model
+                .player_mut(faction)
+                .unwrap()
+                .add_card(TradeCard::Superstition);
+        }
+        let crete = model.player_mut(GameFaction::Crete).unwrap();
+        crete.civ_cards.insert(CivCardName::Mysticism);
+
+        model.resolve_calamities();
+
+        assert_eq!(model.cities_of(GameFaction::Egypt), vec![7]);
+        assert_eq!(model.cities_of(GameFaction::Crete), vec![6]);
+        let egypt = model.player(GameFaction::Egypt).unwrap();
+        assert_eq!((egypt.city_stock, egypt.stock), (8, 46));
+        assert_eq!(model.area(3).unwrap().count(GameFaction::Egypt), 3);
+        assert_eq!(model.player(GameFaction::Crete).unwrap().stock, 49);
+        assert!(egypt.hand.is_empty());
+    }
+
+    #[test]
+    fn famine_spends_grain_with_pottery_and_strikes_the_neighbours() {
+        let mut model = model(1);
+        model.place_from_stock(GameFaction::Egypt, 1, 12);
+        model.place_from_stock(GameFaction::Crete, 1, 5);
+        let egypt = model.player_mut(GameFaction::Egypt).unwrap();
+        egypt.civ_cards.insert(CivCardName::Pottery);
+        egypt.add_card(TradeCard::Grain);
+        egypt.add_card(TradeCard::Famine);
+
+        model.resolve_calamities();
+
+        // One Grain takes 4 off the 10; Crete's 5 are under the 8 cap and
+        // the 20 to share, so all of them go.
+        assert_eq!(model.area(1).unwrap().count(GameFaction::Egypt), 6);
+        assert_eq!(model.area(1).unwrap().count(GameFaction::Crete), 0);
+        assert_eq!(model.grain_locked[&GameFaction::Egypt], 1);
+        let egypt = model.player(GameFaction::Egypt).unwrap();
+        assert_eq!(egypt.hand_as_vec(), vec![(TradeCard::Grain, 1)]);
+    }
+}
@@ -0,0 +1,371 @@
+use crate::civilization::game_model::{GameModel, ModelMove};
+use crate::civilization::{CivCardName, GameFaction, TradeCard, TradeCardTrait};
+
+/// Rule 31.71: commodity cards a player may keep after buying civilization
+/// cards.
+const MAX_RETAINED_COMMODITIES: usize = 8;
+
+impl GameModel {
+    /// `begin_acquire_civ_cards`: everyone buys at once, with credits from
+    /// the cards they hold now. Players who can afford nothing are done
+    /// straight away, as an AI offered only `DoneAcquiringCards` would be.
+    pub(super) fn enter_acquire_civ_cards(&mut self) {
+        self.pending = self.players.iter().map(|p| p.faction).collect();
+        self.held_before = self
+            .players
+            .iter()
+            .map(|p| (p.faction, p.civ_cards.clone()))
+            .collect();
+        for faction in self.pending.clone() {
+            if self.civ_card_moves(faction).len() == 1 {
+                self.finish_acquiring(faction);
+            }
+        }
+    }
+
+    /// A purchase phase picked up from a save, which does not record the
+    /// cards held when it began: the ones held now stand in for them.
+    pub(super) fn resume_acquire_civ_cards(&mut self) {
+        for faction in self.pending.clone() {
+            if let Some(player) = self.player(faction) {
+                self.held_before.insert(faction, player.civ_cards.clone());
+            }
+            if self.civ_card_moves(faction).len() == 1 {
+                self.finish_acquiring(faction);
+            }
+        }
+    }
+
+    /// As in `recalculate_civ_card_moves_for_player`: every card not owned
+    /// whose prerequisites are held and whose cost the hand covers, in
+    /// definition order, then `DoneAcquiringCards`.
+    pub(super) fn civ_card_moves(&self, faction: GameFaction) -> Vec<ModelMove> {
+        let Some(player) = self.player(faction) else {
+            return Vec::new();
+        };
+        let credits = self.rules.credits(&player.civ_cards);
+        let buying_power = player.buying_power();
+        let mut moves: Vec<ModelMove> = self
+            .rules
+            .civ_cards
+            .iter()
+            .filter(|card| {
+                !player.owns(card.name)
+                    && card.prerequisites.iter().all(|p| player.owns(*p))
+                    && card.calculate_cost(&credits) as usize <= buying_power
+            })
+            .map(|card| ModelMove::AcquireCivCard(card.name))
+            .collect();
+        moves.push(ModelMove::DoneAcquiringCards);
+        moves
+    }
+
+    /// `select_stupid_civ_card_move` buying one card: the price is set by
+    /// the cards held when the phase began (rule 31.53) and paid the way
+    /// `compute_ai_payment` pays it; the spent cards go back to their piles.
+    pub(super) fn buy_civ_card(&mut self, faction: GameFaction, card: CivCardName) {
+        let credits = self.rules.credits(
+            self.held_before
+                .get(&faction)
+                .or_else(|| self.player(faction).map(|p| &p.civ_cards))
+                .unwrap_or(&Default::default()),
+        );
+        let cost = self
+            .rules
+            .civ_card(card)
+            .map_or(0, |def| def.calculate_cost(&credits) as usize);
+        let payment = self.ai_payment(faction, cost);
+        let Some(player) = self.player_mut(faction) else {
+            return;
+        };
+        player.civ_cards.insert(card);
+        let mut paid = Vec::new();
+        for (trade_card, count) in payment {
+            for _ in 0..count {
+                if player.remove_card(trade_card) {
+                    paid.push(trade_card);
+                }
+            }
+        }
+        for trade_card in paid {
+            self.piles
+                .entry(trade_card.value())
+                .or_default()
+                .push(trade_card);
+        }
+    }
+
+    /// `compute_ai_payment`: stacks by descending face value, locked Grain
+    /// left out, each spent whole while it does not cover what is left and
+    /// otherwise only as many cards as finish the price.
+    fn ai_payment(&self, faction: GameFaction, cost: usize) -> Vec<(TradeCard, usize)> {
+        let Some(player) = self.player(faction) else {
+            return Vec::new();
+        };
+        let locked = self.grain_locked.get(&faction).copied().unwrap_or(0);
+        let mut stacks: Vec<(TradeCard, usize)> = player
+            .hand_as_vec()
+            .into_iter()
+            .filter(|(card, _)| card.is_commodity())
+            .map(|(card, n)| {
+                if card == TradeCard::Grain {
+                    (card, n.saturating_sub(locked))
+                } else {
+                    (card, n)
+                }
+            })
+            .filter(|(_, n)| *n > 0)
+            .collect();
+        stacks.sort_by_key(|(card, _)| std::cmp::Reverse(card.value()));
+        let mut payment = Vec::new();
+        let mut remaining = cost;
+        for (card, count) in stacks {
+            if remaining == 0 {
+                break;
+            }
+            let face = card.value();
+            if count * count * face <= remaining {
+                payment.push((card, count));
+                remaining -= count * count * face;
+            } else {
+                let mut n = 1;
+                while n * n * face < remaining {
+                    n += 1;
+                }
+                payment.push((card, n.min(count)));
+                remaining = 0;
+            }
+        }
+        payment
+    }
+
+    /// `player_is_done`: the player stops buying and keeps at most eight
+    /// commodity cards.
+    pub(super) fn finish_acquiring(&mut self, faction: GameFaction) {
+        self.pending.retain(|f| *f != faction);
+        self.held_before.remove(&faction);
+        self.enforce_commodity_retention_limit(faction);
+    }
+
+    /// Rule 31.71, as `enforce_commodity_retention_limit` applies it.
+    fn enforce_commodity_retention_limit(&mut self, faction: GameFaction) {
+        loop {
+            let Some(player) = self.player(faction) else {
+                return;
+            };
+            let commodities: usize = player
+                .hand_as_vec()
+                .iter()
+                .filter(|(card, _)| card.is_commodity())
+                .map(|(_, n)| n)
+                .sum();
+            if commodities <= MAX_RETAINED_COMMODITIES {
+                return;
+            }
+            let Some(card) = player.worst_commodity() else {
+                return;
+            };
+            if let Some(player) = self.player_mut(faction) {
+                player.remove_card(card);
+            }
+            self.piles.entry(card.value()).or_default().push(card);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::GameActivity;
+    use crate::civilization::concepts::recalculate_civ_card_moves_for_player;
+    use crate::civilization::game_model::{ModelArea, ModelPlayer, ModelRules};
+    use crate::civilization::{
+        AcquireCivilizationCardsMove, AstTrack, AvailableCivCards, AvailableMoves,
+        CivCardDefinition, CivCardType, Credits, GameMove, PlayerCivilizationCards,
+        PlayerTradeCards, RecalculatePlayerMoves,
+    };
+    use bevy::ecs::system::RunSystemOnce;
+    use bevy::platform::collections::HashSet;
+    use bevy::prelude::{Messages, World};
+    use enumflags2::BitFlags;
+    use rand::rngs::StdRng;
+    use rand::{RngExt, SeedableRng};
+    use std::sync::Arc;
+
+    fn card(name: CivCardName, cost: u32, credits: Vec<Credits>) -> CivCardDefinition {
+        CivCardDefinition {
+            name,
+            description: String::new(),
+            card_type: BitFlags::from(CivCardType::Crafts),
+            cost,
+            credits,
+            prerequisites: Vec::new(),
+        }
+    }
+
+    fn model(hand: &[(TradeCard, usize)]) -> GameModel {
+        let areas = [ModelArea {
+            id: 1,
+            max_population: 3,
+            land: Vec::new(),
+            sea: Vec::new(),
+            city_site: true,
+            flood_plain: false,
+            city_flood: false,
+            volcano: false,
+            start_area: None,
+        }];
+        let mut rules = ModelRules::new(areas, None, AstTrack::standard());
+        rules.civ_cards = vec![
+            card(
+                CivCardName::Pottery,
+                45,
+                vec![Credits::ToType(CivCardType::Crafts, 10)],
+            ),
+            card(CivCardName::ClothMaking, 45, Vec::new()),
+        ];
+        let player = ModelPlayer {
+            faction: GameFaction::Egypt,
+            name: "Egypt".to_string(),
+            is_human: false,
+            stock: 55,
+            city_stock: 9,
+            treasury: 0,
+            hand: hand.iter().copied().collect(),
+            calamity_traded_by: Vec::new(),
+            civ_cards: Default::default(),
+            ast_space: 0,
+            census_population: 0,
+        };
+        let mut model = GameModel::new(Arc::new(rules), vec![player], 1);
+        model.activity = GameActivity::AcquireCivilizationCards;
+        model.enter_acquire_civ_cards();
+        model
+    }
+
+    #[test]
+    fn a_purchase_pays_from_the_best_stack_and_prices_with_earlier_credits() {
+        let mut model = model(&[(TradeCard::Salt, 4), (TradeCard::Ochre, 9)]);
+
+        assert_eq!(
+            model.legal_moves(),
+            vec![
+                ModelMove::AcquireCivCard(CivCardName::Pottery),
+                ModelMove::AcquireCivCard(CivCardName::ClothMaking),
+                ModelMove::DoneAcquiringCards,
+            ]
+        );
+        model
+            .apply(ModelMove::AcquireCivCard(CivCardName::Pottery))
+            .unwrap();
+
+        // 4 Salt (48) is the least of the best stack that covers 45.
+        let egypt = model.player(GameFaction::Egypt).unwrap();
+        assert!(egypt.owns(CivCardName::Pottery));
+        assert_eq!(egypt.hand_as_vec(), vec![(TradeCard::Ochre, 9)]);
+        assert_eq!(model.piles[&3], vec![TradeCard::Salt; 4]);
+
+        model
+            .apply(ModelMove::AcquireCivCard(CivCardName::ClothMaking))
+            .unwrap();
+
+        // Pottery's credit would bring Cloth Making down to 35 (6 Ochre), but
+        // credits only count from the next purchase phase: 7 Ochre pay 45.
+        let egypt = model.player(GameFaction::Egypt).unwrap();
+        assert_eq!(egypt.hand_as_vec(), vec![(TradeCard::Ochre, 2)]);
+        assert!(model.pending.is_empty());
+    }
+
+    #[test]
+    fn finishing_returns_commodities_beyond_eight() {
+        let mut model = model(&[(TradeCard::Ochre, 9)]);
+
+        model.apply(ModelMove::DoneAcquiringCards).unwrap();
+
+        let egypt = model.player(GameFaction::Egypt).unwrap();
+        assert_eq!(egypt.hand_as_vec(), vec![(TradeCard::Ochre, 8)]);
+        assert!(model.pending.is_empty());
+    }
+
+    /// What `recalculate_civ_card_moves_for_player` offers a player holding
+    /// `hand` and `civ_cards`, in command order.
+    fn ecs_moves(
+        defs: &AvailableCivCards,
+        hand: &[(TradeCard, usize)],
+        civ_cards: &HashSet<CivCardName>,
+    ) -> Vec<ModelMove> {
+        let mut world = World::new();
+        world.init_resource::<Messages<RecalculatePlayerMoves>>();
+        world.insert_resource(defs.clone());
+        let mut trade_cards = PlayerTradeCards::default();
+        for (card, count) in hand {
+            trade_cards.add_trade_cards(*card, *count);
+        }
+        let player = world
+            .spawn((
+                trade_cards,
+                PlayerCivilizationCards {
+                    cards: civ_cards.clone(),
+                },
+            ))
+            .id();
+        world
+            .resource_mut::<Messages<RecalculatePlayerMoves>>()
+            .write(RecalculatePlayerMoves::new(player));
+        world
+            .run_system_once(recalculate_civ_card_moves_for_player)
+            .unwrap();
+
+        let available = world.get::<AvailableMoves>(player).unwrap();
+        let mut moves: Vec<_> = available.moves.iter().collect();
+        moves.sort_by_key(|(index, _)| **index);
+        moves
+            .into_iter()
+            .map(|(_, mv)| match mv {
+                GameMove::AcquireCivilizationCards(AcquireCivilizationCardsMove::AcquireCard(
+                    card,
+                )) => ModelMove::AcquireCivCard(*card),
+                GameMove::AcquireCivilizationCards(
+                    AcquireCivilizationCardsMove::DoneAcquiringCards,
+                ) => ModelMove::DoneAcquiringCards,
+                other => panic!("unexpected {other}"),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn legal_purchases_match_the_ecs_moves() {
+        let text = std::fs::read_to_string("assets/definitions/civilization.cards.ron")
+            .expect("Failed to read civilization.cards.ron");
+        let defs: AvailableCivCards = ron::from_str(&text).expect("Failed to deserialize RON");
+        let commodities: Vec<TradeCard> = TradeCard::iter().filter(|c| c.is_commodity()).collect();
+        let mut rng = StdRng::seed_from_u64(11);
+
+        for _ in 0..50 {
+            let hand: Vec<(TradeCard, usize)> = commodities
+                .iter()
+                .map(|card| (*card, rng.random_range(0..4)))
+                .filter(|(_, n)| *n > 0)
+                .collect();
+            let civ_cards: HashSet<CivCardName> = defs
+                .cards
+                .iter()
+                .filter(|_| rng.random_bool(0.3))
+                .map(|card| card.name)
+                .collect();
+            let mut model = model(&hand);
+            model.rules = Arc::new(ModelRules::new(
+                model.rules.areas.values().cloned(),
+                Some(&defs),
+                AstTrack::standard(),
+            ));
+            model.players[0].civ_cards = civ_cards.clone();
+
+            assert_eq!(
+                model.civ_card_moves(GameFaction::Egypt),
+                ecs_moves(&defs, &hand, &civ_cards),
+                "hand {hand:?}, holding {civ_cards:?}"
+            );
+        }
+    }
+}
@@ -0,0 +1,606 @@
+use crate::GameActivity;
+use crate::civilization::game_model::{CityOwner, GameModel};
+use crate::civilization::{CivCardName, GameFaction};
+use std::fmt::Display;
+
+/// Tokens moving from one area to another by land.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ModelMovement {
+    pub from: i32,
+    pub to: i32,
+    pub tokens: usize,
+}
+
+/// A decision in a [`GameModel`], the faction-keyed counterpart of
+/// `GameMove`. Unlike a `GameMove`, which offers "up to `max_tokens`", a
+/// model move carries the exact number of tokens, so every choice is its
+/// own move.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ModelMove {
+    PopulationExpansion { area: i32, tokens: usize },
+    Movement(ModelMovement),
+    AttackArea(ModelMovement),
+    AttackCity(ModelMovement),
+    EndMovement,
+    CityConstruction { area: i32 },
+    EndCityConstruction,
+    EliminateCity { area: i32 },
+    AcquireCivCard(CivCardName),
+    DoneAcquiringCards,
+}
+
+impl Display for ModelMove {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{self:?}")
+    }
+}
+
+impl GameModel {
+    /// The player whose decision the game is waiting on, if any.
+    pub fn to_move(&self) -> Option<GameFaction> {
+        match self.activity {
+            GameActivity::PopulationExpansion
+            | GameActivity::CityConstruction
+            | GameActivity::AcquireCivilizationCards => self.pending.first().copied(),
+            GameActivity::Movement => self.current_mover,
+            GameActivity::CheckCitySupportAfterRemoveSurplusPopulation
+            | GameActivity::CheckCitySupportAfterResolveCalamities => {
+                self.too_many_cities.first().map(|(f, _)| *f)
+            }
+            _ => None,
+        }
+    }
+
+    /// Every move [`GameModel::to_move`] may make, in a stable order.
+    pub fn legal_moves(&self) -> Vec<ModelMove> {
+        let Some(faction) = self.to_move() else {
+            return Vec::new();
+        };
+        match self.activity {
+            GameActivity::PopulationExpansion => self.expansion_moves(faction),
+            GameActivity::Movement => {
+                let mut moves = self.movement_moves(faction);
+                if !moves.is_empty() {
+                    moves.push(ModelMove::EndMovement);
+                }
+                moves
+            }
+            GameActivity::CityConstruction => {
+                let mut moves = self.city_construction_moves(faction);
+                if !moves.is_empty() {
+                    moves.push(ModelMove::EndCityConstruction);
+                }
+                moves
+            }
+            GameActivity::AcquireCivilizationCards => self.civ_card_moves(faction),
+            _ => self
+                .cities_of(faction)
+                .into_iter()
+                .map(|area| ModelMove::EliminateCity { area })
+                .collect(),
+        }
+    }
+
+    /// Plays `mv` for [`GameModel::to_move`], then runs the game forward to
+    /// the next decision.
+    pub fn apply(&mut self, mv: ModelMove) -> Result<(), String> {
+        let Some(faction) = self.to_move() else {
+            return Err(format!("no decision pending in {:?}", self.activity));
+        };
+        if !self.legal_moves().contains(&mv) {
+            return Err(format!("{mv} is not legal for {faction:?}"));
+        }
+        match mv {
+            ModelMove::PopulationExpansion { area, tokens } => {
+                self.place_from_stock(faction, area, tokens);
+                if let Some(left) = self.to_expand.get_mut(&faction) {
+                    left.retain(|a| *a != area);
+                }
+                self.check_expansion_eligibility(faction);
+            }
+            ModelMove::Movement(m) | ModelMove::AttackArea(m) | ModelMove::AttackCity(m) => {
+                let moved = self
+                    .areas
+                    .get_mut(&m.from)
+                    .map_or(0, |a| a.remove(faction, m.tokens));
+                if let Some(target) = self.areas.get_mut(&m.to) {
+                    target.add(faction, moved);
+                }
+                *self.moved.entry(m.to).or_insert(0) += moved;
+                if self.movement_moves(faction).is_empty() {
+                    self.next_mover();
+                }
+            }
+            ModelMove::EndMovement => self.next_mover(),
+            ModelMove::CityConstruction { area } => {
+                self.build_city(faction, area);
+                if self.city_construction_moves(faction).is_empty() {
+                    self.pending.retain(|f| *f != faction);
+                }
+            }
+            ModelMove::EndCityConstruction => self.pending.retain(|f| *f != faction),
+            ModelMove::EliminateCity { area } => {
+                let agriculture = self
+                    .player(faction)
+                    .is_some_and(|p| p.owns(CivCardName::Agriculture));
+                let gained = self.max_population(area) + usize::from(agriculture);
+                self.remove_city(area);
+                self.place_from_stock(faction, area, gained);
+                self.too_many_cities.retain(|(f, _)| *f != faction);
+                self.check_city_support(faction);
+            }
+            ModelMove::AcquireCivCard(card) => {
+                self.buy_civ_card(faction, card);
+                if self.civ_card_moves(faction).len() == 1 {
+                    self.finish_acquiring(faction);
+                }
+            }
+            ModelMove::DoneAcquiringCards => self.finish_acquiring(faction),
+        }
+        self.advance();
+        Ok(())
+    }
+
+    /// As in `recalculate_pop_exp_moves_for_player`: each area still to
+    /// expand, with up to 2 tokens (1 where there is one), +1 with
+    /// Agriculture where the player is alone, capped by stock.
+    fn expansion_moves(&self, faction: GameFaction) -> Vec<ModelMove> {
+        let Some(player) = self.player(faction) else {
+            return Vec::new();
+        };
+        let agriculture = player.owns(CivCardName::Agriculture);
+        let mut moves = Vec::new();
+        for area in self.to_expand.get(&faction).into_iter().flatten() {
+            let Some(occupancy) = self.area(*area) else {
+                continue;
+            };
+            let bonus = usize::from(agriculture && occupancy.number_of_players() == 1);
+            let max = match occupancy.count(faction) {
+                0 => 0,
+                1 => 1 + bonus,
+                _ => 2 + bonus,
+            }
+            .min(player.stock);
+            moves.extend((1..=max).map(|tokens| ModelMove::PopulationExpansion {
+                area: *area,
+                tokens,
+            }));
+        }
+        moves
+    }
+
+    /// As in `recalculate_movement_moves_for_player`, by land only: ships
+    /// are not part of the model (nor of the save format).
+    fn movement_moves(&self, faction: GameFaction) -> Vec<ModelMove> {
+        let road_building = self
+            .player(faction)
+            .is_some_and(|p| p.owns(CivCardName::RoadBuilding));
+        let mut moves = Vec::new();
+        for from in self.areas_of(faction) {
+            let free = self
+                .area(from)
+                .map_or(0, |a| a.count(faction))
+                .saturating_sub(self.moved.get(&from).copied().unwrap_or(0));
+            if free == 0 {
+                continue;
+            }
+            let Some(links) = self.rules.area(from) else {
+                continue;
+            };
+            for &through in &links.land {
+                self.add_land_moves(&mut moves, faction, from, through, free);
+                // Rule 23.31: one more hop through an area with no city and
+                // no enemies.
+                let Some(passage) = self.area(through) else {
+                    continue;
+                };
+                if !road_building || passage.city.is_some() || passage.has_other_players(faction) {
+                    continue;
+                }
+                for &to in self.rules.area(through).map_or(&[][..], |a| &a.land[..]) {
+                    if to != from {
+                        self.add_land_moves(&mut moves, faction, from, to, free);
+                    }
+                }
+            }
+        }
+        moves
+    }
+
+    /// `add_land_move`, once per token count.
+    fn add_land_moves(
+        &self,
+        moves: &mut Vec<ModelMove>,
+        faction: GameFaction,
+        from: i32,
+        to: i32,
+        free: usize,
+    ) {
+        let Some(target) = self.area(to) else {
+            return;
+        };
+        let kind: fn(ModelMovement) -> ModelMove = match target.city {
+            Some(CityOwner::Faction(owner)) if owner == faction => return,
+            Some(_) => ModelMove::AttackCity,
+            None if target.has_other_players(faction) => ModelMove::AttackArea,
+            None => ModelMove::Movement,
+        };
+        for tokens in 1..=free {
+            let mv = kind(ModelMovement { from, to, tokens });
+            if !moves.contains(&mv) {
+                moves.push(mv);
+            }
+        }
+    }
+
+    /// As in `recalculate_city_construction_moves_for_player`: 6 tokens on a
+    /// city site or 12 anywhere, one fewer with Architecture.
+    fn city_construction_moves(&self, faction: GameFaction) -> Vec<ModelMove> {
+        let Some(player) = self.player(faction) else {
+            return Vec::new();
+        };
+        if player.city_stock == 0 {
+            return Vec::new();
+        }
+        let architecture = player.owns(CivCardName::Architecture);
+        let (site, anywhere) = if architecture { (5, 11) } else { (6, 12) };
+        self.areas_of(faction)
+            .into_iter()
+            .filter(|area| {
+                let count = self.area(*area).map_or(0, |a| a.count(faction));
+                let city_site = self.rules.area(*area).is_some_and(|a| a.city_site);
+                (city_site && count >= site) || count >= anywhere
+            })
+            .map(|area| ModelMove::CityConstruction { area })
+            .collect()
+    }
+
+    /// As in `build_city`: with Architecture one token goes to treasury
+    /// (rule 25.3), every other token in the area returns to stock.
+    fn build_city(&mut self, faction: GameFaction, area: i32) {
+        if self
+            .player(faction)
+            .is_some_and(|p| p.owns(CivCardName::Architecture))
+            && self
+                .areas
+                .get_mut(&area)
+                .is_some_and(|a| a.remove(faction, 1) == 1)
+            && let Some(player) = self.player_mut(faction)
+        {
+            player.treasury += 1;
+        }
+        for owner in self.area(area).map(|a| a.factions()).unwrap_or_default() {
+            self.return_to_stock(owner, area, usize::MAX);
+        }
+        if let Some(player) = self.player_mut(faction) {
+            player.city_stock = player.city_stock.saturating_sub(1);
+        }
+        if let Some(occupancy) = self.areas.get_mut(&area) {
+            occupancy.city = Some(CityOwner::Faction(faction));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::civilization::game_model::{ModelArea, ModelPlayer, ModelRules};
+    use crate::civilization::{
+        AstTrack, AvailableMoves, BuiltCity, CitySite, CityTokenStock, GameArea, GameMove,
+        LandPassage, NeedsExpansion, PlayerAreas, PlayerCivilizationCards, PlayerMovementEnded,
+        PlayerShips, Population, RecalculatePlayerMoves, Token, TokenHasMoved, TokenStock,
+        recalculate_city_construction_moves_for_player, recalculate_movement_moves_for_player,
+        recalculate_pop_exp_moves_for_player,
+    };
+    use bevy::ecs::system::RunSystemOnce;
+    use bevy::platform::collections::{HashMap, HashSet};
+    use bevy::prelude::{Entity, IntoSystem, Messages, World};
+    use rand::rngs::StdRng;
+    use rand::{RngExt, SeedableRng};
+    use std::sync::Arc;
+
+    /// Eight areas in a ring with a few chords across it. Egypt, who is to
+    /// move, has tokens in most areas and some of Agriculture, Road Building
+    /// and Architecture; Crete has a few tokens and cities; a pirate city
+    /// may stand somewhere.
+    fn random_model(rng: &mut StdRng) -> GameModel {
+        let mut links: HashMap<i32, HashSet<i32>> = HashMap::default();
+        for id in 1..=8 {
+            let mut link = |a: i32, b: i32| {
+                links.entry(a).or_default().insert(b);
+                links.entry(b).or_default().insert(a);
+            };
+            link(id, id % 8 + 1);
+            if rng.random_bool(0.2) {
+                link(id, rng.random_range(1..=8));
+            }
+        }
+        let areas: Vec<ModelArea> = (1..=8)
+            .map(|id| ModelArea {
+                id,
+                max_population: rng.random_range(1..=5),
+                land: links[&id].iter().copied().filter(|to| *to != id).collect(),
+                sea: Vec::new(),
+                city_site: rng.random_bool(0.5),
+                flood_plain: false,
+                city_flood: false,
+                volcano: false,
+                start_area: None,
+            })
+            .collect();
+        let player = |faction: GameFaction| ModelPlayer {
+            faction,
+            name: format!("{faction:?}"),
+            is_human: false,
+            stock: 55,
+            city_stock: 9,
+            treasury: 0,
+            hand: HashMap::default(),
+            calamity_traded_by: Vec::new(),
+            civ_cards: Default::default(),
+            ast_space: 0,
+            census_population: 0,
+        };
+        let mut model = GameModel::new(
+            Arc::new(ModelRules::new(areas, None, AstTrack::standard())),
+            vec![player(GameFaction::Egypt), player(GameFaction::Crete)],
+            1,
+        );
+
+        let egypt = model.player_mut(GameFaction::Egypt).unwrap();
+        egypt.stock = rng.random_range(0..=4);
+        egypt.city_stock = rng.random_range(0..=2);
+        for card in [
+            CivCardName::Agriculture,
+            CivCardName::RoadBuilding,
+            CivCardName::Architecture,
+        ] {
+            if rng.random_bool(0.5) {
+                egypt.civ_cards.insert(card);
+            }
+        }
+        for occupancy in model.areas.values_mut() {
+            if rng.random_bool(0.2) {
+                occupancy.city = Some(if rng.random_bool(0.5) {
+                    CityOwner::Faction(GameFaction::Crete)
+                } else {
+                    CityOwner::Pirate
+                });
+                continue;
+            }
+            if rng.random_bool(0.7) {
+                occupancy.add(GameFaction::Egypt, rng.random_range(1..=13));
+            }
+            if rng.random_bool(0.3) {
+                occupancy.add(GameFaction::Crete, rng.random_range(1..=3));
+            }
+        }
+        model
+    }
+
+    /// What `system` offers `faction` on the board of `model`, one model move
+    /// per token count. Tokens that `model.moved` counts as arrived carry
+    /// `TokenHasMoved`.
+    fn ecs_moves<M>(
+        model: &GameModel,
+        faction: GameFaction,
+        system: impl IntoSystem<(), (), M>,
+    ) -> HashSet<ModelMove> {
+        let mut world = World::new();
+        world.init_resource::<Messages<RecalculatePlayerMoves>>();
+        world.init_resource::<Messages<PlayerMovementEnded>>();
+
+        let areas: HashMap<i32, Entity> = model
+            .rules
+            .areas
+            .values()
+            .map(|a| {
+                let mut area =
+                    world.spawn((GameArea::new(a.id), Population::new(a.max_population)));
+                if a.city_site {
+                    area.insert(CitySite);
+                }
+                (a.id, area.id())
+            })
+            .collect();
+        let ids: HashMap<Entity, i32> = areas.iter().map(|(id, e)| (*e, *id)).collect();
+        for a in model.rules.areas.values() {
+            let to_areas = a.land.iter().map(|to| areas[to]).collect();
+            world
+                .entity_mut(areas[&a.id])
+                .insert(LandPassage::new(to_areas));
+        }
+
+        let mut players: HashMap<GameFaction, Entity> = HashMap::default();
+        for p in &model.players {
+            let player = world.spawn_empty().id();
+            let stock = (0..p.stock)
+                .map(|_| world.spawn(Token::new(player)).id())
+                .collect();
+            let cities = (0..p.city_stock)
+                .map(|_| world.spawn_empty().id())
+                .collect();
+            world.entity_mut(player).insert((
+                TokenStock::new(55, stock),
+                CityTokenStock::new(9, cities),
+                PlayerAreas::default(),
+                PlayerShips::default(),
+                PlayerCivilizationCards {
+                    cards: p.civ_cards.clone(),
+                },
+            ));
+            players.insert(p.faction, player);
+        }
+
+        for (id, occupancy) in &model.areas {
+            let area = areas[id];
+            for (owner, count) in &occupancy.tokens {
+                let player = players[owner];
+                let moved = if *owner == faction {
+                    model.moved.get(id).copied().unwrap_or(0)
+                } else {
+                    0
+                };
+                for i in 0..*count {
+                    let mut token = world.spawn(Token::new(player));
+                    if i < moved {
+                        token.insert(TokenHasMoved);
+                    }
+                    let token = token.id();
+                    world
+                        .get_mut::<Population>(area)
+                        .unwrap()
+                        .add_token_to_area(player, token);
+                    world
+                        .get_mut::<PlayerAreas>(player)
+                        .unwrap()
+                        .add_token_to_area(area, token);
+                }
+            }
+            if let Some(city) = occupancy.city {
+                let owner = match city {
+                    CityOwner::Faction(owner) => players[&owner],
+                    CityOwner::Pirate => world.spawn_empty().id(),
+                };
+                let token = world.spawn_empty().id();
+                world.entity_mut(area).insert(BuiltCity::new(token, owner));
+            }
+        }
+
+        let player = players[&faction];
+        if let Some(to_expand) = model.to_expand.get(&faction) {
+            world.entity_mut(player).insert(NeedsExpansion::new(
+                to_expand.iter().map(|a| areas[a]).collect(),
+            ));
+        }
+        world
+            .resource_mut::<Messages<RecalculatePlayerMoves>>()
+            .write(RecalculatePlayerMoves::new(player));
+        world.run_system_once(system).unwrap();
+
+        let Some(available) = world.get::<AvailableMoves>(player) else {
+            return HashSet::default();
+        };
+        let mut moves = HashSet::default();
+        for mv in available.moves.values() {
+            match mv {
+                GameMove::PopulationExpansion(m) => {
+                    moves.extend(
+                        (1..=m.max_tokens).map(|tokens| ModelMove::PopulationExpansion {
+                            area: ids[&m.area],
+                            tokens,
+                        }),
+                    );
+                }
+                GameMove::Movement(m) | GameMove::AttackArea(m) | GameMove::AttackCity(m) => {
+                    let kind: fn(ModelMovement) -> ModelMove = match mv {
+                        GameMove::Movement(_) => ModelMove::Movement,
+                        GameMove::AttackArea(_) => ModelMove::AttackArea,
+                        _ => ModelMove::AttackCity,
+                    };
+                    moves.extend((1..=m.max_tokens).map(|tokens| {
+                        kind(ModelMovement {
+                            from: ids[&m.source],
+                            to: ids[&m.target],
+                            tokens,
+                        })
+                    }));
+                }
+                GameMove::EndMovement => {
+                    moves.insert(ModelMove::EndMovement);
+                }
+                GameMove::CityConstruction(m) => {
+                    moves.insert(ModelMove::CityConstruction {
+                        area: ids[&m.target],
+                    });
+                }
+                GameMove::EndCityConstruction => {
+                    moves.insert(ModelMove::EndCityConstruction);
+                }
+                other => panic!("unexpected {other}"),
+            }
+        }
+        moves
+    }
+
+    /// `legal_moves` as a set, after checking it lists no move twice.
+    fn model_moves(model: &GameModel) -> HashSet<ModelMove> {
+        let moves = model.legal_moves();
+        let set: HashSet<ModelMove> = moves.iter().copied().collect();
+        assert_eq!(set.len(), moves.len(), "{moves:?} lists a move twice");
+        set
+    }
+
+    #[test]
+    fn legal_expansions_match_the_ecs_moves() {
+        let mut rng = StdRng::seed_from_u64(21);
+        for _ in 0..50 {
+            let mut model = random_model(&mut rng);
+            model.activity = GameActivity::PopulationExpansion;
+            model.pending = vec![GameFaction::Egypt];
+            let to_expand = model
+                .areas_of(GameFaction::Egypt)
+                .into_iter()
+                .filter(|_| rng.random_bool(0.7))
+                .collect();
+            model.to_expand.insert(GameFaction::Egypt, to_expand);
+
+            assert_eq!(
+                model_moves(&model),
+                ecs_moves(
+                    &model,
+                    GameFaction::Egypt,
+                    recalculate_pop_exp_moves_for_player
+                ),
+                "areas {:?}",
+                model.areas
+            );
+        }
+    }
+
+    #[test]
+    fn legal_movements_match_the_ecs_moves() {
+        let mut rng = StdRng::seed_from_u64(22);
+        for _ in 0..50 {
+            let mut model = random_model(&mut rng);
+            model.activity = GameActivity::Movement;
+            model.current_mover = Some(GameFaction::Egypt);
+            for area in model.areas_of(GameFaction::Egypt) {
+                let count = model.area(area).map_or(0, |a| a.count(GameFaction::Egypt));
+                model.moved.insert(area, rng.random_range(0..=count));
+            }
+
+            assert_eq!(
+                model_moves(&model),
+                ecs_moves(
+                    &model,
+                    GameFaction::Egypt,
+                    recalculate_movement_moves_for_player
+                ),
+                "areas {:?}",
+                model.areas
+            );
+        }
+    }
+
+    #[test]
+    fn legal_cities_match_the_ecs_moves() {
+        let mut rng = StdRng::seed_from_u64(23);
+        for _ in 0..50 {
+            let mut model = random_model(&mut rng);
+            model.activity = GameActivity::CityConstruction;
+            model.pending = vec![GameFaction::Egypt];
+
+            assert_eq!(
+                model_moves(&model),
+                ecs_moves(
+                    &model,
+                    GameFaction::Egypt,
+                    recalculate_city_construction_moves_for_player
+                ),
+                "areas {:?}",
+                model.areas
+            );
+        }
+    }
+}
@@ -0,0 +1,784 @@
+use crate::GameActivity;
+use crate::civilization::game_model::{AreaOccupancy, CityOwner, GameModel};
+use crate::civilization::{
+    AstEpoch, CivCardName, CivCardType, GameFaction, NINTH_STACK_COST, NINTH_STACK_PILE,
+    attack_thresholds,
+};
+use bevy::prelude::debug;
+use enumflags2::BitFlags;
+use rand::RngExt;
+use rand::seq::SliceRandom;
+
+impl GameModel {
+    /// Runs every phase that needs no decision, entering each new phase the
+    /// way its `OnEnter` systems do, until someone has a move to make or the
+    /// game is over.
+    ///
+    /// Trade and ship construction are not modelled: those phases pass
+    /// without anyone acting. Calamities resolve the way they do for AI
+    /// victims, human ones included.
+    pub fn advance(&mut self) {
+        loop {
+            if self.to_move().is_some() || self.is_over() {
+                return;
+            }
+            let next = match self.activity {
+                GameActivity::PrepareGame | GameActivity::StartGame => {
+                    GameActivity::PopulationExpansion
+                }
+                GameActivity::CollectTaxes => {
+                    self.collect_taxes();
+                    GameActivity::PopulationExpansion
+                }
+                GameActivity::PopulationExpansion => GameActivity::Census,
+                GameActivity::Census => {
+                    self.take_census();
+                    GameActivity::ShipConstruction
+                }
+                GameActivity::ShipConstruction => GameActivity::Movement,
+                GameActivity::Movement => GameActivity::Conflict,
+                GameActivity::Conflict => {
+                    self.resolve_conflicts();
+                    GameActivity::CityConstruction
+                }
+                GameActivity::CityConstruction => GameActivity::RemoveSurplusPopulation,
+                GameActivity::RemoveSurplusPopulation => {
+                    self.remove_surplus_population();
+                    GameActivity::CheckCitySupportAfterRemoveSurplusPopulation
+                }
+                GameActivity::CheckCitySupportAfterRemoveSurplusPopulation => {
+                    GameActivity::AcquireTradeCards
+                }
+                GameActivity::AcquireTradeCards => {
+                    self.acquire_trade_cards();
+                    GameActivity::Trade
+                }
+                GameActivity::Trade => GameActivity::ResolveCalamities,
+                GameActivity::ResolveCalamities => {
+                    self.resolve_calamities();
+                    GameActivity::CheckCitySupportAfterResolveCalamities
+                }
+                GameActivity::CheckCitySupportAfterResolveCalamities => {
+                    GameActivity::AcquireCivilizationCards
+                }
+                GameActivity::AcquireCivilizationCards => {
+                    for pile in self.piles.values_mut() {
+                        pile.shuffle(&mut self.rng);
+                    }
+                    GameActivity::MoveSuccessionMarkers
+                }
+                GameActivity::MoveSuccessionMarkers => self.advance_succession_markers(),
+                GameActivity::GameOver => return,
+            };
+            self.enter(next);
+        }
+    }
+
+    fn enter(&mut self, activity: GameActivity) {
+        debug!("[MODEL] round {}: entering {:?}", self.round, activity);
+        self.activity = activity;
+        match self.activity {
+            GameActivity::PopulationExpansion => self.enter_population_expansion(),
+            GameActivity::Movement => {
+                self.left_to_move = self.census_order.clone();
+                self.left_to_move.reverse();
+                self.current_mover = None;
+                self.next_mover();
+            }
+            GameActivity::CityConstruction => {
+                self.pending = self.players.iter().map(|p| p.faction).collect();
+                self.drop_idle_builders();
+            }
+            GameActivity::AcquireCivilizationCards => self.enter_acquire_civ_cards(),
+            GameActivity::CheckCitySupportAfterRemoveSurplusPopulation
+            | GameActivity::CheckCitySupportAfterResolveCalamities => {
+                self.too_many_cities.clear();
+                let factions: Vec<GameFaction> = self.players.iter().map(|p| p.faction).collect();
+                for faction in factions {
+                    self.check_city_support(faction);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Picks up where a loaded save left a phase: the same bookkeeping as
+    /// [`GameModel::advance`] does on entering it, but only for the players
+    /// in `pending`.
+    pub(super) fn resume(&mut self) {
+        match self.activity {
+            GameActivity::PopulationExpansion => {
+                for faction in self.pending.clone() {
+                    self.to_expand.insert(faction, self.areas_of(faction));
+                    self.check_expansion_eligibility(faction);
+                }
+            }
+            GameActivity::Movement => {
+                if self.legal_moves().is_empty() {
+                    self.next_mover();
+                }
+            }
+            GameActivity::CityConstruction => self.drop_idle_builders(),
+            GameActivity::AcquireCivilizationCards => self.resume_acquire_civ_cards(),
+            GameActivity::CheckCitySupportAfterRemoveSurplusPopulation
+            | GameActivity::CheckCitySupportAfterResolveCalamities => {
+                self.enter(self.activity.clone());
+            }
+            _ => {}
+        }
+        self.advance();
+    }
+
+    /// `enter_population_expansion`: a new round starts and every player
+    /// expands into each area they occupy. Grain a Famine locked is free
+    /// again (`clear_grain_lock_for_new_turn`).
+    fn enter_population_expansion(&mut self) {
+        self.round += 1;
+        self.grain_locked.clear();
+        self.pending.clear();
+        self.to_expand.clear();
+        let factions: Vec<GameFaction> = self.players.iter().map(|p| p.faction).collect();
+        for faction in factions {
+            self.to_expand.insert(faction, self.areas_of(faction));
+            self.pending.push(faction);
+            self.check_expansion_eligibility(faction);
+        }
+    }
+
+    /// `check_area_population_expansion_eligibility` and
+    /// `auto_expand_population`: a player who can afford every area at once
+    /// expands automatically; one who cannot picks area by area.
+    pub(super) fn check_expansion_eligibility(&mut self, faction: GameFaction) {
+        let left = self.to_expand.get(&faction).cloned().unwrap_or_default();
+        let stock = self.player(faction).map_or(0, |p| p.stock);
+        if left.is_empty() || stock == 0 {
+            self.finish_expansion(faction);
+            return;
+        }
+        let required: usize = self
+            .areas_of(faction)
+            .iter()
+            .map(|area| self.area(*area).map_or(0, |a| a.count(faction).min(2)))
+            .sum();
+        if required > 0 && required <= stock {
+            for area in left {
+                let needed = self.area(area).map_or(0, |a| a.count(faction).min(2));
+                self.place_from_stock(faction, area, needed);
+            }
+            self.finish_expansion(faction);
+        }
+    }
+
+    fn finish_expansion(&mut self, faction: GameFaction) {
+        self.to_expand.remove(&faction);
+        self.pending.retain(|f| *f != faction);
+    }
+
+    /// `perform_census`: the most populous player first.
+    fn take_census(&mut self) {
+        for i in 0..self.players.len() {
+            let population = self.population(self.players[i].faction);
+            self.players[i].census_population = population;
+        }
+        let mut order: Vec<(GameFaction, usize)> = self
+            .players
+            .iter()
+            .map(|p| (p.faction, p.census_population))
+            .collect();
+        order.sort_by_key(|(_, population)| std::cmp::Reverse(*population));
+        self.census_order = order.into_iter().map(|(f, _)| f).collect();
+    }
+
+    /// Hands movement to the next player in census order who has a move.
+    pub(super) fn next_mover(&mut self) {
+        self.moved.clear();
+        self.current_mover = None;
+        while let Some(faction) = self.left_to_move.pop() {
+            self.current_mover = Some(faction);
+            if !self.legal_moves().is_empty() {
+                return;
+            }
+            self.current_mover = None;
+        }
+    }
+
+    /// Takes players with nothing to build out of city construction.
+    fn drop_idle_builders(&mut self) {
+        let pending = std::mem::take(&mut self.pending);
+        self.pending = pending
+            .into_iter()
+            .filter(|f| {
+                self.player(*f).is_some_and(|p| p.city_stock > 0)
+                    && self.areas_of(*f).iter().any(|area| {
+                        let count = self.area(*area).map_or(0, |a| a.count(*f));
+                        let architecture = self
+                            .player(*f)
+                            .is_some_and(|p| p.owns(CivCardName::Architecture));
+                        let (site, anywhere) = if architecture { (5, 11) } else { (6, 12) };
+                        let city_site = self.rules.area(*area).is_some_and(|a| a.city_site);
+                        (city_site && count >= site) || count >= anywhere
+                    })
+            })
+            .collect();
+    }
+
+    /// `check_player_city_support`: each city needs 2 tokens on the board.
+    pub(super) fn check_city_support(&mut self, faction: GameFaction) {
+        let required = self.cities_of(faction).len() * 2;
+        let population = self.population(faction);
+        self.too_many_cities.retain(|(f, _)| *f != faction);
+        if required > population {
+            self.too_many_cities.push((faction, required - population));
+        }
+    }
+
+    /// `find_conflict_zones` and the two conflict observers, area by area.
+    pub fn resolve_conflicts(&mut self) {
+        let zones: Vec<i32> = self
+            .areas
+            .iter()
+            .filter(|(id, a)| {
+                (a.number_of_players() > 1 && a.total() > self.max_population(**id))
+                    || (a.city.is_some() && a.total() > 0)
+            })
+            .map(|(id, _)| *id)
+            .collect();
+        for area in zones {
+            if self.area(area).is_some_and(|a| a.city.is_some()) {
+                self.resolve_city_conflict(area);
+            } else {
+                self.resolve_regular_conflict(area);
+            }
+        }
+    }
+
+    pub(super) fn resolve_city_conflict(&mut self, area: i32) {
+        let Some(occupancy) = self.area(area).cloned() else {
+            return;
+        };
+        let Some(city) = occupancy.city else {
+            return;
+        };
+        let owner = city.faction();
+        let engineering = |f: Option<GameFaction>| {
+            f.and_then(|f| self.player(f))
+                .is_some_and(|p| p.owns(CivCardName::Engineering))
+        };
+        let defender_engineering = engineering(owner);
+        let invaders: Vec<GameFaction> = occupancy
+            .factions()
+            .into_iter()
+            .filter(|f| Some(*f) != owner)
+            .collect();
+        let thresholds =
+            |f: GameFaction| attack_thresholds(engineering(Some(f)), defender_engineering);
+        let large_invader = invaders
+            .iter()
+            .any(|f| occupancy.count(*f) >= thresholds(*f).0);
+
+        if !large_invader {
+            for invader in invaders {
+                self.return_to_stock(invader, area, usize::MAX);
+            }
+            return;
+        }
+        if let [attacker] = invaders[..] {
+            let replacement = thresholds(attacker).1;
+            self.remove_city(area);
+            // A Pirate city has no owner to put tokens back or be pillaged;
+            // it is simply destroyed (rule 30.913).
+            if let Some(owner) = owner {
+                self.place_from_stock(owner, area, replacement);
+                self.pillage(owner, attacker);
+            }
+        }
+        self.resolve_regular_conflict(area);
+    }
+
+    /// Rules 24.51/24.52: the attacker draws a random card from the owner's
+    /// hand and takes up to 3 tokens from its own stock into treasury.
+    /// Barbarians, who are no player, take nothing (rule 30.526).
+    fn pillage(&mut self, owner: GameFaction, attacker: GameFaction) {
+        if self.player(attacker).is_none() {
+            return;
+        }
+        let hand = self
+            .player(owner)
+            .map(|p| p.hand_as_vec())
+            .unwrap_or_default();
+        let total: usize = hand.iter().map(|(_, n)| n).sum();
+        if total > 0 {
+            let mut pick = self.rng.random_range(0..total);
+            let drawn = hand.iter().find_map(|(card, n)| {
+                if pick < *n {
+                    Some(*card)
+                } else {
+                    pick -= n;
+                    None
+                }
+            });
+            if let Some(card) = drawn
+                && self.player_mut(owner).is_some_and(|p| p.remove_card(card))
+                && let Some(attacker) = self.player_mut(attacker)
+            {
+                attacker.add_card(card);
+            }
+        }
+        if let Some(attacker) = self.player_mut(attacker) {
+            let pillaged = attacker.stock.min(3);
+            attacker.stock -= pillaged;
+            attacker.treasury += pillaged;
+        }
+    }
+
+    /// `on_add_unresolved_conflict`: the same four cases, in the same order.
+    pub(super) fn resolve_regular_conflict(&mut self, area: i32) {
+        let Some(occupancy) = self.area(area).cloned() else {
+            return;
+        };
+        let max = self.max_population(area);
+        let mut players = occupancy.factions();
+        players.sort_by_key(|f| std::cmp::Reverse(occupancy.count(*f)));
+        let metalworking: Vec<GameFaction> = players
+            .iter()
+            .copied()
+            .filter(|f| {
+                self.player(*f)
+                    .is_some_and(|p| p.owns(CivCardName::Metalworking))
+            })
+            .collect();
+        let Some(occupancy) = self.areas.get_mut(&area) else {
+            return;
+        };
+        let mut removed: Vec<(GameFaction, usize)> = Vec::new();
+        if max == 1 {
+            if occupancy.all_lengths_equal() {
+                for faction in &players {
+                    removed.push((*faction, occupancy.remove(*faction, usize::MAX)));
+                }
+            } else {
+                // The largest keeps 2, as `handle_max_pop_is_one_conflicts`
+                // does; the surplus check then trims it to the limit.
+                let largest = players[0];
+                let keep = occupancy.count(largest).min(2);
+                removed.push((largest, occupancy.remove(largest, usize::MAX) - keep));
+                occupancy.add(largest, keep);
+                for faction in players.iter().skip(1) {
+                    removed.push((*faction, occupancy.remove(*faction, usize::MAX)));
+                }
+            }
+        } else if !metalworking.is_empty() && metalworking.len() < players.len() {
+            let mut non_mw: Vec<GameFaction> = players
+                .iter()
+                .copied()
+                .filter(|f| !metalworking.contains(f))
+                .collect();
+            while occupancy.total() > max && !non_mw.is_empty() {
+                let current = non_mw.pop().expect("not empty");
+                removed.push((current, occupancy.remove(current, 1)));
+                if occupancy.count(current) > 0 {
+                    non_mw.insert(0, current);
+                }
+                if non_mw.len() == 1 && occupancy.count(non_mw[0]) > 0 {
+                    break;
+                }
+            }
+            if occupancy.total() > max {
+                let mut all: Vec<GameFaction> = non_mw.into_iter().chain(metalworking).collect();
+                removed.extend(remove_unequal(occupancy, &mut all, max));
+            }
+        } else if occupancy.all_lengths_equal() {
+            let must_remove = occupancy.total().saturating_sub(max);
+            let rounds = must_remove.div_ceil(players.len()).max(1);
+            for faction in &players {
+                removed.push((*faction, occupancy.remove(*faction, rounds)));
+            }
+        } else {
+            removed.extend(remove_unequal(occupancy, &mut players, max));
+        }
+        for (faction, n) in removed {
+            if let Some(player) = self.player_mut(faction) {
+                player.stock += n;
+            }
+        }
+    }
+
+    /// `remove_surplus_population`: city areas are cleared, shared areas lose
+    /// the smallest holding's tokens first, and a lone player is trimmed.
+    fn remove_surplus_population(&mut self) {
+        let ids: Vec<i32> = self.areas.keys().copied().collect();
+        for area in ids {
+            let max = self.max_population(area);
+            let Some(occupancy) = self.areas.get(&area) else {
+                continue;
+            };
+            if occupancy.city.is_some() {
+                for faction in occupancy.factions() {
+                    self.return_to_stock(faction, area, usize::MAX);
+                }
+                continue;
+            }
+            while let Some(occupancy) = self.areas.get(&area)
+                && occupancy.total() > max
+            {
+                let Some(smallest) = occupancy
+                    .tokens
+                    .iter()
+                    .min_by_key(|(_, n)| *n)
+                    .map(|(f, _)| *f)
+                else {
+                    break;
+                };
+                self.return_to_stock(smallest, area, 1);
+            }
+        }
+    }
+
+    /// `acquire_trade_cards`: fewest cities first, one card from each stack
+    /// up to the number of cities; AI players then buy one card from the
+    /// ninth stack if their treasury allows.
+    fn acquire_trade_cards(&mut self) {
+        let mut order: Vec<(usize, usize)> = self
+            .players
+            .iter()
+            .enumerate()
+            .map(|(i, p)| (self.cities_of(p.faction).len(), i))
+            .collect();
+        order.sort_by_key(|(cities, _)| *cities);
+        for (cities, i) in order {
+            for pile in 1..=cities {
+                if let Some(card) = self.piles.get_mut(&pile).and_then(Vec::pop) {
+                    self.players[i].add_card(card);
+                }
+            }
+            if !self.players[i].is_human
+                && self.players[i].treasury >= NINTH_STACK_COST
+                && let Some(card) = self.piles.get_mut(&NINTH_STACK_PILE).and_then(Vec::pop)
+            {
+                let player = &mut self.players[i];
+                player.treasury -= NINTH_STACK_COST;
+                player.stock += NINTH_STACK_COST;
+                player.add_card(card);
+            }
+        }
+    }
+
+    /// `advance_succession_markers`, returning the phase that follows.
+    fn advance_succession_markers(&mut self) -> GameActivity {
+        for i in 0..self.players.len() {
+            let faction = self.players[i].faction;
+            let finish = self.rules.track.finish_index(faction);
+            let space = self.players[i].ast_space;
+            if space >= finish {
+                continue;
+            }
+            let cities = self.cities_of(faction).len();
+            let target = AstEpoch::for_space(space + 1);
+            if cities >= target.min_cities() && self.meets_card_requirements(i, target) {
+                self.players[i].ast_space = space + 1;
+            } else if cities == 0 && AstEpoch::for_space(space) != AstEpoch::StoneAge {
+                self.players[i].ast_space = space.saturating_sub(1);
+            }
+        }
+        let finished = self
+            .players
+            .iter()
+            .any(|p| p.ast_space >= self.rules.track.finish_index(p.faction));
+        let out_of_time = self.round_limit.is_some_and(|limit| self.round >= limit);
+        // Nobody left on the board would otherwise play empty rounds forever.
+        let deserted = self
+            .areas
+            .values()
+            .all(|a| a.total() == 0 && a.city.is_none());
+        if finished || out_of_time || deserted {
+            GameActivity::GameOver
+        } else {
+            GameActivity::CollectTaxes
+        }
+    }
+
+    fn meets_card_requirements(&self, player: usize, epoch: AstEpoch) -> bool {
+        let (min_groups, min_count) = (epoch.min_card_groups(), epoch.min_card_count());
+        if min_groups == 0 && min_count == 0 {
+            return true;
+        }
+        let cards = &self.players[player].civ_cards;
+        if self.rules.card_groups.is_empty() || cards.len() < min_count {
+            return false;
+        }
+        let mut groups: BitFlags<CivCardType> = BitFlags::empty();
+        for card in cards {
+            groups |= self
+                .rules
+                .card_groups
+                .get(card)
+                .copied()
+                .unwrap_or_default();
+        }
+        groups.iter().count() >= min_groups
+    }
+
+    /// `enter_collect_taxes`, `collect_taxes` and `resolve_revolts`. Paid
+    /// tax leaves the stock unchanged, as in `collect_taxes`; only the
+    /// revolts matter.
+    fn collect_taxes(&mut self) {
+        let mut revolting: Vec<(i32, GameFaction)> = Vec::new();
+        for player in &self.players {
+            let cities = self.cities_of(player.faction);
+            if cities.is_empty() || player.owns(CivCardName::Democracy) {
+                continue;
+            }
+            let rate = if !player.owns(CivCardName::Coinage) || player.is_human {
+                2
+            } else if player.stock >= 20 {
+                3
+            } else if player.stock <= 8 {
+                1
+            } else {
+                2
+            };
+            if player.stock >= cities.len() * rate {
+                continue;
+            }
+            let in_revolt = cities.len() - (player.stock / rate).min(cities.len());
+            revolting.extend(
+                cities
+                    .into_iter()
+                    .take(in_revolt)
+                    .map(|area| (area, player.faction)),
+            );
+        }
+        if revolting.is_empty() {
+            return;
+        }
+        let mut candidates: Vec<(GameFaction, usize)> = self
+            .players
+            .iter()
+            .map(|p| (p.faction, p.stock + self.cities_of(p.faction).len() * 5))
+            .collect();
+        candidates.sort_by_key(|(_, points)| *points);
+        for (area, owner) in revolting {
+            match candidates.iter().find(|(f, _)| *f != owner) {
+                Some((new_owner, _)) => {
+                    if let Some(occupancy) = self.areas.get_mut(&area) {
+                        occupancy.city = Some(CityOwner::Faction(*new_owner));
+                    }
+                }
+                None => self.remove_city(area),
+            }
+        }
+    }
+}
+
+/// `handle_unequal_lengths`: the smallest holding loses a token and goes to
+/// the back of the queue, until the area fits or one player is left.
+fn remove_unequal(
+    occupancy: &mut AreaOccupancy,
+    players: &mut Vec<GameFaction>,
+    max: usize,
+) -> Vec<(GameFaction, usize)> {
+    players.sort_by_key(|f| std::cmp::Reverse(occupancy.count(*f)));
+    let mut removed = Vec::new();
+    while occupancy.total() > max && players.len() > 1 {
+        let current = players.pop().expect("more than one");
+        removed.push((current, occupancy.remove(current, 1)));
+        if occupancy.count(current) > 0 {
+            players.insert(0, current);
+        }
+        if players.len() == 1 && occupancy.count(players[0]) > 0 {
+            break;
+        }
+    }
+    removed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::civilization::game_model::{ModelArea, ModelMove, ModelPlayer, ModelRules};
+    use crate::civilization::{AstTrack, TradeCard};
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+    use std::sync::Arc;
+
+    const FACTIONS: [GameFaction; 3] =
+        [GameFaction::Egypt, GameFaction::Crete, GameFaction::Africa];
+
+    /// Areas 1..=`n` in a line, each holding up to `max_population`, with
+    /// city sites on the odd ones.
+    fn line_model(n: i32, max_population: usize) -> GameModel {
+        let areas = (1..=n).map(|id| ModelArea {
+            id,
+            max_population,
+            land: [id - 1, id + 1]
+                .into_iter()
+                .filter(|a| (1..=n).contains(a))
+                .collect(),
+            sea: Vec::new(),
+            city_site: id % 2 == 1,
+            flood_plain: false,
+            city_flood: false,
+            volcano: false,
+            start_area: None,
+        });
+        let rules = ModelRules::new(areas, None, AstTrack::standard());
+        let players = FACTIONS
+            .iter()
+            .map(|faction| ModelPlayer {
+                faction: *faction,
+                name: format!("{faction:?}"),
+                is_human: false,
+                stock: 55,
+                city_stock: 9,
+                treasury: 0,
+                hand: Default::default(),
+                calamity_traded_by: Vec::new(),
+                civ_cards: Default::default(),
+                ast_space: 0,
+                census_population: 0,
+            })
+            .collect();
+        GameModel::new(Arc::new(rules), players, 7)
+    }
+
+    fn count(model: &GameModel, area: i32, faction: GameFaction) -> usize {
+        model.area(area).map_or(0, |a| a.count(faction))
+    }
+
+    #[test]
+    fn equal_holdings_each_lose_the_same_number() {
+        let mut model = line_model(3, 2);
+        model.place_from_stock(GameFaction::Egypt, 2, 2);
+        model.place_from_stock(GameFaction::Crete, 2, 2);
+
+        model.resolve_conflicts();
+
+        assert_eq!(count(&model, 2, GameFaction::Egypt), 1);
+        assert_eq!(count(&model, 2, GameFaction::Crete), 1);
+        assert_eq!(model.player(GameFaction::Crete).unwrap().stock, 54);
+    }
+
+    #[test]
+    fn unequal_holdings_lose_from_the_smallest() {
+        let mut model = line_model(3, 2);
+        model.place_from_stock(GameFaction::Egypt, 2, 3);
+        model.place_from_stock(GameFaction::Crete, 2, 1);
+
+        model.resolve_conflicts();
+
+        assert_eq!(count(&model, 2, GameFaction::Crete), 0);
+        assert_eq!(count(&model, 2, GameFaction::Egypt), 3);
+    }
+
+    #[test]
+    fn largest_keeps_two_where_only_one_fits() {
+        let mut model = line_model(3, 1);
+        model.place_from_stock(GameFaction::Egypt, 2, 4);
+        model.place_from_stock(GameFaction::Crete, 2, 1);
+
+        model.resolve_conflicts();
+
+        assert_eq!(count(&model, 2, GameFaction::Egypt), 2);
+        assert_eq!(count(&model, 2, GameFaction::Crete), 0);
+    }
+
+    #[test]
+    fn large_enough_attack_replaces_the_city_and_pillages() {
+        let mut model = line_model(3, 3);
+        model.areas.get_mut(&1).unwrap().city = Some(CityOwner::Faction(GameFaction::Egypt));
+        model.player_mut(GameFaction::Egypt).unwrap().city_stock = 8;
+        model
+            .player_mut(GameFaction::Egypt)
+            .unwrap()
+            .add_card(TradeCard::Ochre);
+        model.place_from_stock(GameFaction::Crete, 1, 7);
+
+        model.resolve_conflicts();
+
+        let egypt = model.player(GameFaction::Egypt).unwrap();
+        let crete = model.player(GameFaction::Crete).unwrap();
+        assert_eq!(model.area(1).unwrap().city, None);
+        assert_eq!(egypt.city_stock, 9);
+        assert_eq!(egypt.number_of_trade_cards(), 0);
+        assert_eq!(crete.number_of_trade_cards(), 1);
+        assert_eq!(crete.treasury, 3);
+    }
+
+    #[test]
+    fn surplus_removal_clears_city_areas() {
+        let mut model = line_model(3, 3);
+        model.areas.get_mut(&1).unwrap().city = Some(CityOwner::Faction(GameFaction::Egypt));
+        model.place_from_stock(GameFaction::Egypt, 1, 2);
+        model.place_from_stock(GameFaction::Egypt, 2, 5);
+
+        model.remove_surplus_population();
+
+        assert_eq!(count(&model, 1, GameFaction::Egypt), 0);
+        assert_eq!(count(&model, 2, GameFaction::Egypt), 3);
+        assert_eq!(model.player(GameFaction::Egypt).unwrap().stock, 52);
+    }
+
+    #[test]
+    fn building_a_city_returns_its_tokens_to_stock() {
+        let mut model = line_model(3, 6);
+        model.round_limit = Some(1);
+        model.place_from_stock(GameFaction::Egypt, 1, 6);
+        model.activity = GameActivity::CityConstruction;
+        model.pending = vec![GameFaction::Egypt];
+        model.drop_idle_builders();
+
+        assert_eq!(
+            model.legal_moves(),
+            vec![
+                ModelMove::CityConstruction { area: 1 },
+                ModelMove::EndCityConstruction
+            ]
+        );
+        model
+            .apply(ModelMove::CityConstruction { area: 1 })
+            .unwrap();
+
+        let egypt = model.player(GameFaction::Egypt).unwrap();
+        assert_eq!(model.cities_of(GameFaction::Egypt), vec![1]);
+        assert_eq!(egypt.stock, 55);
+        assert_eq!(egypt.city_stock, 8);
+    }
+
+    #[test]
+    fn every_legal_move_applies_over_a_random_game() {
+        let mut model = line_model(12, 3);
+        model.round_limit = Some(10);
+        for (faction, area) in FACTIONS.into_iter().zip([1, 6, 11]) {
+            model.place_from_stock(faction, area, 1);
+        }
+        model.activity = GameActivity::StartGame;
+        model.advance();
+
+        let mut rng = StdRng::seed_from_u64(3);
+        let mut steps = 0;
+        while !model.is_over() {
+            let moves = model.legal_moves();
+            assert!(!moves.is_empty(), "stuck in {:?}", model.activity);
+            let mv = moves[rng.random_range(0..moves.len())];
+            model.apply(mv).unwrap();
+            for player in &model.players {
+                assert_eq!(
+                    player.stock + model.population(player.faction) + player.treasury,
+                    55
+                );
+            }
+            // Revolting cities change owner without changing anyone's stock;
+            // Pirate cities come out of no one's.
+            let cities = model
+                .areas
+                .values()
+                .filter(|a| a.city_owner().is_some())
+                .count();
+            let city_stock: usize = model.players.iter().map(|p| p.city_stock).sum();
+            assert_eq!(cities + city_stock, 27);
+            steps += 1;
+            assert!(steps < 10_000);
+        }
+        assert_eq!(model.round, 10);
+    }
+}
@@ -0,0 +1,414 @@
+use crate::GameActivity;
+use crate::civilization::{
+    Area, AstTrack, AvailableCivCards, CivCardDefinition, CivCardName, CivCardType, Credits,
+    GameFaction, Map, TradeCard, TradeCardTrait,
+};
+use crate::stupid_ai::ModelSearcher;
+use bevy::platform::collections::{HashMap, HashSet};
+use enumflags2::BitFlags;
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+/// One area of the map as the model sees it: the fixed parts of the map
+/// asset, with connections by area id.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ModelArea {
+    pub id: i32,
+    pub max_population: usize,
+    pub land: Vec<i32>,
+    pub sea: Vec<i32>,
+    pub city_site: bool,
+    pub flood_plain: bool,
+    pub city_flood: bool,
+    pub volcano: bool,
+    /// Whose start area this is, where Barbarian Hordes land (rule 30.521).
+    pub start_area: Option<GameFaction>,
+}
+
+impl From<&Area> for ModelArea {
+    fn from(area: &Area) -> Self {
+        ModelArea {
+            id: area.id,
+            max_population: area.max_population,
+            land: area.land_connections.clone(),
+            sea: area.sea_connections.clone(),
+            city_site: area.city_site,
+            flood_plain: area.flood_plain,
+            city_flood: area.city_flood,
+            volcano: area.volcano,
+            start_area: area.start_area,
+        }
+    }
+}
+
+/// Everything about a game that never changes while it is played: the map,
+/// the civilization card definitions and the A.S.T. Shared between clones.
+#[derive(Clone, Debug)]
+pub struct ModelRules {
+    pub areas: BTreeMap<i32, ModelArea>,
+    /// Card groups per civilization card; empty when no definitions were
+    /// loaded, in which case no epoch with a card requirement can be entered
+    /// (as in `advance_succession_markers`).
+    pub card_groups: HashMap<CivCardName, BitFlags<CivCardType>>,
+    /// The civilization card definitions in `AvailableCivCards` order, the
+    /// order purchase moves are listed in.
+    pub civ_cards: Vec<CivCardDefinition>,
+    pub track: AstTrack,
+}
+
+impl ModelRules {
+    pub fn new(
+        areas: impl IntoIterator<Item = ModelArea>,
+        civ_cards: Option<&AvailableCivCards>,
+        track: AstTrack,
+    ) -> Self {
+        ModelRules {
+            areas: areas.into_iter().map(|a| (a.id, a)).collect(),
+            card_groups: civ_cards.map_or_else(HashMap::default, |defs| {
+                defs.cards.iter().map(|d| (d.name, d.card_type)).collect()
+            }),
+            civ_cards: civ_cards.map_or_else(Vec::new, |defs| defs.cards.clone()),
+            track,
+        }
+    }
+
+    pub fn from_map(map: &Map, civ_cards: Option<&AvailableCivCards>) -> Self {
+        Self::new(
+            map.areas.iter().map(ModelArea::from),
+            civ_cards,
+            AstTrack::standard(),
+        )
+    }
+
+    pub fn area(&self, id: i32) -> Option<&ModelArea> {
+        self.areas.get(&id)
+    }
+
+    pub fn civ_card(&self, name: CivCardName) -> Option<&CivCardDefinition> {
+        self.civ_cards.iter().find(|d| d.name == name)
+    }
+
+    /// `AvailableCivCards::total_credits`.
+    pub fn credits(&self, cards: &HashSet<CivCardName>) -> Vec<Credits> {
+        self.civ_cards
+            .iter()
+            .filter(|d| cards.contains(&d.name))
+            .flat_map(|d| d.credits.clone())
+            .collect()
+    }
+}
+
+/// Who a city belongs to. Pirate cities (rule 30.913) have no faction.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum CityOwner {
+    Faction(GameFaction),
+    Pirate,
+}
+
+impl CityOwner {
+    pub fn faction(self) -> Option<GameFaction> {
+        match self {
+            CityOwner::Faction(faction) => Some(faction),
+            CityOwner::Pirate => None,
+        }
+    }
+}
+
+/// The tokens and city in one area. Factions are kept in arrival order and
+/// never with a count of zero.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct AreaOccupancy {
+    pub tokens: Vec<(GameFaction, usize)>,
+    pub city: Option<CityOwner>,
+}
+
+impl AreaOccupancy {
+    pub fn count(&self, faction: GameFaction) -> usize {
+        self.tokens
+            .iter()
+            .find(|(f, _)| *f == faction)
+            .map_or(0, |(_, n)| *n)
+    }
+
+    pub fn total(&self) -> usize {
+        self.tokens.iter().map(|(_, n)| n).sum()
+    }
+
+    pub fn number_of_players(&self) -> usize {
+        self.tokens.len()
+    }
+
+    pub fn factions(&self) -> Vec<GameFaction> {
+        self.tokens.iter().map(|(f, _)| *f).collect()
+    }
+
+    pub fn has_other_players(&self, faction: GameFaction) -> bool {
+        self.tokens.iter().any(|(f, _)| *f != faction)
+    }
+
+    pub fn all_lengths_equal(&self) -> bool {
+        let first = self.tokens.first().map(|(_, n)| *n);
+        self.tokens.iter().all(|(_, n)| Some(*n) == first)
+    }
+
+    pub fn city_owner(&self) -> Option<GameFaction> {
+        self.city.and_then(CityOwner::faction)
+    }
+
+    pub fn add(&mut self, faction: GameFaction, n: usize) {
+        if n == 0 {
+            return;
+        }
+        if let Some((_, count)) = self.tokens.iter_mut().find(|(f, _)| *f == faction) {
+            *count += n;
+        } else {
+            self.tokens.push((faction, n));
+        }
+    }
+
+    /// Takes up to `n` of `faction`'s tokens out, returning how many left.
+    pub fn remove(&mut self, faction: GameFaction, n: usize) -> usize {
+        let Some(i) = self.tokens.iter().position(|(f, _)| *f == faction) else {
+            return 0;
+        };
+        let removed = n.min(self.tokens[i].1);
+        self.tokens[i].1 -= removed;
+        if self.tokens[i].1 == 0 {
+            self.tokens.remove(i);
+        }
+        removed
+    }
+}
+
+/// One player's pieces and holdings, keyed by faction rather than entity.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ModelPlayer {
+    pub faction: GameFaction,
+    pub name: String,
+    pub is_human: bool,
+    pub stock: usize,
+    pub city_stock: usize,
+    pub treasury: usize,
+    pub hand: HashMap<TradeCard, usize>,
+    /// Who traded each held calamity to this player (rules 29.61/30.221).
+    pub calamity_traded_by: Vec<(TradeCard, GameFaction)>,
+    pub civ_cards: HashSet<CivCardName>,
+    pub ast_space: u32,
+    pub census_population: usize,
+}
+
+impl ModelPlayer {
+    pub fn owns(&self, card: CivCardName) -> bool {
+        self.civ_cards.contains(&card)
+    }
+
+    pub fn number_of_trade_cards(&self) -> usize {
+        self.hand.values().sum()
+    }
+
+    /// The hand in `TradeCard::iter` order, so anything that walks it is
+    /// reproducible.
+    pub fn hand_as_vec(&self) -> Vec<(TradeCard, usize)> {
+        TradeCard::iter()
+            .filter_map(|card| self.hand.get(&card).filter(|n| **n > 0).map(|n| (card, *n)))
+            .collect()
+    }
+
+    pub fn add_card(&mut self, card: TradeCard) {
+        *self.hand.entry(card).or_insert(0) += 1;
+    }
+
+    /// The hand's commodity sets, each worth count² × face value, as
+    /// `PlayerTradeCards::total_stack_value_with_mining` adds them up.
+    pub fn buying_power(&self) -> usize {
+        let sets = self
+            .hand_as_vec()
+            .into_iter()
+            .filter(|(card, _)| card.is_commodity());
+        let (value, largest) = sets.fold((0, 0), |(value, largest), (card, n)| {
+            (value + n * n * card.value(), largest.max(n * n))
+        });
+        if self.owns(CivCardName::Mining) {
+            value + largest
+        } else {
+            value
+        }
+    }
+
+    pub fn remove_card(&mut self, card: TradeCard) -> bool {
+        let Some(count) = self.hand.get_mut(&card).filter(|n| **n > 0) else {
+            return false;
+        };
+        *count -= 1;
+        if *count == 0 {
+            self.hand.remove(&card);
+            self.calamity_traded_by.retain(|(c, _)| *c != card);
+        }
+        true
+    }
+
+    /// The commodity whose set is worth least (count² × face value), as
+    /// `PlayerTradeCards::worst_commodity` picks it.
+    pub fn worst_commodity(&self) -> Option<TradeCard> {
+        self.hand_as_vec()
+            .into_iter()
+            .filter(|(card, _)| card.is_commodity())
+            .min_by_key(|(card, count)| count * count * card.value())
+            .map(|(card, _)| card)
+    }
+}
+
+/// A complete game as plain data: the board, every player's holdings, the
+/// trade card stacks and where the round stands. Cloning it is cheap enough
+/// to do per search node; the map and card definitions are shared.
+///
+/// [`GameModel::legal_moves`] lists what the player returned by
+/// [`GameModel::to_move`] may do, and [`GameModel::apply`] plays one of those
+/// moves and runs every phase that needs no decision, the way the ECS
+/// systems would, until the next decision or the end of the game.
+#[derive(Clone, Debug)]
+pub struct GameModel {
+    pub rules: Arc<ModelRules>,
+    pub round: usize,
+    pub activity: GameActivity,
+    pub areas: BTreeMap<i32, AreaOccupancy>,
+    pub players: Vec<ModelPlayer>,
+    pub census_order: Vec<GameFaction>,
+    /// Players still to move, the next one last (as in `GameInfoAndStuff`).
+    pub left_to_move: Vec<GameFaction>,
+    pub current_mover: Option<GameFaction>,
+    /// Players yet to finish a phase everyone plays at once (population
+    /// expansion, city construction), in player order.
+    pub pending: Vec<GameFaction>,
+    /// Areas each manually expanding player has yet to expand into.
+    pub to_expand: HashMap<GameFaction, Vec<i32>>,
+    /// Tokens the current mover has already moved, per area.
+    pub moved: HashMap<i32, usize>,
+    /// Players who must eliminate cities, with the tokens they are short.
+    pub too_many_cities: Vec<(GameFaction, usize)>,
+    pub piles: BTreeMap<usize, Vec<TradeCard>>,
+    /// Civilization cards each player held when the purchase phase began,
+    /// the basis of their credits (rule 31.53).
+    pub held_before: HashMap<GameFaction, HashSet<CivCardName>>,
+    /// Grain cards a Famine left face up until the next turn (rule 30.312).
+    pub grain_locked: HashMap<GameFaction, usize>,
+    pub round_limit: Option<usize>,
+    pub rng: StdRng,
+    /// Who a tree search over this model plays for; `None` outside a search.
+    pub searcher: Option<Arc<ModelSearcher>>,
+}
+
+impl GameModel {
+    /// An empty board at the start of `activity`, for building positions
+    /// by hand. Call [`GameModel::advance`] to run the phase's entry rules.
+    pub fn new(rules: Arc<ModelRules>, players: Vec<ModelPlayer>, seed: u64) -> Self {
+        let areas = rules
+            .areas
+            .keys()
+            .map(|id| (*id, AreaOccupancy::default()))
+            .collect();
+        GameModel {
+            rules,
+            round: 0,
+            activity: GameActivity::PopulationExpansion,
+            areas,
+            players,
+            census_order: Vec::new(),
+            left_to_move: Vec::new(),
+            current_mover: None,
+            pending: Vec::new(),
+            to_expand: HashMap::default(),
+            moved: HashMap::default(),
+            too_many_cities: Vec::new(),
+            piles: BTreeMap::new(),
+            held_before: HashMap::default(),
+            grain_locked: HashMap::default(),
+            round_limit: None,
+            rng: StdRng::seed_from_u64(seed),
+            searcher: None,
+        }
+    }
+
+    pub fn player(&self, faction: GameFaction) -> Option<&ModelPlayer> {
+        self.players.iter().find(|p| p.faction == faction)
+    }
+
+    pub fn player_mut(&mut self, faction: GameFaction) -> Option<&mut ModelPlayer> {
+        self.players.iter_mut().find(|p| p.faction == faction)
+    }
+
+    pub fn area(&self, id: i32) -> Option<&AreaOccupancy> {
+        self.areas.get(&id)
+    }
+
+    pub fn max_population(&self, id: i32) -> usize {
+        self.rules.area(id).map_or(0, |a| a.max_population)
+    }
+
+    /// Tokens `faction` has on the board.
+    pub fn population(&self, faction: GameFaction) -> usize {
+        self.areas.values().map(|a| a.count(faction)).sum()
+    }
+
+    /// The areas `faction` has tokens in, in id order.
+    pub fn areas_of(&self, faction: GameFaction) -> Vec<i32> {
+        self.areas
+            .iter()
+            .filter(|(_, a)| a.count(faction) > 0)
+            .map(|(id, _)| *id)
+            .collect()
+    }
+
+    /// The areas holding one of `faction`'s cities, in id order.
+    pub fn cities_of(&self, faction: GameFaction) -> Vec<i32> {
+        self.areas
+            .iter()
+            .filter(|(_, a)| a.city_owner() == Some(faction))
+            .map(|(id, _)| *id)
+            .collect()
+    }
+
+    pub fn is_over(&self) -> bool {
+        self.activity == GameActivity::GameOver
+    }
+
+    /// Moves up to `n` tokens from `faction`'s stock into `area`, returning
+    /// how many went.
+    pub(super) fn place_from_stock(&mut self, faction: GameFaction, area: i32, n: usize) -> usize {
+        let Some(player) = self.player_mut(faction) else {
+            return 0;
+        };
+        let placed = n.min(player.stock);
+        player.stock -= placed;
+        if let Some(occupancy) = self.areas.get_mut(&area) {
+            occupancy.add(faction, placed);
+        }
+        placed
+    }
+
+    /// Takes up to `n` of `faction`'s tokens out of `area` and back to stock.
+    pub(super) fn return_to_stock(&mut self, faction: GameFaction, area: i32, n: usize) -> usize {
+        let removed = self
+            .areas
+            .get_mut(&area)
+            .map_or(0, |a| a.remove(faction, n));
+        if let Some(player) = self.player_mut(faction) {
+            player.stock += removed;
+        }
+        removed
+    }
+
+    /// Puts the city in `area` back in its owner's stock.
+    pub(super) fn remove_city(&mut self, area: i32) {
+        let owner = self
+            .areas
+            .get_mut(&area)
+            .and_then(|a| a.city.take())
+            .and_then(CityOwner::faction);
+        if let Some(player) = owner.and_then(|f| self.player_mut(f)) {
+            player.city_stock += 1;
+        }
+    }
+}
@@ -0,0 +1,340 @@
+use crate::civilization::game_model::{CityOwner, GameModel, ModelArea, ModelPlayer, ModelRules};
+use crate::civilization::{
+    AstTrack, AvailableCivCards, CityFlood, CitySite, CivilizationTradeCards, FloodPlain, GameArea,
+    GameFaction, GameSaveData, LandPassage, PendingGameLoad, Population, RoundLimit,
+    SAVE_GAME_VERSION, SaveDataSource, SavedAreaPopulation, SavedPhaseState, SavedPlayer,
+    SeaPassage, StartArea, Volcano,
+};
+use crate::{GameActivity, GameState};
+use bevy::ecs::system::SystemParam;
+use bevy::platform::collections::HashMap;
+use bevy::prelude::{Commands, Entity, Has, NextState, Query, Res};
+use std::sync::Arc;
+
+impl GameModel {
+    /// The game in `save`, picked up where the save left the current phase.
+    /// In-flight trades and calamities are not carried over, and credits for
+    /// a purchase phase under way come from the cards held now.
+    pub fn from_save(
+        save: &GameSaveData,
+        rules: Arc<ModelRules>,
+        seed: u64,
+    ) -> Result<Self, String> {
+        let players = save
+            .players
+            .iter()
+            .map(|p| ModelPlayer {
+                faction: p.faction,
+                name: p.name.clone(),
+                is_human: p.is_human,
+                stock: p.tokens_in_stock,
+                city_stock: p.city_tokens_in_stock,
+                treasury: p.treasury,
+                hand: p
+                    .trade_cards
+                    .iter()
+                    .copied()
+                    .filter(|(_, n)| *n > 0)
+                    .collect(),
+                calamity_traded_by: p.calamity_traded_by.clone(),
+                civ_cards: p.owned_civ_cards.iter().copied().collect(),
+                ast_space: p.ast_space,
+                census_population: p.census_population,
+            })
+            .collect();
+        let mut model = GameModel::new(rules, players, seed);
+        model.round = save.round;
+        model.activity = save.game_activity.clone();
+        for saved in &save.area_populations {
+            let Some(occupancy) = model.areas.get_mut(&saved.area_id) else {
+                return Err(format!("area {} is not on the map", saved.area_id));
+            };
+            for (faction, tokens) in &saved.tokens_by_faction {
+                occupancy.add(*faction, *tokens);
+            }
+            occupancy.city = if saved.city_is_pirate {
+                Some(CityOwner::Pirate)
+            } else {
+                saved.city_owner.map(CityOwner::Faction)
+            };
+        }
+        model.census_order.clone_from(&save.census_order);
+        model.left_to_move.clone_from(&save.left_to_move);
+        model.current_mover = save.current_mover;
+        model.pending = save
+            .players
+            .iter()
+            .filter(|p| !p.done_with_current_activity)
+            .map(|p| p.faction)
+            .collect();
+        model.piles = save.phase_state.trade_card_piles.iter().cloned().collect();
+        model.round_limit = save.phase_state.round_limit;
+        model.resume();
+        Ok(model)
+    }
+
+    /// The model as a save file, for loading into the live game with
+    /// [`load_game_model`].
+    pub fn to_save(&self) -> GameSaveData {
+        let players = self
+            .players
+            .iter()
+            .map(|p| SavedPlayer {
+                name: p.name.clone(),
+                faction: p.faction,
+                is_human: p.is_human,
+                census_population: p.census_population,
+                treasury: p.treasury,
+                tokens_in_stock: p.stock,
+                city_tokens_in_stock: p.city_stock,
+                trade_cards: p.hand_as_vec(),
+                done_with_current_activity: self.is_done(p.faction),
+                ast_space: p.ast_space,
+                owned_civ_cards: p.civ_cards.iter().copied().collect(),
+                calamity_traded_by: p.calamity_traded_by.clone(),
+            })
+            .collect();
+        let area_populations = self
+            .areas
+            .iter()
+            .filter(|(_, a)| a.total() > 0 || a.city.is_some())
+            .map(|(id, a)| SavedAreaPopulation {
+                area_id: *id,
+                tokens_by_faction: a.tokens.clone(),
+                city_owner: a.city_owner(),
+                city_is_pirate: a.city == Some(CityOwner::Pirate),
+            })
+            .collect();
+        GameSaveData {
+            version: SAVE_GAME_VERSION.to_string(),
+            round: self.round,
+            game_activity: self.activity.clone(),
+            players,
+            area_populations,
+            census_order: self.census_order.clone(),
+            left_to_move: self.left_to_move.clone(),
+            current_mover: self.current_mover,
+            phase_state: SavedPhaseState {
+                round_limit: self.round_limit,
+                trade_card_piles: self
+                    .piles
+                    .iter()
+                    .map(|(pile, cards)| (*pile, cards.clone()))
+                    .collect(),
+                ..Default::default()
+            },
+        }
+    }
+
+    /// `is_player_done_with_activity`, for the phases the model tracks.
+    fn is_done(&self, faction: GameFaction) -> bool {
+        match self.activity {
+            GameActivity::PopulationExpansion
+            | GameActivity::CityConstruction
+            | GameActivity::AcquireCivilizationCards => !self.pending.contains(&faction),
+            GameActivity::Movement => {
+                self.current_mover != Some(faction) && !self.left_to_move.contains(&faction)
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Replaces the running game with `model`, through the same path as loading
+/// a save file.
+pub fn load_game_model(
+    model: &GameModel,
+    commands: &mut Commands,
+    next_state: &mut NextState<GameState>,
+) {
+    commands.insert_resource(PendingGameLoad(model.to_save()));
+    next_state.set(GameState::Playing);
+}
+
+/// Everything a [`GameModel`] is built from in the running game.
+#[derive(SystemParam)]
+pub struct GameModelSource<'w, 's> {
+    save: SaveDataSource<'w, 's>,
+    areas: Query<
+        'w,
+        's,
+        (
+            Entity,
+            &'static GameArea,
+            &'static Population,
+            &'static LandPassage,
+            Option<&'static SeaPassage>,
+            Has<CitySite>,
+            Has<FloodPlain>,
+            Has<CityFlood>,
+            Has<Volcano>,
+            Option<&'static StartArea>,
+        ),
+    >,
+    trade_cards: Res<'w, CivilizationTradeCards>,
+    round_limit: Option<Res<'w, RoundLimit>>,
+    civ_defs: Option<Res<'w, AvailableCivCards>>,
+    track: Option<Res<'w, AstTrack>>,
+}
+
+impl GameModelSource<'_, '_> {
+    /// The map and card definitions of the running game.
+    pub fn rules(&self) -> ModelRules {
+        let ids: HashMap<Entity, i32> = self
+            .areas
+            .iter()
+            .map(|(entity, area, ..)| (entity, area.id))
+            .collect();
+        let to_ids = |areas: &[Entity]| -> Vec<i32> {
+            areas.iter().filter_map(|a| ids.get(a).copied()).collect()
+        };
+        let areas = self.areas.iter().map(
+            |(
+                _,
+                area,
+                population,
+                land,
+                sea,
+                city_site,
+                flood_plain,
+                city_flood,
+                volcano,
+                start_area,
+            )| {
+                ModelArea {
+                    id: area.id,
+                    max_population: population.max_population,
+                    land: to_ids(&land.to_areas),
+                    sea: sea.map_or_else(Vec::new, |s| to_ids(&s.to_areas)),
+                    city_site,
+                    flood_plain,
+                    city_flood,
+                    volcano,
+                    start_area: start_area.map(|s| s.faction),
+                }
+            },
+        );
+        ModelRules::new(
+            areas,
+            self.civ_defs.as_deref(),
+            self.track
+                .as_deref()
+                .cloned()
+                .unwrap_or_else(AstTrack::standard),
+        )
+    }
+
+    /// The running game in save-file form.
+    pub fn board(&self) -> GameSaveData {
+        self.save.capture()
+    }
+
+    /// Snapshot of the running game as a [`GameModel`] whose random draws
+    /// follow `seed`.
+    pub fn capture(&self, seed: u64) -> Result<GameModel, String> {
+        let mut save = self.save.capture();
+        let mut piles: Vec<_> = self
+            .trade_cards
+            .card_piles
+            .iter()
+            .map(|(pile, cards)| (*pile, cards.clone()))
+            .collect();
+        piles.sort_by_key(|(pile, _)| *pile);
+        save.phase_state.trade_card_piles = piles;
+        save.phase_state.round_limit = self.round_limit.as_deref().and_then(|l| l.0);
+        GameModel::from_save(&save, Arc::new(self.rules()), seed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::civilization::TradeCard;
+    use rand::rngs::StdRng;
+    use rand::{RngExt, SeedableRng};
+
+    fn rules() -> Arc<ModelRules> {
+        let areas = (1..=6).map(|id| ModelArea {
+            id,
+            max_population: 3,
+            land: [id - 1, id + 1]
+                .into_iter()
+                .filter(|a| (1..=6).contains(a))
+                .collect(),
+            sea: Vec::new(),
+            city_site: true,
+            flood_plain: false,
+            city_flood: false,
+            volcano: false,
+            start_area: None,
+        });
+        Arc::new(ModelRules::new(areas, None, AstTrack::standard()))
+    }
+
+    fn player(faction: GameFaction) -> ModelPlayer {
+        ModelPlayer {
+            faction,
+            name: format!("{faction:?}"),
+            is_human: false,
+            stock: 55,
+            city_stock: 9,
+            treasury: 0,
+            hand: HashMap::default(),
+            calamity_traded_by: Vec::new(),
+            civ_cards: Default::default(),
+            ast_space: 0,
+            census_population: 0,
+        }
+    }
+
+    #[test]
+    fn a_save_round_trip_keeps_the_position() {
+        let mut model = GameModel::new(
+            rules(),
+            vec![player(GameFaction::Egypt), player(GameFaction::Crete)],
+            1,
+        );
+        model.place_from_stock(GameFaction::Egypt, 1, 1);
+        model.place_from_stock(GameFaction::Crete, 6, 1);
+        model
+            .player_mut(GameFaction::Crete)
+            .unwrap()
+            .add_card(TradeCard::Ochre);
+        model
+            .piles
+            .insert(1, vec![TradeCard::Hides, TradeCard::Ochre]);
+        model.activity = GameActivity::StartGame;
+        model.advance();
+        let mut rng = StdRng::seed_from_u64(5);
+        while !(model.activity == GameActivity::Movement && model.moved.is_empty()) {
+            let moves = model.legal_moves();
+            model
+                .apply(moves[rng.random_range(0..moves.len())])
+                .unwrap();
+        }
+
+        let loaded = GameModel::from_save(&model.to_save(), model.rules.clone(), 1).unwrap();
+
+        assert_eq!(loaded.round, model.round);
+        assert_eq!(loaded.activity, model.activity);
+        assert_eq!(loaded.areas, model.areas);
+        assert_eq!(loaded.players, model.players);
+        assert_eq!(loaded.piles, model.piles);
+        assert_eq!(loaded.to_move(), model.to_move());
+        assert_eq!(loaded.legal_moves(), model.legal_moves());
+    }
+
+    #[test]
+    fn a_save_naming_an_unknown_area_is_rejected() {
+        let model = GameModel::new(rules(), vec![player(GameFaction::Egypt)], 1);
+        let mut save = model.to_save();
+        save.area_populations.push(SavedAreaPopulation {
+            area_id: 99,
+            tokens_by_faction: vec![(GameFaction::Egypt, 1)],
+            city_owner: None,
+            city_is_pirate: false,
+        });
+
+        assert!(GameModel::from_save(&save, rules(), 1).is_err());
+    }
+}
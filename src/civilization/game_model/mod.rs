@@ -0,0 +1,13 @@
+mod game_model_calamities;
+mod game_model_civ_cards;
+mod game_model_moves;
+mod game_model_rules;
+mod game_model_state;
+mod game_model_world;
+
+pub use game_model_calamities::*;
+pub use game_model_civ_cards::*;
+pub use game_model_moves::*;
+pub use game_model_rules::*;
+pub use game_model_state::*;
+pub use game_model_world::*;
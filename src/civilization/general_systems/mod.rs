@@ -87,7 +87,17 @@ pub fn start_game_after_player_setup(mut next_state: ResMut<NextState<GameActivi
 /// `all` (every non-human player) or a comma-separated, case-insensitive list of
 /// faction names (e.g. `Egypt,Babylon`). Unset/empty = none.
 pub fn faction_is_agent_controlled(faction: GameFaction) -> bool {
-    let Ok(val) = std::env::var("AGENT_FACTIONS") else {
+    faction_listed_in("AGENT_FACTIONS", faction)
+}
+
+/// Whether a faction's AI searches its board moves (`MctsAi`), from the
+/// `MCTS_FACTIONS` env var, in the same format as `AGENT_FACTIONS`.
+pub fn faction_is_mcts_controlled(faction: GameFaction) -> bool {
+    faction_listed_in("MCTS_FACTIONS", faction)
+}
+
+fn faction_listed_in(var: &str, faction: GameFaction) -> bool {
+    let Ok(val) = std::env::var(var) else {
         return false;
     };
     let val = val.trim();
@@ -129,6 +139,8 @@ pub fn setup_players(
     mut commands: Commands,
     mut available_factions: ResMut<AvailableFactions>,
     loading_from_save: Option<Res<LoadingFromSave>>,
+    mut game_rng: ResMut<GameRng>,
+    personalities: Option<Res<Personalities>>,
 ) {
     // Skip setup entirely if we're loading from a save file
     if loading_from_save.is_some() {
@@ -138,7 +150,7 @@ pub fn setup_players(
 
     debug!("3. Setting up players!");
     let mut available_names: Vec<&str> = ANCIENT_RULERS.to_vec();
-    available_names.shuffle(&mut rand::rng());
+    available_names.shuffle(game_rng.rng());
 
     // Factions reserved for network seats come first, so the multiplayer
     // server knows which factions its seats map to.
@@ -166,7 +178,7 @@ pub fn setup_players(
             .iter()
             .copied()
             .collect();
-        remaining.shuffle(&mut rand::rng());
+        remaining.shuffle(game_rng.rng());
         factions.extend(remaining.into_iter().take(remaining_count));
 
         // Remove used factions from available
@@ -181,7 +193,7 @@ pub fn setup_players(
             .iter()
             .copied()
             .collect();
-        remaining.shuffle(&mut rand::rng());
+        remaining.shuffle(game_rng.rng());
         let factions: Vec<_> = remaining
             .into_iter()
             .take(
@@ -198,15 +210,34 @@ pub fn setup_players(
 
     factions_to_use.extend(reserved);
     // Shuffle so human isn't always first
-    factions_to_use.shuffle(&mut rand::rng());
+    factions_to_use.shuffle(game_rng.rng());
+
+    // Give each AI a personality: a forced one for testing, else the
+    // configured line-up, else round-robin over the library's line-up so a
+    // full table shows every archetype.
+    let library = personalities.as_deref().cloned().unwrap_or_default();
+    let lookup = |name: &str| {
+        library.get(name).or_else(|| {
+            warn!("[AI] Unknown personality \"{name}\", ignoring it");
+            None
+        })
+    };
+    let lineup: Vec<Personality> = match &debug_options.force_playstyle {
+        Some(name) => lookup(name).into_iter().collect(),
+        None => debug_options
+            .playstyles
+            .iter()
+            .filter_map(|name| lookup(name))
+            .collect(),
+    };
+    let lineup = if lineup.is_empty() {
+        library.lineup()
+    } else {
+        lineup
+    };
 
     for (n, faction) in factions_to_use.into_iter().enumerate() {
         let ruler_name = available_names.pop().unwrap_or("Unknown");
-        // Give each AI a personality: a forced one for testing, else round-robin
-        // over all archetypes so a full table shows every playstyle.
-        let playstyle = debug_options
-            .force_playstyle
-            .unwrap_or(Playstyle::ALL[n % Playstyle::ALL.len()]);
         // Create Player
         let player = commands
             .spawn((
@@ -218,7 +249,7 @@ pub fn setup_players(
                 PlayerAreas::default(),
                 PlayerCities::default(),
                 StupidAi,
-                Personality::from_playstyle(playstyle),
+                lineup[n % lineup.len()].clone(),
                 PlayerTradeCards::default(),
                 PlayerCivilizationCards::default(),
                 AstPosition::new(0),
@@ -240,6 +271,14 @@ pub fn setup_players(
             commands.entity(player).insert(IsHuman);
             commands.entity(player).insert(AgentControlled);
             info!("Faction {:?} is agent-controlled (AGENT_FACTIONS)", faction);
+        } else if faction_is_mcts_controlled(faction) {
+            commands
+                .entity(player)
+                .insert(MctsAi::new(MctsConfig::from_env()));
+            info!(
+                "Faction {:?} searches its board moves (MCTS_FACTIONS)",
+                faction
+            );
         }
 
         // Determine token count - use debug override for human player if set
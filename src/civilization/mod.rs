@@ -3,8 +3,10 @@ mod concepts;
 mod enums;
 mod events;
 mod functions;
+mod game_model;
 mod game_moves;
 mod general_systems;
+mod observation;
 mod plugins;
 mod triggers;
 mod ui;
@@ -14,8 +16,10 @@ pub use concepts::*;
 pub use enums::*;
 pub use events::*;
 pub use functions::*;
+pub use game_model::*;
 pub use game_moves::*;
 pub use general_systems::*;
+pub use observation::*;
 pub use plugins::*;
 pub use triggers::*;
 pub use ui::*;
@@ -0,0 +1,5 @@
+mod observation_encoder;
+mod observation_moves;
+
+pub use observation_encoder::*;
+pub use observation_moves::*;
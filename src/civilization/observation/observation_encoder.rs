@@ -0,0 +1,344 @@
+use crate::GameActivity;
+use crate::civilization::{
+    CivCardName, GameArea, GameFaction, GameModelSource, GameSaveData, ModelRules, TradeCard,
+};
+use bevy::ecs::system::SystemParam;
+use bevy::platform::collections::HashMap;
+use bevy::prelude::{Entity, Query};
+use std::sync::Arc;
+
+/// Player slots per observation; every faction has one whether seated or not.
+pub const FACTION_SLOTS: usize = 9;
+pub const ACTIVITY_FEATURES: usize = 18;
+pub const CIV_CARD_FEATURES: usize = 24;
+/// Tokens per slot, city owner per slot, pirate city, then max population,
+/// city site, flood plain and volcano.
+pub const AREA_FEATURES: usize = 2 * FACTION_SLOTS + 1 + 4;
+/// Seated, stock, treasury, cities, AST space, hand size, then one bit per
+/// owned civilization card.
+pub const PLAYER_FEATURES: usize = 6 + CIV_CARD_FEATURES;
+
+/// Faction order of the slots after the observer's own.
+pub const SLOT_FACTIONS: [GameFaction; FACTION_SLOTS] = [
+    GameFaction::Egypt,
+    GameFaction::Crete,
+    GameFaction::Africa,
+    GameFaction::Asia,
+    GameFaction::Assyria,
+    GameFaction::Babylon,
+    GameFaction::Illyria,
+    GameFaction::Iberia,
+    GameFaction::Thrace,
+];
+
+pub fn activity_index(activity: &GameActivity) -> usize {
+    match activity {
+        GameActivity::PrepareGame => 0,
+        GameActivity::StartGame => 1,
+        GameActivity::CollectTaxes => 2,
+        GameActivity::PopulationExpansion => 3,
+        GameActivity::Census => 4,
+        GameActivity::ShipConstruction => 5,
+        GameActivity::Movement => 6,
+        GameActivity::Conflict => 7,
+        GameActivity::CityConstruction => 8,
+        GameActivity::RemoveSurplusPopulation => 9,
+        GameActivity::CheckCitySupportAfterRemoveSurplusPopulation => 10,
+        GameActivity::AcquireTradeCards => 11,
+        GameActivity::Trade => 12,
+        GameActivity::ResolveCalamities => 13,
+        GameActivity::CheckCitySupportAfterResolveCalamities => 14,
+        GameActivity::AcquireCivilizationCards => 15,
+        GameActivity::MoveSuccessionMarkers => 16,
+        GameActivity::GameOver => 17,
+    }
+}
+
+pub fn civ_card_index(card: CivCardName) -> usize {
+    card as usize - 1
+}
+
+/// `observer` first, the other factions in [`SLOT_FACTIONS`] order.
+pub fn faction_slots(observer: GameFaction) -> [GameFaction; FACTION_SLOTS] {
+    let mut slots = SLOT_FACTIONS;
+    let own = SLOT_FACTIONS
+        .iter()
+        .position(|f| *f == observer)
+        .unwrap_or_default();
+    slots[..=own].rotate_right(1);
+    slots
+}
+
+/// Turns a board into a fixed-length `f32` vector for one observer. The
+/// length depends only on the map, so one encoder serves a whole training
+/// run. Values are raw counts and flags; scaling is left to the network.
+///
+/// Layout, in order:
+///
+/// | offset                    | width                       | content                      |
+/// |---------------------------|-----------------------------|------------------------------|
+/// | 0                         | 1                           | round                        |
+/// | [`Self::activity_offset`] | [`ACTIVITY_FEATURES`]       | `GameActivity` one-hot       |
+/// | [`Self::area_offset`]     | areas × [`AREA_FEATURES`]   | areas by ascending id        |
+/// | [`Self::player_offset`]   | 9 × [`PLAYER_FEATURES`]     | players by [`faction_slots`] |
+/// | [`Self::hand_offset`]     | one per `TradeCard::iter()` | observer's hand counts       |
+///
+/// Per-faction features inside an area use the same slot order as the
+/// players, so slot 0 is always the observer.
+#[derive(Clone, Debug)]
+pub struct ObservationEncoder {
+    rules: Arc<ModelRules>,
+    area_index: HashMap<i32, usize>,
+    hand_cards: Vec<TradeCard>,
+}
+
+impl ObservationEncoder {
+    pub fn new(rules: Arc<ModelRules>) -> Self {
+        let area_index = rules
+            .areas
+            .keys()
+            .enumerate()
+            .map(|(index, id)| (*id, index))
+            .collect();
+        ObservationEncoder {
+            rules,
+            area_index,
+            hand_cards: TradeCard::iter().collect(),
+        }
+    }
+
+    pub fn rules(&self) -> &Arc<ModelRules> {
+        &self.rules
+    }
+
+    pub fn area_count(&self) -> usize {
+        self.area_index.len()
+    }
+
+    /// Position of area `id` among the areas, by ascending id.
+    pub fn area_index(&self, id: i32) -> Option<usize> {
+        self.area_index.get(&id).copied()
+    }
+
+    pub fn activity_offset(&self) -> usize {
+        1
+    }
+
+    pub fn area_offset(&self) -> usize {
+        self.activity_offset() + ACTIVITY_FEATURES
+    }
+
+    pub fn player_offset(&self) -> usize {
+        self.area_offset() + self.area_count() * AREA_FEATURES
+    }
+
+    pub fn hand_offset(&self) -> usize {
+        self.player_offset() + FACTION_SLOTS * PLAYER_FEATURES
+    }
+
+    /// Length of every vector [`Self::encode`] returns.
+    pub fn width(&self) -> usize {
+        self.hand_offset() + self.hand_cards.len()
+    }
+
+    /// `board` as seen by `observer`. Only the observer's own hand is
+    /// included; for the others only the hand size is.
+    pub fn encode(&self, board: &GameSaveData, observer: GameFaction) -> Vec<f32> {
+        let mut features = vec![0.0; self.width()];
+        let slots = faction_slots(observer);
+        let slot_of = |faction: GameFaction| slots.iter().position(|f| *f == faction);
+
+        features[0] = board.round as f32;
+        features[self.activity_offset() + activity_index(&board.game_activity)] = 1.0;
+
+        // `area_index` numbers the areas in this same ascending-id order.
+        for (index, area) in self.rules.areas.values().enumerate() {
+            let base = self.area_offset() + index * AREA_FEATURES;
+            let terrain = base + 2 * FACTION_SLOTS + 1;
+            features[terrain] = area.max_population as f32;
+            features[terrain + 1] = f32::from(u8::from(area.city_site));
+            features[terrain + 2] = f32::from(u8::from(area.flood_plain));
+            features[terrain + 3] = f32::from(u8::from(area.volcano));
+        }
+        for saved in &board.area_populations {
+            let Some(index) = self.area_index(saved.area_id) else {
+                continue;
+            };
+            let base = self.area_offset() + index * AREA_FEATURES;
+            for (faction, tokens) in &saved.tokens_by_faction {
+                if let Some(slot) = slot_of(*faction) {
+                    features[base + slot] += *tokens as f32;
+                }
+            }
+            if saved.city_is_pirate {
+                features[base + 2 * FACTION_SLOTS] = 1.0;
+            } else if let Some(slot) = saved.city_owner.and_then(slot_of) {
+                features[base + FACTION_SLOTS + slot] = 1.0;
+            }
+        }
+
+        for player in &board.players {
+            let Some(slot) = slot_of(player.faction) else {
+                continue;
+            };
+            let base = self.player_offset() + slot * PLAYER_FEATURES;
+            let cities = board
+                .area_populations
+                .iter()
+                .filter(|a| !a.city_is_pirate && a.city_owner == Some(player.faction))
+                .count();
+            let hand_size: usize = player.trade_cards.iter().map(|(_, n)| n).sum();
+            features[base] = 1.0;
+            features[base + 1] = player.tokens_in_stock as f32;
+            features[base + 2] = player.treasury as f32;
+            features[base + 3] = cities as f32;
+            features[base + 4] = player.ast_space as f32;
+            features[base + 5] = hand_size as f32;
+            for card in &player.owned_civ_cards {
+                features[base + 6 + civ_card_index(*card)] = 1.0;
+            }
+            if player.faction == observer {
+                for (card, count) in &player.trade_cards {
+                    if let Some(i) = self.hand_cards.iter().position(|c| c == card) {
+                        features[self.hand_offset() + i] += *count as f32;
+                    }
+                }
+            }
+        }
+        features
+    }
+}
+
+/// What [`ObservationEncoder`] reads from the running game.
+#[derive(SystemParam)]
+pub struct ObservationSource<'w, 's> {
+    model: GameModelSource<'w, 's>,
+    areas: Query<'w, 's, (Entity, &'static GameArea)>,
+}
+
+impl ObservationSource<'_, '_> {
+    pub fn encoder(&self) -> ObservationEncoder {
+        ObservationEncoder::new(Arc::new(self.model.rules()))
+    }
+
+    /// Area id per area entity, for encoding moves.
+    pub fn area_ids(&self) -> HashMap<Entity, i32> {
+        self.areas
+            .iter()
+            .map(|(entity, area)| (entity, area.id))
+            .collect()
+    }
+
+    pub fn board(&self) -> GameSaveData {
+        self.model.board()
+    }
+
+    pub fn encode(&self, encoder: &ObservationEncoder, observer: GameFaction) -> Vec<f32> {
+        encoder.encode(&self.board(), observer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::civilization::{AstTrack, ModelArea, SavedAreaPopulation, SavedPlayer};
+
+    fn encoder() -> ObservationEncoder {
+        let areas = [3, 1, 2].into_iter().map(|id| ModelArea {
+            id,
+            max_population: id as usize,
+            land: Vec::new(),
+            sea: Vec::new(),
+            city_site: id == 2,
+            flood_plain: false,
+            city_flood: false,
+            volcano: id == 3,
+            start_area: None,
+        });
+        ObservationEncoder::new(Arc::new(ModelRules::new(areas, None, AstTrack::standard())))
+    }
+
+    fn player(faction: GameFaction) -> SavedPlayer {
+        SavedPlayer {
+            name: faction.to_string(),
+            faction,
+            is_human: false,
+            census_population: 0,
+            treasury: 2,
+            tokens_in_stock: 40,
+            city_tokens_in_stock: 8,
+            trade_cards: vec![(TradeCard::Ochre, 2)],
+            done_with_current_activity: false,
+            ast_space: 1,
+            owned_civ_cards: vec![CivCardName::Pottery],
+            calamity_traded_by: Vec::new(),
+        }
+    }
+
+    fn board() -> GameSaveData {
+        GameSaveData {
+            version: String::new(),
+            round: 4,
+            game_activity: GameActivity::Movement,
+            players: vec![player(GameFaction::Egypt), player(GameFaction::Crete)],
+            area_populations: vec![SavedAreaPopulation {
+                area_id: 2,
+                tokens_by_faction: vec![(GameFaction::Egypt, 1), (GameFaction::Crete, 3)],
+                city_owner: Some(GameFaction::Crete),
+                city_is_pirate: false,
+            }],
+            census_order: Vec::new(),
+            left_to_move: Vec::new(),
+            current_mover: None,
+            phase_state: Default::default(),
+        }
+    }
+
+    #[test]
+    fn the_observer_takes_the_first_slot() {
+        let slots = faction_slots(GameFaction::Assyria);
+
+        assert_eq!(slots[0], GameFaction::Assyria);
+        assert_eq!(slots[1], GameFaction::Egypt);
+        assert_eq!(slots[5], GameFaction::Babylon);
+        assert_eq!(faction_slots(GameFaction::Egypt), SLOT_FACTIONS);
+    }
+
+    #[test]
+    fn the_layout_has_a_fixed_width() {
+        let encoder = encoder();
+
+        assert_eq!(encoder.area_index(1), Some(0));
+        assert_eq!(encoder.area_index(3), Some(2));
+        assert_eq!(
+            encoder.encode(&board(), GameFaction::Egypt).len(),
+            encoder.width()
+        );
+        assert_eq!(
+            encoder.encode(&board(), GameFaction::Thrace).len(),
+            encoder.width()
+        );
+    }
+
+    #[test]
+    fn areas_and_players_are_seen_from_the_observer() {
+        let encoder = encoder();
+        let features = encoder.encode(&board(), GameFaction::Crete);
+        let area = encoder.area_offset() + AREA_FEATURES;
+        let crete = encoder.player_offset();
+        let egypt = crete + PLAYER_FEATURES;
+
+        assert_eq!(features[0], 4.0);
+        assert_eq!(features[encoder.activity_offset() + 6], 1.0);
+        assert_eq!(features[area], 3.0);
+        assert_eq!(features[area + 1], 1.0);
+        assert_eq!(features[area + FACTION_SLOTS], 1.0);
+        assert_eq!(features[area + 2 * FACTION_SLOTS + 1], 2.0);
+        assert_eq!(features[area + 2 * FACTION_SLOTS + 2], 1.0);
+        assert_eq!(features[crete + 3], 1.0);
+        assert_eq!(features[egypt + 3], 0.0);
+        assert_eq!(features[egypt + 5], 2.0);
+        assert_eq!(features[egypt + 6], 1.0);
+        assert_eq!(features[encoder.hand_offset()], 2.0);
+    }
+}
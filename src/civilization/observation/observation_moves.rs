@@ -0,0 +1,219 @@
+use crate::civilization::{
+    AcquireCivilizationCardsMove, CIV_CARD_FEATURES, GameFaction, GameMove, GameSaveData,
+    ObservationEncoder, civ_card_index,
+};
+use bevy::platform::collections::HashMap;
+use bevy::prelude::Entity;
+
+/// One per move kind in [`move_kind_index`].
+pub const MOVE_KINDS: usize = 12;
+/// Observer's tokens, others' tokens, max population, city present and own
+/// city, all for the target area.
+pub const MOVE_TARGET_FEATURES: usize = 5;
+
+/// `AcquireCard` and `AcquireCards` share a kind; the cards tell them apart.
+pub fn move_kind_index(game_move: &GameMove) -> usize {
+    match game_move {
+        GameMove::PopulationExpansion(_) => 0,
+        GameMove::Movement(_) => 1,
+        GameMove::ShipFerry(_) => 2,
+        GameMove::AttackArea(_) => 3,
+        GameMove::AttackCity(_) => 4,
+        GameMove::EndMovement => 5,
+        GameMove::CityConstruction(_) => 6,
+        GameMove::EndCityConstruction => 7,
+        GameMove::EliminateCity(_) => 8,
+        GameMove::Trade(_) => 9,
+        GameMove::AcquireCivilizationCards(
+            AcquireCivilizationCardsMove::AcquireCard(_)
+            | AcquireCivilizationCardsMove::AcquireCards(_),
+        ) => 10,
+        GameMove::AcquireCivilizationCards(AcquireCivilizationCardsMove::DoneAcquiringCards) => 11,
+    }
+}
+
+impl ObservationEncoder {
+    /// Length of every vector [`Self::encode_move`] returns.
+    ///
+    /// Layout: kind one-hot ([`MOVE_KINDS`]), chosen tokens, max tokens,
+    /// source area one-hot, target area one-hot (both by ascending area id),
+    /// target context ([`MOVE_TARGET_FEATURES`]), tokens gained and tokens
+    /// needed for city elimination, then one bit per civilization card bought.
+    pub fn move_width(&self) -> usize {
+        MOVE_KINDS + 2 + 2 * self.area_count() + MOVE_TARGET_FEATURES + 2 + CIV_CARD_FEATURES
+    }
+
+    /// Features of one legal move for `mover` on `board`, so a policy can
+    /// score the legal list. `area_ids` maps area entities to map ids (see
+    /// `ObservationSource::area_ids`); `tokens` is the count chosen for moves
+    /// with a token range.
+    pub fn encode_move(
+        &self,
+        board: &GameSaveData,
+        mover: GameFaction,
+        area_ids: &HashMap<Entity, i32>,
+        game_move: &GameMove,
+        tokens: Option<usize>,
+    ) -> Vec<f32> {
+        let mut features = vec![0.0; self.move_width()];
+        features[move_kind_index(game_move)] = 1.0;
+        features[MOVE_KINDS] = tokens.unwrap_or_default() as f32;
+
+        let source_offset = MOVE_KINDS + 2;
+        let target_offset = source_offset + self.area_count();
+        let context_offset = target_offset + self.area_count();
+        let city_offset = context_offset + MOVE_TARGET_FEATURES;
+        let card_offset = city_offset + 2;
+
+        let (source, target, max_tokens) = match game_move {
+            GameMove::PopulationExpansion(m) => (None, Some(m.area), m.max_tokens),
+            GameMove::Movement(m)
+            | GameMove::ShipFerry(m)
+            | GameMove::AttackArea(m)
+            | GameMove::AttackCity(m) => (Some(m.source), Some(m.target), m.max_tokens),
+            GameMove::CityConstruction(m) => (None, Some(m.target), 0),
+            GameMove::EliminateCity(m) => {
+                features[city_offset] = m.tokens_gained as f32;
+                features[city_offset + 1] = m.tokens_needed as f32;
+                (None, Some(m.area), 0)
+            }
+            GameMove::AcquireCivilizationCards(AcquireCivilizationCardsMove::AcquireCard(card)) => {
+                features[card_offset + civ_card_index(*card)] = 1.0;
+                (None, None, 0)
+            }
+            GameMove::AcquireCivilizationCards(AcquireCivilizationCardsMove::AcquireCards(
+                cards,
+            )) => {
+                for card in cards {
+                    features[card_offset + civ_card_index(*card)] = 1.0;
+                }
+                (None, None, 0)
+            }
+            GameMove::EndMovement
+            | GameMove::EndCityConstruction
+            | GameMove::Trade(_)
+            | GameMove::AcquireCivilizationCards(
+                AcquireCivilizationCardsMove::DoneAcquiringCards,
+            ) => (None, None, 0),
+        };
+        features[MOVE_KINDS + 1] = max_tokens as f32;
+
+        let index = |area: Option<Entity>| {
+            area.and_then(|a| area_ids.get(&a))
+                .and_then(|id| self.area_index(*id).map(|i| (*id, i)))
+        };
+        if let Some((_, i)) = index(source) {
+            features[source_offset + i] = 1.0;
+        }
+        if let Some((id, i)) = index(target) {
+            features[target_offset + i] = 1.0;
+            if let Some(area) = self.rules().area(id) {
+                features[context_offset + 2] = area.max_population as f32;
+            }
+            if let Some(saved) = board.area_populations.iter().find(|a| a.area_id == id) {
+                for (faction, count) in &saved.tokens_by_faction {
+                    features[context_offset + usize::from(*faction != mover)] += *count as f32;
+                }
+                if saved.city_owner.is_some() || saved.city_is_pirate {
+                    features[context_offset + 3] = 1.0;
+                }
+                if !saved.city_is_pirate && saved.city_owner == Some(mover) {
+                    features[context_offset + 4] = 1.0;
+                }
+            }
+        }
+        features
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::GameActivity;
+    use crate::civilization::{
+        AstTrack, CivCardName, ModelArea, ModelRules, MovementMove, SavedAreaPopulation,
+    };
+    use std::sync::Arc;
+
+    fn encoder() -> ObservationEncoder {
+        let areas = (1..=3).map(|id| ModelArea {
+            id,
+            max_population: 2,
+            land: Vec::new(),
+            sea: Vec::new(),
+            city_site: false,
+            flood_plain: false,
+            city_flood: false,
+            volcano: false,
+            start_area: None,
+        });
+        ObservationEncoder::new(Arc::new(ModelRules::new(areas, None, AstTrack::standard())))
+    }
+
+    fn board() -> GameSaveData {
+        GameSaveData {
+            version: String::new(),
+            round: 1,
+            game_activity: GameActivity::Movement,
+            players: Vec::new(),
+            area_populations: vec![SavedAreaPopulation {
+                area_id: 3,
+                tokens_by_faction: vec![(GameFaction::Egypt, 1), (GameFaction::Crete, 2)],
+                city_owner: None,
+                city_is_pirate: false,
+            }],
+            census_order: Vec::new(),
+            left_to_move: Vec::new(),
+            current_mover: None,
+            phase_state: Default::default(),
+        }
+    }
+
+    #[test]
+    fn an_attack_names_its_areas_and_the_defenders() {
+        let encoder = encoder();
+        let (source, target, player) = (
+            Entity::from_raw_u32(1).unwrap(),
+            Entity::from_raw_u32(2).unwrap(),
+            Entity::from_raw_u32(3).unwrap(),
+        );
+        let area_ids = HashMap::from_iter([(source, 1), (target, 3)]);
+        let attack = GameMove::AttackArea(MovementMove::new(source, target, player, 4));
+
+        let features =
+            encoder.encode_move(&board(), GameFaction::Egypt, &area_ids, &attack, Some(3));
+
+        assert_eq!(features.len(), encoder.move_width());
+        assert_eq!(features[3], 1.0);
+        assert_eq!(features[MOVE_KINDS], 3.0);
+        assert_eq!(features[MOVE_KINDS + 1], 4.0);
+        assert_eq!(features[MOVE_KINDS + 2], 1.0);
+        assert_eq!(features[MOVE_KINDS + 2 + 3 + 2], 1.0);
+        let context = MOVE_KINDS + 2 + 6;
+        assert_eq!(&features[context..context + 3], &[1.0, 2.0, 2.0]);
+    }
+
+    #[test]
+    fn bought_cards_are_flagged() {
+        let encoder = encoder();
+        let buy =
+            GameMove::AcquireCivilizationCards(AcquireCivilizationCardsMove::AcquireCards(vec![
+                CivCardName::Pottery,
+                CivCardName::Theology,
+            ]));
+
+        let features = encoder.encode_move(
+            &board(),
+            GameFaction::Egypt,
+            &HashMap::default(),
+            &buy,
+            None,
+        );
+        let cards = encoder.move_width() - CIV_CARD_FEATURES;
+
+        assert_eq!(features[10], 1.0);
+        assert_eq!(features[cards], 1.0);
+        assert_eq!(features[cards + 23], 1.0);
+        assert_eq!(features.iter().sum::<f32>(), 3.0);
+    }
+}
@@ -29,6 +29,9 @@ impl Plugin for CivilizationPlugin {
             AreaInfoPlugin,
             lava_ui_builder::LavaUiPlugin,
             AgentApiPlugin,
+            ReplayUiPlugin,
+            SaveBrowserPlugin,
+            AiTraceUiPlugin,
         ));
     }
 }
@@ -48,6 +51,7 @@ impl Plugin for CivLogicPlugins {
     fn build(&self, app: &mut App) {
         // Use DebugOptions::test_manual_pop_exp() to test manual population expansion
         app.insert_resource(DebugOptions::from_env())
+            .init_resource::<GameRng>()
             .register_type::<Player>()
             .register_type::<BarbarianToken>()
             .register_type::<Token>()
@@ -102,6 +106,9 @@ impl Plugin for CivLogicPlugins {
                 TradeCardPlugin,
                 MapPlugin,
                 SaveGamePlugin,
+                JournalPlugin,
+                ReplayPlugin,
+                UndoPlugin,
             ))
             .add_systems(OnEnter(GameActivity::StartGame), start_game)
             .insert_resource(GameInfoAndStuff::default())
@@ -145,9 +152,12 @@ pub struct DebugOptions {
     pub show_debug_ui: bool,
     pub human_trade_cards: Option<Vec<(TradeCard, usize)>>,
     pub human_civ_cards: Option<Vec<CivCardName>>,
-    /// If set, every AI player gets this playstyle instead of the round-robin
-    /// spread over all archetypes (for isolating/testing one personality).
-    pub force_playstyle: Option<Playstyle>,
+    /// If set, every AI player gets the personality of this name instead of
+    /// the round-robin spread over the line-up (for isolating/testing one).
+    pub force_playstyle: Option<String>,
+    /// Without a forced personality, AI players take these (by name) in seat
+    /// order, wrapping round, instead of the library's line-up.
+    pub playstyles: Vec<String>,
     /// Watch mode: keep the whole map framed and suppress all automatic camera
     /// panning/focusing, so you can watch the AI play without the view jumping
     /// around. Manual zoom/pan keys still work.
@@ -156,6 +166,11 @@ pub struct DebugOptions {
     /// first (before the local human / random fill), so the multiplayer
     /// server knows which factions its seats map to.
     pub reserved_factions: Vec<GameFaction>,
+    /// Seed for the shared `GameRng`. `None` picks a fresh random seed per
+    /// game; either way the seed in use is logged on `PrepareGame`.
+    pub game_seed: Option<u64>,
+    /// Write the rotating autosave after every move. Batch runs turn it off.
+    pub autosave: bool,
 }
 
 /// Run condition: automatic camera panning/focusing is enabled (i.e. not in the
@@ -190,8 +205,11 @@ impl Default for DebugOptions {
             human_trade_cards: None,
             human_civ_cards: None,
             force_playstyle: None,
+            playstyles: Vec::new(),
             static_map_view: false,
             reserved_factions: Vec::new(),
+            game_seed: None,
+            autosave: true,
         }
     }
 }
@@ -203,6 +221,9 @@ impl DebugOptions {
     ///   of available factions).
     /// - `HUMAN_PLAYER=0|false|no` — drop the local human (full self-play); any
     ///   other value (or unset) keeps the default human player.
+    /// - `GAME_SEED=<u64>` — seed the `GameRng`, making the game reproducible.
+    /// - `AI_PERSONALITY=<name>` — give every AI player this personality.
+    /// - `AI_LINEUP=<name>,<name>,...` — AI personalities in seat order.
     ///
     /// Orthogonal to `AGENT_FACTIONS`, which decides which non-human factions are
     /// agent-controlled. E.g. `NUM_PLAYERS=4 HUMAN_PLAYER=0 AGENT_FACTIONS=all`
@@ -222,6 +243,24 @@ impl DebugOptions {
             let v = v.trim();
             opts.static_map_view = !matches!(v, "0" | "false" | "no" | "off");
         }
+        if let Ok(seed) = std::env::var("GAME_SEED")
+            && let Ok(seed) = seed.trim().parse::<u64>()
+        {
+            opts.game_seed = Some(seed);
+        }
+        if let Ok(name) = std::env::var("AI_PERSONALITY")
+            && !name.trim().is_empty()
+        {
+            opts.force_playstyle = Some(name.trim().to_string());
+        }
+        if let Ok(names) = std::env::var("AI_LINEUP") {
+            opts.playstyles = names
+                .split(',')
+                .map(str::trim)
+                .filter(|name| !name.is_empty())
+                .map(str::to_string)
+                .collect();
+        }
         opts
     }
 
@@ -249,8 +288,11 @@ impl DebugOptions {
             human_trade_cards: Some(vec![(TradeCard::Wine, 4), (TradeCard::Salt, 4)]),
             human_civ_cards: Some(vec![CivCardName::ClothMaking, CivCardName::Mathematics]),
             force_playstyle: None,
+            playstyles: Vec::new(),
             static_map_view: false,
             reserved_factions: Vec::new(),
+            game_seed: None,
+            autosave: true,
         }
     }
 }
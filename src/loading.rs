@@ -1,4 +1,5 @@
 use crate::GameState;
+use crate::stupid_ai::PersonalitySources;
 use bevy::prelude::*;
 use bevy_asset_loader::prelude::*;
 
@@ -10,13 +11,30 @@ pub struct LoadingPlugin;
 impl Plugin for LoadingPlugin {
     fn build(&self, app: &mut App) {
         app.add_loading_state(
-            LoadingState::new(GameState::Loading)
-                .continue_to_state(GameState::Menu)
-                .load_collection::<TextureAssets>(),
+            LoadingState::new(GameState::Loading).load_collection::<TextureAssets>(),
+        )
+        .add_systems(
+            Update,
+            open_menu_when_loaded.run_if(in_state(GameState::Loading)),
         );
     }
 }
 
+/// The textures come in through the collection, the AI personality
+/// definitions through `load_personalities`; the menu waits for both, or
+/// `setup_players` would deal out the built-in archetypes instead.
+fn open_menu_when_loaded(
+    textures: Option<Res<TextureAssets>>,
+    personalities: Option<Res<PersonalitySources>>,
+    asset_server: Res<AssetServer>,
+    mut next: ResMut<NextState<GameState>>,
+) {
+    let personalities_settled = personalities.is_some_and(|p| p.is_settled(&asset_server));
+    if textures.is_some() && personalities_settled {
+        next.set(GameState::Menu);
+    }
+}
+
 // the following asset collections will be loaded during the State `GameState::Loading`
 // when done loading, they will be inserted as resources (see <https://github.com/NiklasEi/bevy_asset_loader>)
 
@@ -1,11 +1,13 @@
-use crate::civilization::save_game::{LoadGameRequest, SaveGameRequest};
+use crate::civilization::save_game::{LoadGameRequest, OpenSaveBrowser, SaveGameRequest};
 use crate::civilization::{
-    CityToken, CivilizationTradeCards, GameArea, GameCamera, GameInfoAndStuff, GameResult, Token,
+    CityToken, DebugOptions, GameArea, GameCamera, GameInfoAndStuff, GameResult, Token,
 };
 use crate::loading::TextureAssets;
 use crate::player::Player;
+use crate::stupid_ai::Personalities;
 use crate::{GameActivity, GamePaused, GameState};
 use bevy::feathers::FeathersPlugins;
+use bevy::input::keyboard::{Key, KeyboardInput};
 use bevy::{
     feathers::{dark_theme::create_dark_theme, theme::UiTheme},
     prelude::*,
@@ -22,6 +24,15 @@ impl Plugin for MenuPlugin {
             .init_resource::<LavaTheme>()
             .add_systems(OnEnter(GameState::Menu), setup_menu)
             .add_systems(OnExit(GameState::Menu), cleanup_menu)
+            .add_systems(
+                Update,
+                (
+                    update_personality_choice,
+                    type_game_seed,
+                    update_seed_choice,
+                )
+                    .run_if(in_state(GameState::Menu)),
+            )
             .add_systems(
                 Update,
                 spawn_victory_screen.run_if(in_state(GameActivity::GameOver)),
@@ -36,6 +47,16 @@ struct Menu;
 #[derive(Component, Default)]
 struct PauseMenu;
 
+#[derive(Component, Default)]
+struct PersonalityChoiceText;
+
+#[derive(Component, Default)]
+struct SeedText;
+
+/// The seed being typed into the menu's seed field.
+#[derive(Resource, Default)]
+struct SeedEntry(String);
+
 // ============================================================================
 // Main Menu
 // ============================================================================
@@ -44,6 +65,7 @@ fn setup_menu(
     mut commands: Commands,
     _textures: Res<TextureAssets>,
     theme: Res<LavaTheme>,
+    debug_options: Res<DebugOptions>,
     existing_camera: Query<(), With<GameCamera>>,
 ) {
     // Re-entering the menu (e.g. after leaving an online game) must not
@@ -81,6 +103,66 @@ fn setup_menu(
         },
     );
 
+    ui.add_row(|row| {
+        row.align_items_center().gap_px(8.0);
+        row.add_button_observe(
+            "◀",
+            |btn| {
+                btn.size(px(40.0), px(40.0));
+            },
+            |_: On<Activate>,
+             mut debug_options: ResMut<DebugOptions>,
+             personalities: Option<Res<Personalities>>| {
+                let library = personalities.as_deref().cloned().unwrap_or_default();
+                cycle_personality(&mut debug_options, &library, -1);
+            },
+        );
+        row.with_child(|c| {
+            c.component::<PersonalityChoiceText>()
+                .with_text(
+                    personality_label(&debug_options),
+                    Some(TextStyle::size(20.0)),
+                )
+                .width_px(204.0);
+        });
+        row.add_button_observe(
+            "▶",
+            |btn| {
+                btn.size(px(40.0), px(40.0));
+            },
+            |_: On<Activate>,
+             mut debug_options: ResMut<DebugOptions>,
+             personalities: Option<Res<Personalities>>| {
+                let library = personalities.as_deref().cloned().unwrap_or_default();
+                cycle_personality(&mut debug_options, &library, 1);
+            },
+        );
+    });
+
+    ui.add_row(|row| {
+        row.align_items_center().gap_px(8.0);
+        row.add_button_observe(
+            "Seed",
+            |btn| {
+                btn.size(px(88.0), px(40.0));
+            },
+            |_: On<Activate>, mut commands: Commands, debug_options: Res<DebugOptions>| {
+                let typed = debug_options
+                    .game_seed
+                    .map_or_else(String::new, |seed| seed.to_string());
+                commands.insert_resource(SeedEntry(typed));
+            },
+        );
+        row.with_child(|c| {
+            c.component::<SeedText>()
+                .with_text(
+                    seed_label(&debug_options, None),
+                    Some(TextStyle::size(20.0)),
+                )
+                .width_px(204.0);
+        });
+    });
+
     ui.add_button_observe(
         "Play Online",
         |btn| {
@@ -106,15 +188,115 @@ fn setup_menu(
         |btn| {
             btn.size(px(300.0), px(60.0));
         },
-        |_activate: On<Activate>, mut load_writer: MessageWriter<LoadGameRequest>| {
-            load_writer.write(LoadGameRequest);
+        |_activate: On<Activate>, mut open_writer: MessageWriter<OpenSaveBrowser>| {
+            open_writer.write(OpenSaveBrowser);
         },
     );
 
     ui.build();
 }
 
+fn personality_label(debug_options: &DebugOptions) -> String {
+    match &debug_options.force_playstyle {
+        Some(name) => format!("AI: {name}"),
+        None => "AI: line-up".to_string(),
+    }
+}
+
+/// Steps the new game's AI through the line-up, then every loaded personality
+/// forced on all AI players.
+fn cycle_personality(debug_options: &mut DebugOptions, personalities: &Personalities, step: isize) {
+    let choices: Vec<Option<&str>> = std::iter::once(None)
+        .chain(personalities.names().into_iter().map(Some))
+        .collect();
+    let forced = debug_options.force_playstyle.as_deref();
+    let at = choices
+        .iter()
+        .position(|choice| match (choice, forced) {
+            (Some(name), Some(forced)) => name.eq_ignore_ascii_case(forced),
+            (None, None) => true,
+            _ => false,
+        })
+        .unwrap_or(0);
+    let next = (at as isize + step).rem_euclid(choices.len() as isize) as usize;
+    debug_options.force_playstyle = choices[next].map(str::to_string);
+}
+
+fn update_personality_choice(
+    debug_options: Res<DebugOptions>,
+    mut text: Query<&mut Text, With<PersonalityChoiceText>>,
+) {
+    if !debug_options.is_changed() {
+        return;
+    }
+    for mut text in &mut text {
+        text.0 = personality_label(&debug_options);
+    }
+}
+
+fn seed_label(debug_options: &DebugOptions, entry: Option<&SeedEntry>) -> String {
+    match (entry, debug_options.game_seed) {
+        (Some(entry), _) => format!("{}_", entry.0),
+        (None, Some(seed)) => seed.to_string(),
+        (None, None) => "random".to_string(),
+    }
+}
+
+/// Keyboard entry for the new game's seed: digits only, and an empty field
+/// deals a random game. Enter or Escape stops typing.
+fn type_game_seed(
+    mut commands: Commands,
+    mut keys: MessageReader<KeyboardInput>,
+    entry: Option<ResMut<SeedEntry>>,
+    mut debug_options: ResMut<DebugOptions>,
+) {
+    let Some(mut entry) = entry else {
+        keys.clear();
+        return;
+    };
+    for key in keys.read().filter(|k| k.state.is_pressed()) {
+        match &key.logical_key {
+            Key::Enter | Key::Escape => {
+                commands.remove_resource::<SeedEntry>();
+                break;
+            }
+            Key::Backspace => {
+                entry.0.pop();
+            }
+            _ => {
+                let Some(typed) = &key.text else {
+                    continue;
+                };
+                for digit in typed.chars().filter(char::is_ascii_digit) {
+                    let longer = format!("{}{digit}", entry.0);
+                    if longer.parse::<u64>().is_ok() {
+                        entry.0 = longer;
+                    }
+                }
+            }
+        }
+    }
+    let seed = entry.0.parse().ok();
+    if debug_options.game_seed != seed {
+        debug_options.game_seed = seed;
+    }
+}
+
+fn update_seed_choice(
+    debug_options: Res<DebugOptions>,
+    entry: Option<Res<SeedEntry>>,
+    mut text: Query<&mut Text, With<SeedText>>,
+) {
+    let label = seed_label(&debug_options, entry.as_deref());
+    for mut text in &mut text {
+        if text.0 != label {
+            text.0 = label.clone();
+        }
+    }
+}
+
 fn cleanup_menu(mut commands: Commands, menu: Query<Entity, With<Menu>>) {
+    commands.remove_resource::<SeedEntry>();
     for entity in menu.iter() {
         commands.entity(entity).despawn();
     }
@@ -142,9 +324,9 @@ type GameWorldRoots = (
 );
 
 fn reset_game_resources(commands: &mut Commands) {
-    // A fresh game needs a full trade-card deck and a reset round counter. The
-    // map/faction resources are rebuilt by `load_map` on entering PrepareGame.
-    commands.insert_resource(CivilizationTradeCards::new());
+    // A fresh game needs a reset round counter. The map/faction resources are
+    // rebuilt by `load_map`, and the trade-card deck reshuffled from the
+    // reseeded `GameRng`, on entering PrepareGame.
     commands.insert_resource(GameInfoAndStuff::default());
     // Drop the finished game's standings so the next game's victory screen can't
     // read stale results before `determine_winner` republishes them.
@@ -299,7 +481,7 @@ fn spawn_pause_menu(commands: Commands, _theme: &UiTheme) {
             btn.size(px(300.0), px(60.0));
         },
         |_activate: On<Activate>, mut save_writer: MessageWriter<SaveGameRequest>| {
-            save_writer.write(SaveGameRequest);
+            save_writer.write(SaveGameRequest::NewSlot);
         },
     );
 
@@ -309,7 +491,7 @@ fn spawn_pause_menu(commands: Commands, _theme: &UiTheme) {
             btn.size(px(300.0), px(60.0));
         },
         |_activate: On<Activate>, mut load_writer: MessageWriter<LoadGameRequest>| {
-            load_writer.write(LoadGameRequest);
+            load_writer.write(LoadGameRequest::Latest);
         },
     );
 
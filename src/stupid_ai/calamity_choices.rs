@@ -0,0 +1,75 @@
+use crate::civilization::{AstPosition, PlayerCities};
+use crate::player::Player;
+use crate::stupid_ai::{
+    CalamityChooser, NEUTRAL_TRUST, Personality, PlayerStanding, TradeTrust, Weights,
+};
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::{Entity, Query, With};
+
+/// Lets the calamity systems hand an AI player's choices to its
+/// personality: whom to hit, what to keep. See `scoring/calamity.rs`.
+#[derive(SystemParam)]
+pub struct CalamityChoosers<'w, 's> {
+    players:
+        Query<'w, 's, (Entity, Option<&'static AstPosition>, &'static PlayerCities), With<Player>>,
+    minds: Query<'w, 's, (Option<&'static Personality>, Option<&'static TradeTrust>)>,
+}
+
+impl CalamityChoosers<'_, '_> {
+    /// `me`'s view of the table. A player without a personality (an agent
+    /// seat, a test) chooses like a balanced AI.
+    pub fn for_player(&self, me: Entity) -> CalamityChooser {
+        let (personality, trust) = self.minds.get(me).unwrap_or((None, None));
+        let standings = self
+            .players
+            .iter()
+            .map(|(player, ast, cities)| {
+                (
+                    player,
+                    PlayerStanding {
+                        ast_space: ast.map_or(0, |a| a.space),
+                        cities: cities.number_of_cities(),
+                        trust: trust.map_or(NEUTRAL_TRUST, |t| t.trust(player)),
+                    },
+                )
+            })
+            .collect();
+        CalamityChooser {
+            me,
+            standings,
+            weights: personality.map_or(Weights::uniform(0.5), |p| p.weights),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stupid_ai::StupidAi;
+    use bevy::ecs::system::RunSystemOnce;
+    use bevy::prelude::World;
+
+    #[test]
+    fn a_seated_ai_strikes_the_cheat_before_its_trade_partner() {
+        let mut world = World::new();
+        let partner = world.spawn((Player, PlayerCities::default())).id();
+        let cheat = world.spawn((Player, PlayerCities::default())).id();
+        let me = world
+            .spawn((Player, PlayerCities::default(), StupidAi))
+            .id();
+        let mut trust = world.get_mut::<TradeTrust>(me).unwrap();
+        trust.record(partner, 1.0, 1.0);
+        trust.record(cheat, 0.0, 1.0);
+
+        let chooser = world
+            .run_system_once(move |choosers: CalamityChoosers| choosers.for_player(me))
+            .unwrap();
+
+        assert_eq!(chooser.standings[&partner].trust, 1.0);
+        assert_eq!(chooser.standings[&cheat].trust, 0.0);
+        assert_eq!(
+            chooser.rank_victims(&[partner, cheat]),
+            vec![cheat, partner]
+        );
+    }
+}
@@ -0,0 +1,107 @@
+use crate::GameActivity;
+use crate::civilization::{
+    Faction, GameArea, GameInfoAndStuff, GameMove, GameRng, ReplayDecision, ReplayPlayback,
+    ReplayRecorder, ReplayStep,
+};
+use crate::stupid_ai::{
+    AiDecisionTraces, AiMoveQueue, DecisionTrace, Picker, ScoreBreakdown, describe_ai_move, pick,
+};
+use bevy::ecs::system::SystemParam;
+use bevy::platform::collections::HashMap;
+use bevy::prelude::{Entity, Query, Res, ResMut, State};
+use rand::rngs::StdRng;
+use rand::{RngExt, SeedableRng};
+
+/// The single point where the AI commits to one of its `AvailableMoves`.
+/// Live, it picks with the AI's own random stream and records the choice;
+/// under a replay, it plays the recorded choice instead. Either way the
+/// decision is traced for the debug panel and the agent API.
+#[derive(SystemParam)]
+pub struct AiDecider<'w, 's> {
+    game_rng: ResMut<'w, GameRng>,
+    recorder: ResMut<'w, ReplayRecorder>,
+    playback: Option<ResMut<'w, ReplayPlayback>>,
+    queue: ResMut<'w, AiMoveQueue>,
+    traces: ResMut<'w, AiDecisionTraces>,
+    game_info: Res<'w, GameInfoAndStuff>,
+    activity: Option<Res<'w, State<GameActivity>>>,
+    factions: Query<'w, 's, &'static Faction>,
+    areas: Query<'w, 's, &'static GameArea>,
+}
+
+impl AiDecider<'_, '_> {
+    /// A generator for one search, seeded from the AI's stream. However long
+    /// the search runs, the stream moves on by one draw.
+    pub fn search_rng(&mut self) -> StdRng {
+        StdRng::seed_from_u64(self.game_rng.ai_rng().random())
+    }
+
+    /// The `AvailableMoves` key `player` plays, or `None` for no move now.
+    /// A replay that isn't ready for this player yet puts them back in the
+    /// move queue, so they are asked again next frame.
+    pub fn pick(
+        &mut self,
+        player: Entity,
+        moves: &HashMap<usize, GameMove>,
+        explained: &[(usize, ScoreBreakdown)],
+        picker: Picker,
+    ) -> Option<usize> {
+        let faction = self.factions.get(player).ok()?.faction;
+        let activity = self.activity.as_ref()?.get().clone();
+        let round = self.game_info.round;
+        let scored: Vec<(usize, f32)> = explained
+            .iter()
+            .map(|(index, breakdown)| (*index, breakdown.total()))
+            .collect();
+
+        let mut replayed = false;
+        let mut choice = None;
+        if let Some(playback) = self.playback.as_mut() {
+            match playback.next_decision(faction, round, &activity, &scored) {
+                ReplayStep::Choice(recorded) => {
+                    replayed = true;
+                    choice = Some(recorded);
+                }
+                ReplayStep::Wait => {
+                    if !self.queue.pending.iter().any(|(p, _)| *p == player) {
+                        self.queue.push(player, 0.0);
+                    }
+                    return None;
+                }
+                ReplayStep::Exhausted => {}
+            }
+        }
+
+        let choice = match choice {
+            Some(choice) => choice,
+            None => {
+                let choice = pick(&scored, picker, self.game_rng.ai_rng())?;
+                self.recorder.record_decision(ReplayDecision {
+                    faction,
+                    round,
+                    activity: activity.clone(),
+                    choice,
+                });
+                choice
+            }
+        };
+
+        let areas = &self.areas;
+        let mut trace = DecisionTrace::new(
+            round,
+            format!("{activity:?}"),
+            picker,
+            explained,
+            choice,
+            |index| {
+                moves.get(&index).map_or_else(
+                    || format!("move {index}"),
+                    |m| describe_ai_move(m, |area| areas.get(area).ok().map(|a| a.id)),
+                )
+            },
+        );
+        trace.replayed = replayed;
+        self.traces.record(faction, trace);
+        Some(choice)
+    }
+}
@@ -0,0 +1,346 @@
+use crate::civilization::{AcquireCivilizationCardsMove, GameFaction, GameMove};
+use crate::stupid_ai::{Picker, ScoreBreakdown};
+use bevy::platform::collections::HashMap;
+use bevy::prelude::{Entity, ResMut, Resource};
+use serde::Serialize;
+use std::collections::VecDeque;
+
+/// Candidates kept per decision, best first.
+pub const TRACE_CANDIDATES: usize = 5;
+/// Decisions kept per faction, newest last.
+pub const TRACE_HISTORY: usize = 20;
+/// Considerations read off the player's hidden hand: its value and the
+/// calamities in it.
+pub const HAND_CONSIDERATIONS: [&str; 2] = ["hand", "calamities"];
+
+/// One scored move as the AI saw it.
+#[derive(Clone, Debug, Serialize)]
+pub struct TracedCandidate {
+    /// The `AvailableMoves` key.
+    pub index: usize,
+    pub description: String,
+    pub score: f32,
+    /// What each consideration added to the score.
+    pub considerations: Vec<(&'static str, f32)>,
+}
+
+impl TracedCandidate {
+    /// The candidate without the [`HAND_CONSIDERATIONS`], scored as if they
+    /// had added nothing.
+    pub fn public_view(&self) -> Self {
+        let (hidden, shown): (Vec<_>, Vec<_>) = self
+            .considerations
+            .iter()
+            .copied()
+            .partition(|(name, _)| HAND_CONSIDERATIONS.contains(name));
+        TracedCandidate {
+            index: self.index,
+            description: self.description.clone(),
+            score: self.score - hidden.iter().map(|(_, v)| v).sum::<f32>(),
+            considerations: shown,
+        }
+    }
+}
+
+/// Why the AI played what it played: the best candidates with their score
+/// breakdowns, and where the picker's choice ranked among them.
+#[derive(Clone, Debug, Serialize)]
+pub struct DecisionTrace {
+    pub round: usize,
+    pub activity: String,
+    pub picker: Picker,
+    /// How many moves were scored.
+    pub considered: usize,
+    /// The [`TRACE_CANDIDATES`] best, best first.
+    pub candidates: Vec<TracedCandidate>,
+    pub chosen: TracedCandidate,
+    /// 1 = the best-scored move. Softmax pickers take lower ranks now and then.
+    pub chosen_rank: usize,
+    /// Played from a replay file rather than picked.
+    pub replayed: bool,
+}
+
+impl DecisionTrace {
+    /// Rank `explained` and note which one was `chosen`. `describe` names a
+    /// move by its `AvailableMoves` key; only the kept candidates are named.
+    pub fn new(
+        round: usize,
+        activity: String,
+        picker: Picker,
+        explained: &[(usize, ScoreBreakdown)],
+        chosen: usize,
+        describe: impl Fn(usize) -> String,
+    ) -> Self {
+        let mut ranked: Vec<(usize, &ScoreBreakdown, f32)> = explained
+            .iter()
+            .map(|(index, breakdown)| (*index, breakdown, breakdown.total()))
+            .collect();
+        // Best first; unscorable moves last, ties in key order.
+        ranked.sort_by(|a, b| {
+            let score = |s: f32| if s.is_nan() { f32::NEG_INFINITY } else { s };
+            score(b.2).total_cmp(&score(a.2)).then(a.0.cmp(&b.0))
+        });
+        let candidate =
+            |(index, breakdown, score): &(usize, &ScoreBreakdown, f32)| TracedCandidate {
+                index: *index,
+                description: describe(*index),
+                score: *score,
+                considerations: breakdown.terms.clone(),
+            };
+        let chosen_at = ranked.iter().position(|(index, _, _)| *index == chosen);
+        DecisionTrace {
+            round,
+            activity,
+            picker,
+            considered: ranked.len(),
+            candidates: ranked
+                .iter()
+                .take(TRACE_CANDIDATES)
+                .map(candidate)
+                .collect(),
+            chosen: chosen_at.map_or_else(
+                || TracedCandidate {
+                    index: chosen,
+                    description: describe(chosen),
+                    score: f32::NAN,
+                    considerations: Vec::new(),
+                },
+                |at| candidate(&ranked[at]),
+            ),
+            chosen_rank: chosen_at.map_or(0, |at| at + 1),
+            replayed: false,
+        }
+    }
+
+    /// The trace as the other players may see it: nothing read off the
+    /// hidden hand. Candidates keep the rank the full scores gave them.
+    pub fn public_view(&self) -> Self {
+        DecisionTrace {
+            activity: self.activity.clone(),
+            candidates: self
+                .candidates
+                .iter()
+                .map(TracedCandidate::public_view)
+                .collect(),
+            chosen: self.chosen.public_view(),
+            ..*self
+        }
+    }
+
+    /// Multi-line text for the debug panel.
+    pub fn to_text(&self) -> String {
+        let picker = match self.picker {
+            Picker::Greedy => "greedy".to_string(),
+            Picker::Softmax { temperature } => format!("softmax {temperature:.2}"),
+        };
+        let mut text = format!(
+            "Round {}, {} ({picker}{})\nChose #{} of {}: {} ({:.2})",
+            self.round,
+            self.activity,
+            if self.replayed { ", replayed" } else { "" },
+            self.chosen_rank,
+            self.considered,
+            self.chosen.description,
+            self.chosen.score,
+        );
+        for (rank, candidate) in self.candidates.iter().enumerate() {
+            let terms: Vec<String> = candidate
+                .considerations
+                .iter()
+                .map(|(name, value)| format!("{name} {value:+.2}"))
+                .collect();
+            text.push_str(&format!(
+                "\n{}. {:.2} {}\n   {}",
+                rank + 1,
+                candidate.score,
+                candidate.description,
+                terms.join(", ")
+            ));
+        }
+        text
+    }
+}
+
+/// The latest AI decisions of every faction, for the debug panel and the
+/// agent API's `/ai/trace`.
+#[derive(Resource, Default, Debug)]
+pub struct AiDecisionTraces {
+    by_faction: HashMap<GameFaction, VecDeque<DecisionTrace>>,
+}
+
+impl AiDecisionTraces {
+    pub fn record(&mut self, faction: GameFaction, trace: DecisionTrace) {
+        let history = self.by_faction.entry(faction).or_default();
+        if history.len() == TRACE_HISTORY {
+            history.pop_front();
+        }
+        history.push_back(trace);
+    }
+
+    pub fn latest(&self, faction: GameFaction) -> Option<&DecisionTrace> {
+        self.by_faction.get(&faction)?.back()
+    }
+
+    /// Oldest first.
+    pub fn history(&self, faction: GameFaction) -> impl Iterator<Item = &DecisionTrace> {
+        self.by_faction.get(&faction).into_iter().flatten()
+    }
+
+    pub fn factions(&self) -> impl Iterator<Item = GameFaction> + '_ {
+        self.by_faction.keys().copied()
+    }
+
+    pub fn clear(&mut self) {
+        self.by_faction.clear();
+    }
+}
+
+/// A new game starts with no decisions behind it.
+pub fn clear_decision_traces(mut traces: ResMut<AiDecisionTraces>) {
+    traces.clear();
+}
+
+/// A one-line name for a move, with areas by their printed id.
+pub fn describe_ai_move(game_move: &GameMove, area_id: impl Fn(Entity) -> Option<i32>) -> String {
+    let area = |e: Entity| area_id(e).map_or_else(|| "?".to_string(), |id| id.to_string());
+    match game_move {
+        GameMove::PopulationExpansion(m) => format!("Expand in area {}", area(m.area)),
+        GameMove::Movement(m) => format!(
+            "Move up to {} from {} to {}",
+            m.max_tokens,
+            area(m.source),
+            area(m.target)
+        ),
+        GameMove::ShipFerry(m) => format!(
+            "Ferry up to {} from {} to {}",
+            m.max_tokens,
+            area(m.source),
+            area(m.target)
+        ),
+        GameMove::AttackArea(m) => format!(
+            "Attack {} from {} with up to {}",
+            area(m.target),
+            area(m.source),
+            m.max_tokens
+        ),
+        GameMove::AttackCity(m) => format!(
+            "Attack city in {} from {} with up to {}",
+            area(m.target),
+            area(m.source),
+            m.max_tokens
+        ),
+        GameMove::EndMovement => "End movement".to_string(),
+        GameMove::CityConstruction(m) => format!("Build city in {}", area(m.target)),
+        GameMove::EndCityConstruction => "Build no more cities".to_string(),
+        GameMove::EliminateCity(m) => format!("Give up city in {}", area(m.area)),
+        GameMove::Trade(_) => "Trade".to_string(),
+        GameMove::AcquireCivilizationCards(AcquireCivilizationCardsMove::AcquireCard(card)) => {
+            format!("Buy {card:?}")
+        }
+        GameMove::AcquireCivilizationCards(AcquireCivilizationCardsMove::AcquireCards(cards)) => {
+            format!("Buy {cards:?}")
+        }
+        GameMove::AcquireCivilizationCards(AcquireCivilizationCardsMove::DoneAcquiringCards) => {
+            "Buy no more cards".to_string()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn explained(scores: &[(usize, f32)]) -> Vec<(usize, ScoreBreakdown)> {
+        scores
+            .iter()
+            .map(|(i, s)| {
+                let mut breakdown = ScoreBreakdown::fixed("baseline", 0.1);
+                breakdown.add("aggression", s - 0.1);
+                (*i, breakdown)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn a_trace_keeps_the_best_candidates_and_ranks_the_choice() {
+        let scores: Vec<(usize, f32)> = (0..8).map(|i| (i, i as f32 / 10.0)).collect();
+        let trace = DecisionTrace::new(
+            3,
+            "Movement".to_string(),
+            Picker::Softmax { temperature: 0.3 },
+            &explained(&scores),
+            1,
+            |i| format!("move {i}"),
+        );
+
+        assert_eq!(trace.considered, 8);
+        let kept: Vec<usize> = trace.candidates.iter().map(|c| c.index).collect();
+        assert_eq!(kept, vec![7, 6, 5, 4, 3]);
+        assert_eq!(trace.chosen.index, 1);
+        assert_eq!(trace.chosen.description, "move 1");
+        assert_eq!(trace.chosen_rank, 7);
+        for candidate in &trace.candidates {
+            let sum: f32 = candidate.considerations.iter().map(|(_, v)| v).sum();
+            assert!((sum - candidate.score).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn unscorable_moves_rank_last() {
+        let mut moves = explained(&[(0, 0.2)]);
+        moves.push((1, ScoreBreakdown::fixed("unaffordable", f32::NEG_INFINITY)));
+        moves.push((2, ScoreBreakdown::fixed("broken", f32::NAN)));
+        let trace = DecisionTrace::new(1, "X".to_string(), Picker::Greedy, &moves, 0, |i| {
+            i.to_string()
+        });
+        assert_eq!(trace.candidates[0].index, 0);
+        assert_eq!(trace.chosen_rank, 1);
+    }
+
+    #[test]
+    fn the_public_view_leaves_out_what_the_hand_added() {
+        let mut breakdown = ScoreBreakdown::fixed("expansion", 0.4);
+        breakdown.add("calamities", -0.3);
+        breakdown.add("hand", 0.2);
+        let trace = DecisionTrace::new(
+            2,
+            "AcquireCivilizationCards".to_string(),
+            Picker::Greedy,
+            &[(0, breakdown)],
+            0,
+            |i| i.to_string(),
+        );
+
+        let public = trace.public_view();
+
+        assert_eq!(public.chosen.considerations, vec![("expansion", 0.4)]);
+        assert!((public.chosen.score - 0.4).abs() < 1e-6);
+        assert_eq!(
+            public.candidates[0].considerations,
+            public.chosen.considerations
+        );
+        assert_eq!(trace.chosen.considerations.len(), 3);
+    }
+
+    #[test]
+    fn history_is_bounded_per_faction() {
+        let mut traces = AiDecisionTraces::default();
+        for round in 0..TRACE_HISTORY + 5 {
+            let trace = DecisionTrace::new(
+                round,
+                "Movement".to_string(),
+                Picker::Greedy,
+                &explained(&[(0, 0.5)]),
+                0,
+                |i| i.to_string(),
+            );
+            traces.record(GameFaction::Egypt, trace);
+        }
+        assert_eq!(traces.history(GameFaction::Egypt).count(), TRACE_HISTORY);
+        assert_eq!(
+            traces.latest(GameFaction::Egypt).map(|t| t.round),
+            Some(TRACE_HISTORY + 4)
+        );
+        assert!(traces.latest(GameFaction::Babylon).is_none());
+    }
+}
@@ -0,0 +1,166 @@
+use crate::GameActivity;
+use crate::civilization::{DebugOptions, Faction, GameFaction, Z_PANEL};
+use crate::stupid_ai::{AiDecisionTraces, StupidAi};
+use bevy::prelude::*;
+use bevy::ui_widgets::Activate;
+use lava_ui_builder::{LavaTheme, TextStyle, UIBuilder};
+
+#[derive(Component, Default)]
+pub struct AiTracePanel;
+
+#[derive(Component, Default)]
+pub struct AiTraceFactionText;
+
+#[derive(Component, Default)]
+pub struct AiTraceText;
+
+/// The AI player whose decisions the trace panel shows.
+#[derive(Resource, Default)]
+pub struct AiTracePanelFocus(pub Option<GameFaction>);
+
+/// Right-edge debug panel explaining the focused AI player's latest
+/// decision. Spawned when `DebugOptions::show_debug_ui` is on.
+pub struct AiTraceUiPlugin;
+
+impl Plugin for AiTraceUiPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<AiTracePanelFocus>()
+            .add_systems(
+                OnEnter(GameActivity::StartGame),
+                spawn_ai_trace_panel.run_if(debug_ui_enabled),
+            )
+            .add_systems(
+                Update,
+                update_ai_trace_panel.run_if(any_with_component::<AiTracePanel>),
+            );
+    }
+}
+
+fn debug_ui_enabled(debug_options: Res<DebugOptions>) -> bool {
+    debug_options.show_debug_ui
+}
+
+/// The AI factions in a fixed order, for cycling the focus.
+fn ai_factions(ai_players: &Query<&Faction, With<StupidAi>>) -> Vec<GameFaction> {
+    let mut factions: Vec<GameFaction> = ai_players.iter().map(|f| f.faction).collect();
+    factions.sort_by_key(|&f| f as u8);
+    factions
+}
+
+fn cycle_focus(
+    focus: &mut AiTracePanelFocus,
+    ai_players: &Query<&Faction, With<StupidAi>>,
+    step: isize,
+) {
+    let factions = ai_factions(ai_players);
+    if factions.is_empty() {
+        focus.0 = None;
+        return;
+    }
+    let at = focus
+        .0
+        .and_then(|f| factions.iter().position(|&g| g == f))
+        .map_or(0, |at| {
+            (at as isize + step).rem_euclid(factions.len() as isize) as usize
+        });
+    focus.0 = Some(factions[at]);
+}
+
+pub fn spawn_ai_trace_panel(
+    commands: Commands,
+    theme: Res<LavaTheme>,
+    existing: Query<Entity, With<AiTracePanel>>,
+) {
+    if !existing.is_empty() {
+        return;
+    }
+    let mut ui = UIBuilder::new(commands, Some(theme.clone()));
+
+    ui.component::<AiTracePanel>()
+        .absolute_position()
+        .top(Val::Percent(30.0))
+        .right(Val::Px(8.0))
+        .z_index(Z_PANEL)
+        .display_flex()
+        .flex_column()
+        .gap_px(4.0)
+        .padding_all_px(6.0)
+        .bg_color(Color::srgba(0.0, 0.0, 0.0, 0.9))
+        .border_radius_all_px(5.0);
+
+    ui.add_row(|row| {
+        row.align_items_center().gap_px(4.0);
+        row.add_button_observe(
+            "◀",
+            |btn| {
+                btn.size_px(22.0, 22.0).font_size(12.0);
+            },
+            |_: On<Activate>,
+             mut focus: ResMut<AiTracePanelFocus>,
+             ai_players: Query<&Faction, With<StupidAi>>| {
+                cycle_focus(&mut focus, &ai_players, -1);
+            },
+        );
+        row.with_child(|c| {
+            c.component::<AiTraceFactionText>()
+                .with_text("AI trace", Some(TextStyle::size_color(14.0, Color::WHITE)))
+                .width_px(160.0);
+        });
+        row.add_button_observe(
+            "▶",
+            |btn| {
+                btn.size_px(22.0, 22.0).font_size(12.0);
+            },
+            |_: On<Activate>,
+             mut focus: ResMut<AiTracePanelFocus>,
+             ai_players: Query<&Faction, With<StupidAi>>| {
+                cycle_focus(&mut focus, &ai_players, 1);
+            },
+        );
+        row.add_button_observe(
+            "×",
+            |btn| {
+                btn.size_px(22.0, 22.0).font_size(12.0);
+            },
+            |_: On<Activate>, mut commands: Commands, panel: Query<Entity, With<AiTracePanel>>| {
+                for entity in &panel {
+                    commands.entity(entity).despawn();
+                }
+            },
+        );
+    });
+    ui.with_child(|c| {
+        c.component::<AiTraceText>()
+            .with_text("", Some(TextStyle::size(11.0)))
+            .width_px(420.0);
+    });
+
+    ui.build();
+}
+
+fn update_ai_trace_panel(
+    traces: Res<AiDecisionTraces>,
+    mut focus: ResMut<AiTracePanelFocus>,
+    ai_players: Query<&Faction, With<StupidAi>>,
+    mut faction_text: Query<&mut Text, (With<AiTraceFactionText>, Without<AiTraceText>)>,
+    mut trace_text: Query<&mut Text, (With<AiTraceText>, Without<AiTraceFactionText>)>,
+) {
+    // Follow the first AI player until one is picked.
+    if focus.0.is_none() && !ai_players.is_empty() {
+        cycle_focus(&mut focus, &ai_players, 0);
+    }
+    if !traces.is_changed() && !focus.is_changed() {
+        return;
+    }
+    let Some(faction) = focus.0 else {
+        return;
+    };
+    if let Ok(mut text) = faction_text.single_mut() {
+        text.0 = format!("AI trace: {faction:?}");
+    }
+    if let Ok(mut text) = trace_text.single_mut() {
+        text.0 = traces
+            .latest(faction)
+            .map_or_else(|| "No decisions yet".to_string(), |t| t.to_text());
+    }
+}
@@ -0,0 +1,355 @@
+//! Monte Carlo tree search over a [`ForwardModel`], with a fresh
+//! determinization per iteration: every pass through the tree samples the
+//! hidden information again (information-set MCTS), so no single guess about
+//! the other players' hands decides the move. A child only competes while its
+//! move is legal in the sampled world, and its exploration bonus counts how
+//! often it was available rather than how often its parent was visited.
+
+use bevy::platform::time::Instant;
+use bevy::prelude::Reflect;
+use rand::Rng;
+use std::time::Duration;
+
+/// A game the searching player can play forward on a copy. Only the
+/// searcher's own decisions are tree nodes; `apply` plays everyone else's
+/// moves (and any automatic phases) up to the searcher's next decision.
+pub trait ForwardModel: Clone {
+    type Move: Clone + PartialEq;
+
+    /// The searcher's moves from here; empty once the search horizon is
+    /// reached.
+    fn legal_moves(&self) -> Vec<Self::Move>;
+
+    /// Play the searcher's `mv`, then the world up to its next decision.
+    fn apply(&mut self, mv: &Self::Move);
+
+    /// Which of `moves` the rollout policy plays.
+    fn rollout_pick<R: Rng>(&self, moves: &[Self::Move], rng: &mut R) -> usize;
+
+    /// How well the searcher stands; what the search maximises.
+    fn reward(&self) -> f32;
+}
+
+/// How hard an MCTS player thinks.
+#[derive(Clone, Copy, Debug, PartialEq, Reflect)]
+pub struct MctsConfig {
+    /// Wall-clock budget per decision.
+    pub time_budget: Duration,
+    /// Stop early after this many iterations; makes a search reproducible
+    /// when the budget never runs out first.
+    pub max_iterations: u32,
+    /// UCB1 exploration constant.
+    pub exploration: f32,
+    /// Tree depth in searcher decisions; below it the rollout policy plays.
+    pub max_depth: usize,
+    /// Searcher moves a rollout may play before it is scored as it stands.
+    pub max_rollout_moves: usize,
+}
+
+impl Default for MctsConfig {
+    fn default() -> Self {
+        MctsConfig {
+            time_budget: Duration::from_millis(250),
+            max_iterations: 10_000,
+            exploration: 0.3,
+            max_depth: 6,
+            max_rollout_moves: 40,
+        }
+    }
+}
+
+impl MctsConfig {
+    /// The default, overridden from the environment:
+    ///
+    /// - `MCTS_BUDGET_MS=<ms>` — thinking time per decision.
+    /// - `MCTS_ITERATIONS=<n>` — iteration cap. A seeded game only replays
+    ///   move for move if this cap, not the clock, ends every search.
+    pub fn from_env() -> Self {
+        let mut config = Self::default();
+        if let Ok(ms) = std::env::var("MCTS_BUDGET_MS")
+            && let Ok(ms) = ms.trim().parse::<u64>()
+        {
+            config.time_budget = Duration::from_millis(ms);
+        }
+        if let Ok(n) = std::env::var("MCTS_ITERATIONS")
+            && let Ok(n) = n.trim().parse::<u32>()
+        {
+            config.max_iterations = n.max(1);
+        }
+        config
+    }
+}
+
+/// What the search learned about one root move.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct MoveStats {
+    pub visits: u32,
+    /// Mean reward over the visits; `NEG_INFINITY` if never visited.
+    pub mean: f32,
+}
+
+struct Node<M> {
+    mv: Option<M>,
+    children: Vec<usize>,
+    visits: u32,
+    total: f32,
+    /// Iterations in which this node's move was legal at its parent.
+    available: u32,
+}
+
+impl<M> Node<M> {
+    fn new(mv: Option<M>) -> Self {
+        Node {
+            mv,
+            children: Vec::new(),
+            visits: 0,
+            total: 0.0,
+            available: 0,
+        }
+    }
+
+    fn mean(&self) -> f32 {
+        if self.visits == 0 {
+            f32::NEG_INFINITY
+        } else {
+            self.total / self.visits as f32
+        }
+    }
+}
+
+/// Search `root_moves` (the searcher's real options, in order) on worlds
+/// drawn by `determinize`, until the time or iteration budget runs out.
+/// Every root move is tried at least once. Returns one [`MoveStats`] per
+/// root move.
+pub fn search<M, R, D>(
+    root_moves: &[M::Move],
+    mut determinize: D,
+    config: &MctsConfig,
+    rng: &mut R,
+) -> Vec<MoveStats>
+where
+    M: ForwardModel,
+    R: Rng,
+    D: FnMut(&mut R) -> M,
+{
+    let mut nodes: Vec<Node<M::Move>> = vec![Node::new(None)];
+    for mv in root_moves {
+        nodes.push(Node::new(Some(mv.clone())));
+    }
+    nodes[0].children = (1..=root_moves.len()).collect();
+
+    let started = Instant::now();
+    let mut iterations = 0;
+    while iterations < config.max_iterations
+        && (iterations < root_moves.len() as u32 || started.elapsed() < config.time_budget)
+    {
+        let mut model = determinize(rng);
+        let mut path = vec![0];
+        let mut node = 0;
+        let mut depth = 0;
+        loop {
+            // The root's options are the real ones; below it, whatever the
+            // sampled world allows.
+            let moves = if node == 0 {
+                root_moves.to_vec()
+            } else {
+                model.legal_moves()
+            };
+            if moves.is_empty() || depth >= config.max_depth {
+                break;
+            }
+            for &child in &nodes[node].children {
+                if nodes[child].mv.as_ref().is_some_and(|m| moves.contains(m)) {
+                    nodes[child].available += 1;
+                }
+            }
+            let untried = moves.iter().find(|m| {
+                !nodes[node]
+                    .children
+                    .iter()
+                    .any(|&c| nodes[c].mv.as_ref() == Some(*m) && nodes[c].visits > 0)
+            });
+            if let Some(mv) = untried {
+                let child = match nodes[node]
+                    .children
+                    .iter()
+                    .copied()
+                    .find(|&c| nodes[c].mv.as_ref() == Some(mv))
+                {
+                    Some(child) => child,
+                    None => {
+                        let mut fresh = Node::new(Some(mv.clone()));
+                        fresh.available = 1;
+                        nodes.push(fresh);
+                        let child = nodes.len() - 1;
+                        nodes[node].children.push(child);
+                        child
+                    }
+                };
+                model.apply(mv);
+                path.push(child);
+                depth += 1;
+                break;
+            }
+            let Some(child) = select_child(&nodes, node, &moves, config.exploration) else {
+                break;
+            };
+            if let Some(mv) = nodes[child].mv.clone() {
+                model.apply(&mv);
+            }
+            path.push(child);
+            node = child;
+            depth += 1;
+        }
+
+        for _ in 0..config.max_rollout_moves {
+            let moves = model.legal_moves();
+            if moves.is_empty() {
+                break;
+            }
+            let index = model.rollout_pick(&moves, rng).min(moves.len() - 1);
+            model.apply(&moves[index]);
+        }
+
+        let reward = model.reward();
+        for &n in &path {
+            nodes[n].visits += 1;
+            nodes[n].total += reward;
+        }
+        iterations += 1;
+    }
+
+    (1..=root_moves.len())
+        .map(|n| MoveStats {
+            visits: nodes[n].visits,
+            mean: nodes[n].mean(),
+        })
+        .collect()
+}
+
+/// UCB1 over the children of `node` whose move is in `moves`.
+fn select_child<M: PartialEq>(
+    nodes: &[Node<M>],
+    node: usize,
+    moves: &[M],
+    exploration: f32,
+) -> Option<usize> {
+    nodes[node]
+        .children
+        .iter()
+        .copied()
+        .filter(|&c| nodes[c].mv.as_ref().is_some_and(|m| moves.contains(m)))
+        .map(|c| {
+            let child = &nodes[c];
+            let bonus = exploration
+                * ((child.available.max(1) as f32).ln() / child.visits.max(1) as f32).sqrt();
+            (c, child.mean() + bonus)
+        })
+        .max_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(c, _)| c)
+}
+
+/// The root move with the best mean reward; the first on a tie.
+pub fn best_move(stats: &[MoveStats]) -> Option<usize> {
+    stats
+        .iter()
+        .enumerate()
+        .filter(|(_, s)| s.visits > 0)
+        .fold(None, |best: Option<(usize, f32)>, (i, s)| match best {
+            Some((_, mean)) if mean >= s.mean => best,
+            _ => Some((i, s.mean)),
+        })
+        .map(|(i, _)| i)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::{RngExt, SeedableRng};
+
+    /// One decision: move 0 is safe and scores 0.5; move 1 gambles and pays
+    /// 1 if the hidden coin is heads. Only the determinization knows the coin.
+    #[derive(Clone)]
+    struct Coin {
+        heads: bool,
+        played: Option<u8>,
+    }
+
+    impl ForwardModel for Coin {
+        type Move = u8;
+
+        fn legal_moves(&self) -> Vec<u8> {
+            if self.played.is_some() {
+                Vec::new()
+            } else {
+                vec![0, 1]
+            }
+        }
+
+        fn apply(&mut self, mv: &u8) {
+            self.played = Some(*mv);
+        }
+
+        fn rollout_pick<R: Rng>(&self, _moves: &[u8], _rng: &mut R) -> usize {
+            0
+        }
+
+        fn reward(&self) -> f32 {
+            match self.played {
+                Some(1) if self.heads => 1.0,
+                Some(1) => 0.0,
+                _ => 0.5,
+            }
+        }
+    }
+
+    fn run(heads_chance: f64) -> Vec<MoveStats> {
+        let mut rng = StdRng::seed_from_u64(7);
+        let config = MctsConfig {
+            time_budget: Duration::from_secs(60),
+            max_iterations: 400,
+            ..Default::default()
+        };
+        search(
+            &[0u8, 1],
+            |rng: &mut StdRng| Coin {
+                heads: rng.random_bool(heads_chance),
+                played: None,
+            },
+            &config,
+            &mut rng,
+        )
+    }
+
+    #[test]
+    fn the_gamble_is_taken_only_when_the_hidden_odds_favour_it() {
+        let likely = run(0.8);
+        assert_eq!(best_move(&likely), Some(1));
+        assert!(likely[1].mean > 0.6);
+
+        let unlikely = run(0.2);
+        assert_eq!(best_move(&unlikely), Some(0));
+        assert_eq!(unlikely.iter().map(|s| s.visits).sum::<u32>(), 400);
+    }
+
+    #[test]
+    fn every_root_move_is_tried_even_without_time() {
+        let mut rng = StdRng::seed_from_u64(1);
+        let config = MctsConfig {
+            time_budget: Duration::ZERO,
+            ..Default::default()
+        };
+        let stats = search(
+            &[0u8, 1],
+            |_: &mut StdRng| Coin {
+                heads: true,
+                played: None,
+            },
+            &config,
+            &mut rng,
+        );
+        assert!(stats.iter().all(|s| s.visits == 1));
+        assert_eq!(best_move(&stats), Some(1));
+    }
+}
@@ -0,0 +1,458 @@
+//! [`GameModel`] as the [`ForwardModel`] [`search`](super::search) plays
+//! forward. The searcher's decisions are the model's legal moves; everyone
+//! else plays the greedy utility AI through the same scorers the live
+//! systems use, on stand-in entities. The model plays every phase between
+//! decisions, calamities and civ-card purchases included, and leaves out
+//! what it always leaves out: ships and trading.
+
+use crate::GameActivity;
+use crate::civilization::{
+    AcquireCivilizationCardsMove, AvailableCivCards, BuildCityMove, CityOwner, CivCardName,
+    EliminateCityMove, GameFaction, GameModel, GameMove, ModelMove, ModelMovement, MovementMove,
+    PopExpMove, TradeCard, TradeCardTrait,
+};
+use crate::stupid_ai::{
+    AreaSummary, CivCardOption, ForwardModel, Picker, PlayerPosition, Weights,
+    civ_card_credit_value, explain_city_construction, explain_city_elimination, explain_civ_card,
+    explain_movement, explain_population_expansion, explain_position, pick,
+};
+use bevy::platform::collections::HashMap;
+use bevy::prelude::{Entity, debug};
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, RngExt, SeedableRng};
+
+/// Moves a rival makes in one movement turn before it is made to stop.
+const MOVES_PER_TURN: usize = 12;
+
+/// Who a searched [`GameModel`] plays for, and how every player picks the
+/// moves the search does not make.
+#[derive(Clone, Debug)]
+pub struct ModelSearcher {
+    pub me: GameFaction,
+    pub minds: HashMap<GameFaction, (Weights, Picker)>,
+    /// For valuing civ cards in [`ForwardModel::reward`].
+    pub defs: AvailableCivCards,
+}
+
+impl ModelSearcher {
+    fn mind(&self, faction: GameFaction) -> (Weights, Picker) {
+        self.minds
+            .get(&faction)
+            .copied()
+            .unwrap_or((Weights::uniform(0.5), Picker::Greedy))
+    }
+}
+
+/// What the searcher knows about the trade cards it cannot see.
+#[derive(Clone, Debug, Default)]
+pub struct HandKnowledge {
+    /// The draw piles and every rival's hand, mixed together.
+    pub unseen: Vec<TradeCard>,
+    /// How many cards each rival holds, which is public.
+    pub hand_sizes: Vec<(GameFaction, usize)>,
+    /// Calamities I traded away, so I know who holds them.
+    pub known: Vec<(GameFaction, TradeCard)>,
+}
+
+/// `model` with every rival's hand and the draw piles dealt afresh from what
+/// the searcher cannot see, keeping to what `knowledge` pins down, and its
+/// own random draws reseeded from `rng`.
+pub fn determinize<R: Rng>(model: &GameModel, knowledge: &HandKnowledge, rng: &mut R) -> GameModel {
+    let mut pool = knowledge.unseen.clone();
+    for (_, card) in &knowledge.known {
+        if let Some(i) = pool.iter().position(|c| c == card) {
+            pool.swap_remove(i);
+        }
+    }
+    pool.shuffle(rng);
+    let me = model.searcher.as_ref().map(|s| s.me);
+    let mut dealt = model.clone();
+    for &(rival, size) in &knowledge.hand_sizes {
+        let Some(player) = dealt.player_mut(rival) else {
+            continue;
+        };
+        player.hand.clear();
+        player.calamity_traded_by.clear();
+        let mut held = 0;
+        for (_, card) in knowledge.known.iter().filter(|(f, _)| *f == rival) {
+            player.add_card(*card);
+            if let Some(me) = me {
+                player.calamity_traded_by.push((*card, me));
+            }
+            held += 1;
+        }
+        while held < size {
+            let Some(card) = pool.pop() else {
+                break;
+            };
+            player.add_card(card);
+            held += 1;
+        }
+    }
+    dealt.piles.clear();
+    for card in pool {
+        dealt.piles.entry(card.value()).or_default().push(card);
+    }
+    dealt.rng = StdRng::seed_from_u64(rng.random());
+    dealt
+}
+
+/// The entity the utility scorers see for area `id`.
+fn area_entity(id: i32) -> Entity {
+    Entity::from_raw_u32(id as u32).expect("area ids fit in an entity index")
+}
+
+/// The entity the utility scorers see for `faction`, clear of every area's.
+fn seat_entity(faction: GameFaction) -> Entity {
+    Entity::from_raw_u32(u32::MAX - 1 - faction as u32).expect("below the placeholder index")
+}
+
+/// What the utility scorers read, from `faction`'s point of view.
+fn summaries(model: &GameModel, faction: GameFaction) -> HashMap<Entity, AreaSummary> {
+    model
+        .rules
+        .areas
+        .values()
+        .map(|area| {
+            let occupancy = model.area(area.id);
+            let my_pop = occupancy.map_or(0, |o| o.count(faction));
+            let city = occupancy.and_then(|o| o.city);
+            let summary = AreaSummary {
+                max_population: area.max_population,
+                is_city_site: area.city_site,
+                has_city: city.is_some(),
+                city_is_mine: city == Some(CityOwner::Faction(faction)),
+                my_pop,
+                enemy_pop: occupancy.map_or(0, |o| o.total()) - my_pop,
+                neighbours: area.land.iter().map(|a| area_entity(*a)).collect(),
+                sea_neighbours: area.sea.iter().map(|a| area_entity(*a)).collect(),
+                is_open_sea: false,
+            };
+            (area_entity(area.id), summary)
+        })
+        .collect()
+}
+
+/// `mv` as the live move the utility scorers take.
+fn game_move(faction: GameFaction, mv: &ModelMove) -> GameMove {
+    let seat = seat_entity(faction);
+    let movement = |m: &ModelMovement| {
+        MovementMove::new(area_entity(m.from), area_entity(m.to), seat, m.tokens)
+    };
+    match mv {
+        ModelMove::PopulationExpansion { area, tokens } => {
+            GameMove::PopulationExpansion(PopExpMove::new(area_entity(*area), *tokens))
+        }
+        ModelMove::Movement(m) => GameMove::Movement(movement(m)),
+        ModelMove::AttackArea(m) => GameMove::AttackArea(movement(m)),
+        ModelMove::AttackCity(m) => GameMove::AttackCity(movement(m)),
+        ModelMove::EndMovement => GameMove::EndMovement,
+        ModelMove::CityConstruction { area } => {
+            GameMove::CityConstruction(BuildCityMove::new(area_entity(*area), seat))
+        }
+        ModelMove::EndCityConstruction => GameMove::EndCityConstruction,
+        ModelMove::EliminateCity { area } => GameMove::EliminateCity(EliminateCityMove::new(
+            seat,
+            area_entity(*area),
+            area_entity(*area),
+            0,
+            0,
+        )),
+        ModelMove::AcquireCivCard(card) => {
+            GameMove::AcquireCivilizationCards(AcquireCivilizationCardsMove::AcquireCard(*card))
+        }
+        ModelMove::DoneAcquiringCards => {
+            GameMove::AcquireCivilizationCards(AcquireCivilizationCardsMove::DoneAcquiringCards)
+        }
+    }
+}
+
+/// What buying `card` costs `faction` and brings, as
+/// `select_stupid_civ_card_move` weighs it.
+fn civ_card_option(
+    model: &GameModel,
+    faction: GameFaction,
+    card: CivCardName,
+) -> Option<CivCardOption> {
+    let player = model.player(faction)?;
+    let credits = model
+        .rules
+        .credits(model.held_before.get(&faction).unwrap_or(&player.civ_cards));
+    let def = model.rules.civ_card(card)?;
+    Some(CivCardOption {
+        effective_cost: def.calculate_cost(&credits),
+        credit_value: civ_card_credit_value(def),
+        wealth: player.buying_power() as u32,
+    })
+}
+
+/// Each of `moves` scored for `faction` by the scorer of the current phase.
+fn scored(
+    model: &GameModel,
+    faction: GameFaction,
+    moves: &[ModelMove],
+    weights: &Weights,
+) -> Vec<(usize, f32)> {
+    let areas = if model.activity == GameActivity::AcquireCivilizationCards {
+        HashMap::default()
+    } else {
+        summaries(model, faction)
+    };
+    moves
+        .iter()
+        .enumerate()
+        .map(|(i, mv)| {
+            let game_move = game_move(faction, mv);
+            let score = match (&model.activity, &game_move) {
+                (GameActivity::PopulationExpansion, _) => {
+                    explain_population_expansion(&game_move, &areas, weights)
+                }
+                (GameActivity::Movement, _) => {
+                    explain_movement(&game_move, seat_entity(faction), &areas, weights)
+                }
+                (GameActivity::CityConstruction, _) => {
+                    explain_city_construction(&game_move, &areas, weights)
+                }
+                (_, GameMove::AcquireCivilizationCards(civ_move)) => {
+                    let option = match civ_move {
+                        AcquireCivilizationCardsMove::AcquireCard(card) => {
+                            civ_card_option(model, faction, *card)
+                        }
+                        _ => None,
+                    };
+                    explain_civ_card(civ_move, option, weights)
+                }
+                _ => explain_city_elimination(&game_move, &areas, weights),
+            };
+            (i, score.total())
+        })
+        .collect()
+}
+
+/// The move a rival's greedy utility AI makes; the first on a tie.
+fn greedy_move(
+    model: &GameModel,
+    faction: GameFaction,
+    moves: &[ModelMove],
+    weights: &Weights,
+) -> Option<ModelMove> {
+    scored(model, faction, moves, weights)
+        .into_iter()
+        .filter(|(_, s)| s.is_finite())
+        .fold(None, |best: Option<(usize, f32)>, (i, s)| match best {
+            Some((_, top)) if top >= s => best,
+            _ => Some((i, s)),
+        })
+        .map(|(i, _)| moves[i])
+}
+
+/// Plays every rival decision until it is the searcher's turn again or the
+/// game is over.
+fn play_rivals(model: &mut GameModel) {
+    let Some(searcher) = model.searcher.clone() else {
+        return;
+    };
+    let mut turn = None;
+    let mut moves_this_turn = 0;
+    while let Some(rival) = model.to_move()
+        && rival != searcher.me
+    {
+        let moves = GameModel::legal_moves(model);
+        if model.activity == GameActivity::Movement {
+            if turn != Some(rival) {
+                turn = Some(rival);
+                moves_this_turn = 0;
+            }
+            moves_this_turn += 1;
+        }
+        let mv = if moves_this_turn > MOVES_PER_TURN && moves.contains(&ModelMove::EndMovement) {
+            Some(ModelMove::EndMovement)
+        } else {
+            greedy_move(model, rival, &moves, &searcher.mind(rival).0)
+        };
+        let Some(mv) = mv.or_else(|| moves.first().copied()) else {
+            return;
+        };
+        if let Err(e) = GameModel::apply(model, mv) {
+            debug!("Search model stopped on a rival move: {e}");
+            return;
+        }
+    }
+}
+
+/// How well `faction` stands, by the position evaluation.
+fn evaluate(model: &GameModel, faction: GameFaction, defs: &AvailableCivCards) -> f32 {
+    let Some(player) = model.player(faction) else {
+        return 0.0;
+    };
+    let owned: Vec<CivCardName> = player.civ_cards.iter().copied().collect();
+    let position = PlayerPosition {
+        faction,
+        ast_space: player.ast_space,
+        cities: model.cities_of(faction).len(),
+        population: model.population(faction),
+        treasury: player.treasury,
+        trade_cards: player.hand_as_vec(),
+        ..Default::default()
+    }
+    .with_civ_cards(&owned, Some(defs));
+    explain_position(&position).total()
+}
+
+impl ForwardModel for GameModel {
+    type Move = ModelMove;
+
+    fn legal_moves(&self) -> Vec<ModelMove> {
+        match &self.searcher {
+            Some(searcher) if self.to_move() == Some(searcher.me) => GameModel::legal_moves(self),
+            _ => Vec::new(),
+        }
+    }
+
+    fn apply(&mut self, mv: &ModelMove) {
+        if let Err(e) = GameModel::apply(self, *mv) {
+            debug!("Search model rejected {mv}: {e}");
+            return;
+        }
+        play_rivals(self);
+    }
+
+    fn rollout_pick<R: Rng>(&self, moves: &[ModelMove], rng: &mut R) -> usize {
+        let Some(searcher) = &self.searcher else {
+            return 0;
+        };
+        let (weights, picker) = searcher.mind(searcher.me);
+        pick(&scored(self, searcher.me, moves, &weights), picker, rng).unwrap_or(0)
+    }
+
+    /// My evaluation over the best rival's.
+    fn reward(&self) -> f32 {
+        let Some(searcher) = &self.searcher else {
+            return 0.0;
+        };
+        let best_rival = self
+            .players
+            .iter()
+            .filter(|p| p.faction != searcher.me)
+            .map(|p| evaluate(self, p.faction, &searcher.defs))
+            .fold(None, |best: Option<f32>, v| {
+                Some(best.map_or(v, |b| b.max(v)))
+            });
+        evaluate(self, searcher.me, &searcher.defs) - best_rival.unwrap_or(0.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::civilization::{AstTrack, ModelArea, ModelPlayer, ModelRules};
+    use crate::stupid_ai::{MctsConfig, best_move, search};
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    fn player(faction: GameFaction) -> ModelPlayer {
+        ModelPlayer {
+            faction,
+            name: format!("{faction:?}"),
+            is_human: false,
+            stock: 40,
+            city_stock: 9,
+            treasury: 0,
+            hand: HashMap::default(),
+            calamity_traded_by: Vec::new(),
+            civ_cards: Default::default(),
+            ast_space: 0,
+            census_population: 0,
+        }
+    }
+
+    /// A line of areas `1 - 2 - 3`; `1` is a city site.
+    fn line(me: GameFaction, rival: GameFaction) -> GameModel {
+        let areas = (1..=3).map(|id| ModelArea {
+            id,
+            max_population: 3,
+            land: [id - 1, id + 1]
+                .into_iter()
+                .filter(|a| (1..=3).contains(a))
+                .collect(),
+            sea: Vec::new(),
+            city_site: id == 1,
+            flood_plain: false,
+            city_flood: false,
+            volcano: false,
+            start_area: None,
+        });
+        let rules = Arc::new(ModelRules::new(areas, None, AstTrack::standard()));
+        let mut model = GameModel::new(rules, vec![player(me), player(rival)], 1);
+        model.searcher = Some(Arc::new(ModelSearcher {
+            me,
+            minds: HashMap::default(),
+            defs: AvailableCivCards::default(),
+        }));
+        model
+    }
+
+    #[test]
+    fn the_search_builds_a_supported_city() {
+        let (me, rival) = (GameFaction::Egypt, GameFaction::Crete);
+        let mut model = line(me, rival);
+        model.areas.get_mut(&1).unwrap().add(me, 6);
+        model.areas.get_mut(&3).unwrap().add(me, 3);
+        model.piles.insert(1, vec![TradeCard::Ochre; 5]);
+        model.activity = GameActivity::CityConstruction;
+        model.pending = vec![me];
+        model.round_limit = Some(model.round);
+        let root = ForwardModel::legal_moves(&model);
+        assert_eq!(
+            root,
+            vec![
+                ModelMove::CityConstruction { area: 1 },
+                ModelMove::EndCityConstruction
+            ]
+        );
+
+        let config = MctsConfig {
+            time_budget: Duration::from_secs(60),
+            max_iterations: 20,
+            ..Default::default()
+        };
+        let mut rng = StdRng::seed_from_u64(3);
+        let stats = search(&root, |_: &mut StdRng| model.clone(), &config, &mut rng);
+        assert_eq!(best_move(&stats), Some(0));
+    }
+
+    #[test]
+    fn a_determinized_hand_keeps_its_size_and_the_calamity_i_passed_on() {
+        let (me, rival) = (GameFaction::Egypt, GameFaction::Crete);
+        let model = line(me, rival);
+        let mut unseen = vec![TradeCard::Ochre; 6];
+        unseen.extend([TradeCard::BarbarianHordes, TradeCard::Salt, TradeCard::Salt]);
+        let knowledge = HandKnowledge {
+            unseen: unseen.clone(),
+            hand_sizes: vec![(rival, 3)],
+            known: vec![(rival, TradeCard::BarbarianHordes)],
+        };
+
+        let mut rng = StdRng::seed_from_u64(5);
+        for _ in 0..10 {
+            let dealt = determinize(&model, &knowledge, &mut rng);
+            let holder = dealt.player(rival).unwrap();
+            assert_eq!(holder.number_of_trade_cards(), 3);
+            assert_eq!(holder.hand.get(&TradeCard::BarbarianHordes), Some(&1));
+            assert!(
+                holder
+                    .calamity_traded_by
+                    .contains(&(TradeCard::BarbarianHordes, me))
+            );
+            let piled: usize = dealt.piles.values().map(Vec::len).sum();
+            assert_eq!(piled + 3, unseen.len());
+            assert!(
+                dealt
+                    .piles
+                    .iter()
+                    .all(|(pile, cards)| cards.iter().all(|c| c.value() == *pile))
+            );
+        }
+    }
+}
@@ -0,0 +1,209 @@
+use crate::GameActivity;
+use crate::civilization::{
+    AvailableCivCards, CivilizationTradeCards, Faction, GameArea, GameModel, GameModelSource,
+    GameMove, ModelMove, ModelMovement, NeedsExpansion, PlayerAreas, PlayerTradeCards,
+    TokenHasMoved, TradeCardTrait,
+};
+use crate::stupid_ai::{
+    HandKnowledge, MctsAi, ModelSearcher, Personality, Picker, ScoreBreakdown, Weights,
+    determinize, search, tokens_sent,
+};
+use bevy::ecs::system::SystemParam;
+use bevy::platform::collections::HashMap;
+use bevy::prelude::{Entity, Query, Res, With, debug};
+use rand::Rng;
+use std::sync::Arc;
+
+/// Lets the board-move systems hand an `MctsAi` player's decision to a
+/// tree search over a [`GameModel`] captured from the live world.
+#[derive(SystemParam)]
+pub struct MctsSearch<'w, 's> {
+    searchers: Query<'w, 's, &'static MctsAi>,
+    source: GameModelSource<'w, 's>,
+    areas: Query<'w, 's, (Entity, &'static GameArea)>,
+    players: Query<
+        'w,
+        's,
+        (
+            Entity,
+            &'static Faction,
+            &'static PlayerTradeCards,
+            &'static PlayerAreas,
+            Option<&'static Personality>,
+            Option<&'static NeedsExpansion>,
+        ),
+    >,
+    moved_tokens: Query<'w, 's, (), With<TokenHasMoved>>,
+    trade_cards: Res<'w, CivilizationTradeCards>,
+    civ_defs: Option<Res<'w, AvailableCivCards>>,
+}
+
+impl MctsSearch<'_, '_> {
+    pub fn plays(&self, player: Entity) -> bool {
+        self.searchers.contains(player)
+    }
+
+    /// Every move in `moves` scored by its mean search reward, or `None` if
+    /// `player` does not search. Moves the model cannot play are left out.
+    pub fn explain<R: Rng>(
+        &self,
+        player: Entity,
+        moves: &HashMap<usize, GameMove>,
+        rng: &mut R,
+    ) -> Option<Vec<(usize, ScoreBreakdown)>> {
+        let searcher = self.searchers.get(player).ok()?;
+        let (model, knowledge) = self.capture(player)?;
+        let ids = self.area_ids();
+        let legal = model.legal_moves();
+        let mut keyed: Vec<(usize, ModelMove)> = moves
+            .iter()
+            .filter_map(|(i, m)| model_move(m, &ids, &legal).map(|mv| (*i, mv)))
+            .collect();
+        if keyed.is_empty() {
+            return None;
+        }
+        keyed.sort_by_key(|(i, _)| *i);
+        let root: Vec<ModelMove> = keyed.iter().map(|(_, m)| *m).collect();
+        let stats = search(
+            &root,
+            |rng: &mut R| determinize(&model, &knowledge, rng),
+            &searcher.config,
+            rng,
+        );
+        debug!(
+            "MCTS for {:?} in {:?}: {} iterations",
+            player,
+            model.activity,
+            stats.iter().map(|s| s.visits).sum::<u32>()
+        );
+        Some(
+            keyed
+                .iter()
+                .zip(stats)
+                .map(|((i, _), s)| (*i, ScoreBreakdown::fixed("mcts", s.mean)))
+                .collect(),
+        )
+    }
+
+    fn area_ids(&self) -> HashMap<Entity, i32> {
+        self.areas
+            .iter()
+            .map(|(entity, area)| (entity, area.id))
+            .collect()
+    }
+
+    /// The game as `me` sees it now, searched to the end of the round, and
+    /// what `me` knows of the cards it cannot see.
+    fn capture(&self, me: Entity) -> Option<(GameModel, HandKnowledge)> {
+        let mut model = self.source.capture(0).ok()?;
+        let ids = self.area_ids();
+        let (_, my_faction, ..) = self.players.get(me).ok()?;
+        let my_faction = my_faction.faction;
+
+        let mut piles: Vec<_> = self.trade_cards.card_piles.iter().collect();
+        piles.sort_by_key(|(pile, _)| **pile);
+        let mut knowledge = HandKnowledge {
+            unseen: piles
+                .into_iter()
+                .flat_map(|(_, cards)| cards.iter().copied())
+                .collect(),
+            ..Default::default()
+        };
+        let mut minds = HashMap::default();
+        for (player, faction, hand, player_areas, personality, expansion) in &self.players {
+            let faction = faction.faction;
+            minds.insert(
+                faction,
+                (
+                    personality.map_or(Weights::uniform(0.5), |p| p.weights),
+                    personality.map_or(Picker::Greedy, |p| p.picker),
+                ),
+            );
+            if player == me {
+                if model.activity == GameActivity::Movement {
+                    model.moved.clear();
+                    for (area, tokens) in player_areas.areas_and_population() {
+                        let count = tokens
+                            .iter()
+                            .filter(|t| self.moved_tokens.contains(**t))
+                            .count();
+                        if count > 0
+                            && let Some(id) = ids.get(&area)
+                        {
+                            model.moved.insert(*id, count);
+                        }
+                    }
+                }
+            } else {
+                for (card, count) in hand.cards_as_vec() {
+                    knowledge.unseen.extend(std::iter::repeat_n(card, count));
+                }
+                knowledge
+                    .hand_sizes
+                    .push((faction, hand.number_of_trade_cards()));
+                let mut passed_on: Vec<_> = hand
+                    .calamity_origins_as_vec()
+                    .into_iter()
+                    .filter(|(_, from)| *from == me)
+                    .map(|(card, _)| (faction, card))
+                    .collect();
+                passed_on.sort_by_key(|(_, card)| (card.value(), card.to_string()));
+                knowledge.known.extend(passed_on);
+            }
+            if let Some(expansion) = expansion {
+                let mut left: Vec<i32> = expansion
+                    .areas_that_need_expansion
+                    .iter()
+                    .filter_map(|a| ids.get(a).copied())
+                    .collect();
+                left.sort();
+                model.to_expand.insert(faction, left);
+            }
+        }
+        knowledge.hand_sizes.sort_by_key(|(f, _)| *f as u8);
+        if model.to_move() != Some(my_faction) {
+            return None;
+        }
+
+        model.round_limit = Some(model.round);
+        model.searcher = Some(Arc::new(ModelSearcher {
+            me: my_faction,
+            minds,
+            defs: self.civ_defs.as_deref().cloned().unwrap_or_default(),
+        }));
+        Some((model, knowledge))
+    }
+}
+
+/// `mv` as the model plays it, if that is one of `legal`. Movement sends
+/// what the utility AI would send; ferries have no model move.
+fn model_move(mv: &GameMove, ids: &HashMap<Entity, i32>, legal: &[ModelMove]) -> Option<ModelMove> {
+    let candidates = match mv {
+        GameMove::PopulationExpansion(m) => vec![ModelMove::PopulationExpansion {
+            area: *ids.get(&m.area)?,
+            tokens: m.max_tokens,
+        }],
+        GameMove::Movement(m) | GameMove::AttackArea(m) | GameMove::AttackCity(m) => {
+            let movement = ModelMovement {
+                from: *ids.get(&m.source)?,
+                to: *ids.get(&m.target)?,
+                tokens: tokens_sent(mv).min(m.max_tokens),
+            };
+            vec![
+                ModelMove::Movement(movement),
+                ModelMove::AttackArea(movement),
+                ModelMove::AttackCity(movement),
+            ]
+        }
+        GameMove::EndMovement => vec![ModelMove::EndMovement],
+        GameMove::CityConstruction(m) => vec![ModelMove::CityConstruction {
+            area: *ids.get(&m.target)?,
+        }],
+        GameMove::EndCityConstruction => vec![ModelMove::EndCityConstruction],
+        GameMove::ShipFerry(_)
+        | GameMove::EliminateCity(_)
+        | GameMove::Trade(_)
+        | GameMove::AcquireCivilizationCards(_) => Vec::new(),
+    };
+    candidates.into_iter().find(|c| legal.contains(c))
+}
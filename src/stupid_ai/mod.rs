@@ -1,15 +1,33 @@
+mod calamity_choices;
+mod decider;
+mod decision_trace;
+mod decision_trace_ui_plugin;
+mod mcts;
+mod mcts_model;
+mod mcts_search;
 mod personality;
+mod personality_definitions;
 mod scoring;
 mod stupid_ai_components;
 mod stupid_ai_events;
 mod stupid_ai_plugin;
 mod stupid_ai_systems;
 mod stupid_ai_triggers;
+mod trade_trust;
 
+pub use calamity_choices::*;
+pub use decider::*;
+pub use decision_trace::*;
+pub use decision_trace_ui_plugin::*;
+pub use mcts::*;
+pub use mcts_model::*;
+pub use mcts_search::*;
 pub use personality::*;
+pub use personality_definitions::*;
 pub use scoring::*;
 pub use stupid_ai_components::*;
 pub use stupid_ai_events::*;
 pub use stupid_ai_plugin::*;
 pub use stupid_ai_systems::*;
 pub use stupid_ai_triggers::*;
+pub use trade_trust::*;
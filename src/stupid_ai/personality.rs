@@ -1,7 +1,10 @@
 use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
 
-/// A named archetype that fills in a [`Weights`] preset. Carried for display and
-/// logging; the actual behaviour comes entirely from the weights it expands to.
+/// The built-in archetypes. The playable personalities are data
+/// (`assets/definitions/*.personalities.ron`, see [`Personalities`]); these
+/// are the code copies of the shipped ones, used when no definitions are
+/// loaded and by tests.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Reflect)]
 pub enum Playstyle {
     /// The reasonable default — every knob around the middle.
@@ -45,7 +48,7 @@ impl Playstyle {
 /// The tunable knobs the scoring functions read. All in roughly `[0, 1]`, where
 /// higher = "I care more about this". Hand-tuned for now; later these are exactly
 /// what reinforcement learning could optimise (see `docs/reinforcement-learning.md`).
-#[derive(Clone, Copy, Debug, Reflect)]
+#[derive(Clone, Copy, Debug, PartialEq, Reflect, Serialize, Deserialize)]
 pub struct Weights {
     // expansion / economy
     /// Value of feeding population and expanding.
@@ -84,10 +87,69 @@ impl Weights {
             risk: v,
         }
     }
+
+    /// Field names, for overriding single knobs by name (CLI presets, tuning).
+    pub const KNOBS: [&'static str; 9] = [
+        "growth",
+        "city_income",
+        "expansion",
+        "aggression",
+        "defense",
+        "trade_drive",
+        "calamity_aversion",
+        "tech_focus",
+        "risk",
+    ];
+
+    /// The knob called `name` (one of [`Weights::KNOBS`]).
+    pub fn knob_mut(&mut self, name: &str) -> Option<&mut f32> {
+        match name {
+            "growth" => Some(&mut self.growth),
+            "city_income" => Some(&mut self.city_income),
+            "expansion" => Some(&mut self.expansion),
+            "aggression" => Some(&mut self.aggression),
+            "defense" => Some(&mut self.defense),
+            "trade_drive" => Some(&mut self.trade_drive),
+            "calamity_aversion" => Some(&mut self.calamity_aversion),
+            "tech_focus" => Some(&mut self.tech_focus),
+            "risk" => Some(&mut self.risk),
+            _ => None,
+        }
+    }
+
+    /// The knobs as a vector, in [`Weights::KNOBS`] order.
+    pub fn to_array(&self) -> [f32; 9] {
+        [
+            self.growth,
+            self.city_income,
+            self.expansion,
+            self.aggression,
+            self.defense,
+            self.trade_drive,
+            self.calamity_aversion,
+            self.tech_focus,
+            self.risk,
+        ]
+    }
+
+    /// Inverse of [`Weights::to_array`].
+    pub const fn from_array(knobs: [f32; 9]) -> Self {
+        Weights {
+            growth: knobs[0],
+            city_income: knobs[1],
+            expansion: knobs[2],
+            aggression: knobs[3],
+            defense: knobs[4],
+            trade_drive: knobs[5],
+            calamity_aversion: knobs[6],
+            tech_focus: knobs[7],
+            risk: knobs[8],
+        }
+    }
 }
 
 /// How the highest-utility move is chosen from the scored list.
-#[derive(Clone, Copy, Debug, Reflect)]
+#[derive(Clone, Copy, Debug, PartialEq, Reflect, Serialize, Deserialize)]
 pub enum Picker {
     /// Always take the highest score (ties broken randomly).
     Greedy,
@@ -96,16 +158,36 @@ pub enum Picker {
     Softmax { temperature: f32 },
 }
 
+/// Trade-phase habits. Each knob left `None` is derived from the weights
+/// (`trade_drive`, `calamity_aversion`); see `scoring/trade.rs`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Reflect, Serialize, Deserialize)]
+pub struct TradeKnobs {
+    /// Stack-value gain beyond break-even demanded before accepting an offer.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub accept_margin: Option<f32>,
+    /// Take a trade purely to offload a calamity card.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub offload_calamities: Option<bool>,
+    /// Chance per tick of putting up a fresh offer.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub offer_chance: Option<f32>,
+    /// Trade countdown (from 90) below which to stop trading.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stop_trading_at: Option<f32>,
+}
+
 #[derive(Component, Clone, Debug, Reflect)]
 #[reflect(Component)]
 pub struct Personality {
-    pub playstyle: Playstyle,
+    /// The definition's name, for display and logging.
+    pub name: String,
     pub picker: Picker,
     pub weights: Weights,
+    pub trade: TradeKnobs,
 }
 
 impl Personality {
-    /// Build a personality from a named archetype.
+    /// Build a personality from a built-in archetype.
     pub fn from_playstyle(playstyle: Playstyle) -> Self {
         let (weights, picker) = match playstyle {
             Playstyle::Balanced => (Weights::uniform(0.5), Picker::Softmax { temperature: 0.35 }),
@@ -181,9 +263,10 @@ impl Personality {
             ),
         };
         Personality {
-            playstyle,
+            name: format!("{playstyle:?}"),
             picker,
             weights,
+            trade: TradeKnobs::default(),
         }
     }
 }
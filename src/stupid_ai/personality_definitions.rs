@@ -0,0 +1,327 @@
+use crate::stupid_ai::{Personality, Picker, Playstyle, TradeKnobs, Weights};
+use bevy::asset::{LoadedFolder, RecursiveDependencyLoadState};
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// Asset folder scanned for `*.personalities.ron` files.
+pub const PERSONALITIES_DIR: &str = "definitions";
+/// The shipped archetypes. The web build cannot list a folder, so this is
+/// the one file it loads.
+pub const ARCHETYPES_FILE: &str = "definitions/archetypes.personalities.ron";
+
+/// One AI personality as designers write it.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct PersonalityDefinition {
+    /// Unique (case-insensitively) across all files; what `force_playstyle`
+    /// and the line-up select by.
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub weights: Weights,
+    /// Softmax temperature for picking among scored moves; `None` always
+    /// takes the best one.
+    #[serde(default)]
+    pub temperature: Option<f32>,
+    #[serde(default)]
+    pub trade: TradeKnobs,
+    /// Whether new games deal this personality out when no line-up is
+    /// configured. Off for experiments that should only play when asked.
+    #[serde(default = "in_lineup_by_default")]
+    pub in_lineup: bool,
+}
+
+fn in_lineup_by_default() -> bool {
+    true
+}
+
+impl PersonalityDefinition {
+    pub fn personality(&self) -> Personality {
+        Personality {
+            name: self.name.clone(),
+            picker: self
+                .temperature
+                .map_or(Picker::Greedy, |temperature| Picker::Softmax {
+                    temperature,
+                }),
+            weights: self.weights,
+            trade: self.trade,
+        }
+    }
+
+    /// Inverse of [`PersonalityDefinition::personality`], e.g. for tools
+    /// that write definitions.
+    pub fn from_personality(personality: &Personality) -> Self {
+        PersonalityDefinition {
+            name: personality.name.clone(),
+            description: None,
+            weights: personality.weights,
+            temperature: match personality.picker {
+                Picker::Greedy => None,
+                Picker::Softmax { temperature } => Some(temperature),
+            },
+            trade: personality.trade,
+            in_lineup: true,
+        }
+    }
+
+    /// The definition a built-in archetype corresponds to.
+    pub fn from_playstyle(playstyle: Playstyle) -> Self {
+        Self::from_personality(&Personality::from_playstyle(playstyle))
+    }
+}
+
+/// The contents of one `*.personalities.ron` file.
+#[derive(Asset, TypePath, Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct PersonalityDefinitions {
+    pub personalities: Vec<PersonalityDefinition>,
+}
+
+impl PersonalityDefinitions {
+    pub fn to_ron(&self) -> Result<String, String> {
+        ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())
+            .map_err(|e| e.to_string())
+    }
+}
+
+/// Every personality the AI can be given, from all loaded files in path
+/// order. Without any files it holds the built-in archetypes.
+#[derive(Resource, Debug, Clone)]
+pub struct Personalities {
+    pub definitions: Vec<PersonalityDefinition>,
+}
+
+impl Default for Personalities {
+    fn default() -> Self {
+        Personalities {
+            definitions: Playstyle::ALL
+                .iter()
+                .map(|&p| PersonalityDefinition::from_playstyle(p))
+                .collect(),
+        }
+    }
+}
+
+impl Personalities {
+    /// Merge files in order; a name already taken keeps its first definition.
+    pub fn from_files<'a>(files: impl IntoIterator<Item = &'a PersonalityDefinitions>) -> Self {
+        let mut definitions: Vec<PersonalityDefinition> = Vec::new();
+        for definition in files.into_iter().flat_map(|f| &f.personalities) {
+            if definitions
+                .iter()
+                .any(|d| d.name.eq_ignore_ascii_case(&definition.name))
+            {
+                warn!(
+                    "[AI] Personality \"{}\" is defined twice, keeping the first",
+                    definition.name
+                );
+                continue;
+            }
+            definitions.push(definition.clone());
+        }
+        Personalities { definitions }
+    }
+
+    /// Read every `*.personalities.ron` in `dir` directly, for tools that run
+    /// without an asset server.
+    pub fn load_dir(dir: impl AsRef<Path>) -> Result<Self, String> {
+        let dir = dir.as_ref();
+        let mut paths: Vec<_> = std::fs::read_dir(dir)
+            .map_err(|e| format!("Cannot list {}: {e}", dir.display()))?
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|path| {
+                path.file_name()
+                    .and_then(|n| n.to_str())
+                    .is_some_and(|n| n.ends_with(".personalities.ron"))
+            })
+            .collect();
+        paths.sort();
+        let files = paths
+            .iter()
+            .map(|path| {
+                let text = std::fs::read_to_string(path)
+                    .map_err(|e| format!("Cannot read {}: {e}", path.display()))?;
+                ron::from_str::<PersonalityDefinitions>(&text)
+                    .map_err(|e| format!("Cannot parse {}: {e}", path.display()))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Self::from_files(&files))
+    }
+
+    /// Look a personality up by name, ignoring case. The archetypes' aliases
+    /// (`aggressive`, `trader`, ...) work too.
+    pub fn get(&self, name: &str) -> Option<Personality> {
+        let name = name.trim();
+        let canonical = Playstyle::from_name(name).map(|p| format!("{p:?}"));
+        self.definitions
+            .iter()
+            .find(|d| d.name.eq_ignore_ascii_case(name))
+            .or_else(|| {
+                let canonical = canonical?;
+                self.definitions.iter().find(|d| d.name == canonical)
+            })
+            .map(PersonalityDefinition::personality)
+    }
+
+    /// What new games deal out, in seat order, when no line-up is set.
+    pub fn lineup(&self) -> Vec<Personality> {
+        let lineup: Vec<Personality> = self
+            .definitions
+            .iter()
+            .filter(|d| d.in_lineup)
+            .map(PersonalityDefinition::personality)
+            .collect();
+        if lineup.is_empty() {
+            Personalities::default().lineup()
+        } else {
+            lineup
+        }
+    }
+
+    pub fn names(&self) -> Vec<&str> {
+        self.definitions.iter().map(|d| d.name.as_str()).collect()
+    }
+}
+
+/// What is being loaded: the definitions folder, or on the web the one file.
+#[derive(Resource)]
+pub enum PersonalitySources {
+    Folder(Handle<LoadedFolder>),
+    File(Handle<PersonalityDefinitions>),
+}
+
+impl PersonalitySources {
+    /// Done loading, successfully or not; a broken file is skipped, not
+    /// waited on.
+    pub fn is_settled(&self, asset_server: &AssetServer) -> bool {
+        let state = match self {
+            PersonalitySources::Folder(handle) => {
+                asset_server.recursive_dependency_load_state(handle)
+            }
+            PersonalitySources::File(handle) => {
+                asset_server.recursive_dependency_load_state(handle)
+            }
+        };
+        matches!(
+            state,
+            RecursiveDependencyLoadState::Loaded | RecursiveDependencyLoadState::Failed(_)
+        )
+    }
+}
+
+pub fn load_personalities(mut commands: Commands, asset_server: Res<AssetServer>) {
+    #[cfg(not(target_arch = "wasm32"))]
+    let sources = PersonalitySources::Folder(asset_server.load_folder(PERSONALITIES_DIR));
+    #[cfg(target_arch = "wasm32")]
+    let sources = PersonalitySources::File(asset_server.load(ARCHETYPES_FILE));
+    commands.insert_resource(sources);
+}
+
+/// Build [`Personalities`] from whatever definition files have loaded, for
+/// `setup_players` to deal out.
+pub fn init_personalities(
+    mut commands: Commands,
+    sources: Option<Res<PersonalitySources>>,
+    folders: Res<Assets<LoadedFolder>>,
+    files: Res<Assets<PersonalityDefinitions>>,
+    asset_server: Res<AssetServer>,
+) {
+    let handles: Vec<Handle<PersonalityDefinitions>> = match sources.as_deref() {
+        Some(PersonalitySources::Folder(folder)) => folders
+            .get(folder)
+            .map(|folder| {
+                folder
+                    .handles
+                    .iter()
+                    .filter_map(|h| h.clone().try_typed::<PersonalityDefinitions>().ok())
+                    .collect()
+            })
+            .unwrap_or_default(),
+        Some(PersonalitySources::File(file)) => vec![file.clone()],
+        None => Vec::new(),
+    };
+
+    let mut loaded: Vec<(String, &PersonalityDefinitions)> = handles
+        .iter()
+        .filter_map(|h| {
+            let path = asset_server.get_path(h.id())?.to_string();
+            Some((path, files.get(h)?))
+        })
+        .collect();
+    loaded.sort_by(|a, b| a.0.cmp(&b.0));
+
+    let personalities = if loaded.is_empty() {
+        warn!("[AI] No personality definitions loaded, using the built-in archetypes");
+        Personalities::default()
+    } else {
+        Personalities::from_files(loaded.iter().map(|(_, file)| *file))
+    };
+    info!("[AI] Personalities: {}", personalities.names().join(", "));
+    commands.insert_resource(personalities);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ARCHETYPES_RON: &str =
+        include_str!("../../assets/definitions/archetypes.personalities.ron");
+
+    #[test]
+    fn the_shipped_archetypes_match_the_built_in_ones() {
+        let file: PersonalityDefinitions = ron::from_str(ARCHETYPES_RON).unwrap();
+        let mut shipped = Personalities::from_files([&file]).definitions;
+        for definition in &mut shipped {
+            assert!(definition.description.is_some(), "{}", definition.name);
+            definition.description = None;
+        }
+        assert_eq!(shipped, Personalities::default().definitions);
+    }
+
+    #[test]
+    fn definitions_round_trip_through_ron() {
+        let mut definition = PersonalityDefinition::from_playstyle(Playstyle::Merchant);
+        definition.description = Some("haggles".to_string());
+        definition.trade.offer_chance = Some(1.0);
+        definition.in_lineup = false;
+        let file = PersonalityDefinitions {
+            personalities: vec![definition],
+        };
+        let ron = file.to_ron().unwrap();
+        assert_eq!(ron::from_str::<PersonalityDefinitions>(&ron).unwrap(), file);
+    }
+
+    #[test]
+    fn lookup_ignores_case_and_knows_the_aliases() {
+        let mut custom = PersonalityDefinition::from_playstyle(Playstyle::Turtle);
+        custom.name = "Hermit".to_string();
+        custom.temperature = None;
+        custom.in_lineup = false;
+        let mut personalities = Personalities::default();
+        personalities.definitions.push(custom);
+
+        assert_eq!(personalities.get("hermit").unwrap().name, "Hermit");
+        assert_eq!(personalities.get("HERMIT").unwrap().picker, Picker::Greedy);
+        assert_eq!(personalities.get("aggressive").unwrap().name, "Warlord");
+        assert!(personalities.get("pacifist").is_none());
+        assert_eq!(personalities.lineup().len(), Playstyle::ALL.len());
+    }
+
+    #[test]
+    fn a_name_defined_twice_keeps_the_first() {
+        let first = PersonalityDefinitions {
+            personalities: vec![PersonalityDefinition::from_playstyle(Playstyle::Builder)],
+        };
+        let mut clash = PersonalityDefinition::from_playstyle(Playstyle::Warlord);
+        clash.name = "builder".to_string();
+        let second = PersonalityDefinitions {
+            personalities: vec![clash],
+        };
+        let merged = Personalities::from_files([&first, &second]);
+        assert_eq!(merged.definitions.len(), 1);
+        assert_eq!(
+            merged.get("Builder").unwrap().weights,
+            Personality::from_playstyle(Playstyle::Builder).weights
+        );
+    }
+}
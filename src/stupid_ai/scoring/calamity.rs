@@ -0,0 +1,300 @@
+//! Choices a calamity hands to a player: whom secondary losses fall on
+//! (Flood 30.512, Famine 30.311, Epidemic 30.611), where tied Barbarians go
+//! (30.525), which tokens Monotheism takes, and which Civil War faction to
+//! keep (30.415). Opponents ahead on the A.S.T. make good victims, trusted
+//! trade partners are spared, and one's own cities are guarded while the
+//! next epoch gate still needs them.
+
+use super::{ScoreBreakdown, saturating};
+use crate::civilization::AstEpoch;
+use crate::civilization::concepts::resolve_calamities::calamities::civil_war::FactionChoice;
+use crate::stupid_ai::{NEUTRAL_TRUST, Weights};
+use bevy::platform::collections::HashMap;
+use bevy::prelude::Entity;
+
+/// Points a city is worth when a calamity counts unit points (rule 29.62).
+const CITY_POINTS: usize = 5;
+/// What passing the next epoch gate on cities is worth, in unit points.
+const GATE_POINTS: f32 = 10.0;
+
+/// What a chooser knows about one player.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PlayerStanding {
+    pub ast_space: u32,
+    pub cities: usize,
+    /// The chooser's trade trust in this player.
+    pub trust: f32,
+}
+
+impl Default for PlayerStanding {
+    fn default() -> Self {
+        PlayerStanding {
+            ast_space: 0,
+            cities: 0,
+            trust: NEUTRAL_TRUST,
+        }
+    }
+}
+
+/// One AI player's view of the table when a calamity asks it to choose.
+#[derive(Clone, Debug)]
+pub struct CalamityChooser {
+    pub me: Entity,
+    pub standings: HashMap<Entity, PlayerStanding>,
+    pub weights: Weights,
+}
+
+impl CalamityChooser {
+    fn standing(&self, player: Entity) -> PlayerStanding {
+        self.standings.get(&player).copied().unwrap_or_default()
+    }
+
+    /// How much the chooser wants `victim` to take the hit, consideration by
+    /// consideration.
+    pub fn explain_victim(&self, victim: Entity) -> ScoreBreakdown {
+        let w = &self.weights;
+        let mine = self.standing(self.me);
+        let theirs = self.standing(victim);
+        let mut score = ScoreBreakdown::fixed("baseline", 0.5);
+        let lead = theirs.ast_space as f32 - mine.ast_space as f32;
+        score.add(
+            "leader",
+            (0.3 + 0.5 * w.aggression) * (lead / 3.0).clamp(-1.0, 1.0),
+        );
+        score.add(
+            "cities",
+            0.3 * w.aggression * saturating(theirs.cities as f32, 3.0),
+        );
+        // A partner who deals straight is worth keeping strong; a cheat is not.
+        score.add(
+            "trade_partner",
+            -2.0 * w.trade_drive * (theirs.trust - NEUTRAL_TRUST),
+        );
+        score
+    }
+
+    pub fn victim_score(&self, victim: Entity) -> f32 {
+        self.explain_victim(victim).total()
+    }
+
+    /// `victims`, most wanted first; equals keep their given order.
+    pub fn rank_victims(&self, victims: &[Entity]) -> Vec<Entity> {
+        let mut scored: Vec<(Entity, f32)> = victims
+            .iter()
+            .map(|&victim| (victim, self.victim_score(victim)))
+            .collect();
+        scored.sort_by(|a, b| b.1.total_cmp(&a.1));
+        scored.into_iter().map(|(victim, _)| victim).collect()
+    }
+
+    /// Divide a secondary loss of `total` among `(victim, available)`: the
+    /// most wanted victim loses all it can before the next loses anything.
+    /// The result is the primary victim's choice for `allocate_secondary_loss`.
+    pub fn divide_loss(&self, secondary: &[(Entity, usize)], total: usize) -> Vec<(Entity, usize)> {
+        let victims: Vec<Entity> = secondary.iter().map(|(victim, _)| *victim).collect();
+        let mut remaining = total;
+        self.rank_victims(&victims)
+            .into_iter()
+            .map(|victim| {
+                let available = secondary
+                    .iter()
+                    .find(|(v, _)| *v == victim)
+                    .map_or(0, |(_, available)| *available);
+                let take = available.min(remaining);
+                remaining -= take;
+                (victim, take)
+            })
+            .collect()
+    }
+
+    /// Barbarian tie-break (30.525): of the `(area, [(owner, unit points)])`
+    /// candidates, the one whose occupants the chooser most wants hit. Its
+    /// own units there count against the area. Ties keep the first.
+    pub fn pick_area(&self, tied: &[(Entity, Vec<(Entity, usize)>)]) -> Option<Entity> {
+        let own_loss = -(0.5 + self.weights.defense);
+        let mut best: Option<(Entity, f32)> = None;
+        for (area, occupants) in tied {
+            let value: f32 = occupants
+                .iter()
+                .map(|&(owner, points)| {
+                    let per_point = if owner == self.me {
+                        own_loss
+                    } else {
+                        self.victim_score(owner)
+                    };
+                    points as f32 * per_point
+                })
+                .sum();
+            if best.is_none_or(|(_, b)| value > b) {
+                best = Some((*area, value));
+            }
+        }
+        best.map(|(area, _)| area)
+    }
+
+    /// Monotheism: up to `count` of the `(token, owner)` candidates, taken
+    /// from the most wanted owners first.
+    pub fn pick_tokens(&self, candidates: &[(Entity, Entity)], count: usize) -> Vec<Entity> {
+        let mut owners: Vec<Entity> = Vec::new();
+        for (_, owner) in candidates {
+            if !owners.contains(owner) {
+                owners.push(*owner);
+            }
+        }
+        self.rank_victims(&owners)
+            .into_iter()
+            .flat_map(|owner| {
+                candidates
+                    .iter()
+                    .filter(move |(_, o)| *o == owner)
+                    .map(|(token, _)| *token)
+            })
+            .take(count)
+            .collect()
+    }
+
+    /// What keeping a Civil War faction of `points` unit points, `cities` of
+    /// them cities, is worth: its size, plus its cities while they decide the
+    /// next A.S.T. epoch gate.
+    pub fn explain_faction(&self, points: usize, cities: usize) -> ScoreBreakdown {
+        let w = &self.weights;
+        let mut score = ScoreBreakdown::fixed("points", points as f32);
+        score.add("city_income", 2.0 * w.city_income * cities as f32);
+        let epoch = AstEpoch::for_space(self.standing(self.me).ast_space);
+        if let Some(next) = epoch.next()
+            && cities >= next.min_cities()
+        {
+            score.add("gate", GATE_POINTS * (0.5 + w.tech_focus));
+        }
+        score
+    }
+
+    /// Civil War (30.415): keep the faction worth more; the first on a tie.
+    pub fn keep_faction(
+        &self,
+        (first_points, first_cities): (usize, usize),
+        (second_points, second_cities): (usize, usize),
+    ) -> FactionChoice {
+        let first = self.explain_faction(first_points, first_cities).total();
+        let second = self.explain_faction(second_points, second_cities).total();
+        if second > first {
+            FactionChoice::Second
+        } else {
+            FactionChoice::First
+        }
+    }
+}
+
+/// Unit points a player has in an area: tokens plus five for a city.
+pub fn unit_points(tokens: usize, has_city: bool) -> usize {
+    tokens + if has_city { CITY_POINTS } else { 0 }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stupid_ai::{Personality, Playstyle};
+
+    fn e(i: u32) -> Entity {
+        Entity::from_raw_u32(i).unwrap()
+    }
+
+    /// `me` at A.S.T. 4; `leader` at 9, `partner` at 4 and trusted, `cheat`
+    /// at 4 and distrusted.
+    fn table() -> (CalamityChooser, Entity, Entity, Entity) {
+        let (me, leader, partner, cheat) = (e(1), e(2), e(3), e(4));
+        let standings = HashMap::from([
+            (
+                me,
+                PlayerStanding {
+                    ast_space: 4,
+                    cities: 2,
+                    ..Default::default()
+                },
+            ),
+            (
+                leader,
+                PlayerStanding {
+                    ast_space: 9,
+                    cities: 4,
+                    ..Default::default()
+                },
+            ),
+            (
+                partner,
+                PlayerStanding {
+                    ast_space: 4,
+                    cities: 2,
+                    trust: 0.9,
+                },
+            ),
+            (
+                cheat,
+                PlayerStanding {
+                    ast_space: 4,
+                    cities: 2,
+                    trust: 0.1,
+                },
+            ),
+        ]);
+        let chooser = CalamityChooser {
+            me,
+            standings,
+            weights: Personality::from_playstyle(Playstyle::Balanced).weights,
+        };
+        (chooser, leader, partner, cheat)
+    }
+
+    #[test]
+    fn secondary_losses_fall_on_the_leader_and_spare_the_partner() {
+        let (chooser, leader, partner, cheat) = table();
+        assert_eq!(
+            chooser.rank_victims(&[partner, cheat, leader]),
+            vec![leader, cheat, partner]
+        );
+
+        // Flood's 10 points: the leader loses all 6 it has, the cheat the rest.
+        let split = chooser.divide_loss(&[(partner, 8), (cheat, 8), (leader, 6)], 10);
+        assert_eq!(split, vec![(leader, 6), (cheat, 4), (partner, 0)]);
+    }
+
+    #[test]
+    fn barbarians_are_steered_away_from_own_units() {
+        let (chooser, leader, partner, _) = table();
+        let (mine, theirs, friendly) = (e(10), e(20), e(30));
+        let tied = vec![
+            (mine, vec![(chooser.me, 6)]),
+            (friendly, vec![(partner, 6)]),
+            (theirs, vec![(leader, 6), (chooser.me, 1)]),
+        ];
+        assert_eq!(chooser.pick_area(&tied), Some(theirs));
+    }
+
+    #[test]
+    fn monotheism_takes_the_most_wanted_owners_tokens() {
+        let (chooser, leader, partner, cheat) = table();
+        let (t1, t2, t3, t4) = (e(11), e(12), e(13), e(14));
+        let candidates = vec![(t1, partner), (t2, cheat), (t3, leader), (t4, leader)];
+        assert_eq!(chooser.pick_tokens(&candidates, 2), vec![t3, t4]);
+        assert_eq!(chooser.pick_tokens(&candidates, 3), vec![t3, t4, t2]);
+    }
+
+    #[test]
+    fn civil_war_keeps_the_cities_the_next_gate_needs() {
+        let (mut chooser, ..) = table();
+        let me = chooser.me;
+        // Bigger faction wins when cities decide nothing.
+        assert_eq!(chooser.keep_faction((35, 0), (5, 0)), FactionChoice::First);
+
+        // Early Bronze: Late Bronze needs 3 cities; only the second faction
+        // (three cities, 15 points) still has them.
+        chooser.standings.get_mut(&me).unwrap().ast_space = 6;
+        assert_eq!(
+            chooser.keep_faction((20, 0), (15, 3)),
+            FactionChoice::Second
+        );
+        // Early Iron: Late Iron needs 5, which neither faction has.
+        chooser.standings.get_mut(&me).unwrap().ast_space = 11;
+        assert_eq!(chooser.keep_faction((20, 0), (15, 3)), FactionChoice::First);
+    }
+}
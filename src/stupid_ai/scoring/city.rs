@@ -1,4 +1,4 @@
-use super::{AreaSummary, enemy_pressure, saturating};
+use super::{AreaSummary, ScoreBreakdown, enemy_pressure, saturating};
 use crate::civilization::GameMove;
 use crate::stupid_ai::Weights;
 use bevy::platform::collections::HashMap;
@@ -12,24 +12,40 @@ pub fn score_city_construction(
     areas: &HashMap<Entity, AreaSummary>,
     w: &Weights,
 ) -> f32 {
+    explain_city_construction(mv, areas, w).total()
+}
+
+/// [`score_city_construction`], consideration by consideration.
+pub fn explain_city_construction(
+    mv: &GameMove,
+    areas: &HashMap<Entity, AreaSummary>,
+    w: &Weights,
+) -> ScoreBreakdown {
+    let mut score = ScoreBreakdown::default();
     match mv {
         GameMove::CityConstruction(m) => {
             let Some(tgt) = areas.get(&m.target) else {
-                return 0.0;
+                return score;
             };
             let capacity = saturating(tgt.max_population as f32, 4.0);
-            let mut score = w.city_income * (0.6 + 0.4 * capacity);
+            score.add("city_income", w.city_income * (0.6 + 0.4 * capacity));
             // A defensible site (little enemy pressure) is worth more; an exposed
             // one risks being attrited/captured.
             let pressure = enemy_pressure(m.target, areas) as f32;
-            score += w.defense * (0.2 - 0.2 * saturating(pressure, 4.0));
-            score
+            score.add(
+                "defense",
+                w.defense * (0.2 - 0.2 * saturating(pressure, 4.0)),
+            );
         }
         // The "build nothing more" baseline: attractive to players who'd rather keep
         // tokens mobile (low city_income, some aggression) than commit them to a city.
-        GameMove::EndCityConstruction => 0.3 * (1.0 - w.city_income) + 0.1 * w.aggression,
-        _ => f32::NEG_INFINITY,
+        GameMove::EndCityConstruction => {
+            score.add("city_income", 0.3 * (1.0 - w.city_income));
+            score.add("aggression", 0.1 * w.aggression);
+        }
+        _ => score.add("not a city move", f32::NEG_INFINITY),
     }
+    score
 }
 
 /// Score a forced city-elimination choice. The game makes us give up a city; a
@@ -40,15 +56,27 @@ pub fn score_city_elimination(
     areas: &HashMap<Entity, AreaSummary>,
     w: &Weights,
 ) -> f32 {
+    explain_city_elimination(mv, areas, w).total()
+}
+
+/// [`score_city_elimination`], consideration by consideration.
+pub fn explain_city_elimination(
+    mv: &GameMove,
+    areas: &HashMap<Entity, AreaSummary>,
+    w: &Weights,
+) -> ScoreBreakdown {
     let GameMove::EliminateCity(m) = mv else {
-        return f32::NEG_INFINITY;
+        return ScoreBreakdown::fixed("not an elimination", f32::NEG_INFINITY);
     };
+    let mut score = ScoreBreakdown::default();
     let Some(area) = areas.get(&m.area) else {
-        return 0.0;
+        return score;
     };
     let exposure = saturating(enemy_pressure(m.area, areas) as f32, 4.0);
     let board_value = saturating(area.max_population as f32, 4.0);
     // Drop the exposed one (defense likes shedding liabilities), keep the valuable
     // one (city_income hates losing capacity).
-    w.defense * exposure - w.city_income * board_value
+    score.add("defense", w.defense * exposure);
+    score.add("city_income", -w.city_income * board_value);
+    score
 }
@@ -0,0 +1,436 @@
+//! Civ-card purchase planning: rather than rating one card at a time, pick the
+//! bundle worth most to the player's future within what its hand can pay --
+//! the next A.S.T. gates' group and count requirements (rule 33.2), credit
+//! chains towards cards not yet owned, and cover against the calamities in
+//! hand, which resolve right after this phase.
+
+use super::{ScoreBreakdown, saturating};
+use crate::civilization::{
+    AstEpoch, AvailableCivCards, CivCardDefinition, CivCardName, CivCardType, Credits, TradeCard,
+    TradeCardTrait,
+};
+use crate::stupid_ai::Weights;
+use bevy::platform::collections::HashSet;
+use enumflags2::BitFlags;
+
+/// Only the most valuable single cards are combined into bundles, which
+/// keeps the search at a few thousand subsets.
+const MAX_CANDIDATES: usize = 12;
+/// Epochs whose entry asks for civ cards.
+const CARD_GATES: [AstEpoch; 2] = [AstEpoch::LateBronze, AstEpoch::EarlyIron];
+
+/// Everything the planner reads, gathered by the system.
+#[derive(Clone, Debug, Default)]
+pub struct PurchasePlanInput {
+    /// Cards owned right now, this turn's purchases included.
+    pub owned: HashSet<CivCardName>,
+    /// Cards owned when the phase began; only they give credits (rule 31.53).
+    pub held_before: HashSet<CivCardName>,
+    pub ast_space: u32,
+    /// Calamities in hand. They strike once this phase is over.
+    pub calamities: Vec<TradeCard>,
+    /// What `compute_ai_payment` can pay out of the hand.
+    pub budget: u32,
+}
+
+/// The bundle to buy, cheapest card first. Empty means stop buying.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PurchasePlan {
+    pub cards: Vec<CivCardName>,
+    /// Total cost after credits.
+    pub cost: u32,
+    /// Why the bundle is worth buying; sums to its value.
+    pub breakdown: ScoreBreakdown,
+}
+
+impl PurchasePlan {
+    pub fn value(&self) -> f32 {
+        self.breakdown.total()
+    }
+}
+
+/// The civ cards that soften a calamity when held (rules 30.x), as
+/// `resolve_calamities` applies them.
+pub fn calamity_mitigations(calamity: TradeCard) -> &'static [CivCardName] {
+    match calamity {
+        TradeCard::VolcanoEarthquake | TradeCard::Flood => &[CivCardName::Engineering],
+        TradeCard::Famine => &[CivCardName::Pottery],
+        TradeCard::Superstition => &[
+            CivCardName::Mysticism,
+            CivCardName::Deism,
+            CivCardName::Enlightenment,
+        ],
+        TradeCard::CivilWar => &[
+            CivCardName::Music,
+            CivCardName::DramaAndPoetry,
+            CivCardName::Democracy,
+            CivCardName::Philosophy,
+        ],
+        TradeCard::SlaveRevolt => &[CivCardName::Enlightenment],
+        TradeCard::Epidemic => &[CivCardName::Medicine],
+        TradeCard::CivilDisorder => &[
+            CivCardName::Music,
+            CivCardName::DramaAndPoetry,
+            CivCardName::Law,
+            CivCardName::Democracy,
+        ],
+        TradeCard::IconoclasmAndHeresy => &[
+            CivCardName::Law,
+            CivCardName::Philosophy,
+            CivCardName::Theology,
+            CivCardName::Monotheism,
+        ],
+        _ => &[],
+    }
+}
+
+fn is_covered(calamity: TradeCard, cards: &HashSet<CivCardName>) -> bool {
+    calamity_mitigations(calamity)
+        .iter()
+        .any(|card| cards.contains(card))
+}
+
+/// Cards buyable now: not owned, prerequisites held, affordable on their own.
+/// Paired with their cost after credits.
+pub fn purchase_candidates<'a>(
+    input: &PurchasePlanInput,
+    defs: &'a AvailableCivCards,
+) -> Vec<(&'a CivCardDefinition, u32)> {
+    let credits = defs.total_credits(&input.held_before);
+    defs.cards
+        .iter()
+        .filter(|card| {
+            !input.owned.contains(&card.name)
+                && card.prerequisites.iter().all(|p| input.owned.contains(p))
+        })
+        .map(|card| (card, card.calculate_cost(&credits)))
+        .filter(|(_, cost)| *cost <= input.budget)
+        .collect()
+}
+
+fn card_groups(cards: &HashSet<CivCardName>, defs: &AvailableCivCards) -> usize {
+    defs.cards_for_names(cards)
+        .iter()
+        .fold(BitFlags::<CivCardType>::empty(), |groups, card| {
+            groups | card.card_type
+        })
+        .len()
+}
+
+/// Share of a card gate's group and count requirements met, 0 to 1.
+fn card_gate_readiness(gate: AstEpoch, groups: usize, count: usize) -> f32 {
+    let mut met = Vec::new();
+    if gate.min_card_groups() > 0 {
+        met.push((groups as f32 / gate.min_card_groups() as f32).min(1.0));
+    }
+    if gate.min_card_count() > 0 {
+        met.push((count as f32 / gate.min_card_count() as f32).min(1.0));
+    }
+    if met.is_empty() {
+        1.0
+    } else {
+        met.iter().sum::<f32>() / met.len() as f32
+    }
+}
+
+/// Credits `card` grants towards cards still to be bought after `after`.
+fn future_credits(
+    card: &CivCardDefinition,
+    after: &HashSet<CivCardName>,
+    defs: &AvailableCivCards,
+) -> u32 {
+    let unowned = || defs.cards.iter().filter(move |c| !after.contains(&c.name));
+    card.credits
+        .iter()
+        .map(|credit| match credit {
+            Credits::ToType(card_type, value) => {
+                let card_type = BitFlags::from(*card_type);
+                value
+                    * unowned()
+                        .filter(|c| c.card_type.intersects(card_type))
+                        .count() as u32
+            }
+            Credits::ToAll(value) => value * unowned().count() as u32,
+            Credits::ToSpecificCard(name, value) => {
+                if after.contains(name) {
+                    0
+                } else {
+                    *value
+                }
+            }
+        })
+        .sum()
+}
+
+/// What buying `bundle` (with its total `cost`) is worth, term by term.
+pub fn explain_purchase(
+    input: &PurchasePlanInput,
+    defs: &AvailableCivCards,
+    bundle: &[&CivCardDefinition],
+    cost: u32,
+    w: &Weights,
+) -> ScoreBreakdown {
+    let mut score = ScoreBreakdown::default();
+    if bundle.is_empty() {
+        return score;
+    }
+    let mut after = input.owned.clone();
+    after.extend(bundle.iter().map(|card| card.name));
+
+    // A.S.T. gates: the next one counts fully, the one after at half.
+    let current = AstEpoch::for_space(input.ast_space);
+    let (groups_before, groups_after) =
+        (card_groups(&input.owned, defs), card_groups(&after, defs));
+    for gate in CARD_GATES.into_iter().filter(|gate| *gate > current) {
+        let urgency = if current.next() == Some(gate) {
+            1.0
+        } else {
+            0.5
+        };
+        let gained = card_gate_readiness(gate, groups_after, after.len())
+            - card_gate_readiness(gate, groups_before, input.owned.len());
+        score.add("gate", 0.8 * urgency * gained);
+    }
+
+    // Credit chains: discounts on cards still to come (Mathematics towards
+    // the Sciences, Literacy towards Law, Democracy and Philosophy, ...).
+    let credits: u32 = bundle
+        .iter()
+        .map(|card| future_credits(card, &after, defs))
+        .sum();
+    score.add(
+        "credits",
+        (0.15 + 0.25 * w.tech_focus) * saturating(credits as f32, 150.0),
+    );
+
+    // Face value counts in final scoring (rule 35.1A).
+    let points: u32 = bundle.iter().map(|card| card.cost).sum();
+    score.add(
+        "points",
+        (0.15 + 0.2 * w.tech_focus) * saturating(points as f32, 250.0),
+    );
+
+    // Calamity cover: those in hand strike right after this phase, so a card
+    // that softens one is worth its face value now; cover for the rest is a
+    // smaller, later benefit.
+    let struck: f32 = input
+        .calamities
+        .iter()
+        .filter(|c| !is_covered(**c, &input.owned) && is_covered(**c, &after))
+        .map(|c| c.value() as f32)
+        .sum();
+    score.add(
+        "calamities",
+        (0.3 + 0.4 * w.calamity_aversion) * saturating(struck, 4.0),
+    );
+    let newly_covered = TradeCard::iter()
+        .filter(|c| c.is_calamity() && !is_covered(*c, &input.owned) && is_covered(*c, &after))
+        .count();
+    score.add(
+        "calamity_cover",
+        0.1 * w.calamity_aversion * saturating(newly_covered as f32, 2.0),
+    );
+
+    // Reserve discipline, as for a single card.
+    let spent = if input.budget == 0 {
+        1.0
+    } else {
+        (cost as f32 / input.budget as f32).clamp(0.0, 1.0)
+    };
+    score.add("risk", -(1.0 - w.risk) * spent * 0.2);
+    score
+}
+
+/// The most valuable bundle the budget covers. Ties go to the cheaper
+/// bundle, then to card order, so the plan is deterministic.
+pub fn plan_civ_card_purchase(
+    input: &PurchasePlanInput,
+    defs: &AvailableCivCards,
+    w: &Weights,
+) -> PurchasePlan {
+    let mut candidates = purchase_candidates(input, defs);
+    let single = |card: &CivCardDefinition, cost: u32| {
+        explain_purchase(input, defs, &[card], cost, w).total()
+    };
+    candidates.sort_by(|a, b| {
+        single(b.0, b.1)
+            .total_cmp(&single(a.0, a.1))
+            .then((a.0.name as u8).cmp(&(b.0.name as u8)))
+    });
+    candidates.truncate(MAX_CANDIDATES);
+    candidates.sort_by_key(|(card, cost)| (*cost, card.name as u8));
+
+    let mut best = PurchasePlan::default();
+    let mut bundle: Vec<(&CivCardDefinition, u32)> = Vec::new();
+    search(input, defs, w, &candidates, 0, &mut bundle, 0, &mut best);
+    best
+}
+
+fn search<'a>(
+    input: &PurchasePlanInput,
+    defs: &AvailableCivCards,
+    w: &Weights,
+    candidates: &[(&'a CivCardDefinition, u32)],
+    from: usize,
+    bundle: &mut Vec<(&'a CivCardDefinition, u32)>,
+    cost: u32,
+    best: &mut PurchasePlan,
+) {
+    if !bundle.is_empty() {
+        let cards: Vec<&CivCardDefinition> = bundle.iter().map(|(card, _)| *card).collect();
+        let breakdown = explain_purchase(input, defs, &cards, cost, w);
+        let value = breakdown.total();
+        // Buying nothing is worth zero; on a tie, keep the cheaper bundle.
+        let best_value = if best.cards.is_empty() {
+            0.0
+        } else {
+            best.value()
+        };
+        let better = value > best_value + f32::EPSILON
+            || (!best.cards.is_empty()
+                && (value - best_value).abs() <= f32::EPSILON
+                && cost < best.cost);
+        if better {
+            *best = PurchasePlan {
+                cards: cards.iter().map(|card| card.name).collect(),
+                cost,
+                breakdown,
+            };
+        }
+    }
+    for i in from..candidates.len() {
+        let (card, card_cost) = candidates[i];
+        // Sorted by cost: nothing further along fits either.
+        if cost + card_cost > input.budget {
+            break;
+        }
+        bundle.push((card, card_cost));
+        search(
+            input,
+            defs,
+            w,
+            candidates,
+            i + 1,
+            bundle,
+            cost + card_cost,
+            best,
+        );
+        bundle.pop();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stupid_ai::{Personality, Playstyle};
+
+    fn defs() -> AvailableCivCards {
+        let text = std::fs::read_to_string("assets/definitions/civilization.cards.ron")
+            .expect("Failed to read civilization.cards.ron");
+        ron::from_str(&text).expect("Failed to deserialize RON")
+    }
+
+    fn weights() -> Weights {
+        Personality::from_playstyle(Playstyle::Balanced).weights
+    }
+
+    fn input(owned: &[CivCardName], ast_space: u32, budget: u32) -> PurchasePlanInput {
+        let owned: HashSet<CivCardName> = owned.iter().copied().collect();
+        PurchasePlanInput {
+            held_before: owned.clone(),
+            owned,
+            ast_space,
+            calamities: Vec::new(),
+            budget,
+        }
+    }
+
+    #[test]
+    fn the_plan_stays_within_the_budget() {
+        let defs = defs();
+        for budget in [0, 40, 60, 130, 300, 700] {
+            let plan = plan_civ_card_purchase(&input(&[], 6, budget), &defs, &weights());
+            assert!(plan.cost <= budget, "{plan:?} over {budget}");
+            let listed: u32 = plan
+                .cards
+                .iter()
+                .map(|name| defs.cards.iter().find(|c| c.name == *name).unwrap().cost)
+                .sum();
+            assert_eq!(
+                listed, plan.cost,
+                "no credits owned, so face value is the cost"
+            );
+        }
+        assert!(
+            plan_civ_card_purchase(&input(&[], 6, 40), &defs, &weights())
+                .cards
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn near_the_late_bronze_gate_new_groups_beat_more_of_the_same() {
+        let defs = defs();
+        // Crafts only (Pottery, Cloth Making), in Early Bronze: Late Bronze
+        // wants three groups.
+        let owned = [CivCardName::Pottery, CivCardName::ClothMaking];
+        let plan = plan_civ_card_purchase(&input(&owned, 6, 110), &defs, &weights());
+        let groups = |names: &[CivCardName]| {
+            let set: HashSet<CivCardName> = names.iter().chain(owned.iter()).copied().collect();
+            card_groups(&set, &defs)
+        };
+        assert_eq!(groups(&plan.cards), 3, "{plan:?}");
+    }
+
+    #[test]
+    fn a_calamity_in_hand_pulls_in_its_mitigation() {
+        let defs = defs();
+        // Past the last card gate, so only credits, points and cover count.
+        let mut with_epidemic = input(&[], 14, 140);
+        let plain = plan_civ_card_purchase(&with_epidemic, &defs, &weights());
+        assert!(!plain.cards.contains(&CivCardName::Medicine), "{plain:?}");
+
+        with_epidemic.calamities = vec![TradeCard::Epidemic];
+        let plan = plan_civ_card_purchase(&with_epidemic, &defs, &weights());
+        assert_eq!(plan.cards, vec![CivCardName::Medicine]);
+        assert!(
+            plan.breakdown
+                .terms
+                .iter()
+                .any(|(name, _)| *name == "calamities")
+        );
+    }
+
+    #[test]
+    fn credit_chains_are_valued_towards_cards_not_yet_owned() {
+        let defs = defs();
+        let literacy = defs
+            .cards
+            .iter()
+            .find(|c| c.name == CivCardName::Literacy)
+            .unwrap();
+        let nothing = HashSet::default();
+        let all_law: HashSet<CivCardName> = [
+            CivCardName::Literacy,
+            CivCardName::Law,
+            CivCardName::Democracy,
+            CivCardName::Philosophy,
+        ]
+        .into_iter()
+        .collect();
+        assert!(
+            future_credits(literacy, &nothing, &defs) > future_credits(literacy, &all_law, &defs)
+        );
+    }
+
+    #[test]
+    fn unmet_prerequisites_keep_a_card_out() {
+        let defs = defs();
+        let names: Vec<CivCardName> = purchase_candidates(&input(&[], 2, 1000), &defs)
+            .iter()
+            .map(|(card, _)| card.name)
+            .collect();
+        assert!(!names.contains(&CivCardName::Democracy));
+        assert!(names.contains(&CivCardName::Law));
+    }
+}
@@ -1,4 +1,4 @@
-use super::saturating;
+use super::{ScoreBreakdown, saturating};
 use crate::civilization::AcquireCivilizationCardsMove;
 use crate::stupid_ai::Weights;
 
@@ -22,6 +22,16 @@ pub fn score_civ_card(
     option: Option<CivCardOption>,
     w: &Weights,
 ) -> f32 {
+    explain_civ_card(mv, option, w).total()
+}
+
+/// [`score_civ_card`], consideration by consideration.
+pub fn explain_civ_card(
+    mv: &AcquireCivilizationCardsMove,
+    option: Option<CivCardOption>,
+    w: &Weights,
+) -> ScoreBreakdown {
+    let mut score = ScoreBreakdown::default();
     match mv {
         // "Stop buying" baseline. Kept deliberately LOW: civ cards are the win
         // engine (A.S.T. progress, credits, calamity defence) and any commodity
@@ -29,22 +39,26 @@ pub fn score_civ_card(
         // 31.71), so holding back is rarely worth it. Cautious / non-tech players
         // are only slightly more willing to stop.
         AcquireCivilizationCardsMove::DoneAcquiringCards => {
-            0.18 + (1.0 - w.tech_focus) * 0.12 + (1.0 - w.risk) * 0.05
+            score.add("baseline", 0.18);
+            score.add("tech_focus", (1.0 - w.tech_focus) * 0.12);
+            score.add("risk", (1.0 - w.risk) * 0.05);
         }
         AcquireCivilizationCardsMove::AcquireCard(_)
         | AcquireCivilizationCardsMove::AcquireCards(_) => {
             let Some(opt) = option else {
-                return f32::NEG_INFINITY;
+                return ScoreBreakdown::fixed("unknown card", f32::NEG_INFINITY);
             };
             if opt.wealth < opt.effective_cost {
                 // Can't actually pay — keep it out of contention.
-                return f32::NEG_INFINITY;
+                return ScoreBreakdown::fixed("unaffordable", f32::NEG_INFINITY);
             }
             // Strong base for any affordable card so the AI actually invests in
             // its civilization, boosted by tech focus and by credit synergy
             // (cards that discount a whole tech line).
             let synergy = saturating(opt.credit_value as f32, 3.0);
-            let mut score = 0.55 + 0.35 * w.tech_focus + 0.35 * synergy;
+            score.add("baseline", 0.55);
+            score.add("tech_focus", 0.35 * w.tech_focus);
+            score.add("credits", 0.35 * synergy);
             // Mild reserve discipline: a cautious player dislikes blowing its whole
             // hand on one card. Small, because excess cards are lost anyway.
             let spend_fraction = if opt.wealth == 0 {
@@ -52,8 +66,8 @@ pub fn score_civ_card(
             } else {
                 (opt.effective_cost as f32 / opt.wealth as f32).clamp(0.0, 1.0)
             };
-            score -= (1.0 - w.risk) * spend_fraction * 0.2;
-            score
+            score.add("risk", -(1.0 - w.risk) * spend_fraction * 0.2);
         }
     }
+    score
 }
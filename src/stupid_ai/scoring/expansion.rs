@@ -1,4 +1,4 @@
-use super::{AreaSummary, saturating};
+use super::{AreaSummary, ScoreBreakdown, saturating};
 use crate::civilization::GameMove;
 use crate::stupid_ai::Weights;
 use bevy::platform::collections::HashMap;
@@ -12,21 +12,31 @@ pub fn score_population_expansion(
     areas: &HashMap<Entity, AreaSummary>,
     w: &Weights,
 ) -> f32 {
+    explain_population_expansion(mv, areas, w).total()
+}
+
+/// [`score_population_expansion`], consideration by consideration.
+pub fn explain_population_expansion(
+    mv: &GameMove,
+    areas: &HashMap<Entity, AreaSummary>,
+    w: &Weights,
+) -> ScoreBreakdown {
     let GameMove::PopulationExpansion(m) = mv else {
-        return f32::NEG_INFINITY;
+        return ScoreBreakdown::fixed("not an expansion", f32::NEG_INFINITY);
     };
     let Some(area) = areas.get(&m.area) else {
-        return w.growth * 0.5;
+        return ScoreBreakdown::fixed("growth", w.growth * 0.5);
     };
     let capacity = saturating(area.max_population as f32, 4.0);
-    let mut score = w.growth * 0.4;
+    let mut score = ScoreBreakdown::fixed("growth", w.growth * 0.4);
     if area.supports_city() {
-        score += w.city_income * (0.4 + 0.4 * capacity);
+        score.add("city_income", w.city_income * (0.4 + 0.4 * capacity));
     }
     // Contested ground: expansionists/aggressors like pressing into it, cautious
     // players would rather not stack where a conflict looms.
     if area.enemy_pop > 0 {
-        score += w.expansion * 0.2 - w.defense * 0.2;
+        score.add("expansion", w.expansion * 0.2);
+        score.add("defense", -w.defense * 0.2);
     }
     score
 }
@@ -2,20 +2,29 @@
 //! `docs/utility-ai-design.md`. Each `score_*` function is pure given the
 //! pre-gathered [`AreaSummary`] map, so it can be unit-tested without an app.
 
+mod calamity;
 mod city;
+mod civ_card_plan;
 mod civ_cards;
 mod expansion;
 mod movement;
+mod position;
+mod ships;
 mod trade;
+pub use calamity::*;
 pub use city::*;
+pub use civ_card_plan::*;
 pub use civ_cards::*;
 pub use expansion::*;
 pub use movement::*;
+pub use position::*;
+pub use ships::*;
 pub use trade::*;
 
+use crate::civilization::{BuiltCity, CitySite, LandPassage, OpenSea, Population, SeaPassage};
 use crate::stupid_ai::{Personality, Picker};
 use bevy::platform::collections::HashMap;
-use bevy::prelude::Entity;
+use bevy::prelude::{Entity, Has};
 use rand::{Rng, RngExt};
 
 /// A flattened, scoring-friendly snapshot of one area, gathered once per decision
@@ -31,9 +40,49 @@ pub struct AreaSummary {
     /// Tokens belonging to *other* players in this area.
     pub enemy_pop: usize,
     pub neighbours: Vec<Entity>,
+    /// Areas a ship can sail to from here in one hop; empty inland.
+    pub sea_neighbours: Vec<Entity>,
+    /// Open sea (rule 23.52): only ships of an Astronomy holder may enter.
+    pub is_open_sea: bool,
 }
 
+/// The live area components [`AreaSummary::from_components`] reads, besides
+/// the `Population`.
+pub type AreaSummaryQueryData = (
+    &'static LandPassage,
+    Option<&'static SeaPassage>,
+    Option<&'static BuiltCity>,
+    Has<CitySite>,
+    Has<OpenSea>,
+);
+
 impl AreaSummary {
+    /// Summarise one area from `player`'s point of view.
+    pub fn from_components(
+        player: Entity,
+        population: &Population,
+        (land_passage, sea_passage, built_city, is_city_site, is_open_sea): (
+            &LandPassage,
+            Option<&SeaPassage>,
+            Option<&BuiltCity>,
+            bool,
+            bool,
+        ),
+    ) -> Self {
+        let my_pop = population.population_for_player(player);
+        AreaSummary {
+            max_population: population.max_population,
+            is_city_site,
+            has_city: built_city.is_some(),
+            city_is_mine: built_city.is_some_and(|c| c.player == player),
+            my_pop,
+            enemy_pop: population.total_population() - my_pop,
+            neighbours: land_passage.to_areas.clone(),
+            sea_neighbours: sea_passage.map_or_else(Vec::new, |s| s.to_areas.clone()),
+            is_open_sea,
+        }
+    }
+
     /// Can this area host a city (and therefore generate tax/card income)?
     pub fn supports_city(&self) -> bool {
         self.is_city_site || self.max_population >= 6
@@ -42,6 +91,11 @@ impl AreaSummary {
     pub fn is_empty(&self) -> bool {
         self.my_pop == 0 && self.enemy_pop == 0
     }
+
+    /// Every area carries a `SeaPassage`; only those with routes are coastal.
+    pub fn is_coastal(&self) -> bool {
+        !self.sea_neighbours.is_empty()
+    }
 }
 
 /// Total enemy tokens sitting in the areas adjacent to `area` — a proxy for how
@@ -58,6 +112,51 @@ pub fn enemy_pressure(area: Entity, areas: &HashMap<Entity, AreaSummary>) -> usi
         .sum()
 }
 
+// --- explanations -------------------------------------------------------------
+
+/// A move's score, consideration by consideration: what each knob (`growth`,
+/// `aggression`, `defense`, ...) contributed, plus any fixed `baseline`. The
+/// terms always sum to the score, so a trace can show exactly why one move
+/// beat another.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ScoreBreakdown {
+    pub terms: Vec<(&'static str, f32)>,
+}
+
+impl ScoreBreakdown {
+    /// A score that is one flat value, e.g. a veto or an illegal move.
+    pub fn fixed(consideration: &'static str, value: f32) -> Self {
+        let mut breakdown = ScoreBreakdown::default();
+        breakdown.add(consideration, value);
+        breakdown
+    }
+
+    /// Add `value` under `consideration`, merging with an earlier term of the
+    /// same name. Zero contributions are left out.
+    pub fn add(&mut self, consideration: &'static str, value: f32) {
+        if value == 0.0 {
+            return;
+        }
+        match self.terms.iter_mut().find(|(c, _)| *c == consideration) {
+            Some((_, total)) => *total += value,
+            None => self.terms.push((consideration, value)),
+        }
+    }
+
+    pub fn total(&self) -> f32 {
+        self.terms.iter().map(|(_, v)| v).sum()
+    }
+
+    /// Keep the total within `[min, max]`, booking whatever was cut off as a
+    /// `clamp` term.
+    pub fn clamped(mut self, min: f32, max: f32) -> Self {
+        let total = self.total();
+        let clamped = total.clamp(min, max);
+        self.add("clamp", clamped - total);
+        self
+    }
+}
+
 // --- response curves (evaluators) ------------------------------------------
 
 pub fn clamp01(v: f32) -> f32 {
@@ -190,6 +289,22 @@ mod tests {
         }
     }
 
+    #[test]
+    fn a_breakdown_merges_terms_and_books_the_clamp() {
+        let mut breakdown = ScoreBreakdown::fixed("baseline", 0.5);
+        breakdown.add("aggression", 0.75);
+        breakdown.add("aggression", 0.5);
+        breakdown.add("defense", 0.0);
+        assert_eq!(
+            breakdown.terms,
+            vec![("baseline", 0.5), ("aggression", 1.25)]
+        );
+
+        let clamped = breakdown.clamped(-1.0, 1.5);
+        assert_eq!(clamped.total(), 1.5);
+        assert_eq!(clamped.terms.last(), Some(&("clamp", -0.25)));
+    }
+
     #[test]
     fn pick_returns_none_on_empty() {
         let mut rng = rand::rng();
@@ -1,4 +1,6 @@
-use super::{AreaSummary, enemy_pressure, saturating};
+use super::{
+    AreaSummary, ScoreBreakdown, ShipReach, boxed_in, enemy_pressure, landfall_targets, saturating,
+};
 use crate::civilization::GameMove;
 use crate::stupid_ai::Weights;
 use bevy::platform::collections::HashMap;
@@ -7,7 +9,8 @@ use bevy::prelude::Entity;
 /// Score a single movement-phase move for `player`. Higher = more attractive.
 ///
 /// The drivers (see the design doc):
-/// - reach toward city-capable, unowned land (`expansion` + `city_income`),
+/// - reach toward city-capable, unowned land (`expansion` + `city_income`), by
+///   ship where walking can't get there,
 /// - don't abandon city squares we hold, and don't strip a source the enemy can hit
 ///   (`defense`),
 /// - take favourable attacks / cities (`aggression`, gated by `risk`), veto clearly
@@ -18,6 +21,16 @@ pub fn score_movement(
     areas: &HashMap<Entity, AreaSummary>,
     w: &Weights,
 ) -> f32 {
+    explain_movement(mv, player, areas, w).total()
+}
+
+/// [`score_movement`], consideration by consideration.
+pub fn explain_movement(
+    mv: &GameMove,
+    player: Entity,
+    areas: &HashMap<Entity, AreaSummary>,
+    w: &Weights,
+) -> ScoreBreakdown {
     match mv {
         GameMove::EndMovement => score_end_movement(player, areas, w),
         GameMove::Movement(m) | GameMove::ShipFerry(m) => {
@@ -29,7 +42,7 @@ pub fn score_movement(
             score_attack(m.source, m.target, m.max_tokens, takes_city, areas, w)
         }
         // Not a movement-phase move; never selected here.
-        _ => f32::NEG_INFINITY,
+        _ => ScoreBreakdown::fixed("not a movement move", f32::NEG_INFINITY),
     }
 }
 
@@ -40,8 +53,13 @@ fn area(areas: &HashMap<Entity, AreaSummary>, e: Entity) -> Option<&AreaSummary>
 /// The "do nothing / hold position" baseline. Its value is mostly the *defensive*
 /// worth of staying put: holding city squares and not exposing positions. A modest
 /// floor so the AI doesn't shuffle tokens around for no gain.
-fn score_end_movement(player: Entity, areas: &HashMap<Entity, AreaSummary>, w: &Weights) -> f32 {
-    let mut hold_value = 0.15; // small floor: standing pat is a legitimate option
+fn score_end_movement(
+    player: Entity,
+    areas: &HashMap<Entity, AreaSummary>,
+    w: &Weights,
+) -> ScoreBreakdown {
+    // small floor: standing pat is a legitimate option
+    let mut hold_value = ScoreBreakdown::fixed("baseline", 0.15);
     for (entity, s) in areas {
         if s.my_pop == 0 {
             continue;
@@ -49,7 +67,8 @@ fn score_end_movement(player: Entity, areas: &HashMap<Entity, AreaSummary>, w: &
         if s.city_is_mine {
             // Holding a city is valuable, more so when threatened.
             let pressure = enemy_pressure(*entity, areas) as f32;
-            hold_value += w.city_income * 0.5 + w.defense * saturating(pressure, 4.0) * 0.5;
+            hold_value.add("city_income", w.city_income * 0.5);
+            hold_value.add("defense", w.defense * saturating(pressure, 4.0) * 0.5);
         }
     }
     let _ = player;
@@ -63,9 +82,9 @@ fn score_peaceful_move(
     is_ferry: bool,
     areas: &HashMap<Entity, AreaSummary>,
     w: &Weights,
-) -> f32 {
+) -> ScoreBreakdown {
     let (Some(src), Some(tgt)) = (area(areas, source), area(areas, target)) else {
-        return 0.0;
+        return ScoreBreakdown::default();
     };
 
     // A move carrying zero tokens brings no population anywhere -- it's not
@@ -77,29 +96,49 @@ fn score_peaceful_move(
     // ship can oscillate between two connected areas forever (this tripped the
     // 120-selection movement loop guard in practice).
     if max_tokens == 0 {
-        return 0.0;
+        return ScoreBreakdown::default();
     }
 
-    let mut score = 0.0;
+    // Open sea (Astronomy) is only a stop on the way: its worth is the coast
+    // a ship can make from there next turn.
+    if is_ferry && tgt.is_open_sea {
+        let onward = ShipReach::for_cards(false, true);
+        let landfalls = landfall_targets(target, areas, onward).len() as f32;
+        let score =
+            ScoreBreakdown::fixed("expansion", w.expansion * 0.6 * saturating(landfalls, 2.0));
+        return clamp01_soft(score);
+    }
+
+    let mut score = ScoreBreakdown::default();
 
     // Reaching territory: grabbing new land is expansion; reinforcing my own is
     // weaker but consolidates.
     let grabbing_new = tgt.my_pop == 0;
     let capacity = saturating(tgt.max_population as f32, 4.0);
     if grabbing_new {
-        score += w.expansion * (0.4 + 0.6 * capacity);
+        score.add("expansion", w.expansion * (0.4 + 0.6 * capacity));
     } else {
-        score += w.growth * 0.25 * capacity;
+        score.add("growth", w.growth * 0.25 * capacity);
     }
 
     // City potential of the destination.
     if tgt.supports_city() {
-        score += w.city_income * (0.5 + 0.5 * capacity);
+        score.add("city_income", w.city_income * (0.5 + 0.5 * capacity));
     }
 
-    // Ferries open up otherwise-unreachable coasts.
-    if is_ferry {
-        score += w.expansion * 0.3;
+    // Ferries open up otherwise-unreachable coasts, and are the only way on
+    // for tokens with no empty land next door (an island such as Crete's).
+    // A target past the first sea hop is Cloth Making's extra reach.
+    if is_ferry && grabbing_new {
+        if !src.neighbours.contains(&target) {
+            score.add("expansion", w.expansion * 0.3);
+        }
+        if boxed_in(source, areas) {
+            score.add("expansion", w.expansion * 0.3);
+        }
+        if !src.sea_neighbours.contains(&target) {
+            score.add("expansion", w.expansion * 0.1);
+        }
     }
 
     // Defensive cost: what we leave behind at the source.
@@ -117,7 +156,7 @@ fn score_peaceful_move(
         if src.city_is_mine && remaining == 0 {
             penalty += w.defense * 0.8; // walked away from our own city
         }
-        score -= penalty;
+        score.add("defense", -penalty);
     }
 
     clamp01_soft(score)
@@ -130,9 +169,9 @@ fn score_attack(
     takes_city: bool,
     areas: &HashMap<Entity, AreaSummary>,
     w: &Weights,
-) -> f32 {
+) -> ScoreBreakdown {
     let (Some(src), Some(tgt)) = (area(areas, source), area(areas, target)) else {
-        return 0.0;
+        return ScoreBreakdown::default();
     };
 
     // Combat is attrition: roughly equal numbers trade off, so the edge is
@@ -145,30 +184,35 @@ fn score_attack(
     // reckless. This also covers the lone-token attack (1 vs ≥1) that would
     // otherwise tempt an aggressive AI into a pointless, repeatable suicide.
     if edge <= 0.0 && w.risk < 0.5 {
-        return 0.02 * w.aggression;
+        return ScoreBreakdown::fixed("aggression", 0.02 * w.aggression);
     }
 
     let favourable = saturating(edge.max(0.0), 2.0); // [0,1)
     let even_or_worse = if edge <= 0.0 { w.risk } else { 1.0 };
 
-    let mut score = w.aggression * (0.4 + 0.6 * favourable) * even_or_worse;
+    let mut score = ScoreBreakdown::fixed(
+        "aggression",
+        w.aggression * (0.4 + 0.6 * favourable) * even_or_worse,
+    );
 
     // Taking a city is the win engine — almost everyone values it, scaled by how
     // winnable it is.
     if takes_city && edge >= 0.0 {
-        score += (w.aggression * 0.5 + w.city_income) * (0.5 + 0.5 * favourable);
+        let winnable = 0.5 + 0.5 * favourable;
+        score.add("aggression", w.aggression * 0.5 * winnable);
+        score.add("city_income", w.city_income * winnable);
     }
 
     // Weakening a neighbour that pressures us has defensive value too.
     if tgt.supports_city() {
-        score += w.aggression * 0.2;
+        score.add("aggression", w.aggression * 0.2);
     }
 
     // Cost of denuding the source to mount the attack.
     let remaining = src.my_pop.saturating_sub(max_tokens.saturating_sub(1));
     let pressure = enemy_pressure(source, areas) as f32;
     if remaining == 0 && pressure > 0.0 {
-        score -= w.defense * saturating(pressure, 4.0);
+        score.add("defense", -w.defense * saturating(pressure, 4.0));
     }
 
     clamp01_soft(score)
@@ -177,8 +221,8 @@ fn score_attack(
 /// Keep scores in a bounded, comparable range. Negatives (heavy defensive
 /// penalties) are preserved so a bad move can fall below the neutral baseline;
 /// both pickers handle negative scores fine.
-fn clamp01_soft(v: f32) -> f32 {
-    v.clamp(-1.0, 1.5)
+fn clamp01_soft(v: ScoreBreakdown) -> ScoreBreakdown {
+    v.clamped(-1.0, 1.5)
 }
 
 #[cfg(test)]
@@ -208,6 +252,7 @@ mod tests {
                 my_pop: 6,
                 enemy_pop: 0,
                 neighbours: vec![enemy],
+                ..Default::default()
             },
         );
         areas.insert(
@@ -220,6 +265,7 @@ mod tests {
                 my_pop: 0,
                 enemy_pop: 1, // poorly defended city
                 neighbours: vec![home],
+                ..Default::default()
             },
         );
         (player, home, enemy, areas)
@@ -241,6 +287,22 @@ mod tests {
         );
     }
 
+    #[test]
+    fn taking_a_city_is_explained_by_aggression_and_city_income() {
+        let (player, home, enemy, areas) = winnable_city_world();
+        let w = Personality::from_playstyle(Playstyle::Warlord).weights;
+        let attack = GameMove::AttackCity(MovementMove::new(home, enemy, player, 5));
+
+        let explained = explain_movement(&attack, player, &areas, &w);
+        let considerations: Vec<&str> = explained.terms.iter().map(|(c, _)| *c).collect();
+        assert!(considerations.contains(&"aggression"), "{explained:?}");
+        assert!(considerations.contains(&"city_income"), "{explained:?}");
+        assert_eq!(
+            explained.total(),
+            score_movement(&attack, player, &areas, &w)
+        );
+    }
+
     #[test]
     fn cautious_ai_vetoes_a_losing_attack() {
         let player = e(1);
@@ -356,4 +418,96 @@ mod tests {
             "expansionist should move into the empty city site: grab {grab_score} vs hold {hold_score}"
         );
     }
+
+    /// `home` is an island port; `coast` is empty land across the water.
+    fn island_ferry_world(
+        home_has_free_land: bool,
+    ) -> (Entity, Entity, HashMap<Entity, AreaSummary>) {
+        let (home, coast, beach) = (e(10), e(20), e(30));
+        let mut areas = HashMap::default();
+        areas.insert(
+            home,
+            AreaSummary {
+                max_population: 3,
+                my_pop: 3,
+                neighbours: if home_has_free_land {
+                    vec![beach]
+                } else {
+                    vec![]
+                },
+                sea_neighbours: vec![coast],
+                ..Default::default()
+            },
+        );
+        areas.insert(
+            coast,
+            AreaSummary {
+                max_population: 4,
+                sea_neighbours: vec![home],
+                ..Default::default()
+            },
+        );
+        areas.insert(
+            beach,
+            AreaSummary {
+                max_population: 2,
+                neighbours: vec![home],
+                ..Default::default()
+            },
+        );
+        (home, coast, areas)
+    }
+
+    #[test]
+    fn islanders_are_keener_to_take_ship() {
+        let player = e(1);
+        let w = Personality::from_playstyle(Playstyle::Turtle).weights;
+        let (home, coast, island) = island_ferry_world(false);
+        let (_, _, peninsula) = island_ferry_world(true);
+        let ferry = GameMove::ShipFerry(MovementMove::new(home, coast, player, 2));
+
+        let boxed = score_movement(&ferry, player, &island, &w);
+        assert!(boxed > score_movement(&ferry, player, &peninsula, &w));
+        assert!(boxed > score_movement(&GameMove::EndMovement, player, &island, &w));
+    }
+
+    #[test]
+    fn open_sea_is_worth_only_the_coast_beyond_it() {
+        let player = e(1);
+        let (home, open, far) = (e(10), e(20), e(30));
+        let mut areas = HashMap::default();
+        areas.insert(
+            home,
+            AreaSummary {
+                max_population: 3,
+                my_pop: 3,
+                sea_neighbours: vec![open],
+                ..Default::default()
+            },
+        );
+        areas.insert(
+            open,
+            AreaSummary {
+                is_open_sea: true,
+                sea_neighbours: vec![home, far],
+                ..Default::default()
+            },
+        );
+        areas.insert(
+            far,
+            AreaSummary {
+                max_population: 5,
+                sea_neighbours: vec![open],
+                ..Default::default()
+            },
+        );
+        let w = Personality::from_playstyle(Playstyle::Expansionist).weights;
+        let to_sea = GameMove::ShipFerry(MovementMove::new(home, open, player, 2));
+        let hold = score_movement(&GameMove::EndMovement, player, &areas, &w);
+        assert!(score_movement(&to_sea, player, &areas, &w) > hold);
+
+        // Someone got there first: the open sea leads nowhere.
+        areas.get_mut(&far).unwrap().enemy_pop = 2;
+        assert!(score_movement(&to_sea, player, &areas, &w) < hold);
+    }
 }
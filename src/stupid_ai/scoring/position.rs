@@ -0,0 +1,434 @@
+//! Board-state evaluation: how well placed a faction is, as one number.
+//! Where the move scorers rate a single move, this rates the whole position,
+//! so it can compare players (the "who's winning" line on the A.S.T. panel),
+//! rank unfinished tournament games, or score a position before and after a
+//! move.
+
+use crate::civilization::{
+    AST_FINISH, AstEpoch, AstPosition, AvailableCivCards, Census, CivCardName, CivCardType,
+    Credits, Faction, GameFaction, GameSaveData, PlayerCities, PlayerCivilizationCards,
+    PlayerTradeCards, TradeCard, TradeCardTrait, Treasury,
+};
+use crate::stupid_ai::{ScoreBreakdown, clamp01, saturating};
+use bevy::platform::collections::HashSet;
+use enumflags2::BitFlags;
+
+// What a full-strength term is worth; they add up to 1.0 for a player who is
+// at FINISH with everything else saturated.
+const AST_WEIGHT: f32 = 0.35;
+const GATE_WEIGHT: f32 = 0.15;
+const CITIES_WEIGHT: f32 = 0.15;
+const POPULATION_WEIGHT: f32 = 0.10;
+const TREASURY_WEIGHT: f32 = 0.05;
+const CIV_CARDS_WEIGHT: f32 = 0.10;
+const CREDITS_WEIGHT: f32 = 0.05;
+const HAND_WEIGHT: f32 = 0.05;
+/// Taken off for a hand full of calamities.
+const CALAMITY_WEIGHT: f32 = 0.15;
+
+/// City tokens in a stock (rule 6.1): nine cities is as good as it gets.
+const MAX_CITIES: f32 = 9.0;
+/// Population tokens in a stock (rule 6.1).
+const MAX_POPULATION: f32 = 55.0;
+/// Where each `saturating` curve is half way.
+const TREASURY_HALF: f32 = 10.0;
+const CIV_POINTS_HALF: f32 = 600.0;
+const CREDITS_HALF: f32 = 150.0;
+const HAND_HALF: f32 = 40.0;
+const CALAMITY_HALF: f32 = 8.0;
+
+/// What the evaluation reads about one player. Build it from a save
+/// ([`PositionSnapshot::from_save`]), the live components
+/// ([`PlayerPosition::from_components`]) or by hand in a test.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PlayerPosition {
+    pub faction: GameFaction,
+    pub ast_space: u32,
+    pub cities: usize,
+    pub population: usize,
+    pub treasury: usize,
+    pub civ_cards: usize,
+    /// Distinct colour groups among the civ cards (rule 31.551).
+    pub card_groups: usize,
+    /// Face value of the civ cards, as in final scoring (rule 35.1A).
+    pub civ_card_points: u32,
+    /// Sum of all credits the civ cards grant towards further purchases.
+    pub credits: u32,
+    pub trade_cards: Vec<(TradeCard, usize)>,
+}
+
+/// The live components [`PlayerPosition::from_components`] reads.
+pub type PositionQueryData = (
+    &'static Faction,
+    &'static AstPosition,
+    &'static PlayerCities,
+    &'static Census,
+    &'static Treasury,
+    &'static PlayerTradeCards,
+    Option<&'static PlayerCivilizationCards>,
+);
+
+impl PlayerPosition {
+    pub fn from_components(
+        (faction, ast, cities, census, treasury, trade_cards, civ_cards): (
+            &Faction,
+            &AstPosition,
+            &PlayerCities,
+            &Census,
+            &Treasury,
+            &PlayerTradeCards,
+            Option<&PlayerCivilizationCards>,
+        ),
+        defs: Option<&AvailableCivCards>,
+    ) -> Self {
+        let owned: Vec<CivCardName> =
+            civ_cards.map_or_else(Vec::new, |c| c.cards.iter().copied().collect());
+        PlayerPosition {
+            faction: faction.faction,
+            ast_space: ast.space,
+            cities: cities.number_of_cities(),
+            population: census.population,
+            treasury: treasury.tokens_in_treasury(),
+            trade_cards: trade_cards.cards_as_vec(),
+            ..Default::default()
+        }
+        .with_civ_cards(&owned, defs)
+    }
+
+    /// Fill in the civ-card fields from the cards owned. Without definitions
+    /// only the count is known.
+    pub fn with_civ_cards(
+        mut self,
+        owned: &[CivCardName],
+        defs: Option<&AvailableCivCards>,
+    ) -> Self {
+        self.civ_cards = owned.len();
+        let Some(defs) = defs else {
+            return self;
+        };
+        let owned: HashSet<CivCardName> = owned.iter().copied().collect();
+        let cards = defs.cards_for_names(&owned);
+        self.card_groups = cards
+            .iter()
+            .fold(BitFlags::<CivCardType>::empty(), |groups, card| {
+                groups | card.card_type
+            })
+            .len();
+        self.civ_card_points = cards.iter().map(|card| card.cost).sum();
+        self.credits = defs
+            .total_credits(&owned)
+            .iter()
+            .map(|credit| match credit {
+                Credits::ToType(_, value)
+                | Credits::ToAll(value)
+                | Credits::ToSpecificCard(_, value) => *value,
+            })
+            .sum();
+        self
+    }
+
+    /// How close the player is to passing the next epoch gate (rule 33.2),
+    /// from 0 to 1: the mean of cities, card groups and card count over what
+    /// the gate asks for. 1 past the last gate.
+    pub fn gate_readiness(&self) -> f32 {
+        let Some(gate) = AstEpoch::for_space(self.ast_space).next() else {
+            return 1.0;
+        };
+        let requirements = [
+            (self.cities, gate.min_cities()),
+            (self.card_groups, gate.min_card_groups()),
+            (self.civ_cards, gate.min_card_count()),
+        ];
+        let (sum, count) = requirements.iter().filter(|(_, needed)| *needed > 0).fold(
+            (0.0, 0),
+            |(sum, count), (have, needed)| {
+                (sum + clamp01(*have as f32 / *needed as f32), count + 1)
+            },
+        );
+        if count == 0 { 1.0 } else { sum / count as f32 }
+    }
+
+    /// Commodity set value (count² × face value, rule 35.1B).
+    pub fn hand_value(&self) -> usize {
+        self.trade_cards
+            .iter()
+            .filter(|(card, _)| card.is_commodity())
+            .map(|(card, count)| count * count * card.value())
+            .sum()
+    }
+
+    /// Calamities held, by face value. Tradeable ones count half: they may
+    /// still be passed on before they strike.
+    pub fn calamity_exposure(&self) -> f32 {
+        self.trade_cards
+            .iter()
+            .filter(|(card, _)| card.is_calamity())
+            .map(|(card, count)| {
+                let value = (*count * card.value()) as f32;
+                if card.is_tradeable() {
+                    value / 2.0
+                } else {
+                    value
+                }
+            })
+            .sum()
+    }
+}
+
+/// Every player's [`PlayerPosition`] at one moment.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PositionSnapshot {
+    pub players: Vec<PlayerPosition>,
+}
+
+impl PositionSnapshot {
+    pub fn from_save(save: &GameSaveData, defs: Option<&AvailableCivCards>) -> Self {
+        let players = save
+            .players
+            .iter()
+            .map(|player| {
+                PlayerPosition {
+                    faction: player.faction,
+                    ast_space: player.ast_space,
+                    cities: save
+                        .area_populations
+                        .iter()
+                        .filter(|area| area.city_owner == Some(player.faction))
+                        .count(),
+                    population: player.census_population,
+                    treasury: player.treasury,
+                    trade_cards: player.trade_cards.clone(),
+                    ..Default::default()
+                }
+                .with_civ_cards(&player.owned_civ_cards, defs)
+            })
+            .collect();
+        PositionSnapshot { players }
+    }
+
+    pub fn player(&self, faction: GameFaction) -> Option<&PlayerPosition> {
+        self.players.iter().find(|p| p.faction == faction)
+    }
+
+    /// Every player with their evaluation, best first.
+    pub fn ranking(&self) -> Vec<(GameFaction, f32)> {
+        let mut ranking: Vec<(GameFaction, f32)> = self
+            .players
+            .iter()
+            .map(|p| (p.faction, explain_position(p).total()))
+            .collect();
+        ranking.sort_by(|a, b| b.1.total_cmp(&a.1).then((a.0 as u8).cmp(&(b.0 as u8))));
+        ranking
+    }
+}
+
+/// How well placed `faction` is, roughly 0 (nothing yet) to 1 (at FINISH
+/// with a strong civilization), a little below 0 under a pile of calamities.
+/// `NEG_INFINITY` for a faction not in the snapshot.
+pub fn evaluate_position(faction: GameFaction, snapshot: &PositionSnapshot) -> f32 {
+    snapshot
+        .player(faction)
+        .map_or(f32::NEG_INFINITY, |p| explain_position(p).total())
+}
+
+/// [`evaluate_position`] term by term.
+pub fn explain_position(p: &PlayerPosition) -> ScoreBreakdown {
+    let mut breakdown = ScoreBreakdown::default();
+    breakdown.add(
+        "ast",
+        AST_WEIGHT * clamp01(p.ast_space as f32 / AST_FINISH as f32),
+    );
+    breakdown.add("gate", GATE_WEIGHT * p.gate_readiness());
+    breakdown.add(
+        "cities",
+        CITIES_WEIGHT * clamp01(p.cities as f32 / MAX_CITIES),
+    );
+    breakdown.add(
+        "population",
+        POPULATION_WEIGHT * clamp01(p.population as f32 / MAX_POPULATION),
+    );
+    breakdown.add(
+        "treasury",
+        TREASURY_WEIGHT * saturating(p.treasury as f32, TREASURY_HALF),
+    );
+    breakdown.add(
+        "civ_cards",
+        CIV_CARDS_WEIGHT * saturating(p.civ_card_points as f32, CIV_POINTS_HALF),
+    );
+    breakdown.add(
+        "credits",
+        CREDITS_WEIGHT * saturating(p.credits as f32, CREDITS_HALF),
+    );
+    breakdown.add(
+        "hand",
+        HAND_WEIGHT * saturating(p.hand_value() as f32, HAND_HALF),
+    );
+    breakdown.add(
+        "calamities",
+        -CALAMITY_WEIGHT * saturating(p.calamity_exposure(), CALAMITY_HALF),
+    );
+    breakdown
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::civilization::CivCardDefinition;
+
+    fn player(faction: GameFaction) -> PlayerPosition {
+        PlayerPosition {
+            faction,
+            ..Default::default()
+        }
+    }
+
+    fn snapshot(players: Vec<PlayerPosition>) -> PositionSnapshot {
+        PositionSnapshot { players }
+    }
+
+    fn card(
+        name: CivCardName,
+        card_type: CivCardType,
+        cost: u32,
+        credit: u32,
+    ) -> CivCardDefinition {
+        CivCardDefinition {
+            name,
+            description: String::new(),
+            card_type: card_type.into(),
+            cost,
+            credits: vec![Credits::ToType(card_type, credit)],
+            prerequisites: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn a_player_further_along_the_ast_is_ahead() {
+        let behind = PlayerPosition {
+            ast_space: 3,
+            ..player(GameFaction::Egypt)
+        };
+        let ahead = PlayerPosition {
+            ast_space: 7,
+            cities: 3,
+            ..player(GameFaction::Crete)
+        };
+        let snap = snapshot(vec![behind, ahead]);
+        assert!(
+            evaluate_position(GameFaction::Crete, &snap)
+                > evaluate_position(GameFaction::Egypt, &snap)
+        );
+        assert_eq!(snap.ranking()[0].0, GameFaction::Crete);
+        assert_eq!(
+            evaluate_position(GameFaction::Babylon, &snap),
+            f32::NEG_INFINITY
+        );
+    }
+
+    #[test]
+    fn gate_readiness_follows_the_next_epochs_requirements() {
+        // Stone Age: the next gate (Early Bronze) wants 2 cities.
+        let stone = PlayerPosition {
+            ast_space: 4,
+            cities: 1,
+            ..player(GameFaction::Egypt)
+        };
+        assert!((stone.gate_readiness() - 0.5).abs() < 1e-6);
+
+        // Early Bronze: Late Bronze wants 3 cities and 3 groups.
+        let bronze = PlayerPosition {
+            ast_space: 6,
+            cities: 3,
+            card_groups: 0,
+            ..player(GameFaction::Egypt)
+        };
+        assert!((bronze.gate_readiness() - 0.5).abs() < 1e-6);
+        let ready = PlayerPosition {
+            card_groups: 4,
+            ..bronze.clone()
+        };
+        assert_eq!(ready.gate_readiness(), 1.0);
+        assert!(
+            explain_position(&ready).total() > explain_position(&bronze).total(),
+            "meeting the gate is worth something"
+        );
+
+        // Past the last gate there is nothing left to meet.
+        let late = PlayerPosition {
+            ast_space: 15,
+            ..player(GameFaction::Egypt)
+        };
+        assert_eq!(late.gate_readiness(), 1.0);
+    }
+
+    #[test]
+    fn calamities_drag_the_position_down_untradeable_ones_most() {
+        let clean = PlayerPosition {
+            cities: 2,
+            population: 20,
+            ..player(GameFaction::Egypt)
+        };
+        let tradeable = PlayerPosition {
+            trade_cards: vec![(TradeCard::Epidemic, 1)],
+            ..clean.clone()
+        };
+        let stuck = PlayerPosition {
+            trade_cards: vec![(TradeCard::Famine, 1), (TradeCard::Flood, 1)],
+            ..clean.clone()
+        };
+        let value = |p: &PlayerPosition| explain_position(p).total();
+        assert!(value(&tradeable) < value(&clean));
+        assert!(value(&stuck) < value(&tradeable));
+        assert!(
+            explain_position(&stuck)
+                .terms
+                .iter()
+                .any(|(name, v)| *name == "calamities" && *v < 0.0)
+        );
+    }
+
+    #[test]
+    fn civ_cards_count_groups_points_and_credits() {
+        let defs = AvailableCivCards {
+            cards: vec![
+                card(CivCardName::Pottery, CivCardType::Crafts, 45, 10),
+                card(CivCardName::Mysticism, CivCardType::Religion, 50, 5),
+                card(CivCardName::Law, CivCardType::Civics, 60, 10),
+            ],
+        };
+        let p = player(GameFaction::Egypt)
+            .with_civ_cards(&[CivCardName::Pottery, CivCardName::Mysticism], Some(&defs));
+        assert_eq!(p.civ_cards, 2);
+        assert_eq!(p.card_groups, 2);
+        assert_eq!(p.civ_card_points, 95);
+        assert_eq!(p.credits, 15);
+
+        let without_defs = player(GameFaction::Egypt).with_civ_cards(&[CivCardName::Pottery], None);
+        assert_eq!(without_defs.civ_cards, 1);
+        assert_eq!(without_defs.civ_card_points, 0);
+    }
+
+    #[test]
+    fn the_terms_sum_to_the_evaluation_and_stay_in_range() {
+        let maxed = PlayerPosition {
+            ast_space: AST_FINISH,
+            cities: 9,
+            population: 55,
+            treasury: 1000,
+            civ_cards: 24,
+            card_groups: 5,
+            civ_card_points: 100_000,
+            credits: 100_000,
+            trade_cards: vec![(TradeCard::Gold, 8)],
+            ..player(GameFaction::Egypt)
+        };
+        let snap = snapshot(vec![maxed.clone(), player(GameFaction::Crete)]);
+        let top = evaluate_position(GameFaction::Egypt, &snap);
+        let sum: f32 = explain_position(&maxed).terms.iter().map(|(_, v)| v).sum();
+        assert!((top - sum).abs() < 1e-6);
+        assert!(top <= 1.0 && top > 0.95, "{top}");
+        // A fresh player has nothing yet, not even a city towards the first gate.
+        let fresh = evaluate_position(GameFaction::Crete, &snap);
+        assert!((0.0..0.1).contains(&fresh), "{fresh}");
+    }
+}
@@ -0,0 +1,361 @@
+//! Ship construction (rule 22) and how far a ship carries tokens (rule 23).
+//! A ship is only worth its two tokens and its upkeep if it reaches coast
+//! the player cannot walk to; island factions such as Crete have no other
+//! way off their home areas.
+
+use super::{AreaSummary, ScoreBreakdown, enemy_pressure, saturating};
+use crate::stupid_ai::Weights;
+use bevy::platform::collections::{HashMap, HashSet};
+use bevy::prelude::Entity;
+
+/// Tokens a new ship costs, from treasury and/or a levy (rule 22.1).
+pub const SHIP_COST: usize = 2;
+
+/// How far a player's ships sail in one move.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ShipReach {
+    /// Sea hops per move: 1, or 2 with Cloth Making (rule 28.18).
+    pub hops: usize,
+    /// Astronomy lets ships enter open sea (rule 28.23).
+    pub open_sea: bool,
+}
+
+impl ShipReach {
+    pub fn for_cards(cloth_making: bool, astronomy: bool) -> Self {
+        ShipReach {
+            hops: if cloth_making { 2 } else { 1 },
+            open_sea: astronomy,
+        }
+    }
+}
+
+/// Every area a ship leaving `from` can end its move in, with the number of
+/// hops it takes. Open sea is neither a stop nor a waypoint without Astronomy.
+pub fn sea_reach(
+    from: Entity,
+    areas: &HashMap<Entity, AreaSummary>,
+    reach: ShipReach,
+) -> HashMap<Entity, usize> {
+    let mut reached: HashMap<Entity, usize> = HashMap::default();
+    let mut frontier = vec![from];
+    for hop in 1..=reach.hops {
+        let mut next = Vec::new();
+        for area in frontier {
+            let Some(summary) = areas.get(&area) else {
+                continue;
+            };
+            for &to in &summary.sea_neighbours {
+                let blocked = !reach.open_sea && areas.get(&to).is_some_and(|s| s.is_open_sea);
+                if to == from || blocked || reached.contains_key(&to) {
+                    continue;
+                }
+                reached.insert(to, hop);
+                next.push(to);
+            }
+        }
+        frontier = next;
+    }
+    reached
+}
+
+/// Empty land a ship from `from` can put tokens on that they could not walk
+/// to: not open sea, not already ours, and not a land neighbour of `from`.
+pub fn landfall_targets(
+    from: Entity,
+    areas: &HashMap<Entity, AreaSummary>,
+    reach: ShipReach,
+) -> Vec<Entity> {
+    let walkable: HashSet<Entity> = areas
+        .get(&from)
+        .map(|s| s.neighbours.iter().copied().collect())
+        .unwrap_or_default();
+    let mut targets: Vec<Entity> = sea_reach(from, areas, reach)
+        .into_keys()
+        .filter(|to| !walkable.contains(to))
+        .filter(|to| {
+            areas
+                .get(to)
+                .is_some_and(|s| !s.is_open_sea && s.is_empty() && s.max_population > 0)
+        })
+        .collect();
+    targets.sort();
+    targets
+}
+
+/// No empty land next door: every land neighbour is held by someone, or
+/// there is none at all. Tokens here only grow further by sea.
+pub fn boxed_in(area: Entity, areas: &HashMap<Entity, AreaSummary>) -> bool {
+    areas.get(&area).is_some_and(|s| {
+        s.neighbours
+            .iter()
+            .all(|n| areas.get(n).is_none_or(|ns| !ns.is_empty()))
+    })
+}
+
+/// Score building one ship in `area` with `treasury` tokens to spend first.
+/// The ship is worth the coast it opens up, more so when the area is boxed
+/// in; against that stand the treasury tokens, the levied tokens that would
+/// otherwise grow or defend, and a token of upkeep every turn (rule 22.3).
+pub fn score_ship_build(
+    area: Entity,
+    treasury: usize,
+    ships_here: usize,
+    areas: &HashMap<Entity, AreaSummary>,
+    reach: ShipReach,
+    w: &Weights,
+) -> f32 {
+    explain_ship_build(area, treasury, ships_here, areas, reach, w).total()
+}
+
+/// [`score_ship_build`], consideration by consideration.
+pub fn explain_ship_build(
+    area: Entity,
+    treasury: usize,
+    ships_here: usize,
+    areas: &HashMap<Entity, AreaSummary>,
+    reach: ShipReach,
+    w: &Weights,
+) -> ScoreBreakdown {
+    let Some(summary) = areas.get(&area) else {
+        return ScoreBreakdown::fixed("not an area", f32::NEG_INFINITY);
+    };
+    if !summary.is_coastal() {
+        return ScoreBreakdown::fixed("not coastal", f32::NEG_INFINITY);
+    }
+    let (from_treasury, from_levy) = ship_cost_split(treasury);
+    // Never levy the last token: that would empty the area for a ship.
+    if from_levy > summary.my_pop.saturating_sub(1) {
+        return ScoreBreakdown::fixed("unaffordable", f32::NEG_INFINITY);
+    }
+
+    let mut score = ScoreBreakdown::default();
+    let landfalls = landfall_targets(area, areas, reach).len() as f32;
+    if landfalls > 0.0 {
+        // A second ship in the same port opens up nothing new.
+        let fresh = 1.0 / (1 + ships_here) as f32;
+        score.add(
+            "expansion",
+            w.expansion * 1.2 * saturating(landfalls, 2.0) * fresh,
+        );
+        if boxed_in(area, areas) {
+            score.add("expansion", w.expansion * 0.6 * fresh);
+        }
+    }
+    score.add("treasury", -0.05 * from_treasury as f32);
+    let pressure = saturating(enemy_pressure(area, areas) as f32, 4.0);
+    score.add(
+        "levy",
+        -(0.1 * (0.5 + w.growth) + 0.2 * w.defense * pressure) * from_levy as f32,
+    );
+    score.add("upkeep", -0.1);
+    score
+}
+
+/// How a ship's cost is paid: treasury first, the rest levied from the build
+/// area (rule 22.1). Returns `(from_treasury, from_levy)`.
+pub fn ship_cost_split(treasury: usize) -> (usize, usize) {
+    let from_treasury = treasury.min(SHIP_COST);
+    (from_treasury, SHIP_COST - from_treasury)
+}
+
+/// One ship the AI decided to build.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ShipBuild {
+    pub area: Entity,
+    pub from_treasury: usize,
+    pub from_levy: usize,
+    pub breakdown: ScoreBreakdown,
+}
+
+/// Pick up to `buildable` ships for the coastal `candidates`, best first,
+/// while one is still worth more than keeping the tokens. Each build spends
+/// treasury and levied tokens before the next is scored.
+pub fn plan_ship_builds(
+    candidates: &[Entity],
+    mut treasury: usize,
+    ships_by_area: &HashMap<Entity, usize>,
+    buildable: usize,
+    areas: &HashMap<Entity, AreaSummary>,
+    reach: ShipReach,
+    w: &Weights,
+) -> Vec<ShipBuild> {
+    let mut areas = areas.clone();
+    let mut ships = ships_by_area.clone();
+    let mut builds = Vec::new();
+    while builds.len() < buildable {
+        let best = candidates
+            .iter()
+            .map(|&area| {
+                let here = ships.get(&area).copied().unwrap_or(0);
+                (
+                    area,
+                    explain_ship_build(area, treasury, here, &areas, reach, w),
+                )
+            })
+            .filter(|(_, breakdown)| breakdown.total() > 0.0)
+            .reduce(|best, next| {
+                if next.1.total() > best.1.total() {
+                    next
+                } else {
+                    best
+                }
+            });
+        let Some((area, breakdown)) = best else {
+            break;
+        };
+        let (from_treasury, from_levy) = ship_cost_split(treasury);
+        treasury -= from_treasury;
+        if let Some(summary) = areas.get_mut(&area) {
+            summary.my_pop -= from_levy;
+        }
+        *ships.entry(area).or_insert(0) += 1;
+        builds.push(ShipBuild {
+            area,
+            from_treasury,
+            from_levy,
+            breakdown,
+        });
+    }
+    builds
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stupid_ai::{Personality, Playstyle};
+
+    fn e(i: u32) -> Entity {
+        Entity::from_raw_u32(i).unwrap()
+    }
+
+    /// Crete-like: `home` is a full island port with no land neighbours;
+    /// `open` is open sea between it and the empty mainland coast `far`,
+    /// and `near` is an empty coast one hop away.
+    fn island_world() -> (Entity, Entity, Entity, Entity, HashMap<Entity, AreaSummary>) {
+        let (home, near, open, far) = (e(10), e(20), e(30), e(40));
+        let mut areas = HashMap::default();
+        areas.insert(
+            home,
+            AreaSummary {
+                max_population: 3,
+                my_pop: 3,
+                sea_neighbours: vec![near, open],
+                ..Default::default()
+            },
+        );
+        areas.insert(
+            near,
+            AreaSummary {
+                max_population: 2,
+                sea_neighbours: vec![home],
+                ..Default::default()
+            },
+        );
+        areas.insert(
+            open,
+            AreaSummary {
+                is_open_sea: true,
+                sea_neighbours: vec![home, far],
+                ..Default::default()
+            },
+        );
+        areas.insert(
+            far,
+            AreaSummary {
+                max_population: 5,
+                is_city_site: true,
+                sea_neighbours: vec![open],
+                ..Default::default()
+            },
+        );
+        (home, near, open, far, areas)
+    }
+
+    #[test]
+    fn cloth_making_and_astronomy_widen_the_reach() {
+        let (home, near, open, far, areas) = island_world();
+
+        let plain = sea_reach(home, &areas, ShipReach::for_cards(false, false));
+        assert_eq!(plain.keys().copied().collect::<Vec<_>>(), vec![near]);
+
+        // Astronomy alone: into the open sea, but not across it.
+        let astronomy = sea_reach(home, &areas, ShipReach::for_cards(false, true));
+        assert_eq!(astronomy.get(&open), Some(&1));
+        assert!(!astronomy.contains_key(&far));
+
+        // Both: across the open sea in one move, the extra hop from Cloth Making.
+        let both = sea_reach(home, &areas, ShipReach::for_cards(true, true));
+        assert_eq!(both.get(&far), Some(&2));
+        assert_eq!(
+            landfall_targets(home, &areas, ShipReach::for_cards(true, true)),
+            vec![near, far]
+        );
+    }
+
+    #[test]
+    fn a_boxed_in_island_builds_a_ship_even_on_a_levy() {
+        let (home, _, _, _, areas) = island_world();
+        let w = Personality::from_playstyle(Playstyle::Balanced).weights;
+        assert!(boxed_in(home, &areas));
+
+        let plan = plan_ship_builds(
+            &[home],
+            0,
+            &HashMap::default(),
+            4,
+            &areas,
+            ShipReach::for_cards(false, false),
+            &w,
+        );
+        // Three tokens leave two to levy: one ship, and the last token stays.
+        assert_eq!(plan.len(), 1);
+        assert_eq!((plan[0].from_treasury, plan[0].from_levy), (0, 2));
+    }
+
+    #[test]
+    fn no_ship_where_the_sea_leads_nowhere_new() {
+        let (home, near) = (e(10), e(20));
+        let mut areas = HashMap::default();
+        areas.insert(
+            home,
+            AreaSummary {
+                max_population: 5,
+                my_pop: 5,
+                neighbours: vec![near],
+                sea_neighbours: vec![near],
+                ..Default::default()
+            },
+        );
+        // The only port in reach is a short walk away anyway.
+        areas.insert(
+            near,
+            AreaSummary {
+                max_population: 3,
+                neighbours: vec![home],
+                sea_neighbours: vec![home],
+                ..Default::default()
+            },
+        );
+        let w = Personality::from_playstyle(Playstyle::Expansionist).weights;
+        let reach = ShipReach::for_cards(false, false);
+        assert!(score_ship_build(home, 4, 0, &areas, reach, &w) < 0.0);
+        assert!(plan_ship_builds(&[home], 4, &HashMap::default(), 4, &areas, reach, &w).is_empty());
+    }
+
+    #[test]
+    fn inland_or_unaffordable_areas_are_never_built_in() {
+        let (home, _, _, _, mut areas) = island_world();
+        let w = Personality::from_playstyle(Playstyle::Expansionist).weights;
+        let reach = ShipReach::for_cards(false, false);
+        areas.get_mut(&home).unwrap().my_pop = 1;
+        assert_eq!(
+            score_ship_build(home, 0, 0, &areas, reach, &w),
+            f32::NEG_INFINITY
+        );
+        areas.get_mut(&home).unwrap().sea_neighbours.clear();
+        assert_eq!(
+            score_ship_build(home, 4, 0, &areas, reach, &w),
+            f32::NEG_INFINITY
+        );
+    }
+}
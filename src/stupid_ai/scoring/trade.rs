@@ -1,35 +1,400 @@
 //! Personality knobs for the (countdown-driven) AI trade systems in
 //! `concepts/trade`. Trade is hidden-information and multi-step, so rather than a
 //! single move-score we expose small decision helpers the existing `ai_*` trade
-//! systems consult. See `docs/utility-ai-design.md` §4 (Trade).
+//! systems consult. See `docs/utility-ai-design.md` §4 (Trade). A personality's
+//! `TradeKnobs` override any of them outright.
+//!
+//! Trades are aimed at a [`TradeGoal`]: the civ-card purchase the AI plans to
+//! make if its commodity sets grow a little. Offers ask for the cards whose set
+//! completion adds the most buying power (count² × face value), and offers are
+//! accepted on what they do for that purchase.
 
-use crate::stupid_ai::Weights;
+use super::{PurchasePlanInput, ScoreBreakdown, plan_civ_card_purchase};
+use crate::civilization::{
+    AstPosition, AvailableCivCards, CivCardName, PlayerCivilizationCards, PlayerTradeCards,
+    TradeCard, TradeCardTrait,
+};
+use crate::stupid_ai::{NEUTRAL_TRUST, Personality};
+use bevy::platform::collections::HashMap;
+use std::cmp::Reverse;
+
+/// Stack value one offloaded calamity is worth to a fully calamity-averse
+/// player: about a three-card set of mid-value commodities.
+const CALAMITY_OFFLOAD_VALUE: f32 = 15.0;
+/// Stack value of reaching (or losing) the planned purchase, for a player
+/// with no tech focus; doubles at full `tech_focus`.
+const GOAL_VALUE: f32 = 8.0;
+/// Margin swing between full trust and none, in stack value.
+const DISTRUST_MARGIN: f32 = 6.0;
 
 /// Extra stack-value gain (beyond break-even) an AI demands before accepting an
 /// offer. Eager traders (high `trade_drive`) take marginal trades; reluctant ones
-/// hold out for a clear profit.
-pub fn trade_accept_margin(w: &Weights) -> f32 {
+/// hold out for a clear profit. `trust` in the offering player (see
+/// `TradeTrust`) shifts it: a known cheat's hidden cards are priced as junk,
+/// a reliable partner gets the benefit of the doubt.
+pub fn trade_accept_margin(p: &Personality, trust: f32) -> f32 {
     // trade_drive 1.0 -> 0 margin (accept any non-losing trade)
     // trade_drive 0.0 -> require +3 stack value
-    (1.0 - w.trade_drive) * 3.0
+    let base = p
+        .trade
+        .accept_margin
+        .unwrap_or((1.0 - p.weights.trade_drive) * 3.0);
+    // trust 0 -> +3 stack value, trust 1 -> -3
+    (base + (NEUTRAL_TRUST - trust.clamp(0.0, 1.0)) * DISTRUST_MARGIN).max(0.0)
 }
 
 /// Whether this AI bothers to take a trade purely to offload a calamity card.
 /// Calamity-averse players jump at it; the carefree ignore the risk.
-pub fn accepts_calamity_offload(w: &Weights) -> bool {
-    w.calamity_aversion >= 0.4
+pub fn accepts_calamity_offload(p: &Personality) -> bool {
+    p.trade
+        .offload_calamities
+        .unwrap_or(p.weights.calamity_aversion >= 0.4)
 }
 
 /// Probability [0,1] that the AI creates a fresh offer this tick. Merchants flood
 /// the table with offers; turtles/warlords mostly sit out the trade phase.
-pub fn offer_creation_chance(w: &Weights) -> f32 {
-    (0.25 + 0.75 * w.trade_drive).clamp(0.0, 1.0)
+pub fn offer_creation_chance(p: &Personality) -> f32 {
+    p.trade
+        .offer_chance
+        .unwrap_or(0.25 + 0.75 * p.weights.trade_drive)
+        .clamp(0.0, 1.0)
 }
 
 /// Trade-phase countdown value below which this AI stops trading. The phase counts
 /// down from 90; a higher threshold = stops sooner. Eager traders keep going.
-pub fn stop_trading_threshold(w: &Weights) -> f32 {
+pub fn stop_trading_threshold(p: &Personality) -> f32 {
     // trade_drive 1.0 -> 80 (trade until the last ~10s)
     // trade_drive 0.0 -> 88 (bail after ~2s)
-    88.0 - 8.0 * w.trade_drive
+    p.trade
+        .stop_trading_at
+        .unwrap_or(88.0 - 8.0 * p.weights.trade_drive)
+}
+
+/// How an AI fills the hidden part of a trade (the cards beyond the two it
+/// must state truthfully).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HiddenCardPlay {
+    /// Hidden cards worth at least the cheapest stated card; no calamities.
+    Straight,
+    /// Promise an extra hidden card, then pay every hidden slot with the
+    /// cheapest commodities.
+    Bluff,
+    /// Pass tradable calamities on in the hidden slots, junk for the rest.
+    SlipCalamity,
+}
+
+/// Below this `risk` an AI never deceives.
+const HONEST_BELOW_RISK: f32 = 0.3;
+
+/// How this AI plays the hidden cards against a player it trusts `trust`
+/// (see `TradeTrust`). Cautious players play it straight; daring ones
+/// deceive players who have not earned their trust, tit for tat, and the
+/// calamity-averse among them use the hidden slots to pass a calamity on.
+pub fn hidden_card_play(
+    p: &Personality,
+    trust: f32,
+    holds_tradeable_calamity: bool,
+) -> HiddenCardPlay {
+    if p.weights.risk < HONEST_BELOW_RISK {
+        return HiddenCardPlay::Straight;
+    }
+    // risk 0.5 against a stranger -> 0.5; anyone fully trusted -> 0.
+    let appetite = 2.0 * p.weights.risk * (1.0 - trust.clamp(0.0, 1.0));
+    if holds_tradeable_calamity && accepts_calamity_offload(p) && appetite >= 0.25 {
+        HiddenCardPlay::SlipCalamity
+    } else if appetite >= 0.4 {
+        HiddenCardPlay::Bluff
+    } else {
+        HiddenCardPlay::Straight
+    }
+}
+
+/// The civ-card purchase an AI trades towards.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TradeGoal {
+    pub cards: Vec<CivCardName>,
+    /// Buying power the purchase takes.
+    pub cost: u32,
+}
+
+impl TradeGoal {
+    /// Buying power still missing; zero once the purchase is affordable.
+    pub fn shortfall(&self, buying_power: u32) -> u32 {
+        self.cost.saturating_sub(buying_power)
+    }
+}
+
+/// Stack value `hand` gains from `extra` more copies of `card`, never
+/// counting past the number of copies in the game.
+pub fn set_completion_gain(hand: &PlayerTradeCards, card: TradeCard, extra: usize) -> u32 {
+    if !card.is_commodity() {
+        return 0;
+    }
+    let held = hand.number_of_cards_for_trade_card(card);
+    let after = (held + extra).min(card.number_of_cards().max(held));
+    ((after * after - held * held) * card.value()) as u32
+}
+
+/// The most two more cards could add to the hand: the best set completion a
+/// single trade can bring.
+pub fn trade_reach(hand: &PlayerTradeCards) -> u32 {
+    hand.commodities()
+        .into_iter()
+        .map(|card| set_completion_gain(hand, card, 2))
+        .max()
+        .unwrap_or(0)
+}
+
+/// The purchase a trade could unlock: the purchase plan for the hand's buying
+/// power plus its [`trade_reach`]. `None` when nothing is worth buying.
+pub fn trade_goal(
+    hand: &PlayerTradeCards,
+    civ_cards: Option<&PlayerCivilizationCards>,
+    ast: Option<&AstPosition>,
+    defs: &AvailableCivCards,
+    p: &Personality,
+) -> Option<TradeGoal> {
+    let owned = civ_cards.map(|c| c.cards.clone()).unwrap_or_default();
+    let input = PurchasePlanInput {
+        held_before: owned.clone(),
+        owned,
+        ast_space: ast.map_or(0, |a| a.space),
+        calamities: hand.calamity_cards().into_iter().collect(),
+        budget: hand.total_stack_value() as u32 + trade_reach(hand),
+    };
+    let plan = plan_civ_card_purchase(&input, defs, &p.weights);
+    (!plan.cards.is_empty()).then_some(TradeGoal {
+        cards: plan.cards,
+        cost: plan.cost,
+    })
+}
+
+/// The `count` cards to ask for, the ones closing most of `goal`'s shortfall
+/// first. Past the shortfall more buying power buys nothing, so of the cards
+/// that close it the cheapest goes first: a partner parts with it most
+/// easily. Without a goal, or once it is affordable, most buying power
+/// first. A card may be asked for twice when the second copy still ranks
+/// best.
+pub fn goal_wants(
+    hand: &PlayerTradeCards,
+    goal: Option<&TradeGoal>,
+    count: usize,
+) -> HashMap<TradeCard, usize> {
+    let mut simulated = hand.clone();
+    let mut wants: HashMap<TradeCard, usize> = HashMap::default();
+    for _ in 0..count {
+        let shortfall = goal.map_or(0, |g| g.shortfall(simulated.total_stack_value() as u32));
+        let rank = |card: &TradeCard| {
+            let gain = set_completion_gain(&simulated, *card, 1);
+            if shortfall > 0 {
+                (gain.min(shortfall), Reverse(card.value()), gain)
+            } else {
+                (gain, Reverse(0), card.value() as u32)
+            }
+        };
+        let Some(card) = TradeCard::iter()
+            .filter(TradeCardTrait::is_commodity)
+            .filter(|card| set_completion_gain(&simulated, *card, 1) > 0)
+            .max_by_key(rank)
+        else {
+            break;
+        };
+        simulated.add_trade_card(card);
+        *wants.entry(card).or_insert(0) += 1;
+    }
+    wants
+}
+
+/// The `count` commodity cards whose loss costs the least buying power,
+/// never ones in `keep`. Empty when the hand cannot spare that many.
+pub fn goal_gives(
+    hand: &PlayerTradeCards,
+    count: usize,
+    keep: &HashMap<TradeCard, usize>,
+) -> HashMap<TradeCard, usize> {
+    let mut simulated = hand.clone();
+    let mut gives: HashMap<TradeCard, usize> = HashMap::default();
+    for _ in 0..count {
+        let loss = |card: &TradeCard| {
+            let held = simulated.number_of_cards_for_trade_card(*card);
+            (2 * held - 1) * card.value()
+        };
+        let Some(card) = simulated
+            .commodities()
+            .into_iter()
+            .filter(|card| !keep.contains_key(card))
+            .filter(|card| simulated.number_of_cards_for_trade_card(*card) > 0)
+            .min_by_key(|card| (loss(card), card.value(), *card as u8))
+        else {
+            return HashMap::default();
+        };
+        simulated.remove_n_trade_cards(1, card);
+        *gives.entry(card).or_insert(0) += 1;
+    }
+    gives
+}
+
+/// [`score_trade_for_goal`], consideration by consideration, in stack-value
+/// units so it compares directly with [`trade_accept_margin`].
+pub fn explain_trade_for_goal(
+    before: u32,
+    after: u32,
+    goal: Option<&TradeGoal>,
+    offloads_calamity: bool,
+    p: &Personality,
+) -> ScoreBreakdown {
+    let mut score = ScoreBreakdown::default();
+    score.add("buying power", after as f32 - before as f32);
+    if let Some(goal) = goal {
+        let stake = GOAL_VALUE * (1.0 + p.weights.tech_focus);
+        let (had, has) = (before >= goal.cost, after >= goal.cost);
+        if has && !had {
+            score.add("goal", stake);
+        } else if had && !has {
+            score.add("goal", -stake);
+        } else if !has {
+            // Closing part of the gap counts again: those cards are the ones
+            // the plan is short of.
+            score.add("goal", 0.5 * (after as f32 - before as f32));
+        }
+    }
+    if offloads_calamity && accepts_calamity_offload(p) {
+        score.add(
+            "calamity",
+            CALAMITY_OFFLOAD_VALUE * p.weights.calamity_aversion,
+        );
+    }
+    score
+}
+
+/// What a trade taking the hand's buying power from `before` to `after` is
+/// worth towards `goal`, plus any calamity it lets the player pass on.
+pub fn score_trade_for_goal(
+    before: u32,
+    after: u32,
+    goal: Option<&TradeGoal>,
+    offloads_calamity: bool,
+    p: &Personality,
+) -> f32 {
+    explain_trade_for_goal(before, after, goal, offloads_calamity, p).total()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stupid_ai::Playstyle;
+
+    fn hand(cards: &[(TradeCard, usize)]) -> PlayerTradeCards {
+        let mut hand = PlayerTradeCards::default();
+        for (card, count) in cards {
+            hand.add_trade_cards(*card, *count);
+        }
+        hand
+    }
+
+    #[test]
+    fn wants_go_to_the_set_that_grows_most() {
+        // Two Grain (4) gain 5*4 = 20 from a third; a second Salt (3) only 9.
+        let hand = hand(&[
+            (TradeCard::Grain, 2),
+            (TradeCard::Salt, 1),
+            (TradeCard::Ochre, 3),
+        ]);
+        let wants = goal_wants(&hand, None, 2);
+        assert_eq!(wants.get(&TradeCard::Grain), Some(&2), "{wants:?}");
+    }
+
+    #[test]
+    fn wants_close_the_shortfall_with_the_cheapest_card_that_does() {
+        // 3 Ochre (1) + 2 Grain (4) + 1 Salt (3) = 9 + 16 + 3 = 28 buying
+        // power; the goal needs 9 more. A third Grain adds 20 and a second
+        // Salt 9, so either closes it, and Salt is the cheaper card to get.
+        let hand = hand(&[
+            (TradeCard::Ochre, 3),
+            (TradeCard::Grain, 2),
+            (TradeCard::Salt, 1),
+        ]);
+        let goal = TradeGoal {
+            cards: vec![CivCardName::Pottery],
+            cost: 37,
+        };
+        let wants = goal_wants(&hand, Some(&goal), 1);
+        assert_eq!(wants.get(&TradeCard::Salt), Some(&1), "{wants:?}");
+        let wants = goal_wants(&hand, None, 1);
+        assert_eq!(wants.get(&TradeCard::Grain), Some(&1), "{wants:?}");
+    }
+
+    #[test]
+    fn gives_are_the_cheapest_singles_and_never_the_wanted_cards() {
+        let hand = hand(&[
+            (TradeCard::Grain, 2),
+            (TradeCard::Salt, 1),
+            (TradeCard::Ochre, 1),
+        ]);
+        let keep = HashMap::from([(TradeCard::Salt, 1)]);
+        let gives = goal_gives(&hand, 2, &keep);
+        assert_eq!(gives.get(&TradeCard::Ochre), Some(&1), "{gives:?}");
+        assert_eq!(gives.get(&TradeCard::Grain), Some(&1), "{gives:?}");
+        assert!(goal_gives(&hand, 5, &keep).is_empty());
+    }
+
+    #[test]
+    fn reaching_the_goal_outweighs_a_small_loss_elsewhere() {
+        let p = Personality::from_playstyle(Playstyle::Balanced);
+        let goal = TradeGoal {
+            cards: vec![CivCardName::Pottery],
+            cost: 45,
+        };
+        assert!(score_trade_for_goal(40, 46, Some(&goal), false, &p) > 6.0);
+        // Dropping below an affordable purchase is worse than the stack
+        // value lost.
+        assert!(score_trade_for_goal(50, 44, Some(&goal), false, &p) < -6.0);
+        assert_eq!(score_trade_for_goal(50, 44, None, false, &p), -6.0);
+    }
+
+    #[test]
+    fn calamity_offload_is_weighted_by_aversion() {
+        let mut careful = Personality::from_playstyle(Playstyle::Balanced);
+        careful.weights.calamity_aversion = 1.0;
+        let mut carefree = careful.clone();
+        carefree.weights.calamity_aversion = 0.0;
+        assert!(
+            score_trade_for_goal(30, 27, None, true, &careful)
+                > trade_accept_margin(&careful, NEUTRAL_TRUST)
+        );
+        assert!(score_trade_for_goal(30, 27, None, true, &carefree) < 0.0);
+    }
+
+    #[test]
+    fn distrust_raises_the_margin() {
+        let p = Personality::from_playstyle(Playstyle::Balanced);
+        let neutral = trade_accept_margin(&p, NEUTRAL_TRUST);
+        assert!(trade_accept_margin(&p, 0.0) > neutral + 2.0);
+        assert!(trade_accept_margin(&p, 1.0) < neutral);
+        assert!(trade_accept_margin(&p, 1.0) >= 0.0);
+    }
+
+    #[test]
+    fn deception_follows_risk_trust_and_calamity_aversion() {
+        let mut p = Personality::from_playstyle(Playstyle::Balanced);
+        p.weights.risk = 0.1;
+        assert_eq!(hidden_card_play(&p, 0.0, true), HiddenCardPlay::Straight);
+
+        p.weights.risk = 0.8;
+        assert_eq!(hidden_card_play(&p, 1.0, true), HiddenCardPlay::Straight);
+        assert_eq!(
+            hidden_card_play(&p, NEUTRAL_TRUST, false),
+            HiddenCardPlay::Bluff
+        );
+        p.weights.calamity_aversion = 0.9;
+        assert_eq!(
+            hidden_card_play(&p, NEUTRAL_TRUST, true),
+            HiddenCardPlay::SlipCalamity
+        );
+        p.weights.calamity_aversion = 0.0;
+        assert_eq!(
+            hidden_card_play(&p, NEUTRAL_TRUST, true),
+            HiddenCardPlay::Bluff
+        );
+    }
 }
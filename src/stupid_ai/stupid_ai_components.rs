@@ -1,7 +1,11 @@
+use crate::stupid_ai::{MctsConfig, TradeTrust};
 use bevy::prelude::*;
 
+/// A player the game moves for. Every AI remembers who cheated it in trades
+/// from the moment it is seated, however it was seated.
 #[derive(Component, Debug, Reflect)]
 #[reflect(Component)]
+#[require(TradeTrust)]
 pub struct StupidAi;
 
 #[derive(Component, Debug, Reflect)]
@@ -15,6 +19,21 @@ pub struct IsHuman;
 #[reflect(Component)]
 pub struct AgentControlled;
 
+/// A `StupidAi` (`MCTS_FACTIONS`) that picks its population expansion,
+/// movement and city construction moves by Monte Carlo tree search over a
+/// `GameModel`. Trade, civ cards and calamities stay with its personality.
+#[derive(Component, Debug, Reflect)]
+#[reflect(Component)]
+pub struct MctsAi {
+    pub config: MctsConfig,
+}
+
+impl MctsAi {
+    pub fn new(config: MctsConfig) -> Self {
+        MctsAi { config }
+    }
+}
+
 #[derive(Resource, Default)]
 pub struct AiMoveQueue {
     pub pending: Vec<(Entity, f32)>,
@@ -1,7 +1,9 @@
+use crate::civilization::setup_players;
 use crate::stupid_ai::*;
 use crate::{GameActivity, GameState};
 use bevy::app::{Plugin, Update};
 use bevy::prelude::{App, IntoScheduleConfigs, OnEnter, SystemCondition, in_state};
+use bevy_common_assets::ron::RonAssetPlugin;
 
 pub struct StupidAiPlugin;
 
@@ -10,8 +12,21 @@ impl Plugin for StupidAiPlugin {
         app.add_message::<StupidAiMessage>()
             .add_message::<SelectStupidMove>()
             .register_type::<Personality>()
+            .register_type::<MctsAi>()
+            .add_plugins(RonAssetPlugin::<PersonalityDefinitions>::new(&[
+                "personalities.ron",
+            ]))
             .init_resource::<AiMoveQueue>()
+            .init_resource::<AiDecisionTraces>()
             .init_resource::<MovementLoopGuard>()
+            .add_systems(OnEnter(GameState::Loading), load_personalities)
+            .add_systems(
+                OnEnter(GameActivity::PrepareGame),
+                (
+                    init_personalities.before(setup_players),
+                    clear_decision_traces,
+                ),
+            )
             .add_systems(OnEnter(GameActivity::Movement), reset_movement_loop_guard)
             .add_systems(
                 Update,
@@ -27,6 +42,7 @@ impl Plugin for StupidAiPlugin {
                         ),
                     ),
                     select_stupid_trade_move.run_if(in_state(GameActivity::Trade)),
+                    update_trade_trust.run_if(in_state(GameState::Playing)),
                     select_stupid_civ_card_move
                         .run_if(in_state(GameActivity::AcquireCivilizationCards)),
                 ),
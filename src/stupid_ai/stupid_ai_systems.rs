@@ -2,8 +2,7 @@ use crate::civilization::*;
 use crate::stupid_ai::*;
 use bevy::platform::collections::HashMap;
 use bevy::prelude::{
-    Commands, Entity, Has, MessageReader, MessageWriter, Name, Query, Res, ResMut, debug, error,
-    warn,
+    Commands, Entity, MessageReader, MessageWriter, Name, Query, Res, ResMut, debug, error, warn,
 };
 use rand::prelude::SliceRandom;
 
@@ -26,31 +25,39 @@ pub fn reset_movement_loop_guard(mut loop_guard: ResMut<MovementLoopGuard>) {
 pub fn select_stupid_pop_exp(
     mut event_reader: MessageReader<SelectStupidMove>,
     player_moves: Query<(&Name, &AvailableMoves, &Personality)>,
-    area_info_query: Query<(
-        Entity,
-        &Population,
-        &LandPassage,
-        Option<&BuiltCity>,
-        Has<CitySite>,
-    )>,
+    area_info_query: Query<(Entity, &Population, AreaSummaryQueryData)>,
     mut expand_writer: MessageWriter<ExpandPopulationManuallyCommand>,
     debug_options: Res<DebugOptions>,
+    mut decider: AiDecider,
+    mcts: MctsSearch,
 ) {
     for event in event_reader.read() {
         if let Ok((player_name, available_moves, personality)) = player_moves.get(event.player) {
             let areas = gather_area_summaries(event.player, &area_info_query);
-            let scored: Vec<(usize, f32)> = available_moves
+            let mut explained: Vec<(usize, ScoreBreakdown)> = available_moves
                 .moves
                 .iter()
                 .map(|(i, m)| {
                     (
                         *i,
-                        score_population_expansion(m, &areas, &personality.weights),
+                        explain_population_expansion(m, &areas, &personality.weights),
                     )
                 })
                 .collect();
-            let mut rng = rand::rng();
-            if let Some(chosen) = pick(&scored, personality.picker, &mut rng) {
+            let mut picker = personality.picker;
+            if mcts.plays(event.player)
+                && let Some(searched) = mcts.explain(
+                    event.player,
+                    &available_moves.moves,
+                    &mut decider.search_rng(),
+                )
+            {
+                explained = searched;
+                picker = Picker::Greedy;
+            }
+            if let Some(chosen) =
+                decider.pick(event.player, &available_moves.moves, &explained, picker)
+            {
                 let selected_move = &available_moves.moves[&chosen];
                 if debug_options.print_selected_moves {
                     debug!("{} selects {:#?}", player_name, selected_move);
@@ -78,15 +85,11 @@ pub fn select_stupid_movement(
     mut move_tokens_writer: MessageWriter<MoveTokenFromAreaToAreaCommand>,
     mut ship_ferry_writer: MessageWriter<ShipFerryCommand>,
     mut end_movement_writer: MessageWriter<PlayerMovementEnded>,
-    area_info_query: Query<(
-        Entity,
-        &Population,
-        &LandPassage,
-        Option<&BuiltCity>,
-        Has<CitySite>,
-    )>,
+    area_info_query: Query<(Entity, &Population, AreaSummaryQueryData)>,
     debug_options: Res<DebugOptions>,
     mut loop_guard: ResMut<MovementLoopGuard>,
+    mut decider: AiDecider,
+    mcts: MctsSearch,
 ) {
     for event in event_reader.read() {
         if let Ok((player_name, available_moves, _player_areas, personality)) =
@@ -94,19 +97,31 @@ pub fn select_stupid_movement(
         {
             let areas = gather_area_summaries(event.player, &area_info_query);
 
-            let scored: Vec<(usize, f32)> = available_moves
+            let mut explained: Vec<(usize, ScoreBreakdown)> = available_moves
                 .moves
                 .iter()
                 .map(|(i, m)| {
                     (
                         *i,
-                        score_movement(m, event.player, &areas, &personality.weights),
+                        explain_movement(m, event.player, &areas, &personality.weights),
                     )
                 })
                 .collect();
+            let mut picker = personality.picker;
+            if mcts.plays(event.player)
+                && let Some(searched) = mcts.explain(
+                    event.player,
+                    &available_moves.moves,
+                    &mut decider.search_rng(),
+                )
+            {
+                explained = searched;
+                picker = Picker::Greedy;
+            }
 
-            let mut rng = rand::rng();
-            let Some(chosen) = pick(&scored, personality.picker, &mut rng) else {
+            let Some(chosen) =
+                decider.pick(event.player, &available_moves.moves, &explained, picker)
+            else {
                 continue;
             };
             let selected_move = &available_moves.moves[&chosen];
@@ -123,10 +138,10 @@ pub fn select_stupid_movement(
                      Looping move (score {:.3}): {:#?}",
                     player_name,
                     count,
-                    scored
+                    explained
                         .iter()
                         .find(|(i, _)| *i == chosen)
-                        .map_or(f32::NAN, |(_, s)| *s),
+                        .map_or(f32::NAN, |(_, s)| s.total()),
                     selected_move,
                 );
             }
@@ -150,21 +165,21 @@ pub fn select_stupid_movement(
                     debug!("{} selects {:#?}", player_name, selected_move);
                 }
                 match selected_move {
-                    GameMove::Movement(movement_move) => {
-                        //A little complexity here: If possible, leave two, but also, always make a move
-                        send_movement_move(&mut move_tokens_writer, event, movement_move, false);
+                    GameMove::Movement(movement_move)
+                    | GameMove::AttackArea(movement_move)
+                    | GameMove::AttackCity(movement_move) => {
+                        move_tokens_writer.write(MoveTokenFromAreaToAreaCommand::new(
+                            movement_move.source,
+                            movement_move.target,
+                            tokens_sent(selected_move),
+                            event.player,
+                        ));
                     }
                     GameMove::ShipFerry(ferry_move) => {
-                        // Ferry up to max_tokens (≤5) tokens by ship. Keep 1 back if possible.
-                        let n = if ferry_move.max_tokens > 1 {
-                            ferry_move.max_tokens - 1
-                        } else {
-                            1
-                        };
                         ship_ferry_writer.write(ShipFerryCommand::new(
                             ferry_move.source,
                             ferry_move.target,
-                            n,
+                            tokens_sent(selected_move),
                             event.player,
                         ));
                     }
@@ -172,10 +187,6 @@ pub fn select_stupid_movement(
                         loop_guard.counts.remove(&event.player);
                         end_movement_writer.write(PlayerMovementEnded::new(event.player));
                     }
-                    GameMove::AttackArea(movement_move) | GameMove::AttackCity(movement_move) => {
-                        //A little complexity here: If possible, leave two, but also, always make a move
-                        send_movement_move(&mut move_tokens_writer, event, movement_move, true);
-                    }
                     _ => {
                         debug!("In Movement, move was: {:#?}", selected_move);
                     }
@@ -188,28 +199,41 @@ pub fn select_stupid_movement(
 pub fn select_stupid_city_building(
     mut event_reader: MessageReader<SelectStupidMove>,
     player_moves: Query<(&Name, &AvailableMoves, &Personality)>,
-    area_info_query: Query<(
-        Entity,
-        &Population,
-        &LandPassage,
-        Option<&BuiltCity>,
-        Has<CitySite>,
-    )>,
+    area_info_query: Query<(Entity, &Population, AreaSummaryQueryData)>,
     mut build_city_writer: MessageWriter<BuildCityCommand>,
     mut end_player_city_construction: MessageWriter<EndPlayerCityConstruction>,
     debug_options: Res<DebugOptions>,
+    mut decider: AiDecider,
+    mcts: MctsSearch,
 ) {
     for event in event_reader.read() {
         if let Ok((player_name, available_moves, personality)) = player_moves.get(event.player) {
             let areas = gather_area_summaries(event.player, &area_info_query);
-            let scored: Vec<(usize, f32)> = available_moves
+            let mut explained: Vec<(usize, ScoreBreakdown)> = available_moves
                 .moves
                 .iter()
-                .map(|(i, m)| (*i, score_city_construction(m, &areas, &personality.weights)))
+                .map(|(i, m)| {
+                    (
+                        *i,
+                        explain_city_construction(m, &areas, &personality.weights),
+                    )
+                })
                 .collect();
-            let mut rng = rand::rng();
+            let mut picker = personality.picker;
+            if mcts.plays(event.player)
+                && let Some(searched) = mcts.explain(
+                    event.player,
+                    &available_moves.moves,
+                    &mut decider.search_rng(),
+                )
+            {
+                explained = searched;
+                picker = Picker::Greedy;
+            }
 
-            if let Some(chosen) = pick(&scored, personality.picker, &mut rng) {
+            if let Some(chosen) =
+                decider.pick(event.player, &available_moves.moves, &explained, picker)
+            {
                 let selected_move = &available_moves.moves[&chosen];
                 if debug_options.print_selected_moves {
                     debug!("{} selects {:#?}", player_name, selected_move);
@@ -235,27 +259,31 @@ pub fn select_stupid_city_building(
 pub fn select_stupid_city_elimination(
     mut event_reader: MessageReader<SelectStupidMove>,
     player_moves: Query<(&Name, &AvailableMoves, &Personality)>,
-    area_info_query: Query<(
-        Entity,
-        &Population,
-        &LandPassage,
-        Option<&BuiltCity>,
-        Has<CitySite>,
-    )>,
+    area_info_query: Query<(Entity, &Population, AreaSummaryQueryData)>,
     mut eliminate_city: MessageWriter<EliminateCity>,
     debug_options: Res<DebugOptions>,
+    mut decider: AiDecider,
 ) {
     for event in event_reader.read() {
         if let Ok((player_name, available_moves, personality)) = player_moves.get(event.player) {
             let areas = gather_area_summaries(event.player, &area_info_query);
-            let scored: Vec<(usize, f32)> = available_moves
+            let explained: Vec<(usize, ScoreBreakdown)> = available_moves
                 .moves
                 .iter()
-                .map(|(i, m)| (*i, score_city_elimination(m, &areas, &personality.weights)))
+                .map(|(i, m)| {
+                    (
+                        *i,
+                        explain_city_elimination(m, &areas, &personality.weights),
+                    )
+                })
                 .collect();
-            let mut rng = rand::rng();
 
-            if let Some(chosen) = pick(&scored, personality.picker, &mut rng) {
+            if let Some(chosen) = decider.pick(
+                event.player,
+                &available_moves.moves,
+                &explained,
+                personality.picker,
+            ) {
                 let selected_move = &available_moves.moves[&chosen];
                 if debug_options.print_selected_moves {
                     debug!("{} selects {:#?}", player_name, selected_move);
@@ -281,10 +309,15 @@ pub fn select_stupid_city_elimination(
 pub fn select_stupid_trade_move(
     mut event_reader: MessageReader<SelectStupidMove>,
     player_moves: Query<(&Name, &AvailableMoves, &PlayerAreas)>,
-    _commands: Commands,
-    _trade_offer_query: Query<&mut TradeOffer>,
-    player_trade_cards: Query<(&PlayerTradeCards, &PlayerTradeInterests)>,
-    player_wants_query: Query<(&PlayerTradeInterests, &Name)>,
+    player_trade_cards: Query<(
+        &PlayerTradeCards,
+        &Personality,
+        Option<&PlayerCivilizationCards>,
+        Option<&AstPosition>,
+    )>,
+    names: Query<&Name>,
+    civ_card_defs: Option<Res<AvailableCivCards>>,
+    mut game_rng: ResMut<GameRng>,
 ) {
     for event in event_reader.read() {
         // //debug!("Selecting stupid AI move for player {:#?}", event.player);
@@ -299,14 +332,18 @@ pub fn select_stupid_trade_move(
                 .cloned()
                 .collect::<Vec<_>>();
             for trade_move in &trade_moves {
-                let mut rng = rand::rng();
+                let rng = game_rng.ai_rng();
                 match trade_move {
                     TradeMove::ProposeTrade(receiver, matching_cards) => {
-                        if let Ok((player_trade_cards, player_wants)) =
+                        if let Ok((player_trade_cards, personality, civ_cards, ast)) =
                             player_trade_cards.get(event.player)
-                            && let Ok((_receiver_wants, receiver_name)) =
-                                player_wants_query.get(*receiver)
+                            && let Ok(receiver_name) = names.get(*receiver)
                         {
+                            let goal = civ_card_defs.as_deref().and_then(|defs| {
+                                trade_goal(player_trade_cards, civ_cards, ast, defs, personality)
+                            });
+                            // The set completions we ask for, aimed at the planned purchase.
+                            let wants = goal_wants(player_trade_cards, goal.as_ref(), 3);
                             match matching_cards.len() {
                                 1 => {
                                     /*
@@ -337,10 +374,8 @@ pub fn select_stupid_trade_move(
                                                     offer.pay_even_more(lowest_commodity, 1);
                                                 }
                                             }
-                                            //Now add what we want
-                                            for (card, count) in
-                                                player_wants.get_trade_thingie(&mut rng)
-                                            {
+                                            // Now add the set completions we want
+                                            for (card, count) in wants {
                                                 offer.get_even_more(card, count);
                                             }
                                         }
@@ -359,7 +394,7 @@ pub fn select_stupid_trade_move(
                                         *receiver,
                                         receiver_name,
                                     );
-                                    for (card, count) in player_wants.get_trade_thingie(&mut rng) {
+                                    for (card, count) in wants {
                                         offer.get_even_more(card, count);
                                     }
                                 }
@@ -368,7 +403,7 @@ pub fn select_stupid_trade_move(
                                     let mut cards: Vec<TradeCard> =
                                         matching_cards.keys().copied().collect();
                                     // Shuffle to get random selection
-                                    cards.shuffle(&mut rng);
+                                    cards.shuffle(rng);
                                 }
                                 _ => {}
                             }
@@ -403,11 +438,13 @@ pub fn select_stupid_civ_card_move(
         &PlayerCivilizationCards,
         Option<&crate::civilization::resolve_calamities::resolve_calamities_components::GrainLockedForPurchase>,
         Option<&crate::civilization::CardsHeldBeforePurchasing>,
+        Option<&AstPosition>,
     )>,
     cards: Res<AvailableCivCards>,
     mut done_writer: MessageWriter<PlayerDoneAcquiringCivilizationCards>,
     mut purchase_writer: MessageWriter<ConfirmCivCardPurchase>,
     debug_options: Res<DebugOptions>,
+    mut decider: AiDecider,
 ) {
     for event in event_reader.read() {
         if let Ok((player_name, available_moves, personality)) = player_moves.get(event.player) {
@@ -416,40 +453,74 @@ pub fn select_stupid_civ_card_move(
             // this turn's acquiring phase began, not the live hand -- see
             // CardsHeldBeforePurchasing's doc comment. This matters here
             // specifically because this system re-runs iteratively as the AI
-            // buys one card per move, so without the snapshot a card bought
-            // earlier this turn would wrongly discount a later one.
-            let (wealth, credits) = match player_cards_query.get(event.player) {
-                Ok((trade_cards, civ_cards, _, cards_held_before)) => (
+            // buys, so without the snapshot a card bought earlier this turn
+            // would wrongly discount a later one.
+            let player_cards = player_cards_query.get(event.player).ok();
+            let (wealth, credits) = match player_cards {
+                Some((trade_cards, civ_cards, _, cards_held_before, _)) => (
                     trade_cards.total_stack_value() as u32,
                     cards.total_credits(cards_held_before.map_or(&civ_cards.cards, |c| &c.0)),
                 ),
-                Err(_) => (0, Vec::new()),
+                None => (0, Vec::new()),
             };
-
-            let scored: Vec<(usize, f32)> = available_moves
+            // The bundle worth most within what the hand can pay. Cards in it
+            // score on the bundle's value; anything else is only bought if
+            // the plan is out of reach.
+            let plan = player_cards.map(
+                |(trade_cards, civ_cards, grain_locked, cards_held_before, ast)| {
+                    let input = PurchasePlanInput {
+                        owned: civ_cards.cards.clone(),
+                        held_before: cards_held_before
+                            .map_or_else(|| civ_cards.cards.clone(), |c| c.0.clone()),
+                        ast_space: ast.map_or(0, |a| a.space),
+                        calamities: trade_cards.calamity_cards().into_iter().collect(),
+                        budget: ai_buying_power(trade_cards, grain_locked.map_or(0, |l| l.0))
+                            as u32,
+                    };
+                    plan_civ_card_purchase(&input, &cards, &personality.weights)
+                },
+            );
+
+            let explained: Vec<(usize, ScoreBreakdown)> = available_moves
                 .moves
                 .iter()
                 .filter_map(|(i, m)| match m {
                     GameMove::AcquireCivilizationCards(civ_move) => {
-                        let option = match civ_move {
-                            AcquireCivilizationCardsMove::AcquireCard(name) => {
-                                cards.cards.iter().find(|c| c.name == *name).map(|def| {
-                                    CivCardOption {
-                                        effective_cost: def.calculate_cost(&credits),
-                                        credit_value: civ_card_credit_value(def),
-                                        wealth,
-                                    }
-                                })
+                        let breakdown = match (civ_move, &plan) {
+                            (AcquireCivilizationCardsMove::AcquireCard(name), Some(plan))
+                                if plan.cards.contains(name) =>
+                            {
+                                let mut breakdown = ScoreBreakdown::fixed("baseline", 0.55);
+                                for (consideration, value) in &plan.breakdown.terms {
+                                    breakdown.add(*consideration, *value);
+                                }
+                                breakdown
                             }
-                            _ => None,
+                            (AcquireCivilizationCardsMove::AcquireCard(name), _) => {
+                                let option =
+                                    cards.cards.iter().find(|c| c.name == *name).map(|def| {
+                                        CivCardOption {
+                                            effective_cost: def.calculate_cost(&credits),
+                                            credit_value: civ_card_credit_value(def),
+                                            wealth,
+                                        }
+                                    });
+                                let mut breakdown =
+                                    explain_civ_card(civ_move, option, &personality.weights);
+                                if plan.as_ref().is_some_and(|p| !p.cards.is_empty()) {
+                                    breakdown.add("off plan", -0.5);
+                                }
+                                breakdown
+                            }
+                            _ => explain_civ_card(civ_move, None, &personality.weights),
                         };
-                        Some((*i, score_civ_card(civ_move, option, &personality.weights)))
+                        Some((*i, breakdown))
                     }
                     _ => None,
                 })
                 .collect();
 
-            if scored.is_empty() {
+            if explained.is_empty() {
                 // No civ-card move in this player's move set (e.g. a stale
                 // set left over from another phase). Bailing out with `return`
                 // used to drop every remaining SelectStupidMove in the batch,
@@ -461,8 +532,12 @@ pub fn select_stupid_civ_card_move(
                 continue;
             }
 
-            let mut rng = rand::rng();
-            if let Some(chosen) = pick(&scored, personality.picker, &mut rng) {
+            if let Some(chosen) = decider.pick(
+                event.player,
+                &available_moves.moves,
+                &explained,
+                personality.picker,
+            ) {
                 let GameMove::AcquireCivilizationCards(selected_move) =
                     &available_moves.moves[&chosen]
                 else {
@@ -476,29 +551,32 @@ pub fn select_stupid_civ_card_move(
                         done_writer.write(PlayerDoneAcquiringCivilizationCards(event.player));
                     }
                     AcquireCivilizationCardsMove::AcquireCard(card_name) => {
-                        if let Ok((trade_cards, civ_cards, grain_locked, cards_held_before)) =
-                            player_cards_query.get(event.player)
-                        {
-                            let credits = cards.total_credits(
-                                cards_held_before.map_or(&civ_cards.cards, |c| &c.0),
-                            );
-                            let card_def = cards.cards.iter().find(|c| c.name == *card_name);
-                            if let Some(def) = card_def {
-                                let cost = def.calculate_cost(&credits) as usize;
-                                let payment = compute_ai_payment(
-                                    trade_cards,
-                                    cost,
-                                    grain_locked.map_or(0, |l| l.0),
-                                );
-                                purchase_writer.write(ConfirmCivCardPurchase {
-                                    player: event.player,
-                                    cards_to_buy: vec![*card_name],
-                                    payment,
-                                });
-                            } else {
-                                done_writer
-                                    .write(PlayerDoneAcquiringCivilizationCards(event.player));
+                        let Some((trade_cards, _, grain_locked, _, _)) = player_cards else {
+                            done_writer.write(PlayerDoneAcquiringCivilizationCards(event.player));
+                            continue;
+                        };
+                        // A card from the plan buys the whole bundle at once.
+                        let purchase = match &plan {
+                            Some(plan) if plan.cards.contains(card_name) => {
+                                Some((plan.cards.clone(), plan.cost))
                             }
+                            _ => cards
+                                .cards
+                                .iter()
+                                .find(|c| c.name == *card_name)
+                                .map(|def| (vec![*card_name], def.calculate_cost(&credits))),
+                        };
+                        if let Some((cards_to_buy, cost)) = purchase {
+                            let payment = compute_ai_payment(
+                                trade_cards,
+                                cost as usize,
+                                grain_locked.map_or(0, |l| l.0),
+                            );
+                            purchase_writer.write(ConfirmCivCardPurchase {
+                                player: event.player,
+                                cards_to_buy,
+                                payment,
+                            });
                         } else {
                             done_writer.write(PlayerDoneAcquiringCivilizationCards(event.player));
                         }
@@ -515,7 +593,7 @@ pub fn select_stupid_civ_card_move(
 
 /// Sum the credit (future-discount) value a civ card hands out, regardless of which
 /// card type / card it applies to — a coarse proxy for "how much tech synergy".
-fn civ_card_credit_value(def: &CivCardDefinition) -> u32 {
+pub fn civ_card_credit_value(def: &CivCardDefinition) -> u32 {
     def.credits
         .iter()
         .map(|credit| match credit {
@@ -524,6 +602,35 @@ fn civ_card_credit_value(def: &CivCardDefinition) -> u32 {
         .sum()
 }
 
+/// The commodity stacks [`compute_ai_payment`] may spend, highest face value
+/// first, with locked Grain (rule 30.312) taken out.
+fn usable_commodity_stacks(
+    trade_cards: &PlayerTradeCards,
+    grain_locked: usize,
+) -> Vec<PlayerCardStack> {
+    let mut stacks = trade_cards.as_card_stacks_sorted_by_value();
+    if grain_locked > 0
+        && let Some(grain_stack) = stacks.iter_mut().find(|s| s.card_type == TradeCard::Grain)
+    {
+        let usable = crate::civilization::resolve_calamities::resolve_calamities_components::usable_grain_count(
+            grain_stack.count,
+            grain_locked,
+        );
+        grain_stack.count = usable;
+        grain_stack.suite_value = usable * usable * grain_stack.card_type.value();
+    }
+    stacks.retain(|s| s.is_commodity && s.count > 0);
+    stacks
+}
+
+/// The most [`compute_ai_payment`] can pay: every usable stack spent in full.
+pub fn ai_buying_power(trade_cards: &PlayerTradeCards, grain_locked: usize) -> usize {
+    usable_commodity_stacks(trade_cards, grain_locked)
+        .iter()
+        .map(|s| s.suite_value)
+        .sum()
+}
+
 /// Greedy minimum payment: take stacks sorted by descending face value, using just
 /// enough cards from each to cover `cost`.  Returns a HashMap<TradeCard, count>.
 ///
@@ -541,20 +648,7 @@ pub fn compute_ai_payment(
     let mut payment = bevy::platform::collections::HashMap::default();
     let mut remaining = cost;
 
-    let mut stacks = trade_cards.as_card_stacks_sorted_by_value();
-    if grain_locked > 0
-        && let Some(grain_stack) = stacks.iter_mut().find(|s| s.card_type == TradeCard::Grain)
-    {
-        let usable = crate::civilization::resolve_calamities::resolve_calamities_components::usable_grain_count(
-            grain_stack.count,
-            grain_locked,
-        );
-        grain_stack.count = usable;
-        grain_stack.suite_value = usable * usable * grain_stack.card_type.value();
-    }
-    stacks.retain(|s| s.is_commodity && s.count > 0);
-
-    for stack in stacks {
+    for stack in usable_commodity_stacks(trade_cards, grain_locked) {
         if remaining == 0 {
             break;
         }
@@ -585,69 +679,33 @@ pub fn compute_ai_payment(
 /// scoring functions stay pure.
 fn gather_area_summaries(
     player: Entity,
-    area_info_query: &Query<(
-        Entity,
-        &Population,
-        &LandPassage,
-        Option<&BuiltCity>,
-        Has<CitySite>,
-    )>,
+    area_info_query: &Query<(Entity, &Population, AreaSummaryQueryData)>,
 ) -> HashMap<Entity, AreaSummary> {
-    let mut areas = HashMap::default();
-    for (entity, population, land_passage, built_city, is_city_site) in area_info_query.iter() {
-        let my_pop = population.population_for_player(player);
-        let enemy_pop = population.total_population() - my_pop;
-        areas.insert(
-            entity,
-            AreaSummary {
-                max_population: population.max_population,
-                is_city_site,
-                has_city: built_city.is_some(),
-                city_is_mine: built_city.is_some_and(|c| c.player == player),
-                my_pop,
-                enemy_pop,
-                neighbours: land_passage.to_areas.clone(),
-            },
-        );
-    }
-    areas
+    area_info_query
+        .iter()
+        .map(|(entity, population, info)| {
+            (
+                entity,
+                AreaSummary::from_components(player, population, info),
+            )
+        })
+        .collect()
 }
 
-fn send_movement_move(
-    move_tokens_writer: &mut MessageWriter<MoveTokenFromAreaToAreaCommand>,
-    event: &SelectStupidMove,
-    movement_move: &MovementMove,
-    is_attack: bool,
-) {
-    if is_attack {
-        // Commit the attack with at least one token: keep one back if we can, but
-        // never send a 0-token move (which is a no-op the AI would re-pick forever).
-        let n = movement_move.max_tokens.saturating_sub(1).max(1);
-        move_tokens_writer.write(MoveTokenFromAreaToAreaCommand::new(
-            movement_move.source,
-            movement_move.target,
-            n,
-            event.player,
-        ));
-    } else {
-        match movement_move.max_tokens {
-            1 | 2 => {
-                move_tokens_writer.write(MoveTokenFromAreaToAreaCommand::new(
-                    movement_move.source,
-                    movement_move.target,
-                    1,
-                    event.player,
-                ));
-            }
-            _ => {
-                move_tokens_writer.write(MoveTokenFromAreaToAreaCommand::new(
-                    movement_move.source,
-                    movement_move.target,
-                    2,
-                    event.player,
-                ));
-            }
+/// How many tokens the AI sends with a movement-phase move. A peaceful
+/// move leaves tokens behind where it can (1 from a stack of up to two,
+/// else 2); an attack or a ferry keeps one back if it can, but never sends
+/// nothing, which would be a no-op the AI re-picks forever.
+pub fn tokens_sent(mv: &GameMove) -> usize {
+    match mv {
+        GameMove::Movement(m) => match m.max_tokens {
+            0..=2 => 1,
+            _ => 2,
+        },
+        GameMove::AttackArea(m) | GameMove::AttackCity(m) | GameMove::ShipFerry(m) => {
+            m.max_tokens.saturating_sub(1).max(1)
         }
+        _ => 0,
     }
 }
 
@@ -705,4 +763,15 @@ mod grain_lock_tests {
         assert_eq!(payment.get(&TradeCard::Grain), None);
         assert!(payment.get(&TradeCard::Salt).copied().unwrap_or(0) > 0);
     }
+
+    #[test]
+    fn buying_power_leaves_out_locked_grain() {
+        let mut hand = PlayerTradeCards::default();
+        for _ in 0..5 {
+            hand.add_trade_card(TradeCard::Grain);
+        }
+        // 5 Grain -> 5*5*4 = 100; with 3 locked only 2*2*4 = 16 can be paid.
+        assert_eq!(ai_buying_power(&hand, 0), 100);
+        assert_eq!(ai_buying_power(&hand, 3), 16);
+    }
 }
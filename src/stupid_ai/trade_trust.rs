@@ -0,0 +1,224 @@
+use crate::civilization::{OpenTradeOfferSettled, PlayerTradeCards, TradeCard, TradeCardTrait};
+use bevy::platform::collections::{HashMap, HashSet};
+use bevy::prelude::{Component, Entity, MessageReader, Query, debug};
+
+/// Trust in an opponent nobody has traded with yet.
+pub const NEUTRAL_TRUST: f32 = 0.5;
+/// How far one settled trade moves trust towards what it showed.
+const SETTLEMENT_WEIGHT: f32 = 0.3;
+/// How far a calamity slipped into a trade moves trust towards zero.
+const SLIPPED_CALAMITY_WEIGHT: f32 = 0.5;
+
+/// What an AI player has learned about each opponent from trading with them:
+/// 0 = cheats every time, 1 = always delivers. Hidden cards let a player
+/// promise three cards and hand over junk or a calamity; trust is the running
+/// memory of who does.
+#[derive(Component, Debug, Default, Clone)]
+pub struct TradeTrust {
+    scores: HashMap<Entity, f32>,
+    /// Slipped calamities already held against their sender, so a card sitting
+    /// in the hand is only counted once.
+    slipped: HashSet<(TradeCard, Entity)>,
+}
+
+impl TradeTrust {
+    pub fn trust(&self, opponent: Entity) -> f32 {
+        self.scores.get(&opponent).copied().unwrap_or(NEUTRAL_TRUST)
+    }
+
+    /// Move trust in `opponent` a `weight` of the way towards `outcome`.
+    pub fn record(&mut self, opponent: Entity, outcome: f32, weight: f32) {
+        let trust = self.scores.entry(opponent).or_insert(NEUTRAL_TRUST);
+        *trust += weight * (outcome.clamp(0.0, 1.0) - *trust);
+    }
+
+    /// Hold every calamity in `hand` traded in by an opponent against them,
+    /// once per card. Returns the opponents newly penalised.
+    pub fn note_slipped_calamities(&mut self, hand: &PlayerTradeCards) -> Vec<Entity> {
+        let held: HashSet<(TradeCard, Entity)> = hand
+            .calamity_cards()
+            .into_iter()
+            .filter_map(|card| hand.calamity_traded_by(card).map(|from| (card, from)))
+            .collect();
+        let mut fresh: Vec<(TradeCard, Entity)> = held.difference(&self.slipped).copied().collect();
+        fresh.sort_by_key(|(card, from)| (*card as u8, *from));
+        for (_, from) in &fresh {
+            self.record(*from, 0.0, SLIPPED_CALAMITY_WEIGHT);
+        }
+        // Forget cards that left the hand: the same calamity slipped in again
+        // in a later round counts again.
+        self.slipped = held;
+        fresh.into_iter().map(|(_, from)| from).collect()
+    }
+}
+
+/// How well the hidden part of a trade matched what the stated part
+/// promised, 0 to 1: each hidden commodity is measured against the cheapest
+/// guaranteed card, and a promised card never delivered counts as nothing.
+/// Calamities are left to [`TradeTrust::note_slipped_calamities`]. `None` when
+/// there was nothing hidden to judge.
+pub fn hidden_delivery_honesty(
+    delivered: &HashMap<TradeCard, usize>,
+    guaranteed: &HashMap<TradeCard, usize>,
+    hidden_count: usize,
+) -> Option<f32> {
+    let stated = guaranteed.keys().map(TradeCardTrait::value).min()?.max(1) as f32;
+    let mut judged = 0;
+    let mut fairness = 0.0;
+    let mut delivered_hidden = 0;
+    for (card, count) in delivered {
+        let hidden = count.saturating_sub(guaranteed.get(card).copied().unwrap_or(0));
+        delivered_hidden += hidden;
+        if card.is_commodity() {
+            judged += hidden;
+            fairness += hidden as f32 * (card.value() as f32 / stated).min(1.0);
+        }
+    }
+    judged += hidden_count.saturating_sub(delivered_hidden);
+    (judged > 0).then(|| fairness / judged as f32)
+}
+
+/// After every settled trade, each AI side judges the hidden cards the other
+/// delivered, and holds any calamity that came with them against the sender.
+pub fn update_trade_trust(
+    mut settled: MessageReader<OpenTradeOfferSettled>,
+    mut players: Query<(Entity, &PlayerTradeCards, &mut TradeTrust)>,
+) {
+    for OpenTradeOfferSettled(offer) in settled.read() {
+        let Some(acceptor) = offer.accepted_by else {
+            continue;
+        };
+        let judgements = [
+            (
+                offer.creator,
+                acceptor,
+                offer.acceptor_actual_cards.as_ref(),
+                &offer.wanting_guaranteed,
+                offer.wanting_hidden_count,
+            ),
+            (
+                acceptor,
+                offer.creator,
+                offer.creator_actual_cards.as_ref(),
+                &offer.offering_guaranteed,
+                offer.offering_hidden_count,
+            ),
+        ];
+        for (judge, opponent, delivered, guaranteed, hidden_count) in judgements {
+            let Ok((_, _, mut trust)) = players.get_mut(judge) else {
+                continue;
+            };
+            if let Some(honesty) =
+                delivered.and_then(|cards| hidden_delivery_honesty(cards, guaranteed, hidden_count))
+            {
+                trust.record(opponent, honesty, SETTLEMENT_WEIGHT);
+            }
+        }
+    }
+    for (player, hand, mut trust) in &mut players {
+        for from in trust.note_slipped_calamities(hand) {
+            debug!("{player} was slipped a calamity by {from}");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::civilization::OpenTradeOffer;
+    use crate::stupid_ai::StupidAi;
+    use bevy::prelude::{App, Messages, Update};
+
+    #[test]
+    fn fair_hidden_cards_keep_trust_and_junk_loses_it() {
+        let guaranteed = HashMap::from([(TradeCard::Salt, 1), (TradeCard::Grain, 1)]);
+        // Salt (3) is the stated floor; Oil (4) is as good as promised.
+        let fair = HashMap::from([
+            (TradeCard::Salt, 1),
+            (TradeCard::Grain, 1),
+            (TradeCard::Oil, 1),
+        ]);
+        assert_eq!(hidden_delivery_honesty(&fair, &guaranteed, 1), Some(1.0));
+        // Ochre (1) is a third of it.
+        let junk = HashMap::from([
+            (TradeCard::Salt, 1),
+            (TradeCard::Grain, 1),
+            (TradeCard::Ochre, 1),
+        ]);
+        let honesty = hidden_delivery_honesty(&junk, &guaranteed, 1).unwrap();
+        assert!((honesty - 1.0 / 3.0).abs() < 1e-6);
+        // A promised card that never came counts as nothing.
+        let short = HashMap::from([(TradeCard::Salt, 1), (TradeCard::Grain, 1)]);
+        assert_eq!(hidden_delivery_honesty(&short, &guaranteed, 1), Some(0.0));
+        assert_eq!(hidden_delivery_honesty(&short, &guaranteed, 0), None);
+    }
+
+    #[test]
+    fn trust_moves_towards_each_outcome() {
+        let opponent = Entity::from_raw_u32(7).unwrap();
+        let mut trust = TradeTrust::default();
+        assert_eq!(trust.trust(opponent), NEUTRAL_TRUST);
+        trust.record(opponent, 1.0, SETTLEMENT_WEIGHT);
+        assert!(trust.trust(opponent) > NEUTRAL_TRUST);
+        for _ in 0..10 {
+            trust.record(opponent, 0.0, SETTLEMENT_WEIGHT);
+        }
+        assert!(trust.trust(opponent) < 0.05);
+    }
+
+    #[test]
+    fn a_slipped_calamity_counts_once_while_held() {
+        let cheat = Entity::from_raw_u32(3).unwrap();
+        let mut hand = PlayerTradeCards::default();
+        hand.add_traded_cards(TradeCard::Epidemic, 1, cheat);
+        hand.add_trade_card(TradeCard::Superstition);
+
+        let mut trust = TradeTrust::default();
+        assert_eq!(trust.note_slipped_calamities(&hand), vec![cheat]);
+        let after_one = trust.trust(cheat);
+        assert!(after_one < NEUTRAL_TRUST);
+        assert!(trust.note_slipped_calamities(&hand).is_empty());
+        assert_eq!(trust.trust(cheat), after_one);
+    }
+
+    #[test]
+    fn a_seated_ai_loses_trust_in_a_partner_who_settled_in_junk() {
+        let mut app = App::new();
+        app.add_message::<OpenTradeOfferSettled>()
+            .add_systems(Update, update_trade_trust);
+        let creator = app
+            .world_mut()
+            .spawn((StupidAi, PlayerTradeCards::default()))
+            .id();
+        let acceptor = app
+            .world_mut()
+            .spawn((StupidAi, PlayerTradeCards::default()))
+            .id();
+
+        let mut offer = OpenTradeOffer::new(creator, "Crete", None, None);
+        offer.offering_guaranteed = HashMap::from([(TradeCard::Salt, 1), (TradeCard::Grain, 1)]);
+        offer.offering_hidden_count = 1;
+        offer.wanting_guaranteed = HashMap::from([(TradeCard::Oil, 1), (TradeCard::Cloth, 1)]);
+        offer.wanting_hidden_count = 1;
+        assert!(offer.accept(acceptor, "Egypt"));
+        offer.settle_creator(HashMap::from([
+            (TradeCard::Salt, 1),
+            (TradeCard::Grain, 1),
+            (TradeCard::Ochre, 1),
+        ]));
+        offer.settle_acceptor(HashMap::from([(TradeCard::Oil, 1), (TradeCard::Cloth, 2)]));
+        app.world_mut()
+            .resource_mut::<Messages<OpenTradeOfferSettled>>()
+            .write(OpenTradeOfferSettled(offer));
+        app.update();
+
+        let trust_of = |judge: Entity, opponent: Entity| {
+            app.world()
+                .get::<TradeTrust>(judge)
+                .expect("StupidAi requires TradeTrust")
+                .trust(opponent)
+        };
+        assert!(trust_of(acceptor, creator) < NEUTRAL_TRUST);
+        assert!(trust_of(creator, acceptor) > NEUTRAL_TRUST);
+    }
+}
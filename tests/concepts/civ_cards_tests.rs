@@ -7,18 +7,19 @@
 
 use adv_civ::civilization::{
     CardsHeldBeforePurchasing, CivCardName, CivCardSelectionState, CivCardsAcquisition, CivTradeUi,
-    CivilizationTradeCards, ConfirmCivCardPurchase, PlayerCivilizationCards,
+    CivilizationTradeCards, CommandApplied, ConfirmCivCardPurchase, PlayerCivilizationCards,
     PlayerDoneAcquiringCivilizationCards, PlayerTradeCards, RecalculatePlayerMoves, TradeCard,
     begin_acquire_civ_cards, process_civ_card_purchase,
     resolve_calamities::resolve_calamities_components::GrainLockedForPurchase,
 };
 use adv_civ::player::Player;
 use bevy::platform::collections::{HashMap, HashSet};
-use bevy::prelude::{App, Update};
+use bevy::prelude::{App, Messages, Update};
 
 fn setup_app() -> App {
     let mut app = App::new();
     app.add_message::<ConfirmCivCardPurchase>()
+        .add_message::<CommandApplied<ConfirmCivCardPurchase>>()
         .add_message::<PlayerDoneAcquiringCivilizationCards>()
         .add_message::<RecalculatePlayerMoves>()
         .init_resource::<CivilizationTradeCards>()
@@ -91,6 +92,16 @@ fn partially_locked_grain_only_the_unlocked_portion_is_spent() {
         trade_cards.number_of_cards_for_trade_card(TradeCard::Grain),
         3
     );
+
+    // The journal and replay see what was actually paid, not what was asked.
+    let applied = app
+        .world()
+        .resource::<Messages<CommandApplied<ConfirmCivCardPurchase>>>();
+    let paid: Vec<_> = applied
+        .iter_current_update_messages()
+        .map(|CommandApplied(purchase)| purchase.payment.get(&TradeCard::Grain).copied())
+        .collect();
+    assert_eq!(paid, vec![Some(2)]);
 }
 
 #[test]
@@ -1,9 +1,10 @@
 use crate::setup_player;
 use adv_civ::civilization::{
     BuiltCity, CameraFocusQueue, CityTokenStock, CivCardName, ConflictCounterResource, GameArea,
-    GameFaction, LandPassage, PlayerAreas, PlayerCities, PlayerCivilizationCards, PlayerTradeCards,
-    Population, TokenStock, TradeCard, Treasury, UnresolvedCityConflict, UnresolvedConflict,
-    find_conflict_zones, on_add_unresolved_city_conflict, on_add_unresolved_conflict,
+    GameFaction, GameRng, LandPassage, PlayerAreas, PlayerCities, PlayerCivilizationCards,
+    PlayerTradeCards, Population, TokenStock, TradeCard, Treasury, UnresolvedCityConflict,
+    UnresolvedConflict, find_conflict_zones, on_add_unresolved_city_conflict,
+    on_add_unresolved_conflict,
 };
 use adv_civ::{GameActivity, GameState};
 use bevy::ecs::system::RunSystemOnce;
@@ -23,7 +24,8 @@ fn setup_conflict_test_app() -> App {
         .add_sub_state::<GameActivity>()
         .insert_state(GameActivity::Conflict)
         .init_resource::<ConflictCounterResource>()
-        .init_resource::<CameraFocusQueue>();
+        .init_resource::<CameraFocusQueue>()
+        .insert_resource(GameRng::from_seed(0));
     app
 }
 #[test]
@@ -6,7 +6,9 @@ pub mod movement_tests;
 pub mod player_trading_card_tests;
 pub mod population_expansion_tests;
 pub mod remove_surplus_tests;
+pub mod save_game_tests;
 pub mod succession_tests;
 pub mod trade_tests;
 pub mod two_player_game_tests;
+pub mod undo_tests;
 pub mod winning_tests;
@@ -1,6 +1,6 @@
 use crate::{create_area, create_area_w_components, setup_bevy_app, setup_player};
 use adv_civ::civilization::{
-    AvailableMoves, CameraFocusQueue, GameArea, GameFaction, GameMove, LandPassage,
+    AvailableMoves, CameraFocusQueue, CommandApplied, GameArea, GameFaction, GameMove, LandPassage,
     MoveTokenFromAreaToAreaCommand, PlayerAreas, PlayerMovementEnded, PlayerShips, Population,
     RecalculatePlayerMoves, SeaPassage, ShipFerryCommand, TokenHasMoved, TokenStock,
     execute_ship_ferry, move_tokens_from_area_to_area, recalculate_movement_moves_for_player,
@@ -14,6 +14,7 @@ fn setup_app() -> App {
     let mut app = App::new();
     app.add_plugins(StatesPlugin)
         .add_message::<MoveTokenFromAreaToAreaCommand>()
+        .add_message::<CommandApplied<MoveTokenFromAreaToAreaCommand>>()
         .add_message::<RecalculatePlayerMoves>()
         .init_resource::<CameraFocusQueue>()
         .insert_state(GameState::Playing)
@@ -460,6 +461,7 @@ fn ship_ferry_moves_tokens_and_the_ship_to_the_target_area() {
     let mut app = App::new();
     app.add_plugins(StatesPlugin)
         .add_message::<ShipFerryCommand>()
+        .add_message::<CommandApplied<ShipFerryCommand>>()
         .add_message::<RecalculatePlayerMoves>()
         .insert_state(GameState::Playing)
         .add_sub_state::<GameActivity>()
@@ -526,6 +528,7 @@ fn ship_ferry_only_moves_tokens_that_have_not_already_moved() {
     let mut app = App::new();
     app.add_plugins(StatesPlugin)
         .add_message::<ShipFerryCommand>()
+        .add_message::<CommandApplied<ShipFerryCommand>>()
         .add_message::<RecalculatePlayerMoves>()
         .insert_state(GameState::Playing)
         .add_sub_state::<GameActivity>()
@@ -585,6 +588,7 @@ fn ship_ferry_moves_an_empty_ship_with_no_tokens_along() {
     let mut app = App::new();
     app.add_plugins(StatesPlugin)
         .add_message::<ShipFerryCommand>()
+        .add_message::<CommandApplied<ShipFerryCommand>>()
         .add_message::<RecalculatePlayerMoves>()
         .insert_state(GameState::Playing)
         .add_sub_state::<GameActivity>()
@@ -0,0 +1,174 @@
+use adv_civ::GameActivity;
+use adv_civ::civilization::{
+    DebugOptions, GameSaveData, PendingGameLoad, PendingPhaseRestore, SaveDataSource,
+    capture_phase_state,
+};
+use adv_civ_server::simulation::{Outcome, play, simulation_app, use_workspace_assets};
+use bevy::ecs::system::RunSystemOnce;
+use bevy::prelude::{IntoScheduleConfigs, Last, Resource, State, StateTransition, World};
+use bevy::state::state::StateTransitionSystems;
+use serde_json::Value;
+use std::time::{Duration, Instant};
+
+/// The running game as `handle_save_request` saves it.
+fn save_game(world: &mut World) -> GameSaveData {
+    let mut save = world
+        .run_system_once(|source: SaveDataSource| source.capture())
+        .expect("the board can be captured");
+    save.phase_state = capture_phase_state(world)
+        .unwrap_or_else(|e| panic!("{:?} should save: {e}", save.game_activity));
+    save
+}
+
+/// `save` as JSON with every list sorted, except those whose order is
+/// part of the game: the census order, who is left to move and the trade
+/// card stacks. Everything else is listed in entity or hash order, which
+/// a load does not keep.
+fn comparable(save: &GameSaveData) -> Value {
+    fn sort_lists(value: &mut Value) {
+        match value {
+            Value::Array(items) => {
+                items.iter_mut().for_each(sort_lists);
+                items.sort_by_cached_key(ToString::to_string);
+            }
+            Value::Object(fields) => fields.values_mut().for_each(sort_lists),
+            _ => {}
+        }
+    }
+    let mut value = serde_json::to_value(save).expect("saves serialize");
+    let census_order = value["census_order"].take();
+    let left_to_move = value["left_to_move"].take();
+    let piles = value["phase_state"]["trade_card_piles"].take();
+    sort_lists(&mut value);
+    value["census_order"] = census_order;
+    value["left_to_move"] = left_to_move;
+    value["phase_state"]["trade_card_piles"] = piles;
+    value
+}
+
+#[derive(Resource, Default)]
+struct ActivitySaves(Vec<GameSaveData>);
+
+/// Saves the game at the end of the first frame of each activity, as a
+/// save request then would. PrepareGame and StartGame come before the
+/// board is set up, so they have nothing to save.
+fn save_each_activity(world: &mut World) {
+    let Some(activity) = world
+        .get_resource::<State<GameActivity>>()
+        .map(|state| state.get().clone())
+    else {
+        return;
+    };
+    if matches!(
+        activity,
+        GameActivity::PrepareGame | GameActivity::StartGame
+    ) || world
+        .resource::<ActivitySaves>()
+        .0
+        .iter()
+        .any(|save| save.game_activity == activity)
+    {
+        return;
+    }
+    let save = save_game(world);
+    world.resource_mut::<ActivitySaves>().0.push(save);
+}
+
+/// The reloaded game, saved again as soon as it is back in `activity`:
+/// after that activity's `OnEnter` systems, before anything else runs.
+#[derive(Resource)]
+struct Reloaded {
+    activity: GameActivity,
+    save: Option<GameSaveData>,
+}
+
+fn save_when_reloaded(world: &mut World) {
+    let reloaded = world.resource::<Reloaded>();
+    let back = world
+        .get_resource::<State<GameActivity>>()
+        .is_some_and(|state| *state.get() == reloaded.activity);
+    if !back || reloaded.save.is_some() || world.contains_resource::<PendingPhaseRestore>() {
+        return;
+    }
+    let save = save_game(world);
+    world.resource_mut::<Reloaded>().save = Some(save);
+}
+
+#[test]
+fn every_activity_survives_a_save_and_reload() {
+    use_workspace_assets();
+    let mut app = simulation_app(
+        DebugOptions {
+            number_of_players: 3,
+            game_seed: Some(5),
+            ai_always_pulls_trade_cards: true,
+            ..DebugOptions::default()
+        },
+        Some(2),
+    );
+    app.init_resource::<ActivitySaves>()
+        .add_systems(Last, save_each_activity);
+    assert_eq!(play(&mut app, 0, 20_000).outcome, Outcome::Finished);
+
+    let saves = std::mem::take(&mut app.world_mut().resource_mut::<ActivitySaves>().0);
+    let reached: Vec<GameActivity> = saves.iter().map(|s| s.game_activity.clone()).collect();
+    for activity in [
+        GameActivity::CollectTaxes,
+        GameActivity::PopulationExpansion,
+        GameActivity::Census,
+        GameActivity::ShipConstruction,
+        GameActivity::Movement,
+        GameActivity::Conflict,
+        GameActivity::CityConstruction,
+        GameActivity::RemoveSurplusPopulation,
+        GameActivity::CheckCitySupportAfterRemoveSurplusPopulation,
+        GameActivity::AcquireTradeCards,
+        GameActivity::Trade,
+        GameActivity::ResolveCalamities,
+        GameActivity::CheckCitySupportAfterResolveCalamities,
+        GameActivity::AcquireCivilizationCards,
+        GameActivity::MoveSuccessionMarkers,
+        GameActivity::GameOver,
+    ] {
+        assert!(
+            reached.contains(&activity),
+            "{activity:?} not in {reached:?}"
+        );
+    }
+
+    for save in saves {
+        // The same path a load request takes: `load_game_from_save` picks
+        // the pending save up on PrepareGame.
+        let mut reload = simulation_app(DebugOptions::default(), None);
+        reload
+            .insert_resource(PendingGameLoad(save.clone()))
+            .insert_resource(Reloaded {
+                activity: save.game_activity.clone(),
+                save: None,
+            })
+            .add_systems(
+                StateTransition,
+                save_when_reloaded.after(StateTransitionSystems::EnterSchedules),
+            );
+        reload.finish();
+        reload.cleanup();
+
+        // Asset loading waits on real time, not frames.
+        let deadline = Instant::now() + Duration::from_secs(60);
+        while reload.world().resource::<Reloaded>().save.is_none() && Instant::now() < deadline {
+            reload.update();
+        }
+        let again = reload
+            .world_mut()
+            .resource_mut::<Reloaded>()
+            .save
+            .take()
+            .unwrap_or_else(|| panic!("the {:?} save never came back", save.game_activity));
+        assert_eq!(
+            comparable(&again),
+            comparable(&save),
+            "{:?} changed across the save and reload",
+            save.game_activity
+        );
+    }
+}
@@ -0,0 +1,233 @@
+use crate::{setup_bevy_app, setup_player};
+use adv_civ::GameActivity;
+use adv_civ::civilization::*;
+use adv_civ::stupid_ai::IsHuman;
+use bevy::app::Update;
+use bevy::input::ButtonInput;
+use bevy::prelude::{
+    App, Entity, Handle, Has, KeyCode, Message, Messages, Name, NextState, Transform,
+};
+
+/// Everything an undo has to put back for one player, in a form that
+/// compares across token entities: a restored token need not be the one
+/// that left.
+#[derive(Debug, PartialEq)]
+struct Position {
+    tokens_by_area: Vec<(i32, usize)>,
+    city_areas: Vec<i32>,
+    cities: usize,
+    token_stock: usize,
+    city_stock: usize,
+    treasury: usize,
+    moved_tokens: usize,
+    moves: Vec<String>,
+    performing_movement: bool,
+    is_building: bool,
+}
+
+fn position(app: &mut App, player: Entity) -> Position {
+    let world = app.world_mut();
+    let mut tokens_by_area = Vec::new();
+    let mut city_areas = Vec::new();
+    let mut areas = world.query::<(&GameArea, &Population, Option<&BuiltCity>)>();
+    for (area, population, city) in areas.iter(world) {
+        let tokens = population.population_for_player(player);
+        if tokens > 0 {
+            tokens_by_area.push((area.id, tokens));
+        }
+        if city.is_some_and(|c| c.player == player) {
+            city_areas.push(area.id);
+        }
+    }
+    tokens_by_area.sort_unstable();
+    city_areas.sort_unstable();
+    let moved_tokens = world
+        .query::<(&Token, Has<TokenHasMoved>)>()
+        .iter(world)
+        .filter(|(token, moved)| token.player() == player && *moved)
+        .count();
+
+    let entity = world.entity(player);
+    let mut moves: Vec<String> = entity
+        .get::<AvailableMoves>()
+        .map(|m| m.moves.values().map(|m| format!("{m:?}")).collect())
+        .unwrap_or_default();
+    moves.sort();
+    Position {
+        tokens_by_area,
+        city_areas,
+        cities: entity.get::<PlayerCities>().unwrap().number_of_cities(),
+        token_stock: entity.get::<TokenStock>().unwrap().tokens_in_stock(),
+        city_stock: entity
+            .get::<CityTokenStock>()
+            .unwrap()
+            .city_tokens_in_stock(),
+        treasury: entity.get::<Treasury>().unwrap().tokens_in_treasury(),
+        moved_tokens,
+        moves,
+        performing_movement: entity.contains::<PerformingMovement>(),
+        is_building: entity.contains::<IsBuilding>(),
+    }
+}
+
+fn setup_app(activity: GameActivity) -> App {
+    let mut app = setup_bevy_app(|mut app| {
+        app.add_plugins(UndoPlugin)
+            .init_resource::<ButtonInput<KeyCode>>()
+            .init_resource::<GameInfoAndStuff>()
+            .init_resource::<AvailableFactions>()
+            .init_resource::<CameraFocusQueue>()
+            .add_message::<ExpandPopulationManuallyCommand>()
+            .add_message::<CommandApplied<ExpandPopulationManuallyCommand>>()
+            .add_message::<MoveTokenFromAreaToAreaCommand>()
+            .add_message::<CommandApplied<MoveTokenFromAreaToAreaCommand>>()
+            .add_message::<ShipFerryCommand>()
+            .add_message::<CommandApplied<ShipFerryCommand>>()
+            .add_message::<PlayerMovementEnded>()
+            .add_message::<BuildCityCommand>()
+            .add_message::<CommandApplied<BuildCityCommand>>()
+            .add_message::<EndPlayerCityConstruction>()
+            .add_message::<HumanPlayerTradeCardsUpdated>()
+            .add_message::<RecalculatePlayerMoves>()
+            .add_message::<CheckPlayerExpansionEligibility>()
+            .add_observer(on_add_return_token_to_stock)
+            .add_observer(on_add_return_city_to_stock);
+        app
+    });
+    app.world_mut()
+        .resource_mut::<NextState<GameActivity>>()
+        .set(activity);
+    app
+}
+
+fn human(app: &mut App) -> Entity {
+    let (player, _, _) = setup_player(app, "Human", GameFaction::Egypt);
+    app.world_mut()
+        .entity_mut(player)
+        .insert((IsHuman, PlayerTradeCards::default()));
+    player
+}
+
+fn area(app: &mut App, id: i32, max_population: usize) -> Entity {
+    app.world_mut()
+        .spawn((
+            Name::new(format!("Area {id}")),
+            GameArea::new(id),
+            LandPassage::default(),
+            Population::new(max_population),
+            Transform::default(),
+        ))
+        .id()
+}
+
+/// Takes `count` tokens out of `player`'s stock and stands them in `area`.
+fn place_tokens(app: &mut App, player: Entity, area: Entity, count: usize) {
+    let world = app.world_mut();
+    let tokens = world
+        .get_mut::<TokenStock>(player)
+        .unwrap()
+        .remove_at_most_n_tokens_from_stock(count)
+        .unwrap();
+    for token in tokens {
+        world
+            .get_mut::<Population>(area)
+            .unwrap()
+            .add_token_to_area(player, token);
+        world
+            .get_mut::<PlayerAreas>(player)
+            .unwrap()
+            .add_token_to_area(area, token);
+    }
+}
+
+fn send<M: Message>(app: &mut App, message: M) {
+    app.world_mut().resource_mut::<Messages<M>>().write(message);
+}
+
+fn run(app: &mut App, frames: usize) {
+    for _ in 0..frames {
+        app.update();
+    }
+}
+
+#[test]
+fn undoing_a_move_puts_the_movement_phase_back_as_it_was() {
+    let mut app = setup_app(GameActivity::Movement);
+    app.add_systems(
+        Update,
+        (
+            move_tokens_from_area_to_area,
+            recalculate_movement_moves_for_player,
+        ),
+    );
+    let player = human(&mut app);
+    let from = area(&mut app, 1, 4);
+    let to = area(&mut app, 2, 4);
+    app.world_mut()
+        .entity_mut(from)
+        .insert(LandPassage::new(vec![to]));
+    place_tokens(&mut app, player, from, 3);
+    app.world_mut()
+        .entity_mut(player)
+        .insert(PerformingMovement);
+    send(&mut app, RecalculatePlayerMoves::new(player));
+    run(&mut app, 2);
+    let before = position(&mut app, player);
+
+    send(
+        &mut app,
+        MoveTokenFromAreaToAreaCommand::new(from, to, 2, player),
+    );
+    run(&mut app, 2);
+    let moved = position(&mut app, player);
+    assert_eq!(moved.tokens_by_area, vec![(1, 1), (2, 2)]);
+    assert_eq!(moved.moved_tokens, 2);
+
+    send(&mut app, UndoRequest::new(player));
+    run(&mut app, 3);
+
+    assert_eq!(position(&mut app, player), before);
+    assert_eq!(
+        app.world()
+            .resource::<UndoStack>()
+            .steps_for(GameFaction::Egypt),
+        0
+    );
+}
+
+#[test]
+fn undoing_a_city_puts_the_city_construction_phase_back_as_it_was() {
+    let mut app = setup_app(GameActivity::CityConstruction);
+    app.add_systems(
+        Update,
+        (build_city, recalculate_city_construction_moves_for_player),
+    );
+    app.world_mut()
+        .resource_mut::<AvailableFactions>()
+        .faction_city_icons
+        .insert(GameFaction::Egypt, Handle::default());
+    let player = human(&mut app);
+    let mut civ_cards = PlayerCivilizationCards::default();
+    civ_cards.add_card(CivCardName::Architecture);
+    app.world_mut()
+        .entity_mut(player)
+        .insert((civ_cards, IsBuilding));
+    let site = area(&mut app, 1, 6);
+    app.world_mut().entity_mut(site).insert(CitySite);
+    place_tokens(&mut app, player, site, 6);
+    send(&mut app, RecalculatePlayerMoves::new(player));
+    run(&mut app, 2);
+    let before = position(&mut app, player);
+
+    send(&mut app, BuildCityCommand::new(player, site));
+    run(&mut app, 2);
+    let built = position(&mut app, player);
+    assert_eq!(built.city_areas, vec![1]);
+    assert_eq!(built.treasury, 1, "Architecture keeps one token back");
+    assert!(!built.is_building, "no other site to build on");
+
+    send(&mut app, UndoRequest::new(player));
+    run(&mut app, 3);
+
+    assert_eq!(position(&mut app, player), before);
+}